@@ -48,6 +48,7 @@ pub mod android {
                 machine_uid: Some(android_id),
             },
             database: DatabaseSettings {
+                backend: None,
                 username: "n/a".to_string(),
                 password: "n/a".to_string(),
                 port: 0,
@@ -64,6 +65,7 @@ pub mod android {
                     .with_directory(files_dir.to_string_lossy().to_string()),
             ),
             backup: None,
+            identity_provider: None,
         };
 
         logging_init(settings.logging.clone(), None);
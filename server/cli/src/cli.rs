@@ -8,17 +8,22 @@ use log::info;
 use report_builder::{build::build_report_definition, BuildArgs};
 
 use repository::{
-    get_storage_connection_manager, schema_from_row, test_db, ContextType, EqualFilter,
-    FormSchemaRow, FormSchemaRowRepository, KeyType, KeyValueStoreRepository, ReportFilter,
-    ReportRepository, ReportRow, ReportRowRepository, SyncBufferRowRepository,
+    get_storage_connection_manager,
+    migrations::{dry_run_migrations, get_migrations_status},
+    schema_from_row, test_db, ContextType, EqualFilter, FormSchemaRow, FormSchemaRowRepository,
+    KeyType, KeyValueStoreRepository, ReportFilter, ReportRepository, ReportRow,
+    ReportRowRepository, SyncBufferRowRepository,
 };
 use serde::{Deserialize, Serialize};
-use server::configuration;
+use server::{
+    backup::{backup, restore, RestoreArguments},
+    configuration,
+};
 use service::{
     apis::login_v4::LoginUserInfoV4,
     auth_data::AuthData,
     login::{LoginInput, LoginService},
-    plugin::validation::sign_plugin,
+    plugin::{bundle::PluginBundle, validation::sign_plugin},
     service_provider::{ServiceContext, ServiceProvider},
     settings::Settings,
     standard_reports::{ReportData, ReportsData, StandardReports},
@@ -38,8 +43,8 @@ use std::{
 
 use util::inline_init;
 
-mod backup;
-use backup::*;
+mod migrate_database;
+use migrate_database::*;
 
 const DATA_EXPORT_FOLDER: &str = "data";
 
@@ -108,6 +113,30 @@ enum Action {
         /// Path to the certificate file matching the private key
         #[clap(short, long)]
         cert: String,
+
+        /// Plugin code, signed with the plugin files. Required to bundle the plugin, the bundle
+        /// can only be installed under this code
+        #[clap(long)]
+        code: Option<String>,
+    },
+    /// Bundles a signed plugin into a single file that can be uploaded to the central server
+    BundlePlugin {
+        /// Path to the signed plugin
+        #[clap(short, long)]
+        path: String,
+
+        /// Plugin code, the plugin is installed into a plugin dir of this name. Must match the code
+        /// the plugin was signed with
+        #[clap(short, long)]
+        code: String,
+
+        /// Plugin version, e.g. 1.0.0
+        #[clap(short, long)]
+        version: String,
+
+        /// Output path of the plugin bundle
+        #[clap(short, long)]
+        out: String,
     },
     /// Helper tool to upsert report to local omSupply instance, helpful when developing reports, especially with argument schema
     UpsertReport {
@@ -150,6 +179,15 @@ enum Action {
         #[clap(short, long)]
         json_path: Option<String>,
     },
+    /// Copies all data (including changelog, sync buffer and sync cursors) from one database to another, e.g. to move a site from Sqlite to Postgres without re-initialising.
+    /// Destination must be the database backend this cli was built for, it is dropped and re-created. Row counts and checksums are verified after the copy.
+    /// Copying into Postgres needs a superuser, foreign key checks and triggers are skipped while copying
+    MigrateDatabase(MigrateDatabaseArguments),
+    /// Lists database and app version, and applied, pending and skipped one time and fragment migrations
+    ListMigrations,
+    /// Runs pending migrations without changing the database and reports time taken by each migration and the first error.
+    /// Postgres migrations run in a transaction that is rolled back, Sqlite migrations run on a temporary copy of the database
+    DryRunMigrations,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -205,6 +243,7 @@ async fn initialise_from_central(
             username: user[0].to_string(),
             password: user[1].to_string(),
             central_server_url: central_server_url.clone(),
+            totp_code: None,
         };
         LoginService::login(&service_provider, &auth_data, input.clone(), 0)
             .await
@@ -266,6 +305,7 @@ async fn main() -> anyhow::Result<()> {
                     username: user[0].to_string(),
                     password: user[1].to_string(),
                     central_server_url: url.to_string(),
+                    totp_code: None,
                 };
                 synced_user_info_rows.push((
                     input.clone(),
@@ -384,7 +424,22 @@ async fn main() -> anyhow::Result<()> {
 
             info!("Refresh data result: {:#?}", result);
         }
-        Action::SignPlugin { path, key, cert } => sign_plugin(&path, &key, &cert)?,
+        Action::SignPlugin {
+            path,
+            key,
+            cert,
+            code,
+        } => sign_plugin(&path, &key, &cert, code.as_deref())?,
+        Action::BundlePlugin {
+            path,
+            code,
+            version,
+            out,
+        } => {
+            let bundle = PluginBundle::from_dir(Path::new(&path), &code, &version)?;
+            fs::write(&out, serde_json::to_string(&bundle)?)?;
+            info!("Plugin bundle written to {}", out);
+        }
         Action::BuildStandardReports {} => {
             let connection_manager = get_storage_connection_manager(&settings.database);
             let con = connection_manager.connection()?;
@@ -602,6 +657,47 @@ async fn main() -> anyhow::Result<()> {
         Action::Restore(arguments) => {
             restore(&settings, arguments)?;
         }
+        Action::MigrateDatabase(arguments) => {
+            migrate_database(arguments).await?;
+        }
+        Action::ListMigrations => {
+            let connection_manager = get_storage_connection_manager(&settings.database);
+            let status = get_migrations_status(&connection_manager.connection()?)?;
+
+            println!(
+                "Database version: {}, app version: {}",
+                status.database_version, status.app_version
+            );
+            for migration in status.migrations {
+                println!("{} {:?}", migration.version, migration.state);
+                for fragment in migration.fragments {
+                    println!("  {} {:?}", fragment.identifier, fragment.state);
+                }
+            }
+        }
+        Action::DryRunMigrations => {
+            let connection_manager = get_storage_connection_manager(&settings.database);
+            let dry_run = dry_run_migrations(&connection_manager, &settings.database, None)?;
+
+            println!(
+                "Dry run of migrations from {} to {}",
+                dry_run.from_version, dry_run.to_version
+            );
+            for step in dry_run.steps {
+                println!(
+                    "{} {} {:?}",
+                    step.version,
+                    step.fragment.unwrap_or("one time migration"),
+                    step.duration
+                );
+            }
+            println!("Total time {:?}", dry_run.duration);
+
+            if let Some(error) = dry_run.error {
+                return Err(anyhow::Error::new(error).context("Dry run of migrations failed"));
+            }
+            println!("Dry run of migrations finished without errors");
+        }
     }
 
     Ok(())
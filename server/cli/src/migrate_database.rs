@@ -0,0 +1,798 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    io,
+    path::PathBuf,
+    process::Command,
+    str::FromStr,
+};
+
+use chrono::NaiveDateTime;
+use diesel::connection::SimpleConnection;
+use repository::{
+    database_settings::{DatabaseBackend, DatabaseSettings},
+    test_db, KeyType, KeyValueStoreRepository, RepositoryError, StorageConnection,
+};
+use rusqlite::{types::ValueRef, Connection as RusqliteConnection};
+use serde_json::{Map, Number, Value};
+use server::configuration::get_database_configuration;
+use thiserror::Error;
+
+/// Copied after all other tables, inserting other rows adds changelog rows through triggers
+const CHANGELOG_TABLE: &str = "changelog";
+const INSERT_BATCH_SIZE: usize = 500;
+/// Rows read from the source at a time, tables are read in primary key order
+const READ_BATCH_SIZE: usize = 5000;
+const POSTGRES_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const SQLITE_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+
+#[derive(clap::Parser, Debug)]
+pub(super) struct MigrateDatabaseArguments {
+    /// Configuration .yaml file with the `database` settings of the database to copy from
+    #[clap(long)]
+    from: String,
+    /// Configuration .yaml file with the `database` settings of the database to copy to, must be
+    /// the backend this cli was built for (copying back from Postgres to Sqlite needs a cli built
+    /// without the `postgres` feature). The database is dropped and re-created. A Postgres
+    /// destination needs a superuser, see `migrate_database` below
+    #[clap(long)]
+    to: String,
+    /// Postgres bin directory (containing psql), required if psql is not in PATH and copying from
+    /// or verifying a Postgres database
+    #[clap(long)]
+    pg_bin_dir: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub(super) enum MigrateDatabaseError {
+    #[error("Problem reading database configuration from {0}: {1}")]
+    Configuration(String, String),
+    #[error("Database backend of destination ({0:?}) must be the backend this cli was built for ({1:?}), use a cli built for {0:?} to copy into it")]
+    DestinationBackendNotSupported(DatabaseBackend, DatabaseBackend),
+    #[error("Source and destination must be different databases")]
+    SameDatabase,
+    #[error("Source database version ({0}) does not match destination version ({1}), run the server on the source database to upgrade it first")]
+    VersionMismatch(String, String),
+    #[error("Table {0} does not exist in destination database")]
+    TableMissingInDestination(String),
+    #[error("Blob value in {0}.{1} is not supported")]
+    BlobNotSupported(String, String),
+    #[error("Cannot find psql executable in PATH, add it to PATH or specify --pg-bin-dir")]
+    PsqlNotFoundInPath,
+    #[error("Cannot find psql executable in {0}")]
+    PsqlNotFoundInBinPath(String),
+    #[error("Error while executing psql: {0}")]
+    PsqlError(String),
+    #[error("Copying into Postgres needs a superuser (or a role allowed to set session_replication_role) to skip foreign key checks and triggers: {0}")]
+    ReplicationRoleNotPermitted(String),
+    #[error("Verification failed, tables with different data: {0:?}")]
+    VerificationFailed(Vec<String>),
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    StdIO(#[from] io::Error),
+    #[error(transparent)]
+    DatabaseError(#[from] RepositoryError),
+}
+
+type Row = Map<String, Value>;
+
+/// Copies every table from one database to another (the destination can be a different backend),
+/// keeping ids, changelog cursors and sync cursors, and verifies row counts and checksums after
+/// the copy. Tables are streamed in batches of [READ_BATCH_SIZE] rows, the checksums are computed
+/// while copying and while reading the destination back.
+///
+/// The destination is written through the diesel connection of this build, so it has to be the
+/// compiled backend: Sqlite -> Postgres needs the `postgres` feature, Postgres -> Sqlite a default
+/// build. For a Postgres destination foreign key checks and triggers are skipped with
+/// `session_replication_role = replica`, which needs a superuser (or on Postgres 15+ a role
+/// granted `SET` on `session_replication_role`).
+pub(super) async fn migrate_database(
+    MigrateDatabaseArguments {
+        from,
+        to,
+        pg_bin_dir,
+    }: MigrateDatabaseArguments,
+) -> Result<(), MigrateDatabaseError> {
+    let from_settings = get_database_configuration(&from)
+        .map_err(|e| MigrateDatabaseError::Configuration(from.clone(), e.to_string()))?;
+    let to_settings = get_database_configuration(&to)
+        .map_err(|e| MigrateDatabaseError::Configuration(to.clone(), e.to_string()))?;
+
+    migrate(&from_settings, &to_settings, &pg_bin_dir).await
+}
+
+async fn migrate(
+    from_settings: &DatabaseSettings,
+    to_settings: &DatabaseSettings,
+    pg_bin_dir: &Option<String>,
+) -> Result<(), MigrateDatabaseError> {
+    let compiled = DatabaseBackend::compiled();
+    if to_settings.backend() != compiled {
+        return Err(MigrateDatabaseError::DestinationBackendNotSupported(
+            to_settings.backend(),
+            compiled,
+        ));
+    }
+
+    let source = DatabaseReader::new(from_settings, pg_bin_dir);
+    let destination = DatabaseReader::new(to_settings, pg_bin_dir);
+    if source.location() == destination.location() {
+        return Err(MigrateDatabaseError::SameDatabase);
+    }
+
+    println!("Re-creating destination database");
+    let connection = test_db::setup(to_settings).await.connection()?;
+
+    let destination_version = KeyValueStoreRepository::new(&connection)
+        .get_string(KeyType::DatabaseVersion)?
+        .unwrap_or_default();
+    let source_version = source.database_version()?;
+    if source_version != destination_version {
+        return Err(MigrateDatabaseError::VersionMismatch(
+            source_version,
+            destination_version,
+        ));
+    }
+
+    let destination_tables = destination.tables()?;
+    let mut tables = source.tables()?;
+    if let Some(table) = tables.iter().find(|t| !destination_tables.contains(t)) {
+        return Err(MigrateDatabaseError::TableMissingInDestination(
+            table.clone(),
+        ));
+    }
+    // Move changelog to the end
+    tables.sort_by_key(|table| table == CHANGELOG_TABLE);
+
+    let mut checksums = Vec::new();
+    {
+        let mut guard = connection.lock();
+        if !compiled.is_postgres() {
+            // Has no effect inside a transaction
+            guard
+                .connection()
+                .batch_execute("PRAGMA foreign_keys = OFF;")
+                .map_err(RepositoryError::from)?;
+        }
+    }
+    connection
+        .transaction_sync(|con| -> Result<(), MigrateDatabaseError> {
+            if compiled.is_postgres() {
+                // Skips foreign key checks and triggers for this transaction
+                execute(con, "SET LOCAL session_replication_role = replica;").map_err(|e| {
+                    MigrateDatabaseError::ReplicationRoleNotPermitted(e.to_string())
+                })?;
+            }
+            for table in &destination_tables {
+                execute(con, &format!(r#"DELETE FROM "{table}";"#))?;
+            }
+
+            for table in &tables {
+                if table == CHANGELOG_TABLE {
+                    // Remove changelogs added by triggers while copying
+                    execute(con, &format!(r#"DELETE FROM "{table}";"#))?;
+                }
+                let mut checksum = TableChecksum::default();
+                source.for_each_batch(table, |rows| {
+                    for batch in rows.chunks(INSERT_BATCH_SIZE) {
+                        execute(con, &insert_statements(compiled, table, batch))?;
+                    }
+                    checksum.add(rows);
+                    Ok(())
+                })?;
+                println!("Copied {} rows of {table}", checksum.row_count);
+                checksums.push((table.clone(), checksum));
+            }
+
+            if compiled.is_postgres() {
+                reset_postgres_sequences(con)?;
+            }
+            Ok(())
+        })
+        .map_err(|e| e.to_inner_error())?;
+
+    if !compiled.is_postgres() {
+        connection
+            .lock()
+            .connection()
+            .batch_execute("PRAGMA foreign_keys = ON;")
+            .map_err(RepositoryError::from)?;
+    }
+
+    println!("Verifying row counts and checksums");
+    let mut mismatched_tables = Vec::new();
+    for (table, source_checksum) in checksums {
+        let destination_checksum = destination.checksum(&table)?;
+        if source_checksum != destination_checksum {
+            println!("{table}: source {source_checksum:?}, destination {destination_checksum:?}");
+            mismatched_tables.push(table);
+        }
+    }
+    if !mismatched_tables.is_empty() {
+        return Err(MigrateDatabaseError::VerificationFailed(mismatched_tables));
+    }
+
+    println!(
+        "Database migrated from {} to {}",
+        source.location(),
+        destination.location()
+    );
+    Ok(())
+}
+
+fn execute(connection: &StorageConnection, sql: &str) -> Result<(), MigrateDatabaseError> {
+    connection
+        .lock()
+        .connection()
+        .batch_execute(sql)
+        .map_err(RepositoryError::from)?;
+    Ok(())
+}
+
+fn insert_statements(backend: DatabaseBackend, table: &str, rows: &[Row]) -> String {
+    rows.iter()
+        .map(|row| {
+            let columns = row
+                .keys()
+                .map(|column| format!(r#""{column}""#))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let values = row
+                .values()
+                .map(|value| sql_literal(value, backend))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(r#"INSERT INTO "{table}" ({columns}) VALUES ({values});"#)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Values are quoted and left to the database to convert to the column type, e.g. '1' is a valid
+/// boolean in Postgres and becomes an integer in a Sqlite numeric column
+fn sql_literal(value: &Value, backend: DatabaseBackend) -> String {
+    let text = match value {
+        Value::Null => return "NULL".to_string(),
+        Value::Bool(true) => "1".to_string(),
+        Value::Bool(false) => "0".to_string(),
+        Value::Number(number) => number.to_string(),
+        Value::String(string) => match backend {
+            // Diesel stores and compares datetimes in Sqlite as text with a space separator
+            DatabaseBackend::Sqlite => {
+                match NaiveDateTime::parse_from_str(string, POSTGRES_DATETIME_FORMAT) {
+                    Ok(datetime) => datetime.format(SQLITE_DATETIME_FORMAT).to_string(),
+                    Err(_) => string.clone(),
+                }
+            }
+            DatabaseBackend::Postgres => string.clone(),
+        },
+        Value::Array(_) | Value::Object(_) => value.to_string(),
+    };
+
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+fn reset_postgres_sequences(connection: &StorageConnection) -> Result<(), MigrateDatabaseError> {
+    execute(
+        connection,
+        r#"
+        DO $$
+        DECLARE
+            serial_column RECORD;
+        BEGIN
+            FOR serial_column IN
+                SELECT table_name, column_name FROM information_schema.columns
+                WHERE table_schema = 'public' AND column_default LIKE 'nextval(%'
+            LOOP
+                EXECUTE format(
+                    'SELECT setval(pg_get_serial_sequence(%L, %L), COALESCE(MAX(%I), 0) + 1, false) FROM %I',
+                    serial_column.table_name,
+                    serial_column.column_name,
+                    serial_column.column_name,
+                    serial_column.table_name
+                );
+            END LOOP;
+        END $$;
+        "#,
+    )
+}
+
+/// Reads tables of either backend without needing a diesel connection for it: Sqlite through
+/// rusqlite and Postgres through psql
+enum DatabaseReader {
+    Sqlite {
+        path: String,
+    },
+    Postgres {
+        url: String,
+        /// Url without credentials, for messages
+        location: String,
+        psql: PsqlCommand,
+    },
+}
+
+impl DatabaseReader {
+    fn new(settings: &DatabaseSettings, pg_bin_dir: &Option<String>) -> Self {
+        match settings.backend() {
+            DatabaseBackend::Sqlite => DatabaseReader::Sqlite {
+                path: sqlite_path(settings),
+            },
+            DatabaseBackend::Postgres => DatabaseReader::Postgres {
+                url: format!(
+                    "postgres://{}:{}@{}:{}/{}",
+                    settings.username,
+                    settings.password,
+                    settings.host,
+                    settings.port,
+                    settings.database_name
+                ),
+                location: format!(
+                    "postgres://{}:{}/{}",
+                    settings.host, settings.port, settings.database_name
+                ),
+                psql: PsqlCommand {
+                    bin_dir: pg_bin_dir.clone(),
+                },
+            },
+        }
+    }
+
+    fn location(&self) -> &str {
+        match self {
+            DatabaseReader::Sqlite { path } => path,
+            DatabaseReader::Postgres { location, .. } => location,
+        }
+    }
+
+    fn tables(&self) -> Result<Vec<String>, MigrateDatabaseError> {
+        let sql = match self {
+            DatabaseReader::Sqlite { .. } => {
+                "SELECT json_object('name', name) FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name"
+            }
+            DatabaseReader::Postgres { .. } => {
+                "SELECT json_build_object('name', tablename) FROM pg_tables WHERE schemaname = 'public' ORDER BY tablename"
+            }
+        };
+
+        Ok(self
+            .json_rows(sql)?
+            .into_iter()
+            .filter_map(|mut row| match row.remove("name") {
+                Some(Value::String(name)) => Some(name),
+                _ => None,
+            })
+            .collect())
+    }
+
+    fn database_version(&self) -> Result<String, MigrateDatabaseError> {
+        let version = self
+            .select_rows(
+                "key_value_store",
+                "SELECT * FROM key_value_store WHERE id = 'DATABASE_VERSION'",
+            )?
+            .into_iter()
+            .next()
+            .and_then(|mut row| match row.remove("value_string") {
+                Some(Value::String(version)) => Some(version),
+                _ => None,
+            });
+
+        Ok(version.unwrap_or_default())
+    }
+
+    fn primary_key(&self, table: &str) -> Result<Vec<String>, MigrateDatabaseError> {
+        let sql = match self {
+            DatabaseReader::Sqlite { .. } => format!(
+                "SELECT json_object('name', name) FROM pragma_table_info('{table}') WHERE pk > 0 ORDER BY pk"
+            ),
+            DatabaseReader::Postgres { .. } => format!(
+                r#"SELECT json_build_object('name', a.attname) FROM pg_index i
+                JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey)
+                WHERE i.indrelid = '"{table}"'::regclass AND i.indisprimary
+                ORDER BY array_position(i.indkey::int2[], a.attnum)"#
+            ),
+        };
+
+        Ok(self
+            .json_rows(&sql)?
+            .into_iter()
+            .filter_map(|mut row| match row.remove("name") {
+                Some(Value::String(name)) => Some(name),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Reads the table in batches of [READ_BATCH_SIZE] rows ordered by primary key, using the
+    /// last key of a batch as the lower bound of the next one. Tables without a primary key are
+    /// read in one batch
+    fn for_each_batch(
+        &self,
+        table: &str,
+        mut f: impl FnMut(&[Row]) -> Result<(), MigrateDatabaseError>,
+    ) -> Result<(), MigrateDatabaseError> {
+        let key = self.primary_key(table)?;
+        if key.is_empty() {
+            return f(&self.select_rows(table, &format!(r#"SELECT * FROM "{table}""#))?);
+        }
+
+        let key_columns = key
+            .iter()
+            .map(|column| format!(r#""{column}""#))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut last_key: Option<String> = None;
+        loop {
+            let condition = match &last_key {
+                Some(last_key) => format!("WHERE ({key_columns}) > ({last_key})"),
+                None => String::new(),
+            };
+            let rows = self.select_rows(
+                table,
+                &format!(
+                    r#"SELECT * FROM "{table}" {condition} ORDER BY {key_columns} LIMIT {READ_BATCH_SIZE}"#
+                ),
+            )?;
+            let Some(last_row) = rows.last() else {
+                return Ok(());
+            };
+            last_key = Some(
+                key.iter()
+                    .map(|column| key_literal(last_row.get(column).unwrap_or(&Value::Null)))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+
+            f(&rows)?;
+            if rows.len() < READ_BATCH_SIZE {
+                return Ok(());
+            }
+        }
+    }
+
+    fn checksum(&self, table: &str) -> Result<TableChecksum, MigrateDatabaseError> {
+        let mut checksum = TableChecksum::default();
+        self.for_each_batch(table, |rows| {
+            checksum.add(rows);
+            Ok(())
+        })?;
+        Ok(checksum)
+    }
+
+    /// Runs a `SELECT * FROM {table} ...` statement
+    fn select_rows(&self, table: &str, sql: &str) -> Result<Vec<Row>, MigrateDatabaseError> {
+        match self {
+            DatabaseReader::Sqlite { path } => {
+                let connection = RusqliteConnection::open(path)?;
+                let mut statement = connection.prepare(sql)?;
+                let column_names = statement
+                    .column_names()
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>();
+
+                let mut rows = statement.query([])?;
+                let mut result = Vec::new();
+                while let Some(row) = rows.next()? {
+                    let mut object = Row::new();
+                    for (index, name) in column_names.iter().enumerate() {
+                        let value = match row.get_ref(index)? {
+                            ValueRef::Null => Value::Null,
+                            ValueRef::Integer(int) => Value::Number(Number::from(int)),
+                            ValueRef::Real(float) => {
+                                Number::from_f64(float).map_or(Value::Null, Value::Number)
+                            }
+                            ValueRef::Text(text) => {
+                                Value::String(String::from_utf8_lossy(text).to_string())
+                            }
+                            ValueRef::Blob(_) => {
+                                return Err(MigrateDatabaseError::BlobNotSupported(
+                                    table.to_string(),
+                                    name.clone(),
+                                ))
+                            }
+                        };
+                        object.insert(name.clone(), value);
+                    }
+                    result.push(object);
+                }
+                Ok(result)
+            }
+            DatabaseReader::Postgres { .. } => {
+                self.json_rows(&format!("SELECT row_to_json(t) FROM ({sql}) t"))
+            }
+        }
+    }
+
+    /// Runs a query returning one json object per row
+    fn json_rows(&self, sql: &str) -> Result<Vec<Row>, MigrateDatabaseError> {
+        let output = match self {
+            DatabaseReader::Sqlite { path } => {
+                let connection = RusqliteConnection::open(path)?;
+                let mut statement = connection.prepare(sql)?;
+                let rows = statement
+                    .query_map([], |row| row.get::<_, String>(0))?
+                    .collect::<Result<Vec<_>, _>>()?;
+                rows
+            }
+            DatabaseReader::Postgres { url, psql, .. } => psql.query(url, sql)?,
+        };
+
+        output
+            .iter()
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_str(line).map_err(MigrateDatabaseError::from))
+            .collect()
+    }
+}
+
+struct PsqlCommand {
+    bin_dir: Option<String>,
+}
+
+impl PsqlCommand {
+    /// Returns output lines, in unaligned tuples only mode each line is one row
+    fn query(&self, url: &str, sql: &str) -> Result<Vec<String>, MigrateDatabaseError> {
+        let bin_dir = self.bin_dir.clone().unwrap_or_default();
+        let command = PathBuf::from_str(&bin_dir)
+            .map_err(|_| MigrateDatabaseError::PsqlNotFoundInBinPath(bin_dir.clone()))?
+            .join("psql");
+
+        let output = Command::new(command)
+            .args([
+                "--no-psqlrc",
+                "-At",
+                "-v",
+                "ON_ERROR_STOP=1",
+                "-d",
+                url,
+                "-c",
+                sql,
+            ])
+            .output()
+            .map_err(|e| match (e.kind(), &self.bin_dir) {
+                (io::ErrorKind::NotFound, Some(bin_dir)) => {
+                    MigrateDatabaseError::PsqlNotFoundInBinPath(bin_dir.clone())
+                }
+                (io::ErrorKind::NotFound, None) => MigrateDatabaseError::PsqlNotFoundInPath,
+                _ => e.into(),
+            })?;
+
+        if !output.status.success() {
+            return Err(MigrateDatabaseError::PsqlError(
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect())
+    }
+}
+
+/// Key values of the last row of a batch, numbers are not quoted so they compare as numbers in
+/// Sqlite
+fn key_literal(value: &Value) -> String {
+    match value {
+        Value::Number(number) => number.to_string(),
+        value => sql_literal(value, DatabaseBackend::Postgres),
+    }
+}
+
+/// Same naming rules as the server uses for Sqlite database files
+fn sqlite_path(settings: &DatabaseSettings) -> String {
+    let file_name = if settings.database_name.ends_with(".sqlite") {
+        settings.database_name.clone()
+    } else {
+        format!("{}.sqlite", settings.database_name)
+    };
+
+    match &settings.database_path {
+        Some(path) => format!("{path}/{file_name}"),
+        None => file_name,
+    }
+}
+
+/// Row count and an order independent hash of the rows, values are normalised so the same data
+/// read from Sqlite and Postgres results in the same checksum
+#[derive(Debug, PartialEq, Default)]
+struct TableChecksum {
+    row_count: usize,
+    checksum: u64,
+}
+
+impl TableChecksum {
+    fn add(&mut self, rows: &[Row]) {
+        for row in rows {
+            let mut hasher = DefaultHasher::new();
+            let mut columns = row.iter().collect::<Vec<_>>();
+            columns.sort_by_key(|(column, _)| *column);
+            for (column, value) in columns {
+                column.hash(&mut hasher);
+                normalise(value).hash(&mut hasher);
+            }
+            self.checksum = self.checksum.wrapping_add(hasher.finish());
+        }
+        self.row_count += rows.len();
+    }
+}
+
+fn normalise(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(true) => "1".to_string(),
+        Value::Bool(false) => "0".to_string(),
+        Value::Number(number) => number
+            .as_f64()
+            .map(|number| number.to_string())
+            .unwrap_or_else(|| number.to_string()),
+        Value::String(string) => {
+            if let Some(datetime) = [POSTGRES_DATETIME_FORMAT, SQLITE_DATETIME_FORMAT]
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(string, format).ok())
+            {
+                return datetime.format(POSTGRES_DATETIME_FORMAT).to_string();
+            }
+            // Json columns are text in Sqlite
+            match serde_json::from_str::<Value>(string) {
+                Ok(json @ (Value::Array(_) | Value::Object(_))) => normalise(&json),
+                _ => string.clone(),
+            }
+        }
+        Value::Array(_) | Value::Object(_) => serde_json::to_string(value).unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_checksum_across_backends() {
+        let sqlite_rows = json!([
+            {"id": "a", "is_active": 1, "price": 1.0, "created": "2024-01-01 10:00:00.5", "json": "{\"b\":1}"},
+            {"id": "b", "is_active": 0, "price": 2.5, "created": null, "json": null}
+        ]);
+        let postgres_rows = json!([
+            {"created": null, "id": "b", "is_active": false, "price": 2.5, "json": null},
+            {"id": "a", "is_active": true, "price": 1, "created": "2024-01-01T10:00:00.5", "json": {"b": 1}}
+        ]);
+        let rows = |value: Value| -> Vec<Row> { serde_json::from_value(value).unwrap() };
+        let checksum = |rows: Vec<Row>| {
+            let mut checksum = TableChecksum::default();
+            checksum.add(&rows);
+            checksum
+        };
+
+        assert_eq!(
+            checksum(rows(sqlite_rows.clone())),
+            checksum(rows(postgres_rows.clone()))
+        );
+
+        // Same checksum when read in batches
+        let mut batched = TableChecksum::default();
+        for row in rows(postgres_rows) {
+            batched.add(&[row]);
+        }
+        assert_eq!(checksum(rows(sqlite_rows.clone())), batched);
+
+        let changed_rows = json!([
+            {"id": "a", "is_active": 1, "price": 1.0, "created": "2024-01-01 10:00:00.5", "json": "{\"b\":1}"},
+            {"id": "b", "is_active": 1, "price": 2.5, "created": null, "json": null}
+        ]);
+        assert_ne!(checksum(rows(sqlite_rows)), checksum(rows(changed_rows)));
+    }
+
+    #[test]
+    fn test_sql_literal() {
+        assert_eq!(sql_literal(&json!(null), DatabaseBackend::Sqlite), "NULL");
+        assert_eq!(sql_literal(&json!(true), DatabaseBackend::Postgres), "'1'");
+        assert_eq!(
+            sql_literal(&json!("it's"), DatabaseBackend::Sqlite),
+            "'it''s'"
+        );
+        assert_eq!(
+            sql_literal(&json!("2024-01-01T10:00:00"), DatabaseBackend::Sqlite),
+            "'2024-01-01 10:00:00'"
+        );
+        assert_eq!(
+            sql_literal(&json!("2024-01-01T10:00:00"), DatabaseBackend::Postgres),
+            "'2024-01-01T10:00:00'"
+        );
+    }
+
+    /// Creates a Sqlite database from a Postgres database, with column types matching the ones
+    /// the Sqlite migrations use
+    #[cfg(feature = "postgres")]
+    fn create_sqlite_copy(source: &DatabaseReader, path: &str) -> Result<(), MigrateDatabaseError> {
+        let connection = RusqliteConnection::open(path)?;
+        for table in source.tables()? {
+            let columns = source.json_rows(&format!(
+                "SELECT json_build_object('name', column_name, 'type', data_type) FROM information_schema.columns WHERE table_schema = 'public' AND table_name = '{table}' ORDER BY ordinal_position"
+            ))?;
+            let mut definitions = columns
+                .iter()
+                .map(|column| {
+                    let sqlite_type = match column["type"].as_str().unwrap_or_default() {
+                        "integer" | "bigint" | "smallint" => "INTEGER",
+                        "double precision" | "real" | "numeric" => "REAL",
+                        "boolean" => "BOOLEAN",
+                        _ => "TEXT",
+                    };
+                    format!(
+                        r#""{}" {sqlite_type}"#,
+                        column["name"].as_str().unwrap_or_default()
+                    )
+                })
+                .collect::<Vec<_>>();
+            let key = source.primary_key(&table)?;
+            if !key.is_empty() {
+                let key_columns = key
+                    .iter()
+                    .map(|column| format!(r#""{column}""#))
+                    .collect::<Vec<_>>();
+                definitions.push(format!("PRIMARY KEY ({})", key_columns.join(", ")));
+            }
+            connection.execute_batch(&format!(
+                r#"CREATE TABLE "{table}" ({});"#,
+                definitions.join(", ")
+            ))?;
+
+            source.for_each_batch(&table, |rows| {
+                connection.execute_batch(&insert_statements(
+                    DatabaseBackend::Sqlite,
+                    &table,
+                    rows,
+                ))?;
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "postgres")]
+    #[actix_rt::test]
+    async fn test_migrate_sqlite_to_postgres() {
+        use repository::mock::MockDataInserts;
+
+        // Postgres database with mock data, used to create the Sqlite source and to compare the
+        // destination with
+        let (_, _, _, original_settings) =
+            test_db::setup_all("migrate_database_original", MockDataInserts::all()).await;
+        let original = DatabaseReader::new(&original_settings, &None);
+
+        std::fs::create_dir_all("test_output").unwrap();
+        let sqlite_settings = DatabaseSettings {
+            backend: Some(DatabaseBackend::Sqlite),
+            database_name: "test_output/migrate_database_source.sqlite".to_string(),
+            ..test_db::get_test_db_settings("")
+        };
+        let _ = std::fs::remove_file(&sqlite_settings.database_name);
+        create_sqlite_copy(&original, &sqlite_settings.database_name).unwrap();
+
+        let destination_settings = test_db::get_test_db_settings("migrate_database_destination");
+        migrate(&sqlite_settings, &destination_settings, &None)
+            .await
+            .unwrap();
+
+        let destination = DatabaseReader::new(&destination_settings, &None);
+        for table in original.tables().unwrap() {
+            assert_eq!(
+                original.checksum(&table).unwrap(),
+                destination.checksum(&table).unwrap(),
+                "{table}"
+            );
+        }
+
+        // Destination is a different database
+        assert!(matches!(
+            migrate(&destination_settings, &destination_settings, &None).await,
+            Err(MigrateDatabaseError::SameDatabase)
+        ));
+    }
+}
@@ -53,6 +53,16 @@ fn get_timestamp_fields() -> Vec<TableAndFieldName> {
         ("vaccination", "created_datetime"),
         ("invoice", "backdated_datetime"),
         ("contact_form", "created_datetime"),
+        ("insurance_claim_line", "datetime"),
+        ("asset_work_order", "created_datetime"),
+        ("asset_work_order", "completed_datetime"),
+        ("vaccination_reminder", "created_datetime"),
+        ("vaccination_reminder", "sent_datetime"),
+        ("vaccine_vial_opening", "opened_datetime"),
+        ("vaccine_vial_opening", "discard_after_datetime"),
+        ("vaccine_vial_opening", "closed_datetime"),
+        ("plugin_version", "created_datetime"),
+        ("plugin_certificate_revocation", "revoked_datetime"),
     ]
     .iter()
     .map(|(table_name, field_name)| TableAndFieldName {
@@ -118,6 +128,9 @@ fn get_date_fields() -> Vec<TableAndFieldName> {
         ("asset", "warranty_end"),
         ("rnr_form_line", "expiry_date"),
         ("vaccination", "vaccination_date"),
+        ("name_insurance_join", "expiry_date"),
+        ("asset_work_order", "due_date"),
+        ("vaccination_reminder", "due_date"),
     ]
     .iter()
     .map(|(table_name, field_name)| TableAndFieldName {
@@ -85,6 +85,7 @@ impl From<AssetFilterInput> for AssetFilter {
             replacement_date: f.replacement_date.map(DateFilter::from),
             is_non_catalogue: f.is_non_catalogue,
             store: f.store.map(StringFilter::from),
+            store_id: None,
             functional_status: f
                 .functional_status
                 .map(|t| map_filter!(t, AssetLogStatusInput::to_domain)),
@@ -0,0 +1,30 @@
+use actix_web::web::Data;
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use service::{
+    location::cold_chain::{ColdChainWarning, ItemVariantAndLocation},
+    service_provider::ServiceProvider,
+};
+use std::collections::HashMap;
+
+/// Only loads a value for item variants and locations that aren't compatible
+pub struct ColdChainWarningLoader {
+    pub service_provider: Data<ServiceProvider>,
+}
+
+impl Loader<ItemVariantAndLocation> for ColdChainWarningLoader {
+    type Value = ColdChainWarning;
+    type Error = async_graphql::Error;
+
+    async fn load(
+        &self,
+        pairs: &[ItemVariantAndLocation],
+    ) -> Result<HashMap<ItemVariantAndLocation, Self::Value>, Self::Error> {
+        let service_context = self.service_provider.basic_context()?;
+        let warnings = self
+            .service_provider
+            .location_service
+            .check_cold_chain_compatibilities(&service_context, pairs)?;
+        Ok(warnings)
+    }
+}
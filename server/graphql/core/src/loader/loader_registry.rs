@@ -411,6 +411,12 @@ pub async fn get_loaders(
         },
         async_std::task::spawn,
     ));
+    loaders.insert(DataLoader::new(
+        ColdChainWarningLoader {
+            service_provider: service_provider.clone(),
+        },
+        async_std::task::spawn,
+    ));
 
     loaders.insert(DataLoader::new(
         ItemVariantsByItemIdLoader {
@@ -8,6 +8,7 @@ mod asset_status_log;
 mod asset_type;
 mod bundled_item;
 mod clinician;
+mod cold_chain_warning;
 mod cold_storage_type;
 mod demographic;
 mod document;
@@ -61,6 +62,7 @@ pub use asset_status_log::*;
 pub use asset_type::*;
 pub use bundled_item::*;
 pub use clinician::*;
+pub use cold_chain_warning::*;
 pub use cold_storage_type::*;
 pub use demographic::*;
 pub use document::*;
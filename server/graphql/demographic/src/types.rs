@@ -65,6 +65,7 @@ impl From<DemographicIndicatorFilterInput> for DemographicIndicatorFilter {
     fn from(f: DemographicIndicatorFilterInput) -> Self {
         DemographicIndicatorFilter {
             id: f.id.map(EqualFilter::from),
+            demographic_id: None,
             name: f.name.map(StringFilter::from),
             base_year: f.base_year.map(EqualFilter::from),
         }
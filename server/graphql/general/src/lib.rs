@@ -6,32 +6,55 @@ pub mod types;
 pub use self::queries::sync_status::*;
 use self::queries::*;
 
+use chrono::{DateTime, Utc};
 use graphql_core::pagination::PaginationInput;
 use service::sync::CentralServerConfig;
 
 use crate::store_preference::store_preferences;
 use graphql_types::types::{
-    CurrenciesResponse, CurrencyFilterInput, CurrencySortInput, MasterListFilterInput,
-    StorePreferenceNode,
+    CurrenciesResponse, CurrencyFilterInput, CurrencySortInput, InvoiceLineConnector, InvoiceNode,
+    MasterListFilterInput, StockLineConnector, StorePreferenceNode,
 };
 use mutations::{
+    accept_redistribution::{
+        accept_redistribution, AcceptRedistributionInput, AcceptRedistributionResponse,
+    },
     barcode::{insert_barcode, BarcodeInput},
     common::SyncSettingsInput,
     display_settings::{
         update_display_settings, DisplaySettingsInput, UpdateDisplaySettingsResponse,
     },
+    goods_received::{
+        inspect_goods_received_line, receive_goods, InspectGoodsReceivedLineInput,
+        ReceiveGoodsInput,
+    },
     initialise_site::{initialise_site, InitialiseSiteResponse},
     label_printer_settings::{
         update_label_printer_settings, LabelPrinterSettingsInput,
         UpdateLabelPrinterSettingsResponse,
     },
+    local_auth::{
+        change_password, confirm_totp_enrolment, disable_totp, start_totp_enrolment,
+        unlock_user_account, update_auth_policy, ChangePasswordInput, TotpEnrolmentNode,
+        UpdateAuthPolicyInput,
+    },
     log::{update_log_level, LogLevelInput, UpsertLogLevelResponse},
     manual_sync::manual_sync,
+    period_lock::{set_period_lock, SetPeriodLockInput},
+    recall::{close_recall, generate_recall_supplier_returns, insert_recall, InsertRecallInput},
+    role::{
+        assign_user_role, remove_user_role, upsert_role, AssignUserRoleInput, UpsertRoleInput,
+        UserRoleNode,
+    },
     sync_settings::{update_sync_settings, UpdateSyncSettingsResponse},
     update_name_properties::{
         update_name_properties, UpdateNamePropertiesInput, UpdateNamePropertiesResponse,
     },
     update_user,
+    user_session::{
+        delete_api_token, insert_api_token, revoke_user_session, InsertApiTokenInput,
+        NewApiTokenNode,
+    },
 };
 use queries::{
     currency::currencies,
@@ -58,8 +81,29 @@ impl GeneralQueries {
         ctx: &Context<'_>,
         #[graphql(desc = "UserName")] username: String,
         #[graphql(desc = "Password")] password: String,
+        #[graphql(desc = "Code from the authenticator app, for users with TOTP enabled")]
+        totp_code: Option<String>,
     ) -> Result<AuthTokenResponse> {
-        login(ctx, &username, &password).await
+        login(ctx, &username, &password, totp_code).await
+    }
+
+    /// Url of the OpenID Connect login page, the identity provider redirects back with the
+    /// `code` and `state` for `oidcAuthToken`
+    pub async fn oidc_authorisation_url(&self, ctx: &Context<'_>) -> Result<String> {
+        oidc_authorisation_url(ctx).await
+    }
+
+    /// Retrieves a new auth bearer and refresh token after an OpenID Connect login
+    /// The refresh token is returned as a cookie
+    pub async fn oidc_auth_token(
+        &self,
+        ctx: &Context<'_>,
+        code: String,
+        state: String,
+        #[graphql(desc = "Code from the authenticator app, for users with TOTP enabled")]
+        totp_code: Option<String>,
+    ) -> Result<AuthTokenResponse> {
+        oidc_login(ctx, &code, &state, totp_code).await
     }
 
     pub async fn item_price(
@@ -186,6 +230,72 @@ impl GeneralQueries {
         stock_counts(ctx, store_id, timezone_offset, days_till_expired)
     }
 
+    /// Items with stock expected to expire before it is consumed, based on average monthly
+    /// consumption
+    pub async fn expiry_risk(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Timezone offset")] timezone_offset: Option<i32>,
+    ) -> Result<Vec<ItemExpiryRiskNode>> {
+        expiry_risk(ctx, store_id, timezone_offset)
+    }
+
+    /// Suggested transfers of stock at risk of expiry between stores (central server only)
+    pub async fn redistribution_suggestions(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Timezone offset")] timezone_offset: Option<i32>,
+        #[graphql(desc = "Months of stock under-stocked stores are brought up to")]
+        target_months_of_stock: Option<f64>,
+    ) -> Result<Vec<RedistributionSuggestionNode>> {
+        redistribution_suggestions(ctx, store_id, timezone_offset, target_months_of_stock)
+    }
+
+    /// Recalls synced from the central server, most recent first
+    pub async fn recalls(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        item_id: Option<String>,
+        #[graphql(desc = "Only recalls that have not been closed")] is_active: Option<bool>,
+    ) -> Result<Vec<RecallNode>> {
+        recalls(ctx, store_id, item_id, is_active)
+    }
+
+    /// Stock matching the recall in all stores on this site
+    pub async fn recalled_stock(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        recall_id: String,
+    ) -> Result<StockLineConnector> {
+        recalled_stock(ctx, store_id, recall_id)
+    }
+
+    /// Outbound shipment and prescription lines that issued recalled stock to customers and
+    /// patients
+    pub async fn recall_recipients(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        recall_id: String,
+    ) -> Result<InvoiceLineConnector> {
+        recall_recipients(ctx, store_id, recall_id)
+    }
+
+    /// Expected and received quantities of an inbound shipment, or of the inbound shipment
+    /// created from an outbound shipment of the store
+    pub async fn goods_received_lines(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        invoice_id: String,
+    ) -> Result<Vec<GoodsReceivedLineNode>> {
+        goods_received_lines(ctx, store_id, invoice_id)
+    }
+
     pub async fn requisition_line_chart(
         &self,
         ctx: &Context<'_>,
@@ -214,6 +324,47 @@ impl GeneralQueries {
         activity_logs(ctx, page, filter, sort)
     }
 
+    /// Field level changes of a stock related record, oldest first
+    pub async fn audit_trail(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        record_type: AuditedRecordTypeInput,
+        record_id: String,
+    ) -> Result<Vec<AuditTrailNode>> {
+        audit_trail(ctx, store_id, record_type, record_id)
+    }
+
+    /// Exports the changes to the store's records in the period, returns the file id
+    pub async fn export_audit_trail(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        from_datetime: DateTime<Utc>,
+        to_datetime: DateTime<Utc>,
+        format: AuditTrailExportFormatInput,
+    ) -> Result<String> {
+        export_audit_trail(ctx, store_id, from_datetime, to_datetime, format)
+    }
+
+    /// Current period close of the store, null if no period has been closed
+    pub async fn period_lock(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Option<PeriodLockNode>> {
+        period_lock(ctx, store_id)
+    }
+
+    /// Period closes and re-opens of the store, latest first
+    pub async fn period_lock_history(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<PeriodLockNode>> {
+        period_lock_history(ctx, store_id)
+    }
+
     /// Available without authorisation in operational and initialisation states
     pub async fn initialisation_status(
         &self,
@@ -336,6 +487,36 @@ impl GeneralQueries {
         currencies(ctx, filter, sort)
     }
 
+    /// Sessions of logged in users that haven't expired
+    pub async fn user_sessions(&self, ctx: &Context<'_>) -> Result<Vec<UserSessionNode>> {
+        user_sessions(ctx)
+    }
+
+    /// Api tokens for integrations
+    pub async fn api_tokens(&self, ctx: &Context<'_>) -> Result<Vec<ApiTokenNode>> {
+        api_tokens(ctx)
+    }
+
+    /// Roles defined on the central server
+    pub async fn roles(&self, ctx: &Context<'_>) -> Result<Vec<RoleNode>> {
+        roles(ctx)
+    }
+
+    /// Permissions of a user, including those given by the user's roles
+    pub async fn effective_permissions(
+        &self,
+        ctx: &Context<'_>,
+        user_id: String,
+        #[graphql(desc = "Only permissions in this store")] store_id: Option<String>,
+    ) -> Result<Vec<EffectivePermissionNode>> {
+        effective_permissions(ctx, user_id, store_id)
+    }
+
+    /// Lockout and password policy for logins on this site
+    pub async fn auth_policy(&self, ctx: &Context<'_>) -> Result<AuthPolicyNode> {
+        auth_policy(ctx)
+    }
+
     pub async fn database_settings(&self, ctx: &Context<'_>) -> Result<DatabaseSettingsNode> {
         database_settings(ctx)
     }
@@ -483,6 +664,154 @@ impl GeneralMutations {
     ) -> Result<UpdateNamePropertiesResponse> {
         update_name_properties(ctx, &store_id, input)
     }
+
+    pub async fn accept_redistribution(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: AcceptRedistributionInput,
+    ) -> Result<AcceptRedistributionResponse> {
+        accept_redistribution(ctx, &store_id, input)
+    }
+
+    /// Creates a recall (central server only)
+    pub async fn insert_recall(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: InsertRecallInput,
+    ) -> Result<RecallNode> {
+        insert_recall(ctx, &store_id, input)
+    }
+
+    /// Closes a recall (central server only)
+    pub async fn close_recall(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<RecallNode> {
+        close_recall(ctx, &store_id, &id)
+    }
+
+    pub async fn generate_recall_supplier_returns(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        recall_id: String,
+    ) -> Result<Vec<InvoiceNode>> {
+        generate_recall_supplier_returns(ctx, &store_id, &recall_id)
+    }
+
+    /// Receives an inbound shipment, recording discrepancies and quarantined lines
+    pub async fn receive_goods(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: ReceiveGoodsInput,
+    ) -> Result<InvoiceNode> {
+        receive_goods(ctx, &store_id, input)
+    }
+
+    pub async fn inspect_goods_received_line(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: InspectGoodsReceivedLineInput,
+    ) -> Result<GoodsReceivedLineNode> {
+        inspect_goods_received_line(ctx, &store_id, input)
+    }
+
+    /// Logs out a single user session
+    pub async fn revoke_user_session(&self, ctx: &Context<'_>, id: String) -> Result<String> {
+        revoke_user_session(ctx, &id)
+    }
+
+    /// Creates a long lived api token for integrations, the token is only returned once
+    pub async fn insert_api_token(
+        &self,
+        ctx: &Context<'_>,
+        input: InsertApiTokenInput,
+    ) -> Result<NewApiTokenNode> {
+        insert_api_token(ctx, input)
+    }
+
+    pub async fn delete_api_token(&self, ctx: &Context<'_>, id: String) -> Result<String> {
+        delete_api_token(ctx, &id)
+    }
+
+    /// Creates or updates a role (central server only)
+    pub async fn upsert_role(&self, ctx: &Context<'_>, input: UpsertRoleInput) -> Result<RoleNode> {
+        upsert_role(ctx, input)
+    }
+
+    /// Assigns a role to a user in a store (central server only)
+    pub async fn assign_user_role(
+        &self,
+        ctx: &Context<'_>,
+        input: AssignUserRoleInput,
+    ) -> Result<UserRoleNode> {
+        assign_user_role(ctx, input)
+    }
+
+    pub async fn remove_user_role(&self, ctx: &Context<'_>, id: String) -> Result<String> {
+        remove_user_role(ctx, &id)
+    }
+
+    /// Closes (or re-opens) periods of the store, stock movements on or before the lock date
+    /// can't be created or edited without the period lock override permission
+    pub async fn set_period_lock(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: SetPeriodLockInput,
+    ) -> Result<PeriodLockNode> {
+        set_period_lock(ctx, &store_id, input)
+    }
+
+    pub async fn update_auth_policy(
+        &self,
+        ctx: &Context<'_>,
+        input: UpdateAuthPolicyInput,
+    ) -> Result<AuthPolicyNode> {
+        update_auth_policy(ctx, input)
+    }
+
+    /// Lifts the lockout of a user account after too many failed logins
+    pub async fn unlock_user_account(&self, ctx: &Context<'_>, user_id: String) -> Result<String> {
+        unlock_user_account(ctx, &user_id)
+    }
+
+    /// Changes the password of a user managed on this site, also available with an expired
+    /// password
+    pub async fn change_password(
+        &self,
+        ctx: &Context<'_>,
+        input: ChangePasswordInput,
+    ) -> Result<String> {
+        change_password(ctx, input)
+    }
+
+    /// Starts the TOTP second factor enrolment of the logged in user
+    pub async fn start_totp_enrolment(&self, ctx: &Context<'_>) -> Result<TotpEnrolmentNode> {
+        start_totp_enrolment(ctx)
+    }
+
+    /// Enables the second factor once a code from the authenticator app is confirmed
+    pub async fn confirm_totp_enrolment(&self, ctx: &Context<'_>, code: String) -> Result<String> {
+        confirm_totp_enrolment(ctx, &code)
+    }
+
+    /// Disables the second factor of the logged in user, requires a current code. Server admins
+    /// can disable the second factor of any user by passing `userId`.
+    pub async fn disable_totp(
+        &self,
+        ctx: &Context<'_>,
+        code: Option<String>,
+        user_id: Option<String>,
+    ) -> Result<String> {
+        disable_totp(ctx, code, user_id)
+    }
 }
 
 /// Auth is not checked during initialisation stage
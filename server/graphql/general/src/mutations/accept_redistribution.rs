@@ -0,0 +1,146 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::InvoiceNode;
+use repository::RedistributionRequestRow;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    expiry_risk::accept::{
+        AcceptRedistribution, AcceptRedistributionError, AcceptedRedistribution, RedistributionLine,
+    },
+};
+
+#[derive(InputObject)]
+pub struct RedistributionLineInput {
+    pub id: String,
+    pub stock_line_id: String,
+    pub number_of_packs: f64,
+}
+
+#[derive(InputObject)]
+pub struct AcceptRedistributionInput {
+    pub outbound_shipment_id: String,
+    pub to_store_id: String,
+    pub lines: Vec<RedistributionLineInput>,
+}
+
+pub struct RedistributionRequestNode {
+    pub request: RedistributionRequestRow,
+}
+
+#[Object]
+impl RedistributionRequestNode {
+    pub async fn id(&self) -> &str {
+        &self.request.id
+    }
+
+    /// Store the stock is redistributed from, the outbound shipment is created on its site
+    pub async fn store_id(&self) -> &str {
+        &self.request.store_id
+    }
+
+    pub async fn to_store_id(&self) -> &str {
+        &self.request.to_store_id
+    }
+
+    pub async fn outbound_shipment_id(&self) -> &str {
+        &self.request.outbound_shipment_id
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.request.created_datetime, Utc)
+    }
+
+    /// Set once the outbound shipment has been created and the request has synced back
+    pub async fn processed_datetime(&self) -> Option<DateTime<Utc>> {
+        self.request
+            .processed_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+}
+
+#[derive(Union)]
+pub enum AcceptRedistributionResponse {
+    Shipment(InvoiceNode),
+    Request(RedistributionRequestNode),
+}
+
+impl From<AcceptRedistributionInput> for AcceptRedistribution {
+    fn from(
+        AcceptRedistributionInput {
+            outbound_shipment_id,
+            to_store_id,
+            lines,
+        }: AcceptRedistributionInput,
+    ) -> Self {
+        AcceptRedistribution {
+            outbound_shipment_id,
+            to_store_id,
+            lines: lines
+                .into_iter()
+                .map(
+                    |RedistributionLineInput {
+                         id,
+                         stock_line_id,
+                         number_of_packs,
+                     }| RedistributionLine {
+                        id,
+                        stock_line_id,
+                        number_of_packs,
+                    },
+                )
+                .collect(),
+        }
+    }
+}
+
+/// Creates a draft outbound shipment from the store to the receiving store for accepted
+/// redistribution suggestions. For a store active on another site a redistribution request is
+/// returned instead, the shipment is created on that site once the request has synced.
+pub fn accept_redistribution(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: AcceptRedistributionInput,
+) -> Result<AcceptRedistributionResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateOutboundShipment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let accepted = service_provider
+        .expiry_risk_service
+        .accept_redistribution(&service_context, input.into())
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                AcceptRedistributionError::NoLinesSelected
+                | AcceptRedistributionError::ToStoreDoesNotExist
+                | AcceptRedistributionError::CannotRedistributeToSameStore
+                | AcceptRedistributionError::ShipmentError(_)
+                | AcceptRedistributionError::LineError { .. } => BadUserInput(formatted_error),
+                AcceptRedistributionError::NewlyCreatedInvoiceDoesNotExist
+                | AcceptRedistributionError::InvalidRequestLines(_)
+                | AcceptRedistributionError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(match accepted {
+        AcceptedRedistribution::Shipment(invoice) => {
+            AcceptRedistributionResponse::Shipment(InvoiceNode::from_domain(invoice))
+        }
+        AcceptedRedistribution::Request(request) => {
+            AcceptRedistributionResponse::Request(RedistributionRequestNode { request })
+        }
+    })
+}
@@ -0,0 +1,155 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::InvoiceNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    goods_received::{
+        inspect::{InspectGoodsReceivedLine, InspectGoodsReceivedLineError},
+        receive::{ReceiveGoods, ReceiveGoodsError, ReceiveGoodsLine},
+    },
+};
+
+use crate::queries::goods_received::{GoodsReceivedDiscrepancyReasonNode, GoodsReceivedLineNode};
+
+#[derive(InputObject)]
+pub struct ReceiveGoodsLineInput {
+    pub invoice_line_id: String,
+    pub received_number_of_packs: f64,
+    /// Required when the received number of packs differs from the expected number of packs
+    pub discrepancy_reason: Option<GoodsReceivedDiscrepancyReasonNode>,
+    pub comment: Option<String>,
+    /// Place received stock on hold pending quality inspection
+    pub quarantine: Option<bool>,
+}
+
+#[derive(InputObject)]
+pub struct ReceiveGoodsInput {
+    pub invoice_id: String,
+    /// Lines that are not included are received as expected
+    pub lines: Vec<ReceiveGoodsLineInput>,
+}
+
+#[derive(InputObject)]
+pub struct InspectGoodsReceivedLineInput {
+    pub id: String,
+    pub passed: bool,
+    pub comment: Option<String>,
+}
+
+impl From<ReceiveGoodsInput> for ReceiveGoods {
+    fn from(ReceiveGoodsInput { invoice_id, lines }: ReceiveGoodsInput) -> Self {
+        ReceiveGoods {
+            invoice_id,
+            lines: lines
+                .into_iter()
+                .map(
+                    |ReceiveGoodsLineInput {
+                         invoice_line_id,
+                         received_number_of_packs,
+                         discrepancy_reason,
+                         comment,
+                         quarantine,
+                     }| ReceiveGoodsLine {
+                        invoice_line_id,
+                        received_number_of_packs,
+                        discrepancy_reason: discrepancy_reason
+                            .map(GoodsReceivedDiscrepancyReasonNode::to_domain),
+                        comment,
+                        quarantine: quarantine.unwrap_or(false),
+                    },
+                )
+                .collect(),
+        }
+    }
+}
+
+pub fn receive_goods(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: ReceiveGoodsInput,
+) -> Result<InvoiceNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateInboundShipment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let invoice = service_provider
+        .goods_received_service
+        .receive_goods(&service_context, input.into())
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                ReceiveGoodsError::InvoiceDoesNotExist
+                | ReceiveGoodsError::NotThisStoreInvoice
+                | ReceiveGoodsError::NotAnInboundShipment
+                | ReceiveGoodsError::InvoiceAlreadyReceived
+                | ReceiveGoodsError::LineDoesNotExist(_)
+                | ReceiveGoodsError::NumberOfPacksBelowZero(_)
+                | ReceiveGoodsError::DiscrepancyReasonRequired(_)
+                | ReceiveGoodsError::InvalidDiscrepancyReason(_)
+                | ReceiveGoodsError::LineUpdateError { .. }
+                | ReceiveGoodsError::InvoiceUpdateError(_) => BadUserInput(formatted_error),
+                ReceiveGoodsError::UpdatedInvoiceDoesNotExist
+                | ReceiveGoodsError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(InvoiceNode::from_domain(invoice))
+}
+
+/// Records the quality inspection result of a quarantined line, stock is released from hold
+/// when it passes
+pub fn inspect_goods_received_line(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: InspectGoodsReceivedLineInput,
+) -> Result<GoodsReceivedLineNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateInboundShipment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let line = service_provider
+        .goods_received_service
+        .inspect_goods_received_line(
+            &service_context,
+            InspectGoodsReceivedLine {
+                id: input.id,
+                passed: input.passed,
+                comment: input.comment,
+            },
+        )
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                InspectGoodsReceivedLineError::GoodsReceivedLineDoesNotExist
+                | InspectGoodsReceivedLineError::NotThisStoreGoodsReceivedLine
+                | InspectGoodsReceivedLineError::NotPendingInspection => {
+                    BadUserInput(formatted_error)
+                }
+                InspectGoodsReceivedLineError::UpdatedGoodsReceivedLineDoesNotExist
+                | InspectGoodsReceivedLineError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(GoodsReceivedLineNode::from_domain(line))
+}
@@ -0,0 +1,258 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    local_auth::{
+        lockout::UnlockUserAccountError,
+        password::{ChangePassword, ChangePasswordError},
+        totp::{
+            ConfirmTotpEnrolmentError, DisableTotpError, StartTotpEnrolmentError, TotpEnrolment,
+        },
+        AuthPolicy, UpdateAuthPolicyError,
+    },
+};
+
+use crate::queries::{local_auth::AuthPolicyNode, user_session::validate_server_admin};
+
+#[derive(InputObject)]
+pub struct UpdateAuthPolicyInput {
+    pub max_failed_logins: u32,
+    pub lockout_duration_minutes: u32,
+    pub min_password_length: u32,
+    pub require_mixed_characters: bool,
+    pub max_password_age_days: u32,
+}
+
+#[derive(InputObject)]
+pub struct ChangePasswordInput {
+    pub username: String,
+    pub current_password: String,
+    pub new_password: String,
+    /// Required if the user has a TOTP second factor
+    pub totp_code: Option<String>,
+}
+
+pub struct TotpEnrolmentNode {
+    pub totp_enrolment: TotpEnrolment,
+}
+
+#[Object]
+impl TotpEnrolmentNode {
+    /// Base32 encoded secret, for manual entry in the authenticator app
+    pub async fn secret(&self) -> &str {
+        &self.totp_enrolment.secret
+    }
+
+    /// otpauth:// uri, usually shown as a QR code
+    pub async fn uri(&self) -> &str {
+        &self.totp_enrolment.uri
+    }
+}
+
+/// Any logged in user can manage their own second factor
+fn validate_logged_in_user(ctx: &Context<'_>) -> Result<String> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::RouteMe,
+            store_id: None,
+        },
+    )?;
+    Ok(user.user_id)
+}
+
+pub fn update_auth_policy(
+    ctx: &Context<'_>,
+    input: UpdateAuthPolicyInput,
+) -> Result<AuthPolicyNode> {
+    let user_id = validate_server_admin(ctx)?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user_id)?;
+
+    let UpdateAuthPolicyInput {
+        max_failed_logins,
+        lockout_duration_minutes,
+        min_password_length,
+        require_mixed_characters,
+        max_password_age_days,
+    } = input;
+
+    let auth_policy = service_provider
+        .local_auth_service
+        .update_auth_policy(
+            &service_context,
+            AuthPolicy {
+                max_failed_logins,
+                lockout_duration_minutes,
+                min_password_length,
+                require_mixed_characters,
+                max_password_age_days,
+            },
+        )
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                UpdateAuthPolicyError::LockoutDurationRequired
+                | UpdateAuthPolicyError::MinPasswordLengthRequired => BadUserInput(formatted_error),
+                UpdateAuthPolicyError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(AuthPolicyNode::from_domain(auth_policy))
+}
+
+/// Lifts the lockout of a user before it expires
+pub fn unlock_user_account(ctx: &Context<'_>, user_id: &str) -> Result<String> {
+    let admin_user_id = validate_server_admin(ctx)?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), admin_user_id)?;
+
+    service_provider
+        .local_auth_service
+        .unlock_user_account(&service_context, user_id)
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                UnlockUserAccountError::UserDoesNotExist
+                | UnlockUserAccountError::UserAccountNotLocked => BadUserInput(formatted_error),
+                UnlockUserAccountError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(user_id.to_string())
+}
+
+/// Changes the password of a user managed on this site. Doesn't require a logged in user, so
+/// users with an expired password can still change it.
+pub fn change_password(ctx: &Context<'_>, input: ChangePasswordInput) -> Result<String> {
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+
+    let username = input.username.clone();
+    service_provider
+        .local_auth_service
+        .change_password(
+            &service_context,
+            ChangePassword {
+                username: input.username,
+                current_password: input.current_password,
+                new_password: input.new_password,
+                totp_code: input.totp_code,
+            },
+        )
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                ChangePasswordError::InvalidCredentials
+                | ChangePasswordError::TotpRequired
+                | ChangePasswordError::InvalidTotpCode
+                | ChangePasswordError::AccountBlocked(_)
+                | ChangePasswordError::NotLocallyManaged
+                | ChangePasswordError::PasswordUnchanged
+                | ChangePasswordError::PasswordPolicyViolation(_) => BadUserInput(formatted_error),
+                ChangePasswordError::PasswordHashError(_)
+                | ChangePasswordError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(username)
+}
+
+/// Starts the TOTP enrolment of the logged in user, the second factor is enforced once confirmed
+pub fn start_totp_enrolment(ctx: &Context<'_>) -> Result<TotpEnrolmentNode> {
+    let user_id = validate_logged_in_user(ctx)?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user_id)?;
+
+    let totp_enrolment = service_provider
+        .local_auth_service
+        .start_totp_enrolment(&service_context)
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                StartTotpEnrolmentError::UserDoesNotExist
+                | StartTotpEnrolmentError::TotpAlreadyEnabled => BadUserInput(formatted_error),
+                StartTotpEnrolmentError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(TotpEnrolmentNode { totp_enrolment })
+}
+
+pub fn confirm_totp_enrolment(ctx: &Context<'_>, code: &str) -> Result<String> {
+    let user_id = validate_logged_in_user(ctx)?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user_id.clone())?;
+
+    service_provider
+        .local_auth_service
+        .confirm_totp_enrolment(&service_context, code)
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                ConfirmTotpEnrolmentError::NoTotpEnrolmentStarted
+                | ConfirmTotpEnrolmentError::TotpAlreadyEnabled
+                | ConfirmTotpEnrolmentError::InvalidTotpCode => BadUserInput(formatted_error),
+                ConfirmTotpEnrolmentError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(user_id)
+}
+
+/// Disables the second factor of the logged in user, or of any user when `user_id` is provided by
+/// a server admin (e.g. for a lost device)
+pub fn disable_totp(
+    ctx: &Context<'_>,
+    code: Option<String>,
+    user_id: Option<String>,
+) -> Result<String> {
+    let (logged_in_user_id, user_id, code) = match user_id {
+        Some(user_id) => (validate_server_admin(ctx)?, user_id, None),
+        None => {
+            let logged_in_user_id = validate_logged_in_user(ctx)?;
+            let code = code.ok_or_else(|| {
+                StandardGraphqlError::BadUserInput("Authenticator code is required".to_string())
+                    .extend()
+            })?;
+            (logged_in_user_id.clone(), logged_in_user_id, Some(code))
+        }
+    };
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), logged_in_user_id)?;
+
+    service_provider
+        .local_auth_service
+        .disable_totp(&service_context, &user_id, code.as_deref())
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                DisableTotpError::TotpNotEnabled | DisableTotpError::InvalidTotpCode => {
+                    BadUserInput(formatted_error)
+                }
+                DisableTotpError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(user_id)
+}
@@ -1,10 +1,17 @@
+pub mod accept_redistribution;
 pub mod barcode;
 pub mod common;
 pub mod display_settings;
+pub mod goods_received;
 pub mod initialise_site;
 pub mod label_printer_settings;
+pub mod local_auth;
 pub mod log;
 pub mod manual_sync;
+pub mod period_lock;
+pub mod recall;
+pub mod role;
 pub mod sync_settings;
 pub mod update_name_properties;
 pub mod update_user;
+pub mod user_session;
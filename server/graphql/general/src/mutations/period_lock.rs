@@ -0,0 +1,67 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    period_lock::set::{SetPeriodLock, SetPeriodLockError},
+};
+
+use crate::queries::period_lock::PeriodLockNode;
+
+#[derive(InputObject)]
+pub struct SetPeriodLockInput {
+    pub id: String,
+    /// Stock movements on or before this date can't be created or edited, null re-opens all
+    /// periods
+    pub lock_date: Option<NaiveDate>,
+    pub comment: Option<String>,
+}
+
+/// Closes the periods of the store up to and including the lock date
+pub fn set_period_lock(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: SetPeriodLockInput,
+) -> Result<PeriodLockNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutatePeriodLock,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let SetPeriodLockInput {
+        id,
+        lock_date,
+        comment,
+    } = input;
+    let period_lock = service_provider
+        .period_lock_service
+        .set_period_lock(
+            &service_context,
+            SetPeriodLock {
+                id,
+                lock_date,
+                comment,
+            },
+        )
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                SetPeriodLockError::PeriodLockAlreadyExists
+                | SetPeriodLockError::LockDateInFuture => BadUserInput(formatted_error),
+                SetPeriodLockError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(PeriodLockNode::from_domain(period_lock))
+}
@@ -0,0 +1,167 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::InvoiceNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    recall::{
+        close::CloseRecallError,
+        insert::{InsertRecall, InsertRecallError},
+        supplier_return::GenerateRecallSupplierReturnsError,
+    },
+};
+
+use crate::queries::recall::RecallNode;
+
+#[derive(InputObject)]
+pub struct InsertRecallInput {
+    pub id: String,
+    pub item_id: String,
+    pub item_variant_id: Option<String>,
+    pub manufacturer_id: Option<String>,
+    pub batch: Option<String>,
+    pub expiry_date_from: Option<NaiveDate>,
+    pub expiry_date_to: Option<NaiveDate>,
+    pub reason: Option<String>,
+}
+
+impl From<InsertRecallInput> for InsertRecall {
+    fn from(
+        InsertRecallInput {
+            id,
+            item_id,
+            item_variant_id,
+            manufacturer_id,
+            batch,
+            expiry_date_from,
+            expiry_date_to,
+            reason,
+        }: InsertRecallInput,
+    ) -> Self {
+        InsertRecall {
+            id,
+            item_id,
+            item_variant_id,
+            manufacturer_id,
+            batch,
+            expiry_date_from,
+            expiry_date_to,
+            reason,
+        }
+    }
+}
+
+/// Creates a recall on the central server, matching stock is placed on hold on all sites
+pub fn insert_recall(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: InsertRecallInput,
+) -> Result<RecallNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateItems,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let recall = service_provider
+        .recall_service
+        .insert_recall(&service_context, input.into())
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                InsertRecallError::NotACentralServer
+                | InsertRecallError::RecallAlreadyExists
+                | InsertRecallError::ItemDoesNotExist
+                | InsertRecallError::ItemVariantDoesNotExist
+                | InsertRecallError::ManufacturerDoesNotExist
+                | InsertRecallError::InvalidExpiryDateRange => BadUserInput(formatted_error),
+                InsertRecallError::NewlyCreatedRecallDoesNotExist
+                | InsertRecallError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(RecallNode::from_domain(recall))
+}
+
+pub fn close_recall(ctx: &Context<'_>, store_id: &str, id: &str) -> Result<RecallNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateItems,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let recall = service_provider
+        .recall_service
+        .close_recall(&service_context, id)
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                CloseRecallError::NotACentralServer
+                | CloseRecallError::RecallDoesNotExist
+                | CloseRecallError::RecallAlreadyClosed => BadUserInput(formatted_error),
+                CloseRecallError::UpdatedRecallDoesNotExist
+                | CloseRecallError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(RecallNode::from_domain(recall))
+}
+
+/// Creates a supplier return per supplier for the available recalled stock of the store
+pub fn generate_recall_supplier_returns(
+    ctx: &Context<'_>,
+    store_id: &str,
+    recall_id: &str,
+) -> Result<Vec<InvoiceNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateSupplierReturn,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let supplier_returns = service_provider
+        .recall_service
+        .generate_recall_supplier_returns(&service_context, recall_id)
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                GenerateRecallSupplierReturnsError::RecallDoesNotExist
+                | GenerateRecallSupplierReturnsError::NoRecalledStockToReturn
+                | GenerateRecallSupplierReturnsError::SupplierReturnError { .. } => {
+                    BadUserInput(formatted_error)
+                }
+                GenerateRecallSupplierReturnsError::DatabaseError(_) => {
+                    InternalError(formatted_error)
+                }
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(supplier_returns
+        .into_iter()
+        .map(InvoiceNode::from_domain)
+        .collect())
+}
@@ -0,0 +1,167 @@
+use async_graphql::*;
+use graphql_core::{standard_graphql_error::StandardGraphqlError, ContextExt};
+use graphql_types::types::UserPermission;
+use service::role::{
+    assign::{AssignUserRole, AssignUserRoleError},
+    upsert::{RolePermissionInput, UpsertRole, UpsertRoleError},
+};
+
+use crate::queries::{role::RoleNode, user_session::validate_server_admin};
+
+#[derive(InputObject)]
+pub struct RolePermissionNodeInput {
+    pub permission: UserPermission,
+    pub context_id: Option<String>,
+}
+
+#[derive(InputObject)]
+pub struct UpsertRoleInput {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_active: bool,
+    /// Replaces all permissions of the role
+    pub permissions: Vec<RolePermissionNodeInput>,
+}
+
+#[derive(InputObject)]
+pub struct AssignUserRoleInput {
+    pub id: String,
+    pub user_id: String,
+    pub role_id: String,
+    /// Store the role applies in, a role without a store only applies outside of stores
+    pub store_id: Option<String>,
+}
+
+pub struct UserRoleNode {
+    pub id: String,
+    pub user_id: String,
+    pub role_id: String,
+    pub store_id: Option<String>,
+}
+
+#[Object]
+impl UserRoleNode {
+    pub async fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub async fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    pub async fn role_id(&self) -> &str {
+        &self.role_id
+    }
+
+    pub async fn store_id(&self) -> &Option<String> {
+        &self.store_id
+    }
+}
+
+/// Creates or updates a role on the central server, roles are synced to all sites
+pub fn upsert_role(ctx: &Context<'_>, input: UpsertRoleInput) -> Result<RoleNode> {
+    let user_id = validate_server_admin(ctx)?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user_id)?;
+
+    let UpsertRoleInput {
+        id,
+        name,
+        description,
+        is_active,
+        permissions,
+    } = input;
+    let role = service_provider
+        .role_service
+        .upsert_role(
+            &service_context,
+            UpsertRole {
+                id,
+                name,
+                description,
+                is_active,
+                permissions: permissions
+                    .into_iter()
+                    .map(|input| RolePermissionInput {
+                        permission: input.permission.to_domain(),
+                        context_id: input.context_id,
+                    })
+                    .collect(),
+            },
+        )
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                UpsertRoleError::NotACentralServer
+                | UpsertRoleError::RoleNameCannotBeEmpty
+                | UpsertRoleError::RoleNameAlreadyExists => BadUserInput(formatted_error),
+                UpsertRoleError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(RoleNode { role })
+}
+
+pub fn assign_user_role(ctx: &Context<'_>, input: AssignUserRoleInput) -> Result<UserRoleNode> {
+    let user_id = validate_server_admin(ctx)?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user_id)?;
+
+    let AssignUserRoleInput {
+        id,
+        user_id,
+        role_id,
+        store_id,
+    } = input;
+    let user_role = service_provider
+        .role_service
+        .assign_user_role(
+            &service_context,
+            AssignUserRole {
+                id,
+                user_id,
+                role_id,
+                store_id,
+            },
+        )
+        .map_err(map_assign_error)?;
+
+    Ok(UserRoleNode {
+        id: user_role.id,
+        user_id: user_role.user_id,
+        role_id: user_role.role_id,
+        store_id: user_role.store_id,
+    })
+}
+
+pub fn remove_user_role(ctx: &Context<'_>, id: &str) -> Result<String> {
+    let user_id = validate_server_admin(ctx)?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user_id)?;
+
+    service_provider
+        .role_service
+        .remove_user_role(&service_context, id)
+        .map_err(map_assign_error)
+}
+
+fn map_assign_error(error: AssignUserRoleError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+    let graphql_error = match error {
+        AssignUserRoleError::NotACentralServer
+        | AssignUserRoleError::UserDoesNotExist
+        | AssignUserRoleError::RoleDoesNotExist
+        | AssignUserRoleError::StoreDoesNotExist
+        | AssignUserRoleError::RoleAlreadyAssigned
+        | AssignUserRoleError::UserRoleDoesNotExist => BadUserInput(formatted_error),
+        AssignUserRoleError::DatabaseError(_) => InternalError(formatted_error),
+    };
+    graphql_error.extend()
+}
@@ -80,10 +80,14 @@ pub async fn update_user(ctx: &Context<'_>) -> Result<UpdateResponse> {
                 | LoginError::UpdateUserError(_)
                 | LoginError::LoginFailure(LoginFailure::AccountBlocked(_))
                 | LoginError::LoginFailure(LoginFailure::NoSiteAccess)
+                | LoginError::LoginFailure(LoginFailure::TotpRequired)
+                | LoginError::LoginFailure(LoginFailure::InvalidTotpCode)
+                | LoginError::LoginFailure(LoginFailure::PasswordExpired)
                 | LoginError::InternalError(_)
                 | LoginError::DatabaseError(_)
                 | LoginError::FailedToGenerateToken(_) 
                 | LoginError::MSupplyCentralNotReached
+                | LoginError::ExternalAuthError(_)
                 => {
                     StandardGraphqlError::InternalError(formatted_error)
                 }
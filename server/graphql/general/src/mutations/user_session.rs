@@ -0,0 +1,135 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{standard_graphql_error::StandardGraphqlError, ContextExt};
+use service::{
+    api_token::{
+        delete::DeleteApiTokenError,
+        insert::{InsertApiToken, InsertApiTokenError},
+    },
+    auth::Resource,
+    user_session::RevokeUserSessionError,
+};
+
+use crate::queries::user_session::{validate_server_admin, ApiTokenNode};
+
+#[derive(InputObject)]
+pub struct InsertApiTokenInput {
+    pub id: String,
+    pub name: String,
+    /// User the token acts on behalf of, the token can't exceed the permissions of the user
+    pub user_id: String,
+    /// Names of the auth resources the token can access, e.g. QueryStockLine
+    pub resources: Vec<String>,
+    pub store_ids: Vec<String>,
+    pub expiry_datetime: Option<DateTime<Utc>>,
+}
+
+pub struct NewApiTokenNode {
+    pub token: String,
+    pub api_token: ApiTokenNode,
+}
+
+#[Object]
+impl NewApiTokenNode {
+    /// Only returned once, pass it as a bearer token in the Authorization header
+    pub async fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub async fn api_token(&self) -> &ApiTokenNode {
+        &self.api_token
+    }
+}
+
+/// Logs out a single session of a user
+pub fn revoke_user_session(ctx: &Context<'_>, id: &str) -> Result<String> {
+    validate_server_admin(ctx)?;
+
+    let service_provider = ctx.service_provider();
+    service_provider
+        .user_session_service
+        .revoke_user_session(ctx.get_auth_data(), id)
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                RevokeUserSessionError::UserSessionDoesNotExist => BadUserInput(formatted_error),
+                RevokeUserSessionError::InternalError(_)
+                | RevokeUserSessionError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })
+}
+
+pub fn insert_api_token(ctx: &Context<'_>, input: InsertApiTokenInput) -> Result<NewApiTokenNode> {
+    let user_id = validate_server_admin(ctx)?;
+
+    let resources = input
+        .resources
+        .iter()
+        .map(|resource| {
+            serde_json::from_value::<Resource>(serde_json::Value::String(resource.clone())).map_err(
+                |_| {
+                    StandardGraphqlError::BadUserInput(format!("Unknown resource {}", resource))
+                        .extend()
+                },
+            )
+        })
+        .collect::<Result<Vec<Resource>>>()?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user_id)?;
+
+    let new_token = service_provider
+        .api_token_service
+        .insert_api_token(
+            &service_context,
+            InsertApiToken {
+                id: input.id,
+                name: input.name,
+                user_id: input.user_id,
+                resources,
+                store_ids: input.store_ids,
+                expiry_datetime: input.expiry_datetime.map(|datetime| datetime.naive_utc()),
+            },
+        )
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                InsertApiTokenError::ApiTokenAlreadyExists
+                | InsertApiTokenError::UserDoesNotExist
+                | InsertApiTokenError::StoreDoesNotExist(_)
+                | InsertApiTokenError::NoResources
+                | InsertApiTokenError::ExpiryInThePast => BadUserInput(formatted_error),
+                InsertApiTokenError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(NewApiTokenNode {
+        token: new_token.token,
+        api_token: ApiTokenNode::from_domain(new_token.api_token),
+    })
+}
+
+/// Revokes an api token
+pub fn delete_api_token(ctx: &Context<'_>, id: &str) -> Result<String> {
+    let user_id = validate_server_admin(ctx)?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user_id)?;
+
+    service_provider
+        .api_token_service
+        .delete_api_token(&service_context, id)
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                DeleteApiTokenError::ApiTokenDoesNotExist => BadUserInput(formatted_error),
+                DeleteApiTokenError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })
+}
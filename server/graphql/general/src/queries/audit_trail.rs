@@ -0,0 +1,176 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::FieldChange;
+use service::{
+    audit_trail::{
+        export::{AuditTrailExportFormat, ExportAuditTrailError},
+        AuditTrailEntry, AuditedRecordType,
+    },
+    auth::{Resource, ResourceAccessRequest},
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(remote = "service::audit_trail::AuditedRecordType")]
+pub enum AuditedRecordTypeInput {
+    Invoice,
+    InvoiceLine,
+    StockLine,
+    StocktakeLine,
+    Requisition,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(remote = "repository::AuditAction")]
+pub enum AuditActionNode {
+    Insert,
+    Update,
+    Delete,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum AuditTrailExportFormatInput {
+    Csv,
+    Json,
+}
+
+pub struct AuditTrailNode {
+    pub entry: AuditTrailEntry,
+}
+
+#[Object]
+impl AuditTrailNode {
+    pub async fn id(&self) -> &str {
+        &self.entry.row.id
+    }
+
+    pub async fn record_id(&self) -> &str {
+        &self.entry.row.record_id
+    }
+
+    /// Null for records that aren't stock related
+    pub async fn record_type(&self) -> Option<AuditedRecordTypeInput> {
+        AuditedRecordType::from_table_name(&self.entry.row.record_table_name)
+            .map(AuditedRecordTypeInput::from)
+    }
+
+    pub async fn action(&self) -> AuditActionNode {
+        AuditActionNode::from(self.entry.row.action.clone())
+    }
+
+    /// Null for changes made by the system, e.g. processors
+    pub async fn user_id(&self) -> &Option<String> {
+        &self.entry.row.user_id
+    }
+
+    pub async fn store_id(&self) -> &Option<String> {
+        &self.entry.row.store_id
+    }
+
+    pub async fn datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.entry.row.datetime, Utc)
+    }
+
+    pub async fn changes(&self) -> Vec<FieldChangeNode> {
+        self.entry
+            .changes
+            .iter()
+            .cloned()
+            .map(|change| FieldChangeNode { change })
+            .collect()
+    }
+}
+
+pub struct FieldChangeNode {
+    pub change: FieldChange,
+}
+
+#[Object]
+impl FieldChangeNode {
+    /// Database column name of the field
+    pub async fn field(&self) -> &str {
+        &self.change.field
+    }
+
+    pub async fn before(&self) -> &serde_json::Value {
+        &self.change.before
+    }
+
+    pub async fn after(&self) -> &serde_json::Value {
+        &self.change.after
+    }
+}
+
+pub fn audit_trail(
+    ctx: &Context<'_>,
+    store_id: String,
+    record_type: AuditedRecordTypeInput,
+    record_id: String,
+) -> Result<Vec<AuditTrailNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryLog,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let entries = service_provider
+        .audit_trail_service
+        .get_record_audit_trail(&service_context, record_type.into(), &record_id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| AuditTrailNode { entry })
+        .collect())
+}
+
+/// Returns the id of the exported file
+pub fn export_audit_trail(
+    ctx: &Context<'_>,
+    store_id: String,
+    from_datetime: DateTime<Utc>,
+    to_datetime: DateTime<Utc>,
+    format: AuditTrailExportFormatInput,
+) -> Result<String> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryLog,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    service_provider
+        .audit_trail_service
+        .export_audit_trail(
+            &service_context,
+            &ctx.get_settings().server.base_dir,
+            from_datetime.naive_utc(),
+            to_datetime.naive_utc(),
+            match format {
+                AuditTrailExportFormatInput::Csv => AuditTrailExportFormat::Csv,
+                AuditTrailExportFormatInput::Json => AuditTrailExportFormat::Json,
+            },
+        )
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                ExportAuditTrailError::InvalidPeriod => BadUserInput(formatted_error),
+                ExportAuditTrailError::DatabaseError(_)
+                | ExportAuditTrailError::FileGenerationError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })
+}
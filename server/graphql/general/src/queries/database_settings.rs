@@ -1,5 +1,6 @@
 use async_graphql::*;
-use graphql_core::standard_graphql_error::validate_auth;
+use graphql_core::{standard_graphql_error::validate_auth, ContextExt};
+use repository::database_settings::DatabaseBackend;
 use service::auth::{Resource, ResourceAccessRequest};
 
 #[derive(Clone, Copy, Eq, PartialEq, Enum)]
@@ -8,18 +9,17 @@ pub enum DatabaseType {
     SQLite,
 }
 
-pub struct DatabaseSettingsNode {}
-
-#[cfg(feature = "postgres")]
-pub static DATABASE_TYPE: DatabaseType = DatabaseType::Postgres;
-
-#[cfg(not(feature = "postgres"))]
-pub static DATABASE_TYPE: DatabaseType = DatabaseType::SQLite;
+pub struct DatabaseSettingsNode {
+    backend: DatabaseBackend,
+}
 
 #[Object]
 impl DatabaseSettingsNode {
     pub async fn database_type(&self) -> DatabaseType {
-        DATABASE_TYPE
+        match self.backend {
+            DatabaseBackend::Postgres => DatabaseType::Postgres,
+            DatabaseBackend::Sqlite => DatabaseType::SQLite,
+        }
     }
 }
 
@@ -32,5 +32,7 @@ pub(crate) fn database_settings(ctx: &Context<'_>) -> Result<DatabaseSettingsNod
         },
     )?;
 
-    Ok(DatabaseSettingsNode {})
+    Ok(DatabaseSettingsNode {
+        backend: ctx.get_settings().database.backend(),
+    })
 }
@@ -0,0 +1,205 @@
+use async_graphql::*;
+use chrono::{NaiveDate, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    expiry_risk::{
+        forecast::{ItemExpiryRisk, StockLineExpiryRisk},
+        redistribution::{GetRedistributionSuggestionsError, RedistributionSuggestion},
+    },
+};
+use util::timezone::offset_to_timezone;
+
+pub struct StockLineExpiryRiskNode {
+    risk: StockLineExpiryRisk,
+}
+
+#[Object]
+impl StockLineExpiryRiskNode {
+    pub async fn stock_line_id(&self) -> &str {
+        &self.risk.stock_line.id
+    }
+
+    pub async fn batch(&self) -> &Option<String> {
+        &self.risk.stock_line.batch
+    }
+
+    pub async fn expiry_date(&self) -> &Option<NaiveDate> {
+        &self.risk.stock_line.expiry_date
+    }
+
+    pub async fn pack_size(&self) -> f64 {
+        self.risk.stock_line.pack_size
+    }
+
+    pub async fn available_number_of_packs(&self) -> f64 {
+        self.risk.stock_line.available_number_of_packs
+    }
+
+    /// Units expected to expire before they are consumed
+    pub async fn quantity_at_risk(&self) -> f64 {
+        self.risk.quantity_at_risk
+    }
+}
+
+pub struct ItemExpiryRiskNode {
+    risk: ItemExpiryRisk,
+}
+
+#[Object]
+impl ItemExpiryRiskNode {
+    pub async fn item_id(&self) -> &str {
+        &self.risk.item_id
+    }
+
+    pub async fn item_name(&self) -> &str {
+        &self.risk.item_name
+    }
+
+    pub async fn average_monthly_consumption(&self) -> f64 {
+        self.risk.average_monthly_consumption
+    }
+
+    pub async fn available_stock_on_hand(&self) -> f64 {
+        self.risk.available_stock_on_hand
+    }
+
+    /// Units expected to expire before they are consumed
+    pub async fn quantity_at_risk(&self) -> f64 {
+        self.risk.quantity_at_risk
+    }
+
+    pub async fn stock_lines(&self) -> Vec<StockLineExpiryRiskNode> {
+        self.risk
+            .stock_lines
+            .iter()
+            .cloned()
+            .map(|risk| StockLineExpiryRiskNode { risk })
+            .collect()
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct RedistributionSuggestionNode {
+    pub item_id: String,
+    pub item_name: String,
+    pub from_store_id: String,
+    pub to_store_id: String,
+    pub stock_line_id: String,
+    pub batch: Option<String>,
+    pub expiry_date: NaiveDate,
+    pub pack_size: f64,
+    pub number_of_packs: f64,
+}
+
+impl RedistributionSuggestionNode {
+    pub fn from_domain(
+        RedistributionSuggestion {
+            item_id,
+            item_name,
+            from_store_id,
+            to_store_id,
+            stock_line_id,
+            batch,
+            expiry_date,
+            pack_size,
+            number_of_packs,
+        }: RedistributionSuggestion,
+    ) -> Self {
+        RedistributionSuggestionNode {
+            item_id,
+            item_name,
+            from_store_id,
+            to_store_id,
+            stock_line_id,
+            batch,
+            expiry_date,
+            pack_size,
+            number_of_packs,
+        }
+    }
+}
+
+fn reference_date(timezone_offset: Option<i32>) -> Result<NaiveDate> {
+    let timezone_offset = offset_to_timezone(&timezone_offset).ok_or(
+        StandardGraphqlError::BadUserInput("Invalid timezone offset".to_string()),
+    )?;
+    Ok(Utc::now().with_timezone(&timezone_offset).date_naive())
+}
+
+pub fn expiry_risk(
+    ctx: &Context<'_>,
+    store_id: String,
+    timezone_offset: Option<i32>,
+) -> Result<Vec<ItemExpiryRiskNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::StockCount,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+    let items = service_provider
+        .expiry_risk_service
+        .get_expiry_risk(
+            &service_context,
+            &store_id,
+            reference_date(timezone_offset)?,
+        )
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(items
+        .into_iter()
+        .map(|risk| ItemExpiryRiskNode { risk })
+        .collect())
+}
+
+pub fn redistribution_suggestions(
+    ctx: &Context<'_>,
+    store_id: String,
+    timezone_offset: Option<i32>,
+    target_months_of_stock: Option<f64>,
+) -> Result<Vec<RedistributionSuggestionNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::StockCount,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let suggestions = service_provider
+        .expiry_risk_service
+        .get_redistribution_suggestions(
+            &service_context,
+            reference_date(timezone_offset)?,
+            target_months_of_stock,
+        )
+        .map_err(|error| {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                GetRedistributionSuggestionsError::NotACentralServer => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                GetRedistributionSuggestionsError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(suggestions
+        .into_iter()
+        .map(RedistributionSuggestionNode::from_domain)
+        .collect())
+}
@@ -0,0 +1,159 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::{
+    GoodsReceivedDiscrepancyReason, GoodsReceivedInspectionStatus, GoodsReceivedLineRow,
+};
+use service::auth::{Resource, ResourceAccessRequest};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GoodsReceivedDiscrepancyReasonNode {
+    Short,
+    Excess,
+    Damaged,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GoodsReceivedInspectionStatusNode {
+    Pending,
+    Passed,
+    Failed,
+}
+
+pub struct GoodsReceivedLineNode {
+    pub row: GoodsReceivedLineRow,
+}
+
+#[Object]
+impl GoodsReceivedLineNode {
+    pub async fn id(&self) -> &str {
+        &self.row.id
+    }
+
+    pub async fn invoice_id(&self) -> &str {
+        &self.row.invoice_id
+    }
+
+    pub async fn invoice_line_id(&self) -> &str {
+        &self.row.invoice_line_id
+    }
+
+    /// Outbound shipment of the supplier store
+    pub async fn linked_invoice_id(&self) -> &Option<String> {
+        &self.row.linked_invoice_id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.row.item_link_id
+    }
+
+    pub async fn batch(&self) -> &Option<String> {
+        &self.row.batch
+    }
+
+    pub async fn expected_number_of_packs(&self) -> f64 {
+        self.row.expected_number_of_packs
+    }
+
+    pub async fn received_number_of_packs(&self) -> Option<f64> {
+        self.row.received_number_of_packs
+    }
+
+    pub async fn has_discrepancy(&self) -> bool {
+        self.row.has_discrepancy()
+    }
+
+    pub async fn discrepancy_reason(&self) -> Option<GoodsReceivedDiscrepancyReasonNode> {
+        self.row
+            .discrepancy_reason
+            .as_ref()
+            .map(GoodsReceivedDiscrepancyReasonNode::from_domain)
+    }
+
+    pub async fn inspection_status(&self) -> Option<GoodsReceivedInspectionStatusNode> {
+        self.row
+            .inspection_status
+            .as_ref()
+            .map(GoodsReceivedInspectionStatusNode::from_domain)
+    }
+
+    pub async fn comment(&self) -> &Option<String> {
+        &self.row.comment
+    }
+
+    pub async fn received_datetime(&self) -> Option<DateTime<Utc>> {
+        self.row
+            .received_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+}
+
+impl GoodsReceivedLineNode {
+    pub fn from_domain(row: GoodsReceivedLineRow) -> GoodsReceivedLineNode {
+        GoodsReceivedLineNode { row }
+    }
+}
+
+impl GoodsReceivedDiscrepancyReasonNode {
+    pub fn from_domain(reason: &GoodsReceivedDiscrepancyReason) -> Self {
+        use GoodsReceivedDiscrepancyReason as from;
+        use GoodsReceivedDiscrepancyReasonNode as to;
+        match reason {
+            from::Short => to::Short,
+            from::Excess => to::Excess,
+            from::Damaged => to::Damaged,
+        }
+    }
+
+    pub fn to_domain(self) -> GoodsReceivedDiscrepancyReason {
+        use GoodsReceivedDiscrepancyReason as to;
+        use GoodsReceivedDiscrepancyReasonNode as from;
+        match self {
+            from::Short => to::Short,
+            from::Excess => to::Excess,
+            from::Damaged => to::Damaged,
+        }
+    }
+}
+
+impl GoodsReceivedInspectionStatusNode {
+    pub fn from_domain(status: &GoodsReceivedInspectionStatus) -> Self {
+        use GoodsReceivedInspectionStatus as from;
+        use GoodsReceivedInspectionStatusNode as to;
+        match status {
+            from::Pending => to::Pending,
+            from::Passed => to::Passed,
+            from::Failed => to::Failed,
+        }
+    }
+}
+
+pub fn goods_received_lines(
+    ctx: &Context<'_>,
+    store_id: String,
+    invoice_id: String,
+) -> Result<Vec<GoodsReceivedLineNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryInvoice,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let lines = service_provider
+        .goods_received_service
+        .get_goods_received_lines(&service_context, &invoice_id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(lines
+        .into_iter()
+        .map(GoodsReceivedLineNode::from_domain)
+        .collect())
+}
@@ -145,6 +145,7 @@ impl LedgerFilterInput {
 
         LedgerFilter {
             stock_line_id: stock_line_id.map(EqualFilter::from),
+            ..Default::default()
         }
     }
 }
@@ -0,0 +1,52 @@
+use async_graphql::*;
+use graphql_core::{standard_graphql_error::StandardGraphqlError, ContextExt};
+use service::local_auth::AuthPolicy;
+
+use super::user_session::validate_server_admin;
+
+#[derive(SimpleObject)]
+pub struct AuthPolicyNode {
+    /// Consecutive failed logins before the account is locked, 0 disables the lockout
+    pub max_failed_logins: u32,
+    pub lockout_duration_minutes: u32,
+    /// Only enforced for users managed on this site
+    pub min_password_length: u32,
+    /// Passwords need an upper case letter, a lower case letter and a digit
+    pub require_mixed_characters: bool,
+    /// Days until the password of a locally managed user expires, 0 for no expiry
+    pub max_password_age_days: u32,
+}
+
+impl AuthPolicyNode {
+    pub fn from_domain(policy: AuthPolicy) -> AuthPolicyNode {
+        let AuthPolicy {
+            max_failed_logins,
+            lockout_duration_minutes,
+            min_password_length,
+            require_mixed_characters,
+            max_password_age_days,
+        } = policy;
+
+        AuthPolicyNode {
+            max_failed_logins,
+            lockout_duration_minutes,
+            min_password_length,
+            require_mixed_characters,
+            max_password_age_days,
+        }
+    }
+}
+
+pub fn auth_policy(ctx: &Context<'_>) -> Result<AuthPolicyNode> {
+    let user_id = validate_server_admin(ctx)?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user_id)?;
+
+    let auth_policy = service_provider
+        .local_auth_service
+        .get_auth_policy(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(AuthPolicyNode::from_domain(auth_policy))
+}
@@ -4,6 +4,7 @@ use graphql_core::{standard_graphql_error::StandardGraphqlError, ContextExt};
 
 use http2::header::SET_COOKIE;
 use service::{
+    identity_provider::{ldap::LdapAuthenticator, login::ExternalLoginService, ExternalAuthError},
     login::{LoginError, LoginFailure, LoginInput, LoginService},
     token::TokenPair,
 };
@@ -55,6 +56,29 @@ impl CentralSyncRequired {
     }
 }
 
+pub struct TotpRequired;
+#[Object]
+impl TotpRequired {
+    pub async fn description(&self) -> &str {
+        "A code from the authenticator app is required"
+    }
+}
+
+pub struct InvalidTotpCode;
+#[Object]
+impl InvalidTotpCode {
+    pub async fn description(&self) -> &str {
+        "Invalid authenticator code"
+    }
+}
+
+pub struct PasswordExpired;
+#[Object]
+impl PasswordExpired {
+    pub async fn description(&self) -> &str {
+        "Password has expired and needs to be changed"
+    }
+}
 
 pub struct AccountBlocked {
     pub timeout_remaining: u64,
@@ -78,6 +102,9 @@ pub enum AuthTokenErrorInterface {
     AccountBlocked(AccountBlocked),
     NoSiteAccess(NoSiteAccess),
     CentralSyncRequired(CentralSyncRequired),
+    TotpRequired(TotpRequired),
+    InvalidTotpCode(InvalidTotpCode),
+    PasswordExpired(PasswordExpired),
 }
 
 #[derive(SimpleObject)]
@@ -91,10 +118,34 @@ pub enum AuthTokenResponse {
     Error(AuthTokenError),
 }
 
-pub async fn login(ctx: &Context<'_>, username: &str, password: &str) -> Result<AuthTokenResponse> {
+pub async fn login(
+    ctx: &Context<'_>,
+    username: &str,
+    password: &str,
+    totp_code: Option<String>,
+) -> Result<AuthTokenResponse> {
     let service_provider = ctx.service_provider();
-    let service_context = service_provider.basic_context()?;
     let auth_data = ctx.get_auth_data();
+
+    // Passwords are checked against the LDAP directory instead of the central server
+    if let Some(identity_provider) = &ctx.get_settings().identity_provider {
+        if let Some(ldap_settings) = &identity_provider.ldap {
+            let result = ExternalLoginService::password_login(
+                service_provider,
+                auth_data,
+                identity_provider,
+                &LdapAuthenticator::new(ldap_settings.clone()),
+                username,
+                password,
+                totp_code.as_deref(),
+                MIN_ERR_RESPONSE_TIME_SEC,
+            )
+            .await;
+            return auth_token_response(ctx, result);
+        }
+    }
+
+    let service_context = service_provider.basic_context()?;
     let sync_settings = service_provider
         .settings
         .sync_settings(&service_context)?
@@ -102,18 +153,78 @@ pub async fn login(ctx: &Context<'_>, username: &str, password: &str) -> Result<
             "Sync settings not available".to_string(),
         ))?;
 
-    let pair = match LoginService::login(
+    let result = LoginService::login(
         service_provider,
         auth_data,
         LoginInput {
             username: username.to_string(),
             password: password.to_string(),
             central_server_url: sync_settings.url.clone(),
+            totp_code,
         },
         MIN_ERR_RESPONSE_TIME_SEC,
     )
-    .await
-    {
+    .await;
+    auth_token_response(ctx, result)
+}
+
+/// Login with the `code` and `state` the OpenID Connect identity provider redirected back with
+pub async fn oidc_login(
+    ctx: &Context<'_>,
+    code: &str,
+    state: &str,
+    totp_code: Option<String>,
+) -> Result<AuthTokenResponse> {
+    let Some(identity_provider) = &ctx.get_settings().identity_provider else {
+        return Err(StandardGraphqlError::BadUserInput(
+            "No identity provider configured".to_string(),
+        )
+        .extend());
+    };
+
+    let result = ExternalLoginService::oidc_login(
+        ctx.service_provider(),
+        ctx.get_auth_data(),
+        identity_provider,
+        code,
+        state,
+        totp_code.as_deref(),
+        MIN_ERR_RESPONSE_TIME_SEC,
+    )
+    .await;
+    auth_token_response(ctx, result)
+}
+
+/// Url of the OpenID Connect identity provider's login page
+pub async fn oidc_authorisation_url(ctx: &Context<'_>) -> Result<String> {
+    let Some(identity_provider) = &ctx.get_settings().identity_provider else {
+        return Err(StandardGraphqlError::BadUserInput(
+            "No identity provider configured".to_string(),
+        )
+        .extend());
+    };
+
+    ExternalLoginService::start_oidc_login(ctx.service_provider(), identity_provider)
+        .await
+        .map_err(|error| {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                LoginError::ExternalAuthError(ExternalAuthError::NotConfigured) => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                _ => StandardGraphqlError::InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })
+}
+
+/// Maps the login result and sets the refresh token cookie
+fn auth_token_response(
+    ctx: &Context<'_>,
+    result: std::result::Result<TokenPair, LoginError>,
+) -> Result<AuthTokenResponse> {
+    let auth_data = ctx.get_auth_data();
+    let pair = match result {
         Ok(pair) => pair,
         Err(error) => {
             let formatted_error = format!("{:#?}", error);
@@ -132,7 +243,7 @@ pub async fn login(ctx: &Context<'_>, username: &str, password: &str) -> Result<
                 }
                 LoginError::MSupplyCentralNotReached => {
                     return Ok(AuthTokenResponse::Error(AuthTokenError {
-                        error: AuthTokenErrorInterface::CentralSyncRequired(CentralSyncRequired)
+                        error: AuthTokenErrorInterface::CentralSyncRequired(CentralSyncRequired),
                     }))
                 }
                 LoginError::LoginFailure(LoginFailure::NoSiteAccess) => {
@@ -140,6 +251,21 @@ pub async fn login(ctx: &Context<'_>, username: &str, password: &str) -> Result<
                         error: AuthTokenErrorInterface::NoSiteAccess(NoSiteAccess),
                     }))
                 }
+                LoginError::LoginFailure(LoginFailure::TotpRequired) => {
+                    return Ok(AuthTokenResponse::Error(AuthTokenError {
+                        error: AuthTokenErrorInterface::TotpRequired(TotpRequired),
+                    }))
+                }
+                LoginError::LoginFailure(LoginFailure::InvalidTotpCode) => {
+                    return Ok(AuthTokenResponse::Error(AuthTokenError {
+                        error: AuthTokenErrorInterface::InvalidTotpCode(InvalidTotpCode),
+                    }))
+                }
+                LoginError::LoginFailure(LoginFailure::PasswordExpired) => {
+                    return Ok(AuthTokenResponse::Error(AuthTokenError {
+                        error: AuthTokenErrorInterface::PasswordExpired(PasswordExpired),
+                    }))
+                }
                 LoginError::FailedToGenerateToken(_) => {
                     StandardGraphqlError::InternalError(formatted_error)
                 }
@@ -155,6 +281,20 @@ pub async fn login(ctx: &Context<'_>, username: &str, password: &str) -> Result<
                 LoginError::UpdateUserError(_) => {
                     StandardGraphqlError::InternalError(formatted_error)
                 }
+                // Authorisation request was already used or has expired, the login needs to be
+                // started again
+                LoginError::ExternalAuthError(ExternalAuthError::InvalidAuthorisationRequest) => {
+                    return Ok(AuthTokenResponse::Error(AuthTokenError {
+                        error: AuthTokenErrorInterface::InvalidCredentials(InvalidCredentials),
+                    }))
+                }
+                // Account can't be provisioned, needs to be resolved by an administrator
+                LoginError::ExternalAuthError(ExternalAuthError::UsernameTaken(_)) => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                LoginError::ExternalAuthError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
             };
             return Err(graphql_error.extend());
         }
@@ -93,7 +93,8 @@ pub fn logout(ctx: &Context<'_>) -> Result<LogoutResponse> {
         Err(e) => {
             let formatted_error = format!("{:#?}", e);
             let graphql_error = match e {
-                service::token::JWTLogoutError::ConcurrencyLockError(_) => {
+                service::token::JWTLogoutError::ConcurrencyLockError(_)
+                | service::token::JWTLogoutError::DatabaseError(_) => {
                     StandardGraphqlError::InternalError(formatted_error)
                 }
             };
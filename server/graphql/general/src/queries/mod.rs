@@ -24,12 +24,28 @@ pub mod cold_storage_type;
 pub use self::cold_storage_type::*;
 pub mod stock_counts;
 pub use self::stock_counts::*;
+pub mod expiry_risk;
+pub use self::expiry_risk::*;
+pub mod recall;
+pub use self::recall::*;
+pub mod role;
+pub use self::role::*;
+pub mod goods_received;
+pub use self::goods_received::*;
 pub mod store;
 pub use self::store::*;
 pub mod activity_log;
 pub use self::activity_log::*;
+pub mod audit_trail;
+pub use self::audit_trail::*;
+pub mod period_lock;
+pub use self::period_lock::*;
 pub mod database_settings;
 pub use self::database_settings::*;
+pub mod user_session;
+pub use self::user_session::*;
+pub mod local_auth;
+pub use self::local_auth::*;
 pub mod display_settings;
 pub mod initialisation_status;
 pub mod name_property;
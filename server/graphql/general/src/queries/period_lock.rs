@@ -0,0 +1,89 @@
+use async_graphql::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::PeriodLockRow;
+use service::auth::{Resource, ResourceAccessRequest};
+
+pub struct PeriodLockNode {
+    pub period_lock: PeriodLockRow,
+}
+
+#[Object]
+impl PeriodLockNode {
+    pub async fn id(&self) -> &str {
+        &self.period_lock.id
+    }
+
+    pub async fn store_id(&self) -> &str {
+        &self.period_lock.store_id
+    }
+
+    /// Stock movements on or before this date can't be created or edited
+    pub async fn lock_date(&self) -> &Option<NaiveDate> {
+        &self.period_lock.lock_date
+    }
+
+    pub async fn user_id(&self) -> &Option<String> {
+        &self.period_lock.user_id
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.period_lock.created_datetime, Utc)
+    }
+
+    pub async fn comment(&self) -> &Option<String> {
+        &self.period_lock.comment
+    }
+}
+
+impl PeriodLockNode {
+    pub fn from_domain(period_lock: PeriodLockRow) -> PeriodLockNode {
+        PeriodLockNode { period_lock }
+    }
+}
+
+pub fn period_lock(ctx: &Context<'_>, store_id: String) -> Result<Option<PeriodLockNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryPeriodLock,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+    let period_lock = service_provider
+        .period_lock_service
+        .get_period_lock(&service_context, &store_id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(period_lock.map(PeriodLockNode::from_domain))
+}
+
+pub fn period_lock_history(ctx: &Context<'_>, store_id: String) -> Result<Vec<PeriodLockNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryPeriodLock,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+    let history = service_provider
+        .period_lock_service
+        .get_period_lock_history(&service_context, &store_id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(history
+        .into_iter()
+        .map(PeriodLockNode::from_domain)
+        .collect())
+}
@@ -0,0 +1,166 @@
+use async_graphql::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{InvoiceLineConnector, StockLineConnector};
+use repository::{EqualFilter, Recall, RecallFilter};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    recall::affected::GetRecallAffectedError,
+};
+
+pub struct RecallNode {
+    pub recall: Recall,
+}
+
+#[Object]
+impl RecallNode {
+    pub async fn id(&self) -> &str {
+        &self.recall.recall_row.id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.recall.item_row.id
+    }
+
+    pub async fn item_name(&self) -> &str {
+        &self.recall.item_row.name
+    }
+
+    pub async fn item_variant_id(&self) -> &Option<String> {
+        &self.recall.recall_row.item_variant_id
+    }
+
+    pub async fn manufacturer_id(&self) -> &Option<String> {
+        &self.recall.recall_row.manufacturer_link_id
+    }
+
+    pub async fn batch(&self) -> &Option<String> {
+        &self.recall.recall_row.batch
+    }
+
+    pub async fn expiry_date_from(&self) -> &Option<NaiveDate> {
+        &self.recall.recall_row.expiry_date_from
+    }
+
+    pub async fn expiry_date_to(&self) -> &Option<NaiveDate> {
+        &self.recall.recall_row.expiry_date_to
+    }
+
+    pub async fn reason(&self) -> &Option<String> {
+        &self.recall.recall_row.reason
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.recall.recall_row.created_datetime, Utc)
+    }
+
+    pub async fn closed_datetime(&self) -> Option<DateTime<Utc>> {
+        self.recall
+            .recall_row
+            .closed_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+}
+
+impl RecallNode {
+    pub fn from_domain(recall: Recall) -> RecallNode {
+        RecallNode { recall }
+    }
+}
+
+pub fn recalls(
+    ctx: &Context<'_>,
+    store_id: String,
+    item_id: Option<String>,
+    is_active: Option<bool>,
+) -> Result<Vec<RecallNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStockLine,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let mut filter = RecallFilter::new();
+    if let Some(item_id) = item_id {
+        filter = filter.item_id(EqualFilter::equal_to(&item_id));
+    }
+    if let Some(is_active) = is_active {
+        filter = filter.is_active(is_active);
+    }
+
+    let recalls = service_provider
+        .recall_service
+        .get_recalls(&service_context, Some(filter))
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(recalls.into_iter().map(RecallNode::from_domain).collect())
+}
+
+fn map_affected_error(error: GetRecallAffectedError) -> Error {
+    let formatted_error = format!("{:#?}", error);
+    let graphql_error = match error {
+        GetRecallAffectedError::RecallDoesNotExist => {
+            StandardGraphqlError::BadUserInput(formatted_error)
+        }
+        GetRecallAffectedError::DatabaseError(_) => {
+            StandardGraphqlError::InternalError(formatted_error)
+        }
+    };
+    graphql_error.extend()
+}
+
+pub fn recalled_stock(
+    ctx: &Context<'_>,
+    store_id: String,
+    recall_id: String,
+) -> Result<StockLineConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStockLine,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let stock_lines = service_provider
+        .recall_service
+        .get_recalled_stock(&service_context, &recall_id)
+        .map_err(map_affected_error)?;
+
+    Ok(StockLineConnector::from_vec(stock_lines))
+}
+
+pub fn recall_recipients(
+    ctx: &Context<'_>,
+    store_id: String,
+    recall_id: String,
+) -> Result<InvoiceLineConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryInvoice,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let lines = service_provider
+        .recall_service
+        .get_recall_recipients(&service_context, &recall_id)
+        .map_err(map_affected_error)?;
+
+    Ok(InvoiceLineConnector::from_vec(lines))
+}
@@ -125,6 +125,9 @@ pub fn refresh_token(ctx: &Context<'_>) -> RefreshTokenResponse {
                             "Lock error".to_string(),
                         ))
                     }
+                    JWTRefreshError::DatabaseError(_) => RefreshTokenErrorInterface::InternalError(
+                        InternalError("Database error".to_string()),
+                    ),
                 },
             })
         }
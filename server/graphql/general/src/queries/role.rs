@@ -0,0 +1,135 @@
+use async_graphql::*;
+use graphql_core::{standard_graphql_error::StandardGraphqlError, ContextExt};
+use graphql_types::types::UserPermission;
+use repository::{RolePermissionRow, RoleRow};
+use service::role::{
+    query::{EffectivePermission, PermissionSource},
+    Role,
+};
+
+use crate::queries::user_session::validate_server_admin;
+
+pub struct RoleNode {
+    pub role: Role,
+}
+
+#[Object]
+impl RoleNode {
+    pub async fn id(&self) -> &str {
+        &self.role.role_row.id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.role.role_row.name
+    }
+
+    pub async fn description(&self) -> &Option<String> {
+        &self.role.role_row.description
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.role.role_row.is_active
+    }
+
+    pub async fn permissions(&self) -> Vec<RolePermissionNode> {
+        self.role
+            .permissions
+            .iter()
+            .cloned()
+            .map(|role_permission| RolePermissionNode { role_permission })
+            .collect()
+    }
+}
+
+pub struct RolePermissionNode {
+    pub role_permission: RolePermissionRow,
+}
+
+#[Object]
+impl RolePermissionNode {
+    pub async fn id(&self) -> &str {
+        &self.role_permission.id
+    }
+
+    pub async fn permission(&self) -> UserPermission {
+        UserPermission::from_domain(&self.role_permission.permission)
+    }
+
+    pub async fn context_id(&self) -> &Option<String> {
+        &self.role_permission.context_id
+    }
+}
+
+pub struct EffectivePermissionNode {
+    pub effective_permission: EffectivePermission,
+}
+
+#[Object]
+impl EffectivePermissionNode {
+    pub async fn permission(&self) -> UserPermission {
+        UserPermission::from_domain(&self.effective_permission.permission.permission)
+    }
+
+    pub async fn store_id(&self) -> &Option<String> {
+        &self.effective_permission.permission.store_id
+    }
+
+    pub async fn context_id(&self) -> &Option<String> {
+        &self.effective_permission.permission.context_id
+    }
+
+    /// Role the permission is given by, null if the permission is assigned to the user directly
+    pub async fn role_id(&self) -> Option<&str> {
+        self.role_row().map(|role| role.id.as_str())
+    }
+
+    pub async fn role_name(&self) -> Option<&str> {
+        self.role_row().map(|role| role.name.as_str())
+    }
+}
+
+impl EffectivePermissionNode {
+    fn role_row(&self) -> Option<&RoleRow> {
+        match &self.effective_permission.source {
+            PermissionSource::Direct => None,
+            PermissionSource::Role(role) => Some(role),
+        }
+    }
+}
+
+pub fn roles(ctx: &Context<'_>) -> Result<Vec<RoleNode>> {
+    let user_id = validate_server_admin(ctx)?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user_id)?;
+
+    let roles = service_provider
+        .role_service
+        .get_roles(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(roles.into_iter().map(|role| RoleNode { role }).collect())
+}
+
+pub fn effective_permissions(
+    ctx: &Context<'_>,
+    user_id: String,
+    store_id: Option<String>,
+) -> Result<Vec<EffectivePermissionNode>> {
+    let admin_user_id = validate_server_admin(ctx)?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), admin_user_id)?;
+
+    let effective_permissions = service_provider
+        .role_service
+        .effective_permissions(&service_context, &user_id, store_id.as_deref())
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(effective_permissions
+        .into_iter()
+        .map(|effective_permission| EffectivePermissionNode {
+            effective_permission,
+        })
+        .collect())
+}
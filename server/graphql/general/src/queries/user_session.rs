@@ -0,0 +1,142 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    api_token::ApiToken,
+    auth::{Resource, ResourceAccessRequest},
+    user_session::UserSession,
+};
+
+pub struct UserSessionNode {
+    pub user_session: UserSession,
+}
+
+#[Object]
+impl UserSessionNode {
+    pub async fn id(&self) -> &str {
+        &self.user_session.id
+    }
+
+    pub async fn user_id(&self) -> &str {
+        &self.user_session.user_id
+    }
+
+    pub async fn username(&self) -> &str {
+        &self.user_session.username
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.user_session.created_datetime, Utc)
+    }
+
+    pub async fn expiry_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.user_session.expiry_datetime, Utc)
+    }
+}
+
+pub struct ApiTokenNode {
+    pub api_token: ApiToken,
+}
+
+#[Object]
+impl ApiTokenNode {
+    pub async fn id(&self) -> &str {
+        &self.api_token.api_token_row.id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.api_token.api_token_row.name
+    }
+
+    pub async fn user_id(&self) -> &str {
+        &self.api_token.api_token_row.user_id
+    }
+
+    /// Names of the auth resources the token can access, e.g. QueryStockLine
+    pub async fn resources(&self) -> Vec<String> {
+        self.api_token
+            .resources
+            .iter()
+            .map(|resource| format!("{:?}", resource))
+            .collect()
+    }
+
+    pub async fn store_ids(&self) -> &Vec<String> {
+        &self.api_token.store_ids
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(
+            self.api_token.api_token_row.created_datetime,
+            Utc,
+        )
+    }
+
+    pub async fn expiry_datetime(&self) -> Option<DateTime<Utc>> {
+        self.api_token
+            .api_token_row
+            .expiry_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+
+    pub async fn last_used_datetime(&self) -> Option<DateTime<Utc>> {
+        self.api_token
+            .api_token_row
+            .last_used_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+}
+
+impl ApiTokenNode {
+    pub fn from_domain(api_token: ApiToken) -> ApiTokenNode {
+        ApiTokenNode { api_token }
+    }
+}
+
+pub(crate) fn validate_server_admin(ctx: &Context<'_>) -> Result<String> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+    Ok(user.user_id)
+}
+
+pub fn user_sessions(ctx: &Context<'_>) -> Result<Vec<UserSessionNode>> {
+    let user_id = validate_server_admin(ctx)?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user_id)?;
+
+    let user_sessions = service_provider
+        .user_session_service
+        .get_user_sessions(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(user_sessions
+        .into_iter()
+        .map(|user_session| UserSessionNode { user_session })
+        .collect())
+}
+
+pub fn api_tokens(ctx: &Context<'_>) -> Result<Vec<ApiTokenNode>> {
+    let user_id = validate_server_admin(ctx)?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user_id)?;
+
+    let api_tokens = service_provider
+        .api_token_service
+        .get_api_tokens(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(api_tokens
+        .into_iter()
+        .map(ApiTokenNode::from_domain)
+        .collect())
+}
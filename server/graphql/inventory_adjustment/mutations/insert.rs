@@ -109,7 +109,8 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         | ServiceError::InvalidStore
         | ServiceError::InvalidAdjustment
         | ServiceError::AdjustmentReasonNotValid
-        | ServiceError::AdjustmentReasonNotProvided => BadUserInput(formatted_error),
+        | ServiceError::AdjustmentReasonNotProvided
+        | ServiceError::PeriodLocked(_) => BadUserInput(formatted_error),
 
         ServiceError::NewlyCreatedInvoiceDoesNotExist
         | ServiceError::StockInLineInsertError(_)
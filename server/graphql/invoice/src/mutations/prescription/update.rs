@@ -140,7 +140,9 @@ fn map_error(error: ServiceError) -> Result<UpdatePrescriptionErrorInterface> {
         ServiceError::NotAPrescriptionInvoice
         | ServiceError::ClinicianDoesNotExist
         | ServiceError::NotThisStoreInvoice
-        | ServiceError::PatientDoesNotExist => BadUserInput(formatted_error),
+        | ServiceError::PatientDoesNotExist
+        | ServiceError::PeriodLocked(_)
+        | ServiceError::PluginVetoed(_) => BadUserInput(formatted_error),
         ServiceError::DatabaseError(_)
         | ServiceError::InvoiceLineHasNoStockLine(_)
         | ServiceError::UpdatedInvoiceDoesNotExist => InternalError(formatted_error),
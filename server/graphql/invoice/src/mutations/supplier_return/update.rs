@@ -72,7 +72,9 @@ fn map_error(error: ServiceError) -> Result<UpdateResponse> {
         | ServiceError::ReturnIsNotEditable
         | ServiceError::CannotReverseInvoiceStatus
         | ServiceError::CannotChangeStatusOfInvoiceOnHold
-        | ServiceError::ReturnDoesNotExist => BadUserInput(formatted_error),
+        | ServiceError::ReturnDoesNotExist
+        | ServiceError::PeriodLocked(_)
+        | ServiceError::PluginVetoed(_) => BadUserInput(formatted_error),
 
         ServiceError::InvoiceLineHasNoStockLine(_)
         | ServiceError::UpdatedReturnDoesNotExist
@@ -124,6 +124,8 @@ impl InvoiceLineMutations {
 
     // Inbound
 
+    /// The returned line has a `coldChainWarning` when the stock is received into a location that
+    /// doesn't meet the cold storage requirement of its item variant
     async fn insert_inbound_shipment_line(
         &self,
         ctx: &Context<'_>,
@@ -133,6 +135,7 @@ impl InvoiceLineMutations {
         inbound_shipment_line::line::insert::insert(ctx, &store_id, input)
     }
 
+    /// Same `coldChainWarning` as for inserted lines
     async fn update_inbound_shipment_line(
         &self,
         ctx: &Context<'_>,
@@ -106,7 +106,8 @@ fn map_error(error: ServiceError) -> Result<DeleteErrorInterface> {
         // Standard Graphql Errors
         ServiceError::NotThisInvoiceLine(_)
         | ServiceError::NotAStockIn
-        | ServiceError::NotThisStoreInvoice => BadUserInput(formatted_error),
+        | ServiceError::NotThisStoreInvoice
+        | ServiceError::PeriodLocked(_) => BadUserInput(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
         ServiceError::LineUsedInStocktake => InternalError(formatted_error),
     };
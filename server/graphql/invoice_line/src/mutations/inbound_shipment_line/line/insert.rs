@@ -30,6 +30,7 @@ pub struct InsertInput {
     pub total_before_tax: Option<f64>,
     pub tax_percentage: Option<f64>,
     pub item_variant_id: Option<String>,
+    pub donor_id: Option<String>,
 }
 
 #[derive(SimpleObject)]
@@ -88,6 +89,7 @@ impl InsertInput {
             total_before_tax,
             tax_percentage,
             item_variant_id,
+            donor_id,
         } = self;
 
         ServiceInput {
@@ -107,6 +109,7 @@ impl InsertInput {
             tax_percentage,
             r#type: StockInType::InboundShipment,
             item_variant_id,
+            donor_id,
             // Default
             note: None,
             stock_line_id: None,
@@ -153,7 +156,8 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         | ServiceError::PackSizeBelowOne
         | ServiceError::LocationDoesNotExist
         | ServiceError::ItemVariantDoesNotExist
-        | ServiceError::ItemNotFound => BadUserInput(formatted_error),
+        | ServiceError::ItemNotFound
+        | ServiceError::PeriodLocked(_) => BadUserInput(formatted_error),
         ServiceError::DatabaseError(_) | ServiceError::NewlyCreatedLineDoesNotExist => {
             InternalError(formatted_error)
         }
@@ -170,11 +174,13 @@ mod test {
         assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphql_test,
     };
     use repository::{
+        item_variant::item_variant_row::{ItemVariantRow, ItemVariantRowRepository},
         mock::{
             mock_inbound_shipment_c, mock_inbound_shipment_c_invoice_lines, mock_item_a,
             mock_location_1, MockDataInserts,
         },
-        InvoiceLine, RepositoryError, StorageConnectionManager,
+        ColdStorageTypeRow, ColdStorageTypeRowRepository, InvoiceLine, InvoiceLineRow,
+        RepositoryError, StorageConnectionManager,
     };
     use serde_json::json;
     use service::{
@@ -492,4 +498,78 @@ mod test {
             Some(service_provider(test_service, &connection_manager))
         );
     }
+
+    #[actix_rt::test]
+    async fn test_graphql_insert_inbound_line_cold_chain_warning() {
+        let (_, connection, connection_manager, settings) = setup_graphql_test(
+            EmptyMutation,
+            InvoiceLineMutations,
+            "test_graphql_insert_inbound_line_cold_chain_warning",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        ColdStorageTypeRowRepository::new(&connection)
+            .upsert_one(&ColdStorageTypeRow {
+                id: "fridge".to_string(),
+                name: "Fridge".to_string(),
+                min_temperature: 2.0,
+                max_temperature: 8.0,
+            })
+            .unwrap();
+        ItemVariantRowRepository::new(&connection)
+            .upsert_one(&ItemVariantRow {
+                id: "vaccine_variant".to_string(),
+                item_link_id: mock_item_a().id,
+                cold_storage_type_id: Some("fridge".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let mutation = r#"
+        mutation ($input: InsertInboundShipmentLineInput!) {
+            insertInboundShipmentLine(input: $input, storeId: \"store_a\") {
+                ... on InvoiceLineNode {
+                    id
+                    coldChainWarning {
+                        kind
+                    }
+                }
+            }
+          }
+        "#;
+
+        // Stock received into a location that isn't cold storage
+        let test_service = TestService(Box::new(|_| {
+            Ok(InvoiceLine {
+                invoice_line_row: InvoiceLineRow {
+                    item_variant_id: Some("vaccine_variant".to_string()),
+                    location_id: Some(mock_location_1().id),
+                    ..mock_inbound_shipment_c_invoice_lines()[0].clone()
+                },
+                invoice_row: mock_inbound_shipment_c(),
+                item_row: mock_item_a(),
+                location_row_option: Some(mock_location_1()),
+                stock_line_option: None,
+            })
+        }));
+
+        let expected = json!({
+            "insertInboundShipmentLine": {
+                "id": mock_inbound_shipment_c_invoice_lines()[0].id,
+                "coldChainWarning": {
+                    "kind": "locationIsNotColdStorage"
+                }
+            }
+          }
+        );
+
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(empty_variables()),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
 }
@@ -33,6 +33,7 @@ pub struct UpdateInput {
     pub total_before_tax: Option<f64>,
     pub tax: Option<TaxInput>,
     pub item_variant_id: Option<NullableUpdateInput<String>>,
+    pub donor_id: Option<NullableUpdateInput<String>>,
 }
 
 #[derive(SimpleObject)]
@@ -99,6 +100,7 @@ impl UpdateInput {
             total_before_tax,
             tax,
             item_variant_id,
+            donor_id,
         } = self;
 
         ServiceInput {
@@ -117,6 +119,9 @@ impl UpdateInput {
             item_variant_id: item_variant_id.map(|item_variant_id| NullableUpdate {
                 value: item_variant_id.value,
             }),
+            donor_id: donor_id.map(|donor_id| NullableUpdate {
+                value: donor_id.value,
+            }),
             tax_percentage: tax.map(|tax| ShipmentTaxUpdate {
                 percentage: tax.percentage,
             }),
@@ -168,7 +173,8 @@ fn map_error(error: ServiceError) -> Result<UpdateErrorInterface> {
         | ServiceError::PackSizeBelowOne
         | ServiceError::LocationDoesNotExist
         | ServiceError::ItemVariantDoesNotExist
-        | ServiceError::ItemNotFound => BadUserInput(formatted_error),
+        | ServiceError::ItemNotFound
+        | ServiceError::PeriodLocked(_) => BadUserInput(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
         ServiceError::UpdatedLineDoesNotExist => InternalError(formatted_error),
     };
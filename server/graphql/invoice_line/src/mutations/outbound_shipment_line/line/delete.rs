@@ -102,7 +102,8 @@ fn map_error(error: ServiceError) -> Result<DeleteErrorInterface> {
         | InvoiceTypeDoesNotMatch
         | NoInvoiceType
         | NotThisStoreInvoice
-        | StockLineDoesNotExist => StandardGraphqlError::BadUserInput(formatted_error),
+        | StockLineDoesNotExist
+        | PeriodLocked(_) => StandardGraphqlError::BadUserInput(formatted_error),
         DatabaseError(_) => StandardGraphqlError::InternalError(formatted_error),
     };
 
@@ -169,7 +169,8 @@ fn map_error(error: ServiceError) -> Result<UpdateErrorInterface> {
         | ItemNotFound
         | ItemDoesNotMatchStockLine
         | NotThisInvoiceLine(_)
-        | LineDoesNotReferenceStockLine => StandardGraphqlError::BadUserInput(formatted_error),
+        | LineDoesNotReferenceStockLine
+        | PeriodLocked(_) => StandardGraphqlError::BadUserInput(formatted_error),
         DatabaseError(_) | UpdatedLineDoesNotExist => {
             StandardGraphqlError::InternalError(formatted_error)
         }
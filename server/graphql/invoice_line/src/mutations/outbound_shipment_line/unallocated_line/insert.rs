@@ -20,6 +20,8 @@ pub struct InsertInput {
     pub invoice_id: String,
     pub item_id: String,
     pub quantity: u32,
+    /// Only allocate stock owned by this donor
+    pub donor_id: Option<String>,
 }
 
 #[derive(Interface)]
@@ -67,6 +69,7 @@ impl InsertInput {
             invoice_id,
             item_id,
             quantity,
+            donor_id,
         } = self;
 
         ServiceInput {
@@ -74,6 +77,7 @@ impl InsertInput {
             invoice_id,
             item_id,
             quantity,
+            donor_id,
         }
     }
 }
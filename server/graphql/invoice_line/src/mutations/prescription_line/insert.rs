@@ -135,7 +135,8 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         NotThisStoreInvoice
         | InvoiceTypeDoesNotMatch
         | LineAlreadyExists
-        | NumberOfPacksBelowZero => StandardGraphqlError::BadUserInput(formatted_error),
+        | NumberOfPacksBelowZero
+        | PeriodLocked(_) => StandardGraphqlError::BadUserInput(formatted_error),
         DatabaseError(_) | NewlyCreatedLineDoesNotExist => {
             StandardGraphqlError::InternalError(formatted_error)
         }
@@ -1,5 +1,7 @@
 mod mutations;
+mod warehouse;
 use self::mutations::*;
+use self::warehouse::*;
 
 use async_graphql::*;
 use graphql_core::{
@@ -60,6 +62,26 @@ impl LocationQueries {
             locations,
         )))
     }
+
+    /// Locations to put away stock received on an inbound shipment line, best suggestion first
+    pub async fn put_away_suggestions(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        invoice_line_id: String,
+    ) -> Result<Vec<PutAwaySuggestionNode>> {
+        put_away_suggestions(ctx, &store_id, &invoice_line_id)
+    }
+
+    /// Lines to pick for allocated outbound shipments, ordered by location walk sequence
+    pub async fn pick_list(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        invoice_ids: Vec<String>,
+    ) -> Result<InvoiceLineConnector> {
+        pick_list(ctx, &store_id, invoice_ids)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -93,6 +115,16 @@ impl LocationMutations {
     ) -> Result<DeleteLocationResponse> {
         delete_location(ctx, &store_id, input)
     }
+
+    /// Confirms picked quantities of an allocated outbound shipment and changes it to picked
+    async fn confirm_picks(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: ConfirmPicksInput,
+    ) -> Result<InvoiceNode> {
+        confirm_picks(ctx, &store_id, input)
+    }
 }
 
 #[cfg(test)]
@@ -192,6 +224,10 @@ mod test {
                         on_hold: true,
                         store_id: "store_a".to_owned(),
                         cold_storage_type_id: None,
+                        parent_location_id: None,
+                        volume_capacity: None,
+                        pallet_capacity: None,
+                        walk_sequence: None,
                     },
                 }],
                 count: 1,
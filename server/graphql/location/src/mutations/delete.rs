@@ -87,6 +87,7 @@ fn map_error(error: ServiceError) -> Result<DeleteLocationErrorInterface> {
         // Standard Graphql Errors
         ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
         ServiceError::LocationDoesNotBelongToCurrentStore => BadUserInput(formatted_error),
+        ServiceError::LocationHasChildLocations => BadUserInput(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
     };
 
@@ -48,6 +48,11 @@ pub struct InsertLocationInput {
     pub name: Option<String>,
     pub on_hold: Option<bool>,
     pub cold_storage_type_id: Option<String>,
+    pub parent_location_id: Option<String>,
+    pub volume_capacity: Option<f64>,
+    pub pallet_capacity: Option<i32>,
+    /// Order in which locations are visited when picking stock
+    pub walk_sequence: Option<i32>,
 }
 
 impl From<InsertLocationInput> for InsertLocation {
@@ -58,6 +63,10 @@ impl From<InsertLocationInput> for InsertLocation {
             name,
             on_hold,
             cold_storage_type_id,
+            parent_location_id,
+            volume_capacity,
+            pallet_capacity,
+            walk_sequence,
         }: InsertLocationInput,
     ) -> Self {
         InsertLocation {
@@ -66,6 +75,10 @@ impl From<InsertLocationInput> for InsertLocation {
             name,
             on_hold,
             cold_storage_type_id,
+            parent_location_id,
+            volume_capacity,
+            pallet_capacity,
+            walk_sequence,
         }
     }
 }
@@ -97,6 +110,8 @@ fn map_error(error: ServiceError) -> Result<InsertLocationErrorInterface> {
         // Standard Graphql Errors
         ServiceError::LocationAlreadyExists => BadUserInput(formatted_error),
         ServiceError::LocationWithCodeAlreadyExists => BadUserInput(formatted_error),
+        ServiceError::ParentLocationDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::CapacityCannotBeNegative => BadUserInput(formatted_error),
         ServiceError::CreatedRecordNotFound => InternalError(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
     };
@@ -295,6 +310,10 @@ mod test {
                     on_hold: true,
                     store_id: "store_a".to_owned(),
                     cold_storage_type_id: None,
+                    parent_location_id: None,
+                    volume_capacity: None,
+                    pallet_capacity: None,
+                    walk_sequence: None,
                 },
             })
         }));
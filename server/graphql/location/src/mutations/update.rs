@@ -1,6 +1,7 @@
 use async_graphql::*;
 
 use graphql_core::{
+    generic_inputs::NullableUpdateInput,
     simple_generic_errors::{
         DatabaseError, InternalError, RecordBelongsToAnotherStore, RecordNotFound,
         UniqueValueViolation,
@@ -12,6 +13,7 @@ use graphql_types::types::LocationNode;
 use service::{
     auth::{Resource, ResourceAccessRequest},
     location::update::{UpdateLocation, UpdateLocationError as ServiceError},
+    NullableUpdate,
 };
 
 pub fn update_location(
@@ -50,6 +52,11 @@ pub struct UpdateLocationInput {
     pub name: Option<String>,
     pub on_hold: Option<bool>,
     pub cold_storage_type_id: Option<String>,
+    pub parent_location_id: Option<NullableUpdateInput<String>>,
+    pub volume_capacity: Option<NullableUpdateInput<f64>>,
+    pub pallet_capacity: Option<NullableUpdateInput<i32>>,
+    /// Order in which locations are visited when picking stock
+    pub walk_sequence: Option<NullableUpdateInput<i32>>,
 }
 
 impl From<UpdateLocationInput> for UpdateLocation {
@@ -60,6 +67,10 @@ impl From<UpdateLocationInput> for UpdateLocation {
             name,
             on_hold,
             cold_storage_type_id,
+            parent_location_id,
+            volume_capacity,
+            pallet_capacity,
+            walk_sequence,
         }: UpdateLocationInput,
     ) -> Self {
         UpdateLocation {
@@ -68,6 +79,18 @@ impl From<UpdateLocationInput> for UpdateLocation {
             name,
             on_hold,
             cold_storage_type_id,
+            parent_location_id: parent_location_id.map(|parent_location_id| NullableUpdate {
+                value: parent_location_id.value,
+            }),
+            volume_capacity: volume_capacity.map(|volume_capacity| NullableUpdate {
+                value: volume_capacity.value,
+            }),
+            pallet_capacity: pallet_capacity.map(|pallet_capacity| NullableUpdate {
+                value: pallet_capacity.value,
+            }),
+            walk_sequence: walk_sequence.map(|walk_sequence| NullableUpdate {
+                value: walk_sequence.value,
+            }),
         }
     }
 }
@@ -102,6 +125,9 @@ fn map_error(error: ServiceError) -> Result<UpdateLocationErrorInterface> {
         ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
         ServiceError::CodeAlreadyExists => BadUserInput(formatted_error),
         ServiceError::LocationDoesNotBelongToCurrentStore => BadUserInput(formatted_error),
+        ServiceError::ParentLocationDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::ParentLocationIsDescendant => BadUserInput(formatted_error),
+        ServiceError::CapacityCannotBeNegative => BadUserInput(formatted_error),
         ServiceError::UpdatedRecordNotFound => InternalError(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
     };
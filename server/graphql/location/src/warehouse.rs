@@ -0,0 +1,185 @@
+use async_graphql::*;
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{InvoiceLineConnector, InvoiceNode, LocationNode};
+use repository::location::Location;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    warehouse::{
+        confirm_picks::{ConfirmPickLine, ConfirmPicks, ConfirmPicksError},
+        pick_list::GeneratePickListError,
+        put_away::{GetPutAwaySuggestionsError, PutAwaySuggestion},
+    },
+};
+
+pub struct PutAwaySuggestionNode {
+    suggestion: PutAwaySuggestion,
+}
+
+#[Object]
+impl PutAwaySuggestionNode {
+    pub async fn location(&self) -> LocationNode {
+        LocationNode::from_domain(Location {
+            location_row: self.suggestion.location.clone(),
+        })
+    }
+
+    /// Location already holds stock of the same item
+    pub async fn has_same_item(&self) -> bool {
+        self.suggestion.has_same_item
+    }
+
+    /// Volume left in the location after putting the stock away
+    pub async fn remaining_volume(&self) -> Option<f64> {
+        self.suggestion.remaining_volume
+    }
+}
+
+pub fn put_away_suggestions(
+    ctx: &Context<'_>,
+    store_id: &str,
+    invoice_line_id: &str,
+) -> Result<Vec<PutAwaySuggestionNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryInvoice,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let suggestions = service_provider
+        .warehouse_service
+        .get_put_away_suggestions(&service_context, invoice_line_id)
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                GetPutAwaySuggestionsError::InvoiceLineDoesNotExist
+                | GetPutAwaySuggestionsError::NotThisStoreInvoice
+                | GetPutAwaySuggestionsError::NotAnInboundShipmentLine => {
+                    BadUserInput(formatted_error)
+                }
+                GetPutAwaySuggestionsError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(suggestions
+        .into_iter()
+        .map(|suggestion| PutAwaySuggestionNode { suggestion })
+        .collect())
+}
+
+pub fn pick_list(
+    ctx: &Context<'_>,
+    store_id: &str,
+    invoice_ids: Vec<String>,
+) -> Result<InvoiceLineConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryInvoice,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let lines = service_provider
+        .warehouse_service
+        .generate_pick_list(&service_context, invoice_ids)
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                GeneratePickListError::NoShipmentsSelected
+                | GeneratePickListError::InvoiceDoesNotExist(_)
+                | GeneratePickListError::NotThisStoreInvoice(_)
+                | GeneratePickListError::NotAnOutboundShipment(_)
+                | GeneratePickListError::InvoiceIsNotAllocated(_) => BadUserInput(formatted_error),
+                GeneratePickListError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(InvoiceLineConnector::from_vec(lines))
+}
+
+#[derive(InputObject)]
+pub struct ConfirmPickLineInput {
+    pub invoice_line_id: String,
+    pub picked_number_of_packs: f64,
+}
+
+#[derive(InputObject)]
+pub struct ConfirmPicksInput {
+    pub invoice_id: String,
+    /// Lines where a different quantity than allocated was picked
+    pub lines: Vec<ConfirmPickLineInput>,
+}
+
+impl From<ConfirmPicksInput> for ConfirmPicks {
+    fn from(ConfirmPicksInput { invoice_id, lines }: ConfirmPicksInput) -> Self {
+        ConfirmPicks {
+            invoice_id,
+            lines: lines
+                .into_iter()
+                .map(
+                    |ConfirmPickLineInput {
+                         invoice_line_id,
+                         picked_number_of_packs,
+                     }| ConfirmPickLine {
+                        invoice_line_id,
+                        picked_number_of_packs,
+                    },
+                )
+                .collect(),
+        }
+    }
+}
+
+pub fn confirm_picks(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: ConfirmPicksInput,
+) -> Result<InvoiceNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateOutboundShipment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let invoice = service_provider
+        .warehouse_service
+        .confirm_picks(&service_context, input.into())
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                ConfirmPicksError::InvoiceDoesNotExist
+                | ConfirmPicksError::NotThisStoreInvoice
+                | ConfirmPicksError::NotAnOutboundShipment
+                | ConfirmPicksError::InvoiceIsNotAllocated
+                | ConfirmPicksError::LineDoesNotBelongToShipment(_)
+                | ConfirmPicksError::LineError { .. }
+                | ConfirmPicksError::UpdateShipmentError(_) => BadUserInput(formatted_error),
+                ConfirmPicksError::DatabaseError(_) => InternalError(formatted_error),
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(InvoiceNode::from_domain(invoice))
+}
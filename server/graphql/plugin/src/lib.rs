@@ -1,10 +1,15 @@
 pub mod plugin_data;
+pub mod plugin_version;
 use self::plugin_data::mutations;
 pub mod types;
 
 use crate::types::RelatedRecordNodeType;
 use async_graphql::*;
 use plugin_data::query::{PluginDataFilterInput, PluginDataResponse, PluginDataSortInput};
+use plugin_version::{
+    mutations::RevokePluginCertificateInput, PluginCertificateRevocationNode,
+    PluginVersionConnector, PluginVersionNode,
+};
 
 #[derive(Default, Clone)]
 pub struct PluginQueries;
@@ -21,6 +26,11 @@ impl PluginQueries {
     ) -> Result<PluginDataResponse> {
         plugin_data::query::get_plugin_data(ctx, &store_id, r#type, filter, sort)
     }
+
+    /// All uploaded versions of all plugins
+    async fn plugin_versions(&self, ctx: &Context<'_>) -> Result<PluginVersionConnector> {
+        plugin_version::query::plugin_versions(ctx)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -45,4 +55,31 @@ impl PluginMutations {
     ) -> Result<mutations::update::UpdateResponse> {
         mutations::update::update_plugin_data(ctx, &store_id, input)
     }
+
+    /// Installs the plugin version on all sites, replacing the other versions of the plugin.
+    /// Activating a previous version rolls the plugin back.
+    async fn activate_plugin_version(
+        &self,
+        ctx: &Context<'_>,
+        plugin_version_id: String,
+    ) -> Result<PluginVersionNode> {
+        plugin_version::mutations::activate(ctx, &plugin_version_id)
+    }
+
+    /// Uninstalls the plugin from all sites
+    async fn deactivate_plugin_version(
+        &self,
+        ctx: &Context<'_>,
+        plugin_version_id: String,
+    ) -> Result<PluginVersionNode> {
+        plugin_version::mutations::deactivate(ctx, &plugin_version_id)
+    }
+
+    async fn revoke_plugin_certificate(
+        &self,
+        ctx: &Context<'_>,
+        input: RevokePluginCertificateInput,
+    ) -> Result<PluginCertificateRevocationNode> {
+        plugin_version::mutations::revoke_certificate(ctx, input)
+    }
 }
@@ -0,0 +1,94 @@
+pub mod mutations;
+pub mod query;
+
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use repository::{PluginCertificateRevocationRow, PluginVersionRow};
+use service::plugin::plugin_version::PluginVersion;
+
+#[derive(PartialEq, Debug)]
+pub struct PluginVersionNode {
+    pub plugin_version: PluginVersion,
+}
+
+#[derive(SimpleObject)]
+pub struct PluginVersionConnector {
+    total_count: u32,
+    nodes: Vec<PluginVersionNode>,
+}
+
+#[Object]
+impl PluginVersionNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn code(&self) -> &str {
+        &self.row().code
+    }
+
+    pub async fn version(&self) -> &str {
+        &self.row().version
+    }
+
+    pub async fn certificate_fingerprint(&self) -> &str {
+        &self.row().certificate_fingerprint
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.row().is_active
+    }
+
+    pub async fn is_revoked(&self) -> bool {
+        self.plugin_version.is_revoked
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.row().created_datetime, Utc)
+    }
+}
+
+impl PluginVersionNode {
+    pub fn from_domain(plugin_version: PluginVersion) -> PluginVersionNode {
+        PluginVersionNode { plugin_version }
+    }
+
+    pub fn row(&self) -> &PluginVersionRow {
+        &self.plugin_version.plugin_version_row
+    }
+}
+
+impl PluginVersionConnector {
+    pub fn from_vec(plugin_versions: Vec<PluginVersion>) -> PluginVersionConnector {
+        PluginVersionConnector {
+            total_count: plugin_versions.len() as u32,
+            nodes: plugin_versions
+                .into_iter()
+                .map(PluginVersionNode::from_domain)
+                .collect(),
+        }
+    }
+}
+
+pub struct PluginCertificateRevocationNode {
+    pub row: PluginCertificateRevocationRow,
+}
+
+#[Object]
+impl PluginCertificateRevocationNode {
+    pub async fn id(&self) -> &str {
+        &self.row.id
+    }
+
+    pub async fn fingerprint(&self) -> &str {
+        &self.row.fingerprint
+    }
+
+    pub async fn reason(&self) -> &Option<String> {
+        &self.row.reason
+    }
+
+    pub async fn revoked_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.row.revoked_datetime, Utc)
+    }
+}
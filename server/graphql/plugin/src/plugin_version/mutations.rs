@@ -0,0 +1,125 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    plugin::plugin_version::{
+        activate_plugin_version, deactivate_plugin_version, get_plugin_versions,
+        revoke_plugin_certificate, RevokePluginCertificate,
+        RevokePluginCertificateError as RevokeError, UpdatePluginVersionError as UpdateError,
+    },
+};
+
+use super::{PluginCertificateRevocationNode, PluginVersionNode};
+
+#[derive(InputObject)]
+pub struct RevokePluginCertificateInput {
+    /// SHA-256 fingerprint of the certificate, hex encoded (colons are ignored)
+    pub fingerprint: String,
+    pub reason: Option<String>,
+}
+
+pub fn activate(ctx: &Context<'_>, plugin_version_id: &str) -> Result<PluginVersionNode> {
+    update_active(ctx, plugin_version_id, true)
+}
+
+pub fn deactivate(ctx: &Context<'_>, plugin_version_id: &str) -> Result<PluginVersionNode> {
+    update_active(ctx, plugin_version_id, false)
+}
+
+fn update_active(
+    ctx: &Context<'_>,
+    plugin_version_id: &str,
+    is_active: bool,
+) -> Result<PluginVersionNode> {
+    validate_server_admin(ctx)?;
+
+    let settings = ctx.get_settings();
+    let service_context = ctx.service_provider().basic_context()?;
+    let result = match is_active {
+        true => activate_plugin_version(
+            &service_context,
+            &settings.server.base_dir,
+            plugin_version_id,
+        ),
+        false => deactivate_plugin_version(
+            &service_context,
+            &settings.server.base_dir,
+            plugin_version_id,
+        ),
+    };
+
+    let row = match result {
+        Ok(row) => row,
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                UpdateError::NotACentralServer
+                | UpdateError::PluginVersionDoesNotExist
+                | UpdateError::CertificateRevoked => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                UpdateError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            return Err(graphql_error.extend());
+        }
+    };
+
+    // Return the node including the revocation status
+    let plugin_version = get_plugin_versions(&service_context)?
+        .into_iter()
+        .find(|version| version.plugin_version_row.id == row.id)
+        .ok_or(StandardGraphqlError::InternalError(
+            "Plugin version not found".to_string(),
+        ))?;
+    Ok(PluginVersionNode::from_domain(plugin_version))
+}
+
+pub fn revoke_certificate(
+    ctx: &Context<'_>,
+    input: RevokePluginCertificateInput,
+) -> Result<PluginCertificateRevocationNode> {
+    validate_server_admin(ctx)?;
+
+    let settings = ctx.get_settings();
+    let service_context = ctx.service_provider().basic_context()?;
+    match revoke_plugin_certificate(
+        &service_context,
+        &settings.server.base_dir,
+        RevokePluginCertificate {
+            fingerprint: input.fingerprint,
+            reason: input.reason,
+        },
+    ) {
+        Ok(row) => Ok(PluginCertificateRevocationNode { row }),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                RevokeError::NotACentralServer
+                | RevokeError::InvalidFingerprint
+                | RevokeError::CertificateAlreadyRevoked => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                RevokeError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+fn validate_server_admin(ctx: &Context<'_>) -> Result<()> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+    Ok(())
+}
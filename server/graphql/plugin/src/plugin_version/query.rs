@@ -0,0 +1,23 @@
+use async_graphql::*;
+use graphql_core::{standard_graphql_error::validate_auth, ContextExt};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    plugin::plugin_version::get_plugin_versions,
+};
+
+use super::PluginVersionConnector;
+
+pub fn plugin_versions(ctx: &Context<'_>) -> Result<PluginVersionConnector> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let service_context = ctx.service_provider().basic_context()?;
+    let plugin_versions = get_plugin_versions(&service_context)?;
+
+    Ok(PluginVersionConnector::from_vec(plugin_versions))
+}
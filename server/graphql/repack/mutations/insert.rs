@@ -112,6 +112,7 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         // Standard Graphql Errors
         ServiceError::StockLineDoesNotExist => BadUserInput(formatted_error),
         ServiceError::NotThisStoreStockLine => BadUserInput(formatted_error),
+        ServiceError::PeriodLocked(_) => BadUserInput(formatted_error),
         ServiceError::NewlyCreatedInvoiceDoesNotExist => BadUserInput(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
         ServiceError::InternalError(err) => InternalError(err),
@@ -322,31 +322,21 @@ async fn fetch_data(
     Ok(FetchResult::Data(serde_json::Value::Object(data)))
 }
 
-#[cfg(not(feature = "postgres"))]
 fn fetch_sql_data(
     ctx: &Context<'_>,
     query: &SQLQuery,
     variables: serde_json::Map<String, serde_json::Value>,
 ) -> anyhow::Result<serde_json::Value> {
+    let connection = ctx.get_connection_manager().connection()?;
     let data = query_json(
+        &connection,
         &ctx.get_settings().database,
-        &query.query_sqlite,
+        query.query_for(connection.backend()),
         &variables,
     )?;
     Ok(serde_json::Value::Array(data))
 }
 
-#[cfg(feature = "postgres")]
-fn fetch_sql_data(
-    ctx: &Context<'_>,
-    query: &SQLQuery,
-    variables: serde_json::Map<String, serde_json::Value>,
-) -> anyhow::Result<serde_json::Value> {
-    let connection = ctx.get_connection_manager().connection()?;
-    let data = query_json(&connection, &query.query_postgres, &variables)?;
-    Ok(serde_json::Value::Array(data))
-}
-
 async fn fetch_graphql_data(
     ctx: &Context<'_>,
     query: &GraphQlQuery,
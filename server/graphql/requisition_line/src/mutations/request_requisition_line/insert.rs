@@ -120,6 +120,7 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         ServiceError::NotARequestRequisition => BadUserInput(formatted_error),
         ServiceError::ItemDoesNotExist => BadUserInput(formatted_error),
         ServiceError::CannotAddItemToProgramRequisition => BadUserInput(formatted_error),
+        ServiceError::PluginVetoed(_) => BadUserInput(formatted_error),
         ServiceError::CannotFindItemStatusForRequisitionLine => InternalError(formatted_error),
         ServiceError::NewlyCreatedRequisitionLineDoesNotExist => InternalError(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
@@ -0,0 +1,87 @@
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use graphql_core::{
+    loader::{ItemLoader, NameByIdLoader, NameByIdLoaderInput},
+    standard_graphql_error::StandardGraphqlError,
+};
+use graphql_types::types::{ItemNode, NameNode};
+use service::{stock_line::donor_statement::DonorStockStatementLine, usize_to_u32};
+
+pub struct DonorStockStatementLineNode {
+    pub store_id: String,
+    pub line: DonorStockStatementLine,
+}
+
+#[derive(SimpleObject)]
+pub struct DonorStockStatementConnector {
+    total_count: u32,
+    nodes: Vec<DonorStockStatementLineNode>,
+}
+
+#[Object]
+impl DonorStockStatementLineNode {
+    pub async fn donor_id(&self) -> &str {
+        &self.line.donor_id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.line.item_id
+    }
+
+    /// Quantities are in units
+    pub async fn opening_quantity(&self) -> f64 {
+        self.line.opening_quantity
+    }
+
+    pub async fn received_quantity(&self) -> f64 {
+        self.line.received_quantity
+    }
+
+    pub async fn issued_quantity(&self) -> f64 {
+        self.line.issued_quantity
+    }
+
+    pub async fn closing_quantity(&self) -> f64 {
+        self.line.closing_quantity
+    }
+
+    pub async fn donor(&self, ctx: &Context<'_>) -> Result<Option<NameNode>> {
+        let loader = ctx.get_loader::<DataLoader<NameByIdLoader>>();
+        let result = loader
+            .load_one(NameByIdLoaderInput::new(
+                &self.store_id,
+                &self.line.donor_id,
+            ))
+            .await?;
+
+        Ok(result.map(NameNode::from_domain))
+    }
+
+    pub async fn item(&self, ctx: &Context<'_>) -> Result<ItemNode> {
+        let loader = ctx.get_loader::<DataLoader<ItemLoader>>();
+        let item_option = loader.load_one(self.line.item_id.clone()).await?;
+
+        item_option.map(ItemNode::from_domain).ok_or(
+            StandardGraphqlError::InternalError(format!(
+                "Cannot find item ({}) in donor stock statement",
+                &self.line.item_id
+            ))
+            .extend(),
+        )
+    }
+}
+
+impl DonorStockStatementConnector {
+    pub fn from_domain(store_id: &str, lines: Vec<DonorStockStatementLine>) -> Self {
+        DonorStockStatementConnector {
+            total_count: usize_to_u32(lines.len()),
+            nodes: lines
+                .into_iter()
+                .map(|line| DonorStockStatementLineNode {
+                    store_id: store_id.to_string(),
+                    line,
+                })
+                .collect(),
+        }
+    }
+}
@@ -1,6 +1,9 @@
+pub mod donor_statement;
 pub mod mutations;
+pub mod valuation;
 use async_graphql::*;
 use chrono::{DateTime, Utc};
+use donor_statement::DonorStockStatementConnector;
 use graphql_core::{
     generic_filters::{DateFilterInput, EqualFilterStringInput, StringFilterInput},
     pagination::PaginationInput,
@@ -13,6 +16,7 @@ use repository::{
     StockLineSort, StockLineSortField,
 };
 use service::auth::{Resource, ResourceAccessRequest};
+use valuation::{StockValuationConnector, StockValuationMethodInput};
 
 #[derive(Default, Clone)]
 pub struct StockLineQueries;
@@ -51,6 +55,7 @@ pub struct StockLineFilterInput {
     pub location: Option<LocationFilterInput>,
     pub master_list: Option<MasterListFilterInput>,
     pub is_active: Option<bool>,
+    pub donor_id: Option<EqualFilterStringInput>,
 }
 
 impl From<StockLineFilterInput> for StockLineFilter {
@@ -67,6 +72,7 @@ impl From<StockLineFilterInput> for StockLineFilter {
             location: f.location.map(LocationFilter::from),
             master_list: f.master_list.map(|f| f.to_domain()),
             is_active: f.is_active,
+            donor_id: f.donor_id.map(EqualFilter::from),
         }
     }
 }
@@ -190,6 +196,77 @@ impl StockLineQueries {
             StockLineConnector::from_domain(stock_lines),
         ))
     }
+
+    /// Opening, received, issued and closing quantities per donor and item for the period
+    pub async fn donor_stock_statement(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        donor_id: Option<String>,
+        from_datetime: DateTime<Utc>,
+        to_datetime: DateTime<Utc>,
+    ) -> Result<DonorStockStatementConnector> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryStockLine,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+        let lines = service_provider
+            .stock_line_service
+            .get_donor_stock_statement(
+                &service_context,
+                &store_id,
+                donor_id,
+                from_datetime.naive_utc(),
+                to_datetime.naive_utc(),
+            )
+            .map_err(StandardGraphqlError::from_repository_error)?;
+
+        Ok(DonorStockStatementConnector::from_domain(&store_id, lines))
+    }
+
+    /// Stock value and cost of goods issued per item for the period, the valuation at a date is
+    /// the closing value when only toDatetime is set
+    pub async fn stock_valuation(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        method: StockValuationMethodInput,
+        item_id: Option<String>,
+        from_datetime: Option<DateTime<Utc>>,
+        to_datetime: DateTime<Utc>,
+    ) -> Result<StockValuationConnector> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryStockLine,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+        let lines = service_provider
+            .stock_valuation_service
+            .get_stock_valuation(
+                &service_context,
+                &store_id,
+                method.to_domain(),
+                item_id,
+                from_datetime.map(|datetime| datetime.naive_utc()),
+                to_datetime.naive_utc(),
+            )
+            .map_err(StandardGraphqlError::from_repository_error)?;
+
+        Ok(StockValuationConnector::from_domain(lines))
+    }
 }
 
 #[derive(Default, Clone)]
@@ -206,6 +283,8 @@ impl StockLineMutations {
         mutations::insert(ctx, &store_id, input)
     }
 
+    /// The returned stock line has a `coldChainWarning` when it was moved to a location that
+    /// doesn't meet the cold storage requirement of its item variant
     async fn update_stock_line(
         &self,
         ctx: &Context<'_>,
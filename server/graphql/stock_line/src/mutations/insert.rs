@@ -112,7 +112,8 @@ fn map_error(error: AddNewStockLineError) -> Result<InsertResponse> {
         // Standard Graphql Errors
         AddNewStockLineError::AdjustmentReasonNotValid
         | AddNewStockLineError::AdjustmentReasonNotProvided
-        | AddNewStockLineError::StockLineAlreadyExists => BadUserInput(formatted_error),
+        | AddNewStockLineError::StockLineAlreadyExists
+        | AddNewStockLineError::PeriodLocked(_) => BadUserInput(formatted_error),
         AddNewStockLineError::NewlyCreatedStockLineDoesNotExist
         | AddNewStockLineError::LineInsertError(_)
         | AddNewStockLineError::DatabaseError(_) => InternalError(formatted_error),
@@ -27,6 +27,7 @@ pub struct UpdateInput {
     /// Empty barcode will unlink barcode from StockLine
     pub barcode: Option<String>,
     pub item_variant_id: Option<NullableUpdateInput<String>>,
+    pub donor_id: Option<NullableUpdateInput<String>>,
 }
 
 #[derive(Interface)]
@@ -93,6 +94,7 @@ impl UpdateInput {
             on_hold,
             barcode,
             item_variant_id,
+            donor_id,
         } = self;
 
         ServiceInput {
@@ -109,6 +111,9 @@ impl UpdateInput {
             item_variant_id: item_variant_id.map(|item_variant_id| NullableUpdate {
                 value: item_variant_id.value,
             }),
+            donor_id: donor_id.map(|donor_id| NullableUpdate {
+                value: donor_id.value,
+            }),
         }
     }
 }
@@ -144,8 +149,10 @@ mod test {
         assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphql_test,
     };
     use repository::{
-        mock::{mock_item_a, mock_stock_line_a, MockDataInserts},
-        StockLine, StorageConnectionManager,
+        item_variant::item_variant_row::{ItemVariantRow, ItemVariantRowRepository},
+        mock::{mock_item_a, mock_location_1, mock_stock_line_a, MockDataInserts},
+        ColdStorageTypeRow, ColdStorageTypeRowRepository, StockLine, StockLineRow,
+        StorageConnectionManager,
     };
     use serde_json::json;
 
@@ -308,4 +315,92 @@ mod test {
             Some(service_provider(test_service, &connection_manager))
         );
     }
+
+    #[actix_rt::test]
+    async fn test_graphql_update_stock_line_cold_chain_warning() {
+        let (_, connection, connection_manager, settings) = setup_graphql_test(
+            EmptyMutation,
+            StockLineMutations,
+            "test_graphql_update_stock_line_cold_chain_warning",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        ColdStorageTypeRowRepository::new(&connection)
+            .upsert_one(&ColdStorageTypeRow {
+                id: "fridge".to_string(),
+                name: "Fridge".to_string(),
+                min_temperature: 2.0,
+                max_temperature: 8.0,
+            })
+            .unwrap();
+        ItemVariantRowRepository::new(&connection)
+            .upsert_one(&ItemVariantRow {
+                id: "vaccine_variant".to_string(),
+                item_link_id: mock_item_a().id,
+                cold_storage_type_id: Some("fridge".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let mutation = r#"
+            mutation ($storeId: String, $input: UpdateStockLineInput!) {
+                updateStockLine(storeId: $storeId, input: $input) {
+                    ... on StockLineNode {
+                        id
+                        coldChainWarning {
+                            kind
+                            requiredColdStorageType {
+                                id
+                            }
+                        }
+                    }
+                }
+              }
+            "#;
+
+        // Stock moved to a location that isn't cold storage
+        let test_service = TestService(Box::new(|_| {
+            Ok(StockLine {
+                stock_line_row: StockLineRow {
+                    item_variant_id: Some("vaccine_variant".to_string()),
+                    location_id: Some(mock_location_1().id),
+                    ..mock_stock_line_a()
+                },
+                item_row: mock_item_a(),
+                location_row: Some(mock_location_1()),
+                supplier_name_row: None,
+                barcode_row: None,
+            })
+        }));
+
+        let variables = json!({
+          "input": {
+            "id": mock_stock_line_a().id,
+            "location": {"value": mock_location_1().id}
+          },
+          "storeId": "store_a"
+        });
+
+        let expected = json!({
+            "updateStockLine": {
+                "id": mock_stock_line_a().id,
+                "coldChainWarning": {
+                    "kind": "locationIsNotColdStorage",
+                    "requiredColdStorageType": {
+                        "id": "fridge"
+                    }
+                }
+            }
+          }
+        );
+
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
 }
@@ -0,0 +1,103 @@
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use graphql_core::{loader::ItemLoader, standard_graphql_error::StandardGraphqlError};
+use graphql_types::types::ItemNode;
+use service::{
+    stock_valuation::{StockValuationLine, StockValuationMethod},
+    usize_to_u32,
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(rename_items = "camelCase")]
+pub enum StockValuationMethodInput {
+    WeightedAverageCost,
+    Fifo,
+    LastPurchasePrice,
+}
+
+pub struct StockValuationLineNode {
+    pub line: StockValuationLine,
+}
+
+#[derive(SimpleObject)]
+pub struct StockValuationConnector {
+    total_count: u32,
+    /// Store totals of the item values
+    total_opening_value: f64,
+    total_cost_of_goods_issued: f64,
+    total_closing_value: f64,
+    nodes: Vec<StockValuationLineNode>,
+}
+
+#[Object]
+impl StockValuationLineNode {
+    pub async fn item_id(&self) -> &str {
+        &self.line.item_id
+    }
+
+    /// Quantities are in units
+    pub async fn opening_quantity(&self) -> f64 {
+        self.line.opening_quantity
+    }
+
+    pub async fn opening_value(&self) -> f64 {
+        self.line.opening_value
+    }
+
+    /// Quantity issued by outbound shipments and prescriptions
+    pub async fn issued_quantity(&self) -> f64 {
+        self.line.issued_quantity
+    }
+
+    pub async fn cost_of_goods_issued(&self) -> f64 {
+        self.line.cost_of_goods_issued
+    }
+
+    pub async fn closing_quantity(&self) -> f64 {
+        self.line.closing_quantity
+    }
+
+    pub async fn closing_value(&self) -> f64 {
+        self.line.closing_value
+    }
+
+    pub async fn item(&self, ctx: &Context<'_>) -> Result<ItemNode> {
+        let loader = ctx.get_loader::<DataLoader<ItemLoader>>();
+        let item_option = loader.load_one(self.line.item_id.clone()).await?;
+
+        item_option.map(ItemNode::from_domain).ok_or(
+            StandardGraphqlError::InternalError(format!(
+                "Cannot find item ({}) in stock valuation",
+                &self.line.item_id
+            ))
+            .extend(),
+        )
+    }
+}
+
+impl StockValuationConnector {
+    pub fn from_domain(lines: Vec<StockValuationLine>) -> Self {
+        StockValuationConnector {
+            total_count: usize_to_u32(lines.len()),
+            total_opening_value: lines.iter().map(|line| line.opening_value).sum(),
+            total_cost_of_goods_issued: lines.iter().map(|line| line.cost_of_goods_issued).sum(),
+            total_closing_value: lines.iter().map(|line| line.closing_value).sum(),
+            nodes: lines
+                .into_iter()
+                .map(|line| StockValuationLineNode { line })
+                .collect(),
+        }
+    }
+}
+
+impl StockValuationMethodInput {
+    pub fn to_domain(self) -> StockValuationMethod {
+        match self {
+            StockValuationMethodInput::WeightedAverageCost => {
+                StockValuationMethod::WeightedAverageCost
+            }
+            StockValuationMethodInput::Fifo => StockValuationMethod::Fifo,
+            StockValuationMethodInput::LastPurchasePrice => StockValuationMethod::LastPurchasePrice,
+        }
+    }
+}
@@ -36,6 +36,7 @@ pub struct InsertInput {
     pub note: Option<String>,
     pub inventory_adjustment_reason_id: Option<String>,
     pub item_variant_id: Option<String>,
+    pub donor_id: Option<String>,
 }
 
 #[derive(Union)]
@@ -155,6 +156,7 @@ impl InsertInput {
             note,
             inventory_adjustment_reason_id,
             item_variant_id,
+            donor_id,
         } = self;
 
         ServiceInput {
@@ -175,6 +177,7 @@ impl InsertInput {
             note,
             inventory_adjustment_reason_id,
             item_variant_id,
+            donor_id,
         }
     }
 }
@@ -296,6 +299,7 @@ mod test {
                     note: Some("note".to_string()),
                     inventory_adjustment_reason_id: None,
                     item_variant_id: None,
+                    donor_link_id: None,
                 },
                 stock_line: Some(mock_stock_line_a()),
                 location: Some(mock_location_1()),
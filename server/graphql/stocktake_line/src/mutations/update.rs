@@ -38,6 +38,7 @@ pub struct UpdateInput {
     pub note: Option<String>,
     pub inventory_adjustment_reason_id: Option<String>,
     pub item_variant_id: Option<NullableUpdateInput<String>>,
+    pub donor_id: Option<NullableUpdateInput<String>>,
 }
 
 #[derive(Union)]
@@ -109,6 +110,7 @@ impl UpdateInput {
             note,
             inventory_adjustment_reason_id,
             item_variant_id,
+            donor_id,
         } = self;
 
         ServiceInput {
@@ -129,6 +131,9 @@ impl UpdateInput {
             item_variant_id: item_variant_id.map(|item_variant_id| NullableUpdate {
                 value: item_variant_id.value,
             }),
+            donor_id: donor_id.map(|donor_id| NullableUpdate {
+                value: donor_id.value,
+            }),
         }
     }
 }
@@ -292,6 +297,7 @@ mod test {
                     note: Some("note".to_string()),
                     inventory_adjustment_reason_id: None,
                     item_variant_id: None,
+                    donor_link_id: None,
                 },
                 stock_line: Some(mock_stock_line_a()),
                 location: Some(mock_location_1()),
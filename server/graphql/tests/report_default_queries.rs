@@ -8,6 +8,7 @@ mod tests {
     use graphql_invoice_line::InvoiceLineQueries;
     use graphql_location::LocationQueries;
     use graphql_requisition::RequisitionQueries;
+    use graphql_stock_line::StockLineQueries;
     use graphql_stocktake::StocktakeQueries;
     use graphql_stocktake_line::StocktakeLineQueries;
     use repository::mock::{
@@ -27,6 +28,7 @@ mod tests {
         pub StocktakeLineQueries,
         pub GeneralQueries,
         pub RequisitionQueries,
+        pub StockLineQueries,
     );
 
     fn full_query() -> FullQuery {
@@ -38,6 +40,7 @@ mod tests {
             StocktakeLineQueries,
             GeneralQueries,
             RequisitionQueries,
+            StockLineQueries,
         )
     }
 
@@ -119,5 +122,43 @@ mod tests {
             "dataId": mock_requisition.id,
         }));
         assert_graphql_query!(&settings, &query, &variables, &expected, None);
+
+        // donor stock statement
+        let query = get_default_gql_query(DefaultQuery::DonorStockStatement).query;
+        let mock_store_id = mock_outbound_shipment_a().store_id;
+        let expected = json!({
+          "donorStockStatement": {
+            "totalCount": 0,
+            "nodes": []
+          },
+          "store": {
+            "id": mock_store_id
+          }
+        });
+        let variables = Some(json!({
+            "storeId": mock_store_id,
+            "fromDatetime": "2024-01-01T00:00:00Z",
+            "toDatetime": "2024-12-31T23:59:59Z",
+        }));
+        assert_graphql_query!(&settings, &query, &variables, &expected, None);
+
+        // stock valuation, before any stock movements
+        let query = get_default_gql_query(DefaultQuery::StockValuation).query;
+        let expected = json!({
+          "stockValuation": {
+            "totalCount": 0,
+            "totalClosingValue": 0.0,
+            "nodes": []
+          },
+          "store": {
+            "id": mock_store_id
+          }
+        });
+        let variables = Some(json!({
+            "storeId": mock_store_id,
+            "method": "weightedAverageCost",
+            "toDatetime": "2000-01-01T00:00:00Z",
+        }));
+        assert_graphql_query!(&settings, &query, &variables, &expected, None);
     }
 }
@@ -79,6 +79,16 @@ pub enum ActivityLogNodeType {
     DemographicIndicatorUpdated,
     DemographicProjectionCreated,
     DemographicProjectionUpdated,
+    PluginAnnotation,
+    InvoiceDiscrepanciesReported,
+    UserLoginFailed,
+    UserAccountLocked,
+    UserAccountUnlocked,
+    UserPasswordChanged,
+    UserTotpEnabled,
+    UserTotpDisabled,
+    PeriodLocked,
+    PeriodLockOverridden,
 }
 
 #[Object]
@@ -213,6 +223,16 @@ impl ActivityLogNodeType {
             from::DemographicIndicatorUpdated => to::DemographicIndicatorUpdated,
             from::DemographicProjectionCreated => to::DemographicProjectionCreated,
             from::DemographicProjectionUpdated => to::DemographicProjectionUpdated,
+            from::PluginAnnotation => to::PluginAnnotation,
+            from::InvoiceDiscrepanciesReported => to::InvoiceDiscrepanciesReported,
+            from::UserLoginFailed => to::UserLoginFailed,
+            from::UserAccountLocked => to::UserAccountLocked,
+            from::UserAccountUnlocked => to::UserAccountUnlocked,
+            from::UserPasswordChanged => to::UserPasswordChanged,
+            from::UserTotpEnabled => to::UserTotpEnabled,
+            from::UserTotpDisabled => to::UserTotpDisabled,
+            from::PeriodLocked => to::PeriodLocked,
+            from::PeriodLockOverridden => to::PeriodLockOverridden,
         }
     }
 
@@ -277,6 +297,16 @@ impl ActivityLogNodeType {
             from::DemographicIndicatorUpdated => to::DemographicIndicatorUpdated,
             from::DemographicProjectionCreated => to::DemographicProjectionCreated,
             from::DemographicProjectionUpdated => to::DemographicProjectionUpdated,
+            from::PluginAnnotation => to::PluginAnnotation,
+            from::InvoiceDiscrepanciesReported => to::InvoiceDiscrepanciesReported,
+            from::UserLoginFailed => to::UserLoginFailed,
+            from::UserAccountLocked => to::UserAccountLocked,
+            from::UserAccountUnlocked => to::UserAccountUnlocked,
+            from::UserPasswordChanged => to::UserPasswordChanged,
+            from::UserTotpEnabled => to::UserTotpEnabled,
+            from::UserTotpDisabled => to::UserTotpDisabled,
+            from::PeriodLocked => to::PeriodLocked,
+            from::PeriodLockOverridden => to::PeriodLockOverridden,
         }
     }
 }
@@ -1,16 +1,21 @@
-use super::{ItemNode, LocationNode, PricingNode, ReturnReasonNode, StockLineNode};
+use super::{
+    ColdChainWarningNode, ItemNode, LocationNode, PricingNode, ReturnReasonNode, StockLineNode,
+};
 use async_graphql::*;
 use chrono::NaiveDate;
 use dataloader::DataLoader;
 use graphql_core::{
-    loader::{ItemLoader, LocationByIdLoader, ReturnReasonLoader, StockLineByIdLoader},
+    loader::{
+        ColdChainWarningLoader, ItemLoader, LocationByIdLoader, ReturnReasonLoader,
+        StockLineByIdLoader,
+    },
     simple_generic_errors::NodeError,
     standard_graphql_error::StandardGraphqlError,
     ContextExt,
 };
 use repository::{InvoiceLine, InvoiceLineRow, InvoiceLineType, ItemRow};
 use serde::Serialize;
-use service::{usize_to_u32, ListResult};
+use service::{location::cold_chain::ItemVariantAndLocation, usize_to_u32, ListResult};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")] // only needed to be comparable in tests
@@ -89,6 +94,9 @@ impl InvoiceLineNode {
     pub async fn item_variant_id(&self) -> &Option<String> {
         &self.row().item_variant_id
     }
+    pub async fn donor_id(&self) -> &Option<String> {
+        &self.row().donor_link_id
+    }
     // Quantity
     pub async fn pack_size(&self) -> f64 {
         self.row().pack_size
@@ -159,6 +167,27 @@ impl InvoiceLineNode {
 
         Ok(result.map(LocationNode::from_domain))
     }
+    /// Set when the item variant needs cold storage that the location doesn't provide
+    pub async fn cold_chain_warning(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<ColdChainWarningNode>> {
+        let (Some(item_variant_id), Some(location_id)) =
+            (&self.row().item_variant_id, &self.row().location_id)
+        else {
+            return Ok(None);
+        };
+
+        let loader = ctx.get_loader::<DataLoader<ColdChainWarningLoader>>();
+        let warning = loader
+            .load_one(ItemVariantAndLocation {
+                item_variant_id: item_variant_id.clone(),
+                location_id: location_id.clone(),
+            })
+            .await?;
+
+        Ok(warning.map(ColdChainWarningNode::from_domain))
+    }
 
     // Other
     pub async fn note(&self) -> &Option<String> {
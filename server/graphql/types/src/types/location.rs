@@ -4,14 +4,17 @@ use super::StockLineConnector;
 use async_graphql::dataloader::DataLoader;
 use async_graphql::*;
 use graphql_core::generic_filters::{EqualFilterStringInput, StringFilterInput};
-use graphql_core::loader::ColdStorageTypeLoader;
+use graphql_core::loader::{ColdStorageTypeLoader, LocationByIdLoader};
 use graphql_core::simple_generic_errors::NodeError;
+use graphql_core::standard_graphql_error::StandardGraphqlError;
 use graphql_core::{loader::StockLineByLocationIdLoader, ContextExt};
 use repository::StringFilter;
 use repository::{
     location::{Location, LocationFilter, LocationSort, LocationSortField},
     EqualFilter, LocationRow,
 };
+use service::location::cold_chain::ColdChainWarning;
+use service::location::utilisation::LocationUtilisation;
 use service::{usize_to_u32, ListResult};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq)]
@@ -37,6 +40,7 @@ pub struct LocationFilterInput {
     pub assigned_to_asset: Option<bool>,
     pub store_id: Option<EqualFilterStringInput>,
     pub id: Option<EqualFilterStringInput>,
+    pub parent_location_id: Option<EqualFilterStringInput>,
 }
 
 impl From<LocationFilterInput> for LocationFilter {
@@ -48,6 +52,7 @@ impl From<LocationFilterInput> for LocationFilter {
             store_id: f.store_id.map(EqualFilter::from),
             on_hold: f.on_hold,
             assigned_to_asset: f.assigned_to_asset,
+            parent_location_id: f.parent_location_id.map(EqualFilter::from),
         }
     }
 }
@@ -105,6 +110,119 @@ impl LocationNode {
             .await?
             .map(ColdStorageTypeNode::from_domain))
     }
+
+    pub async fn parent_location_id(&self) -> &Option<String> {
+        &self.row().parent_location_id
+    }
+
+    pub async fn parent_location(&self, ctx: &Context<'_>) -> Result<Option<LocationNode>> {
+        let parent_location_id = match &self.row().parent_location_id {
+            Some(parent_location_id) => parent_location_id,
+            None => return Ok(None),
+        };
+
+        let loader = ctx.get_loader::<DataLoader<LocationByIdLoader>>();
+        Ok(loader
+            .load_one(parent_location_id.clone())
+            .await?
+            .map(LocationNode::from_domain))
+    }
+
+    pub async fn volume_capacity(&self) -> Option<f64> {
+        self.row().volume_capacity
+    }
+
+    pub async fn pallet_capacity(&self) -> Option<i32> {
+        self.row().pallet_capacity
+    }
+
+    pub async fn walk_sequence(&self) -> Option<i32> {
+        self.row().walk_sequence
+    }
+
+    /// Utilisation of the location, including stock in sub locations
+    pub async fn utilisation(&self, ctx: &Context<'_>) -> Result<LocationUtilisationNode> {
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.basic_context()?;
+
+        let utilisation = service_provider
+            .location_service
+            .get_location_utilisation(&service_context, self.row())
+            .map_err(StandardGraphqlError::from_repository_error)?;
+
+        Ok(LocationUtilisationNode { utilisation })
+    }
+}
+
+pub struct LocationUtilisationNode {
+    pub utilisation: LocationUtilisation,
+}
+
+#[Object]
+impl LocationUtilisationNode {
+    pub async fn volume_used(&self) -> f64 {
+        self.utilisation.volume_used
+    }
+
+    /// Fraction of the volume capacity that is used, null if the location has no volume capacity
+    pub async fn volume_utilisation(&self) -> Option<f64> {
+        self.utilisation.volume_utilisation()
+    }
+
+    /// Number of stock lines whose volume is unknown and not included in `volumeUsed`
+    pub async fn stock_lines_without_volume(&self) -> u32 {
+        self.utilisation.stock_lines_without_volume
+    }
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(rename_items = "camelCase")]
+pub enum ColdChainWarningKind {
+    LocationIsNotColdStorage,
+    IncompatibleColdStorageType,
+}
+
+/// Warns that stock is stored in a location that doesn't meet the cold storage requirement of
+/// its item variant
+pub struct ColdChainWarningNode {
+    pub warning: ColdChainWarning,
+}
+
+#[Object]
+impl ColdChainWarningNode {
+    pub async fn kind(&self) -> ColdChainWarningKind {
+        match &self.warning {
+            ColdChainWarning::LocationIsNotColdStorage { .. } => {
+                ColdChainWarningKind::LocationIsNotColdStorage
+            }
+            ColdChainWarning::IncompatibleColdStorageType { .. } => {
+                ColdChainWarningKind::IncompatibleColdStorageType
+            }
+        }
+    }
+
+    pub async fn required_cold_storage_type(&self) -> ColdStorageTypeNode {
+        let required = match &self.warning {
+            ColdChainWarning::LocationIsNotColdStorage { required } => required,
+            ColdChainWarning::IncompatibleColdStorageType { required, .. } => required,
+        };
+        ColdStorageTypeNode::from_domain(required.clone())
+    }
+
+    pub async fn location_cold_storage_type(&self) -> Option<ColdStorageTypeNode> {
+        match &self.warning {
+            ColdChainWarning::LocationIsNotColdStorage { .. } => None,
+            ColdChainWarning::IncompatibleColdStorageType { location, .. } => {
+                Some(ColdStorageTypeNode::from_domain(location.clone()))
+            }
+        }
+    }
+}
+
+impl ColdChainWarningNode {
+    pub fn from_domain(warning: ColdChainWarning) -> ColdChainWarningNode {
+        ColdChainWarningNode { warning }
+    }
 }
 
 #[derive(Union)]
@@ -32,6 +32,8 @@ pub enum UserPermission {
     RequisitionSend,
     RnRFormQuery,
     RnRFormMutate,
+    PeriodLockMutate,
+    PeriodLockOverride,
     OutboundShipmentQuery,
     OutboundShipmentMutate,
     InboundShipmentQuery,
@@ -115,6 +117,8 @@ impl UserPermission {
             PermissionType::RequisitionMutate => UserPermission::RequisitionMutate,
             PermissionType::RnrFormQuery => UserPermission::RnRFormQuery,
             PermissionType::RnrFormMutate => UserPermission::RnRFormMutate,
+            PermissionType::PeriodLockMutate => UserPermission::PeriodLockMutate,
+            PermissionType::PeriodLockOverride => UserPermission::PeriodLockOverride,
             PermissionType::RequisitionSend => UserPermission::RequisitionSend,
             PermissionType::OutboundShipmentQuery => UserPermission::OutboundShipmentQuery,
             PermissionType::OutboundShipmentMutate => UserPermission::OutboundShipmentMutate,
@@ -166,6 +170,8 @@ impl UserPermission {
             UserPermission::RequisitionSend => PermissionType::RequisitionSend,
             UserPermission::RnRFormQuery => PermissionType::RnrFormQuery,
             UserPermission::RnRFormMutate => PermissionType::RnrFormMutate,
+            UserPermission::PeriodLockMutate => PermissionType::PeriodLockMutate,
+            UserPermission::PeriodLockOverride => PermissionType::PeriodLockOverride,
             UserPermission::OutboundShipmentQuery => PermissionType::OutboundShipmentQuery,
             UserPermission::OutboundShipmentMutate => PermissionType::OutboundShipmentMutate,
             UserPermission::InboundShipmentQuery => PermissionType::InboundShipmentQuery,
@@ -90,6 +90,7 @@ impl From<ProgramEnrolmentFilterInput> for ProgramEnrolmentFilter {
             program_context_id: None,
             program_name: f.program_name.map(StringFilter::from),
             is_immunisation_program: f.is_immunisation_program,
+            patient_store_id: None,
         }
     }
 }
@@ -1,10 +1,11 @@
-use super::{ItemNode, LocationNode, MasterListNode};
+use super::{ColdChainWarningNode, ItemNode, LocationNode, MasterListNode};
 use async_graphql::dataloader::DataLoader;
 use async_graphql::*;
 use chrono::NaiveDate;
 use graphql_core::{
     loader::{
-        ItemLoader, LocationByIdLoader, MasterListByItemIdLoader, MasterListByItemIdLoaderInput,
+        ColdChainWarningLoader, ItemLoader, LocationByIdLoader, MasterListByItemIdLoader,
+        MasterListByItemIdLoaderInput,
     },
     simple_generic_errors::NodeError,
     standard_graphql_error::StandardGraphqlError,
@@ -12,7 +13,8 @@ use graphql_core::{
 };
 use repository::{ItemRow, StockLine, StockLineRow};
 use service::{
-    service_provider::ServiceContext, stock_line::query::get_stock_line, usize_to_u32, ListResult,
+    location::cold_chain::ItemVariantAndLocation, service_provider::ServiceContext,
+    stock_line::query::get_stock_line, usize_to_u32, ListResult,
 };
 
 pub struct StockLineNode {
@@ -48,6 +50,9 @@ impl StockLineNode {
     pub async fn item_variant_id(&self) -> &Option<String> {
         &self.row().item_variant_id
     }
+    pub async fn donor_id(&self) -> &Option<String> {
+        &self.row().donor_link_id
+    }
     pub async fn cost_price_per_pack(&self) -> f64 {
         self.row().cost_price_per_pack
     }
@@ -87,6 +92,27 @@ impl StockLineNode {
 
         Ok(result.map(LocationNode::from_domain))
     }
+    /// Set when the item variant needs cold storage that the location doesn't provide
+    pub async fn cold_chain_warning(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<ColdChainWarningNode>> {
+        let (Some(item_variant_id), Some(location_id)) =
+            (&self.row().item_variant_id, &self.row().location_id)
+        else {
+            return Ok(None);
+        };
+
+        let loader = ctx.get_loader::<DataLoader<ColdChainWarningLoader>>();
+        let warning = loader
+            .load_one(ItemVariantAndLocation {
+                item_variant_id: item_variant_id.clone(),
+                location_id: location_id.clone(),
+            })
+            .await?;
+
+        Ok(warning.map(ColdChainWarningNode::from_domain))
+    }
     pub async fn item(&self, ctx: &Context<'_>) -> Result<ItemNode> {
         let loader = ctx.get_loader::<DataLoader<ItemLoader>>();
         let item_option = loader.load_one(self.item_row().id.clone()).await?;
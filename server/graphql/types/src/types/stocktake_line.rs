@@ -90,6 +90,10 @@ impl StocktakeLineNode {
         &self.line.line.item_variant_id
     }
 
+    pub async fn donor_id(&self) -> &Option<String> {
+        &self.line.line.donor_link_id
+    }
+
     pub async fn batch(&self) -> &Option<String> {
         &self.line.line.batch
     }
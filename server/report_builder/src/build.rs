@@ -35,6 +35,8 @@ fn parse_default_query(input: &str) -> anyhow::Result<DefaultQuery> {
         "invoice" => DefaultQuery::Invoice,
         "stocktake" => DefaultQuery::Stocktake,
         "requisition" => DefaultQuery::Requisition,
+        "donor_stock_statement" => DefaultQuery::DonorStockStatement,
+        "stock_valuation" => DefaultQuery::StockValuation,
         _ => {
             return Err(anyhow::Error::msg(format!(
                 "Invalid default query: {}",
@@ -36,7 +36,8 @@ pub struct BuildArgs {
     /// Name of the file containing a graphql query
     #[clap(long)]
     pub query_gql: Option<String>,
-    /// Default query type, one of: "invoice" | "stocktake" | "requisition",
+    /// Default query type, one of: "invoice" | "stocktake" | "requisition" |
+    /// "donor_stock_statement" | "stock_valuation",
     #[clap(long)]
     pub query_default: Option<String>,
 
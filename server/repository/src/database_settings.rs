@@ -2,17 +2,53 @@ use crate::db_diesel::{DBBackendConnection, StorageConnectionManager};
 use diesel::connection::SimpleConnection;
 use diesel::r2d2::{ConnectionManager, Pool};
 use log::info;
+use thiserror::Error;
 
 // Timeout for waiting for the SQLite lock (https://www.sqlite.org/c3ref/busy_timeout.html).
 // A locked DB results in the "SQLite database is locked" error.
 #[cfg(not(feature = "postgres"))]
 const SQLITE_LOCKWAIT_MS: u32 = 30 * 1000;
 
-#[cfg(all(not(feature = "postgres"), not(feature = "memory")))]
 const SQLITE_WAL_PRAGMA: &str = "PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;";
 
+/// Database backend a server runs against
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseBackend {
+    Sqlite,
+    Postgres,
+}
+
+impl DatabaseBackend {
+    /// Backend the repository was built for
+    pub const fn compiled() -> Self {
+        if cfg!(feature = "postgres") {
+            DatabaseBackend::Postgres
+        } else {
+            DatabaseBackend::Sqlite
+        }
+    }
+
+    pub fn is_postgres(&self) -> bool {
+        *self == DatabaseBackend::Postgres
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+#[error("Database backend {configured:?} is not supported by this build, only {compiled:?} is available")]
+pub struct UnsupportedDatabaseBackend {
+    pub configured: DatabaseBackend,
+    pub compiled: DatabaseBackend,
+}
+
 #[derive(serde::Deserialize, Clone)]
 pub struct DatabaseSettings {
+    /// Backend the database settings are for, defaults to the backend the server was built for.
+    /// A server only runs against the backend it was built for, configuring the other one is an
+    /// error when the server configuration is loaded. Other backends can only be read, e.g. as the
+    /// source of `cli migrate-database`.
+    #[serde(default)]
+    pub backend: Option<DatabaseBackend>,
     pub username: String,
     pub password: String,
     pub port: u16,
@@ -23,14 +59,34 @@ pub struct DatabaseSettings {
     pub init_sql: Option<String>,
 }
 
-// feature postgres
-#[cfg(feature = "postgres")]
 impl DatabaseSettings {
+    pub fn backend(&self) -> DatabaseBackend {
+        self.backend.unwrap_or(DatabaseBackend::compiled())
+    }
+
+    /// Checks the configured backend is the one this build supports
+    pub fn check_backend(&self) -> Result<(), UnsupportedDatabaseBackend> {
+        let compiled = DatabaseBackend::compiled();
+        if self.backend() != compiled {
+            return Err(UnsupportedDatabaseBackend {
+                configured: self.backend(),
+                compiled,
+            });
+        }
+        Ok(())
+    }
+
     pub fn connection_string(&self) -> String {
-        format!(
-            "postgres://{}:{}@{}:{}/{}",
-            self.username, self.password, self.host, self.port, self.database_name
-        )
+        match self.backend() {
+            DatabaseBackend::Postgres => format!(
+                "postgres://{}:{}@{}:{}/{}",
+                self.username, self.password, self.host, self.port, self.database_name
+            ),
+            DatabaseBackend::Sqlite if cfg!(feature = "memory") => {
+                format!("file:{}?mode=memory&cache=shared", self.database_name)
+            }
+            DatabaseBackend::Sqlite => self.sqlite_file_name(),
+        }
     }
 
     pub fn connection_string_without_db(&self) -> String {
@@ -40,19 +96,29 @@ impl DatabaseSettings {
         )
     }
 
-    pub fn full_init_sql(&self) -> Option<String> {
-        self.init_sql.clone()
+    pub fn database_path(&self) -> String {
+        match (self.backend(), &self.database_path) {
+            (DatabaseBackend::Postgres, _) => self.database_name.clone(),
+            (DatabaseBackend::Sqlite, Some(path)) => {
+                std::fs::create_dir_all(path).expect("failed to create database dir");
+                format!("{}/{}", path, self.connection_string())
+            }
+            (DatabaseBackend::Sqlite, None) => self.connection_string(),
+        }
     }
 
-    pub fn database_path(&self) -> String {
-        self.database_name.clone()
+    pub fn full_init_sql(&self) -> Option<String> {
+        if self.backend().is_postgres() || cfg!(feature = "memory") {
+            return self.init_sql.clone();
+        }
+        //For SQLite we want to enable the Write Head Log on server startup
+        match &self.init_sql {
+            Some(sql_statement) => Some(format!("{};{}", sql_statement, SQLITE_WAL_PRAGMA)),
+            None => Some(SQLITE_WAL_PRAGMA.to_string()),
+        }
     }
-}
 
-// feature sqlite
-#[cfg(all(not(feature = "postgres"), not(feature = "memory")))]
-impl DatabaseSettings {
-    pub fn connection_string(&self) -> String {
+    fn sqlite_file_name(&self) -> String {
         use std::path::Path;
         if self.database_name.ends_with(".sqlite") {
             // just use DB if name ends in .sqlite
@@ -74,36 +140,6 @@ impl DatabaseSettings {
             }
         }
     }
-
-    pub fn database_path(&self) -> String {
-        match &self.database_path {
-            Some(path) => {
-                std::fs::create_dir_all(path).expect("failed to create database dir");
-                format!("{}/{}", path, self.connection_string())
-            }
-            None => self.connection_string(),
-        }
-    }
-
-    pub fn full_init_sql(&self) -> Option<String> {
-        //For SQLite we want to enable the Write Head Log on server startup
-        match &self.init_sql {
-            Some(sql_statement) => Some(format!("{};{}", sql_statement, SQLITE_WAL_PRAGMA)),
-            None => Some(SQLITE_WAL_PRAGMA.to_string()),
-        }
-    }
-}
-
-// feature memory
-#[cfg(feature = "memory")]
-impl DatabaseSettings {
-    pub fn connection_string(&self) -> String {
-        format!("file:{}?mode=memory&cache=shared", self.database_name)
-    }
-
-    pub fn full_init_sql(&self) -> Option<String> {
-        self.init_sql.clone()
-    }
 }
 
 // feature sqlite
@@ -177,6 +213,7 @@ pub fn get_storage_connection_manager(settings: &DatabaseSettings) -> StorageCon
 // feature sqlite
 #[cfg(not(feature = "postgres"))]
 pub fn get_storage_connection_manager(settings: &DatabaseSettings) -> StorageConnectionManager {
+    crate::db_diesel::sqlite_timeout::register_progress_handler();
     info!("Connecting to database '{}'", settings.database_path());
     let connection_manager =
         ConnectionManager::<DBBackendConnection>::new(settings.database_path());
@@ -196,6 +233,7 @@ mod database_setting_test {
     #[allow(dead_code)]
     pub fn empty_db_settings_with_init_sql(init_sql: Option<String>) -> DatabaseSettings {
         DatabaseSettings {
+            backend: None,
             username: "".to_string(),
             password: "".to_string(),
             port: 0,
@@ -206,11 +244,15 @@ mod database_setting_test {
         }
     }
 
-    // feature sqlite
-    #[cfg(all(not(feature = "postgres"), not(feature = "memory")))]
+    #[cfg(not(feature = "memory"))]
     #[test]
     fn test_database_settings_full_init_sql() {
-        use super::SQLITE_WAL_PRAGMA;
+        use super::{DatabaseBackend, SQLITE_WAL_PRAGMA};
+
+        let empty_db_settings_with_init_sql = |init_sql| DatabaseSettings {
+            backend: Some(DatabaseBackend::Sqlite),
+            ..empty_db_settings_with_init_sql(init_sql)
+        };
 
         //Ensure sqlite WAL is enabled if no init_sql is provided
         assert_eq!(
@@ -232,6 +274,39 @@ mod database_setting_test {
             empty_db_settings_with_init_sql(Some(init_sql_missing_semi_colon.to_string()))
                 .full_init_sql(),
             Some(expected_init_sql)
-        )
+        );
+
+        //Postgres init_sql is used as is
+        let postgres_settings = DatabaseSettings {
+            backend: Some(DatabaseBackend::Postgres),
+            ..empty_db_settings_with_init_sql(Some(init_sql.to_string()))
+        };
+        assert_eq!(
+            postgres_settings.full_init_sql(),
+            Some(init_sql.to_string())
+        );
+    }
+
+    #[test]
+    fn test_database_settings_backend() {
+        use super::{DatabaseBackend, UnsupportedDatabaseBackend};
+
+        let mut settings = empty_db_settings_with_init_sql(None);
+        assert_eq!(settings.backend(), DatabaseBackend::compiled());
+        assert_eq!(settings.check_backend(), Ok(()));
+
+        let other = match DatabaseBackend::compiled() {
+            DatabaseBackend::Sqlite => DatabaseBackend::Postgres,
+            DatabaseBackend::Postgres => DatabaseBackend::Sqlite,
+        };
+        settings.backend = Some(other);
+        assert_eq!(settings.backend(), other);
+        assert_eq!(
+            settings.check_backend(),
+            Err(UnsupportedDatabaseBackend {
+                configured: other,
+                compiled: DatabaseBackend::compiled(),
+            })
+        );
     }
 }
@@ -144,3 +144,12 @@ impl ActivityLogFilter {
         self
     }
 }
+
+impl ActivityLogType {
+    pub fn equal_to(&self) -> EqualFilter<Self> {
+        EqualFilter {
+            equal_to: Some(self.clone()),
+            ..Default::default()
+        }
+    }
+}
@@ -85,6 +85,16 @@ pub enum ActivityLogType {
     DemographicIndicatorUpdated,
     DemographicProjectionCreated,
     DemographicProjectionUpdated,
+    PluginAnnotation,
+    InvoiceDiscrepanciesReported,
+    UserLoginFailed,
+    UserAccountLocked,
+    UserAccountUnlocked,
+    UserPasswordChanged,
+    UserTotpEnabled,
+    UserTotpDisabled,
+    PeriodLocked,
+    PeriodLockOverridden,
 }
 
 #[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
@@ -0,0 +1,103 @@
+use super::api_token_row::api_token::dsl::*;
+
+use crate::{RepositoryError, StorageConnection};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    api_token (id) {
+        id -> Text,
+        name -> Text,
+        user_id -> Text,
+        token_hash -> Text,
+        resources -> Text,
+        store_ids -> Text,
+        created_datetime -> Timestamp,
+        expiry_datetime -> Nullable<Timestamp>,
+        last_used_datetime -> Nullable<Timestamp>,
+    }
+}
+
+/// Long lived token for integrations, acting on behalf of `user_id` but restricted to a set of
+/// resources and stores. Local to the site, not synced.
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = api_token)]
+pub struct ApiTokenRow {
+    pub id: String,
+    pub name: String,
+    pub user_id: String,
+    /// SHA-256 hash of the token, the token itself is only shown when it's created
+    pub token_hash: String,
+    /// JSON array of the auth resources the token can access
+    pub resources: String,
+    /// JSON array of the store ids the token can access
+    pub store_ids: String,
+    pub created_datetime: NaiveDateTime,
+    pub expiry_datetime: Option<NaiveDateTime>,
+    pub last_used_datetime: Option<NaiveDateTime>,
+}
+
+pub struct ApiTokenRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> ApiTokenRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        ApiTokenRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &ApiTokenRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(api_token)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, token_id: &str) -> Result<Option<ApiTokenRow>, RepositoryError> {
+        let result = api_token
+            .filter(id.eq(token_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_one_by_token_hash(
+        &self,
+        hash: &str,
+    ) -> Result<Option<ApiTokenRow>, RepositoryError> {
+        let result = api_token
+            .filter(token_hash.eq(hash))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_all(&self) -> Result<Vec<ApiTokenRow>, RepositoryError> {
+        let result = api_token
+            .order(created_datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn update_last_used(
+        &self,
+        token_id: &str,
+        datetime: NaiveDateTime,
+    ) -> Result<(), RepositoryError> {
+        diesel::update(api_token.filter(id.eq(token_id)))
+            .set(last_used_datetime.eq(Some(datetime)))
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn delete(&self, token_id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(api_token.filter(id.eq(token_id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
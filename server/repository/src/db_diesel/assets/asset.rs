@@ -51,6 +51,7 @@ pub struct AssetFilter {
     pub replacement_date: Option<DateFilter>,
     pub is_non_catalogue: Option<bool>,
     pub store: Option<StringFilter>,
+    pub store_id: Option<EqualFilter<String>>,
     pub functional_status: Option<EqualFilter<AssetLogStatus>>,
 }
 
@@ -118,6 +119,16 @@ impl AssetFilter {
         self.store = Some(filter);
         self
     }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn functional_status(mut self, filter: EqualFilter<AssetLogStatus>) -> Self {
+        self.functional_status = Some(filter);
+        self
+    }
 }
 
 pub struct AssetRepository<'a> {
@@ -229,6 +240,7 @@ fn create_filtered_query(filter: Option<AssetFilter>) -> BoxedAssetQuery {
             replacement_date,
             is_non_catalogue,
             store,
+            store_id,
             functional_status,
         } = f;
 
@@ -244,6 +256,7 @@ fn create_filtered_query(filter: Option<AssetFilter>) -> BoxedAssetQuery {
         apply_equal_filter!(query, category_id, asset_dsl::asset_category_id);
         apply_equal_filter!(query, class_id, asset_dsl::asset_class_id);
         apply_equal_filter!(query, type_id, asset_dsl::asset_catalogue_type_id);
+        apply_equal_filter!(query, store_id, asset_dsl::store_id);
 
         if let Some(value) = is_non_catalogue {
             apply_equal_filter!(
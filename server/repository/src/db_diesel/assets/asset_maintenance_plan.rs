@@ -0,0 +1,159 @@
+use super::asset_maintenance_plan_row::{
+    asset_maintenance_plan::{self, dsl as asset_maintenance_plan_dsl},
+    AssetMaintenancePlanRow, AssetMaintenanceScheduleType,
+};
+
+use diesel::{dsl::IntoBoxed, prelude::*};
+use util::inline_init;
+
+use crate::{
+    diesel_macros::{apply_equal_filter, apply_sort_no_case, apply_string_filter},
+    repository_error::RepositoryError,
+    DBType, EqualFilter, Pagination, Sort, StorageConnection, StringFilter,
+};
+
+pub type AssetMaintenancePlan = AssetMaintenancePlanRow;
+
+pub enum AssetMaintenancePlanSortField {
+    Name,
+}
+
+pub type AssetMaintenancePlanSort = Sort<AssetMaintenancePlanSortField>;
+
+#[derive(Clone, Default)]
+pub struct AssetMaintenancePlanFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub asset_type_id: Option<EqualFilter<String>>,
+    pub name: Option<StringFilter>,
+    pub schedule_type: Option<EqualFilter<AssetMaintenanceScheduleType>>,
+    pub is_active: Option<bool>,
+}
+
+impl AssetMaintenancePlanFilter {
+    pub fn new() -> AssetMaintenancePlanFilter {
+        Self::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn asset_type_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.asset_type_id = Some(filter);
+        self
+    }
+
+    pub fn name(mut self, filter: StringFilter) -> Self {
+        self.name = Some(filter);
+        self
+    }
+
+    pub fn schedule_type(mut self, filter: EqualFilter<AssetMaintenanceScheduleType>) -> Self {
+        self.schedule_type = Some(filter);
+        self
+    }
+
+    pub fn is_active(mut self, filter: bool) -> Self {
+        self.is_active = Some(filter);
+        self
+    }
+}
+
+pub struct AssetMaintenancePlanRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> AssetMaintenancePlanRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        AssetMaintenancePlanRepository { connection }
+    }
+
+    pub fn count(
+        &self,
+        filter: Option<AssetMaintenancePlanFilter>,
+    ) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+        Ok(query
+            .count()
+            .get_result(self.connection.lock().connection())?)
+    }
+
+    pub fn query_one(
+        &self,
+        filter: AssetMaintenancePlanFilter,
+    ) -> Result<Option<AssetMaintenancePlan>, RepositoryError> {
+        Ok(self.query_by_filter(filter)?.pop())
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: AssetMaintenancePlanFilter,
+    ) -> Result<Vec<AssetMaintenancePlan>, RepositoryError> {
+        self.query(Pagination::all(), Some(filter), None)
+    }
+
+    pub fn query(
+        &self,
+        pagination: Pagination,
+        filter: Option<AssetMaintenancePlanFilter>,
+        sort: Option<AssetMaintenancePlanSort>,
+    ) -> Result<Vec<AssetMaintenancePlan>, RepositoryError> {
+        let mut query = create_filtered_query(filter);
+
+        if let Some(sort) = sort {
+            match sort.key {
+                AssetMaintenancePlanSortField::Name => {
+                    apply_sort_no_case!(query, sort, asset_maintenance_plan_dsl::name);
+                }
+            }
+        } else {
+            query = query.order(asset_maintenance_plan_dsl::id.asc())
+        }
+
+        let result = query
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64)
+            .load::<AssetMaintenancePlanRow>(self.connection.lock().connection())?;
+
+        Ok(result)
+    }
+}
+
+type BoxedAssetMaintenancePlanQuery = IntoBoxed<'static, asset_maintenance_plan::table, DBType>;
+
+fn create_filtered_query(
+    filter: Option<AssetMaintenancePlanFilter>,
+) -> BoxedAssetMaintenancePlanQuery {
+    let mut query = asset_maintenance_plan_dsl::asset_maintenance_plan.into_boxed();
+
+    if let Some(f) = filter {
+        let AssetMaintenancePlanFilter {
+            id,
+            asset_type_id,
+            name,
+            schedule_type,
+            is_active,
+        } = f;
+
+        apply_equal_filter!(query, id, asset_maintenance_plan_dsl::id);
+        apply_equal_filter!(
+            query,
+            asset_type_id,
+            asset_maintenance_plan_dsl::asset_catalogue_type_id
+        );
+        apply_string_filter!(query, name, asset_maintenance_plan_dsl::name);
+        apply_equal_filter!(query, schedule_type, asset_maintenance_plan_dsl::schedule_type);
+
+        if let Some(is_active) = is_active {
+            query = query.filter(asset_maintenance_plan_dsl::is_active.eq(is_active));
+        }
+    }
+    query
+}
+
+impl AssetMaintenanceScheduleType {
+    pub fn equal_to(&self) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_to = Some(self.clone()))
+    }
+}
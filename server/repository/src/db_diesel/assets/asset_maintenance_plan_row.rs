@@ -0,0 +1,120 @@
+use super::asset_maintenance_plan_row::asset_maintenance_plan::dsl::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    StorageConnection, Upsert,
+};
+
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+
+table! {
+    asset_maintenance_plan (id) {
+        id -> Text,
+        asset_catalogue_type_id -> Text,
+        name -> Text,
+        description -> Nullable<Text>,
+        schedule_type -> crate::db_diesel::assets::asset_maintenance_plan_row::AssetMaintenanceScheduleTypeMapping,
+        interval_days -> Nullable<Integer>,
+        usage_interval -> Nullable<Double>,
+        usage_unit -> Nullable<Text>,
+        checklist -> Nullable<Text>,
+        is_active -> Bool,
+    }
+}
+
+#[derive(DbEnum, Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum AssetMaintenanceScheduleType {
+    /// Due every `interval_days` since the last completed work order
+    #[default]
+    Interval,
+    /// Due every `usage_interval` (e.g. running hours) since the last completed work order
+    Usage,
+}
+
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = asset_maintenance_plan)]
+pub struct AssetMaintenancePlanRow {
+    pub id: String,
+    #[diesel(column_name = "asset_catalogue_type_id")]
+    pub asset_type_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub schedule_type: AssetMaintenanceScheduleType,
+    pub interval_days: Option<i32>,
+    pub usage_interval: Option<f64>,
+    pub usage_unit: Option<String>,
+    /// JSON array of checklist task descriptions, copied to each generated work order
+    pub checklist: Option<String>,
+    pub is_active: bool,
+}
+
+pub struct AssetMaintenancePlanRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> AssetMaintenancePlanRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        AssetMaintenancePlanRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &AssetMaintenancePlanRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(asset_maintenance_plan)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row.id.to_owned(), RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        record_id: String,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::AssetMaintenancePlan,
+            record_id,
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        plan_id: &str,
+    ) -> Result<Option<AssetMaintenancePlanRow>, RepositoryError> {
+        let result = asset_maintenance_plan
+            .filter(id.eq(plan_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+}
+
+impl Upsert for AssetMaintenancePlanRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = AssetMaintenancePlanRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            AssetMaintenancePlanRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
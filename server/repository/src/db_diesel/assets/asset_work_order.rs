@@ -0,0 +1,174 @@
+use super::asset_work_order_row::{
+    asset_work_order::{self, dsl as asset_work_order_dsl},
+    AssetWorkOrderRow, AssetWorkOrderStatus,
+};
+
+use diesel::{dsl::IntoBoxed, prelude::*};
+use util::inline_init;
+
+use crate::{
+    diesel_macros::{apply_date_filter, apply_equal_filter, apply_sort},
+    repository_error::RepositoryError,
+    DBType, DateFilter, EqualFilter, Pagination, Sort, StorageConnection,
+};
+
+pub type AssetWorkOrder = AssetWorkOrderRow;
+
+pub enum AssetWorkOrderSortField {
+    DueDate,
+    Status,
+    CreatedDatetime,
+}
+
+pub type AssetWorkOrderSort = Sort<AssetWorkOrderSortField>;
+
+#[derive(Clone, Default)]
+pub struct AssetWorkOrderFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub asset_id: Option<EqualFilter<String>>,
+    pub maintenance_plan_id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub status: Option<EqualFilter<AssetWorkOrderStatus>>,
+    pub due_date: Option<DateFilter>,
+}
+
+impl AssetWorkOrderFilter {
+    pub fn new() -> AssetWorkOrderFilter {
+        Self::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn asset_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.asset_id = Some(filter);
+        self
+    }
+
+    pub fn maintenance_plan_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.maintenance_plan_id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn status(mut self, filter: EqualFilter<AssetWorkOrderStatus>) -> Self {
+        self.status = Some(filter);
+        self
+    }
+
+    pub fn due_date(mut self, filter: DateFilter) -> Self {
+        self.due_date = Some(filter);
+        self
+    }
+}
+
+pub struct AssetWorkOrderRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> AssetWorkOrderRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        AssetWorkOrderRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<AssetWorkOrderFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+        Ok(query
+            .count()
+            .get_result(self.connection.lock().connection())?)
+    }
+
+    pub fn query_one(
+        &self,
+        filter: AssetWorkOrderFilter,
+    ) -> Result<Option<AssetWorkOrder>, RepositoryError> {
+        Ok(self.query_by_filter(filter)?.pop())
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: AssetWorkOrderFilter,
+    ) -> Result<Vec<AssetWorkOrder>, RepositoryError> {
+        self.query(Pagination::all(), Some(filter), None)
+    }
+
+    pub fn query(
+        &self,
+        pagination: Pagination,
+        filter: Option<AssetWorkOrderFilter>,
+        sort: Option<AssetWorkOrderSort>,
+    ) -> Result<Vec<AssetWorkOrder>, RepositoryError> {
+        let mut query = create_filtered_query(filter);
+
+        if let Some(sort) = sort {
+            match sort.key {
+                AssetWorkOrderSortField::DueDate => {
+                    apply_sort!(query, sort, asset_work_order_dsl::due_date);
+                }
+                AssetWorkOrderSortField::Status => {
+                    apply_sort!(query, sort, asset_work_order_dsl::status);
+                }
+                AssetWorkOrderSortField::CreatedDatetime => {
+                    apply_sort!(query, sort, asset_work_order_dsl::created_datetime);
+                }
+            }
+        } else {
+            query = query.order((
+                asset_work_order_dsl::due_date.asc(),
+                asset_work_order_dsl::id.asc(),
+            ))
+        }
+
+        let result = query
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64)
+            .load::<AssetWorkOrderRow>(self.connection.lock().connection())?;
+
+        Ok(result)
+    }
+}
+
+type BoxedAssetWorkOrderQuery = IntoBoxed<'static, asset_work_order::table, DBType>;
+
+fn create_filtered_query(filter: Option<AssetWorkOrderFilter>) -> BoxedAssetWorkOrderQuery {
+    let mut query = asset_work_order_dsl::asset_work_order.into_boxed();
+
+    if let Some(f) = filter {
+        let AssetWorkOrderFilter {
+            id,
+            asset_id,
+            maintenance_plan_id,
+            store_id,
+            status,
+            due_date,
+        } = f;
+
+        apply_equal_filter!(query, id, asset_work_order_dsl::id);
+        apply_equal_filter!(query, asset_id, asset_work_order_dsl::asset_id);
+        apply_equal_filter!(
+            query,
+            maintenance_plan_id,
+            asset_work_order_dsl::maintenance_plan_id
+        );
+        apply_equal_filter!(query, store_id, asset_work_order_dsl::store_id);
+        apply_equal_filter!(query, status, asset_work_order_dsl::status);
+        apply_date_filter!(query, due_date, asset_work_order_dsl::due_date);
+    }
+    query
+}
+
+impl AssetWorkOrderStatus {
+    pub fn equal_to(&self) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_to = Some(self.clone()))
+    }
+
+    pub fn equal_any(value: Vec<Self>) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_any = Some(value))
+    }
+}
@@ -0,0 +1,130 @@
+use super::asset_work_order_row::asset_work_order::dsl::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    StorageConnection, Upsert,
+};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+
+table! {
+    asset_work_order (id) {
+        id -> Text,
+        asset_id -> Text,
+        maintenance_plan_id -> Nullable<Text>,
+        store_id -> Nullable<Text>,
+        status -> crate::db_diesel::assets::asset_work_order_row::AssetWorkOrderStatusMapping,
+        due_date -> Date,
+        due_usage -> Nullable<Double>,
+        usage_reading -> Nullable<Double>,
+        checklist -> Nullable<Text>,
+        comment -> Nullable<Text>,
+        created_datetime -> Timestamp,
+        completed_datetime -> Nullable<Timestamp>,
+        completed_by_id -> Nullable<Text>,
+        asset_log_id -> Nullable<Text>,
+    }
+}
+
+#[derive(DbEnum, Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum AssetWorkOrderStatus {
+    #[default]
+    Planned,
+    InProgress,
+    Completed,
+    Cancelled,
+}
+
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = asset_work_order)]
+pub struct AssetWorkOrderRow {
+    pub id: String,
+    pub asset_id: String,
+    pub maintenance_plan_id: Option<String>,
+    pub store_id: Option<String>,
+    pub status: AssetWorkOrderStatus,
+    pub due_date: NaiveDate,
+    /// For usage based plans, the usage reading the work order was raised for
+    pub due_usage: Option<f64>,
+    /// Usage reading recorded when the work order was completed
+    pub usage_reading: Option<f64>,
+    /// JSON array of checklist tasks and whether they have been done
+    pub checklist: Option<String>,
+    pub comment: Option<String>,
+    pub created_datetime: NaiveDateTime,
+    pub completed_datetime: Option<NaiveDateTime>,
+    pub completed_by_id: Option<String>,
+    pub asset_log_id: Option<String>,
+}
+
+pub struct AssetWorkOrderRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> AssetWorkOrderRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        AssetWorkOrderRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &AssetWorkOrderRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(asset_work_order)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &AssetWorkOrderRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::AssetWorkOrder,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: row.store_id.clone(),
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        work_order_id: &str,
+    ) -> Result<Option<AssetWorkOrderRow>, RepositoryError> {
+        let result = asset_work_order
+            .filter(id.eq(work_order_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+}
+
+impl Upsert for AssetWorkOrderRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = AssetWorkOrderRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            AssetWorkOrderRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
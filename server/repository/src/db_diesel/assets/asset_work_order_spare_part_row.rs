@@ -0,0 +1,105 @@
+use super::asset_work_order_spare_part_row::asset_work_order_spare_part::dsl::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::asset_work_order_row::AssetWorkOrderRowRepository;
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    StorageConnection, Upsert,
+};
+
+use diesel::prelude::*;
+
+table! {
+    asset_work_order_spare_part (id) {
+        id -> Text,
+        work_order_id -> Text,
+        item_link_id -> Text,
+        stock_line_id -> Text,
+        number_of_packs -> Double,
+        invoice_id -> Text,
+    }
+}
+
+/// Stock consumed by a work order, `invoice_id` is the inventory reduction it was issued with
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = asset_work_order_spare_part)]
+pub struct AssetWorkOrderSparePartRow {
+    pub id: String,
+    pub work_order_id: String,
+    pub item_link_id: String,
+    pub stock_line_id: String,
+    pub number_of_packs: f64,
+    pub invoice_id: String,
+}
+
+pub struct AssetWorkOrderSparePartRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> AssetWorkOrderSparePartRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        AssetWorkOrderSparePartRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &AssetWorkOrderSparePartRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(asset_work_order_spare_part)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        // Spare parts sync with the store of their work order
+        let store_id = AssetWorkOrderRowRepository::new(self.connection)
+            .find_one_by_id(&row.work_order_id)?
+            .and_then(|work_order| work_order.store_id);
+        let changelog = ChangeLogInsertRow {
+            table_name: ChangelogTableName::AssetWorkOrderSparePart,
+            record_id: row.id.clone(),
+            row_action: RowActionType::Upsert,
+            store_id,
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&changelog)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        spare_part_id: &str,
+    ) -> Result<Option<AssetWorkOrderSparePartRow>, RepositoryError> {
+        let result = asset_work_order_spare_part
+            .filter(id.eq(spare_part_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_work_order_id(
+        &self,
+        work_order: &str,
+    ) -> Result<Vec<AssetWorkOrderSparePartRow>, RepositoryError> {
+        let result = asset_work_order_spare_part
+            .filter(work_order_id.eq(work_order))
+            .order(id.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for AssetWorkOrderSparePartRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = AssetWorkOrderSparePartRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            AssetWorkOrderSparePartRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -11,9 +11,14 @@ pub mod asset_log;
 pub mod asset_log_reason;
 pub mod asset_log_reason_row;
 pub mod asset_log_row;
+pub mod asset_maintenance_plan;
+pub mod asset_maintenance_plan_row;
 pub mod asset_property;
 pub mod asset_property_row;
 pub mod asset_row;
 pub mod asset_type;
 pub mod asset_type_row;
+pub mod asset_work_order;
+pub mod asset_work_order_row;
+pub mod asset_work_order_spare_part_row;
 pub mod types;
@@ -0,0 +1,293 @@
+use super::audit_trail_row::audit_trail::dsl::*;
+
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use util::uuid::uuid;
+
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    StorageConnection, Upsert,
+};
+
+table! {
+    audit_trail (id) {
+        id -> Text,
+        record_table_name -> crate::db_diesel::changelog::ChangelogTableNameMapping,
+        record_id -> Text,
+        action -> crate::db_diesel::audit_trail_row::AuditActionMapping,
+        changes -> Text,
+        user_id -> Nullable<Text>,
+        store_id -> Nullable<Text>,
+        datetime -> Timestamp,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum AuditAction {
+    #[default]
+    Insert,
+    Update,
+    Delete,
+}
+
+/// Field of an audited record changed from `before` to `after`, values are the JSON
+/// serialisation of the field (null when the field wasn't set or the record didn't exist)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = audit_trail)]
+pub struct AuditTrailRow {
+    pub id: String,
+    pub record_table_name: ChangelogTableName,
+    pub record_id: String,
+    pub action: AuditAction,
+    /// JSON array of `FieldChange`
+    pub changes: String,
+    pub user_id: Option<String>,
+    pub store_id: Option<String>,
+    pub datetime: NaiveDateTime,
+}
+
+impl AuditTrailRow {
+    pub fn field_changes(&self) -> Result<Vec<FieldChange>, serde_json::Error> {
+        serde_json::from_str(&self.changes)
+    }
+}
+
+pub struct AuditTrailRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> AuditTrailRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        AuditTrailRowRepository { connection }
+    }
+
+    pub fn insert_one(&self, row: &AuditTrailRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(audit_trail)
+            .values(row)
+            .execute(self.connection.lock().connection())?;
+
+        let changelog = ChangeLogInsertRow {
+            table_name: ChangelogTableName::AuditTrail,
+            record_id: row.id.clone(),
+            row_action: RowActionType::Upsert,
+            store_id: row.store_id.clone(),
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&changelog)
+    }
+
+    pub fn find_one_by_id(&self, audit_id: &str) -> Result<Option<AuditTrailRow>, RepositoryError> {
+        let result = audit_trail
+            .filter(id.eq(audit_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Changes of a record, oldest first
+    pub fn find_many_by_record(
+        &self,
+        table_name: ChangelogTableName,
+        audited_record_id: &str,
+    ) -> Result<Vec<AuditTrailRow>, RepositoryError> {
+        let result = audit_trail
+            .filter(record_table_name.eq(table_name))
+            .filter(record_id.eq(audited_record_id))
+            .order(datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    /// Changes to records of the store in the period, oldest first
+    pub fn find_many_by_store(
+        &self,
+        audited_store_id: &str,
+        from_datetime: NaiveDateTime,
+        to_datetime: NaiveDateTime,
+    ) -> Result<Vec<AuditTrailRow>, RepositoryError> {
+        let result = audit_trail
+            .filter(store_id.eq(audited_store_id))
+            .filter(datetime.ge(from_datetime))
+            .filter(datetime.le(to_datetime))
+            .order(datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for AuditTrailRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        // Audit trail records are never changed, records from remote sites are only inserted once
+        if AuditTrailRowRepository::new(con)
+            .find_one_by_id(&self.id)?
+            .is_some()
+        {
+            return Ok(None);
+        }
+        let change_log_id = AuditTrailRowRepository::new(con).insert_one(self)?;
+        Ok(Some(change_log_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            AuditTrailRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
+
+/// Records the fields changed by a repository write in the audit trail. The record is loaded
+/// before the write and compared with the written record, nothing is loaded or recorded when the
+/// connection doesn't have an audit user (see `StorageConnection::set_audit_user`).
+pub(crate) struct AuditedChange<T> {
+    table_name: ChangelogTableName,
+    record_id: String,
+    /// None when the change isn't audited
+    before: Option<Option<T>>,
+}
+
+impl<T: Serialize> AuditedChange<T> {
+    pub(crate) fn load<F>(
+        connection: &StorageConnection,
+        table_name: ChangelogTableName,
+        audited_record_id: &str,
+        find_one: F,
+    ) -> Result<Self, RepositoryError>
+    where
+        F: FnOnce() -> Result<Option<T>, RepositoryError>,
+    {
+        let before = match connection.audit_user() {
+            Some(_) => Some(find_one()?),
+            None => None,
+        };
+        Ok(AuditedChange {
+            table_name,
+            record_id: audited_record_id.to_string(),
+            before,
+        })
+    }
+
+    /// `after` is None when the record was deleted, `find_store_id` is only called when the
+    /// change is audited
+    pub(crate) fn record<F>(
+        self,
+        connection: &StorageConnection,
+        after: Option<&T>,
+        find_store_id: F,
+    ) -> Result<(), RepositoryError>
+    where
+        F: FnOnce() -> Result<Option<String>, RepositoryError>,
+    {
+        let (Some(before), Some(audit_user)) = (self.before, connection.audit_user()) else {
+            return Ok(());
+        };
+
+        let changed_action = match (&before, after) {
+            (None, Some(_)) => AuditAction::Insert,
+            (Some(_), Some(_)) => AuditAction::Update,
+            (Some(_), None) => AuditAction::Delete,
+            (None, None) => return Ok(()),
+        };
+        let changed_fields = field_changes(to_json_map(before.as_ref())?, to_json_map(after)?);
+        if changed_fields.is_empty() {
+            return Ok(());
+        }
+
+        AuditTrailRowRepository::new(connection).insert_one(&AuditTrailRow {
+            id: uuid(),
+            record_table_name: self.table_name,
+            record_id: self.record_id,
+            action: changed_action,
+            changes: serde_json::to_string(&changed_fields)
+                .map_err(|error| RepositoryError::as_db_error(&error.to_string(), ""))?,
+            user_id: audit_user.user_id,
+            store_id: find_store_id()?,
+            datetime: Utc::now().naive_utc(),
+        })?;
+        Ok(())
+    }
+}
+
+fn to_json_map<T: Serialize>(row: Option<&T>) -> Result<Map<String, Value>, RepositoryError> {
+    let Some(row) = row else {
+        return Ok(Map::new());
+    };
+    match serde_json::to_value(row) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Ok(Map::new()),
+        Err(error) => Err(RepositoryError::as_db_error(&error.to_string(), "")),
+    }
+}
+
+fn field_changes(before: Map<String, Value>, mut after: Map<String, Value>) -> Vec<FieldChange> {
+    let mut result = Vec::new();
+    for (field, before) in before {
+        let after = after.remove(&field).unwrap_or(Value::Null);
+        if before != after {
+            result.push(FieldChange {
+                field,
+                before,
+                after,
+            });
+        }
+    }
+    for (field, after) in after {
+        if after != Value::Null {
+            result.push(FieldChange {
+                field,
+                before: Value::Null,
+                after,
+            });
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_field_changes() {
+        let before = json!({"id": "a", "note": "old", "packs": 1.0, "batch": null});
+        let after = json!({"id": "a", "note": "new", "packs": 1.0, "batch": "B1"});
+        let (Value::Object(before), Value::Object(after)) = (before, after) else {
+            unreachable!()
+        };
+
+        let mut changes = field_changes(before, after);
+        changes.sort_by(|a, b| a.field.cmp(&b.field));
+        assert_eq!(
+            changes,
+            vec![
+                FieldChange {
+                    field: "batch".to_string(),
+                    before: Value::Null,
+                    after: json!("B1"),
+                },
+                FieldChange {
+                    field: "note".to_string(),
+                    before: json!("old"),
+                    after: json!("new"),
+                },
+            ]
+        );
+    }
+}
@@ -114,6 +114,25 @@ pub enum ChangelogTableName {
     Item,
     ContactForm,
     SystemLog,
+    InsuranceProvider,
+    InsuranceCoverageRule,
+    NameInsuranceJoin,
+    InsuranceClaimLine,
+    AssetMaintenancePlan,
+    AssetWorkOrder,
+    AssetWorkOrderSparePart,
+    VaccineVialOpening,
+    PluginVersion,
+    PluginCertificateRevocation,
+    PluginData,
+    Recall,
+    GoodsReceivedLine,
+    Role,
+    RolePermission,
+    UserRole,
+    AuditTrail,
+    PeriodLock,
+    RedistributionRequest,
 }
 
 pub(crate) enum ChangeLogSyncStyle {
@@ -122,6 +141,7 @@ pub(crate) enum ChangeLogSyncStyle {
     Remote,
     File,
     RemoteToCentral, // These records won't sync back to the remote site on re-initalisation
+    Transfer, // Remote records that are also synced to the site of the other party store (changelog name_link_id)
 }
 // When adding a new change log record type, specify how it should be synced
 // If new requirements are needed a different ChangeLogSyncStyle can be added
@@ -182,6 +202,25 @@ impl ChangelogTableName {
             ChangelogTableName::BundledItem => ChangeLogSyncStyle::Central,
             ChangelogTableName::ContactForm => ChangeLogSyncStyle::Remote,
             ChangelogTableName::SystemLog => ChangeLogSyncStyle::RemoteToCentral, // System Log records won't be synced to remote site on initialisation
+            ChangelogTableName::InsuranceProvider => ChangeLogSyncStyle::Central,
+            ChangelogTableName::InsuranceCoverageRule => ChangeLogSyncStyle::Central,
+            ChangelogTableName::NameInsuranceJoin => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::InsuranceClaimLine => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::AssetMaintenancePlan => ChangeLogSyncStyle::Central,
+            ChangelogTableName::AssetWorkOrder => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::AssetWorkOrderSparePart => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::VaccineVialOpening => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::PluginVersion => ChangeLogSyncStyle::Central,
+            ChangelogTableName::PluginCertificateRevocation => ChangeLogSyncStyle::Central,
+            ChangelogTableName::PluginData => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::Recall => ChangeLogSyncStyle::Central,
+            ChangelogTableName::GoodsReceivedLine => ChangeLogSyncStyle::Transfer,
+            ChangelogTableName::Role => ChangeLogSyncStyle::Central,
+            ChangelogTableName::RolePermission => ChangeLogSyncStyle::Central,
+            ChangelogTableName::UserRole => ChangeLogSyncStyle::Central,
+            ChangelogTableName::AuditTrail => ChangeLogSyncStyle::RemoteToCentral,
+            ChangelogTableName::PeriodLock => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::RedistributionRequest => ChangeLogSyncStyle::Remote,
         }
     }
 }
@@ -494,9 +533,21 @@ fn create_filtered_outgoing_sync_query(
         })
         .collect();
 
-    let active_stores_for_site = store::table
+    // Transfer Records
+    let transfer_sync_table_names: Vec<ChangelogTableName> = ChangelogTableName::iter()
+        .filter(|table| matches!(table.sync_style(), ChangeLogSyncStyle::Transfer))
+        .collect();
+
+    let active_stores_for_site = || {
+        store::table
+            .filter(store::site_id.eq(sync_site_id))
+            .select(store::id.nullable())
+            .into_boxed()
+    };
+
+    let active_store_names_for_site = store::table
         .filter(store::site_id.eq(sync_site_id))
-        .select(store::id.nullable())
+        .select(store::name_link_id.nullable())
         .into_boxed();
 
     // Filter the query for the matching records for each type
@@ -506,7 +557,14 @@ fn create_filtered_outgoing_sync_query(
             .or(changelog_deduped::table_name.eq(ChangelogTableName::SyncFileReference)) // All sites get all sync file references (not necessarily files)
             .or(changelog_deduped::table_name
                 .eq_any(remote_sync_table_names)
-                .and(changelog_deduped::store_id.eq_any(active_stores_for_site))),
+                .and(changelog_deduped::store_id.eq_any(active_stores_for_site())))
+            .or(changelog_deduped::table_name
+                .eq_any(transfer_sync_table_names)
+                .and(
+                    changelog_deduped::store_id
+                        .eq_any(active_stores_for_site())
+                        .or(changelog_deduped::name_link_id.eq_any(active_store_names_for_site)),
+                )),
         // Any other special cases could be handled here...
     );
 
@@ -538,7 +596,7 @@ fn with_locked_changelog_table<T, F>(
 where
     F: FnOnce(&mut LockedConnection) -> Result<T, RepositoryError>,
 {
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         use diesel::connection::SimpleConnection;
         let result = connection.transaction_sync_etc(
             |con| {
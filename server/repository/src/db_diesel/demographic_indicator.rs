@@ -20,6 +20,7 @@ use crate::{EqualFilter, Sort};
 #[derive(Clone, Default)]
 pub struct DemographicIndicatorFilter {
     pub id: Option<EqualFilter<String>>,
+    pub demographic_id: Option<EqualFilter<String>>,
     pub name: Option<StringFilter>,
     pub base_year: Option<EqualFilter<i32>>,
 }
@@ -95,6 +96,11 @@ fn create_filtered_query(filter: Option<DemographicIndicatorFilter>) -> BoxedLog
 
     if let Some(filter) = filter {
         apply_equal_filter!(query, filter.id, demographic_indicator_dsl::id);
+        apply_equal_filter!(
+            query,
+            filter.demographic_id,
+            demographic_indicator_dsl::demographic_id
+        );
         apply_string_filter!(query, filter.name, demographic_indicator_dsl::name);
         apply_equal_filter!(
             query,
@@ -118,6 +124,10 @@ impl DemographicIndicatorFilter {
         self.id = Some(filter);
         self
     }
+    pub fn demographic_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.demographic_id = Some(filter);
+        self
+    }
     pub fn name(mut self, filter: StringFilter) -> Self {
         self.name = Some(filter);
         self
@@ -0,0 +1,200 @@
+use super::goods_received_line_row::goods_received_line::dsl::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, Delete, RepositoryError,
+    RowActionType, StorageConnection, Upsert,
+};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+
+table! {
+    goods_received_line (id) {
+        id -> Text,
+        store_id -> Text,
+        invoice_id -> Text,
+        invoice_line_id -> Text,
+        linked_invoice_id -> Nullable<Text>,
+        supplier_name_link_id -> Text,
+        item_link_id -> Text,
+        batch -> Nullable<Text>,
+        expected_number_of_packs -> Double,
+        received_number_of_packs -> Nullable<Double>,
+        discrepancy_reason -> Nullable<crate::db_diesel::goods_received_line_row::GoodsReceivedDiscrepancyReasonMapping>,
+        inspection_status -> Nullable<crate::db_diesel::goods_received_line_row::GoodsReceivedInspectionStatusMapping>,
+        comment -> Nullable<Text>,
+        received_datetime -> Nullable<Timestamp>,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum GoodsReceivedDiscrepancyReason {
+    /// Fewer packs were received than were sent
+    Short,
+    /// More packs were received than were sent
+    Excess,
+    /// Packs arrived damaged and were not received into stock
+    Damaged,
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum GoodsReceivedInspectionStatus {
+    /// Received stock is in quarantine (on hold) until it is inspected
+    Pending,
+    Passed,
+    Failed,
+}
+
+/// Receipt of an inbound shipment line, compares the number of packs that were expected (sent
+/// by the supplier) with the number of packs that were received
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = goods_received_line)]
+pub struct GoodsReceivedLineRow {
+    pub id: String,
+    /// Receiving store
+    pub store_id: String,
+    /// Inbound shipment
+    pub invoice_id: String,
+    pub invoice_line_id: String,
+    /// Outbound shipment of the supplier store, for transfers
+    pub linked_invoice_id: Option<String>,
+    pub supplier_name_link_id: String,
+    pub item_link_id: String,
+    pub batch: Option<String>,
+    pub expected_number_of_packs: f64,
+    /// None until the line is received
+    pub received_number_of_packs: Option<f64>,
+    pub discrepancy_reason: Option<GoodsReceivedDiscrepancyReason>,
+    pub inspection_status: Option<GoodsReceivedInspectionStatus>,
+    pub comment: Option<String>,
+    pub received_datetime: Option<NaiveDateTime>,
+}
+
+impl GoodsReceivedLineRow {
+    pub fn has_discrepancy(&self) -> bool {
+        self.received_number_of_packs
+            .is_some_and(|received| received != self.expected_number_of_packs)
+            || self.discrepancy_reason.is_some()
+    }
+}
+
+pub struct GoodsReceivedLineRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> GoodsReceivedLineRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        GoodsReceivedLineRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &GoodsReceivedLineRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(goods_received_line)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    // Changelog name is the supplier, so the receipt is also synced to the supplier store
+    fn insert_changelog(
+        &self,
+        row: &GoodsReceivedLineRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::GoodsReceivedLine,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: Some(row.supplier_name_link_id.clone()),
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn delete(&self, line_id: &str) -> Result<Option<i64>, RepositoryError> {
+        let Some(old_row) = self.find_one_by_id(line_id)? else {
+            return Ok(None);
+        };
+        let change_log_id = self.insert_changelog(&old_row, RowActionType::Delete)?;
+
+        diesel::delete(goods_received_line.filter(id.eq(line_id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(Some(change_log_id))
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        line_id: &str,
+    ) -> Result<Option<GoodsReceivedLineRow>, RepositoryError> {
+        let result = goods_received_line
+            .filter(id.eq(line_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_invoice_id(
+        &self,
+        inbound_invoice_id: &str,
+    ) -> Result<Vec<GoodsReceivedLineRow>, RepositoryError> {
+        let result = goods_received_line
+            .filter(invoice_id.eq(inbound_invoice_id))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    /// Receipts of the inbound shipment that was created from the outbound shipment
+    pub fn find_many_by_linked_invoice_id(
+        &self,
+        outbound_invoice_id: &str,
+    ) -> Result<Vec<GoodsReceivedLineRow>, RepositoryError> {
+        let result = goods_received_line
+            .filter(linked_invoice_id.eq(outbound_invoice_id))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GoodsReceivedLineRowDelete(pub String);
+impl Delete for GoodsReceivedLineRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        GoodsReceivedLineRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            GoodsReceivedLineRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for GoodsReceivedLineRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let change_log_id = GoodsReceivedLineRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(change_log_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            GoodsReceivedLineRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
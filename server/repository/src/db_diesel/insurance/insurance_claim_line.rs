@@ -0,0 +1,126 @@
+use super::insurance_claim_line_row::{
+    insurance_claim_line::{self, dsl as insurance_claim_line_dsl},
+    InsuranceClaimLineRow,
+};
+
+use diesel::{dsl::IntoBoxed, prelude::*};
+
+use crate::{
+    diesel_macros::{apply_date_time_filter, apply_equal_filter},
+    repository_error::RepositoryError,
+    DBType, DatetimeFilter, EqualFilter, StorageConnection,
+};
+
+pub type InsuranceClaimLine = InsuranceClaimLineRow;
+
+#[derive(Clone, Default)]
+pub struct InsuranceClaimLineFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub invoice_id: Option<EqualFilter<String>>,
+    pub insurance_provider_id: Option<EqualFilter<String>>,
+    pub name_insurance_join_id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub datetime: Option<DatetimeFilter>,
+}
+
+impl InsuranceClaimLineFilter {
+    pub fn new() -> InsuranceClaimLineFilter {
+        Self::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn invoice_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.invoice_id = Some(filter);
+        self
+    }
+
+    pub fn insurance_provider_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.insurance_provider_id = Some(filter);
+        self
+    }
+
+    pub fn name_insurance_join_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.name_insurance_join_id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn datetime(mut self, filter: DatetimeFilter) -> Self {
+        self.datetime = Some(filter);
+        self
+    }
+}
+
+pub struct InsuranceClaimLineRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> InsuranceClaimLineRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        InsuranceClaimLineRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<InsuranceClaimLineFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query
+            .count()
+            .get_result(self.connection.lock().connection())?)
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: InsuranceClaimLineFilter,
+    ) -> Result<Vec<InsuranceClaimLine>, RepositoryError> {
+        let result = create_filtered_query(Some(filter))
+            .order((
+                insurance_claim_line_dsl::datetime.asc(),
+                insurance_claim_line_dsl::invoice_id.asc(),
+            ))
+            .load::<InsuranceClaimLineRow>(self.connection.lock().connection())?;
+
+        Ok(result)
+    }
+}
+
+type BoxedInsuranceClaimLineQuery = IntoBoxed<'static, insurance_claim_line::table, DBType>;
+
+fn create_filtered_query(filter: Option<InsuranceClaimLineFilter>) -> BoxedInsuranceClaimLineQuery {
+    let mut query = insurance_claim_line_dsl::insurance_claim_line.into_boxed();
+
+    if let Some(f) = filter {
+        let InsuranceClaimLineFilter {
+            id,
+            invoice_id,
+            insurance_provider_id,
+            name_insurance_join_id,
+            store_id,
+            datetime,
+        } = f;
+
+        apply_equal_filter!(query, id, insurance_claim_line_dsl::id);
+        apply_equal_filter!(query, invoice_id, insurance_claim_line_dsl::invoice_id);
+        apply_equal_filter!(
+            query,
+            insurance_provider_id,
+            insurance_claim_line_dsl::insurance_provider_id
+        );
+        apply_equal_filter!(
+            query,
+            name_insurance_join_id,
+            insurance_claim_line_dsl::name_insurance_join_id
+        );
+        apply_equal_filter!(query, store_id, insurance_claim_line_dsl::store_id);
+        apply_date_time_filter!(query, datetime, insurance_claim_line_dsl::datetime);
+    }
+
+    query
+}
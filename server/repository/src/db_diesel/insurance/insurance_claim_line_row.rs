@@ -0,0 +1,154 @@
+use super::insurance_claim_line_row::insurance_claim_line::dsl::*;
+
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, Delete, RepositoryError,
+    RowActionType, StorageConnection, Upsert,
+};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    insurance_claim_line (id) {
+        id -> Text,
+        invoice_id -> Text,
+        invoice_line_id -> Text,
+        name_insurance_join_id -> Text,
+        insurance_provider_id -> Text,
+        store_id -> Text,
+        item_link_id -> Text,
+        total_amount -> Double,
+        insurer_amount -> Double,
+        patient_amount -> Double,
+        coverage_rule_id -> Nullable<Text>,
+        datetime -> Timestamp,
+    }
+}
+
+/// Split of a prescription line into the insurer and patient portions
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Serialize, Deserialize, Default,
+)]
+#[diesel(table_name = insurance_claim_line)]
+#[diesel(treat_none_as_null = true)]
+pub struct InsuranceClaimLineRow {
+    pub id: String,
+    pub invoice_id: String,
+    pub invoice_line_id: String,
+    pub name_insurance_join_id: String,
+    pub insurance_provider_id: String,
+    pub store_id: String,
+    pub item_link_id: String,
+    pub total_amount: f64,
+    pub insurer_amount: f64,
+    pub patient_amount: f64,
+    /// Rule that was applied to the line, None if the policy default was used
+    pub coverage_rule_id: Option<String>,
+    pub datetime: NaiveDateTime,
+}
+
+pub struct InsuranceClaimLineRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> InsuranceClaimLineRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        InsuranceClaimLineRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &InsuranceClaimLineRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(insurance_claim_line)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row.id.clone(), row.store_id.clone(), RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        record_id: String,
+        store: String,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::InsuranceClaimLine,
+            record_id,
+            row_action: action,
+            store_id: Some(store),
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        claim_line_id: &str,
+    ) -> Result<Option<InsuranceClaimLineRow>, RepositoryError> {
+        let result = insurance_claim_line
+            .filter(id.eq(claim_line_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_invoice_id(
+        &self,
+        claim_invoice_id: &str,
+    ) -> Result<Vec<InsuranceClaimLineRow>, RepositoryError> {
+        let result = insurance_claim_line
+            .filter(invoice_id.eq(claim_invoice_id))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, claim_line_id: &str) -> Result<Option<i64>, RepositoryError> {
+        let old_row = self.find_one_by_id(claim_line_id)?;
+        let change_log_id = match old_row {
+            Some(old_row) => {
+                self.insert_changelog(old_row.id, old_row.store_id, RowActionType::Delete)?
+            }
+            None => {
+                return Ok(None);
+            }
+        };
+
+        diesel::delete(insurance_claim_line.filter(id.eq(claim_line_id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(Some(change_log_id))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InsuranceClaimLineRowDelete(pub String);
+impl Delete for InsuranceClaimLineRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        InsuranceClaimLineRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            InsuranceClaimLineRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for InsuranceClaimLineRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = InsuranceClaimLineRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            InsuranceClaimLineRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
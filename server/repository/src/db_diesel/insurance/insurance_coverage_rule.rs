@@ -0,0 +1,129 @@
+use super::insurance_coverage_rule_row::{
+    insurance_coverage_rule::{self, dsl as insurance_coverage_rule_dsl},
+    InsuranceCoverageRuleRow,
+};
+
+use diesel::{dsl::IntoBoxed, prelude::*};
+
+use crate::{
+    diesel_macros::apply_equal_filter, item_link, repository_error::RepositoryError, DBType,
+    EqualFilter, StorageConnection,
+};
+
+pub type InsuranceCoverageRule = InsuranceCoverageRuleRow;
+
+#[derive(Clone, Default)]
+pub struct InsuranceCoverageRuleFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub insurance_provider_id: Option<EqualFilter<String>>,
+    /// Matches rules for the item (through item link), use `is_default` to also get the provider default
+    pub item_id: Option<EqualFilter<String>>,
+    pub is_default: Option<bool>,
+}
+
+impl InsuranceCoverageRuleFilter {
+    pub fn new() -> InsuranceCoverageRuleFilter {
+        Self::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn insurance_provider_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.insurance_provider_id = Some(filter);
+        self
+    }
+
+    pub fn item_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.item_id = Some(filter);
+        self
+    }
+
+    pub fn is_default(mut self, filter: bool) -> Self {
+        self.is_default = Some(filter);
+        self
+    }
+}
+
+pub struct InsuranceCoverageRuleRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> InsuranceCoverageRuleRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        InsuranceCoverageRuleRepository { connection }
+    }
+
+    pub fn count(
+        &self,
+        filter: Option<InsuranceCoverageRuleFilter>,
+    ) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query
+            .count()
+            .get_result(self.connection.lock().connection())?)
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: InsuranceCoverageRuleFilter,
+    ) -> Result<Vec<InsuranceCoverageRule>, RepositoryError> {
+        let result = create_filtered_query(Some(filter))
+            .order(insurance_coverage_rule_dsl::id.asc())
+            .load::<InsuranceCoverageRuleRow>(self.connection.lock().connection())?;
+
+        Ok(result)
+    }
+}
+
+type BoxedInsuranceCoverageRuleQuery = IntoBoxed<'static, insurance_coverage_rule::table, DBType>;
+
+fn create_filtered_query(
+    filter: Option<InsuranceCoverageRuleFilter>,
+) -> BoxedInsuranceCoverageRuleQuery {
+    let mut query = insurance_coverage_rule_dsl::insurance_coverage_rule.into_boxed();
+
+    if let Some(f) = filter {
+        let InsuranceCoverageRuleFilter {
+            id,
+            insurance_provider_id,
+            item_id,
+            is_default,
+        } = f;
+
+        apply_equal_filter!(query, id, insurance_coverage_rule_dsl::id);
+        apply_equal_filter!(
+            query,
+            insurance_provider_id,
+            insurance_coverage_rule_dsl::insurance_provider_id
+        );
+
+        if let Some(item_id) = item_id {
+            let mut sub_query = item_link::table.into_boxed();
+            apply_equal_filter!(sub_query, Some(item_id), item_link::item_id);
+            let item_link_ids = sub_query.select(item_link::id.nullable());
+
+            query = match is_default {
+                Some(true) => query.filter(
+                    insurance_coverage_rule_dsl::item_link_id
+                        .eq_any(item_link_ids)
+                        .or(insurance_coverage_rule_dsl::item_link_id.is_null()),
+                ),
+                _ => query.filter(insurance_coverage_rule_dsl::item_link_id.eq_any(item_link_ids)),
+            };
+        } else if let Some(is_default) = is_default {
+            query = if is_default {
+                query.filter(insurance_coverage_rule_dsl::item_link_id.is_null())
+            } else {
+                query.filter(insurance_coverage_rule_dsl::item_link_id.is_not_null())
+            };
+        }
+    }
+
+    query = query.filter(insurance_coverage_rule_dsl::deleted_datetime.is_null());
+
+    query
+}
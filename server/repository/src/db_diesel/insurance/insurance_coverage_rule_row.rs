@@ -0,0 +1,114 @@
+use super::insurance_coverage_rule_row::insurance_coverage_rule::dsl::*;
+
+use crate::{
+    item_link, ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError,
+    RowActionType, StorageConnection, Upsert,
+};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    insurance_coverage_rule (id) {
+        id -> Text,
+        insurance_provider_id -> Text,
+        item_link_id -> Nullable<Text>,
+        coverage_percentage -> Nullable<Double>,
+        max_coverage_per_unit -> Nullable<Double>,
+        is_excluded -> Bool,
+        deleted_datetime -> Nullable<Timestamp>,
+    }
+}
+
+allow_tables_to_appear_in_same_query!(insurance_coverage_rule, item_link);
+
+/// Coverage rule for an insurance provider. A rule without an `item_link_id` is the
+/// provider wide default, rules with an item override the default for that item.
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Serialize, Deserialize, Default,
+)]
+#[diesel(table_name = insurance_coverage_rule)]
+#[diesel(treat_none_as_null = true)]
+pub struct InsuranceCoverageRuleRow {
+    pub id: String,
+    pub insurance_provider_id: String,
+    pub item_link_id: Option<String>,
+    /// Percentage of the line total paid by the insurer, falls back to the policy percentage when not set
+    pub coverage_percentage: Option<f64>,
+    /// Maximum amount the insurer will pay per unit of the item
+    pub max_coverage_per_unit: Option<f64>,
+    pub is_excluded: bool,
+    pub deleted_datetime: Option<chrono::NaiveDateTime>,
+}
+
+pub struct InsuranceCoverageRuleRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> InsuranceCoverageRuleRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        InsuranceCoverageRuleRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &InsuranceCoverageRuleRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(insurance_coverage_rule)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row.id.to_owned(), RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        record_id: String,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::InsuranceCoverageRule,
+            record_id,
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        rule_id: &str,
+    ) -> Result<Option<InsuranceCoverageRuleRow>, RepositoryError> {
+        let result = insurance_coverage_rule
+            .filter(id.eq(rule_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn mark_deleted(&self, rule_id: &str) -> Result<i64, RepositoryError> {
+        diesel::update(insurance_coverage_rule.filter(id.eq(rule_id)))
+            .set(deleted_datetime.eq(Some(chrono::Utc::now().naive_utc())))
+            .execute(self.connection.lock().connection())?;
+
+        // Upsert row action as this is a soft delete, not actual delete
+        self.insert_changelog(rule_id.to_owned(), RowActionType::Upsert)
+    }
+}
+
+impl Upsert for InsuranceCoverageRuleRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = InsuranceCoverageRuleRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            InsuranceCoverageRuleRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,128 @@
+use super::insurance_provider_row::{
+    insurance_provider::{self, dsl as insurance_provider_dsl},
+    InsuranceProviderRow,
+};
+
+use diesel::{dsl::IntoBoxed, prelude::*};
+
+use crate::{
+    diesel_macros::{apply_equal_filter, apply_sort_no_case, apply_string_filter},
+    repository_error::RepositoryError,
+    DBType, EqualFilter, Pagination, Sort, StorageConnection, StringFilter,
+};
+
+pub type InsuranceProvider = InsuranceProviderRow;
+
+pub enum InsuranceProviderSortField {
+    ProviderName,
+}
+
+pub type InsuranceProviderSort = Sort<InsuranceProviderSortField>;
+
+#[derive(Clone, Default)]
+pub struct InsuranceProviderFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub provider_name: Option<StringFilter>,
+    pub is_active: Option<bool>,
+}
+
+impl InsuranceProviderFilter {
+    pub fn new() -> InsuranceProviderFilter {
+        Self::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn provider_name(mut self, filter: StringFilter) -> Self {
+        self.provider_name = Some(filter);
+        self
+    }
+
+    pub fn is_active(mut self, filter: bool) -> Self {
+        self.is_active = Some(filter);
+        self
+    }
+}
+
+pub struct InsuranceProviderRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> InsuranceProviderRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        InsuranceProviderRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<InsuranceProviderFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query
+            .count()
+            .get_result(self.connection.lock().connection())?)
+    }
+
+    pub fn query_one(
+        &self,
+        filter: InsuranceProviderFilter,
+    ) -> Result<Option<InsuranceProvider>, RepositoryError> {
+        Ok(self.query_by_filter(filter)?.pop())
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: InsuranceProviderFilter,
+    ) -> Result<Vec<InsuranceProvider>, RepositoryError> {
+        self.query(Pagination::all(), Some(filter), None)
+    }
+
+    pub fn query(
+        &self,
+        pagination: Pagination,
+        filter: Option<InsuranceProviderFilter>,
+        sort: Option<InsuranceProviderSort>,
+    ) -> Result<Vec<InsuranceProvider>, RepositoryError> {
+        let mut query = create_filtered_query(filter);
+
+        if let Some(sort) = sort {
+            match sort.key {
+                InsuranceProviderSortField::ProviderName => {
+                    apply_sort_no_case!(query, sort, insurance_provider_dsl::provider_name);
+                }
+            }
+        } else {
+            query = query.order(insurance_provider_dsl::provider_name.asc())
+        }
+
+        let result = query
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64)
+            .load::<InsuranceProviderRow>(self.connection.lock().connection())?;
+
+        Ok(result)
+    }
+}
+
+type BoxedInsuranceProviderQuery = IntoBoxed<'static, insurance_provider::table, DBType>;
+
+fn create_filtered_query(filter: Option<InsuranceProviderFilter>) -> BoxedInsuranceProviderQuery {
+    let mut query = insurance_provider_dsl::insurance_provider.into_boxed();
+
+    if let Some(f) = filter {
+        let InsuranceProviderFilter {
+            id,
+            provider_name,
+            is_active,
+        } = f;
+
+        apply_equal_filter!(query, id, insurance_provider_dsl::id);
+        apply_string_filter!(query, provider_name, insurance_provider_dsl::provider_name);
+        if let Some(is_active) = is_active {
+            query = query.filter(insurance_provider_dsl::is_active.eq(is_active));
+        }
+    }
+
+    query
+}
@@ -0,0 +1,95 @@
+use super::insurance_provider_row::insurance_provider::dsl::*;
+
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    StorageConnection, Upsert,
+};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    insurance_provider (id) {
+        id -> Text,
+        provider_name -> Text,
+        is_active -> Bool,
+        comment -> Nullable<Text>,
+        prescription_validity_days -> Nullable<Integer>,
+    }
+}
+
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Eq, Serialize, Deserialize, Default,
+)]
+#[diesel(table_name = insurance_provider)]
+#[diesel(treat_none_as_null = true)]
+pub struct InsuranceProviderRow {
+    pub id: String,
+    pub provider_name: String,
+    pub is_active: bool,
+    pub comment: Option<String>,
+    pub prescription_validity_days: Option<i32>,
+}
+
+pub struct InsuranceProviderRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> InsuranceProviderRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        InsuranceProviderRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &InsuranceProviderRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(insurance_provider)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row.id.to_owned(), RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        record_id: String,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::InsuranceProvider,
+            record_id,
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        insurance_provider_id: &str,
+    ) -> Result<Option<InsuranceProviderRow>, RepositoryError> {
+        let result = insurance_provider
+            .filter(id.eq(insurance_provider_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+}
+
+impl Upsert for InsuranceProviderRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = InsuranceProviderRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            InsuranceProviderRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
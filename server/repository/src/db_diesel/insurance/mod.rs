@@ -0,0 +1,8 @@
+pub mod insurance_claim_line;
+pub mod insurance_claim_line_row;
+pub mod insurance_coverage_rule;
+pub mod insurance_coverage_rule_row;
+pub mod insurance_provider;
+pub mod insurance_provider_row;
+pub mod name_insurance_join;
+pub mod name_insurance_join_row;
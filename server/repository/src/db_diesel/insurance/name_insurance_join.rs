@@ -0,0 +1,175 @@
+use super::{
+    insurance_provider_row::{insurance_provider, InsuranceProviderRow},
+    name_insurance_join_row::{
+        name_insurance_join::{self, dsl as name_insurance_join_dsl},
+        NameInsuranceJoinRow,
+    },
+};
+
+use diesel::{
+    dsl::{InnerJoin, IntoBoxed},
+    prelude::*,
+};
+
+use crate::{
+    diesel_macros::{apply_date_filter, apply_equal_filter},
+    name_link,
+    repository_error::RepositoryError,
+    DBType, DateFilter, EqualFilter, Pagination, StorageConnection,
+};
+
+joinable!(name_insurance_join -> insurance_provider (insurance_provider_id));
+allow_tables_to_appear_in_same_query!(name_insurance_join, insurance_provider);
+allow_tables_to_appear_in_same_query!(insurance_provider, name_link);
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct NameInsuranceJoin {
+    pub name_insurance_join_row: NameInsuranceJoinRow,
+    pub insurance_provider_row: InsuranceProviderRow,
+}
+
+#[derive(Clone, Default)]
+pub struct NameInsuranceJoinFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub name_id: Option<EqualFilter<String>>,
+    pub insurance_provider_id: Option<EqualFilter<String>>,
+    pub policy_number: Option<EqualFilter<String>>,
+    pub expiry_date: Option<DateFilter>,
+    pub is_active: Option<bool>,
+}
+
+impl NameInsuranceJoinFilter {
+    pub fn new() -> NameInsuranceJoinFilter {
+        Self::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn name_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.name_id = Some(filter);
+        self
+    }
+
+    pub fn insurance_provider_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.insurance_provider_id = Some(filter);
+        self
+    }
+
+    pub fn policy_number(mut self, filter: EqualFilter<String>) -> Self {
+        self.policy_number = Some(filter);
+        self
+    }
+
+    pub fn expiry_date(mut self, filter: DateFilter) -> Self {
+        self.expiry_date = Some(filter);
+        self
+    }
+
+    pub fn is_active(mut self, filter: bool) -> Self {
+        self.is_active = Some(filter);
+        self
+    }
+}
+
+type NameInsuranceJoinJoin = (NameInsuranceJoinRow, InsuranceProviderRow);
+
+pub struct NameInsuranceJoinRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> NameInsuranceJoinRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        NameInsuranceJoinRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<NameInsuranceJoinFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query
+            .count()
+            .get_result(self.connection.lock().connection())?)
+    }
+
+    pub fn query_one(
+        &self,
+        filter: NameInsuranceJoinFilter,
+    ) -> Result<Option<NameInsuranceJoin>, RepositoryError> {
+        Ok(self.query_by_filter(filter)?.pop())
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: NameInsuranceJoinFilter,
+    ) -> Result<Vec<NameInsuranceJoin>, RepositoryError> {
+        self.query(Pagination::all(), Some(filter))
+    }
+
+    pub fn query(
+        &self,
+        pagination: Pagination,
+        filter: Option<NameInsuranceJoinFilter>,
+    ) -> Result<Vec<NameInsuranceJoin>, RepositoryError> {
+        let result = create_filtered_query(filter)
+            .order(name_insurance_join_dsl::expiry_date.desc())
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64)
+            .load::<NameInsuranceJoinJoin>(self.connection.lock().connection())?;
+
+        Ok(result.into_iter().map(to_domain).collect())
+    }
+}
+
+fn to_domain(
+    (name_insurance_join_row, insurance_provider_row): NameInsuranceJoinJoin,
+) -> NameInsuranceJoin {
+    NameInsuranceJoin {
+        name_insurance_join_row,
+        insurance_provider_row,
+    }
+}
+
+type BoxedNameInsuranceJoinQuery =
+    IntoBoxed<'static, InnerJoin<name_insurance_join::table, insurance_provider::table>, DBType>;
+
+fn create_filtered_query(filter: Option<NameInsuranceJoinFilter>) -> BoxedNameInsuranceJoinQuery {
+    let mut query = name_insurance_join_dsl::name_insurance_join
+        .inner_join(insurance_provider::table)
+        .into_boxed();
+
+    if let Some(f) = filter {
+        let NameInsuranceJoinFilter {
+            id,
+            name_id,
+            insurance_provider_id,
+            policy_number,
+            expiry_date,
+            is_active,
+        } = f;
+
+        apply_equal_filter!(query, id, name_insurance_join_dsl::id);
+        apply_equal_filter!(
+            query,
+            insurance_provider_id,
+            name_insurance_join_dsl::insurance_provider_id
+        );
+        apply_equal_filter!(query, policy_number, name_insurance_join_dsl::policy_number);
+        apply_date_filter!(query, expiry_date, name_insurance_join_dsl::expiry_date);
+
+        if let Some(name_id) = name_id {
+            let mut sub_query = name_link::table.into_boxed();
+            apply_equal_filter!(sub_query, Some(name_id), name_link::name_id);
+            query = query.filter(
+                name_insurance_join_dsl::name_link_id.eq_any(sub_query.select(name_link::id)),
+            );
+        }
+
+        if let Some(is_active) = is_active {
+            query = query.filter(name_insurance_join_dsl::is_active.eq(is_active));
+        }
+    }
+
+    query
+}
@@ -0,0 +1,121 @@
+use super::name_insurance_join_row::name_insurance_join::dsl::*;
+
+use crate::{
+    name_link, ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError,
+    RowActionType, StorageConnection, Upsert,
+};
+
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    name_insurance_join (id) {
+        id -> Text,
+        name_link_id -> Text,
+        insurance_provider_id -> Text,
+        policy_number -> Text,
+        policy_type -> crate::db_diesel::insurance::name_insurance_join_row::InsurancePolicyTypeMapping,
+        coverage_percentage -> Double,
+        expiry_date -> Date,
+        is_active -> Bool,
+        store_id -> Text,
+        entered_by_id -> Nullable<Text>,
+    }
+}
+
+allow_tables_to_appear_in_same_query!(name_insurance_join, name_link);
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum InsurancePolicyType {
+    #[default]
+    Personal,
+    Business,
+}
+
+/// A patient's insurance policy with a provider
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Serialize, Deserialize, Default,
+)]
+#[diesel(table_name = name_insurance_join)]
+#[diesel(treat_none_as_null = true)]
+pub struct NameInsuranceJoinRow {
+    pub id: String,
+    pub name_link_id: String,
+    pub insurance_provider_id: String,
+    pub policy_number: String,
+    pub policy_type: InsurancePolicyType,
+    /// Default percentage of a prescription paid by the insurer
+    pub coverage_percentage: f64,
+    pub expiry_date: NaiveDate,
+    pub is_active: bool,
+    /// Store the policy was entered in, used for sync
+    pub store_id: String,
+    pub entered_by_id: Option<String>,
+}
+
+pub struct NameInsuranceJoinRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> NameInsuranceJoinRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        NameInsuranceJoinRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &NameInsuranceJoinRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(name_insurance_join)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &NameInsuranceJoinRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::NameInsuranceJoin,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: Some(row.name_link_id.clone()),
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        name_insurance_join_id: &str,
+    ) -> Result<Option<NameInsuranceJoinRow>, RepositoryError> {
+        let result = name_insurance_join
+            .filter(id.eq(name_insurance_join_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+}
+
+impl Upsert for NameInsuranceJoinRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = NameInsuranceJoinRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            NameInsuranceJoinRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -7,8 +7,8 @@ use super::{
 
 use crate::repository_error::RepositoryError;
 use crate::{
-    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, InvoiceRowRepository,
-    RowActionType,
+    AuditedChange, ChangeLogInsertRow, ChangelogRepository, ChangelogTableName,
+    InvoiceRowRepository, RowActionType,
 };
 use crate::{Delete, Upsert};
 
@@ -16,6 +16,7 @@ use diesel::prelude::*;
 
 use chrono::NaiveDate;
 use diesel_derive_enum::DbEnum;
+use serde::Serialize;
 
 table! {
     invoice_line (id) {
@@ -41,6 +42,7 @@ table! {
         return_reason_id -> Nullable<Text>,
         foreign_currency_price_before_tax -> Nullable<Double>,
         item_variant_id -> Nullable<Text>,
+        donor_link_id -> Nullable<Text>,
     }
 }
 
@@ -53,7 +55,7 @@ joinable!(invoice_line -> return_reason (return_reason_id));
 allow_tables_to_appear_in_same_query!(invoice_line, item_link);
 allow_tables_to_appear_in_same_query!(invoice_line, name_link);
 
-#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Default)]
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Default, Serialize)]
 #[DbValueStyle = "SCREAMING_SNAKE_CASE"]
 pub enum InvoiceLineType {
     #[default]
@@ -63,7 +65,7 @@ pub enum InvoiceLineType {
     Service,
 }
 
-#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize)]
 #[diesel(treat_none_as_null = true)]
 #[diesel(table_name = invoice_line)]
 pub struct InvoiceLineRow {
@@ -92,6 +94,7 @@ pub struct InvoiceLineRow {
     pub return_reason_id: Option<String>,
     pub foreign_currency_price_before_tax: Option<f64>,
     pub item_variant_id: Option<String>,
+    pub donor_link_id: Option<String>,
 }
 
 pub struct InvoiceLineRowRepository<'a> {
@@ -104,15 +107,28 @@ impl<'a> InvoiceLineRowRepository<'a> {
     }
 
     pub fn upsert_one(&self, row: &InvoiceLineRow) -> Result<i64, RepositoryError> {
+        let audit = AuditedChange::load(
+            self.connection,
+            ChangelogTableName::InvoiceLine,
+            &row.id,
+            || self.find_one_by_id(&row.id),
+        )?;
         diesel::insert_into(invoice_line)
             .values(row)
             .on_conflict(id)
             .do_update()
             .set(row)
             .execute(self.connection.lock().connection())?;
+        audit.record(self.connection, Some(row), || self.store_id(row))?;
         self.insert_changelog(row, RowActionType::Upsert)
     }
 
+    fn store_id(&self, row: &InvoiceLineRow) -> Result<Option<String>, RepositoryError> {
+        Ok(InvoiceRowRepository::new(self.connection)
+            .find_one_by_id(&row.invoice_id)?
+            .map(|invoice| invoice.store_id))
+    }
+
     fn insert_changelog(
         &self,
         row: &InvoiceLineRow,
@@ -191,15 +207,25 @@ impl<'a> InvoiceLineRowRepository<'a> {
 
     pub fn delete(&self, invoice_line_id: &str) -> Result<Option<i64>, RepositoryError> {
         let old_row = self.find_one_by_id(invoice_line_id)?;
-        let change_log_id = match old_row {
-            Some(old_row) => self.insert_changelog(&old_row, RowActionType::Delete)?,
+        let change_log_id = match &old_row {
+            Some(old_row) => self.insert_changelog(old_row, RowActionType::Delete)?,
             None => {
                 return Ok(None);
             }
         };
 
+        let audit = AuditedChange::load(
+            self.connection,
+            ChangelogTableName::InvoiceLine,
+            invoice_line_id,
+            || Ok(old_row.clone()),
+        )?;
         diesel::delete(invoice_line.filter(id.eq(invoice_line_id)))
             .execute(self.connection.lock().connection())?;
+        audit.record(self.connection, None, || match &old_row {
+            Some(old_row) => self.store_id(old_row),
+            None => Ok(None),
+        })?;
         Ok(Some(change_log_id))
     }
 
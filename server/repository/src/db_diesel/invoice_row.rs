@@ -7,7 +7,9 @@ use super::{
 };
 
 use crate::{repository_error::RepositoryError, Delete, Upsert};
-use crate::{ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RowActionType};
+use crate::{
+    AuditedChange, ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RowActionType,
+};
 
 use diesel::{dsl::max, prelude::*};
 
@@ -87,7 +89,7 @@ pub enum InvoiceStatus {
     Verified,
 }
 
-#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Serialize)]
 #[diesel(treat_none_as_null = true)]
 #[diesel(table_name = invoice)]
 pub struct InvoiceRow {
@@ -166,12 +168,21 @@ impl<'a> InvoiceRowRepository<'a> {
     }
 
     pub fn upsert_one(&self, row: &InvoiceRow) -> Result<i64, RepositoryError> {
+        let audit = AuditedChange::load(
+            self.connection,
+            ChangelogTableName::Invoice,
+            &row.id,
+            || self.find_one_by_id(&row.id),
+        )?;
         diesel::insert_into(invoice)
             .values(row)
             .on_conflict(id)
             .do_update()
             .set(row)
             .execute(self.connection.lock().connection())?;
+        audit.record(self.connection, Some(row), || {
+            Ok(Some(row.store_id.clone()))
+        })?;
         self.insert_changelog(row, RowActionType::Upsert)
     }
 
@@ -193,15 +204,26 @@ impl<'a> InvoiceRowRepository<'a> {
 
     pub fn delete(&self, invoice_id: &str) -> Result<Option<i64>, RepositoryError> {
         let old_row = self.find_one_by_id(invoice_id)?;
-        let change_log_id = match old_row {
-            Some(old_row) => self.insert_changelog(&old_row, RowActionType::Delete)?,
+        let change_log_id = match &old_row {
+            Some(old_row) => self.insert_changelog(old_row, RowActionType::Delete)?,
             None => {
                 return Ok(None);
             }
         };
 
+        let audit = AuditedChange::load(
+            self.connection,
+            ChangelogTableName::Invoice,
+            invoice_id,
+            || Ok(old_row.clone()),
+        )?;
         diesel::delete(invoice.filter(id.eq(invoice_id)))
             .execute(self.connection.lock().connection())?;
+        audit.record(
+            self.connection,
+            None,
+            || Ok(old_row.map(|row| row.store_id)),
+        )?;
         Ok(Some(change_log_id))
     }
 
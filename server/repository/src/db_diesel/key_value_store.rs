@@ -27,6 +27,9 @@ pub enum KeyType {
     RemoteSyncPushCursor,
     ShipmentTransferProcessorCursor,
     RequisitionTransferProcessorCursor,
+    RecallProcessorCursor,
+    GoodsReceivedTransferProcessorCursor,
+    RedistributionRequestProcessorCursor,
 
     SettingsSyncUrl,
     SettingsSyncUsername,
@@ -45,6 +48,7 @@ pub enum KeyType {
     SettingsDisplayCustomTheme,
     SettingsDisplayCustomThemeHash,
     SettingsLabelPrinter,
+    SettingsAuthPolicy,
 
     LogLevel,
     LogDirectory,
@@ -1,6 +1,6 @@
 use crate::{
-    diesel_macros::{apply_equal_filter, apply_sort, apply_sort_no_case},
-    EqualFilter, InvoiceType, Pagination, RepositoryError, Sort,
+    diesel_macros::{apply_date_time_filter, apply_equal_filter, apply_sort, apply_sort_no_case},
+    DatetimeFilter, EqualFilter, InvoiceType, Pagination, RepositoryError, Sort,
 };
 
 use super::{ledger::ledger::dsl as ledger_dsl, StorageConnection};
@@ -22,6 +22,8 @@ table! {
         invoice_number -> BigInt,
         inventory_adjustment_reason -> Nullable<Text>,
         return_reason ->  Nullable<Text>,
+        cost_price_per_pack -> Double,
+        pack_size -> Double,
     }
 }
 
@@ -38,11 +40,27 @@ pub struct LedgerRow {
     pub invoice_number: i64,
     pub inventory_adjustment_reason: Option<String>,
     pub return_reason: Option<String>,
+    pub cost_price_per_pack: f64,
+    pub pack_size: f64,
+}
+
+impl LedgerRow {
+    /// Cost price of a single unit of the moved stock
+    pub fn cost_price_per_unit(&self) -> f64 {
+        if self.pack_size > 0.0 {
+            self.cost_price_per_pack / self.pack_size
+        } else {
+            0.0
+        }
+    }
 }
 
 #[derive(Clone, Default)]
 pub struct LedgerFilter {
     pub stock_line_id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub item_id: Option<EqualFilter<String>>,
+    pub datetime: Option<DatetimeFilter>,
 }
 
 #[derive(PartialEq, Debug)]
@@ -67,6 +85,21 @@ impl LedgerFilter {
         self.stock_line_id = Some(filter);
         self
     }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn item_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.item_id = Some(filter);
+        self
+    }
+
+    pub fn datetime(mut self, filter: DatetimeFilter) -> Self {
+        self.datetime = Some(filter);
+        self
+    }
 }
 
 pub struct LedgerRepository<'a> {
@@ -89,9 +122,17 @@ impl<'a> LedgerRepository<'a> {
         query = query.filter(ledger_dsl::datetime.is_not_null());
 
         if let Some(f) = filter {
-            let LedgerFilter { stock_line_id } = f;
+            let LedgerFilter {
+                stock_line_id,
+                store_id,
+                item_id,
+                datetime,
+            } = f;
 
             apply_equal_filter!(query, stock_line_id, ledger_dsl::stock_line_id);
+            apply_equal_filter!(query, store_id, ledger_dsl::store_id);
+            apply_equal_filter!(query, item_id, ledger_dsl::item_id);
+            apply_date_time_filter!(query, datetime, ledger_dsl::datetime);
         }
 
         if let Some(sort) = sort {
@@ -25,6 +25,7 @@ pub struct LocationFilter {
     pub on_hold: Option<bool>,
     pub store_id: Option<EqualFilter<String>>,
     pub assigned_to_asset: Option<bool>,
+    pub parent_location_id: Option<EqualFilter<String>>,
 }
 
 #[derive(PartialEq, Debug)]
@@ -116,6 +117,11 @@ impl<'a> LocationRepository<'a> {
             }
 
             apply_equal_filter!(query, filter.store_id, location_dsl::store_id);
+            apply_equal_filter!(
+                query,
+                filter.parent_location_id,
+                location_dsl::parent_location_id
+            );
         }
 
         query
@@ -162,4 +168,9 @@ impl LocationFilter {
         self.store_id = Some(filter);
         self
     }
+
+    pub fn parent_location_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.parent_location_id = Some(filter);
+        self
+    }
 }
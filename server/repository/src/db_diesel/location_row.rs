@@ -14,7 +14,11 @@ table! {
         code -> Text,
         on_hold -> Bool,
         store_id -> Text,
-        cold_storage_type_id -> Nullable<Text>
+        cold_storage_type_id -> Nullable<Text>,
+        parent_location_id -> Nullable<Text>,
+        volume_capacity -> Nullable<Double>,
+        pallet_capacity -> Nullable<Integer>,
+        walk_sequence -> Nullable<Integer>,
     }
 }
 
@@ -33,6 +37,13 @@ pub struct LocationRow {
     pub on_hold: bool,
     pub store_id: String,
     pub cold_storage_type_id: Option<String>,
+    /// Locations can be nested, e.g. warehouse -> room -> shelf -> bin
+    pub parent_location_id: Option<String>,
+    /// Volume that fits into the location, in the same unit as `packaging_variant.volume_per_unit`
+    pub volume_capacity: Option<f64>,
+    pub pallet_capacity: Option<i32>,
+    /// Order in which locations are visited when picking stock
+    pub walk_sequence: Option<i32>,
 }
 
 pub struct LocationRowRepository<'a> {
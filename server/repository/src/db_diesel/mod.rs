@@ -5,7 +5,9 @@ pub mod abbreviation_row;
 pub mod activity_log;
 mod activity_log_row;
 pub mod adjustment;
+mod api_token_row;
 pub mod assets;
+mod audit_trail_row;
 pub mod barcode;
 mod barcode_row;
 pub mod category_row;
@@ -41,12 +43,14 @@ mod filter_restriction;
 mod filter_sort_pagination;
 pub mod form_schema;
 mod form_schema_row;
+mod goods_received_line_row;
 pub mod indicator_column;
 mod indicator_column_row;
 pub mod indicator_line;
 mod indicator_line_row;
 pub mod indicator_value;
 mod indicator_value_row;
+pub mod insurance;
 pub mod inventory_adjustment_reason;
 mod inventory_adjustment_reason_row;
 pub mod invoice;
@@ -81,10 +85,14 @@ pub mod name_tag;
 pub mod name_tag_join;
 mod name_tag_row;
 mod number_row;
+mod oidc_authorisation_request_row;
 mod patient;
 pub mod period;
+mod period_lock_row;
+pub mod plugin_certificate_revocation_row;
 pub mod plugin_data;
 mod plugin_data_row;
+pub mod plugin_version_row;
 pub mod program_enrolment;
 mod program_enrolment_row;
 pub mod program_event;
@@ -94,8 +102,12 @@ mod program_indicator_row;
 mod program_requisition;
 pub mod property;
 pub mod property_row;
+mod read_only_query;
 pub mod reason_option;
 pub mod reason_option_row;
+pub mod recall;
+mod recall_row;
+mod redistribution_request_row;
 pub mod replenishment;
 pub mod report;
 mod report_query;
@@ -108,6 +120,8 @@ pub mod rnr_form;
 pub mod rnr_form_line;
 pub mod rnr_form_line_row;
 pub mod rnr_form_row;
+mod role_permission_row;
+mod role_row;
 pub mod sensor;
 mod sensor_row;
 pub mod stock_line;
@@ -137,19 +151,27 @@ pub mod temperature_log;
 mod temperature_log_row;
 mod unit_row;
 mod user;
+mod user_auth_state_row;
 pub mod user_permission;
 mod user_permission_row;
+mod user_role_row;
 mod user_row;
+mod user_session_token_row;
 mod user_store_join_row;
 pub mod vaccination;
 pub mod vaccination_card;
+pub mod vaccination_reminder_row;
 pub mod vaccination_row;
 pub mod vaccine_course;
+pub mod vaccine_vial_opening;
+pub mod vaccine_vial_opening_row;
 
 pub use abbreviation_row::*;
 pub use activity_log_row::*;
 pub use adjustment::*;
+pub use api_token_row::*;
 pub use assets::*;
+pub use audit_trail_row::*;
 pub use barcode_row::*;
 pub use changelog::*;
 pub use clinician::*;
@@ -175,6 +197,7 @@ pub use encounter_row::*;
 pub use filter_sort_pagination::*;
 pub use form_schema::*;
 pub use form_schema_row::*;
+pub use goods_received_line_row::*;
 pub use indicator_column_row::*;
 pub use indicator_line_row::*;
 pub use indicator_value_row::*;
@@ -205,10 +228,14 @@ pub use name_tag::*;
 pub use name_tag_join::*;
 pub use name_tag_row::*;
 pub use number_row::*;
+pub use oidc_authorisation_request_row::*;
 pub use patient::*;
 pub use period::*;
+pub use period_lock_row::*;
+pub use plugin_certificate_revocation_row::*;
 pub use plugin_data::*;
 pub use plugin_data_row::*;
+pub use plugin_version_row::*;
 pub use program_enrolment::*;
 pub use program_enrolment_row::*;
 pub use program_event::*;
@@ -217,7 +244,11 @@ pub use program_indicator::*;
 pub use program_indicator_row::*;
 pub use program_requisition::*;
 pub use property_row::*;
+pub use read_only_query::*;
 pub use reason_option::*;
+pub use recall::*;
+pub use recall_row::*;
+pub use redistribution_request_row::*;
 pub use replenishment::*;
 pub use report::*;
 pub use report_query::*;
@@ -229,6 +260,8 @@ pub use rnr_form::*;
 pub use rnr_form_line::*;
 pub use rnr_form_line_row::*;
 pub use rnr_form_row::*;
+pub use role_permission_row::*;
+pub use role_row::*;
 pub use sensor::*;
 pub use sensor_row::*;
 pub use stock_line::*;
@@ -257,13 +290,19 @@ pub use temperature_log::*;
 pub use temperature_log_row::*;
 pub use unit_row::*;
 pub use user::*;
+pub use user_auth_state_row::*;
 pub use user_permission::*;
 pub use user_permission_row::*;
+pub use user_role_row::*;
 pub use user_row::*;
+pub use user_session_token_row::*;
 pub use user_store_join_row::*;
 pub use vaccination::*;
 pub use vaccination_card::*;
+pub use vaccination_reminder_row::*;
 pub use vaccination_row::*;
+pub use vaccine_vial_opening::*;
+pub use vaccine_vial_opening_row::*;
 
 use diesel::{
     prelude::*,
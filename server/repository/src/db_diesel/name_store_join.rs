@@ -53,6 +53,7 @@ type NameStoreJoins = (NameStoreJoinRow, (NameLinkRow, NameRow));
 pub struct NameStoreJoinFilter {
     pub id: Option<EqualFilter<String>>,
     pub name_id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
 }
 
 pub struct NameStoreJoinRepository<'a> {
@@ -142,10 +143,15 @@ fn create_filtered_query(filter: Option<NameStoreJoinFilter>) -> BoxedNameStoreJ
         .into_boxed();
 
     if let Some(f) = filter {
-        let NameStoreJoinFilter { id, name_id } = f;
+        let NameStoreJoinFilter {
+            id,
+            name_id,
+            store_id,
+        } = f;
 
         apply_equal_filter!(query, id, name_store_join_dsl::id);
         apply_equal_filter!(query, name_id, name_dsl::id);
+        apply_equal_filter!(query, store_id, name_store_join_dsl::store_id);
     }
 
     query
@@ -172,6 +178,11 @@ impl NameStoreJoinFilter {
         self.name_id = Some(filter);
         self
     }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
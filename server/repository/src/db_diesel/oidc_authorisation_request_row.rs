@@ -0,0 +1,68 @@
+use super::oidc_authorisation_request_row::oidc_authorisation_request::dsl::*;
+
+use crate::{RepositoryError, StorageConnection};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    oidc_authorisation_request (id) {
+        id -> Text,
+        code_verifier -> Text,
+        nonce -> Text,
+        created_datetime -> Timestamp,
+    }
+}
+
+/// Pending OpenID Connect login, created when the user is sent to the identity provider and
+/// consumed when the identity provider redirects back. Local to the site, not synced.
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(table_name = oidc_authorisation_request)]
+pub struct OidcAuthorisationRequestRow {
+    /// The `state` parameter of the authorisation request
+    pub id: String,
+    /// PKCE code verifier, only its hash is sent to the identity provider
+    pub code_verifier: String,
+    pub nonce: String,
+    pub created_datetime: NaiveDateTime,
+}
+
+pub struct OidcAuthorisationRequestRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> OidcAuthorisationRequestRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        OidcAuthorisationRequestRowRepository { connection }
+    }
+
+    pub fn insert_one(&self, row: &OidcAuthorisationRequestRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(oidc_authorisation_request)
+            .values(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        request_id: &str,
+    ) -> Result<Option<OidcAuthorisationRequestRow>, RepositoryError> {
+        let result = oidc_authorisation_request
+            .filter(id.eq(request_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, request_id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(oidc_authorisation_request.filter(id.eq(request_id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn delete_created_before(&self, datetime: NaiveDateTime) -> Result<(), RepositoryError> {
+        diesel::delete(oidc_authorisation_request.filter(created_datetime.lt(datetime)))
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
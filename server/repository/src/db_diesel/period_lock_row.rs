@@ -0,0 +1,124 @@
+use super::period_lock_row::period_lock::dsl::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    StorageConnection, Upsert,
+};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+
+table! {
+    period_lock (id) {
+        id -> Text,
+        store_id -> Text,
+        lock_date -> Nullable<Date>,
+        user_id -> Nullable<Text>,
+        created_datetime -> Timestamp,
+        comment -> Nullable<Text>,
+    }
+}
+
+/// Period close for a store. Rows are never updated, the latest row for a store holds the current
+/// lock date and earlier rows keep the history of closed and re-opened periods.
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = period_lock)]
+pub struct PeriodLockRow {
+    pub id: String,
+    pub store_id: String,
+    /// Stock movements on or before this date can't be created or edited, None re-opens all periods
+    pub lock_date: Option<NaiveDate>,
+    pub user_id: Option<String>,
+    pub created_datetime: NaiveDateTime,
+    pub comment: Option<String>,
+}
+
+pub struct PeriodLockRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> PeriodLockRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        PeriodLockRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &PeriodLockRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(period_lock)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &PeriodLockRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::PeriodLock,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        period_lock_id: &str,
+    ) -> Result<Option<PeriodLockRow>, RepositoryError> {
+        let result = period_lock
+            .filter(id.eq(period_lock_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Current period lock of the store
+    pub fn find_latest_by_store(
+        &self,
+        store: &str,
+    ) -> Result<Option<PeriodLockRow>, RepositoryError> {
+        let result = period_lock
+            .filter(store_id.eq(store))
+            .order(created_datetime.desc())
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Period lock history of the store, latest first
+    pub fn find_many_by_store(&self, store: &str) -> Result<Vec<PeriodLockRow>, RepositoryError> {
+        let result = period_lock
+            .filter(store_id.eq(store))
+            .order(created_datetime.desc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for PeriodLockRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = PeriodLockRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PeriodLockRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,114 @@
+use super::plugin_certificate_revocation_row::plugin_certificate_revocation::dsl::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    StorageConnection, Upsert,
+};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    plugin_certificate_revocation (id) {
+        id -> Text,
+        fingerprint -> Text,
+        reason -> Nullable<Text>,
+        revoked_datetime -> Timestamp,
+    }
+}
+
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = plugin_certificate_revocation)]
+pub struct PluginCertificateRevocationRow {
+    pub id: String,
+    /// SHA-256 fingerprint (hex) of the DER encoded certificate
+    pub fingerprint: String,
+    pub reason: Option<String>,
+    pub revoked_datetime: NaiveDateTime,
+}
+
+pub struct PluginCertificateRevocationRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> PluginCertificateRevocationRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        PluginCertificateRevocationRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &PluginCertificateRevocationRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(plugin_certificate_revocation)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row.id.to_owned(), RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        record_id: String,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::PluginCertificateRevocation,
+            record_id,
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        revocation_id: &str,
+    ) -> Result<Option<PluginCertificateRevocationRow>, RepositoryError> {
+        let result = plugin_certificate_revocation
+            .filter(id.eq(revocation_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_one_by_fingerprint(
+        &self,
+        certificate_fingerprint: &str,
+    ) -> Result<Option<PluginCertificateRevocationRow>, RepositoryError> {
+        let result = plugin_certificate_revocation
+            .filter(fingerprint.eq(certificate_fingerprint))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_all(&self) -> Result<Vec<PluginCertificateRevocationRow>, RepositoryError> {
+        let result = plugin_certificate_revocation
+            .order(revoked_datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for PluginCertificateRevocationRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = PluginCertificateRevocationRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PluginCertificateRevocationRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -1,6 +1,9 @@
 use super::{store_row::store, StorageConnection};
 
-use crate::repository_error::RepositoryError;
+use crate::{
+    repository_error::RepositoryError, ChangeLogInsertRow, ChangelogRepository, ChangelogTableName,
+    RowActionType, Upsert,
+};
 
 use diesel::prelude::*;
 
@@ -15,6 +18,7 @@ table! {
         related_record_type -> crate::db_diesel::plugin_data_row::RelatedRecordTypeMapping,
         store_id -> Text,
         data -> Text,
+        version -> BigInt,
     }
 }
 
@@ -26,7 +30,7 @@ pub enum RelatedRecordType {
     StockLine,
 }
 
-#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Serialize, Deserialize)]
 #[diesel(treat_none_as_null = true)]
 #[diesel(table_name = plugin_data)]
 pub struct PluginDataRow {
@@ -36,6 +40,9 @@ pub struct PluginDataRow {
     pub related_record_type: RelatedRecordType,
     pub store_id: String,
     pub data: String,
+    /// Incremented on every change, the change with the higher version wins when central and the
+    /// site of the store change the same record
+    pub version: i64,
 }
 
 pub struct PluginDataRowRepository<'a> {
@@ -47,21 +54,37 @@ impl<'a> PluginDataRowRepository<'a> {
         PluginDataRowRepository { connection }
     }
 
-    pub fn insert_one(&self, row: &PluginDataRow) -> Result<(), RepositoryError> {
+    pub fn insert_one(&self, row: &PluginDataRow) -> Result<i64, RepositoryError> {
         diesel::insert_into(plugin_data::table)
             .values(row)
             .execute(self.connection.lock().connection())?;
-        Ok(())
+        self.insert_changelog(row, RowActionType::Upsert)
     }
 
-    pub fn upsert_one(&self, row: &PluginDataRow) -> Result<(), RepositoryError> {
+    pub fn upsert_one(&self, row: &PluginDataRow) -> Result<i64, RepositoryError> {
         diesel::insert_into(plugin_data::table)
             .values(row)
             .on_conflict(plugin_data::id)
             .do_update()
             .set(row)
             .execute(self.connection.lock().connection())?;
-        Ok(())
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &PluginDataRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::PluginData,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
     }
 
     pub fn find_one_by_id(&self, id: &str) -> Result<Option<PluginDataRow>, RepositoryError> {
@@ -73,3 +96,18 @@ impl<'a> PluginDataRowRepository<'a> {
         Ok(result)
     }
 }
+
+impl Upsert for PluginDataRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = PluginDataRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PluginDataRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
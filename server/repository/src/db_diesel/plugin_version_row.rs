@@ -0,0 +1,131 @@
+use super::plugin_version_row::plugin_version::dsl::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    StorageConnection, Upsert,
+};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    plugin_version (id) {
+        id -> Text,
+        code -> Text,
+        version -> Text,
+        bundle_file_id -> Text,
+        certificate_fingerprint -> Text,
+        is_active -> Bool,
+        created_datetime -> Timestamp,
+    }
+}
+
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = plugin_version)]
+pub struct PluginVersionRow {
+    pub id: String,
+    /// Plugin code, which is also the name of the installed plugin dir
+    pub code: String,
+    pub version: String,
+    /// Sync file reference id of the uploaded plugin bundle
+    pub bundle_file_id: String,
+    /// SHA-256 fingerprint of the certificate the plugin manifest was signed with
+    pub certificate_fingerprint: String,
+    /// Only one version of a plugin is active, i.e. installed, at a time
+    pub is_active: bool,
+    pub created_datetime: NaiveDateTime,
+}
+
+pub struct PluginVersionRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> PluginVersionRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        PluginVersionRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &PluginVersionRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(plugin_version)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row.id.to_owned(), RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        record_id: String,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::PluginVersion,
+            record_id,
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        plugin_version_id: &str,
+    ) -> Result<Option<PluginVersionRow>, RepositoryError> {
+        let result = plugin_version
+            .filter(id.eq(plugin_version_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// All versions of all plugins, latest version of a plugin first
+    pub fn find_all(&self) -> Result<Vec<PluginVersionRow>, RepositoryError> {
+        let result = plugin_version
+            .order((code.asc(), created_datetime.desc()))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_code(
+        &self,
+        plugin_code: &str,
+    ) -> Result<Vec<PluginVersionRow>, RepositoryError> {
+        let result = plugin_version
+            .filter(code.eq(plugin_code))
+            .order(created_datetime.desc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_all_active(&self) -> Result<Vec<PluginVersionRow>, RepositoryError> {
+        let result = plugin_version
+            .filter(is_active.eq(true))
+            .order(code.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for PluginVersionRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = PluginVersionRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PluginVersionRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -1,6 +1,7 @@
 use super::{
     name_link_row::{name_link, name_link::dsl as name_link_dsl},
     name_row::{name, name::dsl as name_dsl},
+    name_store_join::name_store_join::dsl as name_store_join_dsl,
     program_enrolment_row::program_enrolment::{self, dsl as program_enlrolment_dsl},
     program_row::{program, program::dsl as program_dsl},
     StorageConnection,
@@ -27,6 +28,8 @@ pub struct ProgramEnrolmentFilter {
     pub program_context_id: Option<EqualFilter<String>>,
     pub program_name: Option<StringFilter>,
     pub is_immunisation_program: Option<bool>,
+    /// Only enrolments of patients visible in this store (with a name_store_join)
+    pub patient_store_id: Option<String>,
 }
 
 impl ProgramEnrolmentFilter {
@@ -88,6 +91,11 @@ impl ProgramEnrolmentFilter {
         self.is_immunisation_program = Some(filter);
         self
     }
+
+    pub fn patient_store_id(mut self, store_id: &str) -> Self {
+        self.patient_store_id = Some(store_id.to_string());
+        self
+    }
 }
 
 pub enum ProgramEnrolmentSortField {
@@ -208,6 +216,7 @@ impl<'a> ProgramEnrolmentRepository<'a> {
             program_context_id: context,
             program_name,
             is_immunisation_program,
+            patient_store_id,
         }) = filter
         {
             apply_equal_filter!(query, id, program_enlrolment_dsl::id);
@@ -232,6 +241,14 @@ impl<'a> ProgramEnrolmentRepository<'a> {
             if let Some(is_immunisation_program) = is_immunisation_program {
                 query = query.filter(program_dsl::is_immunisation.eq(is_immunisation_program))
             }
+
+            if let Some(store_id) = patient_store_id {
+                let sub_query = name_store_join_dsl::name_store_join
+                    .inner_join(name_link_dsl::name_link)
+                    .select(name_link_dsl::name_id)
+                    .filter(name_store_join_dsl::store_id.eq(store_id));
+                query = query.filter(name_dsl::id.eq_any(sub_query));
+            }
         }
         query
     }
@@ -0,0 +1,339 @@
+use std::{
+    convert::Infallible,
+    time::{Duration, Instant},
+};
+
+use diesel::{
+    connection::SimpleConnection,
+    query_builder::{BoxedSqlQuery, SqlQuery},
+    sql_query,
+    sql_types::{BigInt, Bool, Double, Nullable, Text},
+    RunQueryDsl,
+};
+
+use crate::{
+    database_settings::DatabaseBackend, DBBackendConnection, DBType, JsonRawRow, RepositoryError,
+    StorageConnection, TransactionError,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RawQueryParameter {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadOnlyQueryLimits {
+    pub max_rows: usize,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReadOnlyQueryError {
+    /// Statement returned more than the max rows
+    TooManyRows(usize),
+    /// Statement ran longer than the timeout
+    Timeout(Duration),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for ReadOnlyQueryError {
+    fn from(error: RepositoryError) -> Self {
+        ReadOnlyQueryError::DatabaseError(error)
+    }
+}
+
+impl From<diesel::result::Error> for ReadOnlyQueryError {
+    fn from(error: diesel::result::Error) -> Self {
+        ReadOnlyQueryError::DatabaseError(error.into())
+    }
+}
+
+/// Runs a statement that returns a `json_row` text column, e.g. `SELECT ... AS json_row`, with
+/// positional parameters (`$1`, `$2`, ...) bound to `parameters`.
+///
+/// The statement runs in its own (nested) transaction that doesn't allow writes and that is always
+/// rolled back. Postgres cancels the statement after the statement timeout, sqlite interrupts it
+/// from a progress handler once the timeout passed.
+pub fn read_only_raw_query(
+    connection: &StorageConnection,
+    statement: &str,
+    parameters: &[RawQueryParameter],
+    limits: &ReadOnlyQueryLimits,
+) -> Result<Vec<JsonRawRow>, ReadOnlyQueryError> {
+    let started = Instant::now();
+    // The result is returned as the transaction error to always roll back
+    let result = connection.transaction_sync_etc(
+        |connection| {
+            Err::<Infallible, _>(query_read_only(connection, statement, parameters, limits))
+        },
+        false,
+    );
+    let result = match result {
+        Ok(never) => match never {},
+        Err(TransactionError::Inner(result)) => result,
+        Err(TransactionError::Transaction { msg, level }) => {
+            Err(RepositoryError::TransactionError { msg, level }.into())
+        }
+    };
+
+    if started.elapsed() > limits.timeout {
+        return Err(ReadOnlyQueryError::Timeout(limits.timeout));
+    }
+    result
+}
+
+fn query_read_only(
+    connection: &StorageConnection,
+    statement: &str,
+    parameters: &[RawQueryParameter],
+    limits: &ReadOnlyQueryLimits,
+) -> Result<Vec<JsonRawRow>, ReadOnlyQueryError> {
+    let mut guard = connection.lock();
+    let con = guard.connection();
+
+    begin_read_only(connection, con, limits)?;
+    // Ask for one more row than allowed to find out if there are too many
+    let query = sql_query(format!(
+        "SELECT json_row FROM ({}) AS read_only_query LIMIT {}",
+        placeholders(connection, statement.trim().trim_end_matches(';')),
+        limits.max_rows + 1
+    ))
+    .into_boxed::<DBType>();
+    let result = with_timeout(connection, limits, || {
+        bind_parameters(query, parameters).load::<JsonRawRow>(con)
+    });
+    end_read_only(connection, con)?;
+
+    let rows = result?;
+    if rows.len() > limits.max_rows {
+        return Err(ReadOnlyQueryError::TooManyRows(limits.max_rows));
+    }
+    Ok(rows)
+}
+
+fn bind_parameters<'a>(
+    mut query: BoxedSqlQuery<'a, DBType, SqlQuery>,
+    parameters: &[RawQueryParameter],
+) -> BoxedSqlQuery<'a, DBType, SqlQuery> {
+    for parameter in parameters {
+        query = match parameter.clone() {
+            RawQueryParameter::Null => query.bind::<Nullable<Text>, _>(None::<String>),
+            RawQueryParameter::Bool(value) => query.bind::<Bool, _>(value),
+            RawQueryParameter::Integer(value) => query.bind::<BigInt, _>(value),
+            RawQueryParameter::Float(value) => query.bind::<Double, _>(value),
+            RawQueryParameter::Text(value) => query.bind::<Text, _>(value),
+        };
+    }
+    query
+}
+
+fn begin_read_only(
+    connection: &StorageConnection,
+    con: &mut DBBackendConnection,
+    limits: &ReadOnlyQueryLimits,
+) -> Result<(), ReadOnlyQueryError> {
+    let statement = match connection.backend() {
+        DatabaseBackend::Postgres => format!(
+            "SET TRANSACTION READ ONLY; SET LOCAL statement_timeout = {};",
+            limits.timeout.as_millis()
+        ),
+        DatabaseBackend::Sqlite => "PRAGMA query_only = ON;".to_string(),
+    };
+    con.batch_execute(&statement)?;
+    Ok(())
+}
+
+fn end_read_only(
+    connection: &StorageConnection,
+    con: &mut DBBackendConnection,
+) -> Result<(), ReadOnlyQueryError> {
+    match connection.backend() {
+        // Settings are reverted when the transaction is rolled back
+        DatabaseBackend::Postgres => {}
+        // Pragmas are not rolled back with the transaction
+        DatabaseBackend::Sqlite => con.batch_execute("PRAGMA query_only = OFF;")?,
+    }
+    Ok(())
+}
+
+fn with_timeout<R>(
+    connection: &StorageConnection,
+    limits: &ReadOnlyQueryLimits,
+    f: impl FnOnce() -> R,
+) -> R {
+    match connection.backend() {
+        // The statement_timeout set in begin_read_only cancels the statement
+        DatabaseBackend::Postgres => f(),
+        #[cfg(any(feature = "sqlite", feature = "memory"))]
+        DatabaseBackend::Sqlite => {
+            sqlite_timeout::with_deadline(Instant::now() + limits.timeout, f)
+        }
+        #[cfg(not(any(feature = "sqlite", feature = "memory")))]
+        DatabaseBackend::Sqlite => {
+            let _ = limits;
+            f()
+        }
+    }
+}
+
+/// sqlite binds `$1` by order of appearance, `?1` binds to the numbered parameter
+fn placeholders(connection: &StorageConnection, statement: &str) -> String {
+    match connection.backend() {
+        DatabaseBackend::Postgres => statement.to_string(),
+        DatabaseBackend::Sqlite => {
+            use regex::Regex;
+
+            let re = Regex::new(r"\$(\d+)").unwrap();
+            re.replace_all(statement, "?${1}").to_string()
+        }
+    }
+}
+
+/// sqlite has no statement timeout, instead every connection gets a progress handler (registered
+/// as an auto extension so it's installed when the connection is opened) that interrupts the
+/// running statement once the deadline of the read only query on the same thread passed
+#[cfg(any(feature = "sqlite", feature = "memory"))]
+pub(crate) mod sqlite_timeout {
+    use std::{
+        cell::Cell,
+        os::raw::{c_char, c_int, c_void},
+        sync::Once,
+        time::Instant,
+    };
+
+    use libsqlite3_sys::{
+        sqlite3, sqlite3_api_routines, sqlite3_auto_extension, sqlite3_progress_handler, SQLITE_OK,
+    };
+
+    /// Number of virtual machine instructions between deadline checks
+    const PROGRESS_HANDLER_INSTRUCTIONS: c_int = 1000;
+
+    thread_local! {
+        static QUERY_DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+    }
+
+    /// Needs to be called before the connections are opened
+    pub(crate) fn register_progress_handler() {
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(|| unsafe {
+            sqlite3_auto_extension(Some(install_progress_handler));
+        });
+    }
+
+    unsafe extern "C" fn install_progress_handler(
+        db: *mut sqlite3,
+        _: *mut *const c_char,
+        _: *const sqlite3_api_routines,
+    ) -> c_int {
+        sqlite3_progress_handler(
+            db,
+            PROGRESS_HANDLER_INSTRUCTIONS,
+            Some(check_deadline),
+            std::ptr::null_mut(),
+        );
+        SQLITE_OK
+    }
+
+    /// Returning non zero interrupts the statement
+    unsafe extern "C" fn check_deadline(_: *mut c_void) -> c_int {
+        QUERY_DEADLINE
+            .try_with(|deadline| match deadline.get() {
+                Some(deadline) if Instant::now() > deadline => 1,
+                _ => 0,
+            })
+            .unwrap_or(0)
+    }
+
+    pub(super) fn with_deadline<R>(deadline: Instant, f: impl FnOnce() -> R) -> R {
+        struct Reset(Option<Instant>);
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                QUERY_DEADLINE.with(|deadline| deadline.set(self.0));
+            }
+        }
+
+        let previous = QUERY_DEADLINE.with(|current| current.replace(Some(deadline)));
+        let _reset = Reset(previous);
+        f()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        mock::{mock_store_a, MockDataInserts},
+        test_db, JsonRawRow, StoreRowRepository,
+    };
+
+    use super::{read_only_raw_query, RawQueryParameter, ReadOnlyQueryError, ReadOnlyQueryLimits};
+
+    #[actix_rt::test]
+    async fn test_read_only_raw_query() {
+        let (_, connection, _, _) = test_db::setup_all(
+            "test_read_only_raw_query",
+            MockDataInserts::none().names().stores(),
+        )
+        .await;
+        let limits = ReadOnlyQueryLimits {
+            max_rows: 2,
+            timeout: Duration::from_secs(10),
+        };
+
+        let result = read_only_raw_query(
+            &connection,
+            "SELECT '{\"id\":\"' || id || '\"}' AS json_row FROM store WHERE id = $1 AND $2 < 10;",
+            &[
+                RawQueryParameter::Text(mock_store_a().id),
+                RawQueryParameter::Integer(5),
+            ],
+            &limits,
+        );
+        assert_eq!(
+            result,
+            Ok(vec![JsonRawRow {
+                json_row: format!("{{\"id\":\"{}\"}}", mock_store_a().id)
+            }])
+        );
+
+        // Parameter used twice
+        let result = read_only_raw_query(
+            &connection,
+            "SELECT id AS json_row FROM store WHERE id = $1 OR code = $1",
+            &[RawQueryParameter::Text(mock_store_a().id)],
+            &limits,
+        );
+        assert_eq!(result.unwrap().len(), 1);
+
+        let result = read_only_raw_query(
+            &connection,
+            "SELECT id AS json_row FROM store",
+            &[],
+            &limits,
+        );
+        assert_eq!(result, Err(ReadOnlyQueryError::TooManyRows(2)));
+
+        // Statement that never finishes is interrupted
+        let timeout = Duration::from_millis(200);
+        let result = read_only_raw_query(
+            &connection,
+            "WITH RECURSIVE counter(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM counter) SELECT CAST(x AS TEXT) AS json_row FROM counter WHERE x < 0",
+            &[],
+            &ReadOnlyQueryLimits {
+                timeout,
+                ..limits.clone()
+            },
+        );
+        assert_eq!(result, Err(ReadOnlyQueryError::Timeout(timeout)));
+
+        // Connection can still write after the read only query
+        StoreRowRepository::new(&connection)
+            .upsert_one(&mock_store_a())
+            .unwrap();
+    }
+}
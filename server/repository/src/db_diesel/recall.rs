@@ -0,0 +1,145 @@
+use super::recall_row::{recall, RecallRow};
+use crate::{
+    db_diesel::item_row::item,
+    diesel_macros::{apply_equal_filter, apply_sort},
+    item_link,
+    repository_error::RepositoryError,
+    DBType, EqualFilter, ItemLinkRow, ItemRow, Pagination, Sort, StorageConnection,
+};
+use diesel::{
+    dsl::{InnerJoin, IntoBoxed},
+    prelude::*,
+};
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Recall {
+    pub recall_row: RecallRow,
+    pub item_row: ItemRow,
+}
+
+pub enum RecallSortField {
+    CreatedDatetime,
+}
+
+pub type RecallSort = Sort<RecallSortField>;
+
+type RecallJoin = (RecallRow, (ItemLinkRow, ItemRow));
+
+#[derive(Clone, Default)]
+pub struct RecallFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub item_id: Option<EqualFilter<String>>,
+    pub is_active: Option<bool>,
+}
+
+impl RecallFilter {
+    pub fn new() -> RecallFilter {
+        Self::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn item_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.item_id = Some(filter);
+        self
+    }
+
+    /// Recalls that have not been closed
+    pub fn is_active(mut self, filter: bool) -> Self {
+        self.is_active = Some(filter);
+        self
+    }
+}
+
+pub struct RecallRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> RecallRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        RecallRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<RecallFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query
+            .count()
+            .get_result(self.connection.lock().connection())?)
+    }
+
+    pub fn query_one(&self, filter: RecallFilter) -> Result<Option<Recall>, RepositoryError> {
+        Ok(self.query_by_filter(filter)?.pop())
+    }
+
+    pub fn query_by_filter(&self, filter: RecallFilter) -> Result<Vec<Recall>, RepositoryError> {
+        self.query(Pagination::all(), Some(filter), None)
+    }
+
+    pub fn query(
+        &self,
+        pagination: Pagination,
+        filter: Option<RecallFilter>,
+        sort: Option<RecallSort>,
+    ) -> Result<Vec<Recall>, RepositoryError> {
+        let mut query = create_filtered_query(filter);
+
+        if let Some(sort) = sort {
+            match sort.key {
+                RecallSortField::CreatedDatetime => {
+                    apply_sort!(query, sort, recall::created_datetime);
+                }
+            }
+        } else {
+            query = query.order(recall::created_datetime.desc())
+        }
+
+        let final_query = query
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64);
+
+        let result = final_query.load::<RecallJoin>(self.connection.lock().connection())?;
+
+        Ok(result.into_iter().map(to_domain).collect())
+    }
+}
+
+fn to_domain((recall_row, (_, item_row)): RecallJoin) -> Recall {
+    Recall {
+        recall_row,
+        item_row,
+    }
+}
+
+type BoxedRecallQuery = IntoBoxed<
+    'static,
+    InnerJoin<recall::table, InnerJoin<item_link::table, item::table>>,
+    DBType,
+>;
+
+fn create_filtered_query(filter: Option<RecallFilter>) -> BoxedRecallQuery {
+    let mut query = recall::table
+        .inner_join(item_link::table.inner_join(item::table))
+        .into_boxed();
+
+    if let Some(f) = filter {
+        let RecallFilter {
+            id,
+            item_id,
+            is_active,
+        } = f;
+
+        apply_equal_filter!(query, id, recall::id);
+        apply_equal_filter!(query, item_id, item::id);
+
+        query = match is_active {
+            Some(true) => query.filter(recall::closed_datetime.is_null()),
+            Some(false) => query.filter(recall::closed_datetime.is_not_null()),
+            None => query,
+        };
+    }
+    query
+}
@@ -0,0 +1,107 @@
+use crate::{
+    db_diesel::item_row::item, item_link, ChangeLogInsertRow, ChangelogRepository,
+    ChangelogTableName, RepositoryError, RowActionType, StorageConnection, Upsert,
+};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    recall (id) {
+        id -> Text,
+        item_link_id -> Text,
+        item_variant_id -> Nullable<Text>,
+        manufacturer_link_id -> Nullable<Text>,
+        batch -> Nullable<Text>,
+        expiry_date_from -> Nullable<Date>,
+        expiry_date_to -> Nullable<Date>,
+        reason -> Nullable<Text>,
+        created_datetime -> Timestamp,
+        closed_datetime -> Nullable<Timestamp>,
+    }
+}
+
+joinable!(recall -> item_link (item_link_id));
+allow_tables_to_appear_in_same_query!(recall, item_link);
+allow_tables_to_appear_in_same_query!(recall, item);
+
+/// Recall of an item by its manufacturer. Stock of the item matching all of the specified
+/// criteria (batch, expiry date range, item variant and manufacturer) is recalled.
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = recall)]
+#[diesel(treat_none_as_null = true)]
+pub struct RecallRow {
+    pub id: String,
+    pub item_link_id: String,
+    pub item_variant_id: Option<String>,
+    /// Recall stock of item variants made by this manufacturer
+    pub manufacturer_link_id: Option<String>,
+    pub batch: Option<String>,
+    pub expiry_date_from: Option<NaiveDate>,
+    pub expiry_date_to: Option<NaiveDate>,
+    pub reason: Option<String>,
+    pub created_datetime: NaiveDateTime,
+    pub closed_datetime: Option<NaiveDateTime>,
+}
+
+pub struct RecallRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> RecallRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        RecallRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &RecallRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(recall::table)
+            .values(row)
+            .on_conflict(recall::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row.id.to_owned(), RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row_id: String,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::Recall,
+            record_id: row_id,
+            row_action: action,
+            store_id: None,
+            ..Default::default()
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(&self, recall_id: &str) -> Result<Option<RecallRow>, RepositoryError> {
+        let result = recall::table
+            .filter(recall::id.eq(recall_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+}
+
+impl Upsert for RecallRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = RecallRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            RecallRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,108 @@
+use super::redistribution_request_row::redistribution_request::dsl::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    StorageConnection, Upsert,
+};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    redistribution_request (id) {
+        id -> Text,
+        store_id -> Text,
+        to_store_id -> Text,
+        outbound_shipment_id -> Text,
+        lines -> Text,
+        user_id -> Nullable<Text>,
+        created_datetime -> Timestamp,
+        processed_datetime -> Nullable<Timestamp>,
+    }
+}
+
+/// Accepted redistribution for a store that is active on another site. The request syncs to the
+/// site of the source store (`store_id`), where the draft outbound shipment is created.
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = redistribution_request)]
+pub struct RedistributionRequestRow {
+    pub id: String,
+    pub store_id: String,
+    pub to_store_id: String,
+    /// Id of the outbound shipment created on the site of the source store
+    pub outbound_shipment_id: String,
+    /// JSON array of the accepted stock lines and number of packs
+    pub lines: String,
+    pub user_id: Option<String>,
+    pub created_datetime: NaiveDateTime,
+    /// Set once the outbound shipment has been created
+    pub processed_datetime: Option<NaiveDateTime>,
+}
+
+pub struct RedistributionRequestRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> RedistributionRequestRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        RedistributionRequestRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &RedistributionRequestRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(redistribution_request)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &RedistributionRequestRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::RedistributionRequest,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        request_id: &str,
+    ) -> Result<Option<RedistributionRequestRow>, RepositoryError> {
+        let result = redistribution_request
+            .filter(id.eq(request_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+}
+
+impl Upsert for RedistributionRequestRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = RedistributionRequestRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            RedistributionRequestRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
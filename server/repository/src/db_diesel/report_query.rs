@@ -1,18 +1,28 @@
-use crate::RepositoryError;
-#[cfg(feature = "postgres")]
-use crate::StorageConnection;
-#[cfg(feature = "postgres")]
+use crate::{database_settings::DatabaseSettings, RepositoryError, StorageConnection};
 use diesel::sql_types::*;
 
-#[cfg(feature = "postgres")]
 #[derive(QueryableByName, Debug, PartialEq)]
 pub struct JsonDataRow {
     #[diesel(sql_type = Text)]
     data: String,
 }
 
-#[cfg(feature = "postgres")]
+/// Runs a report SQL query against the backend of the connection and returns the rows as JSON
+/// objects. The sql must be written for that backend.
 pub fn query_json(
+    connection: &StorageConnection,
+    settings: &DatabaseSettings,
+    sql: &str,
+    parameters: &serde_json::Map<String, serde_json::Value>,
+) -> Result<Vec<serde_json::Value>, RepositoryError> {
+    if connection.backend().is_postgres() {
+        query_json_postgres(connection, sql, parameters)
+    } else {
+        query_json_sqlite(settings, sql, parameters)
+    }
+}
+
+fn query_json_postgres(
     connection: &StorageConnection,
     sql: &str,
     parameters: &serde_json::Map<String, serde_json::Value>,
@@ -98,9 +108,6 @@ pub fn query_json(
     Ok(rows)
 }
 
-#[cfg(not(feature = "postgres"))]
-use crate::database_settings::DatabaseSettings;
-#[cfg(not(feature = "postgres"))]
 impl From<rusqlite::Error> for RepositoryError {
     fn from(value: rusqlite::Error) -> Self {
         RepositoryError::DBError {
@@ -110,8 +117,7 @@ impl From<rusqlite::Error> for RepositoryError {
     }
 }
 
-#[cfg(not(feature = "postgres"))]
-pub fn query_json(
+fn query_json_sqlite(
     settings: &DatabaseSettings,
     sql: &str,
     parameters: &serde_json::Map<String, serde_json::Value>,
@@ -202,28 +208,6 @@ mod tests {
 
     use crate::{mock::MockDataInserts, query_json, test_db};
 
-    use crate::{database_settings::DatabaseSettings, RepositoryError, StorageConnection};
-
-    #[cfg(feature = "postgres")]
-    pub fn query(
-        connection: &StorageConnection,
-        _settings: &DatabaseSettings,
-        sql: &str,
-        parameters: &serde_json::Map<String, serde_json::Value>,
-    ) -> Result<Vec<serde_json::Value>, RepositoryError> {
-        query_json(connection, sql, parameters)
-    }
-
-    #[cfg(not(feature = "postgres"))]
-    pub fn query(
-        _connection: &StorageConnection,
-        settings: &DatabaseSettings,
-        sql: &str,
-        parameters: &serde_json::Map<String, serde_json::Value>,
-    ) -> Result<Vec<serde_json::Value>, RepositoryError> {
-        query_json(settings, sql, parameters)
-    }
-
     #[actix_rt::test]
     async fn test_report_query() {
         let (_, connection, _, settings) = test_db::setup_all(
@@ -233,7 +217,7 @@ mod tests {
         .await;
 
         // query with no params
-        let result = query(
+        let result = query_json(
             &connection,
             &settings,
             "SELECT id, code, logo FROM store LIMIT 1;", // test with trailing ";"
@@ -246,7 +230,7 @@ mod tests {
         );
 
         // simple params
-        let result = query(
+        let result = query_json(
             &connection,
             &settings,
             "SELECT id, code FROM store WHERE id=$store LIMIT $limit", // test without trailing ";"
@@ -265,7 +249,7 @@ mod tests {
         );
 
         // multiple used params
-        let result = query(
+        let result = query_json(
             &connection,
             &settings,
             "SELECT id, code FROM store WHERE id LIKE $b || '%' AND code LIKE $b || '%' LIMIT $a",
@@ -9,7 +9,9 @@ use crate::db_diesel::{
 use crate::repository_error::RepositoryError;
 use crate::StorageConnection;
 
-use crate::{ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RowActionType};
+use crate::{
+    AuditedChange, ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RowActionType,
+};
 use crate::{Delete, Upsert};
 use chrono::{NaiveDate, NaiveDateTime};
 use diesel::dsl::max;
@@ -52,7 +54,7 @@ joinable!(requisition -> program (program_id));
 allow_tables_to_appear_in_same_query!(requisition, name_link);
 allow_tables_to_appear_in_same_query!(requisition, item_link);
 
-#[derive(DbEnum, Debug, Clone, PartialEq, Eq)]
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize)]
 #[DbValueStyle = "SCREAMING_SNAKE_CASE"]
 pub enum RequisitionType {
     Request,
@@ -67,7 +69,7 @@ pub enum RequisitionStatus {
     Sent,
     Finalised,
 }
-#[derive(DbEnum, Debug, Clone, PartialEq, Eq)]
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize)]
 #[cfg_attr(test, derive(strum::EnumIter))]
 #[DbValueStyle = "SCREAMING_SNAKE_CASE"]
 pub enum ApprovalStatusType {
@@ -80,7 +82,7 @@ pub enum ApprovalStatusType {
     DeniedByAnother,
 }
 
-#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Serialize)]
 #[diesel(treat_none_as_null = true)]
 #[diesel(table_name = requisition)]
 pub struct RequisitionRow {
@@ -147,12 +149,21 @@ impl<'a> RequisitionRowRepository<'a> {
     }
 
     pub fn upsert_one(&self, row: &RequisitionRow) -> Result<i64, RepositoryError> {
+        let audit = AuditedChange::load(
+            self.connection,
+            ChangelogTableName::Requisition,
+            &row.id,
+            || self.find_one_by_id(&row.id),
+        )?;
         diesel::insert_into(requisition_dsl::requisition)
             .values(row)
             .on_conflict(requisition_dsl::id)
             .do_update()
             .set(row)
             .execute(self.connection.lock().connection())?;
+        audit.record(self.connection, Some(row), || {
+            Ok(Some(row.store_id.clone()))
+        })?;
         self.insert_changelog(row, RowActionType::Upsert)
     }
 
@@ -181,8 +192,15 @@ impl<'a> RequisitionRowRepository<'a> {
 
         let change_log_id = self.insert_changelog(&requisition, RowActionType::Delete)?;
 
+        let audit = AuditedChange::load(
+            self.connection,
+            ChangelogTableName::Requisition,
+            requisition_id,
+            || Ok(Some(requisition.clone())),
+        )?;
         diesel::delete(requisition_dsl::requisition.filter(requisition_dsl::id.eq(requisition_id)))
             .execute(self.connection.lock().connection())?;
+        audit.record(self.connection, None, || Ok(Some(requisition.store_id)))?;
 
         Ok(Some(change_log_id))
     }
@@ -0,0 +1,131 @@
+use super::role_permission_row::role_permission::dsl::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, Delete, PermissionType,
+    RepositoryError, RowActionType, StorageConnection, Upsert,
+};
+
+use diesel::prelude::*;
+
+table! {
+    role_permission (id) {
+        id -> Text,
+        role_id -> Text,
+        permission -> crate::db_diesel::user_permission_row::PermissionTypeMapping,
+        context_id -> Nullable<Text>,
+    }
+}
+
+/// Permission given by a role, same meaning as a user_permission row in the stores the role is
+/// assigned to
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Serialize, Deserialize)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = role_permission)]
+pub struct RolePermissionRow {
+    pub id: String,
+    pub role_id: String,
+    pub permission: PermissionType,
+    pub context_id: Option<String>,
+}
+
+pub struct RolePermissionRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> RolePermissionRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        RolePermissionRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &RolePermissionRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(role_permission)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row.id.to_owned(), RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        record_id: String,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::RolePermission,
+            record_id,
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn delete(&self, permission_id: &str) -> Result<Option<i64>, RepositoryError> {
+        if self.find_one_by_id(permission_id)?.is_none() {
+            return Ok(None);
+        }
+        let change_log_id =
+            self.insert_changelog(permission_id.to_owned(), RowActionType::Delete)?;
+
+        diesel::delete(role_permission.filter(id.eq(permission_id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(Some(change_log_id))
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        permission_id: &str,
+    ) -> Result<Option<RolePermissionRow>, RepositoryError> {
+        let result = role_permission
+            .filter(id.eq(permission_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_role_ids(
+        &self,
+        role_ids: &[String],
+    ) -> Result<Vec<RolePermissionRow>, RepositoryError> {
+        let result = role_permission
+            .filter(role_id.eq_any(role_ids))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RolePermissionRowDelete(pub String);
+impl Delete for RolePermissionRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        RolePermissionRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            RolePermissionRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for RolePermissionRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = RolePermissionRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            RolePermissionRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
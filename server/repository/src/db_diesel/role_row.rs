@@ -0,0 +1,116 @@
+use super::role_row::role::dsl::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    StorageConnection, Upsert,
+};
+
+use diesel::prelude::*;
+
+table! {
+    role (id) {
+        id -> Text,
+        name -> Text,
+        description -> Nullable<Text>,
+        is_active -> Bool,
+    }
+}
+
+/// Named set of permissions (see role_permission) that can be assigned to users per store.
+/// Defined on the omSupply central server and synced to all sites.
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = role)]
+pub struct RoleRow {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// Inactive roles don't give any permissions, roles are deactivated instead of deleted
+    pub is_active: bool,
+}
+
+pub struct RoleRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> RoleRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        RoleRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &RoleRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(role)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row.id.to_owned(), RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        record_id: String,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::Role,
+            record_id,
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(&self, role_id: &str) -> Result<Option<RoleRow>, RepositoryError> {
+        let result = role
+            .filter(id.eq(role_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_one_by_name(&self, role_name: &str) -> Result<Option<RoleRow>, RepositoryError> {
+        let result = role
+            .filter(name.eq(role_name))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_id(&self, ids: &[String]) -> Result<Vec<RoleRow>, RepositoryError> {
+        let result = role
+            .filter(id.eq_any(ids))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_all(&self) -> Result<Vec<RoleRow>, RepositoryError> {
+        let result = role
+            .order(name.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for RoleRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = RoleRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            RoleRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -58,6 +58,8 @@ pub struct StockLineFilter {
     pub location: Option<LocationFilter>,
     pub master_list: Option<MasterListFilter>,
     pub is_active: Option<bool>,
+    /// Name id of the donor or funding program that owns the stock
+    pub donor_id: Option<EqualFilter<String>>,
 }
 
 pub type StockLineSort = Sort<StockLineSortField>;
@@ -193,6 +195,7 @@ fn create_filtered_query(filter: Option<StockLineFilter>) -> BoxedStockLineQuery
             location,
             master_list,
             is_active,
+            donor_id,
         } = f;
 
         apply_equal_filter!(query, id, stock_line_dsl::id);
@@ -223,6 +226,15 @@ fn create_filtered_query(filter: Option<StockLineFilter>) -> BoxedStockLineQuery
             query = query.filter(stock_line_dsl::location_id.eq_any(location_ids));
         }
 
+        if donor_id.is_some() {
+            let mut donor_link_ids = name_link_dsl::name_link.into_boxed();
+            apply_equal_filter!(donor_link_ids, donor_id, name_link_dsl::name_id);
+            query = query.filter(
+                stock_line_dsl::donor_link_id
+                    .eq_any(donor_link_ids.select(name_link_dsl::id).nullable()),
+            );
+        }
+
         if master_list.is_some() {
             let item_ids = MasterListLineRepository::create_filtered_query(Some(
                 MasterListLineFilter::new().master_list(master_list.unwrap()),
@@ -316,6 +328,11 @@ impl StockLineFilter {
         self.location = Some(filter);
         self
     }
+
+    pub fn donor_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.donor_id = Some(filter);
+        self
+    }
 }
 
 impl StockLine {
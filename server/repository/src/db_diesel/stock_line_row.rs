@@ -4,11 +4,14 @@ use super::{
 };
 
 use crate::{db_diesel::barcode_row::barcode, repository_error::RepositoryError, Delete, Upsert};
-use crate::{ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RowActionType};
+use crate::{
+    AuditedChange, ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RowActionType,
+};
 
 use diesel::prelude::*;
 
 use chrono::NaiveDate;
+use serde::Serialize;
 
 table! {
     stock_line (id) {
@@ -28,6 +31,7 @@ table! {
         supplier_link_id -> Nullable<Text>,
         barcode_id -> Nullable<Text>,
         item_variant_id -> Nullable<Text>,
+        donor_link_id -> Nullable<Text>,
     }
 }
 
@@ -39,7 +43,7 @@ joinable!(stock_line -> barcode (barcode_id));
 allow_tables_to_appear_in_same_query!(stock_line, item_link);
 allow_tables_to_appear_in_same_query!(stock_line, name_link);
 
-#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize)]
 #[diesel(treat_none_as_null = true)]
 #[diesel(table_name = stock_line)]
 pub struct StockLineRow {
@@ -59,6 +63,8 @@ pub struct StockLineRow {
     pub supplier_link_id: Option<String>,
     pub barcode_id: Option<String>,
     pub item_variant_id: Option<String>,
+    /// Donor or funding program that owns the stock
+    pub donor_link_id: Option<String>,
 }
 
 pub struct StockLineRowRepository<'a> {
@@ -71,12 +77,21 @@ impl<'a> StockLineRowRepository<'a> {
     }
 
     pub fn upsert_one(&self, row: &StockLineRow) -> Result<i64, RepositoryError> {
+        let audit = AuditedChange::load(
+            self.connection,
+            ChangelogTableName::StockLine,
+            &row.id,
+            || self.find_one_by_id(&row.id),
+        )?;
         diesel::insert_into(stock_line_dsl::stock_line)
             .values(row)
             .on_conflict(stock_line_dsl::id)
             .do_update()
             .set(row)
             .execute(self.connection.lock().connection())?;
+        audit.record(self.connection, Some(row), || {
+            Ok(Some(row.store_id.clone()))
+        })?;
         self.insert_changelog(row, RowActionType::Upsert)
     }
 
@@ -98,15 +113,24 @@ impl<'a> StockLineRowRepository<'a> {
 
     pub fn delete(&self, id: &str) -> Result<Option<i64>, RepositoryError> {
         let old_row = self.find_one_by_id(id)?;
-        let change_log_id = match old_row {
-            Some(old_row) => self.insert_changelog(&old_row, RowActionType::Delete)?,
+        let change_log_id = match &old_row {
+            Some(old_row) => self.insert_changelog(old_row, RowActionType::Delete)?,
             None => {
                 return Ok(None);
             }
         };
 
+        let audit =
+            AuditedChange::load(self.connection, ChangelogTableName::StockLine, id, || {
+                Ok(old_row.clone())
+            })?;
         diesel::delete(stock_line_dsl::stock_line.filter(stock_line_dsl::id.eq(id)))
             .execute(self.connection.lock().connection())?;
+        audit.record(
+            self.connection,
+            None,
+            || Ok(old_row.map(|row| row.store_id)),
+        )?;
         Ok(Some(change_log_id))
     }
 
@@ -16,6 +16,7 @@ table! {
         quantity -> Double,
         datetime -> Timestamp,
         stock_line_id -> Nullable<Text>, // TODO: Make this non-nullable, null is only used for non-stock lines so don't count as movements
+        donor_id -> Nullable<Text>,
     }
 }
 
@@ -27,6 +28,8 @@ pub struct StockMovementRow {
     pub quantity: f64,
     pub datetime: NaiveDateTime,
     pub stock_line_id: Option<String>,
+    /// Name id of the donor that owns the moved stock
+    pub donor_id: Option<String>,
 }
 
 impl Default for StockMovementRow {
@@ -39,6 +42,7 @@ impl Default for StockMovementRow {
             store_id: Default::default(),
             quantity: Default::default(),
             stock_line_id: Default::default(),
+            donor_id: Default::default(),
         }
     }
 }
@@ -49,6 +53,7 @@ pub struct StockMovementFilter {
     pub store_id: Option<EqualFilter<String>>,
     pub datetime: Option<DatetimeFilter>,
     pub stock_line_id: Option<EqualFilter<String>>,
+    pub donor_id: Option<EqualFilter<String>>,
 }
 
 pub struct StockMovementRepository<'a> {
@@ -80,12 +85,14 @@ impl<'a> StockMovementRepository<'a> {
                 datetime,
                 store_id,
                 stock_line_id,
+                donor_id,
             } = f;
 
             apply_equal_filter!(query, item_id, stock_movement_dsl::item_id);
             apply_equal_filter!(query, store_id, stock_movement_dsl::store_id);
             apply_equal_filter!(query, stock_line_id, stock_movement_dsl::stock_line_id);
             apply_date_time_filter!(query, datetime, stock_movement_dsl::datetime);
+            apply_equal_filter!(query, donor_id, stock_movement_dsl::donor_id);
         }
 
         // Debug diesel query
@@ -122,6 +129,11 @@ impl StockMovementFilter {
         self.stock_line_id = Some(filter);
         self
     }
+
+    pub fn donor_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.donor_id = Some(filter);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -269,6 +281,7 @@ mod test {
                 item_id: Some(EqualFilter::equal_to(&mock_item_a().id)),
                 datetime: None,
                 stock_line_id: None,
+                donor_id: None,
             }))
             .unwrap();
 
@@ -294,6 +307,7 @@ mod test {
                         .and_hms_opt(0, 0, 0)
                         .unwrap(),
                     stock_line_id: None,
+                    donor_id: None,
                 },
                 StockMovementRow {
                     id: "n/a".to_string(),
@@ -305,6 +319,7 @@ mod test {
                         .and_hms_opt(0, 0, 0)
                         .unwrap(),
                     stock_line_id: None,
+                    donor_id: None,
                 },
                 StockMovementRow {
                     id: "n/a".to_string(),
@@ -316,6 +331,7 @@ mod test {
                         .and_hms_opt(0, 0, 0)
                         .unwrap(),
                     stock_line_id: None,
+                    donor_id: None,
                 },
                 StockMovementRow {
                     id: "n/a".to_string(),
@@ -327,6 +343,7 @@ mod test {
                         .and_hms_opt(0, 0, 0)
                         .unwrap(),
                     stock_line_id: None,
+                    donor_id: None,
                 },
                 StockMovementRow {
                     id: "n/a".to_string(),
@@ -338,6 +355,7 @@ mod test {
                         .and_hms_opt(0, 0, 0)
                         .unwrap(),
                     stock_line_id: None,
+                    donor_id: None,
                 },
             ]
         )
@@ -7,11 +7,12 @@ use super::{
 
 use crate::{repository_error::RepositoryError, Delete, Upsert};
 use crate::{
-    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RowActionType,
+    AuditedChange, ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RowActionType,
     StocktakeRowRepository,
 };
 
 use diesel::prelude::*;
+use serde::Serialize;
 
 use chrono::NaiveDate;
 
@@ -36,6 +37,7 @@ table! {
         note -> Nullable<Text>,
         inventory_adjustment_reason_id -> Nullable<Text>,
         item_variant_id -> Nullable<Text>,
+        donor_link_id -> Nullable<Text>,
     }
 }
 
@@ -46,7 +48,7 @@ joinable!(stocktake_line -> stock_line (stock_line_id));
 joinable!(stocktake_line -> inventory_adjustment_reason (inventory_adjustment_reason_id));
 allow_tables_to_appear_in_same_query!(stocktake_line, item_link);
 
-#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize)]
 #[diesel(treat_none_as_null = true)]
 #[diesel(table_name = stocktake_line)]
 pub struct StocktakeLineRow {
@@ -71,6 +73,7 @@ pub struct StocktakeLineRow {
     pub note: Option<String>,
     pub inventory_adjustment_reason_id: Option<String>,
     pub item_variant_id: Option<String>,
+    pub donor_link_id: Option<String>,
 }
 
 pub struct StocktakeLineRowRepository<'a> {
@@ -83,15 +86,28 @@ impl<'a> StocktakeLineRowRepository<'a> {
     }
 
     pub fn upsert_one(&self, row: &StocktakeLineRow) -> Result<i64, RepositoryError> {
+        let audit = AuditedChange::load(
+            self.connection,
+            ChangelogTableName::StocktakeLine,
+            &row.id,
+            || self.find_one_by_id(&row.id),
+        )?;
         diesel::insert_into(stocktake_line_dsl::stocktake_line)
             .values(row)
             .on_conflict(stocktake_line_dsl::id)
             .do_update()
             .set(row)
             .execute(self.connection.lock().connection())?;
+        audit.record(self.connection, Some(row), || self.store_id(row))?;
         self.insert_changelog(row, RowActionType::Upsert)
     }
 
+    fn store_id(&self, row: &StocktakeLineRow) -> Result<Option<String>, RepositoryError> {
+        Ok(StocktakeRowRepository::new(self.connection)
+            .find_one_by_id(&row.stocktake_id)?
+            .map(|stocktake| stocktake.store_id))
+    }
+
     fn insert_changelog(
         &self,
         row: &StocktakeLineRow,
@@ -117,15 +133,25 @@ impl<'a> StocktakeLineRowRepository<'a> {
 
     pub fn delete(&self, id: &str) -> Result<Option<i64>, RepositoryError> {
         let old_row = self.find_one_by_id(id)?;
-        let change_log_id = match old_row {
-            Some(old_row) => self.insert_changelog(&old_row, RowActionType::Delete)?,
+        let change_log_id = match &old_row {
+            Some(old_row) => self.insert_changelog(old_row, RowActionType::Delete)?,
             None => {
                 return Ok(None);
             }
         };
 
+        let audit = AuditedChange::load(
+            self.connection,
+            ChangelogTableName::StocktakeLine,
+            id,
+            || Ok(old_row.clone()),
+        )?;
         diesel::delete(stocktake_line_dsl::stocktake_line.filter(stocktake_line_dsl::id.eq(id)))
             .execute(self.connection.lock().connection())?;
+        audit.record(self.connection, None, || match &old_row {
+            Some(old_row) => self.store_id(old_row),
+            None => Ok(None),
+        })?;
         Ok(Some(change_log_id))
     }
 
@@ -2,7 +2,7 @@ use std::sync::{Mutex, MutexGuard};
 
 use super::{get_connection, DBBackendConnection, DBConnection};
 
-use crate::repository_error::RepositoryError;
+use crate::{database_settings::DatabaseBackend, repository_error::RepositoryError};
 
 use diesel::{
     connection::{AnsiTransactionManager, SimpleConnection, TransactionManager},
@@ -57,8 +57,17 @@ impl<'a> LockedConnection<'a> {
     }
 }
 
+/// Who changes made on a connection are attributed to in the audit trail
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct AuditUser {
+    /// None for changes made by the system, e.g. processors
+    pub user_id: Option<String>,
+}
+
 pub struct StorageConnection {
     raw_connection: Mutex<DBConnection>,
+    /// Changes to audited records are only recorded when set, see `AuditedChange`
+    audit_user: Mutex<Option<AuditUser>>,
 }
 
 impl StorageConnection {
@@ -109,9 +118,27 @@ impl StorageConnection {
     pub fn new(connection: DBConnection) -> StorageConnection {
         StorageConnection {
             raw_connection: Mutex::new(connection),
+            audit_user: Mutex::new(None),
         }
     }
 
+    /// Sets who subsequent changes are attributed to in the audit trail, None stops changes from
+    /// being audited. Returns the previous audit user.
+    pub fn set_audit_user(&self, audit_user: Option<AuditUser>) -> Option<AuditUser> {
+        std::mem::replace(&mut *self.audit_user.lock().unwrap(), audit_user)
+    }
+
+    pub fn audit_user(&self) -> Option<AuditUser> {
+        self.audit_user.lock().unwrap().clone()
+    }
+
+    /// Backend of the underlying database connection, use this rather than checking the
+    /// `postgres` feature when choosing between backend specific SQL. Connections are typed by
+    /// the build, so this is always the backend the server was built for.
+    pub fn backend(&self) -> DatabaseBackend {
+        DatabaseBackend::compiled()
+    }
+
     /// Executes operations in transaction. A new transaction is only started if not already in a
     /// transaction.
     pub fn transaction_sync<T, E, F>(&self, f: F) -> Result<T, TransactionError<E>>
@@ -211,6 +238,11 @@ impl StorageConnectionManager {
         Ok(StorageConnection::new(get_connection(&self.pool)?))
     }
 
+    /// Always the backend the server was built for, see [StorageConnection::backend]
+    pub fn backend(&self) -> DatabaseBackend {
+        DatabaseBackend::compiled()
+    }
+
     // Note, this method is only needed for an Android workaround to avoid adding a diesel
     // dependency to the server crate.
     pub fn execute(&self, sql: &str) -> Result<(), RepositoryError> {
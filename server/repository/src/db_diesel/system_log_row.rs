@@ -21,12 +21,14 @@ table! {
 #[DbValueStyle = "SCREAMING_SNAKE_CASE"]
 pub enum SystemLogType {
     ProcessorError,
+    LoginFailed,
 }
 
 impl SystemLogType {
     pub fn is_error(&self) -> bool {
         match self {
             SystemLogType::ProcessorError => true,
+            SystemLogType::LoginFailed => false,
         }
     }
 }
@@ -84,6 +86,18 @@ impl<'a> SystemLogRowRepository<'a> {
         Ok(result)
     }
 
+    pub fn find_latest_by_type(
+        &self,
+        log_type: SystemLogType,
+    ) -> Result<Option<SystemLogRow>, RepositoryError> {
+        let result = system_log::table
+            .filter(system_log::type_.eq(log_type))
+            .order(system_log::datetime.desc())
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
     pub fn last_x_messages(&self, count: i64) -> Result<Vec<SystemLogRow>, RepositoryError> {
         let result = system_log::table
             .limit(count)
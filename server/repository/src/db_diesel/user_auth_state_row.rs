@@ -0,0 +1,111 @@
+use super::user_auth_state_row::user_auth_state::dsl::*;
+
+use crate::{RepositoryError, StorageConnection};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    user_auth_state (user_id) {
+        user_id -> Text,
+        failed_login_count -> Integer,
+        last_failed_login_datetime -> Nullable<Timestamp>,
+        locked_until_datetime -> Nullable<Timestamp>,
+        password_changed_datetime -> Nullable<Timestamp>,
+        is_locally_managed -> Bool,
+        totp_secret -> Nullable<Text>,
+        totp_confirmed_datetime -> Nullable<Timestamp>,
+        external_id -> Nullable<Text>,
+    }
+}
+
+/// Local authentication state of a user, kept apart from `user_account` since the account row is
+/// overwritten whenever the user logs in against central. Local to the site, not synced.
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = user_auth_state)]
+pub struct UserAuthStateRow {
+    pub user_id: String,
+    /// Consecutive failed logins since the last successful login
+    pub failed_login_count: i32,
+    pub last_failed_login_datetime: Option<NaiveDateTime>,
+    pub locked_until_datetime: Option<NaiveDateTime>,
+    pub password_changed_datetime: Option<NaiveDateTime>,
+    /// User was created on this site rather than synced from central, password policies only
+    /// apply to these users
+    pub is_locally_managed: bool,
+    /// Base32 encoded TOTP secret, only enforced once `totp_confirmed_datetime` is set
+    pub totp_secret: Option<String>,
+    pub totp_confirmed_datetime: Option<NaiveDateTime>,
+    /// Issuer and subject of the identity provider account, only set for users provisioned by
+    /// an identity provider
+    pub external_id: Option<String>,
+}
+
+impl UserAuthStateRow {
+    pub fn new(for_user_id: &str) -> Self {
+        UserAuthStateRow {
+            user_id: for_user_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// TOTP secret once the enrolment is confirmed
+    pub fn enabled_totp_secret(&self) -> Option<&str> {
+        self.totp_confirmed_datetime
+            .and(self.totp_secret.as_deref())
+    }
+
+    pub fn is_totp_enabled(&self) -> bool {
+        self.enabled_totp_secret().is_some()
+    }
+}
+
+pub struct UserAuthStateRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> UserAuthStateRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        UserAuthStateRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &UserAuthStateRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(user_auth_state)
+            .values(row)
+            .on_conflict(user_id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_user_id(
+        &self,
+        for_user_id: &str,
+    ) -> Result<Option<UserAuthStateRow>, RepositoryError> {
+        let result = user_auth_state
+            .filter(user_id.eq(for_user_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_one_by_external_id(
+        &self,
+        for_external_id: &str,
+    ) -> Result<Option<UserAuthStateRow>, RepositoryError> {
+        let result = user_auth_state
+            .filter(external_id.eq(for_external_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Returns the stored state or a fresh default state for the user
+    pub fn find_or_default(&self, for_user_id: &str) -> Result<UserAuthStateRow, RepositoryError> {
+        Ok(self
+            .find_one_by_user_id(for_user_id)?
+            .unwrap_or_else(|| UserAuthStateRow::new(for_user_id)))
+    }
+}
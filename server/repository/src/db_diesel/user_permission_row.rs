@@ -8,6 +8,7 @@ use crate::{Delete, Upsert};
 use diesel::prelude::*;
 
 use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
 
 table! {
   user_permission (id) {
@@ -19,7 +20,7 @@ table! {
     }
 }
 
-#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[DbValueStyle = "SCREAMING_SNAKE_CASE"]
 pub enum PermissionType {
     ServerAdmin,
@@ -50,6 +51,10 @@ pub enum PermissionType {
     // r&r form,
     RnrFormQuery,
     RnrFormMutate,
+    // period close
+    PeriodLockMutate,
+    /// Allows creating and editing stock movements in a closed period
+    PeriodLockOverride,
     // outbound shipment
     OutboundShipmentQuery,
     OutboundShipmentMutate,
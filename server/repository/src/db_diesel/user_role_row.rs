@@ -0,0 +1,130 @@
+use super::user_role_row::user_role::dsl::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, Delete, RepositoryError,
+    RowActionType, StorageConnection, Upsert,
+};
+
+use diesel::prelude::*;
+
+table! {
+    user_role (id) {
+        id -> Text,
+        user_id -> Text,
+        role_id -> Text,
+        store_id -> Nullable<Text>,
+    }
+}
+
+/// Assignment of a role to a user in a store. Like user_permission rows without a store, a role
+/// assigned without a store only applies to requests that aren't for a specific store.
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = user_role)]
+pub struct UserRoleRow {
+    pub id: String,
+    pub user_id: String,
+    pub role_id: String,
+    pub store_id: Option<String>,
+}
+
+pub struct UserRoleRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> UserRoleRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        UserRoleRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &UserRoleRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(user_role)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row.id.to_owned(), RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        record_id: String,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::UserRole,
+            record_id,
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn delete(&self, user_role_id: &str) -> Result<Option<i64>, RepositoryError> {
+        if self.find_one_by_id(user_role_id)?.is_none() {
+            return Ok(None);
+        }
+        let change_log_id =
+            self.insert_changelog(user_role_id.to_owned(), RowActionType::Delete)?;
+
+        diesel::delete(user_role.filter(id.eq(user_role_id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(Some(change_log_id))
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        user_role_id: &str,
+    ) -> Result<Option<UserRoleRow>, RepositoryError> {
+        let result = user_role
+            .filter(id.eq(user_role_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_user_id(&self, user: &str) -> Result<Vec<UserRoleRow>, RepositoryError> {
+        let result = user_role
+            .filter(user_id.eq(user))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UserRoleRowDelete(pub String);
+impl Delete for UserRoleRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        UserRoleRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            UserRoleRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for UserRoleRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = UserRoleRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            UserRoleRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,92 @@
+use super::user_session_token_row::user_session_token::dsl::*;
+
+use crate::{RepositoryError, StorageConnection};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    user_session_token (id) {
+        id -> Text,
+        session_id -> Text,
+        user_id -> Text,
+        created_datetime -> Timestamp,
+        expiry_datetime -> Timestamp,
+    }
+}
+
+/// Auth or refresh token issued to a user, persisted so sessions survive a server restart.
+/// All tokens issued from a login and its token refreshes share the same session id.
+/// Local to the site, not synced.
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(table_name = user_session_token)]
+pub struct UserSessionTokenRow {
+    /// SHA-256 hash of the token
+    pub id: String,
+    pub session_id: String,
+    pub user_id: String,
+    pub created_datetime: NaiveDateTime,
+    pub expiry_datetime: NaiveDateTime,
+}
+
+pub struct UserSessionTokenRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> UserSessionTokenRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        UserSessionTokenRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &UserSessionTokenRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(user_session_token)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<UserSessionTokenRow>, RepositoryError> {
+        let result = user_session_token
+            .filter(id.eq(token_hash))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Tokens that expire after `datetime`
+    pub fn find_unexpired(
+        &self,
+        datetime: NaiveDateTime,
+    ) -> Result<Vec<UserSessionTokenRow>, RepositoryError> {
+        let result = user_session_token
+            .filter(expiry_datetime.gt(datetime))
+            .order(created_datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete_by_session_id(&self, session: &str) -> Result<usize, RepositoryError> {
+        let result = diesel::delete(user_session_token.filter(session_id.eq(session)))
+            .execute(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete_by_user_id(&self, user: &str) -> Result<usize, RepositoryError> {
+        let result = diesel::delete(user_session_token.filter(user_id.eq(user)))
+            .execute(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    /// Removes tokens that expired on or before `datetime`
+    pub fn delete_expired(&self, datetime: NaiveDateTime) -> Result<usize, RepositoryError> {
+        let result = diesel::delete(user_session_token.filter(expiry_datetime.le(datetime)))
+            .execute(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
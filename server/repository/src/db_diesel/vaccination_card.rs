@@ -1,6 +1,17 @@
-use super::{vaccination_card::vaccination_card::dsl as vaccination_card_dsl, StorageConnection};
+use super::{
+    name_link_row::name_link,
+    name_row::name,
+    name_store_join::name_store_join,
+    program_enrolment_row::program_enrolment::{self, dsl as program_enrolment_dsl},
+    program_row::program,
+    vaccination_card::vaccination_card::dsl as vaccination_card_dsl,
+    StorageConnection,
+};
 
-use crate::{diesel_macros::apply_equal_filter, EqualFilter, RepositoryError};
+use crate::{
+    diesel_macros::apply_equal_filter, EqualFilter, ProgramEnrolmentFilter,
+    ProgramEnrolmentRepository, RepositoryError,
+};
 use diesel::prelude::*;
 
 table! {
@@ -24,6 +35,13 @@ table! {
     }
 }
 
+// Enrolments are filtered in a sub query, see query_by_enrolment_filter
+allow_tables_to_appear_in_same_query!(vaccination_card, program_enrolment);
+allow_tables_to_appear_in_same_query!(vaccination_card, program);
+allow_tables_to_appear_in_same_query!(vaccination_card, name_link);
+allow_tables_to_appear_in_same_query!(vaccination_card, name);
+allow_tables_to_appear_in_same_query!(vaccination_card, name_store_join);
+
 use chrono::NaiveDate;
 
 #[derive(Clone, Queryable, Debug, PartialEq, Default)]
@@ -72,4 +90,21 @@ impl<'a> VaccinationCardRepository<'a> {
             .order(vaccination_card_dsl::min_age.asc())
             .load::<VaccinationCardRow>(self.connection.lock().connection())?)
     }
+
+    /// Vaccination cards of all enrolments matching the filter, ordered by enrolment
+    pub fn query_by_enrolment_filter(
+        &self,
+        filter: ProgramEnrolmentFilter,
+    ) -> Result<Vec<VaccinationCardRow>, RepositoryError> {
+        let enrolment_ids = ProgramEnrolmentRepository::create_filtered_query(Some(filter))
+            .select(program_enrolment_dsl::id);
+
+        Ok(vaccination_card_dsl::vaccination_card
+            .filter(vaccination_card_dsl::program_enrolment_id.eq_any(enrolment_ids))
+            .order((
+                vaccination_card_dsl::program_enrolment_id.asc(),
+                vaccination_card_dsl::min_age.asc(),
+            ))
+            .load::<VaccinationCardRow>(self.connection.lock().connection())?)
+    }
 }
@@ -0,0 +1,130 @@
+use super::vaccination_reminder_row::vaccination_reminder::dsl::*;
+
+use crate::{RepositoryError, StorageConnection, Upsert};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    vaccination_reminder (id) {
+        id -> Text,
+        store_id -> Text,
+        program_enrolment_id -> Text,
+        vaccine_course_dose_id -> Text,
+        patient_link_id -> Text,
+        phone_number -> Text,
+        message -> Text,
+        due_date -> Date,
+        status -> crate::db_diesel::vaccination_reminder_row::VaccinationReminderStatusMapping,
+        created_datetime -> Timestamp,
+        sent_datetime -> Nullable<Timestamp>,
+        error_message -> Nullable<Text>,
+    }
+}
+
+#[derive(DbEnum, Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum VaccinationReminderStatus {
+    #[default]
+    Pending,
+    Sent,
+    Failed,
+}
+
+/// SMS reminder queued for a patient's upcoming or overdue vaccine dose. Reminders are local to
+/// the site sending them and are not synced.
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = vaccination_reminder)]
+pub struct VaccinationReminderRow {
+    pub id: String,
+    pub store_id: String,
+    pub program_enrolment_id: String,
+    pub vaccine_course_dose_id: String,
+    pub patient_link_id: String,
+    pub phone_number: String,
+    pub message: String,
+    pub due_date: NaiveDate,
+    pub status: VaccinationReminderStatus,
+    pub created_datetime: NaiveDateTime,
+    pub sent_datetime: Option<NaiveDateTime>,
+    pub error_message: Option<String>,
+}
+
+pub struct VaccinationReminderRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> VaccinationReminderRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        VaccinationReminderRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &VaccinationReminderRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(vaccination_reminder)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        reminder_id: &str,
+    ) -> Result<Option<VaccinationReminderRow>, RepositoryError> {
+        let result = vaccination_reminder
+            .filter(id.eq(reminder_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_store_id_and_status(
+        &self,
+        reminder_store_id: &str,
+        reminder_status: VaccinationReminderStatus,
+    ) -> Result<Vec<VaccinationReminderRow>, RepositoryError> {
+        let result = vaccination_reminder
+            .filter(store_id.eq(reminder_store_id))
+            .filter(status.eq(reminder_status))
+            .order(created_datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    /// Reminders already queued or sent for the dose, used to avoid reminding twice for the same
+    /// due date
+    pub fn find_many_by_dose(
+        &self,
+        reminder_program_enrolment_id: &str,
+        reminder_vaccine_course_dose_id: &str,
+    ) -> Result<Vec<VaccinationReminderRow>, RepositoryError> {
+        let result = vaccination_reminder
+            .filter(program_enrolment_id.eq(reminder_program_enrolment_id))
+            .filter(vaccine_course_dose_id.eq(reminder_vaccine_course_dose_id))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for VaccinationReminderRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        VaccinationReminderRowRepository::new(con).upsert_one(self)?;
+        Ok(None)
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            VaccinationReminderRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,182 @@
+use super::vaccine_vial_opening_row::{
+    vaccine_vial_opening::{self, dsl as vaccine_vial_opening_dsl},
+    VaccineVialOpeningRow, VaccineVialStatus,
+};
+
+use diesel::{dsl::IntoBoxed, prelude::*};
+use util::inline_init;
+
+use crate::{
+    diesel_macros::{apply_date_time_filter, apply_equal_filter, apply_sort},
+    repository_error::RepositoryError,
+    DBType, DatetimeFilter, EqualFilter, Pagination, Sort, StorageConnection,
+};
+
+pub type VaccineVialOpening = VaccineVialOpeningRow;
+
+pub enum VaccineVialOpeningSortField {
+    OpenedDatetime,
+    DiscardAfterDatetime,
+}
+
+pub type VaccineVialOpeningSort = Sort<VaccineVialOpeningSortField>;
+
+#[derive(Clone, Default)]
+pub struct VaccineVialOpeningFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub item_link_id: Option<EqualFilter<String>>,
+    pub stock_line_id: Option<EqualFilter<String>>,
+    pub status: Option<EqualFilter<VaccineVialStatus>>,
+    pub opened_datetime: Option<DatetimeFilter>,
+    pub discard_after_datetime: Option<DatetimeFilter>,
+}
+
+impl VaccineVialOpeningFilter {
+    pub fn new() -> VaccineVialOpeningFilter {
+        Self::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn item_link_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.item_link_id = Some(filter);
+        self
+    }
+
+    pub fn stock_line_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.stock_line_id = Some(filter);
+        self
+    }
+
+    pub fn status(mut self, filter: EqualFilter<VaccineVialStatus>) -> Self {
+        self.status = Some(filter);
+        self
+    }
+
+    pub fn opened_datetime(mut self, filter: DatetimeFilter) -> Self {
+        self.opened_datetime = Some(filter);
+        self
+    }
+
+    pub fn discard_after_datetime(mut self, filter: DatetimeFilter) -> Self {
+        self.discard_after_datetime = Some(filter);
+        self
+    }
+}
+
+pub struct VaccineVialOpeningRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> VaccineVialOpeningRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        VaccineVialOpeningRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<VaccineVialOpeningFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+        Ok(query
+            .count()
+            .get_result(self.connection.lock().connection())?)
+    }
+
+    pub fn query_one(
+        &self,
+        filter: VaccineVialOpeningFilter,
+    ) -> Result<Option<VaccineVialOpening>, RepositoryError> {
+        Ok(self.query_by_filter(filter)?.pop())
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: VaccineVialOpeningFilter,
+    ) -> Result<Vec<VaccineVialOpening>, RepositoryError> {
+        self.query(Pagination::all(), Some(filter), None)
+    }
+
+    pub fn query(
+        &self,
+        pagination: Pagination,
+        filter: Option<VaccineVialOpeningFilter>,
+        sort: Option<VaccineVialOpeningSort>,
+    ) -> Result<Vec<VaccineVialOpening>, RepositoryError> {
+        let mut query = create_filtered_query(filter);
+
+        if let Some(sort) = sort {
+            match sort.key {
+                VaccineVialOpeningSortField::OpenedDatetime => {
+                    apply_sort!(query, sort, vaccine_vial_opening_dsl::opened_datetime);
+                }
+                VaccineVialOpeningSortField::DiscardAfterDatetime => {
+                    apply_sort!(query, sort, vaccine_vial_opening_dsl::discard_after_datetime);
+                }
+            }
+        } else {
+            query = query.order((
+                vaccine_vial_opening_dsl::opened_datetime.asc(),
+                vaccine_vial_opening_dsl::id.asc(),
+            ))
+        }
+
+        let result = query
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64)
+            .load::<VaccineVialOpeningRow>(self.connection.lock().connection())?;
+
+        Ok(result)
+    }
+}
+
+type BoxedVaccineVialOpeningQuery = IntoBoxed<'static, vaccine_vial_opening::table, DBType>;
+
+fn create_filtered_query(filter: Option<VaccineVialOpeningFilter>) -> BoxedVaccineVialOpeningQuery {
+    let mut query = vaccine_vial_opening_dsl::vaccine_vial_opening.into_boxed();
+
+    if let Some(f) = filter {
+        let VaccineVialOpeningFilter {
+            id,
+            store_id,
+            item_link_id,
+            stock_line_id,
+            status,
+            opened_datetime,
+            discard_after_datetime,
+        } = f;
+
+        apply_equal_filter!(query, id, vaccine_vial_opening_dsl::id);
+        apply_equal_filter!(query, store_id, vaccine_vial_opening_dsl::store_id);
+        apply_equal_filter!(query, item_link_id, vaccine_vial_opening_dsl::item_link_id);
+        apply_equal_filter!(query, stock_line_id, vaccine_vial_opening_dsl::stock_line_id);
+        apply_equal_filter!(query, status, vaccine_vial_opening_dsl::status);
+        apply_date_time_filter!(
+            query,
+            opened_datetime,
+            vaccine_vial_opening_dsl::opened_datetime
+        );
+        apply_date_time_filter!(
+            query,
+            discard_after_datetime,
+            vaccine_vial_opening_dsl::discard_after_datetime
+        );
+    }
+    query
+}
+
+impl VaccineVialStatus {
+    pub fn equal_to(&self) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_to = Some(self.clone()))
+    }
+
+    pub fn equal_any(value: Vec<Self>) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_any = Some(value))
+    }
+}
@@ -0,0 +1,126 @@
+use super::vaccine_vial_opening_row::vaccine_vial_opening::dsl::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    StorageConnection, Upsert,
+};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+
+table! {
+    vaccine_vial_opening (id) {
+        id -> Text,
+        store_id -> Text,
+        item_link_id -> Text,
+        stock_line_id -> Text,
+        status -> crate::db_diesel::vaccine_vial_opening_row::VaccineVialStatusMapping,
+        doses_per_vial -> Integer,
+        doses_drawn -> Integer,
+        wasted_doses -> Integer,
+        opened_datetime -> Timestamp,
+        discard_after_datetime -> Timestamp,
+        closed_datetime -> Nullable<Timestamp>,
+        inventory_adjustment_id -> Nullable<Text>,
+    }
+}
+
+#[derive(DbEnum, Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum VaccineVialStatus {
+    #[default]
+    Open,
+    /// All doses in the vial were drawn
+    Empty,
+    /// Vial was thrown away with doses left in it, e.g. after the open vial policy period
+    Discarded,
+}
+
+#[derive(
+    Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = vaccine_vial_opening)]
+pub struct VaccineVialOpeningRow {
+    pub id: String,
+    pub store_id: String,
+    pub item_link_id: String,
+    pub stock_line_id: String,
+    pub status: VaccineVialStatus,
+    pub doses_per_vial: i32,
+    pub doses_drawn: i32,
+    pub wasted_doses: i32,
+    pub opened_datetime: NaiveDateTime,
+    /// End of the open vial policy period, remaining doses are wasted after this time
+    pub discard_after_datetime: NaiveDateTime,
+    pub closed_datetime: Option<NaiveDateTime>,
+    /// Inventory adjustment that removed the wasted doses from stock
+    pub inventory_adjustment_id: Option<String>,
+}
+
+pub struct VaccineVialOpeningRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> VaccineVialOpeningRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        VaccineVialOpeningRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &VaccineVialOpeningRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(vaccine_vial_opening)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &VaccineVialOpeningRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::VaccineVialOpening,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        vial_opening_id: &str,
+    ) -> Result<Option<VaccineVialOpeningRow>, RepositoryError> {
+        let result = vaccine_vial_opening
+            .filter(id.eq(vial_opening_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+}
+
+impl Upsert for VaccineVialOpeningRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = VaccineVialOpeningRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            VaccineVialOpeningRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,260 @@
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    fs,
+    time::{Duration, Instant},
+};
+
+use diesel::connection::SimpleConnection;
+use regex::Regex;
+
+use super::{get_database_version, migrate_etc, MigrationError, MigrationStep, SqlError, Version};
+use crate::{
+    database_settings::DatabaseSettings, get_storage_connection_manager, RepositoryError,
+    StorageConnection, StorageConnectionManager, TransactionError,
+};
+
+#[derive(Debug)]
+pub struct MigrationDryRun {
+    pub from_version: Version,
+    pub to_version: Version,
+    /// Migrations that ran before an error or until the end
+    pub steps: Vec<MigrationStep>,
+    pub duration: Duration,
+    pub error: Option<MigrationError>,
+}
+
+/// Runs pending migrations without changing the database and reports the time taken by each
+/// migration and the first error.
+/// Postgres migrations run in a transaction that is rolled back. Sqlite migrations run on a copy
+/// of the database next to the database file, which is removed afterwards.
+///
+/// Postgres doesn't allow using an enum value in the transaction that added it, so
+/// `ALTER TYPE .. ADD VALUE IF NOT EXISTS` statements of pending migrations are committed on a
+/// separate connection, see [commit_enum_values]. The new enum values are not used until the
+/// migrations run, and the migrations skip them as they already exist.
+pub fn dry_run_migrations(
+    connection_manager: &StorageConnectionManager,
+    settings: &DatabaseSettings,
+    to_version: Option<Version>,
+) -> Result<MigrationDryRun, RepositoryError> {
+    let connection = connection_manager.connection()?;
+
+    let sqlite_file = match &settings.database_path {
+        Some(path) => format!("{}/{}", path, settings.connection_string()),
+        None => settings.connection_string(),
+    };
+    // In memory databases can't be copied to a file
+    if connection.backend().is_postgres() {
+        let _enum_value_connection = EnumValueConnection::set(connection_manager.connection()?);
+        return dry_run_in_transaction(&connection, to_version);
+    }
+    if sqlite_file.starts_with("file:") {
+        return dry_run_in_transaction(&connection, to_version);
+    }
+
+    let copy_file = format!("{}.dry_run.sqlite", sqlite_file.trim_end_matches(".sqlite"));
+    remove_sqlite_files(&copy_file);
+    connection
+        .lock()
+        .connection()
+        .batch_execute(&format!("VACUUM INTO '{}';", copy_file.replace('\'', "''")))?;
+
+    let copy_settings = DatabaseSettings {
+        database_name: copy_file.clone(),
+        database_path: None,
+        init_sql: None,
+        ..settings.clone()
+    };
+    let result = dry_run(
+        &get_storage_connection_manager(&copy_settings).connection()?,
+        to_version,
+    );
+    remove_sqlite_files(&copy_file);
+
+    Ok(result)
+}
+
+fn dry_run_in_transaction(
+    connection: &StorageConnection,
+    to_version: Option<Version>,
+) -> Result<MigrationDryRun, RepositoryError> {
+    // Returning an error rolls back the transaction
+    let result =
+        connection.transaction_sync_etc(|con| Err::<(), _>(dry_run(con, to_version)), false);
+
+    match result {
+        Err(TransactionError::Inner(dry_run)) => Ok(dry_run),
+        Err(TransactionError::Transaction { msg, level }) => {
+            Err(RepositoryError::TransactionError { msg, level })
+        }
+        Ok(()) => unreachable!("dry run transaction is always rolled back"),
+    }
+}
+
+fn dry_run(connection: &StorageConnection, to_version: Option<Version>) -> MigrationDryRun {
+    let from_version = get_database_version(connection);
+    let start = Instant::now();
+    let mut steps = Vec::new();
+    let result = migrate_etc(connection, to_version.clone(), &mut steps);
+
+    MigrationDryRun {
+        from_version,
+        to_version: to_version.unwrap_or(Version::from_package_json()),
+        steps,
+        duration: start.elapsed(),
+        error: result.err(),
+    }
+}
+
+thread_local! {
+    static ENUM_VALUE_CONNECTION: RefCell<Option<StorageConnection>> = const { RefCell::new(None) };
+}
+
+/// Connection used by [commit_enum_values] on this thread while it's in scope
+struct EnumValueConnection;
+
+impl EnumValueConnection {
+    fn set(connection: StorageConnection) -> Self {
+        ENUM_VALUE_CONNECTION.with(|current| current.replace(Some(connection)));
+        EnumValueConnection
+    }
+}
+
+impl Drop for EnumValueConnection {
+    fn drop(&mut self) {
+        ENUM_VALUE_CONNECTION.with(|current| current.replace(None));
+    }
+}
+
+/// During a Postgres dry run, executes the `ALTER TYPE .. ADD VALUE IF NOT EXISTS` statements of
+/// `query` outside of the dry run transaction and returns the remaining statements. Enum values
+/// added without `IF NOT EXISTS` stay in the transaction, committing them would make the
+/// migration fail when it runs.
+pub(super) fn commit_enum_values(query: &str) -> Result<Cow<str>, SqlError> {
+    ENUM_VALUE_CONNECTION.with(|current| {
+        let current = current.borrow();
+        let Some(connection) = current.as_ref() else {
+            return Ok(Cow::Borrowed(query));
+        };
+
+        let re = Regex::new(
+            r"(?i)ALTER\s+TYPE\s+\w+\s+ADD\s+VALUE\s+IF\s+NOT\s+EXISTS\s+'[^']*'(\s+(AFTER|BEFORE)\s+'[^']*')?\s*;?",
+        )
+        .unwrap();
+        for statement in re.find_iter(query) {
+            connection
+                .lock()
+                .connection()
+                .batch_execute(statement.as_str())
+                .map_err(|source| SqlError(statement.as_str().to_string(), source.into()))?;
+        }
+        Ok(re.replace_all(query, ""))
+    })
+}
+
+fn remove_sqlite_files(file: &str) {
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = fs::remove_file(format!("{file}{suffix}"));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        migrations::{dry_run_migrations, get_migrations_status, MigrationState, Version},
+        mock::MockDataInserts,
+        test_db::{setup_test, SetupOption, SetupResult},
+        KeyType, KeyValueStoreRepository,
+    };
+
+    #[actix_rt::test]
+    async fn migration_dry_run() {
+        let previous_version = Version::from_str("2.4.0");
+        let SetupResult {
+            connection,
+            connection_manager,
+            db_settings,
+            ..
+        } = setup_test(SetupOption {
+            db_name: "migration_dry_run",
+            version: Some(previous_version.clone()),
+            inserts: MockDataInserts::none(),
+            ..Default::default()
+        })
+        .await;
+
+        let status = get_migrations_status(&connection).unwrap();
+        assert_eq!(status.database_version, previous_version);
+        assert!(status.has_pending());
+        let pending = status
+            .migrations
+            .iter()
+            .find(|migration| migration.version == Version::from_str("2.5.0"))
+            .unwrap();
+        assert_eq!(pending.state, MigrationState::Pending);
+
+        let result = dry_run_migrations(&connection_manager, &db_settings, None).unwrap();
+        assert!(result.error.is_none(), "{:?}", result.error);
+        assert_eq!(result.from_version, previous_version);
+        assert!(result
+            .steps
+            .iter()
+            .any(|step| step.version == Version::from_str("2.5.0")));
+
+        // Database is unchanged
+        assert_eq!(
+            KeyValueStoreRepository::new(&connection)
+                .get_string(KeyType::DatabaseVersion)
+                .unwrap(),
+            Some(previous_version.to_string())
+        );
+        assert!(get_migrations_status(&connection).unwrap().has_pending());
+    }
+
+    #[cfg(feature = "postgres")]
+    #[actix_rt::test]
+    async fn migration_dry_run_postgres_enum_values() {
+        use crate::migrations::migrate;
+        use diesel::connection::SimpleConnection;
+
+        // 2.5.0 adds changelog_table_name values and uses them in the same fragment
+        let previous_version = Version::from_str("2.4.0");
+        let SetupResult {
+            connection,
+            connection_manager,
+            db_settings,
+            ..
+        } = setup_test(SetupOption {
+            db_name: "migration_dry_run_postgres_enum_values",
+            version: Some(previous_version.clone()),
+            inserts: MockDataInserts::none(),
+            ..Default::default()
+        })
+        .await;
+
+        let result = dry_run_migrations(&connection_manager, &db_settings, None).unwrap();
+        assert!(result.error.is_none(), "{:?}", result.error);
+        assert!(result
+            .steps
+            .iter()
+            .any(|step| step.fragment == Some("add_plugin_data_sync")));
+
+        // Enum value is committed, the migration is not
+        let enum_value_exists = connection.lock().connection().batch_execute(
+            r#"
+            DO $$ BEGIN
+                IF NOT EXISTS (SELECT 1 FROM pg_enum WHERE enumlabel = 'plugin_data') THEN
+                    RAISE EXCEPTION 'plugin_data enum value is missing';
+                END IF;
+            END $$;
+            "#,
+        );
+        assert!(enum_value_exists.is_ok(), "{:?}", enum_value_exists);
+        assert!(get_migrations_status(&connection).unwrap().has_pending());
+
+        // Migrations still run after the dry run
+        migrate(&connection, None).unwrap();
+        assert!(!get_migrations_status(&connection).unwrap().has_pending());
+    }
+}
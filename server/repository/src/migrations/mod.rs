@@ -1,4 +1,6 @@
 pub mod constants;
+mod dry_run;
+mod status;
 mod types;
 mod v1_00_04;
 mod v1_01_01;
@@ -40,6 +42,8 @@ use self::v1_01_03::V1_01_03;
 pub(crate) mod helpers;
 mod templates;
 
+pub use self::dry_run::*;
+pub use self::status::*;
 pub use self::version::*;
 
 use crate::{
@@ -47,6 +51,7 @@ use crate::{
     RepositoryError, StorageConnection,
 };
 use diesel::connection::SimpleConnection;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 pub(crate) trait Migration {
@@ -77,6 +82,8 @@ pub enum MigrationError {
     DatabaseVersionIsPreRelease(Version),
     #[error("Migration version ({0}) is higher then app version ({1}), consider increasing app version in root package.json")]
     MigrationAboveAppVersion(Version, Version),
+    #[error("Error running diesel migrations: {0}")]
+    DieselMigrationError(String),
     #[error("Problem dropping or re-creating views")]
     DatabaseViewsError(anyhow::Error),
     #[error("Error during one time migration ({version})")]
@@ -94,11 +101,17 @@ pub enum MigrationError {
     DatabaseError(#[from] RepositoryError),
 }
 
-pub fn migrate(
-    connection: &StorageConnection,
-    to_version: Option<Version>,
-) -> Result<Version, MigrationError> {
-    let migrations: Vec<Box<dyn Migration>> = vec![
+/// Time taken by a one time or fragment migration
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationStep {
+    pub version: Version,
+    /// None for one time migrations
+    pub fragment: Option<&'static str>,
+    pub duration: Duration,
+}
+
+fn migrations() -> Vec<Box<dyn Migration>> {
+    vec![
         Box::new(V1_00_04),
         Box::new(V1_01_01),
         Box::new(V1_01_02),
@@ -127,10 +140,25 @@ pub fn migrate(
         Box::new(v2_04_00::V2_04_00),
         Box::new(v2_04_01::V2_04_01),
         Box::new(v2_05_00::V2_05_00),
-    ];
+    ]
+}
+
+pub fn migrate(
+    connection: &StorageConnection,
+    to_version: Option<Version>,
+) -> Result<Version, MigrationError> {
+    migrate_etc(connection, to_version, &mut Vec::new())
+}
 
+/// # Arguments
+/// * `steps` - collects the duration of each one time and fragment migration that ran
+fn migrate_etc(
+    connection: &StorageConnection,
+    to_version: Option<Version>,
+    steps: &mut Vec<MigrationStep>,
+) -> Result<Version, MigrationError> {
     // Historic diesel migrations
-    run_db_migrations(connection).unwrap();
+    run_db_migrations(connection).map_err(MigrationError::DieselMigrationError)?;
 
     // Rust migrations
     let to_version = to_version.unwrap_or(Version::from_package_json());
@@ -153,7 +181,7 @@ pub fn migrate(
     let min_version_for_dropping_views = v2_03_00::V2_03_00.version();
     let mut drop_view_has_run = false;
 
-    for migration in migrations {
+    for migration in migrations() {
         let migration_version = migration.version();
 
         if migration_version > to_version {
@@ -182,12 +210,18 @@ pub fn migrate(
         // Run one time migrations
         if migration_version > database_version {
             log::info!("Running one time database migration {}", migration_version);
+            let start = Instant::now();
             migration
                 .migrate(connection)
                 .map_err(|source| MigrationError::MigrationError {
                     source,
                     version: migration_version.clone(),
                 })?;
+            steps.push(MigrationStep {
+                version: migration_version.clone(),
+                fragment: None,
+                duration: start.elapsed(),
+            });
             set_database_version(connection, &migration_version)?;
         }
 
@@ -198,6 +232,7 @@ pub fn migrate(
                     continue;
                 }
 
+                let start = Instant::now();
                 fragment.migrate(connection).map_err(|source| {
                     MigrationError::FragmentMigrationError {
                         source,
@@ -206,6 +241,11 @@ pub fn migrate(
                     }
                 })?;
 
+                steps.push(MigrationStep {
+                    version: migration_version.clone(),
+                    fragment: Some(fragment.identifier()),
+                    duration: start.elapsed(),
+                });
                 migration_fragment_log_repo.insert(&migration, &fragment)?;
             }
         }
@@ -281,6 +321,10 @@ pub(crate) fn batch_execute_sql_with_error(
     connection: &StorageConnection,
     query: &str,
 ) -> Result<(), SqlError> {
+    let query = &dry_run::commit_enum_values(query)?;
+    if query.trim().is_empty() {
+        return Ok(());
+    }
     connection
         .lock()
         .connection()
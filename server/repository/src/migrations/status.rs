@@ -0,0 +1,105 @@
+use super::{create_migration_fragment_table, get_database_version, migrations, Version};
+use crate::{
+    KeyType, KeyValueStoreRepository, MigrationFragmentLogRepository, RepositoryError,
+    StorageConnection,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationState {
+    Applied,
+    /// Will run on the next migration
+    Pending,
+    /// Fragment of a version below the database version that never ran, it will not run
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FragmentStatus {
+    pub identifier: &'static str,
+    pub state: MigrationState,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationStatus {
+    pub version: Version,
+    /// State of the one time migration
+    pub state: MigrationState,
+    pub fragments: Vec<FragmentStatus>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationsStatus {
+    pub database_version: Version,
+    /// No migrations have run yet, e.g. the database was just created
+    pub is_new_database: bool,
+    pub app_version: Version,
+    pub migrations: Vec<MigrationStatus>,
+}
+
+impl MigrationsStatus {
+    pub fn has_pending(&self) -> bool {
+        self.migrations.iter().any(|migration| {
+            migration.state == MigrationState::Pending
+                || migration
+                    .fragments
+                    .iter()
+                    .any(|fragment| fragment.state == MigrationState::Pending)
+        })
+    }
+}
+
+/// Applied and pending one time and fragment migrations, without running any of them
+pub fn get_migrations_status(
+    connection: &StorageConnection,
+) -> Result<MigrationsStatus, RepositoryError> {
+    let database_version = get_database_version(connection);
+    // key_value_store doesn't exist before the first migration
+    let is_new_database = !matches!(
+        KeyValueStoreRepository::new(connection).get_string(KeyType::DatabaseVersion),
+        Ok(Some(_))
+    );
+    let app_version = Version::from_package_json();
+
+    create_migration_fragment_table(connection)?;
+    let migration_fragment_log_repo = MigrationFragmentLogRepository::new(connection);
+
+    let mut result = Vec::new();
+    for migration in migrations() {
+        let version = migration.version();
+        if version > app_version {
+            break;
+        }
+
+        let mut fragments = Vec::new();
+        for fragment in migration.migrate_fragments() {
+            let state = if migration_fragment_log_repo.has_run(&migration, &fragment)? {
+                MigrationState::Applied
+            } else if version >= database_version {
+                MigrationState::Pending
+            } else {
+                MigrationState::Skipped
+            };
+            fragments.push(FragmentStatus {
+                identifier: fragment.identifier(),
+                state,
+            });
+        }
+
+        result.push(MigrationStatus {
+            state: if version > database_version {
+                MigrationState::Pending
+            } else {
+                MigrationState::Applied
+            },
+            version,
+            fragments,
+        });
+    }
+
+    Ok(MigrationsStatus {
+        database_version,
+        is_new_database,
+        app_version,
+        migrations: result,
+    })
+}
@@ -76,7 +76,7 @@ impl Migration for V1_00_05 {
                     id {serial}
                 );
                 "#,
-            serial = if cfg!(feature = "postgres") {
+            serial = if connection.backend().is_postgres() {
                 "BIGSERIAL NOT NULL PRIMARY KEY"
             } else {
                 "INTEGER PRIMARY KEY"
@@ -2,7 +2,7 @@ use crate::{migrations::sql, StorageConnection};
 
 pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
     // Split INVENTORY_ADJUSTMENT to INVENTORY_REDUCTION and INVENTORY_ADDITION
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
@@ -30,7 +30,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
     )?;
 
     // Update inventory_adjustment_stock_movement VIEW to use INVENTORY_REDUCTION and INVENTORY_ADDITION
-    let create_or_replace_view = if cfg!(feature = "postgres") {
+    let create_or_replace_view = if connection.backend().is_postgres() {
         "CREATE OR REPLACE VIEW"
     } else {
         "DROP VIEW inventory_adjustment_stock_movement; CREATE VIEW"
@@ -2,7 +2,7 @@ use crate::migrations::{DATETIME, DOUBLE};
 use crate::{migrations::sql, StorageConnection};
 
 pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
@@ -52,7 +52,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
             "#
     )?;
 
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
@@ -3,7 +3,7 @@ use crate::StorageConnection;
 pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
     use crate::migrations::sql;
 
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
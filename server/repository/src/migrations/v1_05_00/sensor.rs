@@ -36,7 +36,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
             "#
     )?;
 
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
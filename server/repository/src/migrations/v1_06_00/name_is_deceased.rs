@@ -8,7 +8,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
       "#
     )?;
 
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
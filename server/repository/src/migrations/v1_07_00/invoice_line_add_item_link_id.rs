@@ -98,7 +98,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
         "#,
     )?;
 
-    let (casting, absolute) = if cfg!(feature = "postgres") {
+    let (casting, absolute) = if connection.backend().is_postgres() {
         ("::BIGINT", "@")
     } else {
         ("", "abs")
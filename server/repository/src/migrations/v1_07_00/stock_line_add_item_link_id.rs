@@ -34,7 +34,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
      "#,
     )?;
 
-    let casting = if cfg!(feature = "postgres") {
+    let casting = if connection.backend().is_postgres() {
         "::BIGINT"
     } else {
         ""
@@ -42,7 +42,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
         "#,
     )?;
 
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
@@ -54,7 +54,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
         )?;
     }
 
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
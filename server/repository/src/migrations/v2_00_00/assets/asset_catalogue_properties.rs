@@ -4,13 +4,13 @@ use crate::{
 };
 
 pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
-    const PROPERTY_VALUE_TYPE: &str = if cfg!(feature = "postgres") {
+    const PROPERTY_VALUE_TYPE: &str = if connection.backend().is_postgres() {
         "PROPERTY_VALUE_TYPE"
     } else {
         "TEXT"
     };
 
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
@@ -45,7 +45,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
         "#
     )?;
 
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
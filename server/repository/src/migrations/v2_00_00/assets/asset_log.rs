@@ -16,7 +16,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
         "#
     )?;
 
-    const ASSET_LOG_STATUS_ENUM_TYPE: &str = if cfg!(feature = "postgres") {
+    const ASSET_LOG_STATUS_ENUM_TYPE: &str = if connection.backend().is_postgres() {
         "asset_log_status"
     } else {
         "TEXT"
@@ -38,7 +38,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
         "#,
     )?;
 
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
@@ -2,7 +2,7 @@ use crate::{migrations::sql, StorageConnection};
 
 pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
     // Asset Class triggers
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
@@ -40,7 +40,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
     }
 
     // Asset Category triggers
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
@@ -77,7 +77,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
     }
 
     // Asset Type triggers
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
@@ -114,7 +114,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
     }
 
     // Asset Catalogue Item triggers
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
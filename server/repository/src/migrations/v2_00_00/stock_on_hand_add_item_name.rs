@@ -1,7 +1,7 @@
 use crate::{migrations::sql, StorageConnection};
 
 pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
-    let casting = if cfg!(feature = "postgres") {
+    let casting = if connection.backend().is_postgres() {
         "::BIGINT"
     } else {
         ""
@@ -18,12 +18,12 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
         );
         "#,
     )?;
-    const SYNC_FILE_STATUS_ENUM_TYPE: &str = if cfg!(feature = "postgres") {
+    const SYNC_FILE_STATUS_ENUM_TYPE: &str = if connection.backend().is_postgres() {
         "sync_file_status"
     } else {
         "TEXT"
     };
-    const SYNC_FILE_DIRECTION_ENUM_TYPE: &str = if cfg!(feature = "postgres") {
+    const SYNC_FILE_DIRECTION_ENUM_TYPE: &str = if connection.backend().is_postgres() {
         "sync_file_direction"
     } else {
         "TEXT"
@@ -52,7 +52,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
         "#,
     )?;
 
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
@@ -16,7 +16,7 @@ pub(crate) fn drop_ledger_views(connection: &StorageConnection) -> anyhow::Resul
 }
 
 pub(crate) fn create_ledger_views(connection: &StorageConnection) -> anyhow::Result<()> {
-    let absolute = if cfg!(feature = "postgres") {
+    let absolute = if connection.backend().is_postgres() {
         "@"
     } else {
         "abs"
@@ -20,7 +20,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
     )?;
 
     // Add postgres enum options
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
@@ -33,7 +33,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
     }
 
     // remove name changelog triggers (done in code now)
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
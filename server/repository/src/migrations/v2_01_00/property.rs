@@ -1,7 +1,7 @@
 use crate::{migrations::sql, StorageConnection};
 
 pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
-    const PROPERTY_VALUE_TYPE: &str = if cfg!(feature = "postgres") {
+    const PROPERTY_VALUE_TYPE: &str = if connection.backend().is_postgres() {
         "property_value_type" // This is created as part of the asset_catalogue_property migration
     } else {
         "TEXT"
@@ -20,7 +20,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
         "#
     )?;
 
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
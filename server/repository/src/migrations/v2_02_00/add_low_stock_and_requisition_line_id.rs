@@ -8,7 +8,7 @@ impl MigrationFragment for Migrate {
     }
 
     fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
-        if cfg!(feature = "postgres") {
+        if connection.backend().is_postgres() {
             sql!(
                 connection,
                 r#"
@@ -21,7 +21,7 @@ impl MigrationFragment for Migrate {
             )?;
         }
 
-        const RNR_LOW_STOCK_ENUM_TYPE: &str = if cfg!(feature = "postgres") {
+        const RNR_LOW_STOCK_ENUM_TYPE: &str = if connection.backend().is_postgres() {
             "rn_r_form_low_stock"
         } else {
             "TEXT"
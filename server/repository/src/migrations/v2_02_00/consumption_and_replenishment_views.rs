@@ -8,7 +8,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
         "#
     )?;
 
-    let absolute = if cfg!(feature = "postgres") {
+    let absolute = if connection.backend().is_postgres() {
         "@"
     } else {
         "abs"
@@ -14,7 +14,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
         "#
     )?;
 
-    const VEN_CATEGORY_ENUM_TYPE: &str = if cfg!(feature = "postgres") {
+    const VEN_CATEGORY_ENUM_TYPE: &str = if connection.backend().is_postgres() {
         "ven_category"
     } else {
         "TEXT"
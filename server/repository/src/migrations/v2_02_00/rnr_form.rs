@@ -15,7 +15,7 @@ pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
         "#
     )?;
 
-    const RNR_FORM_STATUS_ENUM_TYPE: &str = if cfg!(feature = "postgres") {
+    const RNR_FORM_STATUS_ENUM_TYPE: &str = if connection.backend().is_postgres() {
         "rn_r_form_status"
     } else {
         "TEXT"
@@ -1,7 +1,7 @@
 use crate::{migrations::*, StorageConnection};
 
 pub fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
@@ -41,7 +41,7 @@ impl MigrationFragment for Migrate {
             "#
         )?;
 
-        if cfg!(feature = "postgres") {
+        if connection.backend().is_postgres() {
             sql!(
                 connection,
                 r#"
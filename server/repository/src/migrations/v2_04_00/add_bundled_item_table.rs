@@ -21,7 +21,7 @@ impl MigrationFragment for Migrate {
             "#
         )?;
 
-        if cfg!(feature = "postgres") {
+        if connection.backend().is_postgres() {
             // Postgres changelog variant
             sql!(
                 connection,
@@ -19,7 +19,7 @@ impl MigrationFragment for Migrate {
         // Add the expected lifespan at 10 years for all cold chain equipment assets
         // properties are stored as JSON so we nee to update the JSON object
 
-        if cfg!(feature = "postgres") {
+        if connection.backend().is_postgres() {
             sql!(
                 connection,
                 r#"
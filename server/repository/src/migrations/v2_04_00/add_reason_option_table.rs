@@ -8,7 +8,7 @@ impl MigrationFragment for Migrate {
     }
 
     fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
-        if cfg!(feature = "postgres") {
+        if connection.backend().is_postgres() {
             sql!(
                 connection,
                 r#"
@@ -22,7 +22,7 @@ impl MigrationFragment for Migrate {
             )?;
         }
 
-        const OPTION_TYPE_ENUM: &str = if cfg!(feature = "postgres") {
+        const OPTION_TYPE_ENUM: &str = if connection.backend().is_postgres() {
             "reason_option_type"
         } else {
             "TEXT"
@@ -7,7 +7,7 @@ impl MigrationFragment for Migrate {
     }
 
     fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
-        if cfg!(feature = "postgres") {
+        if connection.backend().is_postgres() {
             sql!(
                 connection,
                 r#"
@@ -19,7 +19,7 @@ impl MigrationFragment for Migrate {
             )?
         }
 
-        let value_type = if cfg!(feature = "postgres") {
+        let value_type = if connection.backend().is_postgres() {
             "indicator_value_type"
         } else {
             "TEXT"
@@ -8,7 +8,7 @@ impl MigrationFragment for Migrate {
     }
 
     fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
-        if cfg!(feature = "postgres") {
+        if connection.backend().is_postgres() {
             // Postgres changelog variant
             sql!(
                 connection,
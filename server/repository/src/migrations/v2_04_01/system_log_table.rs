@@ -8,7 +8,7 @@ impl MigrationFragment for Migrate {
     }
 
     fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
-        if cfg!(feature = "postgres") {
+        if connection.backend().is_postgres() {
             sql!(
                 connection,
                 "CREATE TYPE system_log_type AS ENUM ('PROCESSOR_ERROR');"
@@ -20,7 +20,7 @@ impl MigrationFragment for Migrate {
             )?;
         }
 
-        let system_log_type = if cfg!(feature = "postgres") {
+        let system_log_type = if connection.backend().is_postgres() {
             "system_log_type"
         } else {
             "TEXT"
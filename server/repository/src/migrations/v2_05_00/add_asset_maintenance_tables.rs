@@ -0,0 +1,88 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_asset_maintenance_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if connection.backend().is_postgres() {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE asset_maintenance_schedule_type AS ENUM (
+                    'INTERVAL',
+                    'USAGE'
+                );
+                CREATE TYPE asset_work_order_status AS ENUM (
+                    'PLANNED',
+                    'IN_PROGRESS',
+                    'COMPLETED',
+                    'CANCELLED'
+                );
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'asset_maintenance_plan';
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'asset_work_order';
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'asset_work_order_spare_part';
+                "#
+            )?;
+        }
+
+        let (schedule_type, work_order_status) = if connection.backend().is_postgres() {
+            ("asset_maintenance_schedule_type", "asset_work_order_status")
+        } else {
+            ("TEXT", "TEXT")
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE asset_maintenance_plan (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    asset_catalogue_type_id TEXT NOT NULL REFERENCES asset_catalogue_type(id),
+                    name TEXT NOT NULL,
+                    description TEXT,
+                    schedule_type {schedule_type} NOT NULL,
+                    interval_days INTEGER,
+                    usage_interval {DOUBLE},
+                    usage_unit TEXT,
+                    checklist TEXT,
+                    is_active BOOLEAN NOT NULL DEFAULT TRUE
+                );
+
+                CREATE TABLE asset_work_order (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    asset_id TEXT NOT NULL REFERENCES asset(id),
+                    maintenance_plan_id TEXT REFERENCES asset_maintenance_plan(id),
+                    store_id TEXT,
+                    status {work_order_status} NOT NULL,
+                    due_date {DATE} NOT NULL,
+                    due_usage {DOUBLE},
+                    usage_reading {DOUBLE},
+                    checklist TEXT,
+                    comment TEXT,
+                    created_datetime {DATETIME} NOT NULL,
+                    completed_datetime {DATETIME},
+                    completed_by_id TEXT,
+                    asset_log_id TEXT
+                );
+
+                CREATE TABLE asset_work_order_spare_part (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    work_order_id TEXT NOT NULL REFERENCES asset_work_order(id),
+                    item_link_id TEXT NOT NULL REFERENCES item_link(id),
+                    stock_line_id TEXT NOT NULL,
+                    number_of_packs {DOUBLE} NOT NULL,
+                    invoice_id TEXT NOT NULL
+                );
+
+                CREATE INDEX index_asset_work_order_asset_id ON asset_work_order (asset_id);
+                CREATE INDEX index_asset_work_order_due_date ON asset_work_order (due_date);
+                CREATE INDEX index_asset_work_order_spare_part_work_order_id ON asset_work_order_spare_part (work_order_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_audit_trail_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if connection.backend().is_postgres() {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE audit_action AS ENUM (
+                    'INSERT',
+                    'UPDATE',
+                    'DELETE'
+                );
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'audit_trail';
+                "#
+            )?;
+        }
+
+        let (table_name, audit_action) = if connection.backend().is_postgres() {
+            ("changelog_table_name", "audit_action")
+        } else {
+            ("TEXT", "TEXT")
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE audit_trail (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    record_table_name {table_name} NOT NULL,
+                    record_id TEXT NOT NULL,
+                    action {audit_action} NOT NULL,
+                    changes TEXT NOT NULL,
+                    user_id TEXT,
+                    store_id TEXT,
+                    datetime {DATETIME} NOT NULL
+                );
+
+                CREATE INDEX index_audit_trail_record_id ON audit_trail (record_id);
+                CREATE INDEX index_audit_trail_store_id_datetime ON audit_trail (store_id, datetime);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
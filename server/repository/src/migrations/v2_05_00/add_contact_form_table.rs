@@ -8,7 +8,7 @@ impl MigrationFragment for Migrate {
     }
 
     fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
-        if cfg!(feature = "postgres") {
+        if connection.backend().is_postgres() {
             sql!(
                 connection,
                 r#"
@@ -20,7 +20,7 @@ impl MigrationFragment for Migrate {
             )?
         }
 
-        let contact_type = if cfg!(feature = "postgres") {
+        let contact_type = if connection.backend().is_postgres() {
             "contact_type_enum"
         } else {
             "TEXT"
@@ -42,7 +42,7 @@ impl MigrationFragment for Migrate {
             "#
         )?;
 
-        if cfg!(feature = "postgres") {
+        if connection.backend().is_postgres() {
             // Postgres changelog variant
             sql!(
                 connection,
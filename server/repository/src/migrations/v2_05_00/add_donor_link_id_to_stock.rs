@@ -0,0 +1,22 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_donor_link_id_to_stock"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                ALTER TABLE stock_line ADD COLUMN donor_link_id TEXT REFERENCES name_link(id);
+                ALTER TABLE invoice_line ADD COLUMN donor_link_id TEXT REFERENCES name_link(id);
+                ALTER TABLE stocktake_line ADD COLUMN donor_link_id TEXT REFERENCES name_link(id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_external_id_to_user_auth_state"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                ALTER TABLE user_auth_state ADD COLUMN external_id TEXT;
+                CREATE UNIQUE INDEX ix_user_auth_state_external_id ON user_auth_state(external_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
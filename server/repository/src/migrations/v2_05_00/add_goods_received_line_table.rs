@@ -0,0 +1,68 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_goods_received_line_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if connection.backend().is_postgres() {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE goods_received_discrepancy_reason AS ENUM (
+                    'SHORT',
+                    'EXCESS',
+                    'DAMAGED'
+                );
+                CREATE TYPE goods_received_inspection_status AS ENUM (
+                    'PENDING',
+                    'PASSED',
+                    'FAILED'
+                );
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'goods_received_line';
+                ALTER TYPE activity_log_type ADD VALUE IF NOT EXISTS 'INVOICE_DISCREPANCIES_REPORTED';
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'GOODS_RECEIVED_TRANSFER_PROCESSOR_CURSOR';
+                "#
+            )?;
+        }
+
+        let (discrepancy_reason, inspection_status) = if connection.backend().is_postgres() {
+            (
+                "goods_received_discrepancy_reason",
+                "goods_received_inspection_status",
+            )
+        } else {
+            ("TEXT", "TEXT")
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE goods_received_line (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    invoice_id TEXT NOT NULL,
+                    invoice_line_id TEXT NOT NULL,
+                    linked_invoice_id TEXT,
+                    supplier_name_link_id TEXT NOT NULL REFERENCES name_link(id),
+                    item_link_id TEXT NOT NULL REFERENCES item_link(id),
+                    batch TEXT,
+                    expected_number_of_packs {DOUBLE} NOT NULL,
+                    received_number_of_packs {DOUBLE},
+                    discrepancy_reason {discrepancy_reason},
+                    inspection_status {inspection_status},
+                    comment TEXT,
+                    received_datetime {DATETIME}
+                );
+
+                CREATE INDEX index_goods_received_line_invoice_id ON goods_received_line (invoice_id);
+                CREATE INDEX index_goods_received_line_linked_invoice_id ON goods_received_line (linked_invoice_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_insurance_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if connection.backend().is_postgres() {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE insurance_policy_type AS ENUM (
+                    'PERSONAL',
+                    'BUSINESS'
+                );
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'insurance_provider';
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'insurance_coverage_rule';
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'name_insurance_join';
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'insurance_claim_line';
+                "#
+            )?;
+        }
+
+        let policy_type = if connection.backend().is_postgres() {
+            "insurance_policy_type"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE insurance_provider (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    provider_name TEXT NOT NULL,
+                    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+                    comment TEXT,
+                    prescription_validity_days INTEGER
+                );
+
+                CREATE TABLE insurance_coverage_rule (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    insurance_provider_id TEXT NOT NULL REFERENCES insurance_provider(id),
+                    item_link_id TEXT REFERENCES item_link(id),
+                    coverage_percentage {DOUBLE},
+                    max_coverage_per_unit {DOUBLE},
+                    is_excluded BOOLEAN NOT NULL DEFAULT FALSE,
+                    deleted_datetime {DATETIME}
+                );
+
+                CREATE TABLE name_insurance_join (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    name_link_id TEXT NOT NULL REFERENCES name_link(id),
+                    insurance_provider_id TEXT NOT NULL REFERENCES insurance_provider(id),
+                    policy_number TEXT NOT NULL,
+                    policy_type {policy_type} NOT NULL,
+                    coverage_percentage {DOUBLE} NOT NULL,
+                    expiry_date {DATE} NOT NULL,
+                    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+                    store_id TEXT NOT NULL,
+                    entered_by_id TEXT
+                );
+
+                CREATE TABLE insurance_claim_line (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    invoice_id TEXT NOT NULL REFERENCES invoice(id),
+                    invoice_line_id TEXT NOT NULL,
+                    name_insurance_join_id TEXT NOT NULL REFERENCES name_insurance_join(id),
+                    insurance_provider_id TEXT NOT NULL REFERENCES insurance_provider(id),
+                    store_id TEXT NOT NULL,
+                    item_link_id TEXT NOT NULL REFERENCES item_link(id),
+                    total_amount {DOUBLE} NOT NULL,
+                    insurer_amount {DOUBLE} NOT NULL,
+                    patient_amount {DOUBLE} NOT NULL,
+                    coverage_rule_id TEXT,
+                    datetime {DATETIME} NOT NULL
+                );
+
+                CREATE INDEX index_name_insurance_join_name_link_id ON name_insurance_join (name_link_id);
+                CREATE INDEX index_insurance_claim_line_invoice_id ON insurance_claim_line (invoice_id);
+                CREATE INDEX index_insurance_claim_line_provider_datetime ON insurance_claim_line (insurance_provider_id, datetime);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_location_hierarchy_and_capacity"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                ALTER TABLE location ADD COLUMN parent_location_id TEXT;
+                ALTER TABLE location ADD COLUMN volume_capacity {DOUBLE};
+                ALTER TABLE location ADD COLUMN pallet_capacity INTEGER;
+                CREATE INDEX location_parent_location_id ON location (parent_location_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_oidc_authorisation_request_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE oidc_authorisation_request (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    code_verifier TEXT NOT NULL,
+                    nonce TEXT NOT NULL,
+                    created_datetime {DATETIME} NOT NULL
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_period_lock_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if connection.backend().is_postgres() {
+            sql!(
+                connection,
+                r#"
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'period_lock';
+                ALTER TYPE permission_type ADD VALUE IF NOT EXISTS 'PERIOD_LOCK_MUTATE';
+                ALTER TYPE permission_type ADD VALUE IF NOT EXISTS 'PERIOD_LOCK_OVERRIDE';
+                ALTER TYPE activity_log_type ADD VALUE IF NOT EXISTS 'PERIOD_LOCKED';
+                ALTER TYPE activity_log_type ADD VALUE IF NOT EXISTS 'PERIOD_LOCK_OVERRIDDEN';
+                "#
+            )?;
+        }
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE period_lock (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    lock_date {DATE},
+                    user_id TEXT,
+                    created_datetime {DATETIME} NOT NULL,
+                    comment TEXT
+                );
+
+                CREATE INDEX index_period_lock_store_id_created_datetime ON period_lock (store_id, created_datetime);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
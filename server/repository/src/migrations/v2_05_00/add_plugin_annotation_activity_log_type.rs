@@ -0,0 +1,22 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_plugin_annotation_activity_log_type"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if connection.backend().is_postgres() {
+            sql!(
+                connection,
+                r#"
+                ALTER TYPE activity_log_type ADD VALUE IF NOT EXISTS 'PLUGIN_ANNOTATION';
+            "#
+            )?;
+        }
+
+        Ok(())
+    }
+}
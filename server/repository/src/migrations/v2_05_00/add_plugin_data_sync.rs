@@ -0,0 +1,39 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_plugin_data_sync"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if connection.backend().is_postgres() {
+            // New enum values can't be used in the same transaction, so this needs its own statement
+            sql!(
+                connection,
+                r#"
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'plugin_data';
+                "#
+            )?;
+        }
+
+        sql!(
+            connection,
+            r#"
+                ALTER TABLE plugin_data ADD COLUMN version BIGINT NOT NULL DEFAULT 0;
+            "#
+        )?;
+
+        // Sync existing plugin data
+        sql!(
+            connection,
+            r#"
+                INSERT INTO changelog (table_name, record_id, row_action, store_id)
+                SELECT 'plugin_data', id, 'UPSERT', store_id FROM plugin_data;
+            "#
+        )?;
+
+        Ok(())
+    }
+}
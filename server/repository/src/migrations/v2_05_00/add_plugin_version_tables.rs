@@ -0,0 +1,46 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_plugin_version_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if connection.backend().is_postgres() {
+            sql!(
+                connection,
+                r#"
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'plugin_version';
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'plugin_certificate_revocation';
+                "#
+            )?;
+        }
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE plugin_version (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    code TEXT NOT NULL,
+                    version TEXT NOT NULL,
+                    bundle_file_id TEXT NOT NULL,
+                    certificate_fingerprint TEXT NOT NULL,
+                    is_active BOOLEAN NOT NULL DEFAULT FALSE,
+                    created_datetime {DATETIME} NOT NULL,
+                    UNIQUE (code, version)
+                );
+
+                CREATE TABLE plugin_certificate_revocation (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    fingerprint TEXT NOT NULL UNIQUE,
+                    reason TEXT,
+                    revoked_datetime {DATETIME} NOT NULL
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
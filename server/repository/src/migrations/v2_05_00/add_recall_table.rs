@@ -0,0 +1,41 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_recall_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if connection.backend().is_postgres() {
+            sql!(
+                connection,
+                r#"
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'recall';
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'RECALL_PROCESSOR_CURSOR';
+                "#
+            )?;
+        }
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE recall (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    item_link_id TEXT NOT NULL REFERENCES item_link(id),
+                    item_variant_id TEXT,
+                    manufacturer_link_id TEXT,
+                    batch TEXT,
+                    expiry_date_from DATE,
+                    expiry_date_to DATE,
+                    reason TEXT,
+                    created_datetime {DATETIME} NOT NULL,
+                    closed_datetime {DATETIME}
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
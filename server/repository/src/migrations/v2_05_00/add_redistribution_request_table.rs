@@ -0,0 +1,39 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_redistribution_request_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if connection.backend().is_postgres() {
+            sql!(
+                connection,
+                r#"
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'redistribution_request';
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'REDISTRIBUTION_REQUEST_PROCESSOR_CURSOR';
+                "#
+            )?;
+        }
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE redistribution_request (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    to_store_id TEXT NOT NULL REFERENCES store(id),
+                    outbound_shipment_id TEXT NOT NULL,
+                    lines TEXT NOT NULL,
+                    user_id TEXT,
+                    created_datetime {DATETIME} NOT NULL,
+                    processed_datetime {DATETIME}
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_role_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if connection.backend().is_postgres() {
+            sql!(
+                connection,
+                r#"
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'role';
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'role_permission';
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'user_role';
+                "#
+            )?;
+        }
+
+        let permission_type = if connection.backend().is_postgres() {
+            "permission_type"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE role (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    description TEXT,
+                    is_active BOOLEAN NOT NULL DEFAULT TRUE
+                );
+
+                CREATE TABLE role_permission (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    role_id TEXT NOT NULL REFERENCES role(id),
+                    permission {permission_type} NOT NULL,
+                    context_id TEXT
+                );
+
+                CREATE TABLE user_role (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    user_id TEXT NOT NULL,
+                    role_id TEXT NOT NULL REFERENCES role(id),
+                    store_id TEXT
+                );
+
+                CREATE INDEX index_role_permission_role_id ON role_permission (role_id);
+                CREATE INDEX index_user_role_user_id ON user_role (user_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_user_auth_state_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if connection.backend().is_postgres() {
+            sql!(
+                connection,
+                r#"
+                ALTER TYPE activity_log_type ADD VALUE IF NOT EXISTS 'USER_LOGIN_FAILED';
+                ALTER TYPE activity_log_type ADD VALUE IF NOT EXISTS 'USER_ACCOUNT_LOCKED';
+                ALTER TYPE activity_log_type ADD VALUE IF NOT EXISTS 'USER_ACCOUNT_UNLOCKED';
+                ALTER TYPE activity_log_type ADD VALUE IF NOT EXISTS 'USER_PASSWORD_CHANGED';
+                ALTER TYPE activity_log_type ADD VALUE IF NOT EXISTS 'USER_TOTP_ENABLED';
+                ALTER TYPE activity_log_type ADD VALUE IF NOT EXISTS 'USER_TOTP_DISABLED';
+                ALTER TYPE system_log_type ADD VALUE IF NOT EXISTS 'LOGIN_FAILED';
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'SETTINGS_AUTH_POLICY';
+            "#
+            )?;
+        }
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE user_auth_state (
+                    user_id TEXT NOT NULL PRIMARY KEY,
+                    failed_login_count INTEGER NOT NULL DEFAULT 0,
+                    last_failed_login_datetime {DATETIME},
+                    locked_until_datetime {DATETIME},
+                    password_changed_datetime {DATETIME},
+                    is_locally_managed BOOLEAN NOT NULL DEFAULT FALSE,
+                    totp_secret TEXT,
+                    totp_confirmed_datetime {DATETIME}
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
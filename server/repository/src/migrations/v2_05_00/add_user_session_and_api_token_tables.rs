@@ -0,0 +1,40 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_user_session_and_api_token_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE user_session_token (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    session_id TEXT NOT NULL,
+                    user_id TEXT NOT NULL,
+                    created_datetime {DATETIME} NOT NULL,
+                    expiry_datetime {DATETIME} NOT NULL
+                );
+                CREATE INDEX index_user_session_token_session_id ON user_session_token (session_id);
+                CREATE INDEX index_user_session_token_user_id ON user_session_token (user_id);
+
+                CREATE TABLE api_token (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    user_id TEXT NOT NULL REFERENCES user_account(id),
+                    token_hash TEXT NOT NULL UNIQUE,
+                    resources TEXT NOT NULL,
+                    store_ids TEXT NOT NULL,
+                    created_datetime {DATETIME} NOT NULL,
+                    expiry_datetime {DATETIME},
+                    last_used_datetime {DATETIME}
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
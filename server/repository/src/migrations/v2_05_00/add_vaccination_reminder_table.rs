@@ -0,0 +1,55 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_vaccination_reminder_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if connection.backend().is_postgres() {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE vaccination_reminder_status AS ENUM (
+                    'PENDING',
+                    'SENT',
+                    'FAILED'
+                );
+                "#
+            )?;
+        }
+
+        let reminder_status = if connection.backend().is_postgres() {
+            "vaccination_reminder_status"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE vaccination_reminder (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    program_enrolment_id TEXT NOT NULL,
+                    vaccine_course_dose_id TEXT NOT NULL REFERENCES vaccine_course_dose(id),
+                    patient_link_id TEXT NOT NULL REFERENCES name_link(id),
+                    phone_number TEXT NOT NULL,
+                    message TEXT NOT NULL,
+                    due_date {DATE} NOT NULL,
+                    status {reminder_status} NOT NULL,
+                    created_datetime {DATETIME} NOT NULL,
+                    sent_datetime {DATETIME},
+                    error_message TEXT
+                );
+
+                CREATE INDEX index_vaccination_reminder_store_id_status ON vaccination_reminder (store_id, status);
+                CREATE INDEX index_vaccination_reminder_program_enrolment_id ON vaccination_reminder (program_enrolment_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
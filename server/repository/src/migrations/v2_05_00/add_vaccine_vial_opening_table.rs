@@ -0,0 +1,56 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_vaccine_vial_opening_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if connection.backend().is_postgres() {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE vaccine_vial_status AS ENUM (
+                    'OPEN',
+                    'EMPTY',
+                    'DISCARDED'
+                );
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'vaccine_vial_opening';
+                "#
+            )?;
+        }
+
+        let vial_status = if connection.backend().is_postgres() {
+            "vaccine_vial_status"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE vaccine_vial_opening (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    item_link_id TEXT NOT NULL REFERENCES item_link(id),
+                    stock_line_id TEXT NOT NULL,
+                    status {vial_status} NOT NULL,
+                    doses_per_vial INTEGER NOT NULL,
+                    doses_drawn INTEGER NOT NULL DEFAULT 0,
+                    wasted_doses INTEGER NOT NULL DEFAULT 0,
+                    opened_datetime {DATETIME} NOT NULL,
+                    discard_after_datetime {DATETIME} NOT NULL,
+                    closed_datetime {DATETIME},
+                    inventory_adjustment_id TEXT
+                );
+
+                CREATE INDEX index_vaccine_vial_opening_stock_line_id ON vaccine_vial_opening (stock_line_id);
+                CREATE INDEX index_vaccine_vial_opening_opened_datetime ON vaccine_vial_opening (opened_datetime);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
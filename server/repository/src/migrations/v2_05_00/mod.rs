@@ -1,8 +1,28 @@
 use super::{version::Version, Migration, MigrationFragment};
 
 mod abbreviation_create_table;
+mod add_asset_maintenance_tables;
+mod add_audit_trail_table;
 mod add_contact_form_table;
+mod add_donor_link_id_to_stock;
 mod add_emergency_orders;
+mod add_external_id_to_user_auth_state;
+mod add_goods_received_line_table;
+mod add_insurance_tables;
+mod add_location_hierarchy_and_capacity;
+mod add_location_walk_sequence;
+mod add_oidc_authorisation_request_table;
+mod add_period_lock_table;
+mod add_plugin_annotation_activity_log_type;
+mod add_plugin_data_sync;
+mod add_plugin_version_tables;
+mod add_recall_table;
+mod add_redistribution_request_table;
+mod add_role_tables;
+mod add_user_auth_state_table;
+mod add_user_session_and_api_token_tables;
+mod add_vaccination_reminder_table;
+mod add_vaccine_vial_opening_table;
 mod new_store_preferences;
 mod remove_unique_description_on_tmp_breach;
 
@@ -26,6 +46,26 @@ impl Migration for V2_05_00 {
             Box::new(remove_unique_description_on_tmp_breach::Migrate),
             Box::new(add_emergency_orders::Migrate),
             Box::new(abbreviation_create_table::Migrate),
+            Box::new(add_insurance_tables::Migrate),
+            Box::new(add_asset_maintenance_tables::Migrate),
+            Box::new(add_vaccination_reminder_table::Migrate),
+            Box::new(add_vaccine_vial_opening_table::Migrate),
+            Box::new(add_plugin_annotation_activity_log_type::Migrate),
+            Box::new(add_plugin_version_tables::Migrate),
+            Box::new(add_plugin_data_sync::Migrate),
+            Box::new(add_location_hierarchy_and_capacity::Migrate),
+            Box::new(add_location_walk_sequence::Migrate),
+            Box::new(add_recall_table::Migrate),
+            Box::new(add_goods_received_line_table::Migrate),
+            Box::new(add_donor_link_id_to_stock::Migrate),
+            Box::new(add_user_session_and_api_token_tables::Migrate),
+            Box::new(add_user_auth_state_table::Migrate),
+            Box::new(add_oidc_authorisation_request_table::Migrate),
+            Box::new(add_role_tables::Migrate),
+            Box::new(add_audit_trail_table::Migrate),
+            Box::new(add_period_lock_table::Migrate),
+            Box::new(add_redistribution_request_table::Migrate),
+            Box::new(add_external_id_to_user_auth_state::Migrate),
         ]
     }
 }
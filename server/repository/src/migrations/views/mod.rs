@@ -37,7 +37,7 @@ pub(crate) fn drop_views(connection: &StorageConnection) -> anyhow::Result<()> {
 pub(crate) fn rebuild_views(connection: &StorageConnection) -> anyhow::Result<()> {
     log::info!("Re-creating database views...");
 
-    let absolute = if cfg!(feature = "postgres") {
+    let absolute = if connection.backend().is_postgres() {
         "@"
     } else {
         "abs"
@@ -69,6 +69,7 @@ pub(crate) fn rebuild_views(connection: &StorageConnection) -> anyhow::Result<()
         invoice_line.foreign_currency_price_before_tax,
         invoice_line.item_link_id,
         invoice_line.return_reason_id,
+        invoice_line.donor_link_id,
         item_link.item_id AS item_id,
         CASE
             WHEN "type" = 'STOCK_IN' THEN (number_of_packs * pack_size)
@@ -145,7 +146,10 @@ pub(crate) fn rebuild_views(connection: &StorageConnection) -> anyhow::Result<()
         invoice.invoice_number AS invoice_number,
         inventory_adjustment_reason.reason as inventory_adjustment_reason,
         return_reason.reason as return_reason,
-        stock_line_id
+        stock_line_id,
+        donor_name_link.name_id AS donor_id,
+        invoice_line_stock_movement.cost_price_per_pack AS cost_price_per_pack,
+        invoice_line_stock_movement.pack_size AS pack_size
     FROM
         invoice_line_stock_movement
         LEFT JOIN inventory_adjustment_reason ON invoice_line_stock_movement.inventory_adjustment_reason_id = inventory_adjustment_reason.id
@@ -153,6 +157,7 @@ pub(crate) fn rebuild_views(connection: &StorageConnection) -> anyhow::Result<()
         JOIN invoice ON invoice.id = invoice_line_stock_movement.invoice_id
         JOIN name_link ON invoice.name_link_id = name_link.id
         JOIN name ON name_link.name_id = name.id
+        LEFT JOIN name_link donor_name_link ON invoice_line_stock_movement.donor_link_id = donor_name_link.id
     )
     SELECT * FROM all_movements
     WHERE datetime IS NOT NULL;
@@ -478,7 +483,7 @@ pub(crate) fn rebuild_views(connection: &StorageConnection) -> anyhow::Result<()
         )?;
     }
 
-    if cfg!(feature = "postgres") {
+    if connection.backend().is_postgres() {
         sql!(
             connection,
             r#"
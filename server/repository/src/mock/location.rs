@@ -8,6 +8,10 @@ pub fn mock_location_1() -> LocationRow {
         on_hold: false,
         store_id: "store_a".to_string(),
         cold_storage_type_id: None,
+        parent_location_id: None,
+        volume_capacity: None,
+        pallet_capacity: None,
+        walk_sequence: None,
     }
 }
 
@@ -19,6 +23,10 @@ pub fn mock_location_on_hold() -> LocationRow {
         on_hold: true,
         store_id: "store_a".to_string(),
         cold_storage_type_id: None,
+        parent_location_id: None,
+        volume_capacity: None,
+        pallet_capacity: None,
+        walk_sequence: None,
     }
 }
 
@@ -31,6 +39,10 @@ pub fn mock_location_2() -> LocationRow {
         on_hold: false,
         store_id: "store_a".to_string(),
         cold_storage_type_id: None,
+        parent_location_id: None,
+        volume_capacity: None,
+        pallet_capacity: None,
+        walk_sequence: None,
     }
 }
 
@@ -42,6 +54,10 @@ pub fn mock_location_3() -> LocationRow {
         on_hold: false,
         store_id: "store_a".to_string(),
         cold_storage_type_id: None,
+        parent_location_id: None,
+        volume_capacity: None,
+        pallet_capacity: None,
+        walk_sequence: None,
     }
 }
 
@@ -54,6 +70,10 @@ pub fn mock_location_in_another_store() -> LocationRow {
         on_hold: false,
         store_id: "store_b".to_string(),
         cold_storage_type_id: None,
+        parent_location_id: None,
+        volume_capacity: None,
+        pallet_capacity: None,
+        walk_sequence: None,
     }
 }
 
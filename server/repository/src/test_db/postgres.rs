@@ -17,6 +17,7 @@ use crate::{
 
 pub fn get_test_db_settings(db_name: &str) -> DatabaseSettings {
     DatabaseSettings {
+        backend: None,
         username: "postgres".to_string(),
         password: "password".to_string(),
         port: 5432,
@@ -23,6 +23,7 @@ pub fn get_test_db_settings(db_name: &str) -> DatabaseSettings {
 
 fn get_test_db_settings_etc(db_name: &str, is_template: bool) -> DatabaseSettings {
     DatabaseSettings {
+        backend: None,
         username: "postgres".to_string(),
         password: "password".to_string(),
         port: 5432,
@@ -147,6 +148,7 @@ pub(crate) async fn setup_with_version(
 }
 
 fn connection_manager(db_settings: &DatabaseSettings) -> StorageConnectionManager {
+    crate::db_diesel::sqlite_timeout::register_progress_handler();
     let connection_manager =
         ConnectionManager::<DBBackendConnection>::new(db_settings.connection_string());
     const SQLITE_LOCKWAIT_MS: u32 = 10 * 1000; // 10 second wait for test lock timeout
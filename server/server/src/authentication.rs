@@ -1,7 +1,11 @@
 use actix_web::HttpRequest;
 use service::{
-    auth::{validate_auth, AuthDeniedKind, AuthError, ValidatedUserAuth},
+    auth::{
+        validate_auth, AuthDeniedKind, AuthError, ResourceAccessRequest, ValidatedUser,
+        ValidatedUserAuth,
+    },
     auth_data::AuthData,
+    service_provider::ServiceProvider,
 };
 
 const COOKIE_NAME: &str = "auth";
@@ -15,20 +19,42 @@ pub(crate) fn validate_cookie_auth(
     request: HttpRequest,
     auth_data: &AuthData,
 ) -> Result<ValidatedUserAuth, AuthError> {
-    let token = match request.cookie(COOKIE_NAME) {
-        Some(cookie) => {
-            let auth_cookie: AuthCookie = match serde_json::from_str(cookie.value()) {
-                Ok(auth_cookie) => auth_cookie,
-                Err(err) => {
-                    return Err(AuthError::Denied(AuthDeniedKind::NotAuthenticated(
-                        err.to_string(),
-                    )))
-                }
-            };
-            Some(auth_cookie.token)
-        }
-        None => None,
-    };
+    let token = cookie_token(&request)?;
 
     validate_auth(auth_data, &token)
 }
+
+/// Same as `validate_cookie_auth` but also checks that the user has access to the resource
+pub(crate) fn validate_cookie_access(
+    request: HttpRequest,
+    auth_data: &AuthData,
+    service_provider: &ServiceProvider,
+    resource_request: &ResourceAccessRequest,
+) -> Result<ValidatedUser, AuthError> {
+    let token = cookie_token(&request)?;
+    let service_context = service_provider
+        .basic_context()
+        .map_err(|err| AuthError::InternalError(err.to_string()))?;
+
+    service_provider.validation_service.validate(
+        &service_context,
+        auth_data,
+        &token,
+        resource_request,
+    )
+}
+
+fn cookie_token(request: &HttpRequest) -> Result<Option<String>, AuthError> {
+    let Some(cookie) = request.cookie(COOKIE_NAME) else {
+        return Ok(None);
+    };
+    let auth_cookie: AuthCookie = match serde_json::from_str(cookie.value()) {
+        Ok(auth_cookie) => auth_cookie,
+        Err(err) => {
+            return Err(AuthError::Denied(AuthDeniedKind::NotAuthenticated(
+                err.to_string(),
+            )))
+        }
+    };
+    Ok(Some(auth_cookie.token))
+}
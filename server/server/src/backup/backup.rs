@@ -4,7 +4,7 @@ use copy_dir::copy_dir;
 use service::settings::Settings;
 use std::{fs, io, path::PathBuf, process::Command, str::FromStr};
 
-pub(crate) fn backup(settings: &Settings) -> Result<(), BackupError> {
+pub fn backup(settings: &Settings) -> Result<(), BackupError> {
     let DirSettings {
         backup_dir,
         pg_bin_dir,
@@ -26,7 +26,7 @@ pub(crate) fn backup(settings: &Settings) -> Result<(), BackupError> {
     copy_files(settings, &file_dir)?;
 
     // Backup database
-    if cfg!(feature = "postgres") {
+    if settings.database.backend().is_postgres() {
         dump_postgres_database(settings, &database_dir, pg_bin_dir)?;
     } else {
         copy_sqlite_files(settings, &database_dir)?;
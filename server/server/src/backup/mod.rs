@@ -1,7 +1,7 @@
 mod backup;
-pub(super) use self::backup::*;
+pub use self::backup::*;
 mod restore;
-pub(super) use self::restore::*;
+pub use self::restore::*;
 
 use std::env::VarError;
 use std::fs;
@@ -22,7 +22,7 @@ const BACKUP_DATABASE_DIR: &'static str = "postgres";
 const BACKUP_DATABASE_DIR: &'static str = "sqlite";
 
 #[derive(Error, Debug)]
-pub(super) enum BackupError {
+pub enum BackupError {
     #[error("Cannot find pg_dump or pg_restore executable in PATH, add it to PATH or specify Postgres bin directory in the configuration file")]
     PgCommandNotFoundInPath,
     #[error("Cannot find pg_dump or pg_restore executable in Postgres bin directory specified in configurations")]
@@ -57,7 +57,7 @@ pub(super) enum BackupError {
     Other(#[from] anyhow::Error),
 }
 #[derive(clap::Parser, Debug)]
-pub(super) struct RestoreArguments {
+pub struct RestoreArguments {
     /// Name of backup in directory specified by backup configurations
     #[clap(short, long)]
     backup_name: String,
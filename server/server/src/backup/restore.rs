@@ -5,7 +5,7 @@ use repository::DBBackendConnection;
 use service::settings::{is_develop, Settings};
 use std::{fs, io, path::PathBuf, process::Command, str::FromStr};
 
-pub(crate) fn restore(
+pub fn restore(
     settings: &Settings,
     RestoreArguments {
         skip_confirmation,
@@ -27,7 +27,7 @@ pub(crate) fn restore(
     copy_files(settings, &file_dir)?;
 
     // Backup database
-    if cfg!(feature = "postgres") {
+    if settings.database.backend().is_postgres() {
         restore_postgres_database(settings, &database_dir, pg_bin_dir)?;
     } else {
         copy_sqlite_files(settings, &database_dir)?;
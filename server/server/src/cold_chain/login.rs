@@ -59,6 +59,7 @@ async fn do_login(
             username: user_info.username.clone(),
             password: user_info.password.clone(),
             central_server_url: sync_settings.url.clone(),
+            totp_code: None,
         },
         MIN_ERR_RESPONSE_TIME_SEC,
     )
@@ -1,5 +1,7 @@
 use config::{Config, ConfigError, Environment, File, FileFormat, FileSourceFile};
-use repository::{KeyType, KeyValueStoreRepository, StorageConnection};
+use repository::{
+    database_settings::DatabaseSettings, KeyType, KeyValueStoreRepository, StorageConnection,
+};
 use service::settings::{is_develop, Settings};
 use std::{
     env::{self, VarError},
@@ -129,10 +131,31 @@ pub fn get_configuration() -> Result<Settings, SettingsError> {
 
     let configuration = builder.build()?;
     let settings: Settings = configuration.try_deserialize()?;
+    settings
+        .database
+        .check_backend()
+        .map_err(|e| SettingsError::Config(ConfigError::Message(e.to_string())))?;
 
     Ok(settings)
 }
 
+#[derive(serde::Deserialize)]
+struct DatabaseConfiguration {
+    database: DatabaseSettings,
+}
+
+/// Reads only the `database` section of a configuration file, used by cli commands working with
+/// more than one database. The backend is not checked against the build, as a source database
+/// can be read with another backend.
+pub fn get_database_configuration(file_path: &str) -> Result<DatabaseSettings, SettingsError> {
+    let configuration = Config::builder()
+        .add_source(File::new(file_path, FileFormat::Yaml))
+        .build()?;
+    let DatabaseConfiguration { database } = configuration.try_deserialize()?;
+
+    Ok(database)
+}
+
 impl Debug for SettingsError {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         match self {
@@ -6,7 +6,7 @@ use crate::{
     cors::cors_policy, middleware::central_server_only, print::config_print,
     serve_frontend::config_serve_frontend, static_files::config_static_files,
     support::config_support, sync_on_central::config_sync_on_central,
-    upload_fridge_tag::config_upload_fridge_tag,
+    upload_fridge_tag::config_upload_fridge_tag, upload_plugin::config_upload_plugin,
 };
 
 use self::middleware::{compress as compress_middleware, logger as logger_middleware};
@@ -18,12 +18,18 @@ use graphql_core::loader::{get_loaders, LoaderRegistry};
 use graphql::{
     attach_discovery_graphql_schema, attach_graphql_schema, GraphSchemaData, GraphqlSchema,
 };
-use log::info;
-use repository::{get_storage_connection_manager, migrations::migrate};
+use log::{error, info, warn};
+use repository::{
+    get_storage_connection_manager,
+    migrations::{get_migrations_status, migrate},
+};
 
 use service::{
     auth_data::AuthData,
-    plugin::validation::ValidatedPluginBucket,
+    plugin::{
+        plugin_version::install_plugin_versions, server_plugin::host::ServerPluginHost,
+        validation::ValidatedPluginBucket,
+    },
     processors::Processors,
     service_provider::ServiceProvider,
     settings::{is_develop, ServerSettings, Settings},
@@ -39,6 +45,7 @@ use actix_web::{web::Data, App, HttpServer};
 use std::sync::{Arc, Mutex, RwLock};
 
 mod authentication;
+pub mod backup;
 pub mod certs;
 pub mod cold_chain;
 pub mod configuration;
@@ -50,6 +57,7 @@ mod serve_frontend;
 pub mod static_files;
 pub mod support;
 mod upload_fridge_tag;
+mod upload_plugin;
 pub use self::logging::*;
 
 pub mod print;
@@ -84,6 +92,21 @@ pub async fn start_server(
     if let Some(init_sql) = &settings.database.full_init_sql() {
         connection_manager.execute(init_sql).unwrap();
     }
+    let migrations_status = get_migrations_status(&connection_manager.connection().unwrap())
+        .context("Failed to check DB migrations")
+        .unwrap();
+    // Nothing to back up in a new database
+    if migrations_status.has_pending() && !migrations_status.is_new_database {
+        match &settings.backup {
+            Some(_) => {
+                info!("Backing up database before migrations...");
+                backup::backup(&settings)
+                    .context("Failed to back up database before migrations")
+                    .unwrap();
+            }
+            None => warn!("Backup is not configured, running DB migrations without a backup"),
+        }
+    }
     info!("Run DB migrations...");
     let version = migrate(&connection_manager.connection().unwrap(), None)
         .context("Failed to run DB migrations")
@@ -109,7 +132,9 @@ pub async fn start_server(
     ));
     let loaders = get_loaders(&connection_manager, service_provider.clone()).await;
     let certificates = Certificates::try_load(&settings.server).unwrap();
-    let token_bucket = Arc::new(RwLock::new(TokenBucket::new()));
+    let token_bucket = Arc::new(RwLock::new(
+        TokenBucket::persisted(connection_manager.clone()).unwrap(),
+    ));
     let token_secret = get_or_create_token_secret(&connection_manager.connection().unwrap());
     let auth = auth_data(&settings.server, token_bucket, token_secret, &certificates);
     info!("Initialising server context..done");
@@ -228,9 +253,22 @@ pub async fn start_server(
         }
     );
 
+    // Plugin versions synced since the last start are installed by file sync, this picks up
+    // plugin versions that were activated or revoked while the server was down
+    if let Err(err) =
+        install_plugin_versions(&service_context.connection, &settings.server.base_dir)
+    {
+        error!("Failed to install plugin versions: {:#}", err);
+    }
+
     let validated_plugins = ValidatedPluginBucket::new(&settings.server.base_dir).unwrap();
     let validated_plugins = Data::new(Mutex::new(validated_plugins));
 
+    match ServerPluginHost::load(&validated_plugins, &settings.server.base_dir) {
+        Ok(server_plugin_host) => server_plugin_host.install(),
+        Err(err) => error!("Failed to load server plugins: {}", err),
+    }
+
     let graphql_schema = Data::new(GraphqlSchema::new(
         GraphSchemaData {
             connection_manager: Data::new(connection_manager),
@@ -301,6 +339,7 @@ pub async fn start_server(
             .configure(config_static_files)
             .configure(config_cold_chain)
             .configure(config_upload_fridge_tag)
+            .configure(config_upload_plugin)
             .configure(config_sync_on_central)
             .configure(config_support)
             .configure(config_print)
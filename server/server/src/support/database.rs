@@ -16,7 +16,7 @@ pub async fn get_database(
     use actix_web::http::header::DispositionType;
     use std::path::Path;
 
-    if cfg!(feature = "postgres") {
+    if service_provider.connection_manager.backend().is_postgres() {
         return Ok(
             HttpResponse::InternalServerError().body("Postgres Databases export not supported")
         );
@@ -46,7 +46,7 @@ pub async fn get_database(
 }
 
 pub async fn vacuum_database(service_provider: Data<ServiceProvider>) -> HttpResponse {
-    if cfg!(feature = "postgres") {
+    if service_provider.connection_manager.backend().is_postgres() {
         return HttpResponse::InternalServerError().body("Postgres Databases vacuum not supported");
     }
 
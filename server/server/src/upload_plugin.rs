@@ -0,0 +1,81 @@
+use actix_multipart::form::MultipartForm;
+use actix_web::{
+    post,
+    web::{self, Data},
+    HttpRequest, HttpResponse,
+};
+
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    auth_data::AuthData,
+    plugin::plugin_version::{upload_plugin_bundle, UploadPluginBundleError},
+    service_provider::ServiceProvider,
+    settings::Settings,
+};
+
+use crate::{
+    authentication::validate_cookie_access, middleware::limit_content_length,
+    static_files::UploadForm,
+};
+
+pub fn config_upload_plugin(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/plugin-bundle")
+            .service(upload)
+            .wrap(limit_content_length()),
+    );
+}
+
+/// Uploads a plugin bundle (see `remote_server_cli bundle-plugin`) to the central server
+#[post("")]
+async fn upload(
+    MultipartForm(UploadForm { file }): MultipartForm<UploadForm>,
+    settings: Data<Settings>,
+    service_provider: Data<ServiceProvider>,
+    auth_data: Data<AuthData>,
+    request: HttpRequest,
+) -> HttpResponse {
+    if let Err(error) = validate_cookie_access(
+        request,
+        &auth_data,
+        &service_provider,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    ) {
+        log::info!("Plugin upload denied: {:?}", error);
+        return HttpResponse::Forbidden().body("You need to be logged in as server admin");
+    };
+
+    let data = match std::fs::read(file.file.path()) {
+        Ok(data) => data,
+        Err(error) => {
+            log::error!("Failed to read uploaded plugin bundle: {}", error);
+            return HttpResponse::InternalServerError().body("Failed to read plugin bundle");
+        }
+    };
+
+    let result = service_provider
+        .basic_context()
+        .map_err(UploadPluginBundleError::DatabaseError)
+        .and_then(|ctx| upload_plugin_bundle(&ctx, &settings.server.base_dir, &data));
+
+    match result {
+        Ok(plugin_version) => HttpResponse::Ok().json(plugin_version),
+        Err(error) => {
+            use UploadPluginBundleError as Error;
+            let message = format!("{:?}", error);
+            match error {
+                Error::NotACentralServer
+                | Error::InvalidBundle(_)
+                | Error::PluginValidationFailed(_)
+                | Error::PluginVersionAlreadyExists => HttpResponse::BadRequest().body(message),
+                Error::DatabaseError(_) | Error::InternalError(_) => {
+                    log::error!("Error uploading plugin bundle: {}", message);
+                    HttpResponse::InternalServerError().body(message)
+                }
+            }
+        }
+    }
+}
@@ -84,6 +84,29 @@ pub fn system_activity_log_entry(
     Ok(())
 }
 
+/// Logs an auth event on behalf of a user who isn't authenticated yet, e.g. a failed login
+pub fn user_activity_log_entry(
+    connection: &StorageConnection,
+    log_type: ActivityLogType,
+    user_id: &str,
+    changed_from: Option<String>,
+    changed_to: Option<String>,
+) -> Result<(), RepositoryError> {
+    let log = &ActivityLogRow {
+        id: uuid(),
+        r#type: log_type,
+        user_id: Some(user_id.to_string()),
+        store_id: None,
+        record_id: Some(user_id.to_string()),
+        datetime: Utc::now().naive_utc(),
+        changed_from,
+        changed_to,
+    };
+
+    let _change_log_id = ActivityLogRowRepository::new(connection).insert_one(log)?;
+    Ok(())
+}
+
 pub fn system_log_entry(
     connection: &StorageConnection,
     log_type: SystemLogType,
@@ -0,0 +1,28 @@
+use repository::{ApiTokenRowRepository, RepositoryError};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, PartialEq)]
+pub enum DeleteApiTokenError {
+    ApiTokenDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+/// Revokes the api token, requests using it are rejected straight away
+pub fn delete_api_token(ctx: &ServiceContext, id: &str) -> Result<String, DeleteApiTokenError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let repo = ApiTokenRowRepository::new(connection);
+            repo.find_one_by_id(id)?
+                .ok_or(DeleteApiTokenError::ApiTokenDoesNotExist)?;
+            repo.delete(id)?;
+            Ok(id.to_string())
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+impl From<RepositoryError> for DeleteApiTokenError {
+    fn from(error: RepositoryError) -> Self {
+        DeleteApiTokenError::DatabaseError(error)
+    }
+}
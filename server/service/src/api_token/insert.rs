@@ -0,0 +1,129 @@
+use chrono::{NaiveDateTime, Utc};
+use rand::Rng;
+use repository::{
+    ApiTokenRow, ApiTokenRowRepository, RepositoryError, StorageConnection, StoreRowRepository,
+    UserAccountRowRepository,
+};
+use util::hash::sha256;
+
+use super::{ApiToken, API_TOKEN_PREFIX};
+use crate::{auth::Resource, service_provider::ServiceContext};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InsertApiToken {
+    pub id: String,
+    pub name: String,
+    /// User the token acts on behalf of
+    pub user_id: String,
+    pub resources: Vec<Resource>,
+    pub store_ids: Vec<String>,
+    pub expiry_datetime: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewApiToken {
+    pub api_token: ApiToken,
+    /// The token is only stored hashed and can't be retrieved later
+    pub token: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InsertApiTokenError {
+    ApiTokenAlreadyExists,
+    UserDoesNotExist,
+    StoreDoesNotExist(String),
+    NoResources,
+    ExpiryInThePast,
+    DatabaseError(RepositoryError),
+}
+
+pub fn insert_api_token(
+    ctx: &ServiceContext,
+    input: InsertApiToken,
+) -> Result<NewApiToken, InsertApiTokenError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            validate(connection, &input)?;
+
+            let token = generate_token();
+            let row = generate(input, &token);
+            ApiTokenRowRepository::new(connection).upsert_one(&row)?;
+
+            Ok(NewApiToken {
+                api_token: ApiToken::from_row(row),
+                token,
+            })
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+fn validate(
+    connection: &StorageConnection,
+    input: &InsertApiToken,
+) -> Result<(), InsertApiTokenError> {
+    if ApiTokenRowRepository::new(connection)
+        .find_one_by_id(&input.id)?
+        .is_some()
+    {
+        return Err(InsertApiTokenError::ApiTokenAlreadyExists);
+    }
+    if UserAccountRowRepository::new(connection)
+        .find_one_by_id(&input.user_id)?
+        .is_none()
+    {
+        return Err(InsertApiTokenError::UserDoesNotExist);
+    }
+    let store_repo = StoreRowRepository::new(connection);
+    for store_id in &input.store_ids {
+        if store_repo.find_one_by_id(store_id)?.is_none() {
+            return Err(InsertApiTokenError::StoreDoesNotExist(store_id.clone()));
+        }
+    }
+    if input.resources.is_empty() {
+        return Err(InsertApiTokenError::NoResources);
+    }
+    if input
+        .expiry_datetime
+        .is_some_and(|expiry| expiry <= Utc::now().naive_utc())
+    {
+        return Err(InsertApiTokenError::ExpiryInThePast);
+    }
+    Ok(())
+}
+
+fn generate_token() -> String {
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("{API_TOKEN_PREFIX}{hex}")
+}
+
+fn generate(
+    InsertApiToken {
+        id,
+        name,
+        user_id,
+        resources,
+        store_ids,
+        expiry_datetime,
+    }: InsertApiToken,
+    token: &str,
+) -> ApiTokenRow {
+    ApiTokenRow {
+        id,
+        name,
+        user_id,
+        token_hash: sha256(token),
+        // Serializing a list of strings or unit enum variants can't fail
+        resources: serde_json::to_string(&resources).unwrap_or_default(),
+        store_ids: serde_json::to_string(&store_ids).unwrap_or_default(),
+        created_datetime: Utc::now().naive_utc(),
+        expiry_datetime,
+        last_used_datetime: None,
+    }
+}
+
+impl From<RepositoryError> for InsertApiTokenError {
+    fn from(error: RepositoryError) -> Self {
+        InsertApiTokenError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::{NaiveDateTime, Utc};
+use repository::{ApiTokenRow, ApiTokenRowRepository, RepositoryError, StorageConnection};
+use util::hash::sha256;
+
+use self::{
+    delete::{delete_api_token, DeleteApiTokenError},
+    insert::{insert_api_token, InsertApiToken, InsertApiTokenError, NewApiToken},
+};
+use crate::{
+    auth::{Resource, ResourceAccessRequest},
+    service_provider::ServiceContext,
+};
+
+pub mod delete;
+pub mod insert;
+
+/// Prefix of api tokens, used to tell them apart from JWT auth tokens
+pub const API_TOKEN_PREFIX: &str = "omsapi_";
+
+pub fn is_api_token(token: &str) -> bool {
+    token.starts_with(API_TOKEN_PREFIX)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiToken {
+    pub api_token_row: ApiTokenRow,
+    pub resources: Vec<Resource>,
+    pub store_ids: Vec<String>,
+}
+
+impl ApiToken {
+    /// A token with an unreadable resource or store list can't access anything
+    pub fn from_row(api_token_row: ApiTokenRow) -> Self {
+        ApiToken {
+            resources: serde_json::from_str(&api_token_row.resources).unwrap_or_default(),
+            store_ids: serde_json::from_str(&api_token_row.store_ids).unwrap_or_default(),
+            api_token_row,
+        }
+    }
+
+    pub fn is_expired(&self, now: NaiveDateTime) -> bool {
+        self.api_token_row
+            .expiry_datetime
+            .is_some_and(|expiry| expiry <= now)
+    }
+
+    /// Checks the request is within the scope of the token. The user of the token must still have
+    /// the permissions for the request.
+    pub fn allows(&self, resource_request: &ResourceAccessRequest) -> bool {
+        if !self.resources.contains(&resource_request.resource) {
+            return false;
+        }
+        match &resource_request.store_id {
+            Some(store_id) => self.store_ids.contains(store_id),
+            None => true,
+        }
+    }
+}
+
+pub fn get_api_tokens(ctx: &ServiceContext) -> Result<Vec<ApiToken>, RepositoryError> {
+    let rows = ApiTokenRowRepository::new(&ctx.connection).find_all()?;
+    Ok(rows.into_iter().map(ApiToken::from_row).collect())
+}
+
+/// Finds the api token and records that it has been used
+pub fn use_api_token(
+    connection: &StorageConnection,
+    token: &str,
+) -> Result<Option<ApiToken>, RepositoryError> {
+    let repo = ApiTokenRowRepository::new(connection);
+    let Some(mut row) = repo.find_one_by_token_hash(&sha256(token))? else {
+        return Ok(None);
+    };
+
+    // Avoid a database write on every request
+    let now = Utc::now().naive_utc();
+    let recently_used = row
+        .last_used_datetime
+        .is_some_and(|last_used| now - last_used < chrono::Duration::minutes(1));
+    if !recently_used {
+        repo.update_last_used(&row.id, now)?;
+        row.last_used_datetime = Some(now);
+    }
+
+    Ok(Some(ApiToken::from_row(row)))
+}
+
+pub trait ApiTokenServiceTrait: Sync + Send {
+    fn get_api_tokens(&self, ctx: &ServiceContext) -> Result<Vec<ApiToken>, RepositoryError> {
+        get_api_tokens(ctx)
+    }
+
+    fn insert_api_token(
+        &self,
+        ctx: &ServiceContext,
+        input: InsertApiToken,
+    ) -> Result<NewApiToken, InsertApiTokenError> {
+        insert_api_token(ctx, input)
+    }
+
+    fn delete_api_token(
+        &self,
+        ctx: &ServiceContext,
+        id: &str,
+    ) -> Result<String, DeleteApiTokenError> {
+        delete_api_token(ctx, id)
+    }
+}
+
+pub struct ApiTokenService {}
+impl ApiTokenServiceTrait for ApiTokenService {}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,276 @@
+use std::sync::{Arc, RwLock};
+
+use chrono::{Duration, Utc};
+use repository::{
+    mock::{mock_store_a, mock_store_b, mock_user_account_a, MockDataInserts},
+    test_db::setup_all,
+    ApiTokenRowRepository, PermissionType, UserPermissionRow, UserPermissionRowRepository,
+};
+
+use crate::{
+    api_token::{
+        delete::DeleteApiTokenError,
+        insert::{InsertApiToken, InsertApiTokenError},
+    },
+    auth::{
+        AuthDeniedKind, AuthError, AuthService, AuthServiceTrait, Resource, ResourceAccessRequest,
+    },
+    auth_data::AuthData,
+    service_provider::ServiceProvider,
+    token_bucket::TokenBucket,
+};
+
+#[actix_rt::test]
+async fn insert_api_token_errors() {
+    let (_, _, connection_manager, _) = setup_all(
+        "insert_api_token_errors",
+        MockDataInserts::none().names().stores().user_accounts(),
+    )
+    .await;
+
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider.basic_context().unwrap();
+    let service = &service_provider.api_token_service;
+
+    let input = InsertApiToken {
+        id: "api_token".to_string(),
+        name: "Dashboard".to_string(),
+        user_id: mock_user_account_a().id,
+        resources: vec![Resource::QueryStocktake],
+        store_ids: vec![mock_store_a().id],
+        expiry_datetime: None,
+    };
+
+    assert_eq!(
+        service.insert_api_token(
+            &context,
+            InsertApiToken {
+                user_id: "invalid".to_string(),
+                ..input.clone()
+            }
+        ),
+        Err(InsertApiTokenError::UserDoesNotExist)
+    );
+    assert_eq!(
+        service.insert_api_token(
+            &context,
+            InsertApiToken {
+                store_ids: vec!["invalid".to_string()],
+                ..input.clone()
+            }
+        ),
+        Err(InsertApiTokenError::StoreDoesNotExist(
+            "invalid".to_string()
+        ))
+    );
+    assert_eq!(
+        service.insert_api_token(
+            &context,
+            InsertApiToken {
+                resources: vec![],
+                ..input.clone()
+            }
+        ),
+        Err(InsertApiTokenError::NoResources)
+    );
+    assert_eq!(
+        service.insert_api_token(
+            &context,
+            InsertApiToken {
+                expiry_datetime: Some(Utc::now().naive_utc() - Duration::hours(1)),
+                ..input.clone()
+            }
+        ),
+        Err(InsertApiTokenError::ExpiryInThePast)
+    );
+
+    service.insert_api_token(&context, input.clone()).unwrap();
+    assert_eq!(
+        service.insert_api_token(&context, input),
+        Err(InsertApiTokenError::ApiTokenAlreadyExists)
+    );
+
+    assert_eq!(
+        service.delete_api_token(&context, "invalid"),
+        Err(DeleteApiTokenError::ApiTokenDoesNotExist)
+    );
+}
+
+#[actix_rt::test]
+async fn api_token_validation() {
+    let (_, connection, connection_manager, _) = setup_all(
+        "api_token_validation",
+        MockDataInserts::none().names().stores().user_accounts(),
+    )
+    .await;
+
+    let user_id = mock_user_account_a().id;
+    let permission_repo = UserPermissionRowRepository::new(&connection);
+    for (id, store_id, permission) in [
+        (
+            "store_a_access",
+            mock_store_a().id,
+            PermissionType::StoreAccess,
+        ),
+        (
+            "store_a_query",
+            mock_store_a().id,
+            PermissionType::StocktakeQuery,
+        ),
+        (
+            "store_b_access",
+            mock_store_b().id,
+            PermissionType::StoreAccess,
+        ),
+        (
+            "store_b_query",
+            mock_store_b().id,
+            PermissionType::StocktakeQuery,
+        ),
+    ] {
+        permission_repo
+            .upsert_one(&UserPermissionRow {
+                id: id.to_string(),
+                user_id: user_id.clone(),
+                store_id: Some(store_id),
+                permission,
+                context_id: None,
+            })
+            .unwrap();
+    }
+
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider.basic_context().unwrap();
+    let auth_data = AuthData {
+        auth_token_secret: "some secret".to_string(),
+        token_bucket: Arc::new(RwLock::new(TokenBucket::new())),
+        no_ssl: true,
+        debug_no_access_control: false,
+    };
+    let auth_service = AuthService::new();
+    let request = |resource: Resource, store_id: &str| ResourceAccessRequest {
+        resource,
+        store_id: Some(store_id.to_string()),
+    };
+
+    let new_token = service_provider
+        .api_token_service
+        .insert_api_token(
+            &context,
+            InsertApiToken {
+                id: "api_token".to_string(),
+                name: "Dashboard".to_string(),
+                user_id: user_id.clone(),
+                resources: vec![Resource::QueryStocktake, Resource::MutateStocktake],
+                store_ids: vec![mock_store_a().id],
+                expiry_datetime: Some(Utc::now().naive_utc() + Duration::days(30)),
+            },
+        )
+        .unwrap();
+    let token = Some(new_token.token.clone());
+    // Only the hash is stored
+    let row = ApiTokenRowRepository::new(&connection)
+        .find_one_by_id("api_token")
+        .unwrap()
+        .unwrap();
+    assert_ne!(row.token_hash, new_token.token);
+    assert_eq!(row.last_used_datetime, None);
+
+    // Within the scope of the token and the permissions of the user
+    let validated = auth_service
+        .validate(
+            &context,
+            &auth_data,
+            &token,
+            &request(Resource::QueryStocktake, &mock_store_a().id),
+        )
+        .unwrap();
+    assert_eq!(validated.user_id, user_id);
+    let row = ApiTokenRowRepository::new(&connection)
+        .find_one_by_id("api_token")
+        .unwrap()
+        .unwrap();
+    assert!(row.last_used_datetime.is_some());
+
+    // Store isn't in the scope of the token, even though the user has access
+    assert!(matches!(
+        auth_service.validate(
+            &context,
+            &auth_data,
+            &token,
+            &request(Resource::QueryStocktake, &mock_store_b().id),
+        ),
+        Err(AuthError::Denied(
+            AuthDeniedKind::InsufficientPermission { .. }
+        ))
+    ));
+
+    // Resource isn't in the scope of the token
+    assert!(matches!(
+        auth_service.validate(
+            &context,
+            &auth_data,
+            &token,
+            &request(Resource::QueryInvoice, &mock_store_a().id),
+        ),
+        Err(AuthError::Denied(
+            AuthDeniedKind::InsufficientPermission { .. }
+        ))
+    ));
+
+    // Resource is in the scope of the token but the user doesn't have the permission
+    assert!(matches!(
+        auth_service.validate(
+            &context,
+            &auth_data,
+            &token,
+            &request(Resource::MutateStocktake, &mock_store_a().id),
+        ),
+        Err(AuthError::Denied(
+            AuthDeniedKind::InsufficientPermission { .. }
+        ))
+    ));
+
+    // Unknown and expired tokens
+    assert!(matches!(
+        auth_service.validate(
+            &context,
+            &auth_data,
+            &Some("omsapi_unknown".to_string()),
+            &request(Resource::QueryStocktake, &mock_store_a().id),
+        ),
+        Err(AuthError::Denied(AuthDeniedKind::NotAuthenticated(_)))
+    ));
+    let mut expired = row.clone();
+    expired.expiry_datetime = Some(Utc::now().naive_utc() - Duration::hours(1));
+    ApiTokenRowRepository::new(&connection)
+        .upsert_one(&expired)
+        .unwrap();
+    assert!(matches!(
+        auth_service.validate(
+            &context,
+            &auth_data,
+            &token,
+            &request(Resource::QueryStocktake, &mock_store_a().id),
+        ),
+        Err(AuthError::Denied(AuthDeniedKind::NotAuthenticated(_)))
+    ));
+    ApiTokenRowRepository::new(&connection)
+        .upsert_one(&row)
+        .unwrap();
+
+    // Revoked token
+    service_provider
+        .api_token_service
+        .delete_api_token(&context, "api_token")
+        .unwrap();
+    assert!(matches!(
+        auth_service.validate(
+            &context,
+            &auth_data,
+            &token,
+            &request(Resource::QueryStocktake, &mock_store_a().id),
+        ),
+        Err(AuthError::Denied(AuthDeniedKind::NotAuthenticated(_)))
+    ));
+}
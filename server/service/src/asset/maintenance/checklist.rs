@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// A task on a work order checklist, stored as JSON on `asset_work_order.checklist`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkOrderChecklistItem {
+    pub task: String,
+    pub is_done: bool,
+}
+
+/// Maintenance plans store their checklist as a JSON array of task descriptions
+pub fn parse_plan_checklist(checklist: &Option<String>) -> Result<Vec<String>, serde_json::Error> {
+    match checklist {
+        Some(checklist) => serde_json::from_str(checklist),
+        None => Ok(Vec::new()),
+    }
+}
+
+pub fn parse_work_order_checklist(
+    checklist: &Option<String>,
+) -> Result<Vec<WorkOrderChecklistItem>, serde_json::Error> {
+    match checklist {
+        Some(checklist) => serde_json::from_str(checklist),
+        None => Ok(Vec::new()),
+    }
+}
+
+pub(crate) fn work_order_checklist_from_plan(tasks: Vec<String>) -> Option<String> {
+    if tasks.is_empty() {
+        return None;
+    }
+
+    let items: Vec<WorkOrderChecklistItem> = tasks
+        .into_iter()
+        .map(|task| WorkOrderChecklistItem {
+            task,
+            is_done: false,
+        })
+        .collect();
+    serde_json::to_string(&items).ok()
+}
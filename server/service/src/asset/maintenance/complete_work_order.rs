@@ -0,0 +1,215 @@
+use chrono::Utc;
+use repository::{
+    asset_log_row::AssetLogStatus,
+    asset_maintenance_plan_row::AssetMaintenancePlanRowRepository,
+    asset_work_order_row::{AssetWorkOrderRow, AssetWorkOrderRowRepository, AssetWorkOrderStatus},
+    asset_work_order_spare_part_row::{
+        AssetWorkOrderSparePartRow, AssetWorkOrderSparePartRowRepository,
+    },
+    RepositoryError, StockLineRowRepository, StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::{
+    asset::insert_log::{insert_asset_log, InsertAssetLog, InsertAssetLogError},
+    invoice::inventory_adjustment::{
+        insert_inventory_adjustment, AdjustmentType, InsertInventoryAdjustment,
+        InsertInventoryAdjustmentError,
+    },
+    service_provider::ServiceContext,
+};
+
+use super::{
+    checklist::parse_work_order_checklist,
+    update_work_order::{check_open_work_order, UpdateWorkOrderError},
+};
+
+#[derive(PartialEq, Debug)]
+pub enum CompleteWorkOrderError {
+    WorkOrderDoesNotExist,
+    NotThisStoreWorkOrder,
+    WorkOrderIsClosed,
+    InvalidChecklist,
+    ChecklistIncomplete,
+    SparePartStockLineDoesNotExist(String),
+    SparePartNumberOfPacksMustBePositive(String),
+    SparePartError(InsertInventoryAdjustmentError),
+    AssetLogError(InsertAssetLogError),
+    UpdatedRecordNotFound,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct WorkOrderSparePart {
+    pub stock_line_id: String,
+    pub number_of_packs: f64,
+    pub inventory_adjustment_reason_id: Option<String>,
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct CompleteWorkOrder {
+    pub id: String,
+    pub usage_reading: Option<f64>,
+    pub checklist: Option<String>,
+    pub comment: Option<String>,
+    /// Status recorded in the asset log, defaults to `Functioning`
+    pub asset_status: Option<AssetLogStatus>,
+    pub spare_parts: Vec<WorkOrderSparePart>,
+}
+
+/// Completes a work order: spare parts are issued from stock with an inventory reduction each and
+/// an asset log entry is written with the asset's status after maintenance
+pub fn complete_work_order(
+    ctx: &ServiceContext,
+    input: CompleteWorkOrder,
+) -> Result<AssetWorkOrderRow, CompleteWorkOrderError> {
+    let work_order = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let work_order = validate(connection, &ctx.store_id, &input)?;
+
+            for spare_part in input.spare_parts.iter() {
+                let invoice = insert_inventory_adjustment(
+                    ctx,
+                    InsertInventoryAdjustment {
+                        stock_line_id: spare_part.stock_line_id.clone(),
+                        adjustment: spare_part.number_of_packs,
+                        adjustment_type: AdjustmentType::Reduction,
+                        inventory_adjustment_reason_id: spare_part
+                            .inventory_adjustment_reason_id
+                            .clone(),
+                    },
+                )
+                .map_err(CompleteWorkOrderError::SparePartError)?;
+
+                let stock_line = StockLineRowRepository::new(connection)
+                    .find_one_by_id(&spare_part.stock_line_id)?
+                    .ok_or(CompleteWorkOrderError::SparePartStockLineDoesNotExist(
+                        spare_part.stock_line_id.clone(),
+                    ))?;
+
+                AssetWorkOrderSparePartRowRepository::new(connection).upsert_one(
+                    &AssetWorkOrderSparePartRow {
+                        id: uuid(),
+                        work_order_id: work_order.id.clone(),
+                        item_link_id: stock_line.item_link_id,
+                        stock_line_id: stock_line.id,
+                        number_of_packs: spare_part.number_of_packs,
+                        invoice_id: invoice.invoice_row.id,
+                    },
+                )?;
+            }
+
+            let plan_name = match &work_order.maintenance_plan_id {
+                Some(plan_id) => AssetMaintenancePlanRowRepository::new(connection)
+                    .find_one_by_id(plan_id)?
+                    .map(|plan| plan.name),
+                None => None,
+            };
+            let asset_log = insert_asset_log(
+                ctx,
+                InsertAssetLog {
+                    id: uuid(),
+                    asset_id: work_order.asset_id.clone(),
+                    status: Some(
+                        input
+                            .asset_status
+                            .clone()
+                            .unwrap_or(AssetLogStatus::Functioning),
+                    ),
+                    comment: Some(match plan_name {
+                        Some(plan_name) => format!("Maintenance completed: {}", plan_name),
+                        None => "Maintenance completed".to_string(),
+                    }),
+                    r#type: None,
+                    reason_id: None,
+                },
+            )
+            .map_err(CompleteWorkOrderError::AssetLogError)?;
+
+            let completed = generate(ctx, work_order, input, asset_log.id);
+            let repository = AssetWorkOrderRowRepository::new(connection);
+            repository.upsert_one(&completed)?;
+
+            repository
+                .find_one_by_id(&completed.id)?
+                .ok_or(CompleteWorkOrderError::UpdatedRecordNotFound)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(work_order)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &CompleteWorkOrder,
+) -> Result<AssetWorkOrderRow, CompleteWorkOrderError> {
+    use CompleteWorkOrderError::*;
+
+    let work_order = check_open_work_order(connection, store_id, &input.id)?;
+
+    let checklist = match &input.checklist {
+        Some(_) => &input.checklist,
+        None => &work_order.checklist,
+    };
+    let checklist = parse_work_order_checklist(checklist).map_err(|_| InvalidChecklist)?;
+    if checklist.iter().any(|item| !item.is_done) {
+        return Err(ChecklistIncomplete);
+    }
+
+    for spare_part in input.spare_parts.iter() {
+        if spare_part.number_of_packs <= 0.0 {
+            return Err(SparePartNumberOfPacksMustBePositive(
+                spare_part.stock_line_id.clone(),
+            ));
+        }
+    }
+
+    Ok(work_order)
+}
+
+fn generate(
+    ctx: &ServiceContext,
+    existing: AssetWorkOrderRow,
+    CompleteWorkOrder {
+        id: _,
+        usage_reading,
+        checklist,
+        comment,
+        asset_status: _,
+        spare_parts: _,
+    }: CompleteWorkOrder,
+    asset_log_id: String,
+) -> AssetWorkOrderRow {
+    AssetWorkOrderRow {
+        status: AssetWorkOrderStatus::Completed,
+        usage_reading: usage_reading.or(existing.usage_reading),
+        checklist: checklist.or(existing.checklist),
+        comment: comment.or(existing.comment),
+        completed_datetime: Some(Utc::now().naive_utc()),
+        completed_by_id: Some(ctx.user_id.clone()),
+        asset_log_id: Some(asset_log_id),
+        ..existing
+    }
+}
+
+impl From<UpdateWorkOrderError> for CompleteWorkOrderError {
+    fn from(error: UpdateWorkOrderError) -> Self {
+        use CompleteWorkOrderError::*;
+        match error {
+            UpdateWorkOrderError::WorkOrderDoesNotExist => WorkOrderDoesNotExist,
+            UpdateWorkOrderError::NotThisStoreWorkOrder => NotThisStoreWorkOrder,
+            UpdateWorkOrderError::WorkOrderIsClosed
+            | UpdateWorkOrderError::CannotSetStatusToCompleted => WorkOrderIsClosed,
+            UpdateWorkOrderError::InvalidChecklist => InvalidChecklist,
+            UpdateWorkOrderError::UpdatedRecordNotFound => UpdatedRecordNotFound,
+            UpdateWorkOrderError::DatabaseError(error) => DatabaseError(error),
+        }
+    }
+}
+
+impl From<RepositoryError> for CompleteWorkOrderError {
+    fn from(error: RepositoryError) -> Self {
+        CompleteWorkOrderError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,234 @@
+use chrono::{Duration, NaiveDate, Utc};
+use repository::{
+    asset::{AssetFilter, AssetRepository},
+    asset_maintenance_plan::{AssetMaintenancePlanFilter, AssetMaintenancePlanRepository},
+    asset_maintenance_plan_row::{AssetMaintenancePlanRow, AssetMaintenanceScheduleType},
+    asset_row::AssetRow,
+    asset_work_order::{AssetWorkOrderFilter, AssetWorkOrderRepository},
+    asset_work_order_row::{AssetWorkOrderRow, AssetWorkOrderRowRepository, AssetWorkOrderStatus},
+    EqualFilter, RepositoryError,
+};
+use util::uuid::uuid;
+
+use crate::service_provider::ServiceContext;
+
+use super::checklist::{parse_plan_checklist, work_order_checklist_from_plan};
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct AssetUsageReading {
+    pub asset_id: String,
+    pub reading: f64,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct GenerateWorkOrders {
+    pub as_of: NaiveDate,
+    /// Interval based work orders due within this many days of `as_of` are raised early
+    pub lookahead_days: i64,
+    /// Current usage of assets, needed for usage based plans
+    pub usage_readings: Vec<AssetUsageReading>,
+}
+
+/// Raises work orders for the store's assets that are due maintenance, according to the active
+/// maintenance plans of their asset type. An asset won't get a second work order for a plan
+/// while one is still open.
+pub fn generate_work_orders(
+    ctx: &ServiceContext,
+    input: GenerateWorkOrders,
+) -> Result<Vec<AssetWorkOrderRow>, RepositoryError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let plans = AssetMaintenancePlanRepository::new(connection)
+                .query_by_filter(AssetMaintenancePlanFilter::new().is_active(true))?;
+            let work_order_repository = AssetWorkOrderRepository::new(connection);
+            let mut work_orders = Vec::new();
+
+            for plan in plans {
+                let assets = AssetRepository::new(connection).query_by_filter(
+                    AssetFilter::new()
+                        .type_id(EqualFilter::equal_to(&plan.asset_type_id))
+                        .store_id(EqualFilter::equal_to(&ctx.store_id)),
+                )?;
+
+                for asset in assets {
+                    let plan_filter = AssetWorkOrderFilter::new()
+                        .asset_id(EqualFilter::equal_to(&asset.id))
+                        .maintenance_plan_id(EqualFilter::equal_to(&plan.id));
+
+                    let open_count =
+                        work_order_repository.count(Some(plan_filter.clone().status(
+                            AssetWorkOrderStatus::equal_any(vec![
+                                AssetWorkOrderStatus::Planned,
+                                AssetWorkOrderStatus::InProgress,
+                            ]),
+                        )))?;
+                    if open_count > 0 {
+                        continue;
+                    }
+
+                    let last_completed = work_order_repository
+                        .query_by_filter(
+                            plan_filter.status(AssetWorkOrderStatus::Completed.equal_to()),
+                        )?
+                        .into_iter()
+                        .max_by_key(|work_order| work_order.completed_datetime);
+                    let usage_reading = input
+                        .usage_readings
+                        .iter()
+                        .find(|reading| reading.asset_id == asset.id)
+                        .map(|reading| reading.reading);
+
+                    let Some((due_date, due_usage)) = next_due(
+                        &plan,
+                        &asset,
+                        last_completed.as_ref(),
+                        input.as_of,
+                        input.lookahead_days,
+                        usage_reading,
+                    ) else {
+                        continue;
+                    };
+
+                    let work_order = AssetWorkOrderRow {
+                        id: uuid(),
+                        asset_id: asset.id.clone(),
+                        maintenance_plan_id: Some(plan.id.clone()),
+                        store_id: asset.store_id.clone(),
+                        status: AssetWorkOrderStatus::Planned,
+                        due_date,
+                        due_usage,
+                        checklist: work_order_checklist_from_plan(
+                            parse_plan_checklist(&plan.checklist).unwrap_or_default(),
+                        ),
+                        created_datetime: Utc::now().naive_utc(),
+                        ..Default::default()
+                    };
+                    AssetWorkOrderRowRepository::new(connection).upsert_one(&work_order)?;
+                    work_orders.push(work_order);
+                }
+            }
+
+            Ok(work_orders)
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+/// Returns the due date (and due usage for usage based plans) if a work order should be raised
+pub(crate) fn next_due(
+    plan: &AssetMaintenancePlanRow,
+    asset: &AssetRow,
+    last_completed: Option<&AssetWorkOrderRow>,
+    as_of: NaiveDate,
+    lookahead_days: i64,
+    usage_reading: Option<f64>,
+) -> Option<(NaiveDate, Option<f64>)> {
+    match plan.schedule_type {
+        AssetMaintenanceScheduleType::Interval => {
+            let interval_days = plan.interval_days?;
+            let last_serviced = last_completed
+                .and_then(|work_order| work_order.completed_datetime)
+                .map(|datetime| datetime.date())
+                .or(asset.installation_date)
+                .unwrap_or(asset.created_datetime.date());
+
+            let due_date = last_serviced + Duration::days(interval_days as i64);
+            (due_date <= as_of + Duration::days(lookahead_days)).then_some((due_date, None))
+        }
+        AssetMaintenanceScheduleType::Usage => {
+            let usage_interval = plan.usage_interval?;
+            let last_usage = last_completed
+                .and_then(|work_order| work_order.usage_reading)
+                .unwrap_or_default();
+
+            let due_usage = last_usage + usage_interval;
+            (usage_reading? >= due_usage).then_some((as_of, Some(due_usage)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        asset_maintenance_plan_row::{AssetMaintenancePlanRow, AssetMaintenanceScheduleType},
+        asset_row::AssetRow,
+        asset_work_order_row::AssetWorkOrderRow,
+    };
+
+    use super::next_due;
+
+    #[test]
+    fn maintenance_next_due() {
+        let date = |day: u32| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        let asset = AssetRow {
+            installation_date: Some(date(1)),
+            ..Default::default()
+        };
+
+        // Interval, due 10 days after installation
+        let plan = AssetMaintenancePlanRow {
+            schedule_type: AssetMaintenanceScheduleType::Interval,
+            interval_days: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(next_due(&plan, &asset, None, date(5), 0, None), None);
+        assert_eq!(
+            next_due(&plan, &asset, None, date(5), 7, None),
+            Some((date(11), None))
+        );
+        assert_eq!(
+            next_due(&plan, &asset, None, date(20), 0, None),
+            Some((date(11), None))
+        );
+
+        // Interval, due 10 days after last completed work order
+        let last_completed = AssetWorkOrderRow {
+            completed_datetime: Some(date(12).and_hms_opt(10, 0, 0).unwrap()),
+            usage_reading: Some(120.0),
+            ..Default::default()
+        };
+        assert_eq!(
+            next_due(&plan, &asset, Some(&last_completed), date(20), 0, None),
+            None
+        );
+        assert_eq!(
+            next_due(&plan, &asset, Some(&last_completed), date(22), 0, None),
+            Some((date(22), None))
+        );
+
+        // Usage, due every 100 units after the last completed reading
+        let plan = AssetMaintenancePlanRow {
+            schedule_type: AssetMaintenanceScheduleType::Usage,
+            usage_interval: Some(100.0),
+            ..Default::default()
+        };
+        assert_eq!(next_due(&plan, &asset, None, date(5), 0, None), None);
+        assert_eq!(next_due(&plan, &asset, None, date(5), 0, Some(99.0)), None);
+        assert_eq!(
+            next_due(&plan, &asset, None, date(5), 0, Some(100.0)),
+            Some((date(5), Some(100.0)))
+        );
+        assert_eq!(
+            next_due(
+                &plan,
+                &asset,
+                Some(&last_completed),
+                date(5),
+                0,
+                Some(150.0)
+            ),
+            None
+        );
+        assert_eq!(
+            next_due(
+                &plan,
+                &asset,
+                Some(&last_completed),
+                date(5),
+                0,
+                Some(230.0)
+            ),
+            Some((date(5), Some(220.0)))
+        );
+    }
+}
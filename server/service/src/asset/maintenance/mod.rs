@@ -0,0 +1,6 @@
+pub mod checklist;
+pub mod complete_work_order;
+pub mod generate_work_orders;
+pub mod query;
+pub mod update_work_order;
+pub mod upsert_plan;
@@ -0,0 +1,65 @@
+use chrono::{Duration, NaiveDate};
+use repository::{
+    asset_maintenance_plan::{
+        AssetMaintenancePlan, AssetMaintenancePlanFilter, AssetMaintenancePlanRepository,
+        AssetMaintenancePlanSort,
+    },
+    asset_work_order::{
+        AssetWorkOrder, AssetWorkOrderFilter, AssetWorkOrderRepository, AssetWorkOrderSort,
+    },
+    asset_work_order_row::AssetWorkOrderStatus,
+    DateFilter, EqualFilter, PaginationOption, RepositoryError, StorageConnection,
+};
+
+use crate::{
+    get_default_pagination, i64_to_u32, service_provider::ServiceContext, ListError, ListResult,
+};
+
+pub const MAX_LIMIT: u32 = 1000;
+pub const MIN_LIMIT: u32 = 1;
+
+pub fn get_maintenance_plans(
+    connection: &StorageConnection,
+    pagination: Option<PaginationOption>,
+    filter: Option<AssetMaintenancePlanFilter>,
+    sort: Option<AssetMaintenancePlanSort>,
+) -> Result<ListResult<AssetMaintenancePlan>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let repository = AssetMaintenancePlanRepository::new(connection);
+
+    Ok(ListResult {
+        rows: repository.query(pagination, filter.clone(), sort)?,
+        count: i64_to_u32(repository.count(filter)?),
+    })
+}
+
+pub fn get_work_orders(
+    connection: &StorageConnection,
+    pagination: Option<PaginationOption>,
+    filter: Option<AssetWorkOrderFilter>,
+    sort: Option<AssetWorkOrderSort>,
+) -> Result<ListResult<AssetWorkOrder>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let repository = AssetWorkOrderRepository::new(connection);
+
+    Ok(ListResult {
+        rows: repository.query(pagination, filter.clone(), sort)?,
+        count: i64_to_u32(repository.count(filter)?),
+    })
+}
+
+/// Open (planned or in progress) work orders of the store that were due before `as_of`
+pub fn get_overdue_work_orders(
+    ctx: &ServiceContext,
+    as_of: NaiveDate,
+) -> Result<Vec<AssetWorkOrder>, RepositoryError> {
+    let filter = AssetWorkOrderFilter::new()
+        .store_id(EqualFilter::equal_to(&ctx.store_id))
+        .status(AssetWorkOrderStatus::equal_any(vec![
+            AssetWorkOrderStatus::Planned,
+            AssetWorkOrderStatus::InProgress,
+        ]))
+        .due_date(DateFilter::before_or_equal_to(as_of - Duration::days(1)));
+
+    AssetWorkOrderRepository::new(&ctx.connection).query_by_filter(filter)
+}
@@ -0,0 +1,116 @@
+use chrono::NaiveDate;
+use repository::{
+    asset_work_order_row::{AssetWorkOrderRow, AssetWorkOrderRowRepository, AssetWorkOrderStatus},
+    RepositoryError, StorageConnection,
+};
+
+use crate::service_provider::ServiceContext;
+
+use super::checklist::parse_work_order_checklist;
+
+#[derive(PartialEq, Debug)]
+pub enum UpdateWorkOrderError {
+    WorkOrderDoesNotExist,
+    NotThisStoreWorkOrder,
+    WorkOrderIsClosed,
+    /// Use complete work order instead
+    CannotSetStatusToCompleted,
+    InvalidChecklist,
+    UpdatedRecordNotFound,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct UpdateWorkOrder {
+    pub id: String,
+    pub status: Option<AssetWorkOrderStatus>,
+    pub due_date: Option<NaiveDate>,
+    pub checklist: Option<String>,
+    pub comment: Option<String>,
+}
+
+pub fn update_work_order(
+    ctx: &ServiceContext,
+    input: UpdateWorkOrder,
+) -> Result<AssetWorkOrderRow, UpdateWorkOrderError> {
+    let work_order = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let work_order = validate(connection, &ctx.store_id, &input)?;
+            let updated = generate(work_order, input);
+            let repository = AssetWorkOrderRowRepository::new(connection);
+            repository.upsert_one(&updated)?;
+
+            repository
+                .find_one_by_id(&updated.id)?
+                .ok_or(UpdateWorkOrderError::UpdatedRecordNotFound)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(work_order)
+}
+
+pub(crate) fn check_open_work_order(
+    connection: &StorageConnection,
+    store_id: &str,
+    id: &str,
+) -> Result<AssetWorkOrderRow, UpdateWorkOrderError> {
+    let work_order = AssetWorkOrderRowRepository::new(connection)
+        .find_one_by_id(id)?
+        .ok_or(UpdateWorkOrderError::WorkOrderDoesNotExist)?;
+
+    if work_order.store_id.as_deref() != Some(store_id) {
+        return Err(UpdateWorkOrderError::NotThisStoreWorkOrder);
+    }
+
+    if matches!(
+        work_order.status,
+        AssetWorkOrderStatus::Completed | AssetWorkOrderStatus::Cancelled
+    ) {
+        return Err(UpdateWorkOrderError::WorkOrderIsClosed);
+    }
+
+    Ok(work_order)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &UpdateWorkOrder,
+) -> Result<AssetWorkOrderRow, UpdateWorkOrderError> {
+    let work_order = check_open_work_order(connection, store_id, &input.id)?;
+
+    if input.status == Some(AssetWorkOrderStatus::Completed) {
+        return Err(UpdateWorkOrderError::CannotSetStatusToCompleted);
+    }
+
+    if input.checklist.is_some() && parse_work_order_checklist(&input.checklist).is_err() {
+        return Err(UpdateWorkOrderError::InvalidChecklist);
+    }
+
+    Ok(work_order)
+}
+
+fn generate(
+    existing: AssetWorkOrderRow,
+    UpdateWorkOrder {
+        id: _,
+        status,
+        due_date,
+        checklist,
+        comment,
+    }: UpdateWorkOrder,
+) -> AssetWorkOrderRow {
+    AssetWorkOrderRow {
+        status: status.unwrap_or(existing.status),
+        due_date: due_date.unwrap_or(existing.due_date),
+        checklist: checklist.or(existing.checklist),
+        comment: comment.or(existing.comment),
+        ..existing
+    }
+}
+
+impl From<RepositoryError> for UpdateWorkOrderError {
+    fn from(error: RepositoryError) -> Self {
+        UpdateWorkOrderError::DatabaseError(error)
+    }
+}
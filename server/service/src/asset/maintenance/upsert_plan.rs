@@ -0,0 +1,128 @@
+use repository::{
+    asset_maintenance_plan_row::{
+        AssetMaintenancePlanRow, AssetMaintenancePlanRowRepository, AssetMaintenanceScheduleType,
+    },
+    asset_type_row::AssetTypeRowRepository,
+    RepositoryError, StorageConnection,
+};
+
+use crate::service_provider::ServiceContext;
+
+use super::checklist::parse_plan_checklist;
+
+#[derive(PartialEq, Debug)]
+pub enum UpsertMaintenancePlanError {
+    AssetTypeDoesNotExist,
+    PlanNameCannotBeEmpty,
+    IntervalDaysRequired,
+    UsageIntervalRequired,
+    InvalidChecklist,
+    UpsertedRecordNotFound,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct UpsertMaintenancePlan {
+    pub id: String,
+    pub asset_type_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub schedule_type: AssetMaintenanceScheduleType,
+    pub interval_days: Option<i32>,
+    pub usage_interval: Option<f64>,
+    pub usage_unit: Option<String>,
+    /// JSON array of task descriptions
+    pub checklist: Option<String>,
+    pub is_active: bool,
+}
+
+pub fn upsert_maintenance_plan(
+    ctx: &ServiceContext,
+    input: UpsertMaintenancePlan,
+) -> Result<AssetMaintenancePlanRow, UpsertMaintenancePlanError> {
+    let plan = ctx
+        .connection
+        .transaction_sync(|connection| {
+            validate(connection, &input)?;
+            let row = generate(input);
+            let repository = AssetMaintenancePlanRowRepository::new(connection);
+            repository.upsert_one(&row)?;
+
+            repository
+                .find_one_by_id(&row.id)?
+                .ok_or(UpsertMaintenancePlanError::UpsertedRecordNotFound)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(plan)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    input: &UpsertMaintenancePlan,
+) -> Result<(), UpsertMaintenancePlanError> {
+    use UpsertMaintenancePlanError::*;
+
+    if AssetTypeRowRepository::new(connection)
+        .find_one_by_id(&input.asset_type_id)?
+        .is_none()
+    {
+        return Err(AssetTypeDoesNotExist);
+    }
+
+    if input.name.trim().is_empty() {
+        return Err(PlanNameCannotBeEmpty);
+    }
+
+    match input.schedule_type {
+        AssetMaintenanceScheduleType::Interval => {
+            if !matches!(input.interval_days, Some(days) if days > 0) {
+                return Err(IntervalDaysRequired);
+            }
+        }
+        AssetMaintenanceScheduleType::Usage => {
+            if !matches!(input.usage_interval, Some(usage) if usage > 0.0) {
+                return Err(UsageIntervalRequired);
+            }
+        }
+    }
+
+    if parse_plan_checklist(&input.checklist).is_err() {
+        return Err(InvalidChecklist);
+    }
+
+    Ok(())
+}
+
+fn generate(
+    UpsertMaintenancePlan {
+        id,
+        asset_type_id,
+        name,
+        description,
+        schedule_type,
+        interval_days,
+        usage_interval,
+        usage_unit,
+        checklist,
+        is_active,
+    }: UpsertMaintenancePlan,
+) -> AssetMaintenancePlanRow {
+    AssetMaintenancePlanRow {
+        id,
+        asset_type_id,
+        name: name.trim().to_string(),
+        description,
+        schedule_type,
+        interval_days,
+        usage_interval,
+        usage_unit,
+        checklist,
+        is_active,
+    }
+}
+
+impl From<RepositoryError> for UpsertMaintenancePlanError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertMaintenancePlanError::DatabaseError(error)
+    }
+}
@@ -5,6 +5,17 @@ use self::insert_log::{insert_asset_log, InsertAssetLog, InsertAssetLogError};
 use self::insert_log_reason::{
     insert_asset_log_reason, InsertAssetLogReason, InsertAssetLogReasonError,
 };
+use self::maintenance::complete_work_order::{
+    complete_work_order, CompleteWorkOrder, CompleteWorkOrderError,
+};
+use self::maintenance::generate_work_orders::{generate_work_orders, GenerateWorkOrders};
+use self::maintenance::query::{get_maintenance_plans, get_overdue_work_orders, get_work_orders};
+use self::maintenance::update_work_order::{
+    update_work_order, UpdateWorkOrder, UpdateWorkOrderError,
+};
+use self::maintenance::upsert_plan::{
+    upsert_maintenance_plan, UpsertMaintenancePlan, UpsertMaintenancePlanError,
+};
 use self::query::{get_asset, get_assets};
 use self::query_asset_property::get_asset_properties;
 use self::query_log::{get_asset_log, get_asset_logs};
@@ -13,13 +24,22 @@ use self::update::{update_asset, UpdateAsset, UpdateAssetError};
 
 use super::{ListError, ListResult};
 use crate::{service_provider::ServiceContext, SingleRecordError};
+use chrono::NaiveDate;
 use parse::AssetFromGs1Error;
 use repository::asset_log_reason::{AssetLogReason, AssetLogReasonFilter, AssetLogReasonSort};
 use repository::asset_property::AssetPropertyFilter;
 use repository::asset_property_row::AssetPropertyRow;
 use repository::assets::asset::{Asset, AssetFilter, AssetSort};
 use repository::assets::asset_log::{AssetLog, AssetLogFilter, AssetLogSort};
-use repository::{PaginationOption, StorageConnection};
+use repository::assets::asset_maintenance_plan::{
+    AssetMaintenancePlan, AssetMaintenancePlanFilter, AssetMaintenancePlanSort,
+};
+use repository::assets::asset_maintenance_plan_row::AssetMaintenancePlanRow;
+use repository::assets::asset_work_order::{
+    AssetWorkOrder, AssetWorkOrderFilter, AssetWorkOrderSort,
+};
+use repository::assets::asset_work_order_row::AssetWorkOrderRow;
+use repository::{PaginationOption, RepositoryError, StorageConnection};
 use util::GS1DataElement;
 
 pub mod delete;
@@ -29,6 +49,7 @@ pub mod insert_asset_property;
 pub mod insert_log;
 pub mod insert_log_reason;
 pub mod location;
+pub mod maintenance;
 pub mod parse;
 pub mod query;
 pub mod query_asset_property;
@@ -147,6 +168,66 @@ pub trait AssetServiceTrait: Sync + Send {
     ) -> Result<Asset, AssetFromGs1Error> {
         parse::get_or_create_from_gs1_data(ctx, gs1_data)
     }
+
+    fn get_maintenance_plans(
+        &self,
+        connection: &StorageConnection,
+        pagination: Option<PaginationOption>,
+        filter: Option<AssetMaintenancePlanFilter>,
+        sort: Option<AssetMaintenancePlanSort>,
+    ) -> Result<ListResult<AssetMaintenancePlan>, ListError> {
+        get_maintenance_plans(connection, pagination, filter, sort)
+    }
+
+    fn upsert_maintenance_plan(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertMaintenancePlan,
+    ) -> Result<AssetMaintenancePlanRow, UpsertMaintenancePlanError> {
+        upsert_maintenance_plan(ctx, input)
+    }
+
+    fn get_work_orders(
+        &self,
+        connection: &StorageConnection,
+        pagination: Option<PaginationOption>,
+        filter: Option<AssetWorkOrderFilter>,
+        sort: Option<AssetWorkOrderSort>,
+    ) -> Result<ListResult<AssetWorkOrder>, ListError> {
+        get_work_orders(connection, pagination, filter, sort)
+    }
+
+    fn get_overdue_work_orders(
+        &self,
+        ctx: &ServiceContext,
+        as_of: NaiveDate,
+    ) -> Result<Vec<AssetWorkOrder>, RepositoryError> {
+        get_overdue_work_orders(ctx, as_of)
+    }
+
+    fn generate_work_orders(
+        &self,
+        ctx: &ServiceContext,
+        input: GenerateWorkOrders,
+    ) -> Result<Vec<AssetWorkOrderRow>, RepositoryError> {
+        generate_work_orders(ctx, input)
+    }
+
+    fn update_work_order(
+        &self,
+        ctx: &ServiceContext,
+        input: UpdateWorkOrder,
+    ) -> Result<AssetWorkOrderRow, UpdateWorkOrderError> {
+        update_work_order(ctx, input)
+    }
+
+    fn complete_work_order(
+        &self,
+        ctx: &ServiceContext,
+        input: CompleteWorkOrder,
+    ) -> Result<AssetWorkOrderRow, CompleteWorkOrderError> {
+        complete_work_order(ctx, input)
+    }
 }
 
 pub struct AssetService {}
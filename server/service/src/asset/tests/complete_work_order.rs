@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod query {
+    use repository::{
+        asset_log_row::{AssetLogRowRepository, AssetLogStatus},
+        asset_work_order_row::{
+            AssetWorkOrderRow, AssetWorkOrderRowRepository, AssetWorkOrderStatus,
+        },
+        asset_work_order_spare_part_row::AssetWorkOrderSparePartRowRepository,
+        mock::{
+            mock_asset_a, mock_stock_line_a, mock_store_a, mock_user_account_a, MockDataInserts,
+        },
+        test_db::setup_all,
+        StockLineRowRepository,
+    };
+
+    use crate::{
+        asset::maintenance::complete_work_order::{
+            CompleteWorkOrder, CompleteWorkOrderError, WorkOrderSparePart,
+        },
+        invoice::inventory_adjustment::InsertInventoryAdjustmentError,
+        service_provider::ServiceProvider,
+    };
+
+    fn work_order() -> AssetWorkOrderRow {
+        AssetWorkOrderRow {
+            id: "work_order".to_string(),
+            asset_id: mock_asset_a().id,
+            store_id: Some(mock_store_a().id),
+            status: AssetWorkOrderStatus::InProgress,
+            checklist: Some(r#"[{"task":"Defrost","is_done":false}]"#.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[actix_rt::test]
+    async fn complete_work_order_service() {
+        let (_, connection, connection_manager, _) =
+            setup_all("complete_work_order_service", MockDataInserts::all()).await;
+
+        AssetWorkOrderRowRepository::new(&connection)
+            .upsert_one(&work_order())
+            .unwrap();
+
+        let service_provider = ServiceProvider::new(connection_manager);
+        let ctx = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = &service_provider.asset_service;
+        let spare_part = |stock_line_id: String| WorkOrderSparePart {
+            stock_line_id,
+            number_of_packs: 5.0,
+            inventory_adjustment_reason_id: None,
+        };
+
+        // Checklist isn't done
+        assert_eq!(
+            service.complete_work_order(
+                &ctx,
+                CompleteWorkOrder {
+                    id: work_order().id,
+                    ..Default::default()
+                }
+            ),
+            Err(CompleteWorkOrderError::ChecklistIncomplete)
+        );
+
+        // Spare part stock line doesn't exist, nothing is issued or completed
+        let done_checklist = Some(r#"[{"task":"Defrost","is_done":true}]"#.to_string());
+        assert_eq!(
+            service.complete_work_order(
+                &ctx,
+                CompleteWorkOrder {
+                    id: work_order().id,
+                    checklist: done_checklist.clone(),
+                    spare_parts: vec![
+                        spare_part(mock_stock_line_a().id),
+                        spare_part("invalid".to_string())
+                    ],
+                    ..Default::default()
+                }
+            ),
+            Err(CompleteWorkOrderError::SparePartError(
+                InsertInventoryAdjustmentError::StockLineDoesNotExist
+            ))
+        );
+        let stock_line = StockLineRowRepository::new(&connection)
+            .find_one_by_id(&mock_stock_line_a().id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stock_line.total_number_of_packs, 40.0);
+
+        // Success
+        let completed = service
+            .complete_work_order(
+                &ctx,
+                CompleteWorkOrder {
+                    id: work_order().id,
+                    checklist: done_checklist,
+                    asset_status: Some(AssetLogStatus::FunctioningButNeedsAttention),
+                    spare_parts: vec![spare_part(mock_stock_line_a().id)],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(completed.status, AssetWorkOrderStatus::Completed);
+        assert_eq!(completed.completed_by_id, Some(mock_user_account_a().id));
+
+        // Spare part is issued from stock with an inventory reduction
+        let stock_line = StockLineRowRepository::new(&connection)
+            .find_one_by_id(&mock_stock_line_a().id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stock_line.available_number_of_packs, 25.0);
+        assert_eq!(stock_line.total_number_of_packs, 35.0);
+
+        let spare_parts = AssetWorkOrderSparePartRowRepository::new(&connection)
+            .find_many_by_work_order_id(&work_order().id)
+            .unwrap();
+        assert_eq!(spare_parts.len(), 1);
+        assert_eq!(spare_parts[0].stock_line_id, mock_stock_line_a().id);
+        assert_eq!(spare_parts[0].number_of_packs, 5.0);
+
+        // Asset log entry with the status after maintenance
+        let asset_log = AssetLogRowRepository::new(&connection)
+            .find_one_by_id(completed.asset_log_id.as_deref().unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(asset_log.asset_id, mock_asset_a().id);
+        assert_eq!(
+            asset_log.status,
+            Some(AssetLogStatus::FunctioningButNeedsAttention)
+        );
+        assert_eq!(asset_log.comment, Some("Maintenance completed".to_string()));
+
+        // Work order can't be completed twice
+        assert_eq!(
+            service.complete_work_order(
+                &ctx,
+                CompleteWorkOrder {
+                    id: work_order().id,
+                    ..Default::default()
+                }
+            ),
+            Err(CompleteWorkOrderError::WorkOrderIsClosed)
+        );
+    }
+}
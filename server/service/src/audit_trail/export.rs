@@ -0,0 +1,122 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use repository::{AuditAction, ChangelogTableName, FieldChange, RepositoryError};
+use serde::Serialize;
+use util::to_csv;
+
+use crate::{
+    service_provider::ServiceContext,
+    static_files::{StaticFileCategory, StaticFileService},
+};
+
+use super::{get_store_audit_trail, AuditTrailEntry};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuditTrailExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug)]
+pub enum ExportAuditTrailError {
+    InvalidPeriod,
+    DatabaseError(RepositoryError),
+    FileGenerationError(String),
+}
+
+#[derive(Serialize)]
+struct AuditTrailExportRow<'a> {
+    datetime: NaiveDateTime,
+    record_table_name: &'a ChangelogTableName,
+    record_id: &'a str,
+    action: &'a AuditAction,
+    user_id: &'a Option<String>,
+    changes: &'a [FieldChange],
+}
+
+/// Exports the changes to the store's records in the period and returns the file id
+pub fn export_audit_trail(
+    ctx: &ServiceContext,
+    base_dir: &Option<String>,
+    from_datetime: NaiveDateTime,
+    to_datetime: NaiveDateTime,
+    format: AuditTrailExportFormat,
+) -> Result<String, ExportAuditTrailError> {
+    if from_datetime > to_datetime {
+        return Err(ExportAuditTrailError::InvalidPeriod);
+    }
+    let entries = get_store_audit_trail(ctx, from_datetime, to_datetime)?;
+
+    let (extension, content) = match format {
+        AuditTrailExportFormat::Csv => ("csv", audit_trail_to_csv(&entries)),
+        AuditTrailExportFormat::Json => {
+            let rows: Vec<AuditTrailExportRow> = entries
+                .iter()
+                .map(|entry| AuditTrailExportRow {
+                    datetime: entry.row.datetime,
+                    record_table_name: &entry.row.record_table_name,
+                    record_id: &entry.row.record_id,
+                    action: &entry.row.action,
+                    user_id: &entry.row.user_id,
+                    changes: &entry.changes,
+                })
+                .collect();
+            (
+                "json",
+                serde_json::to_string_pretty(&rows)
+                    .map_err(|err| ExportAuditTrailError::FileGenerationError(err.to_string()))?,
+            )
+        }
+    };
+
+    let file_service = StaticFileService::new(base_dir)
+        .map_err(|err| ExportAuditTrailError::FileGenerationError(format!("{}", err)))?;
+    let now: DateTime<Utc> = Utc::now();
+    let file = file_service
+        .store_file(
+            &format!("{}_audit_trail.{}", now.format("%Y%m%d_%H%M%S"), extension),
+            StaticFileCategory::Temporary,
+            content.as_bytes(),
+        )
+        .map_err(|err| ExportAuditTrailError::FileGenerationError(format!("{}", err)))?;
+    Ok(file.id)
+}
+
+/// One row per changed field, with the change details repeated on each row
+pub(crate) fn audit_trail_to_csv(entries: &[AuditTrailEntry]) -> String {
+    let headers = [
+        "datetime",
+        "table_name",
+        "record_id",
+        "action",
+        "user_id",
+        "field",
+        "before",
+        "after",
+    ];
+
+    let rows: Vec<Vec<String>> = entries
+        .iter()
+        .flat_map(|entry| {
+            entry.changes.iter().map(move |change| {
+                vec![
+                    entry.row.datetime.to_string(),
+                    format!("{:?}", entry.row.record_table_name),
+                    entry.row.record_id.clone(),
+                    format!("{:?}", entry.row.action),
+                    entry.row.user_id.clone().unwrap_or_default(),
+                    change.field.clone(),
+                    change.before.to_string(),
+                    change.after.to_string(),
+                ]
+            })
+        })
+        .collect();
+
+    to_csv(&headers, &rows)
+}
+
+impl From<RepositoryError> for ExportAuditTrailError {
+    fn from(error: RepositoryError) -> Self {
+        ExportAuditTrailError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::NaiveDateTime;
+use repository::{
+    AuditTrailRow, AuditTrailRowRepository, ChangelogTableName, FieldChange, RepositoryError,
+};
+
+use self::export::{export_audit_trail, AuditTrailExportFormat, ExportAuditTrailError};
+use crate::service_provider::ServiceContext;
+
+pub mod export;
+
+/// Records with field level changes in the audit trail
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AuditedRecordType {
+    Invoice,
+    InvoiceLine,
+    StockLine,
+    StocktakeLine,
+    Requisition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditTrailEntry {
+    pub row: AuditTrailRow,
+    pub changes: Vec<FieldChange>,
+}
+
+pub fn get_record_audit_trail(
+    ctx: &ServiceContext,
+    record_type: AuditedRecordType,
+    record_id: &str,
+) -> Result<Vec<AuditTrailEntry>, RepositoryError> {
+    let rows = AuditTrailRowRepository::new(&ctx.connection)
+        .find_many_by_record(record_type.to_table_name(), record_id)?;
+    to_entries(rows)
+}
+
+/// Changes to records of the context store in the period
+pub fn get_store_audit_trail(
+    ctx: &ServiceContext,
+    from_datetime: NaiveDateTime,
+    to_datetime: NaiveDateTime,
+) -> Result<Vec<AuditTrailEntry>, RepositoryError> {
+    let rows = AuditTrailRowRepository::new(&ctx.connection).find_many_by_store(
+        &ctx.store_id,
+        from_datetime,
+        to_datetime,
+    )?;
+    to_entries(rows)
+}
+
+fn to_entries(rows: Vec<AuditTrailRow>) -> Result<Vec<AuditTrailEntry>, RepositoryError> {
+    rows.into_iter()
+        .map(|row| {
+            let changes = row.field_changes().map_err(|error| {
+                RepositoryError::as_db_error("Invalid audit trail changes", error.to_string())
+            })?;
+            Ok(AuditTrailEntry { row, changes })
+        })
+        .collect()
+}
+
+impl AuditedRecordType {
+    pub fn to_table_name(self) -> ChangelogTableName {
+        match self {
+            AuditedRecordType::Invoice => ChangelogTableName::Invoice,
+            AuditedRecordType::InvoiceLine => ChangelogTableName::InvoiceLine,
+            AuditedRecordType::StockLine => ChangelogTableName::StockLine,
+            AuditedRecordType::StocktakeLine => ChangelogTableName::StocktakeLine,
+            AuditedRecordType::Requisition => ChangelogTableName::Requisition,
+        }
+    }
+
+    pub fn from_table_name(table_name: &ChangelogTableName) -> Option<Self> {
+        let record_type = match table_name {
+            ChangelogTableName::Invoice => AuditedRecordType::Invoice,
+            ChangelogTableName::InvoiceLine => AuditedRecordType::InvoiceLine,
+            ChangelogTableName::StockLine => AuditedRecordType::StockLine,
+            ChangelogTableName::StocktakeLine => AuditedRecordType::StocktakeLine,
+            ChangelogTableName::Requisition => AuditedRecordType::Requisition,
+            _ => return None,
+        };
+        Some(record_type)
+    }
+}
+
+pub trait AuditTrailServiceTrait: Sync + Send {
+    fn get_record_audit_trail(
+        &self,
+        ctx: &ServiceContext,
+        record_type: AuditedRecordType,
+        record_id: &str,
+    ) -> Result<Vec<AuditTrailEntry>, RepositoryError> {
+        get_record_audit_trail(ctx, record_type, record_id)
+    }
+
+    fn export_audit_trail(
+        &self,
+        ctx: &ServiceContext,
+        base_dir: &Option<String>,
+        from_datetime: NaiveDateTime,
+        to_datetime: NaiveDateTime,
+        format: AuditTrailExportFormat,
+    ) -> Result<String, ExportAuditTrailError> {
+        export_audit_trail(ctx, base_dir, from_datetime, to_datetime, format)
+    }
+}
+
+pub struct AuditTrailService {}
+impl AuditTrailServiceTrait for AuditTrailService {}
+
+#[cfg(test)]
+mod tests;
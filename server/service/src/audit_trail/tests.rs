@@ -0,0 +1,114 @@
+use chrono::{Duration, Utc};
+use repository::{
+    mock::{mock_item_a, mock_store_a, MockDataInserts},
+    test_db::setup_all,
+    AuditAction, StockLineRow, StockLineRowRepository,
+};
+use serde_json::json;
+
+use crate::{
+    audit_trail::{
+        export::audit_trail_to_csv, get_record_audit_trail, get_store_audit_trail,
+        AuditedRecordType,
+    },
+    service_provider::ServiceProvider,
+};
+
+fn stock_line() -> StockLineRow {
+    StockLineRow {
+        id: "audited_stock_line".to_string(),
+        item_link_id: mock_item_a().id,
+        store_id: mock_store_a().id,
+        pack_size: 1.0,
+        total_number_of_packs: 10.0,
+        available_number_of_packs: 10.0,
+        ..Default::default()
+    }
+}
+
+#[actix_rt::test]
+async fn audit_trail_records_field_changes() {
+    let (_, connection, connection_manager, _) = setup_all(
+        "audit_trail_records_field_changes",
+        MockDataInserts::none().names().stores().units().items(),
+    )
+    .await;
+
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider
+        .context(mock_store_a().id, "user_account_a".to_string())
+        .unwrap();
+    let repo = StockLineRowRepository::new(&context.connection);
+
+    repo.upsert_one(&stock_line()).unwrap();
+    repo.upsert_one(&StockLineRow {
+        available_number_of_packs: 6.0,
+        note: Some("counted".to_string()),
+        ..stock_line()
+    })
+    .unwrap();
+    // Unchanged records aren't recorded
+    repo.upsert_one(&StockLineRow {
+        available_number_of_packs: 6.0,
+        note: Some("counted".to_string()),
+        ..stock_line()
+    })
+    .unwrap();
+
+    let entries =
+        get_record_audit_trail(&context, AuditedRecordType::StockLine, &stock_line().id).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].row.action, AuditAction::Insert);
+    assert_eq!(entries[0].row.user_id, Some("user_account_a".to_string()));
+    assert_eq!(entries[0].row.store_id, Some(mock_store_a().id));
+
+    let update = &entries[1];
+    assert_eq!(update.row.action, AuditAction::Update);
+    let mut changes = update.changes.clone();
+    changes.sort_by(|a, b| a.field.cmp(&b.field));
+    assert_eq!(
+        changes
+            .iter()
+            .map(|change| (change.field.as_str(), &change.before, &change.after))
+            .collect::<Vec<_>>(),
+        vec![
+            ("available_number_of_packs", &json!(10.0), &json!(6.0)),
+            ("note", &json!(null), &json!("counted")),
+        ]
+    );
+
+    // Changes on connections without an audit user aren't recorded
+    StockLineRowRepository::new(&connection)
+        .upsert_one(&StockLineRow {
+            available_number_of_packs: 1.0,
+            ..stock_line()
+        })
+        .unwrap();
+    StockLineRowRepository::new(&context.connection)
+        .delete(&stock_line().id)
+        .unwrap();
+    let entries =
+        get_record_audit_trail(&context, AuditedRecordType::StockLine, &stock_line().id).unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[2].row.action, AuditAction::Delete);
+    assert_eq!(
+        entries[2].changes.len(),
+        entries[2]
+            .changes
+            .iter()
+            .filter(|change| change.after == json!(null))
+            .count()
+    );
+
+    let now = Utc::now().naive_utc();
+    let store_entries =
+        get_store_audit_trail(&context, now - Duration::hours(1), now + Duration::hours(1))
+            .unwrap();
+    assert_eq!(store_entries.len(), 3);
+
+    let csv = audit_trail_to_csv(&store_entries[1..2]);
+    assert!(csv.starts_with("datetime,table_name,record_id,action,user_id,field,before,after\r\n"));
+    assert!(csv.contains(
+        ",StockLine,audited_stock_line,Update,user_account_a,note,null,\"\"\"counted\"\"\"\r\n"
+    ));
+}
@@ -1,16 +1,17 @@
 use std::collections::HashMap;
 
-use repository::{
-    EqualFilter, Pagination, PermissionType, RepositoryError, UserPermissionFilter,
-    UserPermissionRepository, UserPermissionRow,
-};
+use chrono::Utc;
+use repository::{PermissionType, RepositoryError, UserPermissionRow};
+use serde::{Deserialize, Serialize};
 use util::{constants::PATIENT_CONTEXT_ID, uuid::uuid};
 
 use crate::{
+    api_token::{is_api_token, use_api_token},
     auth_data::AuthData,
+    role::resolve_user_permissions,
     service_provider::ServiceContext,
     settings::is_develop,
-    token::{JWTValidationError, OmSupplyClaim, TokenService},
+    token::{Audience, JWTValidationError, OmSupplyClaim, TokenService},
 };
 
 #[derive(Debug, Clone)]
@@ -26,7 +27,7 @@ pub enum PermissionDSL {
 }
 
 /// Resources for permission checks
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Resource {
     RouteMe,
     // name
@@ -113,6 +114,9 @@ pub enum Resource {
     // RnR
     QueryRnRForms,
     MutateRnRForms,
+    // period close
+    QueryPeriodLock,
+    MutatePeriodLock,
 
     SyncInfo,
     ManualSync,
@@ -347,6 +351,15 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
             PermissionDSL::HasPermission(PermissionType::RnrFormMutate),
         ]),
     );
+    // period close
+    map.insert(Resource::QueryPeriodLock, PermissionDSL::HasStoreAccess);
+    map.insert(
+        Resource::MutatePeriodLock,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(PermissionType::PeriodLockMutate),
+        ]),
+    );
     // invoice
     map.insert(
         Resource::QueryInvoice,
@@ -608,10 +621,11 @@ fn dummy_user_auth() -> ValidatedUserAuth {
         user_id: user_id.to_string(),
         claims: OmSupplyClaim {
             exp: 0,
-            aud: crate::token::Audience::Api,
+            aud: Audience::Api,
             iat: 0,
             iss: "omSupply-debug".to_string(),
             sub: user_id.to_string(),
+            jti: None,
         },
     }
 }
@@ -632,6 +646,11 @@ pub fn validate_auth(
             )));
         }
     };
+    if is_api_token(auth_token) {
+        return Err(AuthError::Denied(AuthDeniedKind::NotAuthenticated(
+            "Api tokens can only be used for resource requests".to_string(),
+        )));
+    }
     let service = TokenService::new(
         &auth_data.token_bucket,
         auth_data.auth_token_secret.as_bytes(),
@@ -812,6 +831,54 @@ impl AuthService {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Checks the api token is valid and the request is within the scope of the token. The request
+    /// is then validated against the permissions of the user of the token.
+    fn validate_api_token_auth(
+        &self,
+        context: &ServiceContext,
+        token: &str,
+        resource_request: &ResourceAccessRequest,
+    ) -> Result<ValidatedUserAuth, AuthError> {
+        let not_authenticated =
+            |msg: &str| AuthError::Denied(AuthDeniedKind::NotAuthenticated(msg.to_string()));
+
+        let api_token = use_api_token(&context.connection, token)?
+            .ok_or_else(|| not_authenticated("Invalid api token"))?;
+        let now = Utc::now().naive_utc();
+        if api_token.is_expired(now) {
+            return Err(not_authenticated("Expired api token"));
+        }
+        if !api_token.allows(resource_request) {
+            return Err(AuthError::Denied(AuthDeniedKind::InsufficientPermission {
+                msg: format!(
+                    "Api token {} has no access to {:?}",
+                    api_token.api_token_row.name, resource_request
+                ),
+                required_permissions: self
+                    .resource_permissions
+                    .get(&resource_request.resource)
+                    .cloned()
+                    .unwrap_or(PermissionDSL::NoPermissionRequired),
+            }));
+        }
+
+        let row = api_token.api_token_row;
+        Ok(ValidatedUserAuth {
+            user_id: row.user_id.clone(),
+            claims: OmSupplyClaim {
+                exp: row
+                    .expiry_datetime
+                    .map(|expiry| expiry.and_utc().timestamp() as usize)
+                    .unwrap_or(0),
+                aud: Audience::Api,
+                iat: row.created_datetime.and_utc().timestamp() as usize,
+                iss: "omSupply-api-token".to_string(),
+                sub: row.user_id,
+                jti: Some(row.id),
+            },
+        })
+    }
 }
 
 impl AuthServiceTrait for AuthService {
@@ -822,18 +889,19 @@ impl AuthServiceTrait for AuthService {
         auth_token: &Option<String>,
         resource_request: &ResourceAccessRequest,
     ) -> Result<ValidatedUser, AuthError> {
-        let validated_auth = validate_auth(auth_data, auth_token)?;
         let connection = &context.connection;
+        let validated_auth = match auth_token.as_deref().filter(|token| is_api_token(token)) {
+            Some(api_token) => {
+                self.validate_api_token_auth(context, api_token, resource_request)?
+            }
+            None => validate_auth(auth_data, auth_token)?,
+        };
 
-        let mut permission_filter =
-            UserPermissionFilter::new().user_id(EqualFilter::equal_to(&validated_auth.user_id));
-        if let Some(store_id) = &resource_request.store_id {
-            permission_filter = permission_filter.store_id(EqualFilter::equal_to(store_id));
-        }
-        let mut user_permissions = UserPermissionRepository::new(connection).query(
-            Pagination::all(),
-            Some(permission_filter),
-            None,
+        // Includes the permissions given by the user's roles
+        let mut user_permissions = resolve_user_permissions(
+            connection,
+            &validated_auth.user_id,
+            resource_request.store_id.as_deref(),
         )?;
 
         // Dynamically add Patient context permissions if the user has PatientQuery/PatientMutate
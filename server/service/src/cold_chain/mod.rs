@@ -10,6 +10,10 @@ use self::insert_temperature_breach::{
     insert_temperature_breach, InsertTemperatureBreach, InsertTemperatureBreachError,
 };
 use self::query_temperature_breach::{get_temperature_breach, temperature_breaches};
+use self::storage_gap::{
+    get_cold_storage_gap_report, get_store_cold_storage_gap, ColdStorageGapError,
+    ColdStorageGapReport,
+};
 use self::update_temperature_breach::{
     update_temperature_breach, update_temperature_breach_acknowledgement, UpdateTemperatureBreach,
     UpdateTemperatureBreachAcknowledgement, UpdateTemperatureBreachError,
@@ -20,12 +24,13 @@ use repository::temperature_breach::{
     TemperatureBreach, TemperatureBreachFilter, TemperatureBreachSort,
 };
 use repository::temperature_log::{TemperatureLog, TemperatureLogFilter, TemperatureLogSort};
-use repository::{PaginationOption, StorageConnection};
+use repository::{PaginationOption, StorageConnection, StoreFilter};
 
 pub mod insert_temperature_breach;
 pub mod insert_temperature_log;
 pub mod query_temperature_breach;
 pub mod query_temperature_log;
+pub mod storage_gap;
 pub mod update_temperature_breach;
 pub mod update_temperature_log;
 mod validate;
@@ -105,6 +110,24 @@ pub trait ColdChainServiceTrait: Sync + Send {
     ) -> Result<TemperatureBreach, UpdateTemperatureBreachError> {
         update_temperature_breach_acknowledgement(ctx, input)
     }
+
+    fn get_store_cold_storage_gap(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        year: Option<i32>,
+    ) -> Result<ColdStorageGapReport, ColdStorageGapError> {
+        get_store_cold_storage_gap(ctx, store_id, year)
+    }
+
+    fn get_cold_storage_gap_report(
+        &self,
+        ctx: &ServiceContext,
+        store_filter: Option<StoreFilter>,
+        year: Option<i32>,
+    ) -> Result<ColdStorageGapReport, ColdStorageGapError> {
+        get_cold_storage_gap_report(ctx, store_filter, year)
+    }
 }
 
 pub struct ColdChainService {}
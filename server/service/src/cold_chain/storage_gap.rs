@@ -0,0 +1,513 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, Utc};
+use repository::{
+    asset::{AssetFilter, AssetRepository},
+    asset_catalogue_item_row::AssetCatalogueItemRowRepository,
+    asset_log_row::AssetLogStatus,
+    item_variant::{
+        item_variant::{ItemVariantFilter, ItemVariantRepository},
+        packaging_variant::{PackagingVariantFilter, PackagingVariantRepository},
+    },
+    vaccine_course::{
+        vaccine_course::{VaccineCourseFilter, VaccineCourseRepository},
+        vaccine_course_dose::{VaccineCourseDoseFilter, VaccineCourseDoseRepository},
+        vaccine_course_item::{VaccineCourseItemFilter, VaccineCourseItemRepository},
+        vaccine_course_row::VaccineCourseRow,
+    },
+    ColdStorageTypeRow, ColdStorageTypeRowRepository, DemographicIndicatorFilter,
+    DemographicIndicatorRepository, DemographicIndicatorRow, EqualFilter, RepositoryError,
+    StorageConnection, Store, StoreFilter, StoreRepository,
+};
+use serde_json::Value;
+
+use crate::{service_provider::ServiceContext, sync::CentralServerConfig};
+
+// Name property keys configured for stores through the gaps name properties
+pub const POPULATION_SERVED_KEY: &str = "population_served";
+pub const SUPPLY_INTERVAL_KEY: &str = "supply_interval";
+pub const BUFFER_STOCK_KEY: &str = "buffer_stock";
+
+/// Used when a store doesn't have a supply interval configured
+const DEFAULT_SUPPLY_INTERVAL_MONTHS: f64 = 1.0;
+/// Packaging level of the primary (vial) packaging, which holds volume per dose
+const PRIMARY_PACKAGING_LEVEL: i32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ColdStorageBand {
+    Plus5,
+    Minus20,
+    Minus70,
+}
+
+impl ColdStorageBand {
+    pub fn all() -> Vec<ColdStorageBand> {
+        vec![
+            ColdStorageBand::Plus5,
+            ColdStorageBand::Minus20,
+            ColdStorageBand::Minus70,
+        ]
+    }
+
+    /// Asset (and asset catalogue item) property holding storage capacity in litres
+    pub fn capacity_property_key(&self) -> &'static str {
+        match self {
+            ColdStorageBand::Plus5 => "storage_capacity_5c",
+            ColdStorageBand::Minus20 => "storage_capacity_20c",
+            ColdStorageBand::Minus70 => "storage_capacity_70c",
+        }
+    }
+
+    pub fn from_cold_storage_type(cold_storage_type: &ColdStorageTypeRow) -> Self {
+        match cold_storage_type.max_temperature {
+            t if t <= -60.0 => ColdStorageBand::Minus70,
+            t if t <= -10.0 => ColdStorageBand::Minus20,
+            _ => ColdStorageBand::Plus5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColdStorageGapLine {
+    pub band: ColdStorageBand,
+    pub required_litres: f64,
+    pub available_litres: f64,
+}
+
+impl ColdStorageGapLine {
+    /// Negative when there isn't enough functioning cold storage
+    pub fn gap_litres(&self) -> f64 {
+        self.available_litres - self.required_litres
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StoreColdStorageGap {
+    pub store_id: String,
+    pub store_name: String,
+    pub population_served: Option<f64>,
+    pub lines: Vec<ColdStorageGapLine>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColdStorageGapReport {
+    pub year: i32,
+    pub stores: Vec<StoreColdStorageGap>,
+    /// Sum of all stores, per band
+    pub total: Vec<ColdStorageGapLine>,
+    /// Active vaccine courses that couldn't be included, as none of their items have a
+    /// primary packaging volume
+    pub vaccine_courses_without_volume: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ColdStorageGapError {
+    StoreDoesNotExist,
+    NotACentralServer,
+    DatabaseError(RepositoryError),
+}
+
+/// Storage requirement of one vaccine course for each person of the population served
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CourseRequirement {
+    pub band: ColdStorageBand,
+    /// Doses needed in the year, including wastage, per person of the population served
+    pub annual_doses_per_capita: f64,
+    pub volume_per_dose_cm3: f64,
+}
+
+/// Compares required cold storage of a single store against its functioning cold chain equipment
+pub fn get_store_cold_storage_gap(
+    ctx: &ServiceContext,
+    store_id: &str,
+    year: Option<i32>,
+) -> Result<ColdStorageGapReport, ColdStorageGapError> {
+    let store = StoreRepository::new(&ctx.connection)
+        .query_one(StoreFilter::new().id(EqualFilter::equal_to(store_id)))?
+        .ok_or(ColdStorageGapError::StoreDoesNotExist)?;
+
+    generate_report(&ctx.connection, vec![store], year)
+}
+
+/// Gap analysis of every store (optionally filtered), only available on the central server
+pub fn get_cold_storage_gap_report(
+    ctx: &ServiceContext,
+    store_filter: Option<StoreFilter>,
+    year: Option<i32>,
+) -> Result<ColdStorageGapReport, ColdStorageGapError> {
+    if !CentralServerConfig::is_central_server() {
+        return Err(ColdStorageGapError::NotACentralServer);
+    }
+
+    let stores =
+        StoreRepository::new(&ctx.connection).query_by_filter(store_filter.unwrap_or_default())?;
+
+    generate_report(&ctx.connection, stores, year)
+}
+
+fn generate_report(
+    connection: &StorageConnection,
+    stores: Vec<Store>,
+    year: Option<i32>,
+) -> Result<ColdStorageGapReport, ColdStorageGapError> {
+    let year = year.unwrap_or_else(|| Utc::now().year());
+    let (requirements, vaccine_courses_without_volume) = course_requirements(connection, year)?;
+
+    let mut total: HashMap<ColdStorageBand, ColdStorageGapLine> = HashMap::new();
+    let mut store_gaps = Vec::new();
+
+    for store in stores {
+        let store_gap = store_gap(connection, store, &requirements)?;
+        for line in store_gap.lines.iter() {
+            let total_line = total.entry(line.band).or_insert(ColdStorageGapLine {
+                band: line.band,
+                required_litres: 0.0,
+                available_litres: 0.0,
+            });
+            total_line.required_litres += line.required_litres;
+            total_line.available_litres += line.available_litres;
+        }
+        store_gaps.push(store_gap);
+    }
+
+    let mut total: Vec<ColdStorageGapLine> = total.into_values().collect();
+    total.sort_by_key(|line| line.band);
+
+    Ok(ColdStorageGapReport {
+        year,
+        stores: store_gaps,
+        total,
+        vaccine_courses_without_volume,
+    })
+}
+
+fn store_gap(
+    connection: &StorageConnection,
+    store: Store,
+    requirements: &[CourseRequirement],
+) -> Result<StoreColdStorageGap, RepositoryError> {
+    let name_properties = parse_properties(&store.name_row.properties);
+    let population_served = number_property(&name_properties, POPULATION_SERVED_KEY);
+    let stock_months = number_property(&name_properties, SUPPLY_INTERVAL_KEY)
+        .unwrap_or(DEFAULT_SUPPLY_INTERVAL_MONTHS)
+        + number_property(&name_properties, BUFFER_STOCK_KEY).unwrap_or(0.0);
+
+    let required = required_litres(requirements, population_served.unwrap_or(0.0), stock_months);
+    let available = available_litres(connection, &store.store_row.id)?;
+
+    let lines = ColdStorageBand::all()
+        .into_iter()
+        .map(|band| ColdStorageGapLine {
+            band,
+            required_litres: required.get(&band).copied().unwrap_or(0.0),
+            available_litres: available.get(&band).copied().unwrap_or(0.0),
+        })
+        .collect();
+
+    Ok(StoreColdStorageGap {
+        store_id: store.store_row.id,
+        store_name: store.name_row.name,
+        population_served,
+        lines,
+    })
+}
+
+/// Litres needed per band to hold `stock_months` of vaccine for the population served
+pub(crate) fn required_litres(
+    requirements: &[CourseRequirement],
+    population_served: f64,
+    stock_months: f64,
+) -> HashMap<ColdStorageBand, f64> {
+    let mut result = HashMap::new();
+    for requirement in requirements {
+        let doses = population_served * requirement.annual_doses_per_capita / 12.0 * stock_months;
+        // Packaging volumes are in cm3
+        *result.entry(requirement.band).or_insert(0.0) +=
+            doses * requirement.volume_per_dose_cm3 / 1000.0;
+    }
+    result
+}
+
+/// Capacity of the store's functioning assets, an asset's own properties take precedence
+/// over those of its catalogue item
+fn available_litres(
+    connection: &StorageConnection,
+    store_id: &str,
+) -> Result<HashMap<ColdStorageBand, f64>, RepositoryError> {
+    let assets = AssetRepository::new(connection).query_by_filter(
+        AssetFilter::new()
+            .store_id(EqualFilter::equal_to(store_id))
+            .functional_status(AssetLogStatus::equal_any(vec![
+                AssetLogStatus::Functioning,
+                AssetLogStatus::FunctioningButNeedsAttention,
+            ])),
+    )?;
+    let catalogue_item_repository = AssetCatalogueItemRowRepository::new(connection);
+
+    let mut result = HashMap::new();
+    for asset in assets {
+        let asset_properties = parse_properties(&asset.properties);
+        let catalogue_properties = match &asset.catalogue_item_id {
+            Some(catalogue_item_id) => parse_properties(
+                &catalogue_item_repository
+                    .find_one_by_id(catalogue_item_id)?
+                    .and_then(|catalogue_item| catalogue_item.properties),
+            ),
+            None => serde_json::Map::new(),
+        };
+
+        for band in ColdStorageBand::all() {
+            let key = band.capacity_property_key();
+            let capacity = number_property(&asset_properties, key)
+                .or_else(|| number_property(&catalogue_properties, key))
+                .unwrap_or(0.0);
+            *result.entry(band).or_insert(0.0) += capacity;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Requirements of active vaccine courses, and the ids of courses without packaging volumes
+fn course_requirements(
+    connection: &StorageConnection,
+    year: i32,
+) -> Result<(Vec<CourseRequirement>, Vec<String>), RepositoryError> {
+    let vaccine_courses = VaccineCourseRepository::new(connection)
+        .query_by_filter(VaccineCourseFilter::new())?
+        .into_iter()
+        .filter(|course| course.is_active && course.deleted_datetime.is_none());
+
+    let mut requirements = Vec::new();
+    let mut without_volume = Vec::new();
+
+    for course in vaccine_courses {
+        let Some((band, volume_per_dose_cm3)) = course_volume(connection, &course.id)? else {
+            without_volume.push(course.id);
+            continue;
+        };
+
+        let number_of_doses = VaccineCourseDoseRepository::new(connection).count(Some(
+            VaccineCourseDoseFilter::new().vaccine_course_id(EqualFilter::equal_to(&course.id)),
+        ))?;
+
+        let target_population_fraction = match &course.demographic_id {
+            Some(demographic_id) => {
+                let indicators = DemographicIndicatorRepository::new(connection).query_by_filter(
+                    DemographicIndicatorFilter::new()
+                        .demographic_id(EqualFilter::equal_to(demographic_id)),
+                )?;
+                target_population_fraction(&indicators, year)
+            }
+            // Course applies to the whole population
+            None => 1.0,
+        };
+
+        requirements.push(CourseRequirement {
+            annual_doses_per_capita: annual_doses_per_capita(
+                &course,
+                number_of_doses as f64,
+                target_population_fraction,
+            ),
+            band,
+            volume_per_dose_cm3,
+        });
+    }
+
+    Ok((requirements, without_volume))
+}
+
+pub(crate) fn annual_doses_per_capita(
+    course: &VaccineCourseRow,
+    number_of_doses: f64,
+    target_population_fraction: f64,
+) -> f64 {
+    let wastage_factor = if course.wastage_rate > 0.0 && course.wastage_rate < 100.0 {
+        100.0 / (100.0 - course.wastage_rate)
+    } else {
+        1.0
+    };
+
+    target_population_fraction * number_of_doses * course.coverage_rate / 100.0 * wastage_factor
+}
+
+/// Share of the population in the demographic for the year, grown by the indicator's projection.
+/// Uses the latest indicator with a base year on or before `year`, or the earliest one.
+pub(crate) fn target_population_fraction(indicators: &[DemographicIndicatorRow], year: i32) -> f64 {
+    let indicator = indicators
+        .iter()
+        .filter(|indicator| indicator.base_year <= year)
+        .max_by_key(|indicator| indicator.base_year)
+        .or_else(|| {
+            indicators
+                .iter()
+                .min_by_key(|indicator| indicator.base_year)
+        });
+
+    let Some(indicator) = indicator else {
+        return 1.0;
+    };
+
+    let projected_population = match (year - indicator.base_year).clamp(0, 5) {
+        0 => indicator.base_population,
+        1 => indicator.year_1_projection,
+        2 => indicator.year_2_projection,
+        3 => indicator.year_3_projection,
+        4 => indicator.year_4_projection,
+        _ => indicator.year_5_projection,
+    };
+    let growth = if indicator.base_population > 0 && projected_population > 0 {
+        projected_population as f64 / indicator.base_population as f64
+    } else {
+        1.0
+    };
+
+    indicator.population_percentage / 100.0 * growth
+}
+
+/// Largest primary packaging volume of the course's items, with the cold storage band of that
+/// item variant (defaults to +5 °C when the variant has no cold storage type)
+fn course_volume(
+    connection: &StorageConnection,
+    vaccine_course_id: &str,
+) -> Result<Option<(ColdStorageBand, f64)>, RepositoryError> {
+    let item_ids: Vec<String> = VaccineCourseItemRepository::new(connection)
+        .query_by_filter(
+            VaccineCourseItemFilter::new()
+                .vaccine_course_id(EqualFilter::equal_to(vaccine_course_id)),
+        )?
+        .into_iter()
+        .filter(|course_item| course_item.vaccine_course_item.deleted_datetime.is_none())
+        .map(|course_item| course_item.item.id)
+        .collect();
+
+    if item_ids.is_empty() {
+        return Ok(None);
+    }
+
+    let item_variants = ItemVariantRepository::new(connection)
+        .query_by_filter(ItemVariantFilter::new().item_id(EqualFilter::equal_any(item_ids)))?;
+    let packaging_variant_repository = PackagingVariantRepository::new(connection);
+    let cold_storage_type_repository = ColdStorageTypeRowRepository::new(connection);
+
+    let mut result: Option<(ColdStorageBand, f64)> = None;
+    for item_variant in item_variants {
+        let item_variant = item_variant.item_variant_row;
+        if item_variant.deleted_datetime.is_some() {
+            continue;
+        }
+
+        let volume = packaging_variant_repository
+            .query_by_filter(
+                PackagingVariantFilter::new()
+                    .item_variant_id(EqualFilter::equal_to(&item_variant.id)),
+            )?
+            .into_iter()
+            .filter(|packaging| {
+                packaging.deleted_datetime.is_none()
+                    && packaging.packaging_level == PRIMARY_PACKAGING_LEVEL
+            })
+            .filter_map(|packaging| packaging.volume_per_unit)
+            .fold(None, |max: Option<f64>, volume| {
+                Some(max.map_or(volume, |max| max.max(volume)))
+            });
+
+        let Some(volume) = volume else {
+            continue;
+        };
+        if matches!(result, Some((_, max_volume)) if max_volume >= volume) {
+            continue;
+        }
+
+        let band = match &item_variant.cold_storage_type_id {
+            Some(cold_storage_type_id) => cold_storage_type_repository
+                .find_one_by_id(cold_storage_type_id)?
+                .map(|cold_storage_type| {
+                    ColdStorageBand::from_cold_storage_type(&cold_storage_type)
+                })
+                .unwrap_or(ColdStorageBand::Plus5),
+            None => ColdStorageBand::Plus5,
+        };
+        result = Some((band, volume));
+    }
+
+    Ok(result)
+}
+
+fn parse_properties(properties: &Option<String>) -> serde_json::Map<String, Value> {
+    properties
+        .as_ref()
+        .and_then(|properties| serde_json::from_str::<Value>(properties).ok())
+        .and_then(|value| match value {
+            Value::Object(map) => Some(map),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Property values can be stored either as numbers or as strings
+fn number_property(properties: &serde_json::Map<String, Value>, key: &str) -> Option<f64> {
+    match properties.get(key)? {
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) => string.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+impl From<RepositoryError> for ColdStorageGapError {
+    fn from(error: RepositoryError) -> Self {
+        ColdStorageGapError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use util::inline_init;
+
+    use super::*;
+
+    #[test]
+    fn cold_storage_gap_calculation() {
+        let course = inline_init(|r: &mut VaccineCourseRow| {
+            r.coverage_rate = 90.0;
+            r.wastage_rate = 10.0;
+        });
+        let indicator = inline_init(|r: &mut DemographicIndicatorRow| {
+            r.base_year = 2024;
+            r.base_population = 1000;
+            r.population_percentage = 4.0;
+            r.year_1_projection = 1100;
+        });
+
+        // 4% of the population, grown 10% in the first projected year
+        assert_eq!(target_population_fraction(&[indicator.clone()], 2024), 0.04);
+        assert!((target_population_fraction(&[indicator.clone()], 2025) - 0.044).abs() < 1e-9);
+        // No indicator means the whole population is targeted
+        assert_eq!(target_population_fraction(&[], 2025), 1.0);
+
+        // 3 doses, 90% coverage, wastage factor of 100 / 90
+        let per_capita = annual_doses_per_capita(&course, 3.0, 0.04);
+        assert!((per_capita - 0.12).abs() < 1e-9);
+
+        let requirements = vec![
+            CourseRequirement {
+                band: ColdStorageBand::Plus5,
+                annual_doses_per_capita: per_capita,
+                volume_per_dose_cm3: 2.5,
+            },
+            CourseRequirement {
+                band: ColdStorageBand::Minus20,
+                annual_doses_per_capita: 0.05,
+                volume_per_dose_cm3: 10.0,
+            },
+        ];
+
+        // 120,000 people with 3 months of stock
+        let required = required_litres(&requirements, 120_000.0, 3.0);
+        // 120,000 * 0.12 / 12 * 3 = 3,600 doses of 2.5 cm3
+        assert!((required[&ColdStorageBand::Plus5] - 9.0).abs() < 1e-9);
+        // 120,000 * 0.05 / 12 * 3 = 1,500 doses of 10 cm3
+        assert!((required[&ColdStorageBand::Minus20] - 15.0).abs() < 1e-9);
+        assert_eq!(required.get(&ColdStorageBand::Minus70), None);
+    }
+}
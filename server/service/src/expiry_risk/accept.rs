@@ -0,0 +1,412 @@
+use chrono::Utc;
+use repository::{
+    Invoice, KeyType, KeyValueStoreRepository, NameLinkRowRepository, RedistributionRequestRow,
+    RedistributionRequestRowRepository, RepositoryError, StorageConnection, StoreRow,
+    StoreRowRepository,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    invoice::{
+        outbound_shipment::insert::{
+            insert_outbound_shipment, InsertOutboundShipment, InsertOutboundShipmentError,
+        },
+        query::get_invoice,
+    },
+    invoice_line::stock_out_line::{
+        insert_stock_out_line, InsertStockOutLine, InsertStockOutLineError, StockOutType,
+    },
+    service_provider::ServiceContext,
+};
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct RedistributionLine {
+    pub id: String,
+    pub stock_line_id: String,
+    pub number_of_packs: f64,
+}
+
+/// Accepted redistribution suggestions from the current store to another store
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct AcceptRedistribution {
+    pub outbound_shipment_id: String,
+    pub to_store_id: String,
+    pub lines: Vec<RedistributionLine>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AcceptedRedistribution {
+    /// Draft outbound shipment created in the current store
+    Shipment(Invoice),
+    /// The current store is active on another site, the draft outbound shipment is created there
+    /// once the request has synced
+    Request(RedistributionRequestRow),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AcceptRedistributionError {
+    NoLinesSelected,
+    ToStoreDoesNotExist,
+    CannotRedistributeToSameStore,
+    ShipmentError(InsertOutboundShipmentError),
+    LineError {
+        line_id: String,
+        error: InsertStockOutLineError,
+    },
+    NewlyCreatedInvoiceDoesNotExist,
+    /// Lines of a synced redistribution request couldn't be read
+    InvalidRequestLines(String),
+    DatabaseError(RepositoryError),
+}
+
+/// Creates a draft (new) outbound shipment to the receiving store for the accepted
+/// redistribution suggestions. Stock is reserved until the shipment is finalised or deleted.
+///
+/// Stock of a store active on another site (e.g. when accepting suggestions on central) can only
+/// be reserved by that site, a redistribution request is created instead and synced to it.
+pub fn accept_redistribution(
+    ctx: &ServiceContext,
+    input: AcceptRedistribution,
+) -> Result<AcceptedRedistribution, AcceptRedistributionError> {
+    use AcceptRedistributionError::*;
+
+    let accepted = ctx
+        .connection
+        .transaction_sync(|connection| {
+            if input.lines.is_empty() {
+                return Err(NoLinesSelected);
+            }
+            if input.to_store_id == ctx.store_id {
+                return Err(CannotRedistributeToSameStore);
+            }
+            let store_repo = StoreRowRepository::new(connection);
+            let to_store = store_repo
+                .find_one_by_id(&input.to_store_id)?
+                .ok_or(ToStoreDoesNotExist)?;
+            let from_store = store_repo
+                .find_one_by_id(&ctx.store_id)?
+                .ok_or(RepositoryError::NotFound)?;
+
+            if is_store_on_site(connection, &from_store)? {
+                return create_shipment(ctx, &input.outbound_shipment_id, &to_store, input.lines)
+                    .map(AcceptedRedistribution::Shipment);
+            }
+
+            let request = RedistributionRequestRow {
+                id: input.outbound_shipment_id.clone(),
+                store_id: from_store.id,
+                to_store_id: to_store.id,
+                outbound_shipment_id: input.outbound_shipment_id,
+                lines: serde_json::to_string(&input.lines).unwrap_or_default(),
+                user_id: Some(ctx.user_id.clone()),
+                created_datetime: Utc::now().naive_utc(),
+                processed_datetime: None,
+            };
+            RedistributionRequestRowRepository::new(connection).upsert_one(&request)?;
+
+            Ok(AcceptedRedistribution::Request(request))
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(accepted)
+}
+
+/// Creates the draft outbound shipment of a synced redistribution request for the current store,
+/// returns None if the request doesn't exist, is for another store or was already processed
+pub(crate) fn create_requested_shipment(
+    ctx: &ServiceContext,
+    request_id: &str,
+) -> Result<Option<Invoice>, AcceptRedistributionError> {
+    use AcceptRedistributionError::*;
+
+    let invoice = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let repo = RedistributionRequestRowRepository::new(connection);
+            let Some(request) = repo.find_one_by_id(request_id)? else {
+                return Ok(None);
+            };
+            if request.store_id != ctx.store_id || request.processed_datetime.is_some() {
+                return Ok(None);
+            }
+            let to_store = StoreRowRepository::new(connection)
+                .find_one_by_id(&request.to_store_id)?
+                .ok_or(ToStoreDoesNotExist)?;
+            let lines: Vec<RedistributionLine> = serde_json::from_str(&request.lines)
+                .map_err(|error| InvalidRequestLines(error.to_string()))?;
+
+            let invoice = create_shipment(ctx, &request.outbound_shipment_id, &to_store, lines)?;
+
+            repo.upsert_one(&RedistributionRequestRow {
+                processed_datetime: Some(Utc::now().naive_utc()),
+                ..request
+            })?;
+
+            Ok(Some(invoice))
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(invoice)
+}
+
+/// Stores are on this site when the site id isn't set yet, i.e. the server doesn't sync
+fn is_store_on_site(
+    connection: &StorageConnection,
+    store: &StoreRow,
+) -> Result<bool, RepositoryError> {
+    let site_id = KeyValueStoreRepository::new(connection).get_i32(KeyType::SettingsSyncSiteId)?;
+
+    Ok(match site_id {
+        Some(site_id) => site_id == store.site_id,
+        None => true,
+    })
+}
+
+fn create_shipment(
+    ctx: &ServiceContext,
+    outbound_shipment_id: &str,
+    to_store: &StoreRow,
+    lines: Vec<RedistributionLine>,
+) -> Result<Invoice, AcceptRedistributionError> {
+    use AcceptRedistributionError::*;
+
+    if lines.is_empty() {
+        return Err(NoLinesSelected);
+    }
+    let other_party_id = NameLinkRowRepository::new(&ctx.connection)
+        .find_one_by_id(&to_store.name_link_id)?
+        .ok_or(ToStoreDoesNotExist)?
+        .name_id;
+
+    insert_outbound_shipment(
+        ctx,
+        InsertOutboundShipment {
+            id: outbound_shipment_id.to_string(),
+            other_party_id,
+            comment: Some("Redistribution of stock at risk of expiry".to_string()),
+            ..Default::default()
+        },
+    )
+    .map_err(ShipmentError)?;
+
+    for line in lines {
+        insert_stock_out_line(
+            ctx,
+            InsertStockOutLine {
+                id: line.id.clone(),
+                r#type: StockOutType::OutboundShipment,
+                invoice_id: outbound_shipment_id.to_string(),
+                stock_line_id: line.stock_line_id,
+                number_of_packs: line.number_of_packs,
+                ..Default::default()
+            },
+        )
+        .map_err(|error| LineError {
+            line_id: line.id,
+            error,
+        })?;
+    }
+
+    get_invoice(ctx, None, outbound_shipment_id)?.ok_or(NewlyCreatedInvoiceDoesNotExist)
+}
+
+impl From<RepositoryError> for AcceptRedistributionError {
+    fn from(error: RepositoryError) -> Self {
+        AcceptRedistributionError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_stock_line_a, mock_store_a, mock_store_b, MockDataInserts},
+        test_db::setup_all,
+        InvoiceLineRowRepository, InvoiceRowRepository, InvoiceStatus, KeyType,
+        KeyValueStoreRepository, RedistributionRequestRow, RedistributionRequestRowRepository,
+        StockLineRowRepository,
+    };
+
+    use crate::{
+        expiry_risk::accept::{
+            create_requested_shipment, AcceptRedistribution, AcceptRedistributionError,
+            AcceptedRedistribution, RedistributionLine,
+        },
+        processors::redistribution_request::process_redistribution_requests,
+        service_provider::ServiceProvider,
+    };
+
+    #[actix_rt::test]
+    async fn accept_redistribution() {
+        let (_, connection, connection_manager, _) =
+            setup_all("accept_redistribution", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager);
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = &service_provider.expiry_risk_service;
+
+        assert_eq!(
+            service.accept_redistribution(
+                &context,
+                AcceptRedistribution {
+                    outbound_shipment_id: "redistribution".to_string(),
+                    to_store_id: mock_store_b().id,
+                    lines: vec![],
+                },
+            ),
+            Err(AcceptRedistributionError::NoLinesSelected)
+        );
+        assert_eq!(
+            service.accept_redistribution(
+                &context,
+                AcceptRedistribution {
+                    outbound_shipment_id: "redistribution".to_string(),
+                    to_store_id: "invalid".to_string(),
+                    lines: vec![RedistributionLine::default()],
+                },
+            ),
+            Err(AcceptRedistributionError::ToStoreDoesNotExist)
+        );
+
+        let available_before = mock_stock_line_a().available_number_of_packs;
+        let Ok(AcceptedRedistribution::Shipment(invoice)) = service.accept_redistribution(
+            &context,
+            AcceptRedistribution {
+                outbound_shipment_id: "redistribution".to_string(),
+                to_store_id: mock_store_b().id,
+                lines: vec![RedistributionLine {
+                    id: "redistribution_line".to_string(),
+                    stock_line_id: mock_stock_line_a().id,
+                    number_of_packs: 2.0,
+                }],
+            },
+        ) else {
+            panic!("Store is on this site, shipment should be created");
+        };
+        assert_eq!(invoice.invoice_row.status, InvoiceStatus::New);
+        assert_eq!(invoice.invoice_row.name_link_id, "name_store_b");
+
+        let line = InvoiceLineRowRepository::new(&connection)
+            .find_one_by_id("redistribution_line")
+            .unwrap()
+            .unwrap();
+        assert_eq!(line.number_of_packs, 2.0);
+        let stock_line = StockLineRowRepository::new(&connection)
+            .find_one_by_id(&mock_stock_line_a().id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stock_line.available_number_of_packs, available_before - 2.0);
+    }
+
+    #[actix_rt::test]
+    async fn accept_redistribution_for_store_on_another_site() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "accept_redistribution_for_store_on_another_site",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager);
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = &service_provider.expiry_risk_service;
+        let site_id_repo = KeyValueStoreRepository::new(&connection);
+
+        // Accepted on central (store b's site), store a's stock can't be reserved here
+        site_id_repo
+            .set_i32(KeyType::SettingsSyncSiteId, Some(mock_store_b().site_id))
+            .unwrap();
+        let Ok(AcceptedRedistribution::Request(request)) = service.accept_redistribution(
+            &context,
+            AcceptRedistribution {
+                outbound_shipment_id: "redistribution".to_string(),
+                to_store_id: mock_store_b().id,
+                lines: vec![RedistributionLine {
+                    id: "redistribution_line".to_string(),
+                    stock_line_id: mock_stock_line_a().id,
+                    number_of_packs: 2.0,
+                }],
+            },
+        ) else {
+            panic!("Store is on another site, request should be created");
+        };
+        assert_eq!(request.store_id, mock_store_a().id);
+        assert_eq!(request.processed_datetime, None);
+        assert_eq!(
+            InvoiceRowRepository::new(&connection).find_one_by_id("redistribution"),
+            Ok(None)
+        );
+        let available_before = mock_stock_line_a().available_number_of_packs;
+        let stock_line = StockLineRowRepository::new(&connection)
+            .find_one_by_id(&mock_stock_line_a().id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stock_line.available_number_of_packs, available_before);
+
+        // Not processed for stores on other sites
+        process_redistribution_requests(&service_provider).unwrap();
+        assert_eq!(
+            InvoiceRowRepository::new(&connection).find_one_by_id("redistribution"),
+            Ok(None)
+        );
+
+        // Request synced to store a's site, the shipment is created there
+        site_id_repo
+            .set_i32(KeyType::SettingsSyncSiteId, Some(mock_store_a().site_id))
+            .unwrap();
+        process_redistribution_requests(&service_provider).unwrap();
+
+        let invoice = InvoiceRowRepository::new(&connection)
+            .find_one_by_id("redistribution")
+            .unwrap()
+            .unwrap();
+        assert_eq!(invoice.store_id, mock_store_a().id);
+        assert_eq!(invoice.status, InvoiceStatus::New);
+        assert_eq!(invoice.name_link_id, "name_store_b");
+        let stock_line = StockLineRowRepository::new(&connection)
+            .find_one_by_id(&mock_stock_line_a().id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stock_line.available_number_of_packs, available_before - 2.0);
+
+        let request = RedistributionRequestRowRepository::new(&connection)
+            .find_one_by_id(&request.id)
+            .unwrap()
+            .unwrap();
+        assert!(request.processed_datetime.is_some());
+    }
+
+    #[actix_rt::test]
+    async fn create_requested_shipment_with_invalid_lines() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "create_requested_shipment_with_invalid_lines",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager);
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let repo = RedistributionRequestRowRepository::new(&connection);
+        repo.upsert_one(&RedistributionRequestRow {
+            id: "invalid_request".to_string(),
+            store_id: mock_store_a().id,
+            to_store_id: mock_store_b().id,
+            outbound_shipment_id: "invalid_request".to_string(),
+            lines: "not json".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert!(matches!(
+            create_requested_shipment(&context, "invalid_request"),
+            Err(AcceptRedistributionError::InvalidRequestLines(_))
+        ));
+        // Left unprocessed
+        let request = repo.find_one_by_id("invalid_request").unwrap().unwrap();
+        assert_eq!(request.processed_datetime, None);
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use repository::{
+    EqualFilter, RepositoryError, StockLineFilter, StockLineRepository, StockLineRow,
+};
+use util::constants::NUMBER_OF_DAYS_IN_A_MONTH;
+
+use crate::{
+    item_stats::{get_item_stats, ItemStats},
+    service_provider::ServiceContext,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct StockLineExpiryRisk {
+    pub stock_line: StockLineRow,
+    /// Units of the stock line expected to expire before they are consumed
+    pub quantity_at_risk: f64,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ItemExpiryRisk {
+    pub store_id: String,
+    pub item_id: String,
+    pub item_name: String,
+    pub average_monthly_consumption: f64,
+    pub available_stock_on_hand: f64,
+    /// Units expected to expire before they are consumed
+    pub quantity_at_risk: f64,
+    /// Available stock lines in the order they are expected to be consumed
+    pub stock_lines: Vec<StockLineExpiryRisk>,
+}
+
+/// Items of the store with stock that is expected to expire before it is consumed, largest
+/// quantity at risk first.
+///
+/// Consumption is forecast from the average monthly consumption of the item, stock is assumed
+/// to be used first expiry first out.
+pub fn get_expiry_risk(
+    ctx: &ServiceContext,
+    store_id: &str,
+    reference_date: NaiveDate,
+) -> Result<Vec<ItemExpiryRisk>, RepositoryError> {
+    let mut result: Vec<ItemExpiryRisk> = forecast_store(ctx, store_id, reference_date)?
+        .into_iter()
+        .filter(|item| item.quantity_at_risk > 0.0)
+        .collect();
+
+    result.sort_by(|a, b| {
+        b.quantity_at_risk
+            .total_cmp(&a.quantity_at_risk)
+            .then_with(|| a.item_name.cmp(&b.item_name))
+    });
+
+    Ok(result)
+}
+
+/// Expiry forecast of all items that are in stock or consumed in the store
+pub(crate) fn forecast_store(
+    ctx: &ServiceContext,
+    store_id: &str,
+    reference_date: NaiveDate,
+) -> Result<Vec<ItemExpiryRisk>, RepositoryError> {
+    let mut stock_lines_by_item: HashMap<String, Vec<StockLineRow>> = HashMap::new();
+    for stock_line in StockLineRepository::new(&ctx.connection).query_by_filter(
+        StockLineFilter::new()
+            .store_id(EqualFilter::equal_to(store_id))
+            .is_available(true),
+        Some(store_id.to_string()),
+    )? {
+        stock_lines_by_item
+            .entry(stock_line.item_row.id)
+            .or_default()
+            .push(stock_line.stock_line_row);
+    }
+
+    let result = get_item_stats(ctx, store_id, None, None)?
+        .into_iter()
+        .filter(|item_stats| {
+            item_stats.available_stock_on_hand > 0.0 || item_stats.average_monthly_consumption > 0.0
+        })
+        .map(|item_stats| {
+            let stock_lines = stock_lines_by_item
+                .remove(&item_stats.item_id)
+                .unwrap_or_default();
+            forecast_item_expiry(store_id, &item_stats, stock_lines, reference_date)
+        })
+        .collect();
+
+    Ok(result)
+}
+
+pub(crate) fn forecast_item_expiry(
+    store_id: &str,
+    item_stats: &ItemStats,
+    mut stock_lines: Vec<StockLineRow>,
+    reference_date: NaiveDate,
+) -> ItemExpiryRisk {
+    let daily_consumption = item_stats.average_monthly_consumption / NUMBER_OF_DAYS_IN_A_MONTH;
+    // Stock without expiry is used last
+    stock_lines
+        .sort_by_key(|stock_line| (stock_line.expiry_date.is_none(), stock_line.expiry_date));
+
+    let mut consumed = 0.0;
+    let mut quantity_at_risk = 0.0;
+    let stock_lines = stock_lines
+        .into_iter()
+        .map(|stock_line| {
+            let quantity = stock_line.available_number_of_packs * stock_line.pack_size;
+            let consumable = match stock_line.expiry_date {
+                Some(expiry_date) => {
+                    let days_until_expiry = (expiry_date - reference_date).num_days().max(0);
+                    (days_until_expiry as f64 * daily_consumption - consumed)
+                        .max(0.0)
+                        .min(quantity)
+                }
+                None => quantity,
+            };
+            consumed += consumable;
+            quantity_at_risk += quantity - consumable;
+
+            StockLineExpiryRisk {
+                stock_line,
+                quantity_at_risk: quantity - consumable,
+            }
+        })
+        .collect();
+
+    ItemExpiryRisk {
+        store_id: store_id.to_string(),
+        item_id: item_stats.item_id.clone(),
+        item_name: item_stats.item_name.clone(),
+        average_monthly_consumption: item_stats.average_monthly_consumption,
+        available_stock_on_hand: item_stats.available_stock_on_hand,
+        quantity_at_risk,
+        stock_lines,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::StockLineRow;
+
+    use crate::item_stats::ItemStats;
+
+    use super::forecast_item_expiry;
+
+    #[test]
+    fn forecast_item_expiry_first_expiry_first_out() {
+        let reference_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let stock_line = |id: &str, packs: f64, expiry_date: Option<NaiveDate>| StockLineRow {
+            id: id.to_string(),
+            pack_size: 10.0,
+            available_number_of_packs: packs,
+            total_number_of_packs: packs,
+            expiry_date,
+            ..Default::default()
+        };
+        // 10 units per day
+        let item_stats = ItemStats {
+            total_consumption: 900.0,
+            average_monthly_consumption: 300.0,
+            available_stock_on_hand: 1200.0,
+            total_stock_on_hand: 1200.0,
+            item_id: "item".to_string(),
+            item_name: "item".to_string(),
+        };
+
+        let result = forecast_item_expiry(
+            "store",
+            &item_stats,
+            vec![
+                stock_line("no_expiry", 10.0, None),
+                // 40 days of consumption, 200 units can be used after the first line
+                stock_line("second", 50.0, NaiveDate::from_ymd_opt(2024, 2, 10)),
+                // 20 days of consumption, all 200 units can be used
+                stock_line("first", 20.0, NaiveDate::from_ymd_opt(2024, 1, 21)),
+                stock_line("expired", 10.0, NaiveDate::from_ymd_opt(2023, 12, 1)),
+            ],
+            reference_date,
+        );
+
+        let at_risk: Vec<(&str, f64)> = result
+            .stock_lines
+            .iter()
+            .map(|line| (line.stock_line.id.as_str(), line.quantity_at_risk))
+            .collect();
+        assert_eq!(
+            at_risk,
+            vec![
+                ("expired", 100.0),
+                ("first", 0.0),
+                ("second", 300.0),
+                ("no_expiry", 0.0)
+            ]
+        );
+        assert_eq!(result.quantity_at_risk, 400.0);
+    }
+}
@@ -0,0 +1,49 @@
+use chrono::NaiveDate;
+use repository::RepositoryError;
+
+use self::{
+    accept::{
+        accept_redistribution, AcceptRedistribution, AcceptRedistributionError,
+        AcceptedRedistribution,
+    },
+    forecast::{get_expiry_risk, ItemExpiryRisk},
+    redistribution::{
+        get_redistribution_suggestions, GetRedistributionSuggestionsError, RedistributionSuggestion,
+    },
+};
+use crate::service_provider::ServiceContext;
+
+pub mod accept;
+pub mod forecast;
+pub mod redistribution;
+
+pub trait ExpiryRiskServiceTrait: Sync + Send {
+    fn get_expiry_risk(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        reference_date: NaiveDate,
+    ) -> Result<Vec<ItemExpiryRisk>, RepositoryError> {
+        get_expiry_risk(ctx, store_id, reference_date)
+    }
+
+    fn get_redistribution_suggestions(
+        &self,
+        ctx: &ServiceContext,
+        reference_date: NaiveDate,
+        target_months_of_stock: Option<f64>,
+    ) -> Result<Vec<RedistributionSuggestion>, GetRedistributionSuggestionsError> {
+        get_redistribution_suggestions(ctx, reference_date, target_months_of_stock)
+    }
+
+    fn accept_redistribution(
+        &self,
+        ctx: &ServiceContext,
+        input: AcceptRedistribution,
+    ) -> Result<AcceptedRedistribution, AcceptRedistributionError> {
+        accept_redistribution(ctx, input)
+    }
+}
+
+pub struct ExpiryRiskService {}
+impl ExpiryRiskServiceTrait for ExpiryRiskService {}
@@ -0,0 +1,255 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use repository::{RepositoryError, StoreFilter, StoreRepository};
+use util::constants::NUMBER_OF_DAYS_IN_A_MONTH;
+
+use crate::{service_provider::ServiceContext, sync::CentralServerConfig};
+
+use super::forecast::{forecast_store, ItemExpiryRisk};
+
+/// Months of stock an under-stocked store is brought up to by redistribution
+pub const DEFAULT_TARGET_MONTHS_OF_STOCK: f64 = 3.0;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RedistributionSuggestion {
+    pub item_id: String,
+    pub item_name: String,
+    pub from_store_id: String,
+    pub to_store_id: String,
+    pub stock_line_id: String,
+    pub batch: Option<String>,
+    pub expiry_date: NaiveDate,
+    pub pack_size: f64,
+    pub number_of_packs: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GetRedistributionSuggestionsError {
+    NotACentralServer,
+    DatabaseError(RepositoryError),
+}
+
+/// Suggests transfers of stock that is expected to expire before it is consumed, from
+/// over-stocked stores to stores that can consume it before it expires.
+///
+/// Only available on the central server, which holds the synced stock of all stores.
+pub fn get_redistribution_suggestions(
+    ctx: &ServiceContext,
+    reference_date: NaiveDate,
+    target_months_of_stock: Option<f64>,
+) -> Result<Vec<RedistributionSuggestion>, GetRedistributionSuggestionsError> {
+    if !CentralServerConfig::is_central_server() {
+        return Err(GetRedistributionSuggestionsError::NotACentralServer);
+    }
+
+    let mut forecasts = Vec::new();
+    for store in StoreRepository::new(&ctx.connection).query_by_filter(StoreFilter::new())? {
+        if store.store_row.is_disabled {
+            continue;
+        }
+        forecasts.extend(forecast_store(ctx, &store.store_row.id, reference_date)?);
+    }
+
+    Ok(generate_suggestions(
+        forecasts,
+        reference_date,
+        target_months_of_stock.unwrap_or(DEFAULT_TARGET_MONTHS_OF_STOCK),
+    ))
+}
+
+struct Receiver {
+    store_id: String,
+    daily_consumption: f64,
+    stock_on_hand: f64,
+    /// Units missing to reach the target months of stock
+    need: f64,
+}
+
+pub(crate) fn generate_suggestions(
+    forecasts: Vec<ItemExpiryRisk>,
+    reference_date: NaiveDate,
+    target_months_of_stock: f64,
+) -> Vec<RedistributionSuggestion> {
+    let mut forecasts_by_item: BTreeMap<String, Vec<ItemExpiryRisk>> = BTreeMap::new();
+    for forecast in forecasts {
+        forecasts_by_item
+            .entry(forecast.item_id.clone())
+            .or_default()
+            .push(forecast);
+    }
+
+    let mut suggestions = Vec::new();
+    for (item_id, forecasts) in forecasts_by_item {
+        let mut receivers: Vec<Receiver> = forecasts
+            .iter()
+            .filter(|forecast| forecast.quantity_at_risk == 0.0)
+            .map(|forecast| Receiver {
+                store_id: forecast.store_id.clone(),
+                daily_consumption: forecast.average_monthly_consumption / NUMBER_OF_DAYS_IN_A_MONTH,
+                stock_on_hand: forecast.available_stock_on_hand,
+                need: forecast.average_monthly_consumption * target_months_of_stock
+                    - forecast.available_stock_on_hand,
+            })
+            .filter(|receiver| receiver.need > 0.0)
+            .collect();
+        if receivers.is_empty() {
+            continue;
+        }
+
+        // Stock expiring first is redistributed first
+        let mut batches: Vec<_> = forecasts
+            .iter()
+            .flat_map(|forecast| {
+                forecast
+                    .stock_lines
+                    .iter()
+                    .map(move |line| (forecast, line))
+            })
+            .filter(|(_, line)| line.quantity_at_risk > 0.0)
+            .filter_map(|(forecast, line)| {
+                let expiry_date = line.stock_line.expiry_date?;
+                (expiry_date > reference_date).then_some((forecast, line, expiry_date))
+            })
+            .collect();
+        batches.sort_by(
+            |(a_forecast, a_line, a_expiry), (b_forecast, b_line, b_expiry)| {
+                a_expiry
+                    .cmp(b_expiry)
+                    .then_with(|| a_forecast.store_id.cmp(&b_forecast.store_id))
+                    .then_with(|| a_line.stock_line.id.cmp(&b_line.stock_line.id))
+            },
+        );
+
+        for (forecast, line, expiry_date) in batches {
+            let pack_size = line.stock_line.pack_size;
+            if pack_size <= 0.0 {
+                continue;
+            }
+            let days_until_expiry = (expiry_date - reference_date).num_days() as f64;
+            let mut remaining_packs = (line.quantity_at_risk / pack_size).floor();
+
+            receivers.sort_by(|a, b| {
+                b.need
+                    .total_cmp(&a.need)
+                    .then_with(|| a.store_id.cmp(&b.store_id))
+            });
+            for receiver in receivers.iter_mut() {
+                if remaining_packs <= 0.0 {
+                    break;
+                }
+                // Stock already in the receiving store is assumed to be used first
+                let consumable_before_expiry =
+                    receiver.daily_consumption * days_until_expiry - receiver.stock_on_hand;
+                let capacity = receiver.need.min(consumable_before_expiry);
+                let number_of_packs = remaining_packs.min((capacity / pack_size).floor());
+                if number_of_packs <= 0.0 {
+                    continue;
+                }
+
+                let quantity = number_of_packs * pack_size;
+                receiver.need -= quantity;
+                receiver.stock_on_hand += quantity;
+                remaining_packs -= number_of_packs;
+
+                suggestions.push(RedistributionSuggestion {
+                    item_id: item_id.clone(),
+                    item_name: forecast.item_name.clone(),
+                    from_store_id: forecast.store_id.clone(),
+                    to_store_id: receiver.store_id.clone(),
+                    stock_line_id: line.stock_line.id.clone(),
+                    batch: line.stock_line.batch.clone(),
+                    expiry_date,
+                    pack_size,
+                    number_of_packs,
+                });
+            }
+        }
+    }
+
+    suggestions
+}
+
+impl From<RepositoryError> for GetRedistributionSuggestionsError {
+    fn from(error: RepositoryError) -> Self {
+        GetRedistributionSuggestionsError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::StockLineRow;
+
+    use crate::expiry_risk::forecast::{ItemExpiryRisk, StockLineExpiryRisk};
+
+    use super::generate_suggestions;
+
+    fn forecast(
+        store_id: &str,
+        average_monthly_consumption: f64,
+        available_stock_on_hand: f64,
+        stock_lines: Vec<StockLineExpiryRisk>,
+    ) -> ItemExpiryRisk {
+        ItemExpiryRisk {
+            store_id: store_id.to_string(),
+            item_id: "item".to_string(),
+            item_name: "item".to_string(),
+            average_monthly_consumption,
+            available_stock_on_hand,
+            quantity_at_risk: stock_lines.iter().map(|line| line.quantity_at_risk).sum(),
+            stock_lines,
+        }
+    }
+
+    #[test]
+    fn redistribution_suggestions() {
+        let reference_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        // 60 days until expiry
+        let expiry_date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let at_risk = StockLineExpiryRisk {
+            stock_line: StockLineRow {
+                id: "at_risk".to_string(),
+                pack_size: 10.0,
+                available_number_of_packs: 100.0,
+                expiry_date: Some(expiry_date),
+                ..Default::default()
+            },
+            quantity_at_risk: 800.0,
+        };
+
+        let suggestions = generate_suggestions(
+            vec![
+                forecast("over_stocked", 30.0, 1000.0, vec![at_risk]),
+                // Needs 300 - 20 = 280, can consume 200 - 20 = 180 before expiry
+                forecast("under_stocked", 100.0, 20.0, vec![]),
+                // Needs 900 - 500 = 400, can consume 600 - 500 = 100 before expiry
+                forecast("slow_to_consume", 300.0, 500.0, vec![]),
+                // Has enough stock
+                forecast("well_stocked", 10.0, 100.0, vec![]),
+            ],
+            reference_date,
+            3.0,
+        );
+
+        let result: Vec<(&str, &str, f64)> = suggestions
+            .iter()
+            .map(|suggestion| {
+                (
+                    suggestion.from_store_id.as_str(),
+                    suggestion.to_store_id.as_str(),
+                    suggestion.number_of_packs,
+                )
+            })
+            .collect();
+        assert_eq!(
+            result,
+            vec![
+                ("over_stocked", "slow_to_consume", 10.0),
+                ("over_stocked", "under_stocked", 18.0)
+            ]
+        );
+        assert_eq!(suggestions[0].expiry_date, expiry_date);
+        assert_eq!(suggestions[0].stock_line_id, "at_risk");
+    }
+}
@@ -0,0 +1,71 @@
+use repository::{
+    GoodsReceivedInspectionStatus, GoodsReceivedLineRow, GoodsReceivedLineRowRepository,
+    RepositoryError,
+};
+
+use crate::service_provider::ServiceContext;
+
+use super::receive::set_stock_on_hold;
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct InspectGoodsReceivedLine {
+    pub id: String,
+    pub passed: bool,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InspectGoodsReceivedLineError {
+    GoodsReceivedLineDoesNotExist,
+    NotThisStoreGoodsReceivedLine,
+    NotPendingInspection,
+    UpdatedGoodsReceivedLineDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+/// Records the result of the quality inspection of a quarantined line. Stock that passed
+/// inspection is released from hold, stock that failed stays on hold.
+pub fn inspect_goods_received_line(
+    ctx: &ServiceContext,
+    input: InspectGoodsReceivedLine,
+) -> Result<GoodsReceivedLineRow, InspectGoodsReceivedLineError> {
+    use InspectGoodsReceivedLineError::*;
+
+    ctx.connection
+        .transaction_sync(|connection| {
+            let repo = GoodsReceivedLineRowRepository::new(connection);
+            let line = repo
+                .find_one_by_id(&input.id)?
+                .ok_or(GoodsReceivedLineDoesNotExist)?;
+            if line.store_id != ctx.store_id {
+                return Err(NotThisStoreGoodsReceivedLine);
+            }
+            if line.inspection_status != Some(GoodsReceivedInspectionStatus::Pending) {
+                return Err(NotPendingInspection);
+            }
+
+            let inspection_status = match input.passed {
+                true => GoodsReceivedInspectionStatus::Passed,
+                false => GoodsReceivedInspectionStatus::Failed,
+            };
+            if input.passed {
+                set_stock_on_hold(connection, &line.invoice_line_id, false)?;
+            }
+
+            repo.upsert_one(&GoodsReceivedLineRow {
+                inspection_status: Some(inspection_status),
+                comment: input.comment.clone().or(line.comment),
+                ..line
+            })?;
+
+            repo.find_one_by_id(&input.id)?
+                .ok_or(UpdatedGoodsReceivedLineDoesNotExist)
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+impl From<RepositoryError> for InspectGoodsReceivedLineError {
+    fn from(error: RepositoryError) -> Self {
+        InspectGoodsReceivedLineError::DatabaseError(error)
+    }
+}
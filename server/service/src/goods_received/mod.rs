@@ -0,0 +1,46 @@
+use repository::{GoodsReceivedLineRow, Invoice, RepositoryError};
+
+use self::{
+    inspect::{
+        inspect_goods_received_line, InspectGoodsReceivedLine, InspectGoodsReceivedLineError,
+    },
+    query::get_goods_received_lines,
+    receive::{receive_goods, ReceiveGoods, ReceiveGoodsError},
+};
+use crate::service_provider::ServiceContext;
+
+pub mod inspect;
+pub mod query;
+pub mod receive;
+
+pub trait GoodsReceivedServiceTrait: Sync + Send {
+    fn get_goods_received_lines(
+        &self,
+        ctx: &ServiceContext,
+        invoice_id: &str,
+    ) -> Result<Vec<GoodsReceivedLineRow>, RepositoryError> {
+        get_goods_received_lines(ctx, invoice_id)
+    }
+
+    fn receive_goods(
+        &self,
+        ctx: &ServiceContext,
+        input: ReceiveGoods,
+    ) -> Result<Invoice, ReceiveGoodsError> {
+        receive_goods(ctx, input)
+    }
+
+    fn inspect_goods_received_line(
+        &self,
+        ctx: &ServiceContext,
+        input: InspectGoodsReceivedLine,
+    ) -> Result<GoodsReceivedLineRow, InspectGoodsReceivedLineError> {
+        inspect_goods_received_line(ctx, input)
+    }
+}
+
+pub struct GoodsReceivedService {}
+impl GoodsReceivedServiceTrait for GoodsReceivedService {}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,32 @@
+use repository::{
+    GoodsReceivedLineRow, GoodsReceivedLineRowRepository, InvoiceRowRepository, RepositoryError,
+};
+
+use crate::service_provider::ServiceContext;
+
+/// Goods received lines of an inbound shipment of the store, or of the inbound shipment that was
+/// created from an outbound shipment of the store (for the supplier to review discrepancies)
+pub fn get_goods_received_lines(
+    ctx: &ServiceContext,
+    invoice_id: &str,
+) -> Result<Vec<GoodsReceivedLineRow>, RepositoryError> {
+    let repo = GoodsReceivedLineRowRepository::new(&ctx.connection);
+
+    let lines: Vec<GoodsReceivedLineRow> = repo
+        .find_many_by_invoice_id(invoice_id)?
+        .into_iter()
+        .filter(|line| line.store_id == ctx.store_id)
+        .collect();
+    if !lines.is_empty() {
+        return Ok(lines);
+    }
+
+    let is_store_outbound = InvoiceRowRepository::new(&ctx.connection)
+        .find_one_by_id(invoice_id)?
+        .is_some_and(|invoice| invoice.store_id == ctx.store_id);
+    if !is_store_outbound {
+        return Ok(Vec::new());
+    }
+
+    repo.find_many_by_linked_invoice_id(invoice_id)
+}
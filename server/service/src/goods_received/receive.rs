@@ -0,0 +1,301 @@
+use chrono::Utc;
+use repository::{
+    GoodsReceivedDiscrepancyReason, GoodsReceivedInspectionStatus, GoodsReceivedLineRow,
+    GoodsReceivedLineRowRepository, Invoice, InvoiceLineRow, InvoiceLineRowRepository,
+    InvoiceLineType, InvoiceRow, InvoiceStatus, InvoiceType, RepositoryError,
+    StockLineRowRepository, StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::{
+    invoice::{
+        check_invoice_exists, check_store,
+        inbound_shipment::{
+            update_inbound_shipment, UpdateInboundShipment, UpdateInboundShipmentError,
+            UpdateInboundShipmentStatus,
+        },
+        query::get_invoice,
+    },
+    invoice_line::stock_in_line::{
+        update_stock_in_line, StockInType, UpdateStockInLine, UpdateStockInLineError,
+    },
+    service_provider::ServiceContext,
+};
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ReceiveGoodsLine {
+    pub invoice_line_id: String,
+    pub received_number_of_packs: f64,
+    pub discrepancy_reason: Option<GoodsReceivedDiscrepancyReason>,
+    pub comment: Option<String>,
+    /// Place received stock on hold until it passes quality inspection
+    pub quarantine: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ReceiveGoods {
+    pub invoice_id: String,
+    /// Lines that are not included are received as expected
+    pub lines: Vec<ReceiveGoodsLine>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReceiveGoodsError {
+    InvoiceDoesNotExist,
+    NotThisStoreInvoice,
+    NotAnInboundShipment,
+    InvoiceAlreadyReceived,
+    LineDoesNotExist(String),
+    NumberOfPacksBelowZero(String),
+    /// Received number of packs differs from the expected number of packs
+    DiscrepancyReasonRequired(String),
+    /// Reason does not match the discrepancy, i.e. excess reason when less packs were received
+    InvalidDiscrepancyReason(String),
+    LineUpdateError {
+        line_id: String,
+        error: UpdateStockInLineError,
+    },
+    InvoiceUpdateError(UpdateInboundShipmentError),
+    UpdatedInvoiceDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+/// Receives an inbound shipment (status changes to Delivered). The received number of packs
+/// of each line is compared with the expected number of packs, lines received with a
+/// discrepancy need a reason. Quarantined lines are placed on hold pending quality inspection.
+pub fn receive_goods(
+    ctx: &ServiceContext,
+    input: ReceiveGoods,
+) -> Result<Invoice, ReceiveGoodsError> {
+    let invoice = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let invoice = validate(connection, &ctx.store_id, &input)?;
+            let goods_received_lines = generate(connection, &invoice, &input)?;
+
+            for (goods_received_line, line) in goods_received_lines.iter() {
+                let Some(line) = line else {
+                    continue;
+                };
+                let received_number_of_packs =
+                    goods_received_line.received_number_of_packs.unwrap_or(0.0);
+                if received_number_of_packs == line.number_of_packs {
+                    continue;
+                }
+                update_stock_in_line(
+                    ctx,
+                    UpdateStockInLine {
+                        id: line.id.clone(),
+                        number_of_packs: Some(received_number_of_packs),
+                        r#type: StockInType::InboundShipment,
+                        ..Default::default()
+                    },
+                )
+                .map_err(|error| ReceiveGoodsError::LineUpdateError {
+                    line_id: line.id.clone(),
+                    error,
+                })?;
+            }
+
+            update_inbound_shipment(
+                ctx,
+                UpdateInboundShipment {
+                    id: invoice.id.clone(),
+                    status: Some(UpdateInboundShipmentStatus::Delivered),
+                    ..Default::default()
+                },
+            )
+            .map_err(ReceiveGoodsError::InvoiceUpdateError)?;
+
+            let goods_received_line_repository = GoodsReceivedLineRowRepository::new(connection);
+            for (goods_received_line, _) in goods_received_lines.iter() {
+                goods_received_line_repository.upsert_one(goods_received_line)?;
+
+                if goods_received_line.inspection_status
+                    == Some(GoodsReceivedInspectionStatus::Pending)
+                {
+                    set_stock_on_hold(connection, &goods_received_line.invoice_line_id, true)?;
+                }
+            }
+
+            get_invoice(ctx, None, &invoice.id)
+                .map_err(ReceiveGoodsError::DatabaseError)?
+                .ok_or(ReceiveGoodsError::UpdatedInvoiceDoesNotExist)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    ctx.processors_trigger.trigger_goods_received_processors();
+
+    Ok(invoice)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &ReceiveGoods,
+) -> Result<InvoiceRow, ReceiveGoodsError> {
+    use ReceiveGoodsError::*;
+
+    let invoice =
+        check_invoice_exists(&input.invoice_id, connection)?.ok_or(InvoiceDoesNotExist)?;
+    if !check_store(&invoice, store_id) {
+        return Err(NotThisStoreInvoice);
+    }
+    if invoice.r#type != InvoiceType::InboundShipment {
+        return Err(NotAnInboundShipment);
+    }
+    if matches!(
+        invoice.status,
+        InvoiceStatus::Delivered | InvoiceStatus::Verified
+    ) {
+        return Err(InvoiceAlreadyReceived);
+    }
+
+    let line_repository = InvoiceLineRowRepository::new(connection);
+    for line in input.lines.iter() {
+        let line_belongs_to_invoice = line_repository
+            .find_one_by_id(&line.invoice_line_id)?
+            .is_some_and(|l| l.invoice_id == invoice.id && l.r#type == InvoiceLineType::StockIn);
+        if !line_belongs_to_invoice {
+            return Err(LineDoesNotExist(line.invoice_line_id.clone()));
+        }
+        if line.received_number_of_packs < 0.0 {
+            return Err(NumberOfPacksBelowZero(line.invoice_line_id.clone()));
+        }
+    }
+
+    Ok(invoice)
+}
+
+/// Goods received line for each stock in line of the invoice (and its invoice line), expected
+/// lines that were removed from the invoice are received as short
+fn generate(
+    connection: &StorageConnection,
+    invoice: &InvoiceRow,
+    input: &ReceiveGoods,
+) -> Result<Vec<(GoodsReceivedLineRow, Option<InvoiceLineRow>)>, ReceiveGoodsError> {
+    let received_datetime = Some(Utc::now().naive_utc());
+    let mut expected_lines =
+        GoodsReceivedLineRowRepository::new(connection).find_many_by_invoice_id(&invoice.id)?;
+    let invoice_lines = InvoiceLineRowRepository::new(connection)
+        .find_many_by_invoice_id(&invoice.id)?
+        .into_iter()
+        .filter(|line| line.r#type == InvoiceLineType::StockIn);
+
+    let mut result = Vec::new();
+    for line in invoice_lines {
+        let expected_line = match expected_lines
+            .iter()
+            .position(|expected| expected.invoice_line_id == line.id)
+        {
+            Some(index) => expected_lines.remove(index),
+            // Not a transfer or line was added after the shipment was sent
+            None => GoodsReceivedLineRow {
+                id: uuid(),
+                store_id: invoice.store_id.clone(),
+                invoice_id: invoice.id.clone(),
+                invoice_line_id: line.id.clone(),
+                linked_invoice_id: invoice.linked_invoice_id.clone(),
+                supplier_name_link_id: invoice.name_link_id.clone(),
+                item_link_id: line.item_link_id.clone(),
+                batch: line.batch.clone(),
+                expected_number_of_packs: line.number_of_packs,
+                ..Default::default()
+            },
+        };
+
+        let received_line = match input
+            .lines
+            .iter()
+            .find(|received| received.invoice_line_id == line.id)
+        {
+            Some(received) => {
+                validate_discrepancy_reason(&expected_line, received)?;
+
+                GoodsReceivedLineRow {
+                    received_number_of_packs: Some(received.received_number_of_packs),
+                    discrepancy_reason: received.discrepancy_reason.clone(),
+                    comment: received.comment.clone(),
+                    // Nothing to inspect if no stock was received
+                    inspection_status: (received.quarantine
+                        && received.received_number_of_packs > 0.0)
+                        .then_some(GoodsReceivedInspectionStatus::Pending),
+                    received_datetime,
+                    ..expected_line
+                }
+            }
+            None => GoodsReceivedLineRow {
+                received_number_of_packs: Some(expected_line.expected_number_of_packs),
+                received_datetime,
+                ..expected_line
+            },
+        };
+
+        result.push((received_line, Some(line)));
+    }
+
+    for missing_line in expected_lines {
+        result.push((
+            GoodsReceivedLineRow {
+                received_number_of_packs: Some(0.0),
+                discrepancy_reason: Some(GoodsReceivedDiscrepancyReason::Short),
+                received_datetime,
+                ..missing_line
+            },
+            None,
+        ));
+    }
+
+    Ok(result)
+}
+
+fn validate_discrepancy_reason(
+    expected_line: &GoodsReceivedLineRow,
+    received: &ReceiveGoodsLine,
+) -> Result<(), ReceiveGoodsError> {
+    use GoodsReceivedDiscrepancyReason as Reason;
+    use ReceiveGoodsError::*;
+
+    let expected = expected_line.expected_number_of_packs;
+    let received_number_of_packs = received.received_number_of_packs;
+
+    let reason_is_valid = match &received.discrepancy_reason {
+        None if received_number_of_packs == expected => true,
+        None => return Err(DiscrepancyReasonRequired(received.invoice_line_id.clone())),
+        Some(Reason::Short | Reason::Damaged) => received_number_of_packs < expected,
+        Some(Reason::Excess) => received_number_of_packs > expected,
+    };
+
+    if !reason_is_valid {
+        return Err(InvalidDiscrepancyReason(received.invoice_line_id.clone()));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn set_stock_on_hold(
+    connection: &StorageConnection,
+    invoice_line_id: &str,
+    on_hold: bool,
+) -> Result<(), RepositoryError> {
+    let Some(stock_line_id) = InvoiceLineRowRepository::new(connection)
+        .find_one_by_id(invoice_line_id)?
+        .and_then(|line| line.stock_line_id)
+    else {
+        return Ok(());
+    };
+
+    let repo = StockLineRowRepository::new(connection);
+    if let Some(mut stock_line) = repo.find_one_by_id(&stock_line_id)? {
+        stock_line.on_hold = on_hold;
+        repo.upsert_one(&stock_line)?;
+    }
+
+    Ok(())
+}
+
+impl From<RepositoryError> for ReceiveGoodsError {
+    fn from(error: RepositoryError) -> Self {
+        ReceiveGoodsError::DatabaseError(error)
+    }
+}
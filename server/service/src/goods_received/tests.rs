@@ -0,0 +1,302 @@
+use repository::{
+    mock::{mock_item_a, mock_name_a, mock_store_a, mock_store_b, MockData, MockDataInserts},
+    test_db::setup_all_with_data,
+    ActivityLogFilter, ActivityLogRepository, ActivityLogType, EqualFilter,
+    GoodsReceivedDiscrepancyReason, GoodsReceivedInspectionStatus, GoodsReceivedLineRow,
+    GoodsReceivedLineRowRepository, InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineType,
+    InvoiceRow, InvoiceStatus, InvoiceType, StockLineRowRepository, StorageConnection,
+};
+use util::inline_init;
+
+use crate::{
+    goods_received::{
+        inspect::{InspectGoodsReceivedLine, InspectGoodsReceivedLineError},
+        receive::{ReceiveGoods, ReceiveGoodsError, ReceiveGoodsLine},
+    },
+    processors::goods_received::report_discrepancies,
+    service_provider::ServiceProvider,
+};
+
+fn inbound_shipment() -> InvoiceRow {
+    inline_init(|r: &mut InvoiceRow| {
+        r.id = "inbound".to_string();
+        r.name_link_id = mock_name_a().id;
+        r.store_id = mock_store_a().id;
+        r.r#type = InvoiceType::InboundShipment;
+        r.status = InvoiceStatus::Shipped;
+        r.linked_invoice_id = Some("outbound".to_string());
+    })
+}
+
+fn outbound_shipment() -> InvoiceRow {
+    inline_init(|r: &mut InvoiceRow| {
+        r.id = "outbound".to_string();
+        r.name_link_id = mock_name_a().id;
+        r.store_id = mock_store_b().id;
+        r.r#type = InvoiceType::OutboundShipment;
+        r.status = InvoiceStatus::Shipped;
+    })
+}
+
+fn stock_in_line(id: &str, number_of_packs: f64) -> InvoiceLineRow {
+    InvoiceLineRow {
+        id: id.to_string(),
+        invoice_id: "inbound".to_string(),
+        item_link_id: mock_item_a().id,
+        item_name: mock_item_a().name,
+        batch: Some(id.to_string()),
+        pack_size: 1.0,
+        number_of_packs,
+        r#type: InvoiceLineType::StockIn,
+        ..Default::default()
+    }
+}
+
+fn expected_line(id: &str, invoice_line_id: &str, expected: f64) -> GoodsReceivedLineRow {
+    GoodsReceivedLineRow {
+        id: id.to_string(),
+        store_id: mock_store_a().id,
+        invoice_id: "inbound".to_string(),
+        invoice_line_id: invoice_line_id.to_string(),
+        linked_invoice_id: Some("outbound".to_string()),
+        supplier_name_link_id: mock_name_a().id,
+        item_link_id: mock_item_a().id,
+        expected_number_of_packs: expected,
+        ..Default::default()
+    }
+}
+
+fn received_line(connection: &StorageConnection, invoice_line_id: &str) -> GoodsReceivedLineRow {
+    GoodsReceivedLineRowRepository::new(connection)
+        .find_many_by_invoice_id("inbound")
+        .unwrap()
+        .into_iter()
+        .find(|line| line.invoice_line_id == invoice_line_id)
+        .unwrap()
+}
+
+fn is_stock_on_hold(connection: &StorageConnection, invoice_line_id: &str) -> bool {
+    let stock_line_id = InvoiceLineRowRepository::new(connection)
+        .find_one_by_id(invoice_line_id)
+        .unwrap()
+        .unwrap()
+        .stock_line_id
+        .unwrap();
+    StockLineRowRepository::new(connection)
+        .find_one_by_id(&stock_line_id)
+        .unwrap()
+        .unwrap()
+        .on_hold
+}
+
+#[actix_rt::test]
+async fn receive_and_inspect_goods() {
+    let (_, connection, connection_manager, _) = setup_all_with_data(
+        "receive_and_inspect_goods",
+        MockDataInserts::all(),
+        MockData {
+            invoices: vec![outbound_shipment(), inbound_shipment()],
+            invoice_lines: vec![
+                stock_in_line("damaged", 10.0),
+                stock_in_line("excess", 10.0),
+                stock_in_line("as_expected", 5.0),
+            ],
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let repo = GoodsReceivedLineRowRepository::new(&connection);
+    repo.upsert_one(&expected_line("expected_damaged", "damaged", 10.0))
+        .unwrap();
+    repo.upsert_one(&expected_line("expected_removed", "removed", 4.0))
+        .unwrap();
+
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider
+        .context(mock_store_a().id, "".to_string())
+        .unwrap();
+    let service = &service_provider.goods_received_service;
+
+    let receive = |lines: Vec<ReceiveGoodsLine>| ReceiveGoods {
+        invoice_id: "inbound".to_string(),
+        lines,
+    };
+
+    // LineDoesNotExist
+    assert_eq!(
+        service.receive_goods(
+            &context,
+            receive(vec![ReceiveGoodsLine {
+                invoice_line_id: "removed".to_string(),
+                ..Default::default()
+            }])
+        ),
+        Err(ReceiveGoodsError::LineDoesNotExist("removed".to_string()))
+    );
+
+    // DiscrepancyReasonRequired
+    assert_eq!(
+        service.receive_goods(
+            &context,
+            receive(vec![ReceiveGoodsLine {
+                invoice_line_id: "damaged".to_string(),
+                received_number_of_packs: 8.0,
+                ..Default::default()
+            }])
+        ),
+        Err(ReceiveGoodsError::DiscrepancyReasonRequired(
+            "damaged".to_string()
+        ))
+    );
+
+    // InvalidDiscrepancyReason
+    assert_eq!(
+        service.receive_goods(
+            &context,
+            receive(vec![ReceiveGoodsLine {
+                invoice_line_id: "excess".to_string(),
+                received_number_of_packs: 12.0,
+                discrepancy_reason: Some(GoodsReceivedDiscrepancyReason::Short),
+                ..Default::default()
+            }])
+        ),
+        Err(ReceiveGoodsError::InvalidDiscrepancyReason(
+            "excess".to_string()
+        ))
+    );
+
+    // Success
+    let invoice = service
+        .receive_goods(
+            &context,
+            receive(vec![
+                ReceiveGoodsLine {
+                    invoice_line_id: "damaged".to_string(),
+                    received_number_of_packs: 8.0,
+                    discrepancy_reason: Some(GoodsReceivedDiscrepancyReason::Damaged),
+                    comment: Some("Broken vials".to_string()),
+                    quarantine: false,
+                },
+                ReceiveGoodsLine {
+                    invoice_line_id: "excess".to_string(),
+                    received_number_of_packs: 12.0,
+                    discrepancy_reason: Some(GoodsReceivedDiscrepancyReason::Excess),
+                    comment: None,
+                    quarantine: true,
+                },
+            ]),
+        )
+        .unwrap();
+    assert_eq!(invoice.invoice_row.status, InvoiceStatus::Delivered);
+
+    let damaged = received_line(&connection, "damaged");
+    assert_eq!(damaged.id, "expected_damaged");
+    assert_eq!(damaged.expected_number_of_packs, 10.0);
+    assert_eq!(damaged.received_number_of_packs, Some(8.0));
+    assert_eq!(damaged.comment, Some("Broken vials".to_string()));
+    assert!(!is_stock_on_hold(&connection, "damaged"));
+
+    let excess = received_line(&connection, "excess");
+    assert_eq!(excess.expected_number_of_packs, 10.0);
+    assert_eq!(excess.received_number_of_packs, Some(12.0));
+    assert_eq!(
+        excess.inspection_status,
+        Some(GoodsReceivedInspectionStatus::Pending)
+    );
+    assert!(is_stock_on_hold(&connection, "excess"));
+
+    let as_expected = received_line(&connection, "as_expected");
+    assert_eq!(as_expected.received_number_of_packs, Some(5.0));
+    assert!(!as_expected.has_discrepancy());
+
+    let removed = received_line(&connection, "removed");
+    assert_eq!(removed.received_number_of_packs, Some(0.0));
+    assert_eq!(
+        removed.discrepancy_reason,
+        Some(GoodsReceivedDiscrepancyReason::Short)
+    );
+
+    assert_eq!(
+        InvoiceLineRowRepository::new(&connection)
+            .find_one_by_id("damaged")
+            .unwrap()
+            .unwrap()
+            .number_of_packs,
+        8.0
+    );
+
+    // InvoiceAlreadyReceived
+    assert_eq!(
+        service.receive_goods(&context, receive(Vec::new())),
+        Err(ReceiveGoodsError::InvoiceAlreadyReceived)
+    );
+
+    // Inspection
+    assert_eq!(
+        service.inspect_goods_received_line(
+            &context,
+            InspectGoodsReceivedLine {
+                id: damaged.id.clone(),
+                passed: true,
+                comment: None,
+            }
+        ),
+        Err(InspectGoodsReceivedLineError::NotPendingInspection)
+    );
+
+    let inspected = service
+        .inspect_goods_received_line(
+            &context,
+            InspectGoodsReceivedLine {
+                id: excess.id.clone(),
+                passed: true,
+                comment: Some("Seals intact".to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        inspected.inspection_status,
+        Some(GoodsReceivedInspectionStatus::Passed)
+    );
+    assert_eq!(inspected.comment, Some("Seals intact".to_string()));
+    assert!(!is_stock_on_hold(&connection, "excess"));
+
+    // Supplier store can review the discrepancies of its outbound shipment
+    let supplier_context = service_provider
+        .context(mock_store_b().id, "".to_string())
+        .unwrap();
+    assert_eq!(
+        service
+            .get_goods_received_lines(&supplier_context, "outbound")
+            .unwrap()
+            .len(),
+        4
+    );
+    assert_eq!(
+        service
+            .get_goods_received_lines(&supplier_context, "inbound")
+            .unwrap(),
+        Vec::new()
+    );
+
+    // Discrepancies are reported to the supplier store once
+    let store_ids = vec![mock_store_b().id];
+    assert_eq!(
+        report_discrepancies(&connection, &removed.id, &store_ids),
+        Ok(Some("outbound".to_string()))
+    );
+    assert_eq!(
+        report_discrepancies(&connection, &damaged.id, &store_ids),
+        Ok(None)
+    );
+    let logs = ActivityLogRepository::new(&connection)
+        .query_by_filter(
+            ActivityLogFilter::new()
+                .r#type(ActivityLogType::InvoiceDiscrepanciesReported.equal_to())
+                .record_id(EqualFilter::equal_to("outbound")),
+        )
+        .unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].activity_log_row.store_id, Some(mock_store_b().id));
+}
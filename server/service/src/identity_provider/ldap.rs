@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use ldap3::{LdapConnAsync, Scope, SearchEntry};
+
+use super::{Authenticator, Credentials, ExternalAuthError, ExternalIdentity};
+
+/// LDAP result code for rejected bind credentials
+const LDAP_INVALID_CREDENTIALS: u32 = 49;
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct LdapSettings {
+    /// e.g. ldaps://ldap.example.org:636
+    pub url: String,
+    /// Service account used to look up the user's DN, anonymous search if not set
+    pub bind_dn: Option<String>,
+    pub bind_password: Option<String>,
+    pub user_base_dn: String,
+    /// `{username}` is replaced by the escaped username
+    #[serde(default = "default_user_filter")]
+    pub user_filter: String,
+    #[serde(default = "default_username_attribute")]
+    pub username_attribute: String,
+    #[serde(default = "default_email_attribute")]
+    pub email_attribute: String,
+    #[serde(default = "default_first_name_attribute")]
+    pub first_name_attribute: String,
+    #[serde(default = "default_last_name_attribute")]
+    pub last_name_attribute: String,
+    /// Attribute listing the user's groups, either as group names or as group DNs
+    #[serde(default = "default_group_attribute")]
+    pub group_attribute: String,
+}
+
+fn default_user_filter() -> String {
+    "(uid={username})".to_string()
+}
+
+fn default_username_attribute() -> String {
+    "uid".to_string()
+}
+
+fn default_email_attribute() -> String {
+    "mail".to_string()
+}
+
+fn default_first_name_attribute() -> String {
+    "givenName".to_string()
+}
+
+fn default_last_name_attribute() -> String {
+    "sn".to_string()
+}
+
+fn default_group_attribute() -> String {
+    "memberOf".to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LdapEntry {
+    pub dn: String,
+    pub attributes: HashMap<String, Vec<String>>,
+}
+
+impl LdapEntry {
+    fn first_value(&self, attribute: &str) -> Option<String> {
+        self.attributes
+            .get(attribute)
+            .and_then(|values| values.first())
+            .cloned()
+    }
+}
+
+/// Opens connections to the directory, allows the directory to be stubbed in tests
+#[async_trait]
+pub trait LdapConnector: Send + Sync {
+    async fn connect(&self, url: &str) -> Result<Box<dyn LdapSession>, ExternalAuthError>;
+}
+
+#[async_trait]
+pub trait LdapSession: Send {
+    /// Returns false if the directory rejected the credentials
+    async fn simple_bind(&mut self, dn: &str, password: &str) -> Result<bool, ExternalAuthError>;
+
+    async fn search(
+        &mut self,
+        base_dn: &str,
+        filter: &str,
+        attributes: &[String],
+    ) -> Result<Vec<LdapEntry>, ExternalAuthError>;
+
+    async fn unbind(&mut self) -> Result<(), ExternalAuthError>;
+}
+
+fn connection_error(error: ldap3::LdapError) -> ExternalAuthError {
+    ExternalAuthError::ConnectionError(format!("{:?}", error))
+}
+
+pub struct Ldap3Connector;
+
+#[async_trait]
+impl LdapConnector for Ldap3Connector {
+    async fn connect(&self, url: &str) -> Result<Box<dyn LdapSession>, ExternalAuthError> {
+        let (connection, ldap) = LdapConnAsync::new(url).await.map_err(connection_error)?;
+        ldap3::drive!(connection);
+        Ok(Box::new(Ldap3Session { ldap }))
+    }
+}
+
+struct Ldap3Session {
+    ldap: ldap3::Ldap,
+}
+
+#[async_trait]
+impl LdapSession for Ldap3Session {
+    async fn simple_bind(&mut self, dn: &str, password: &str) -> Result<bool, ExternalAuthError> {
+        let result = self
+            .ldap
+            .simple_bind(dn, password)
+            .await
+            .map_err(connection_error)?;
+        match result.rc {
+            0 => Ok(true),
+            LDAP_INVALID_CREDENTIALS => Ok(false),
+            _ => Err(ExternalAuthError::ConnectionError(format!(
+                "LDAP bind failed: {:?}",
+                result
+            ))),
+        }
+    }
+
+    async fn search(
+        &mut self,
+        base_dn: &str,
+        filter: &str,
+        attributes: &[String],
+    ) -> Result<Vec<LdapEntry>, ExternalAuthError> {
+        let (entries, _) = self
+            .ldap
+            .search(base_dn, Scope::Subtree, filter, attributes.to_vec())
+            .await
+            .and_then(|result| result.success())
+            .map_err(connection_error)?;
+
+        Ok(entries
+            .into_iter()
+            .map(|entry| {
+                let entry = SearchEntry::construct(entry);
+                LdapEntry {
+                    dn: entry.dn,
+                    attributes: entry.attrs,
+                }
+            })
+            .collect())
+    }
+
+    async fn unbind(&mut self) -> Result<(), ExternalAuthError> {
+        self.ldap.unbind().await.map_err(connection_error)
+    }
+}
+
+/// Escapes a value for use in a search filter (RFC 4515)
+pub fn escape_filter_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '*' => escaped.push_str("\\2a"),
+            '(' => escaped.push_str("\\28"),
+            ')' => escaped.push_str("\\29"),
+            '\\' => escaped.push_str("\\5c"),
+            '\0' => escaped.push_str("\\00"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Group name from a group DN, e.g. `cn=pharmacists,ou=groups,dc=example,dc=org` ->
+/// `pharmacists`. Values that aren't DNs are returned as is.
+pub fn group_name(value: &str) -> String {
+    let first_rdn = value.split(',').next().unwrap_or(value);
+    match first_rdn.split_once('=') {
+        Some((attribute, name)) if attribute.trim().eq_ignore_ascii_case("cn") => {
+            name.trim().to_string()
+        }
+        _ => value.to_string(),
+    }
+}
+
+/// Authenticates users with a bind against an LDAP directory
+pub struct LdapAuthenticator {
+    settings: LdapSettings,
+    connector: Box<dyn LdapConnector>,
+}
+
+impl LdapAuthenticator {
+    pub fn new(settings: LdapSettings) -> Self {
+        Self::with_connector(settings, Box::new(Ldap3Connector))
+    }
+
+    pub fn with_connector(settings: LdapSettings, connector: Box<dyn LdapConnector>) -> Self {
+        LdapAuthenticator {
+            settings,
+            connector,
+        }
+    }
+
+    async fn find_and_bind_user(
+        &self,
+        session: &mut dyn LdapSession,
+        username: &str,
+        password: &str,
+    ) -> Result<ExternalIdentity, ExternalAuthError> {
+        let settings = &self.settings;
+
+        if let Some(bind_dn) = &settings.bind_dn {
+            let bind_password = settings.bind_password.as_deref().unwrap_or_default();
+            if !session.simple_bind(bind_dn, bind_password).await? {
+                return Err(ExternalAuthError::ConnectionError(
+                    "LDAP service account bind was rejected".to_string(),
+                ));
+            }
+        }
+
+        let filter = settings
+            .user_filter
+            .replace("{username}", &escape_filter_value(username));
+        let attributes = vec![
+            settings.username_attribute.clone(),
+            settings.email_attribute.clone(),
+            settings.first_name_attribute.clone(),
+            settings.last_name_attribute.clone(),
+            settings.group_attribute.clone(),
+        ];
+        let mut entries = session
+            .search(&settings.user_base_dn, &filter, &attributes)
+            .await?;
+        let entry = match entries.len() {
+            0 => return Err(ExternalAuthError::InvalidCredentials),
+            1 => entries.remove(0),
+            _ => {
+                return Err(ExternalAuthError::InvalidResponse(format!(
+                    "More than one LDAP entry matches {}",
+                    filter
+                )))
+            }
+        };
+
+        if !session.simple_bind(&entry.dn, password).await? {
+            return Err(ExternalAuthError::InvalidCredentials);
+        }
+
+        Ok(ExternalIdentity {
+            external_id: format!("{}|{}", settings.url, entry.dn),
+            username: entry
+                .first_value(&settings.username_attribute)
+                .unwrap_or_else(|| username.to_string()),
+            email: entry.first_value(&settings.email_attribute),
+            first_name: entry.first_value(&settings.first_name_attribute),
+            last_name: entry.first_value(&settings.last_name_attribute),
+            groups: entry
+                .attributes
+                .get(&settings.group_attribute)
+                .map(|values| values.iter().map(|value| group_name(value)).collect())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+#[async_trait]
+impl Authenticator for LdapAuthenticator {
+    async fn authenticate(
+        &self,
+        credentials: Credentials,
+    ) -> Result<ExternalIdentity, ExternalAuthError> {
+        let Credentials::Password { username, password } = credentials else {
+            return Err(ExternalAuthError::UnsupportedCredentials);
+        };
+        // A bind with an empty password is an unauthenticated bind which most directories accept
+        if username.is_empty() || password.is_empty() {
+            return Err(ExternalAuthError::InvalidCredentials);
+        }
+
+        let mut session = self.connector.connect(&self.settings.url).await?;
+        let result = self
+            .find_and_bind_user(session.as_mut(), &username, &password)
+            .await;
+        // Result of the login is already known, a failing unbind doesn't change it
+        let _ = session.unbind().await;
+
+        result
+    }
+}
@@ -0,0 +1,269 @@
+use std::time::SystemTime;
+
+use chrono::{Duration, Utc};
+use repository::{
+    OidcAuthorisationRequestRow, OidcAuthorisationRequestRowRepository, UserAuthStateRowRepository,
+};
+
+use crate::{
+    auth_data::AuthData,
+    local_auth::lockout::{
+        find_auth_state_by_username, lockout_remaining, record_failed_login, FailedLoginReason,
+    },
+    login::{LoginError, LoginFailure, LoginService},
+    service_provider::ServiceProvider,
+    token::TokenPair,
+};
+
+use super::{
+    oidc::OidcAuthenticator, provision_external_user, Authenticator, Credentials,
+    ExternalAuthError, ExternalIdentity, IdentityProviderSettings, ProvisionExternalUserError,
+};
+
+/// Time the user has to complete the login with the identity provider
+const OIDC_AUTHORISATION_REQUEST_EXPIRY_MINUTES: i64 = 10;
+
+/// Logins with an identity provider. Like the LoginService this takes a ServiceProvider since
+/// the identity provider is called asynchronously.
+pub struct ExternalLoginService {}
+
+impl ExternalLoginService {
+    /// Returns the url of the identity provider's login page, the identity provider redirects
+    /// back with the `code` and `state` to be passed to `oidc_login`
+    pub async fn start_oidc_login(
+        service_provider: &ServiceProvider,
+        settings: &IdentityProviderSettings,
+    ) -> Result<String, LoginError> {
+        let oidc_settings = settings.oidc.clone().ok_or(LoginError::ExternalAuthError(
+            ExternalAuthError::NotConfigured,
+        ))?;
+        let request = OidcAuthenticator::new(oidc_settings)?
+            .authorisation_request()
+            .await?;
+
+        let service_ctx = service_provider.basic_context()?;
+        let repo = OidcAuthorisationRequestRowRepository::new(&service_ctx.connection);
+        let now = Utc::now().naive_utc();
+        // Clean up logins that were never completed
+        repo.delete_created_before(
+            now - Duration::minutes(OIDC_AUTHORISATION_REQUEST_EXPIRY_MINUTES),
+        )?;
+        repo.insert_one(&OidcAuthorisationRequestRow {
+            id: request.state,
+            code_verifier: request.code_verifier,
+            nonce: request.nonce,
+            created_datetime: now,
+        })?;
+
+        Ok(request.url)
+    }
+
+    pub async fn oidc_login(
+        service_provider: &ServiceProvider,
+        auth_data: &AuthData,
+        settings: &IdentityProviderSettings,
+        code: &str,
+        state: &str,
+        totp_code: Option<&str>,
+        min_err_response_time_sec: u64,
+    ) -> Result<TokenPair, LoginError> {
+        let now = SystemTime::now();
+        let result = ExternalLoginService::do_oidc_login(
+            service_provider,
+            auth_data,
+            settings,
+            code,
+            state,
+            totp_code,
+        )
+        .await;
+        LoginService::delay_error(now, min_err_response_time_sec, result).await
+    }
+
+    async fn do_oidc_login(
+        service_provider: &ServiceProvider,
+        auth_data: &AuthData,
+        settings: &IdentityProviderSettings,
+        code: &str,
+        state: &str,
+        totp_code: Option<&str>,
+    ) -> Result<TokenPair, LoginError> {
+        let oidc_settings = settings.oidc.clone().ok_or(LoginError::ExternalAuthError(
+            ExternalAuthError::NotConfigured,
+        ))?;
+
+        // Each authorisation request can only be used once
+        let request = {
+            let service_ctx = service_provider.basic_context()?;
+            let repo = OidcAuthorisationRequestRowRepository::new(&service_ctx.connection);
+            let request = repo.find_one_by_id(state)?;
+            if request.is_some() {
+                repo.delete(state)?;
+            }
+            let expired_before = Utc::now().naive_utc()
+                - Duration::minutes(OIDC_AUTHORISATION_REQUEST_EXPIRY_MINUTES);
+            request.filter(|request| request.created_datetime >= expired_before)
+        };
+        let Some(request) = request else {
+            return Err(LoginError::ExternalAuthError(
+                ExternalAuthError::InvalidAuthorisationRequest,
+            ));
+        };
+
+        let identity = OidcAuthenticator::new(oidc_settings)?
+            .authenticate(Credentials::AuthorisationCode {
+                code: code.to_string(),
+                code_verifier: request.code_verifier,
+                nonce: request.nonce,
+            })
+            .await?;
+
+        ExternalLoginService::complete_login(
+            service_provider,
+            auth_data,
+            settings,
+            identity,
+            totp_code,
+        )
+    }
+
+    /// Login with username and password checked by the authenticator, e.g. an LDAP directory
+    pub async fn password_login(
+        service_provider: &ServiceProvider,
+        auth_data: &AuthData,
+        settings: &IdentityProviderSettings,
+        authenticator: &dyn Authenticator,
+        username: &str,
+        password: &str,
+        totp_code: Option<&str>,
+        min_err_response_time_sec: u64,
+    ) -> Result<TokenPair, LoginError> {
+        let now = SystemTime::now();
+        let result = ExternalLoginService::do_password_login(
+            service_provider,
+            auth_data,
+            settings,
+            authenticator,
+            username,
+            password,
+            totp_code,
+        )
+        .await;
+        LoginService::delay_error(now, min_err_response_time_sec, result).await
+    }
+
+    async fn do_password_login(
+        service_provider: &ServiceProvider,
+        auth_data: &AuthData,
+        settings: &IdentityProviderSettings,
+        authenticator: &dyn Authenticator,
+        username: &str,
+        password: &str,
+        totp_code: Option<&str>,
+    ) -> Result<TokenPair, LoginError> {
+        // Same lockout as for local logins, stops passwords from being tried against the directory
+        {
+            let service_ctx = service_provider.basic_context()?;
+            let timeout_remaining = find_auth_state_by_username(&service_ctx.connection, username)?
+                .and_then(|auth_state| lockout_remaining(&auth_state, Utc::now().naive_utc()));
+            if let Some(timeout_remaining) = timeout_remaining {
+                record_failed_login(
+                    &service_ctx.connection,
+                    username,
+                    FailedLoginReason::AccountLocked,
+                )?;
+                return Err(LoginError::LoginFailure(LoginFailure::AccountBlocked(
+                    timeout_remaining,
+                )));
+            }
+        }
+
+        let identity = match authenticator
+            .authenticate(Credentials::Password {
+                username: username.to_string(),
+                password: password.to_string(),
+            })
+            .await
+        {
+            Ok(identity) => identity,
+            Err(ExternalAuthError::InvalidCredentials) => {
+                let service_ctx = service_provider.basic_context()?;
+                record_failed_login(
+                    &service_ctx.connection,
+                    username,
+                    FailedLoginReason::InvalidCredentials,
+                )?;
+                return Err(LoginError::LoginFailure(LoginFailure::InvalidCredentials));
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        ExternalLoginService::complete_login(
+            service_provider,
+            auth_data,
+            settings,
+            identity,
+            totp_code,
+        )
+    }
+
+    /// Provisions the authenticated user, runs the same checks as a local login and issues the
+    /// tokens
+    fn complete_login(
+        service_provider: &ServiceProvider,
+        auth_data: &AuthData,
+        settings: &IdentityProviderSettings,
+        identity: ExternalIdentity,
+        totp_code: Option<&str>,
+    ) -> Result<TokenPair, LoginError> {
+        let mut service_ctx = service_provider.basic_context()?;
+        let user_account = provision_external_user(&service_ctx, settings, identity)?;
+
+        // The identity provider's own lockout doesn't cover the TOTP code checked by this site
+        let auth_state = UserAuthStateRowRepository::new(&service_ctx.connection)
+            .find_or_default(&user_account.id)?;
+        if let Some(timeout_remaining) = lockout_remaining(&auth_state, Utc::now().naive_utc()) {
+            record_failed_login(
+                &service_ctx.connection,
+                &user_account.username,
+                FailedLoginReason::AccountLocked,
+            )?;
+            return Err(LoginError::LoginFailure(LoginFailure::AccountBlocked(
+                timeout_remaining,
+            )));
+        }
+
+        LoginService::complete_authentication(
+            &mut service_ctx,
+            &user_account.username,
+            &user_account.id,
+            totp_code,
+        )?;
+
+        // The password isn't known to this site, it's only needed for syncing with central which
+        // identity provider deployments don't use
+        LoginService::issue_tokens(auth_data, &user_account.id, "")
+    }
+}
+
+impl From<ProvisionExternalUserError> for LoginError {
+    fn from(error: ProvisionExternalUserError) -> Self {
+        match error {
+            ProvisionExternalUserError::UsernameTaken(username) => {
+                LoginError::ExternalAuthError(ExternalAuthError::UsernameTaken(username))
+            }
+            ProvisionExternalUserError::DatabaseError(error) => LoginError::DatabaseError(error),
+        }
+    }
+}
+
+impl From<ExternalAuthError> for LoginError {
+    fn from(error: ExternalAuthError) -> Self {
+        match error {
+            ExternalAuthError::InvalidCredentials => {
+                LoginError::LoginFailure(LoginFailure::InvalidCredentials)
+            }
+            error => LoginError::ExternalAuthError(error),
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use repository::{
+    PermissionType, RepositoryError, UserAccountRow, UserAccountRowRepository, UserAuthStateRow,
+    UserAuthStateRowRepository, UserPermissionRow, UserStoreJoinRow,
+};
+use util::uuid::uuid;
+
+use crate::{
+    service_provider::ServiceContext,
+    user_account::{StorePermissions, UserAccountService},
+};
+
+use self::{ldap::LdapSettings, oidc::OidcSettings};
+
+pub mod ldap;
+pub mod login;
+pub mod oidc;
+
+#[cfg(test)]
+mod tests;
+
+/// Identity providers used instead of the central server to authenticate users, e.g. for
+/// deployments without a legacy mSupply central server
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct IdentityProviderSettings {
+    pub oidc: Option<OidcSettings>,
+    pub ldap: Option<LdapSettings>,
+    /// Stores and permissions given to the members of a group
+    #[serde(default)]
+    pub group_mappings: Vec<GroupMapping>,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct GroupMapping {
+    /// Group name as provided by the identity provider, compared case insensitive
+    pub group: String,
+    pub store_ids: Vec<String>,
+    /// Permissions in the stores, store access is always given
+    #[serde(default)]
+    pub permissions: Vec<PermissionType>,
+}
+
+/// User as described by the identity provider
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ExternalIdentity {
+    /// Stable id of the user at the identity provider, e.g. issuer and subject of an OIDC id token.
+    /// Matched instead of the username, which users can often choose or change themselves.
+    pub external_id: String,
+    pub username: String,
+    pub email: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub groups: Vec<String>,
+}
+
+pub enum Credentials {
+    Password {
+        username: String,
+        password: String,
+    },
+    /// Result of an OpenID Connect authorisation code flow
+    AuthorisationCode {
+        code: String,
+        code_verifier: String,
+        nonce: String,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ExternalAuthError {
+    /// No identity provider of this kind is configured
+    NotConfigured,
+    InvalidCredentials,
+    /// The authenticator doesn't support this kind of credentials
+    UnsupportedCredentials,
+    /// Authorisation request is unknown, was already used or has expired
+    InvalidAuthorisationRequest,
+    ConnectionError(String),
+    /// The identity provider returned something we can't trust or understand
+    InvalidResponse(String),
+    /// The username belongs to a user account that wasn't provisioned for this external user
+    UsernameTaken(String),
+}
+
+/// Verifies credentials with an identity provider
+#[async_trait]
+pub trait Authenticator: Send + Sync {
+    async fn authenticate(
+        &self,
+        credentials: Credentials,
+    ) -> Result<ExternalIdentity, ExternalAuthError>;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ProvisionExternalUserError {
+    /// The username belongs to an account that wasn't provisioned for this external user, e.g. a
+    /// local or central user. The account is never adopted since the identity provider could
+    /// then take over any account by asserting its username.
+    UsernameTaken(String),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for ProvisionExternalUserError {
+    fn from(error: RepositoryError) -> Self {
+        ProvisionExternalUserError::DatabaseError(error)
+    }
+}
+
+/// Creates or updates the user account of an externally authenticated user. The account is
+/// matched by the identity's `external_id`, the username is only copied from the identity provider.
+/// Store access and permissions are replaced by the ones mapped from the user's groups.
+pub fn provision_external_user(
+    ctx: &ServiceContext,
+    settings: &IdentityProviderSettings,
+    identity: ExternalIdentity,
+) -> Result<UserAccountRow, ProvisionExternalUserError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let user_repo = UserAccountRowRepository::new(connection);
+            let auth_state_repo = UserAuthStateRowRepository::new(connection);
+
+            let existing = match auth_state_repo.find_one_by_external_id(&identity.external_id)? {
+                Some(auth_state) => user_repo.find_one_by_id(&auth_state.user_id)?,
+                None => None,
+            };
+            if let Some(user) = user_repo.find_one_by_user_name(&identity.username)? {
+                if !existing
+                    .as_ref()
+                    .is_some_and(|existing| existing.id == user.id)
+                {
+                    return Err(ProvisionExternalUserError::UsernameTaken(
+                        identity.username.clone(),
+                    ));
+                }
+            }
+
+            let hashed_password = match &existing {
+                Some(user) if !user.hashed_password.is_empty() => user.hashed_password.clone(),
+                // Password is managed by the identity provider, store a random hash so the user is
+                // treated as active on the site but can't log in with a local password
+                _ => UserAccountService::hash_password(&uuid()).map_err(|error| {
+                    RepositoryError::as_db_error("Failed to hash password", error)
+                })?,
+            };
+
+            let user = UserAccountRow {
+                id: existing
+                    .as_ref()
+                    .map(|user| user.id.clone())
+                    .unwrap_or_else(uuid),
+                username: identity.username.clone(),
+                hashed_password,
+                email: identity.email.clone(),
+                first_name: identity.first_name.clone(),
+                last_name: identity.last_name.clone(),
+                ..existing.unwrap_or_default()
+            };
+
+            UserAccountService::new(connection).upsert_user(
+                user.clone(),
+                stores_permissions(settings, &identity, &user.id),
+            )?;
+            auth_state_repo.upsert_one(&UserAuthStateRow {
+                external_id: Some(identity.external_id.clone()),
+                ..auth_state_repo.find_or_default(&user.id)?
+            })?;
+            Ok(user)
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+/// Store access and permissions mapped from the user's groups
+fn stores_permissions(
+    settings: &IdentityProviderSettings,
+    identity: &ExternalIdentity,
+    user_id: &str,
+) -> Vec<StorePermissions> {
+    // Keep the store order of the mappings so the first mapped store becomes the default store
+    let mut store_permissions: Vec<(String, HashSet<PermissionType>)> = Vec::new();
+    for mapping in mapped_groups(settings, &identity.groups) {
+        for store_id in &mapping.store_ids {
+            let index = match store_permissions.iter().position(|(id, _)| id == store_id) {
+                Some(index) => index,
+                None => {
+                    store_permissions.push((store_id.clone(), HashSet::new()));
+                    store_permissions.len() - 1
+                }
+            };
+            let permissions = &mut store_permissions[index].1;
+            permissions.insert(PermissionType::StoreAccess);
+            permissions.extend(mapping.permissions.iter().cloned());
+        }
+    }
+
+    store_permissions
+        .into_iter()
+        .enumerate()
+        .map(|(index, (store_id, permissions))| StorePermissions {
+            user_store_join: UserStoreJoinRow {
+                id: uuid(),
+                user_id: user_id.to_string(),
+                store_id: store_id.clone(),
+                is_default: index == 0,
+            },
+            permissions: permissions
+                .into_iter()
+                .map(|permission| UserPermissionRow {
+                    id: uuid(),
+                    user_id: user_id.to_string(),
+                    store_id: Some(store_id.clone()),
+                    permission,
+                    context_id: None,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn mapped_groups<'a>(
+    settings: &'a IdentityProviderSettings,
+    groups: &'a [String],
+) -> impl Iterator<Item = &'a GroupMapping> {
+    settings.group_mappings.iter().filter(|mapping| {
+        groups
+            .iter()
+            .any(|group| group.eq_ignore_ascii_case(&mapping.group))
+    })
+}
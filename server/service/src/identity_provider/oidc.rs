@@ -0,0 +1,316 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::prelude::*;
+use jsonwebtoken::{
+    decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, TokenData, Validation,
+};
+use rand::{distributions::Alphanumeric, Rng};
+use reqwest::{Client, ClientBuilder, Url};
+use serde::{de::DeserializeOwned, Deserialize};
+use sha2::{Digest, Sha256};
+
+use super::{Authenticator, Credentials, ExternalAuthError, ExternalIdentity};
+
+const CONNECTION_TIMEOUT_SEC: u64 = 10;
+/// RFC 7636 allows 43 to 128 characters
+const CODE_VERIFIER_LENGTH: usize = 64;
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct OidcSettings {
+    /// Issuer url, the discovery document is expected at
+    /// `{issuer_url}/.well-known/openid-configuration`
+    pub issuer_url: String,
+    pub client_id: String,
+    /// Not needed for public clients, also used to verify HMAC signed id tokens
+    pub client_secret: Option<String>,
+    /// Url of the omSupply login page the identity provider redirects back to
+    pub redirect_url: String,
+    #[serde(default = "default_scopes")]
+    pub scopes: Vec<String>,
+    /// Claim of the id token containing the user's groups
+    #[serde(default = "default_groups_claim")]
+    pub groups_claim: String,
+    #[serde(default = "default_username_claim")]
+    pub username_claim: String,
+}
+
+fn default_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "profile".to_string(),
+        "email".to_string(),
+    ]
+}
+
+fn default_groups_claim() -> String {
+    "groups".to_string()
+}
+
+fn default_username_claim() -> String {
+    "preferred_username".to_string()
+}
+
+/// Subset of the OpenID provider metadata we need
+#[derive(Deserialize, Debug, Clone)]
+pub struct OidcProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: Option<String>,
+}
+
+/// Parameters of a new authorisation request, the verifier and nonce need to be kept until the
+/// identity provider redirects back
+#[derive(Debug, Clone)]
+pub struct OidcAuthorisationRequest {
+    pub url: String,
+    pub state: String,
+    pub code_verifier: String,
+    pub nonce: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+pub fn random_string(length: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+/// S256 PKCE code challenge (RFC 7636) for the verifier
+pub fn pkce_code_challenge(code_verifier: &str) -> String {
+    BASE64_URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+pub struct OidcAuthenticator {
+    settings: OidcSettings,
+    client: Client,
+}
+
+impl OidcAuthenticator {
+    pub fn new(settings: OidcSettings) -> Result<Self, ExternalAuthError> {
+        let client = ClientBuilder::new()
+            .connect_timeout(Duration::from_secs(CONNECTION_TIMEOUT_SEC))
+            .build()
+            .map_err(|error| ExternalAuthError::ConnectionError(format!("{:?}", error)))?;
+        Ok(OidcAuthenticator { settings, client })
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, ExternalAuthError> {
+        self.client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| ExternalAuthError::ConnectionError(format!("{:?}", error)))?
+            .json::<T>()
+            .await
+            .map_err(|error| ExternalAuthError::InvalidResponse(format!("{:?}", error)))
+    }
+
+    pub async fn discover(&self) -> Result<OidcProviderMetadata, ExternalAuthError> {
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            self.settings.issuer_url.trim_end_matches('/')
+        );
+        self.get_json(&url).await
+    }
+
+    /// Creates the url the user is sent to for logging in with the identity provider
+    pub async fn authorisation_request(
+        &self,
+    ) -> Result<OidcAuthorisationRequest, ExternalAuthError> {
+        let metadata = self.discover().await?;
+
+        let state = random_string(32);
+        let nonce = random_string(32);
+        let code_verifier = random_string(CODE_VERIFIER_LENGTH);
+
+        let mut url = Url::parse(&metadata.authorization_endpoint)
+            .map_err(|error| ExternalAuthError::InvalidResponse(format!("{:?}", error)))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.settings.client_id)
+            .append_pair("redirect_uri", &self.settings.redirect_url)
+            .append_pair("scope", &self.settings.scopes.join(" "))
+            .append_pair("state", &state)
+            .append_pair("nonce", &nonce)
+            .append_pair("code_challenge", &pkce_code_challenge(&code_verifier))
+            .append_pair("code_challenge_method", "S256");
+
+        Ok(OidcAuthorisationRequest {
+            url: url.to_string(),
+            state,
+            code_verifier,
+            nonce,
+        })
+    }
+
+    async fn exchange_code(
+        &self,
+        metadata: &OidcProviderMetadata,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<String, ExternalAuthError> {
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.settings.redirect_url.as_str()),
+            ("client_id", self.settings.client_id.as_str()),
+            ("code_verifier", code_verifier),
+        ];
+        if let Some(client_secret) = &self.settings.client_secret {
+            form.push(("client_secret", client_secret.as_str()));
+        }
+
+        let response = self
+            .client
+            .post(&metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|error| ExternalAuthError::ConnectionError(format!("{:?}", error)))?;
+        // Authorisation codes are single use and expire quickly, a rejected code is treated like
+        // wrong credentials
+        if response.status().is_client_error() {
+            return Err(ExternalAuthError::InvalidCredentials);
+        }
+        let response = response
+            .error_for_status()
+            .map_err(|error| ExternalAuthError::ConnectionError(format!("{:?}", error)))?
+            .json::<TokenResponse>()
+            .await
+            .map_err(|error| ExternalAuthError::InvalidResponse(format!("{:?}", error)))?;
+
+        Ok(response.id_token)
+    }
+
+    async fn decoding_key(
+        &self,
+        metadata: &OidcProviderMetadata,
+        algorithm: Algorithm,
+        key_id: Option<String>,
+    ) -> Result<DecodingKey, ExternalAuthError> {
+        if matches!(
+            algorithm,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            let client_secret = self.settings.client_secret.as_ref().ok_or_else(|| {
+                ExternalAuthError::InvalidResponse(
+                    "HMAC signed id token but no client secret configured".to_string(),
+                )
+            })?;
+            return Ok(DecodingKey::from_secret(client_secret.as_bytes()));
+        }
+
+        let jwks_uri = metadata.jwks_uri.as_ref().ok_or_else(|| {
+            ExternalAuthError::InvalidResponse("Identity provider has no jwks_uri".to_string())
+        })?;
+        let jwks: JwkSet = self.get_json(jwks_uri).await?;
+        let jwk = match &key_id {
+            Some(key_id) => jwks.find(key_id),
+            None => jwks.keys.first(),
+        }
+        .ok_or_else(|| {
+            ExternalAuthError::InvalidResponse(format!("No signing key found for {:?}", key_id))
+        })?;
+
+        DecodingKey::from_jwk(jwk)
+            .map_err(|error| ExternalAuthError::InvalidResponse(format!("{:?}", error)))
+    }
+
+    /// Verifies signature, issuer, audience, expiry and nonce of the id token
+    async fn verify_id_token(
+        &self,
+        metadata: &OidcProviderMetadata,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, ExternalAuthError> {
+        let header = decode_header(id_token)
+            .map_err(|error| ExternalAuthError::InvalidResponse(format!("{:?}", error)))?;
+        let key = self.decoding_key(metadata, header.alg, header.kid).await?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_audience(&[&self.settings.client_id]);
+        validation.set_issuer(&[&metadata.issuer]);
+        // Issuer and subject identify the user, don't accept tokens without them
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let TokenData { claims, .. } =
+            decode::<serde_json::Map<String, serde_json::Value>>(id_token, &key, &validation)
+                .map_err(|error| ExternalAuthError::InvalidResponse(format!("{:?}", error)))?;
+
+        if claims.get("nonce").and_then(|value| value.as_str()) != Some(nonce) {
+            return Err(ExternalAuthError::InvalidResponse(
+                "Id token nonce doesn't match the authorisation request".to_string(),
+            ));
+        }
+
+        Ok(claims)
+    }
+
+    fn identity_from_claims(
+        &self,
+        claims: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<ExternalIdentity, ExternalAuthError> {
+        let claim = |name: &str| {
+            claims
+                .get(name)
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+        };
+
+        // `sub` is only unique per issuer
+        let (Some(issuer), Some(subject)) = (claim("iss"), claim("sub")) else {
+            return Err(ExternalAuthError::InvalidResponse(
+                "Id token has no issuer or subject".to_string(),
+            ));
+        };
+        let username = claim(&self.settings.username_claim).unwrap_or_else(|| subject.clone());
+        let groups = match claims.get(&self.settings.groups_claim) {
+            Some(serde_json::Value::Array(values)) => values
+                .iter()
+                .filter_map(|value| value.as_str().map(str::to_string))
+                .collect(),
+            Some(serde_json::Value::String(value)) => vec![value.clone()],
+            _ => Vec::new(),
+        };
+
+        Ok(ExternalIdentity {
+            external_id: format!("{issuer}|{subject}"),
+            username,
+            email: claim("email"),
+            first_name: claim("given_name"),
+            last_name: claim("family_name"),
+            groups,
+        })
+    }
+}
+
+#[async_trait]
+impl Authenticator for OidcAuthenticator {
+    async fn authenticate(
+        &self,
+        credentials: Credentials,
+    ) -> Result<ExternalIdentity, ExternalAuthError> {
+        let Credentials::AuthorisationCode {
+            code,
+            code_verifier,
+            nonce,
+        } = credentials
+        else {
+            return Err(ExternalAuthError::UnsupportedCredentials);
+        };
+
+        let metadata = self.discover().await?;
+        let id_token = self.exchange_code(&metadata, &code, &code_verifier).await?;
+        let claims = self.verify_id_token(&metadata, &id_token, &nonce).await?;
+        self.identity_from_claims(&claims)
+    }
+}
@@ -0,0 +1,581 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use httpmock::{
+    Method::{GET, POST},
+    MockServer,
+};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use repository::{
+    mock::{mock_store_a, mock_user_account_a, MockDataInserts},
+    test_db::setup_all,
+    EqualFilter, KeyType, KeyValueStoreRepository, PermissionType, StorageConnection,
+    UserAccountRowRepository, UserAuthStateRow, UserAuthStateRowRepository, UserPermissionFilter,
+    UserPermissionRepository,
+};
+use serde_json::json;
+use util::assert_matches;
+
+use crate::{
+    auth_data::AuthData,
+    identity_provider::{
+        ldap::{
+            escape_filter_value, group_name, LdapAuthenticator, LdapConnector, LdapEntry,
+            LdapSession, LdapSettings,
+        },
+        login::ExternalLoginService,
+        oidc::{pkce_code_challenge, OidcSettings},
+        provision_external_user, Authenticator, Credentials, ExternalAuthError, ExternalIdentity,
+        GroupMapping, IdentityProviderSettings, ProvisionExternalUserError,
+    },
+    local_auth::totp::generate_totp_code,
+    login::{LoginError, LoginFailure},
+    service_provider::ServiceProvider,
+    token_bucket::TokenBucket,
+    user_account::UserAccountService,
+};
+
+#[test]
+fn pkce_and_ldap_helpers() {
+    // RFC 7636 appendix B
+    assert_eq!(
+        pkce_code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+        "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+    );
+
+    assert_eq!(
+        escape_filter_value("*)(uid=*))(|(uid=*"),
+        "\\2a\\29\\28uid=\\2a\\29\\29\\28|\\28uid=\\2a"
+    );
+    assert_eq!(
+        group_name("CN=Pharmacists,OU=Groups,DC=example,DC=org"),
+        "Pharmacists"
+    );
+    assert_eq!(group_name("pharmacists"), "pharmacists");
+}
+
+fn auth_data() -> AuthData {
+    AuthData {
+        auth_token_secret: "secret".to_string(),
+        token_bucket: Arc::new(RwLock::new(TokenBucket::new())),
+        no_ssl: true,
+        debug_no_access_control: false,
+    }
+}
+
+fn group_mappings() -> Vec<GroupMapping> {
+    vec![
+        GroupMapping {
+            group: "Pharmacists".to_string(),
+            store_ids: vec![mock_store_a().id],
+            permissions: vec![PermissionType::StockLineQuery],
+        },
+        GroupMapping {
+            group: "stock-controllers".to_string(),
+            store_ids: vec![mock_store_a().id],
+            permissions: vec![PermissionType::StocktakeMutate],
+        },
+        GroupMapping {
+            group: "not-a-member".to_string(),
+            store_ids: vec![mock_store_a().id],
+            permissions: vec![PermissionType::ServerAdmin],
+        },
+    ]
+}
+
+fn store_a_permissions(connection: &StorageConnection, user_id: &str) -> Vec<PermissionType> {
+    let mut permissions: Vec<PermissionType> = UserPermissionRepository::new(connection)
+        .query_by_filter(
+            UserPermissionFilter::new()
+                .user_id(EqualFilter::equal_to(user_id))
+                .store_id(EqualFilter::equal_to(&mock_store_a().id)),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|permission| permission.permission)
+        .collect();
+    permissions.sort_by_key(|permission| format!("{:?}", permission));
+    permissions
+}
+
+#[actix_rt::test]
+async fn oidc_login() {
+    let (_, connection, connection_manager, _) = setup_all(
+        "identity_provider_oidc_login",
+        MockDataInserts::none().names().stores(),
+    )
+    .await;
+    KeyValueStoreRepository::new(&connection)
+        .set_i32(KeyType::SettingsSyncSiteId, Some(mock_store_a().site_id))
+        .unwrap();
+
+    let mock_server = MockServer::start_async().await;
+    let issuer = mock_server.base_url();
+    let client_secret = "client secret";
+    mock_server
+        .mock_async(|when, then| {
+            when.method(GET).path("/.well-known/openid-configuration");
+            then.status(200).json_body(json!({
+                "issuer": issuer,
+                "authorization_endpoint": format!("{issuer}/authorize"),
+                "token_endpoint": format!("{issuer}/token"),
+            }));
+        })
+        .await;
+
+    let settings = IdentityProviderSettings {
+        oidc: Some(OidcSettings {
+            issuer_url: issuer.clone(),
+            client_id: "omsupply".to_string(),
+            client_secret: Some(client_secret.to_string()),
+            redirect_url: "http://localhost:8000/oidc".to_string(),
+            scopes: vec!["openid".to_string()],
+            groups_claim: "groups".to_string(),
+            username_claim: "preferred_username".to_string(),
+        }),
+        ldap: None,
+        group_mappings: group_mappings(),
+    };
+    let service_provider = ServiceProvider::new(connection_manager);
+    let auth_data = auth_data();
+
+    let url = ExternalLoginService::start_oidc_login(&service_provider, &settings)
+        .await
+        .unwrap();
+    let url = url::Url::parse(&url).unwrap();
+    assert_eq!(url.path(), "/authorize");
+    let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    assert_eq!(query["client_id"], "omsupply");
+    assert_eq!(query["code_challenge_method"], "S256");
+    let state = query["state"].clone();
+
+    // The identity provider signs the id token with the client secret and includes the nonce of
+    // the authorisation request
+    let id_token = encode(
+        &Header::default(),
+        &json!({
+            "iss": issuer,
+            "aud": "omsupply",
+            "sub": "user-1",
+            "exp": Utc::now().timestamp() + 300,
+            "nonce": query["nonce"],
+            "preferred_username": "oidc_user",
+            "email": "oidc_user@example.org",
+            "groups": ["pharmacists", "stock-controllers", "other"],
+        }),
+        &EncodingKey::from_secret(client_secret.as_bytes()),
+    )
+    .unwrap();
+    let token_mock = mock_server
+        .mock_async(|when, then| {
+            when.method(POST)
+                .path("/token")
+                .body_contains("code=valid-code")
+                .body_contains("code_verifier=");
+            then.status(200)
+                .json_body(json!({ "id_token": id_token, "token_type": "Bearer" }));
+        })
+        .await;
+
+    let login = |code: &'static str, state: String| {
+        let service_provider = &service_provider;
+        let auth_data = &auth_data;
+        let settings = &settings;
+        async move {
+            ExternalLoginService::oidc_login(
+                service_provider,
+                auth_data,
+                settings,
+                code,
+                &state,
+                None,
+                0,
+            )
+            .await
+        }
+    };
+
+    assert_matches!(
+        login("valid-code", "unknown-state".to_string()).await,
+        Err(LoginError::ExternalAuthError(
+            ExternalAuthError::InvalidAuthorisationRequest
+        ))
+    );
+    login("valid-code", state.clone()).await.unwrap();
+    token_mock.assert_async().await;
+
+    let user = UserAccountRowRepository::new(&connection)
+        .find_one_by_user_name("oidc_user")
+        .unwrap()
+        .unwrap();
+    assert_eq!(user.email, Some("oidc_user@example.org".to_string()));
+    assert_eq!(
+        UserAuthStateRowRepository::new(&connection)
+            .find_one_by_user_id(&user.id)
+            .unwrap()
+            .unwrap()
+            .external_id,
+        Some(format!("{issuer}|user-1"))
+    );
+    assert_eq!(
+        store_a_permissions(&connection, &user.id),
+        vec![
+            PermissionType::StockLineQuery,
+            PermissionType::StocktakeMutate,
+            PermissionType::StoreAccess,
+        ]
+    );
+    let stores = UserAccountService::new(&connection)
+        .find_user_active_on_this_site(&user.id)
+        .unwrap()
+        .unwrap()
+        .stores;
+    assert_eq!(stores.len(), 1);
+    assert!(stores[0].user_store_join.is_default);
+
+    // Authorisation requests can only be used once
+    assert_matches!(
+        login("valid-code", state).await,
+        Err(LoginError::ExternalAuthError(
+            ExternalAuthError::InvalidAuthorisationRequest
+        ))
+    );
+
+    // Code rejected by the identity provider
+    let url = ExternalLoginService::start_oidc_login(&service_provider, &settings)
+        .await
+        .unwrap();
+    let state = url::Url::parse(&url)
+        .unwrap()
+        .query_pairs()
+        .find(|(key, _)| key == "state")
+        .map(|(_, value)| value.to_string())
+        .unwrap();
+    assert_matches!(
+        login("invalid-code", state).await,
+        Err(LoginError::LoginFailure(LoginFailure::InvalidCredentials))
+    );
+}
+
+/// In process directory with a single user
+#[derive(Clone)]
+struct StubDirectory {
+    binds: Arc<Mutex<Vec<String>>>,
+    filters: Arc<Mutex<Vec<String>>>,
+}
+
+/// RFC 6238 test secret
+const TOTP_SECRET: &[u8] = b"12345678901234567890";
+const TOTP_SECRET_BASE32: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+const USER_DN: &str = "uid=ldap_user,ou=people,dc=example,dc=org";
+const SERVICE_DN: &str = "cn=omsupply,dc=example,dc=org";
+
+#[async_trait]
+impl LdapConnector for StubDirectory {
+    async fn connect(&self, _: &str) -> Result<Box<dyn LdapSession>, ExternalAuthError> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+#[async_trait]
+impl LdapSession for StubDirectory {
+    async fn simple_bind(&mut self, dn: &str, password: &str) -> Result<bool, ExternalAuthError> {
+        self.binds.lock().unwrap().push(dn.to_string());
+        Ok(matches!(
+            (dn, password),
+            (USER_DN, "ldap password") | (SERVICE_DN, "service password")
+        ))
+    }
+
+    async fn search(
+        &mut self,
+        _: &str,
+        filter: &str,
+        _: &[String],
+    ) -> Result<Vec<LdapEntry>, ExternalAuthError> {
+        self.filters.lock().unwrap().push(filter.to_string());
+        if filter != "(uid=ldap_user)" {
+            return Ok(Vec::new());
+        }
+        Ok(vec![LdapEntry {
+            dn: USER_DN.to_string(),
+            attributes: HashMap::from([
+                ("uid".to_string(), vec!["ldap_user".to_string()]),
+                ("givenName".to_string(), vec!["Ldap".to_string()]),
+                (
+                    "memberOf".to_string(),
+                    vec!["cn=pharmacists,ou=groups,dc=example,dc=org".to_string()],
+                ),
+            ]),
+        }])
+    }
+
+    async fn unbind(&mut self) -> Result<(), ExternalAuthError> {
+        Ok(())
+    }
+}
+
+#[actix_rt::test]
+async fn ldap_login() {
+    let (_, connection, connection_manager, _) = setup_all(
+        "identity_provider_ldap_login",
+        MockDataInserts::none().names().stores(),
+    )
+    .await;
+    KeyValueStoreRepository::new(&connection)
+        .set_i32(KeyType::SettingsSyncSiteId, Some(mock_store_a().site_id))
+        .unwrap();
+
+    let ldap_settings = LdapSettings {
+        url: "ldap://localhost".to_string(),
+        bind_dn: Some(SERVICE_DN.to_string()),
+        bind_password: Some("service password".to_string()),
+        user_base_dn: "ou=people,dc=example,dc=org".to_string(),
+        user_filter: "(uid={username})".to_string(),
+        username_attribute: "uid".to_string(),
+        email_attribute: "mail".to_string(),
+        first_name_attribute: "givenName".to_string(),
+        last_name_attribute: "sn".to_string(),
+        group_attribute: "memberOf".to_string(),
+    };
+    let directory = StubDirectory {
+        binds: Default::default(),
+        filters: Default::default(),
+    };
+    let authenticator =
+        LdapAuthenticator::with_connector(ldap_settings.clone(), Box::new(directory.clone()));
+
+    // Direct authenticator checks
+    let identity = authenticator
+        .authenticate(Credentials::Password {
+            username: "ldap_user".to_string(),
+            password: "ldap password".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(identity.first_name, Some("Ldap".to_string()));
+    assert_eq!(identity.groups, vec!["pharmacists".to_string()]);
+    assert_eq!(
+        *directory.binds.lock().unwrap(),
+        vec![SERVICE_DN.to_string(), USER_DN.to_string()]
+    );
+    assert_eq!(
+        authenticator
+            .authenticate(Credentials::Password {
+                username: "ldap_user".to_string(),
+                password: "".to_string(),
+            })
+            .await,
+        Err(ExternalAuthError::InvalidCredentials)
+    );
+    assert_eq!(
+        authenticator
+            .authenticate(Credentials::Password {
+                username: "*".to_string(),
+                password: "ldap password".to_string(),
+            })
+            .await,
+        Err(ExternalAuthError::InvalidCredentials)
+    );
+    assert_eq!(
+        directory.filters.lock().unwrap().last(),
+        Some(&"(uid=\\2a)".to_string())
+    );
+
+    // Login through the service
+    let settings = IdentityProviderSettings {
+        oidc: None,
+        ldap: Some(ldap_settings),
+        group_mappings: group_mappings(),
+    };
+    let service_provider = ServiceProvider::new(connection_manager);
+    let auth_data = auth_data();
+    let login = |username: &'static str, password: &'static str| {
+        ExternalLoginService::password_login(
+            &service_provider,
+            &auth_data,
+            &settings,
+            &authenticator,
+            username,
+            password,
+            None,
+            0,
+        )
+    };
+
+    assert_matches!(
+        login("ldap_user", "wrong").await,
+        Err(LoginError::LoginFailure(LoginFailure::InvalidCredentials))
+    );
+    assert_matches!(
+        login("unknown", "ldap password").await,
+        Err(LoginError::LoginFailure(LoginFailure::InvalidCredentials))
+    );
+    login("ldap_user", "ldap password").await.unwrap();
+
+    let user = UserAccountRowRepository::new(&connection)
+        .find_one_by_user_name("ldap_user")
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        store_a_permissions(&connection, &user.id),
+        vec![PermissionType::StockLineQuery, PermissionType::StoreAccess]
+    );
+
+    // TOTP second factor of the provisioned user is checked like for local logins
+    let auth_state_repo = UserAuthStateRowRepository::new(&connection);
+    let auth_state = auth_state_repo
+        .find_one_by_user_id(&user.id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        auth_state.external_id,
+        Some(format!("ldap://localhost|{USER_DN}"))
+    );
+    auth_state_repo
+        .upsert_one(&UserAuthStateRow {
+            totp_secret: Some(TOTP_SECRET_BASE32.to_string()),
+            totp_confirmed_datetime: Some(Utc::now().naive_utc()),
+            ..auth_state
+        })
+        .unwrap();
+    assert_matches!(
+        login("ldap_user", "ldap password").await,
+        Err(LoginError::LoginFailure(LoginFailure::TotpRequired))
+    );
+    let totp_login = |totp_code: String| {
+        let service_provider = &service_provider;
+        let auth_data = &auth_data;
+        let settings = &settings;
+        let authenticator = &authenticator;
+        async move {
+            ExternalLoginService::password_login(
+                service_provider,
+                auth_data,
+                settings,
+                authenticator,
+                "ldap_user",
+                "ldap password",
+                Some(&totp_code),
+                0,
+            )
+            .await
+        }
+    };
+    assert_matches!(
+        totp_login("000000".to_string()).await,
+        Err(LoginError::LoginFailure(LoginFailure::InvalidTotpCode))
+    );
+    totp_login(generate_totp_code(
+        TOTP_SECRET,
+        Utc::now().timestamp() as u64,
+    ))
+    .await
+    .unwrap();
+    auth_state_repo
+        .upsert_one(&UserAuthStateRow {
+            totp_secret: None,
+            totp_confirmed_datetime: None,
+            ..auth_state_repo.find_or_default(&user.id).unwrap()
+        })
+        .unwrap();
+
+    // Users without a mapped group have no access to the site
+    let settings = IdentityProviderSettings {
+        group_mappings: Vec::new(),
+        ..settings.clone()
+    };
+    assert_matches!(
+        ExternalLoginService::password_login(
+            &service_provider,
+            &auth_data,
+            &settings,
+            &authenticator,
+            "ldap_user",
+            "ldap password",
+            None,
+            0,
+        )
+        .await,
+        Err(LoginError::LoginFailure(LoginFailure::NoSiteAccess))
+    );
+    assert!(store_a_permissions(&connection, &user.id).is_empty());
+}
+
+#[actix_rt::test]
+async fn provision_external_user_by_external_id() {
+    let (_, connection, connection_manager, _) = setup_all(
+        "identity_provider_provision_external_user",
+        MockDataInserts::none().names().stores().user_accounts(),
+    )
+    .await;
+    let service_provider = ServiceProvider::new(connection_manager);
+    let ctx = service_provider.basic_context().unwrap();
+    let settings = IdentityProviderSettings {
+        oidc: None,
+        ldap: None,
+        group_mappings: group_mappings(),
+    };
+    let identity = ExternalIdentity {
+        external_id: "https://idp.example.org|user-1".to_string(),
+        username: "external_user".to_string(),
+        groups: vec!["pharmacists".to_string()],
+        ..Default::default()
+    };
+
+    // Accounts that weren't provisioned for the external user are never adopted
+    let local_user = mock_user_account_a();
+    assert_eq!(
+        provision_external_user(
+            &ctx,
+            &settings,
+            ExternalIdentity {
+                username: local_user.username.to_uppercase(),
+                ..identity.clone()
+            }
+        ),
+        Err(ProvisionExternalUserError::UsernameTaken(
+            local_user.username.to_uppercase()
+        ))
+    );
+    assert_eq!(
+        UserAccountRowRepository::new(&connection)
+            .find_one_by_id(&local_user.id)
+            .unwrap(),
+        Some(local_user)
+    );
+
+    let user = provision_external_user(&ctx, &settings, identity.clone()).unwrap();
+    // Same username from another issuer or subject
+    assert_eq!(
+        provision_external_user(
+            &ctx,
+            &settings,
+            ExternalIdentity {
+                external_id: "https://other.example.org|user-1".to_string(),
+                ..identity.clone()
+            }
+        ),
+        Err(ProvisionExternalUserError::UsernameTaken(
+            "external_user".to_string()
+        ))
+    );
+
+    // Renamed at the identity provider, still the same account
+    let renamed = provision_external_user(
+        &ctx,
+        &settings,
+        ExternalIdentity {
+            username: "renamed_user".to_string(),
+            ..identity
+        },
+    )
+    .unwrap();
+    assert_eq!(renamed.id, user.id);
+    assert_eq!(renamed.username, "renamed_user");
+    assert_eq!(renamed.hashed_password, user.hashed_password);
+}
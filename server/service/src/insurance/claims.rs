@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use repository::{
+    insurance::{
+        insurance_claim_line::{InsuranceClaimLineFilter, InsuranceClaimLineRepository},
+        name_insurance_join::{NameInsuranceJoinFilter, NameInsuranceJoinRepository},
+    },
+    DatetimeFilter, EqualFilter, InvoiceFilter, InvoiceRepository, RepositoryError,
+};
+use serde::Serialize;
+use util::to_csv;
+
+use crate::{
+    service_provider::ServiceContext,
+    static_files::{StaticFileCategory, StaticFileService},
+};
+
+use super::{prescription::split_from_claim_lines, split::InsuranceSplitLine};
+
+#[derive(Debug, Clone)]
+pub struct InsuranceClaimsFilter {
+    pub insurance_provider_id: String,
+    pub store_id: Option<String>,
+    pub from_datetime: NaiveDateTime,
+    pub to_datetime: NaiveDateTime,
+}
+
+/// A claim against an insurance provider for a single prescription
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InsuranceClaim {
+    pub invoice_id: String,
+    pub invoice_number: i64,
+    pub store_id: String,
+    pub patient_id: String,
+    pub patient_name: String,
+    pub policy_number: String,
+    pub prescriber_name: Option<String>,
+    pub prescription_datetime: NaiveDateTime,
+    pub total_amount: f64,
+    pub insurer_amount: f64,
+    pub patient_amount: f64,
+    pub lines: Vec<InsuranceSplitLine>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InsuranceClaimExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug)]
+pub enum ExportInsuranceClaimsError {
+    DatabaseError(RepositoryError),
+    FileGenerationError(String),
+}
+
+pub fn get_insurance_claims(
+    ctx: &ServiceContext,
+    filter: InsuranceClaimsFilter,
+) -> Result<Vec<InsuranceClaim>, RepositoryError> {
+    let connection = &ctx.connection;
+
+    let mut claim_line_filter = InsuranceClaimLineFilter::new()
+        .insurance_provider_id(EqualFilter::equal_to(&filter.insurance_provider_id))
+        .datetime(DatetimeFilter::date_range(
+            filter.from_datetime,
+            filter.to_datetime,
+        ));
+    if let Some(store_id) = &filter.store_id {
+        claim_line_filter = claim_line_filter.store_id(EqualFilter::equal_to(store_id));
+    }
+    let claim_lines =
+        InsuranceClaimLineRepository::new(connection).query_by_filter(claim_line_filter)?;
+
+    // Group claim lines per prescription, keeping the order they were returned in
+    let mut invoice_ids: Vec<String> = Vec::new();
+    let mut lines_by_invoice: HashMap<String, Vec<_>> = HashMap::new();
+    for line in claim_lines {
+        if !lines_by_invoice.contains_key(&line.invoice_id) {
+            invoice_ids.push(line.invoice_id.clone());
+        }
+        lines_by_invoice
+            .entry(line.invoice_id.clone())
+            .or_default()
+            .push(line);
+    }
+
+    let invoices: HashMap<String, _> = InvoiceRepository::new(connection)
+        .query_by_filter(InvoiceFilter::new().id(EqualFilter::equal_any(invoice_ids.clone())))?
+        .into_iter()
+        .map(|invoice| (invoice.invoice_row.id.clone(), invoice))
+        .collect();
+
+    let policy_ids: Vec<String> = lines_by_invoice
+        .values()
+        .filter_map(|lines| lines.first())
+        .map(|line| line.name_insurance_join_id.clone())
+        .collect();
+    let policies: HashMap<String, _> = NameInsuranceJoinRepository::new(connection)
+        .query_by_filter(NameInsuranceJoinFilter::new().id(EqualFilter::equal_any(policy_ids)))?
+        .into_iter()
+        .map(|policy| (policy.name_insurance_join_row.id.clone(), policy))
+        .collect();
+
+    let mut claims = Vec::new();
+    for invoice_id in invoice_ids {
+        let (Some(lines), Some(invoice)) = (
+            lines_by_invoice.remove(&invoice_id),
+            invoices.get(&invoice_id),
+        ) else {
+            continue;
+        };
+        let policy_number = lines
+            .first()
+            .and_then(|line| policies.get(&line.name_insurance_join_id))
+            .map(|policy| policy.name_insurance_join_row.policy_number.clone())
+            .unwrap_or_default();
+        let split = split_from_claim_lines(connection, lines)?;
+
+        let invoice_row = &invoice.invoice_row;
+        claims.push(InsuranceClaim {
+            invoice_id,
+            invoice_number: invoice_row.invoice_number,
+            store_id: invoice_row.store_id.clone(),
+            patient_id: invoice.name_row.id.clone(),
+            patient_name: invoice.name_row.name.clone(),
+            policy_number,
+            prescriber_name: invoice.clinician_row.as_ref().map(|clinician| {
+                match &clinician.first_name {
+                    Some(first_name) => format!("{} {}", first_name, clinician.last_name),
+                    None => clinician.last_name.clone(),
+                }
+            }),
+            prescription_datetime: invoice_row
+                .backdated_datetime
+                .unwrap_or(invoice_row.created_datetime),
+            total_amount: split.total_amount,
+            insurer_amount: split.insurer_amount,
+            patient_amount: split.patient_amount,
+            lines: split.lines,
+        });
+    }
+
+    Ok(claims)
+}
+
+/// Exports the provider's claims for the period and returns the file id
+pub fn export_insurance_claims(
+    ctx: &ServiceContext,
+    base_dir: &Option<String>,
+    filter: InsuranceClaimsFilter,
+    format: InsuranceClaimExportFormat,
+) -> Result<String, ExportInsuranceClaimsError> {
+    let claims = get_insurance_claims(ctx, filter)?;
+
+    let (extension, content) = match format {
+        InsuranceClaimExportFormat::Csv => ("csv", claims_to_csv(&claims)),
+        InsuranceClaimExportFormat::Json => (
+            "json",
+            serde_json::to_string_pretty(&claims)
+                .map_err(|err| ExportInsuranceClaimsError::FileGenerationError(err.to_string()))?,
+        ),
+    };
+
+    let file_service = StaticFileService::new(base_dir)
+        .map_err(|err| ExportInsuranceClaimsError::FileGenerationError(format!("{}", err)))?;
+    let now: DateTime<Utc> = Utc::now();
+    let file = file_service
+        .store_file(
+            &format!(
+                "{}_insurance_claims.{}",
+                now.format("%Y%m%d_%H%M%S"),
+                extension
+            ),
+            StaticFileCategory::Temporary,
+            content.as_bytes(),
+        )
+        .map_err(|err| ExportInsuranceClaimsError::FileGenerationError(format!("{}", err)))?;
+    Ok(file.id)
+}
+
+/// One row per claim line, with the prescription details repeated on each row
+fn claims_to_csv(claims: &[InsuranceClaim]) -> String {
+    let headers = [
+        "invoice_number",
+        "prescription_datetime",
+        "patient_id",
+        "patient_name",
+        "policy_number",
+        "prescriber",
+        "item_id",
+        "total_amount",
+        "insurer_amount",
+        "patient_amount",
+    ];
+
+    let rows: Vec<Vec<String>> = claims
+        .iter()
+        .flat_map(|claim| {
+            claim.lines.iter().map(move |line| {
+                vec![
+                    claim.invoice_number.to_string(),
+                    claim.prescription_datetime.to_string(),
+                    claim.patient_id.clone(),
+                    claim.patient_name.clone(),
+                    claim.policy_number.clone(),
+                    claim.prescriber_name.clone().unwrap_or_default(),
+                    line.item_id.clone(),
+                    format!("{:.2}", line.total_amount),
+                    format!("{:.2}", line.insurer_amount),
+                    format!("{:.2}", line.patient_amount),
+                ]
+            })
+        })
+        .collect();
+
+    to_csv(&headers, &rows)
+}
+
+impl From<RepositoryError> for ExportInsuranceClaimsError {
+    fn from(error: RepositoryError) -> Self {
+        ExportInsuranceClaimsError::DatabaseError(error)
+    }
+}
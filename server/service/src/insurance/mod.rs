@@ -0,0 +1,139 @@
+use repository::{
+    insurance::{
+        insurance_coverage_rule::InsuranceCoverageRuleFilter,
+        insurance_coverage_rule_row::InsuranceCoverageRuleRow,
+        insurance_provider::{InsuranceProvider, InsuranceProviderFilter, InsuranceProviderSort},
+        insurance_provider_row::InsuranceProviderRow,
+        name_insurance_join::{NameInsuranceJoin, NameInsuranceJoinFilter},
+    },
+    PaginationOption, RepositoryError,
+};
+
+use crate::{service_provider::ServiceContext, ListError, ListResult};
+
+pub mod claims;
+pub mod prescription;
+pub mod query;
+pub mod split;
+pub mod upsert_coverage_rule;
+pub mod upsert_insurance_provider;
+pub mod upsert_patient_policy;
+
+use self::{
+    claims::{
+        export_insurance_claims, get_insurance_claims, ExportInsuranceClaimsError, InsuranceClaim,
+        InsuranceClaimExportFormat, InsuranceClaimsFilter,
+    },
+    prescription::{
+        apply_prescription_insurance, get_prescription_insurance_split, ApplyPrescriptionInsurance,
+        ApplyPrescriptionInsuranceError,
+    },
+    query::{get_coverage_rules, get_insurance_providers, get_patient_policies},
+    split::InsuranceSplit,
+    upsert_coverage_rule::{
+        delete_coverage_rule, upsert_coverage_rule, DeleteCoverageRuleError, UpsertCoverageRule,
+        UpsertCoverageRuleError,
+    },
+    upsert_insurance_provider::{
+        upsert_insurance_provider, UpsertInsuranceProvider, UpsertInsuranceProviderError,
+    },
+    upsert_patient_policy::{upsert_patient_policy, UpsertPatientPolicy, UpsertPatientPolicyError},
+};
+
+pub trait InsuranceServiceTrait: Sync + Send {
+    fn get_insurance_providers(
+        &self,
+        ctx: &ServiceContext,
+        pagination: Option<PaginationOption>,
+        filter: Option<InsuranceProviderFilter>,
+        sort: Option<InsuranceProviderSort>,
+    ) -> Result<ListResult<InsuranceProvider>, ListError> {
+        get_insurance_providers(ctx, pagination, filter, sort)
+    }
+
+    fn get_coverage_rules(
+        &self,
+        ctx: &ServiceContext,
+        filter: InsuranceCoverageRuleFilter,
+    ) -> Result<Vec<InsuranceCoverageRuleRow>, RepositoryError> {
+        get_coverage_rules(ctx, filter)
+    }
+
+    fn get_patient_policies(
+        &self,
+        ctx: &ServiceContext,
+        pagination: Option<PaginationOption>,
+        filter: Option<NameInsuranceJoinFilter>,
+    ) -> Result<ListResult<NameInsuranceJoin>, ListError> {
+        get_patient_policies(ctx, pagination, filter)
+    }
+
+    fn upsert_insurance_provider(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertInsuranceProvider,
+    ) -> Result<InsuranceProviderRow, UpsertInsuranceProviderError> {
+        upsert_insurance_provider(ctx, input)
+    }
+
+    fn upsert_coverage_rule(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertCoverageRule,
+    ) -> Result<InsuranceCoverageRuleRow, UpsertCoverageRuleError> {
+        upsert_coverage_rule(ctx, input)
+    }
+
+    fn delete_coverage_rule(
+        &self,
+        ctx: &ServiceContext,
+        id: &str,
+    ) -> Result<String, DeleteCoverageRuleError> {
+        delete_coverage_rule(ctx, id)
+    }
+
+    fn upsert_patient_policy(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertPatientPolicy,
+    ) -> Result<NameInsuranceJoin, UpsertPatientPolicyError> {
+        upsert_patient_policy(ctx, input)
+    }
+
+    fn apply_prescription_insurance(
+        &self,
+        ctx: &ServiceContext,
+        input: ApplyPrescriptionInsurance,
+    ) -> Result<InsuranceSplit, ApplyPrescriptionInsuranceError> {
+        apply_prescription_insurance(ctx, input)
+    }
+
+    fn get_prescription_insurance_split(
+        &self,
+        ctx: &ServiceContext,
+        invoice_id: &str,
+    ) -> Result<Option<InsuranceSplit>, RepositoryError> {
+        get_prescription_insurance_split(ctx, invoice_id)
+    }
+
+    fn get_insurance_claims(
+        &self,
+        ctx: &ServiceContext,
+        filter: InsuranceClaimsFilter,
+    ) -> Result<Vec<InsuranceClaim>, RepositoryError> {
+        get_insurance_claims(ctx, filter)
+    }
+
+    fn export_insurance_claims(
+        &self,
+        ctx: &ServiceContext,
+        base_dir: &Option<String>,
+        filter: InsuranceClaimsFilter,
+        format: InsuranceClaimExportFormat,
+    ) -> Result<String, ExportInsuranceClaimsError> {
+        export_insurance_claims(ctx, base_dir, filter, format)
+    }
+}
+
+pub struct InsuranceService {}
+impl InsuranceServiceTrait for InsuranceService {}
@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+use repository::{
+    insurance::{
+        insurance_claim_line_row::{InsuranceClaimLineRow, InsuranceClaimLineRowRepository},
+        insurance_coverage_rule::{InsuranceCoverageRuleFilter, InsuranceCoverageRuleRepository},
+        name_insurance_join::{
+            NameInsuranceJoin, NameInsuranceJoinFilter, NameInsuranceJoinRepository,
+        },
+    },
+    EqualFilter, Invoice, InvoiceFilter, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineType,
+    InvoiceRepository, InvoiceType, ItemLinkRowRepository, RepositoryError, StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::{
+    invoice::validate::{check_invoice_is_editable, check_invoice_type, check_store},
+    pricing::item_price::{get_pricing_for_item, ItemPriceLookup},
+    service_provider::ServiceContext,
+};
+
+use super::split::{
+    calculate_insurance_split, InsuranceSplit, InsuranceSplitLine, InsuranceSplitLineInput,
+};
+
+#[derive(PartialEq, Debug)]
+pub enum ApplyPrescriptionInsuranceError {
+    InvoiceDoesNotExist,
+    NotAPrescription,
+    NotThisStoreInvoice,
+    CannotEditFinalised,
+    PolicyDoesNotExist,
+    PolicyDoesNotBelongToPatient,
+    PolicyIsNotActive,
+    PolicyHasExpired,
+    InsuranceProviderIsNotActive,
+    PrescriptionValidityExceeded,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct ApplyPrescriptionInsurance {
+    pub invoice_id: String,
+    pub name_insurance_join_id: String,
+}
+
+/// Splits a prescription between the patient's insurer and the patient, replacing any
+/// previously calculated claim lines for the prescription
+pub fn apply_prescription_insurance(
+    ctx: &ServiceContext,
+    input: ApplyPrescriptionInsurance,
+) -> Result<InsuranceSplit, ApplyPrescriptionInsuranceError> {
+    let split = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let (invoice, policy) = validate(connection, &ctx.store_id, &input)?;
+            let split = generate(ctx, connection, &invoice, &policy)?;
+
+            let repository = InsuranceClaimLineRowRepository::new(connection);
+            for existing in repository.find_many_by_invoice_id(&input.invoice_id)? {
+                repository.delete(&existing.id)?;
+            }
+
+            let datetime = Utc::now().naive_utc();
+            let item_link_ids = invoice_line_item_link_ids(connection, &input.invoice_id)?;
+            for line in split.lines.iter() {
+                repository.upsert_one(&InsuranceClaimLineRow {
+                    id: uuid(),
+                    invoice_id: invoice.invoice_row.id.clone(),
+                    invoice_line_id: line.invoice_line_id.clone(),
+                    name_insurance_join_id: policy.name_insurance_join_row.id.clone(),
+                    insurance_provider_id: policy.insurance_provider_row.id.clone(),
+                    store_id: invoice.invoice_row.store_id.clone(),
+                    item_link_id: item_link_ids
+                        .get(&line.invoice_line_id)
+                        .cloned()
+                        .unwrap_or_else(|| line.item_id.clone()),
+                    total_amount: line.total_amount,
+                    insurer_amount: line.insurer_amount,
+                    patient_amount: line.patient_amount,
+                    coverage_rule_id: line.coverage_rule_id.clone(),
+                    datetime,
+                })?;
+            }
+
+            Ok(split)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(split)
+}
+
+/// Returns the stored insurer/patient split for a prescription, if insurance has been applied
+pub fn get_prescription_insurance_split(
+    ctx: &ServiceContext,
+    invoice_id: &str,
+) -> Result<Option<InsuranceSplit>, RepositoryError> {
+    let claim_lines = InsuranceClaimLineRowRepository::new(&ctx.connection)
+        .find_many_by_invoice_id(invoice_id)?;
+    if claim_lines.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(split_from_claim_lines(&ctx.connection, claim_lines)?))
+}
+
+pub(crate) fn split_from_claim_lines(
+    connection: &StorageConnection,
+    claim_lines: Vec<InsuranceClaimLineRow>,
+) -> Result<InsuranceSplit, RepositoryError> {
+    let item_link_ids: Vec<String> = claim_lines
+        .iter()
+        .map(|line| line.item_link_id.clone())
+        .collect();
+    let item_ids: HashMap<String, String> = ItemLinkRowRepository::new(connection)
+        .find_many_by_id(&item_link_ids)?
+        .into_iter()
+        .map(|link| (link.id, link.item_id))
+        .collect();
+
+    let lines: Vec<InsuranceSplitLine> = claim_lines
+        .into_iter()
+        .map(|line| InsuranceSplitLine {
+            item_id: item_ids
+                .get(&line.item_link_id)
+                .cloned()
+                .unwrap_or(line.item_link_id),
+            invoice_line_id: line.invoice_line_id,
+            total_amount: line.total_amount,
+            insurer_amount: line.insurer_amount,
+            patient_amount: line.patient_amount,
+            coverage_rule_id: line.coverage_rule_id,
+        })
+        .collect();
+
+    Ok(InsuranceSplit {
+        total_amount: lines.iter().map(|l| l.total_amount).sum(),
+        insurer_amount: lines.iter().map(|l| l.insurer_amount).sum(),
+        patient_amount: lines.iter().map(|l| l.patient_amount).sum(),
+        lines,
+    })
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &ApplyPrescriptionInsurance,
+) -> Result<(Invoice, NameInsuranceJoin), ApplyPrescriptionInsuranceError> {
+    use ApplyPrescriptionInsuranceError::*;
+
+    let invoice = InvoiceRepository::new(connection)
+        .query_one(InvoiceFilter::new().id(EqualFilter::equal_to(&input.invoice_id)))?
+        .ok_or(InvoiceDoesNotExist)?;
+    let invoice_row = &invoice.invoice_row;
+
+    if !check_invoice_type(invoice_row, InvoiceType::Prescription) {
+        return Err(NotAPrescription);
+    }
+    if !check_store(invoice_row, store_id) {
+        return Err(NotThisStoreInvoice);
+    }
+    if !check_invoice_is_editable(invoice_row) {
+        return Err(CannotEditFinalised);
+    }
+
+    let repository = NameInsuranceJoinRepository::new(connection);
+    let policy_filter =
+        NameInsuranceJoinFilter::new().id(EqualFilter::equal_to(&input.name_insurance_join_id));
+    if repository.count(Some(policy_filter.clone()))? == 0 {
+        return Err(PolicyDoesNotExist);
+    }
+    let policy = repository
+        .query_one(policy_filter.name_id(EqualFilter::equal_to(&invoice.name_row.id)))?
+        .ok_or(PolicyDoesNotBelongToPatient)?;
+
+    if !policy.name_insurance_join_row.is_active {
+        return Err(PolicyIsNotActive);
+    }
+    if !policy.insurance_provider_row.is_active {
+        return Err(InsuranceProviderIsNotActive);
+    }
+
+    let prescription_datetime = invoice_row
+        .backdated_datetime
+        .unwrap_or(invoice_row.created_datetime);
+    if policy.name_insurance_join_row.expiry_date < prescription_datetime.date() {
+        return Err(PolicyHasExpired);
+    }
+
+    if let Some(validity_days) = policy.insurance_provider_row.prescription_validity_days {
+        let valid_until = prescription_datetime + Duration::days(validity_days as i64);
+        if valid_until < Utc::now().naive_utc() {
+            return Err(PrescriptionValidityExceeded);
+        }
+    }
+
+    Ok((invoice, policy))
+}
+
+fn generate(
+    ctx: &ServiceContext,
+    connection: &StorageConnection,
+    invoice: &Invoice,
+    policy: &NameInsuranceJoin,
+) -> Result<InsuranceSplit, RepositoryError> {
+    let invoice_lines = InvoiceLineRepository::new(connection).query_by_filter(
+        InvoiceLineFilter::new()
+            .invoice_id(EqualFilter::equal_to(&invoice.invoice_row.id))
+            .r#type(InvoiceLineType::UnallocatedStock.not_equal_to()),
+    )?;
+
+    let mut lines = Vec::new();
+    for invoice_line in invoice_lines {
+        let row = invoice_line.invoice_line_row;
+        let number_of_units = row.number_of_packs * row.pack_size;
+
+        // Lines without a price fall back to the default price list for the patient
+        let total_amount = if row.total_after_tax > 0.0 {
+            row.total_after_tax
+        } else {
+            let pricing = get_pricing_for_item(
+                ctx,
+                ItemPriceLookup {
+                    item_id: invoice_line.item_row.id.clone(),
+                    customer_name_id: Some(invoice.name_row.id.clone()),
+                },
+            )?;
+            pricing.calculated_price_per_unit.unwrap_or_default() * number_of_units
+        };
+
+        lines.push(InsuranceSplitLineInput {
+            invoice_line_id: row.id,
+            item_id: invoice_line.item_row.id,
+            number_of_units,
+            total_amount,
+        });
+    }
+
+    let rules = InsuranceCoverageRuleRepository::new(connection).query_by_filter(
+        InsuranceCoverageRuleFilter::new()
+            .insurance_provider_id(EqualFilter::equal_to(&policy.insurance_provider_row.id)),
+    )?;
+    let rule_item_link_ids: Vec<String> = rules
+        .iter()
+        .filter_map(|rule| rule.item_link_id.clone())
+        .collect();
+    let item_ids: HashMap<String, String> = ItemLinkRowRepository::new(connection)
+        .find_many_by_id(&rule_item_link_ids)?
+        .into_iter()
+        .map(|link| (link.id, link.item_id))
+        .collect();
+    let rules: Vec<_> = rules
+        .into_iter()
+        .map(|rule| {
+            let item_id = rule.item_link_id.as_ref().map(|link_id| {
+                item_ids
+                    .get(link_id)
+                    .cloned()
+                    .unwrap_or_else(|| link_id.clone())
+            });
+            (item_id, rule)
+        })
+        .collect();
+
+    Ok(calculate_insurance_split(
+        &lines,
+        policy.name_insurance_join_row.coverage_percentage,
+        &rules,
+    ))
+}
+
+fn invoice_line_item_link_ids(
+    connection: &StorageConnection,
+    invoice_id: &str,
+) -> Result<HashMap<String, String>, RepositoryError> {
+    Ok(InvoiceLineRepository::new(connection)
+        .query_by_filter(InvoiceLineFilter::new().invoice_id(EqualFilter::equal_to(invoice_id)))?
+        .into_iter()
+        .map(|line| (line.invoice_line_row.id, line.invoice_line_row.item_link_id))
+        .collect())
+}
+
+impl From<RepositoryError> for ApplyPrescriptionInsuranceError {
+    fn from(error: RepositoryError) -> Self {
+        ApplyPrescriptionInsuranceError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use chrono::NaiveDate;
+    use repository::{
+        insurance::{
+            insurance_claim_line_row::InsuranceClaimLineRowRepository,
+            name_insurance_join_row::InsurancePolicyType,
+        },
+        mock::{
+            mock_patient, mock_patient_b, mock_prescription_a, mock_prescription_verified,
+            mock_store_a, mock_user_account_a, MockDataInserts,
+        },
+        test_db::setup_all,
+        ChangelogFilter, ChangelogRepository, ChangelogTableName,
+    };
+
+    use crate::{
+        insurance::{
+            upsert_coverage_rule::UpsertCoverageRule,
+            upsert_insurance_provider::UpsertInsuranceProvider,
+            upsert_patient_policy::UpsertPatientPolicy,
+        },
+        service_provider::ServiceProvider,
+        sync::{
+            api::SyncAction,
+            translations::{
+                translate_changelogs_to_sync_records, PushSyncRecord, ToSyncRecordTranslationType,
+            },
+        },
+    };
+
+    use super::{ApplyPrescriptionInsurance, ApplyPrescriptionInsuranceError};
+
+    type ServiceError = ApplyPrescriptionInsuranceError;
+
+    #[actix_rt::test]
+    async fn apply_prescription_insurance() {
+        let (_, connection, connection_manager, _) =
+            setup_all("apply_prescription_insurance", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager);
+        let context = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = &service_provider.insurance_service;
+
+        service
+            .upsert_insurance_provider(
+                &context,
+                UpsertInsuranceProvider {
+                    id: "provider".to_string(),
+                    provider_name: "Provider".to_string(),
+                    is_active: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        service
+            .upsert_coverage_rule(
+                &context,
+                UpsertCoverageRule {
+                    id: "exclude_item_b".to_string(),
+                    insurance_provider_id: "provider".to_string(),
+                    item_id: Some("item_b".to_string()),
+                    is_excluded: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let policy = |id: &str, patient_id: &str, is_active: bool| UpsertPatientPolicy {
+            id: id.to_string(),
+            patient_id: patient_id.to_string(),
+            insurance_provider_id: "provider".to_string(),
+            policy_number: id.to_string(),
+            policy_type: InsurancePolicyType::Personal,
+            coverage_percentage: 80.0,
+            expiry_date: NaiveDate::from_ymd_opt(2100, 1, 1).unwrap(),
+            is_active,
+        };
+        service
+            .upsert_patient_policy(&context, policy("policy", &mock_patient().id, true))
+            .unwrap();
+        service
+            .upsert_patient_policy(&context, policy("inactive", &mock_patient().id, false))
+            .unwrap();
+        service
+            .upsert_patient_policy(
+                &context,
+                policy("other_patient", &mock_patient_b().id, true),
+            )
+            .unwrap();
+
+        let input = |invoice_id: &str, policy_id: &str| ApplyPrescriptionInsurance {
+            invoice_id: invoice_id.to_string(),
+            name_insurance_join_id: policy_id.to_string(),
+        };
+
+        // Errors
+        assert_eq!(
+            service.apply_prescription_insurance(&context, input("invalid", "policy")),
+            Err(ServiceError::InvoiceDoesNotExist)
+        );
+        assert_eq!(
+            service.apply_prescription_insurance(
+                &context,
+                input(&mock_prescription_verified().id, "policy")
+            ),
+            Err(ServiceError::CannotEditFinalised)
+        );
+        assert_eq!(
+            service.apply_prescription_insurance(
+                &context,
+                input(&mock_prescription_a().id, "other_patient")
+            ),
+            Err(ServiceError::PolicyDoesNotBelongToPatient)
+        );
+        assert_eq!(
+            service.apply_prescription_insurance(
+                &context,
+                input(&mock_prescription_a().id, "inactive")
+            ),
+            Err(ServiceError::PolicyIsNotActive)
+        );
+
+        // Success, item_a line (10.0) at policy percentage, item_b line (50.0) excluded
+        let split = service
+            .apply_prescription_insurance(&context, input(&mock_prescription_a().id, "policy"))
+            .unwrap();
+        assert_eq!(split.total_amount, 60.0);
+        assert_eq!(split.insurer_amount, 8.0);
+        assert_eq!(split.patient_amount, 52.0);
+
+        // Re-applying replaces existing claim lines
+        service
+            .apply_prescription_insurance(&context, input(&mock_prescription_a().id, "policy"))
+            .unwrap();
+        let claim_lines = InsuranceClaimLineRowRepository::new(&connection)
+            .find_many_by_invoice_id(&mock_prescription_a().id)
+            .unwrap();
+        assert_eq!(claim_lines.len(), 2);
+
+        // Replaced claim lines are deleted on central too
+        let changelogs = ChangelogRepository::new(&connection)
+            .changelogs(
+                0,
+                1_000_000,
+                Some(
+                    ChangelogFilter::new()
+                        .table_name(ChangelogTableName::InsuranceClaimLine.equal_to()),
+                ),
+            )
+            .unwrap();
+        let mut pushed_ids = HashSet::new();
+        for PushSyncRecord { record, .. } in translate_changelogs_to_sync_records(
+            &connection,
+            changelogs,
+            ToSyncRecordTranslationType::PushToOmSupplyCentral,
+        )
+        .unwrap()
+        {
+            assert_eq!(record.table_name, "insurance_claim_line");
+            match record.action {
+                SyncAction::Delete => pushed_ids.remove(&record.record_id),
+                _ => pushed_ids.insert(record.record_id),
+            };
+        }
+        assert_eq!(
+            pushed_ids,
+            claim_lines.iter().map(|line| line.id.clone()).collect()
+        );
+
+        let stored_split = service
+            .get_prescription_insurance_split(&context, &mock_prescription_a().id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored_split.insurer_amount, split.insurer_amount);
+        assert_eq!(stored_split.patient_amount, split.patient_amount);
+    }
+}
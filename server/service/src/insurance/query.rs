@@ -0,0 +1,57 @@
+use repository::{
+    insurance::{
+        insurance_coverage_rule::{InsuranceCoverageRuleFilter, InsuranceCoverageRuleRepository},
+        insurance_coverage_rule_row::InsuranceCoverageRuleRow,
+        insurance_provider::{
+            InsuranceProvider, InsuranceProviderFilter, InsuranceProviderRepository,
+            InsuranceProviderSort,
+        },
+        name_insurance_join::{
+            NameInsuranceJoin, NameInsuranceJoinFilter, NameInsuranceJoinRepository,
+        },
+    },
+    PaginationOption, RepositoryError,
+};
+
+use crate::{
+    get_default_pagination, i64_to_u32, service_provider::ServiceContext, ListError, ListResult,
+};
+
+pub const MAX_LIMIT: u32 = 1000;
+pub const MIN_LIMIT: u32 = 1;
+
+pub fn get_insurance_providers(
+    ctx: &ServiceContext,
+    pagination: Option<PaginationOption>,
+    filter: Option<InsuranceProviderFilter>,
+    sort: Option<InsuranceProviderSort>,
+) -> Result<ListResult<InsuranceProvider>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let repository = InsuranceProviderRepository::new(&ctx.connection);
+
+    Ok(ListResult {
+        rows: repository.query(pagination, filter.clone(), sort)?,
+        count: i64_to_u32(repository.count(filter)?),
+    })
+}
+
+pub fn get_coverage_rules(
+    ctx: &ServiceContext,
+    filter: InsuranceCoverageRuleFilter,
+) -> Result<Vec<InsuranceCoverageRuleRow>, RepositoryError> {
+    InsuranceCoverageRuleRepository::new(&ctx.connection).query_by_filter(filter)
+}
+
+pub fn get_patient_policies(
+    ctx: &ServiceContext,
+    pagination: Option<PaginationOption>,
+    filter: Option<NameInsuranceJoinFilter>,
+) -> Result<ListResult<NameInsuranceJoin>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let repository = NameInsuranceJoinRepository::new(&ctx.connection);
+
+    Ok(ListResult {
+        rows: repository.query(pagination, filter.clone())?,
+        count: i64_to_u32(repository.count(filter)?),
+    })
+}
@@ -0,0 +1,197 @@
+use repository::insurance::insurance_coverage_rule_row::InsuranceCoverageRuleRow;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InsuranceSplitLineInput {
+    pub invoice_line_id: String,
+    pub item_id: String,
+    pub number_of_units: f64,
+    pub total_amount: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct InsuranceSplitLine {
+    pub invoice_line_id: String,
+    pub item_id: String,
+    pub total_amount: f64,
+    pub insurer_amount: f64,
+    pub patient_amount: f64,
+    pub coverage_rule_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct InsuranceSplit {
+    pub lines: Vec<InsuranceSplitLine>,
+    pub total_amount: f64,
+    pub insurer_amount: f64,
+    pub patient_amount: f64,
+}
+
+fn round_currency(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// Splits a line between insurer and patient.
+///
+/// The coverage rule (item specific, or the provider default) takes precedence over the policy
+/// coverage percentage. Excluded items are paid in full by the patient, and the insurer portion
+/// is capped at `max_coverage_per_unit` for each unit dispensed.
+pub fn split_line(
+    line: &InsuranceSplitLineInput,
+    policy_coverage_percentage: f64,
+    rule: Option<&InsuranceCoverageRuleRow>,
+) -> InsuranceSplitLine {
+    let total_amount = round_currency(line.total_amount);
+
+    let insurer_amount = match rule {
+        Some(rule) if rule.is_excluded => 0.0,
+        _ => {
+            let percentage = rule
+                .and_then(|rule| rule.coverage_percentage)
+                .unwrap_or(policy_coverage_percentage)
+                .clamp(0.0, 100.0);
+            let insurer_amount = total_amount * percentage / 100.0;
+
+            match rule.and_then(|rule| rule.max_coverage_per_unit) {
+                Some(max_per_unit) => insurer_amount.min(max_per_unit * line.number_of_units),
+                None => insurer_amount,
+            }
+        }
+    };
+    let insurer_amount = round_currency(insurer_amount.max(0.0));
+
+    InsuranceSplitLine {
+        invoice_line_id: line.invoice_line_id.clone(),
+        item_id: line.item_id.clone(),
+        total_amount,
+        insurer_amount,
+        patient_amount: round_currency(total_amount - insurer_amount),
+        coverage_rule_id: rule.map(|rule| rule.id.clone()),
+    }
+}
+
+/// `rules` are the provider's active coverage rules, keyed by item id (`None` for the default rule)
+pub fn calculate_insurance_split(
+    lines: &[InsuranceSplitLineInput],
+    policy_coverage_percentage: f64,
+    rules: &[(Option<String>, InsuranceCoverageRuleRow)],
+) -> InsuranceSplit {
+    let default_rule = rules
+        .iter()
+        .find(|(item_id, _)| item_id.is_none())
+        .map(|(_, rule)| rule);
+
+    let lines: Vec<InsuranceSplitLine> = lines
+        .iter()
+        .map(|line| {
+            let rule = rules
+                .iter()
+                .find(|(item_id, _)| item_id.as_deref() == Some(line.item_id.as_str()))
+                .map(|(_, rule)| rule)
+                .or(default_rule);
+            split_line(line, policy_coverage_percentage, rule)
+        })
+        .collect();
+
+    InsuranceSplit {
+        total_amount: round_currency(lines.iter().map(|l| l.total_amount).sum()),
+        insurer_amount: round_currency(lines.iter().map(|l| l.insurer_amount).sum()),
+        patient_amount: round_currency(lines.iter().map(|l| l.patient_amount).sum()),
+        lines,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::insurance::insurance_coverage_rule_row::InsuranceCoverageRuleRow;
+
+    use super::{calculate_insurance_split, InsuranceSplitLineInput};
+
+    fn line(
+        id: &str,
+        item_id: &str,
+        number_of_units: f64,
+        total_amount: f64,
+    ) -> InsuranceSplitLineInput {
+        InsuranceSplitLineInput {
+            invoice_line_id: id.to_string(),
+            item_id: item_id.to_string(),
+            number_of_units,
+            total_amount,
+        }
+    }
+
+    #[test]
+    fn insurance_split() {
+        let lines = vec![
+            line("line_a", "item_a", 10.0, 100.0),
+            line("line_b", "item_b", 10.0, 100.0),
+            line("line_c", "item_c", 10.0, 100.0),
+            line("line_d", "item_d", 10.0, 100.0),
+        ];
+
+        // No rules, policy percentage applies
+        let result = calculate_insurance_split(&lines, 80.0, &[]);
+        assert_eq!(result.total_amount, 400.0);
+        assert_eq!(result.insurer_amount, 320.0);
+        assert_eq!(result.patient_amount, 80.0);
+
+        let rules = vec![
+            (
+                None,
+                InsuranceCoverageRuleRow {
+                    id: "default".to_string(),
+                    coverage_percentage: Some(50.0),
+                    ..Default::default()
+                },
+            ),
+            (
+                Some("item_a".to_string()),
+                InsuranceCoverageRuleRow {
+                    id: "excluded".to_string(),
+                    is_excluded: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                Some("item_b".to_string()),
+                InsuranceCoverageRuleRow {
+                    id: "capped".to_string(),
+                    coverage_percentage: Some(100.0),
+                    max_coverage_per_unit: Some(2.5),
+                    ..Default::default()
+                },
+            ),
+            (
+                Some("item_c".to_string()),
+                InsuranceCoverageRuleRow {
+                    id: "policy_percentage".to_string(),
+                    ..Default::default()
+                },
+            ),
+        ];
+        let result = calculate_insurance_split(&lines, 80.0, &rules);
+
+        // Excluded item, patient pays everything
+        assert_eq!(result.lines[0].insurer_amount, 0.0);
+        assert_eq!(result.lines[0].patient_amount, 100.0);
+        assert_eq!(
+            result.lines[0].coverage_rule_id,
+            Some("excluded".to_string())
+        );
+        // Capped at 2.5 per unit
+        assert_eq!(result.lines[1].insurer_amount, 25.0);
+        assert_eq!(result.lines[1].patient_amount, 75.0);
+        // Item rule without percentage falls back to policy percentage
+        assert_eq!(result.lines[2].insurer_amount, 80.0);
+        // No item rule, provider default applies
+        assert_eq!(result.lines[3].insurer_amount, 50.0);
+        assert_eq!(
+            result.lines[3].coverage_rule_id,
+            Some("default".to_string())
+        );
+
+        assert_eq!(result.insurer_amount, 155.0);
+        assert_eq!(result.patient_amount, 245.0);
+    }
+}
@@ -0,0 +1,155 @@
+use repository::{
+    insurance::{
+        insurance_coverage_rule::{InsuranceCoverageRuleFilter, InsuranceCoverageRuleRepository},
+        insurance_coverage_rule_row::{
+            InsuranceCoverageRuleRow, InsuranceCoverageRuleRowRepository,
+        },
+    },
+    EqualFilter, ItemRowRepository, RepositoryError, StorageConnection,
+};
+
+use crate::service_provider::ServiceContext;
+
+use super::upsert_insurance_provider::check_insurance_provider_exists;
+
+#[derive(PartialEq, Debug)]
+pub enum UpsertCoverageRuleError {
+    InsuranceProviderDoesNotExist,
+    ItemDoesNotExist,
+    CoveragePercentageOutOfRange,
+    MaxCoveragePerUnitCannotBeNegative,
+    RuleAlreadyExistsForItem,
+    UpsertedRecordNotFound,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(PartialEq, Debug)]
+pub enum DeleteCoverageRuleError {
+    CoverageRuleDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+/// A rule without an `item_id` is the provider default, applied to any item without its own rule
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct UpsertCoverageRule {
+    pub id: String,
+    pub insurance_provider_id: String,
+    pub item_id: Option<String>,
+    pub coverage_percentage: Option<f64>,
+    pub max_coverage_per_unit: Option<f64>,
+    pub is_excluded: bool,
+}
+
+pub fn upsert_coverage_rule(
+    ctx: &ServiceContext,
+    input: UpsertCoverageRule,
+) -> Result<InsuranceCoverageRuleRow, UpsertCoverageRuleError> {
+    let rule = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let item_link_id = validate(connection, &input)?;
+            let row = generate(input, item_link_id);
+            let repository = InsuranceCoverageRuleRowRepository::new(connection);
+            repository.upsert_one(&row)?;
+
+            repository
+                .find_one_by_id(&row.id)?
+                .ok_or(UpsertCoverageRuleError::UpsertedRecordNotFound)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(rule)
+}
+
+pub fn delete_coverage_rule(
+    ctx: &ServiceContext,
+    id: &str,
+) -> Result<String, DeleteCoverageRuleError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let repository = InsuranceCoverageRuleRowRepository::new(connection);
+            match repository.find_one_by_id(id)? {
+                Some(rule) if rule.deleted_datetime.is_none() => {}
+                _ => return Err(DeleteCoverageRuleError::CoverageRuleDoesNotExist),
+            }
+            repository.mark_deleted(id)?;
+            Ok(id.to_string())
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+fn validate(
+    connection: &StorageConnection,
+    input: &UpsertCoverageRule,
+) -> Result<Option<String>, UpsertCoverageRuleError> {
+    if check_insurance_provider_exists(connection, &input.insurance_provider_id)?.is_none() {
+        return Err(UpsertCoverageRuleError::InsuranceProviderDoesNotExist);
+    }
+
+    if let Some(percentage) = input.coverage_percentage {
+        if !(0.0..=100.0).contains(&percentage) {
+            return Err(UpsertCoverageRuleError::CoveragePercentageOutOfRange);
+        }
+    }
+
+    if matches!(input.max_coverage_per_unit, Some(max) if max < 0.0) {
+        return Err(UpsertCoverageRuleError::MaxCoveragePerUnitCannotBeNegative);
+    }
+
+    let item_link_id = match &input.item_id {
+        Some(item_id) => {
+            let item = ItemRowRepository::new(connection)
+                .find_active_by_id(item_id)?
+                .ok_or(UpsertCoverageRuleError::ItemDoesNotExist)?;
+            Some(item.id)
+        }
+        None => None,
+    };
+
+    // Only one active rule per provider and item (or one default rule per provider)
+    let mut filter = InsuranceCoverageRuleFilter::new()
+        .insurance_provider_id(EqualFilter::equal_to(&input.insurance_provider_id))
+        .id(EqualFilter::not_equal_to(&input.id));
+    filter = match &input.item_id {
+        Some(item_id) => filter.item_id(EqualFilter::equal_to(item_id)),
+        None => filter.is_default(true),
+    };
+    if InsuranceCoverageRuleRepository::new(connection).count(Some(filter))? > 0 {
+        return Err(UpsertCoverageRuleError::RuleAlreadyExistsForItem);
+    }
+
+    Ok(item_link_id)
+}
+
+fn generate(
+    UpsertCoverageRule {
+        id,
+        insurance_provider_id,
+        item_id: _,
+        coverage_percentage,
+        max_coverage_per_unit,
+        is_excluded,
+    }: UpsertCoverageRule,
+    item_link_id: Option<String>,
+) -> InsuranceCoverageRuleRow {
+    InsuranceCoverageRuleRow {
+        id,
+        insurance_provider_id,
+        item_link_id,
+        coverage_percentage,
+        max_coverage_per_unit,
+        is_excluded,
+        deleted_datetime: None,
+    }
+}
+
+impl From<RepositoryError> for UpsertCoverageRuleError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertCoverageRuleError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for DeleteCoverageRuleError {
+    fn from(error: RepositoryError) -> Self {
+        DeleteCoverageRuleError::DatabaseError(error)
+    }
+}
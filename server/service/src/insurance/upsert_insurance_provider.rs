@@ -0,0 +1,86 @@
+use repository::{
+    insurance::insurance_provider_row::{InsuranceProviderRow, InsuranceProviderRowRepository},
+    RepositoryError, StorageConnection,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(PartialEq, Debug)]
+pub enum UpsertInsuranceProviderError {
+    ProviderNameCannotBeEmpty,
+    InvalidPrescriptionValidityDays,
+    UpsertedRecordNotFound,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct UpsertInsuranceProvider {
+    pub id: String,
+    pub provider_name: String,
+    pub is_active: bool,
+    pub comment: Option<String>,
+    pub prescription_validity_days: Option<i32>,
+}
+
+pub fn upsert_insurance_provider(
+    ctx: &ServiceContext,
+    input: UpsertInsuranceProvider,
+) -> Result<InsuranceProviderRow, UpsertInsuranceProviderError> {
+    let provider = ctx
+        .connection
+        .transaction_sync(|connection| {
+            validate(&input)?;
+            let row = generate(input);
+            let repository = InsuranceProviderRowRepository::new(connection);
+            repository.upsert_one(&row)?;
+
+            repository
+                .find_one_by_id(&row.id)?
+                .ok_or(UpsertInsuranceProviderError::UpsertedRecordNotFound)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(provider)
+}
+
+fn validate(input: &UpsertInsuranceProvider) -> Result<(), UpsertInsuranceProviderError> {
+    if input.provider_name.trim().is_empty() {
+        return Err(UpsertInsuranceProviderError::ProviderNameCannotBeEmpty);
+    }
+
+    if matches!(input.prescription_validity_days, Some(days) if days < 0) {
+        return Err(UpsertInsuranceProviderError::InvalidPrescriptionValidityDays);
+    }
+
+    Ok(())
+}
+
+fn generate(
+    UpsertInsuranceProvider {
+        id,
+        provider_name,
+        is_active,
+        comment,
+        prescription_validity_days,
+    }: UpsertInsuranceProvider,
+) -> InsuranceProviderRow {
+    InsuranceProviderRow {
+        id,
+        provider_name: provider_name.trim().to_string(),
+        is_active,
+        comment,
+        prescription_validity_days,
+    }
+}
+
+pub(crate) fn check_insurance_provider_exists(
+    connection: &StorageConnection,
+    id: &str,
+) -> Result<Option<InsuranceProviderRow>, RepositoryError> {
+    InsuranceProviderRowRepository::new(connection).find_one_by_id(id)
+}
+
+impl From<RepositoryError> for UpsertInsuranceProviderError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertInsuranceProviderError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,125 @@
+use chrono::NaiveDate;
+use repository::{
+    insurance::{
+        name_insurance_join::{
+            NameInsuranceJoin, NameInsuranceJoinFilter, NameInsuranceJoinRepository,
+        },
+        name_insurance_join_row::{
+            InsurancePolicyType, NameInsuranceJoinRow, NameInsuranceJoinRowRepository,
+        },
+    },
+    EqualFilter, RepositoryError, StorageConnection,
+};
+
+use crate::{service_provider::ServiceContext, validate::check_patient_exists};
+
+use super::upsert_insurance_provider::check_insurance_provider_exists;
+
+#[derive(PartialEq, Debug)]
+pub enum UpsertPatientPolicyError {
+    PatientDoesNotExist,
+    InsuranceProviderDoesNotExist,
+    PolicyNumberCannotBeEmpty,
+    CoveragePercentageOutOfRange,
+    PolicyBelongsToAnotherPatient,
+    UpsertedRecordNotFound,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct UpsertPatientPolicy {
+    pub id: String,
+    pub patient_id: String,
+    pub insurance_provider_id: String,
+    pub policy_number: String,
+    pub policy_type: InsurancePolicyType,
+    pub coverage_percentage: f64,
+    pub expiry_date: NaiveDate,
+    pub is_active: bool,
+}
+
+pub fn upsert_patient_policy(
+    ctx: &ServiceContext,
+    input: UpsertPatientPolicy,
+) -> Result<NameInsuranceJoin, UpsertPatientPolicyError> {
+    let policy = ctx
+        .connection
+        .transaction_sync(|connection| {
+            validate(connection, &input)?;
+            let row = generate(ctx, input);
+            NameInsuranceJoinRowRepository::new(connection).upsert_one(&row)?;
+
+            NameInsuranceJoinRepository::new(connection)
+                .query_one(NameInsuranceJoinFilter::new().id(EqualFilter::equal_to(&row.id)))?
+                .ok_or(UpsertPatientPolicyError::UpsertedRecordNotFound)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(policy)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    input: &UpsertPatientPolicy,
+) -> Result<(), UpsertPatientPolicyError> {
+    if check_patient_exists(connection, &input.patient_id)?.is_none() {
+        return Err(UpsertPatientPolicyError::PatientDoesNotExist);
+    }
+
+    if check_insurance_provider_exists(connection, &input.insurance_provider_id)?.is_none() {
+        return Err(UpsertPatientPolicyError::InsuranceProviderDoesNotExist);
+    }
+
+    if input.policy_number.trim().is_empty() {
+        return Err(UpsertPatientPolicyError::PolicyNumberCannotBeEmpty);
+    }
+
+    if !(0.0..=100.0).contains(&input.coverage_percentage) {
+        return Err(UpsertPatientPolicyError::CoveragePercentageOutOfRange);
+    }
+
+    // An existing policy can't be moved to a different patient
+    let repository = NameInsuranceJoinRepository::new(connection);
+    let id_filter = NameInsuranceJoinFilter::new().id(EqualFilter::equal_to(&input.id));
+    if repository.count(Some(id_filter.clone()))? > 0
+        && repository.count(Some(
+            id_filter.name_id(EqualFilter::equal_to(&input.patient_id)),
+        ))? == 0
+    {
+        return Err(UpsertPatientPolicyError::PolicyBelongsToAnotherPatient);
+    }
+
+    Ok(())
+}
+
+fn generate(
+    ctx: &ServiceContext,
+    UpsertPatientPolicy {
+        id,
+        patient_id,
+        insurance_provider_id,
+        policy_number,
+        policy_type,
+        coverage_percentage,
+        expiry_date,
+        is_active,
+    }: UpsertPatientPolicy,
+) -> NameInsuranceJoinRow {
+    NameInsuranceJoinRow {
+        id,
+        name_link_id: patient_id,
+        insurance_provider_id,
+        policy_number: policy_number.trim().to_string(),
+        policy_type,
+        coverage_percentage,
+        expiry_date,
+        is_active,
+        store_id: ctx.store_id.clone(),
+        entered_by_id: Some(ctx.user_id.clone()),
+    }
+}
+
+impl From<RepositoryError> for UpsertPatientPolicyError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertPatientPolicyError::DatabaseError(error)
+    }
+}
@@ -1,5 +1,11 @@
 use crate::activity_log::{activity_log_entry, log_type_from_invoice_status};
+use crate::period_lock::{check_invoice_update_period_lock, PeriodLockError};
+use crate::plugin::server_plugin::hooks::{
+    call_plugin_hook, InvoiceStatusChangeHookInput, PluginHookError, PluginVeto,
+};
+use crate::plugin::server_plugin::PluginHook;
 use crate::{invoice::query::get_invoice, service_provider::ServiceContext, WithDBError};
+use chrono::NaiveDate;
 use repository::Invoice;
 use repository::{
     InvoiceLineRowRepository, InvoiceRowRepository, InvoiceStatus, RepositoryError,
@@ -42,6 +48,7 @@ pub fn update_customer_return(
         .transaction_sync(|connection| {
             let (existing_return, other_party, status_changed) =
                 validate(connection, &ctx.store_id, &patch)?;
+            let existing_invoice = existing_return.clone();
             let GenerateResult {
                 batches_to_update,
                 updated_return,
@@ -52,6 +59,23 @@ pub fn update_customer_return(
                 other_party,
                 patch.clone(),
             )?;
+            check_invoice_update_period_lock(ctx, &existing_invoice, &updated_return)?;
+
+            if status_changed {
+                call_plugin_hook(
+                    ctx,
+                    PluginHook::BeforeInvoiceStatusChange,
+                    &updated_return.id,
+                    &InvoiceStatusChangeHookInput {
+                        store_id: ctx.store_id.clone(),
+                        invoice_id: updated_return.id.clone(),
+                        invoice_type: updated_return.r#type.clone(),
+                        name_link_id: updated_return.name_link_id.clone(),
+                        current_status: existing_invoice.status.clone(),
+                        new_status: updated_return.status.clone(),
+                    },
+                )?;
+            }
 
             InvoiceRowRepository::new(connection).upsert_one(&updated_return)?;
             let invoice_line_repository = InvoiceLineRowRepository::new(connection);
@@ -101,6 +125,10 @@ pub enum UpdateCustomerReturnError {
     // Internal
     DatabaseError(RepositoryError),
     UpdatedInvoiceDoesNotExist,
+    /// A server plugin rejected the status change
+    PluginVetoed(PluginVeto),
+    /// Holds the lock date of the closed period the return would move stock in
+    PeriodLocked(NaiveDate),
 }
 
 impl From<RepositoryError> for UpdateCustomerReturnError {
@@ -109,6 +137,30 @@ impl From<RepositoryError> for UpdateCustomerReturnError {
     }
 }
 
+impl From<PluginHookError> for UpdateCustomerReturnError {
+    fn from(error: PluginHookError) -> Self {
+        match error {
+            PluginHookError::Vetoed(veto) => UpdateCustomerReturnError::PluginVetoed(veto),
+            PluginHookError::DatabaseError(error) => {
+                UpdateCustomerReturnError::DatabaseError(error)
+            }
+        }
+    }
+}
+
+impl From<PeriodLockError> for UpdateCustomerReturnError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => {
+                UpdateCustomerReturnError::PeriodLocked(lock_date)
+            }
+            PeriodLockError::DatabaseError(error) => {
+                UpdateCustomerReturnError::DatabaseError(error)
+            }
+        }
+    }
+}
+
 impl<ERR> From<WithDBError<ERR>> for UpdateCustomerReturnError
 where
     ERR: Into<UpdateCustomerReturnError>,
@@ -37,6 +37,7 @@ pub fn generate_empty_invoice_lines(
                     return_reason_id: None,
                     foreign_currency_price_before_tax: None,
                     item_variant_id: None,
+                    donor_link_id: None,
                 });
             }
             Ok(None) => {}
@@ -329,6 +329,7 @@ pub fn generate_lines_and_stock_lines(
             return_reason_id: _,
             foreign_currency_price_before_tax: _,
             item_variant_id,
+            donor_link_id,
         }: InvoiceLineRow = invoice_lines;
 
         if number_of_packs > 0.0 {
@@ -349,6 +350,7 @@ pub fn generate_lines_and_stock_lines(
                 supplier_link_id: Some(supplier_id.to_string()),
                 barcode_id: None,
                 item_variant_id,
+                donor_link_id,
             };
             result.push(LineAndStockLine { line, stock_line });
         }
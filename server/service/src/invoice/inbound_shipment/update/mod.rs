@@ -1,6 +1,13 @@
 use crate::activity_log::{activity_log_entry, log_type_from_invoice_status};
 use crate::invoice_line::ShipmentTaxUpdate;
+use crate::period_lock::{check_invoice_update_period_lock, PeriodLockError};
+use crate::plugin::server_plugin::hooks::{
+    call_plugin_hook, InvoiceStatusChangeHookInput, PluginHookError, PluginVeto,
+};
+use crate::plugin::server_plugin::PluginHook;
+use crate::recall::apply::hold_new_recalled_stock;
 use crate::{invoice::query::get_invoice, service_provider::ServiceContext, WithDBError};
+use chrono::NaiveDate;
 use repository::{Invoice, LocationMovementRowRepository};
 use repository::{
     InvoiceLineRowRepository, InvoiceRowRepository, InvoiceStatus, RepositoryError,
@@ -47,6 +54,7 @@ pub fn update_inbound_shipment(
         .transaction_sync(|connection| {
             let (invoice, other_party, status_changed) =
                 validate(connection, &ctx.store_id, &patch)?;
+            let existing_invoice = invoice.clone();
             let GenerateResult {
                 batches_to_update,
                 update_invoice,
@@ -62,6 +70,23 @@ pub fn update_inbound_shipment(
                 other_party,
                 patch.clone(),
             )?;
+            check_invoice_update_period_lock(ctx, &existing_invoice, &update_invoice)?;
+
+            if status_changed {
+                call_plugin_hook(
+                    ctx,
+                    PluginHook::BeforeInvoiceStatusChange,
+                    &update_invoice.id,
+                    &InvoiceStatusChangeHookInput {
+                        store_id: ctx.store_id.clone(),
+                        invoice_id: update_invoice.id.clone(),
+                        invoice_type: update_invoice.r#type.clone(),
+                        name_link_id: update_invoice.name_link_id.clone(),
+                        current_status: existing_invoice.status.clone(),
+                        new_status: update_invoice.status.clone(),
+                    },
+                )?;
+            }
 
             InvoiceRowRepository::new(connection).upsert_one(&update_invoice)?;
             let invoice_line_repository = InvoiceLineRowRepository::new(connection);
@@ -69,7 +94,12 @@ pub fn update_inbound_shipment(
             if let Some(lines_and_invoice_lines) = batches_to_update {
                 let stock_line_repository = StockLineRowRepository::new(connection);
 
-                for LineAndStockLine { line, stock_line } in lines_and_invoice_lines.into_iter() {
+                for LineAndStockLine {
+                    line,
+                    mut stock_line,
+                } in lines_and_invoice_lines.into_iter()
+                {
+                    hold_new_recalled_stock(connection, &mut stock_line)?;
                     stock_line_repository.upsert_one(&stock_line)?;
                     invoice_line_repository.upsert_one(&line)?;
                 }
@@ -144,6 +174,10 @@ pub enum UpdateInboundShipmentError {
     // Internal
     DatabaseError(RepositoryError),
     UpdatedInvoiceDoesNotExist,
+    /// A server plugin rejected the status change
+    PluginVetoed(PluginVeto),
+    /// Holds the lock date of the closed period the shipment would move stock in
+    PeriodLocked(NaiveDate),
 }
 
 impl From<RepositoryError> for UpdateInboundShipmentError {
@@ -152,6 +186,30 @@ impl From<RepositoryError> for UpdateInboundShipmentError {
     }
 }
 
+impl From<PluginHookError> for UpdateInboundShipmentError {
+    fn from(error: PluginHookError) -> Self {
+        match error {
+            PluginHookError::Vetoed(veto) => UpdateInboundShipmentError::PluginVetoed(veto),
+            PluginHookError::DatabaseError(error) => {
+                UpdateInboundShipmentError::DatabaseError(error)
+            }
+        }
+    }
+}
+
+impl From<PeriodLockError> for UpdateInboundShipmentError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => {
+                UpdateInboundShipmentError::PeriodLocked(lock_date)
+            }
+            PeriodLockError::DatabaseError(error) => {
+                UpdateInboundShipmentError::DatabaseError(error)
+            }
+        }
+    }
+}
+
 impl<ERR> From<WithDBError<ERR>> for UpdateInboundShipmentError
 where
     ERR: Into<UpdateInboundShipmentError>,
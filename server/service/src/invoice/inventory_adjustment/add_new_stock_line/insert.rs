@@ -9,6 +9,7 @@ use super::validate::validate;
 
 use crate::activity_log::activity_log_entry;
 use crate::invoice_line::stock_in_line::{insert_stock_in_line, InsertStockInLineError};
+use crate::period_lock::{check_invoice_period_lock, PeriodLockError};
 use crate::service_provider::ServiceContext;
 use crate::stock_line::query::get_stock_line;
 use crate::{NullableUpdate, SingleRecordError};
@@ -37,6 +38,8 @@ pub enum AddNewStockLineError {
     AdjustmentReasonNotProvided,
     NewlyCreatedStockLineDoesNotExist,
     DatabaseError(RepositoryError),
+    /// Holds the lock date of the closed period
+    PeriodLocked(NaiveDate),
     // Line Errors
     LineInsertError(InsertStockInLineError),
 }
@@ -81,6 +84,7 @@ pub fn add_new_stock_line(
                 verified_datetime: Some(verified_datetime),
                 ..invoice
             };
+            check_invoice_period_lock(ctx, &verified_invoice)?;
 
             invoice_row_repo.upsert_one(&verified_invoice)?;
 
@@ -113,6 +117,17 @@ impl From<RepositoryError> for AddNewStockLineError {
     }
 }
 
+impl From<PeriodLockError> for AddNewStockLineError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => {
+                AddNewStockLineError::PeriodLocked(lock_date)
+            }
+            PeriodLockError::DatabaseError(error) => AddNewStockLineError::DatabaseError(error),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use repository::{
@@ -97,6 +97,7 @@ pub fn generate(
         note,
         on_hold,
         item_variant_id,
+        donor_link_id,
         ..
     } = stock_line.stock_line_row.clone();
 
@@ -122,6 +123,7 @@ pub fn generate(
             stock_on_hold: on_hold,
             note,
             item_variant_id,
+            donor_id: donor_link_id,
             // Default
             barcode: None,
             total_before_tax: None,
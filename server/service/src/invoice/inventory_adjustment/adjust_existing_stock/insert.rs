@@ -1,4 +1,4 @@
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use repository::RepositoryError;
 use repository::{
     ActivityLogType, Invoice, InvoiceLineRowRepository, InvoiceRow, InvoiceRowRepository,
@@ -12,6 +12,7 @@ use crate::activity_log::activity_log_entry;
 use crate::invoice::query::get_invoice;
 use crate::invoice_line::stock_in_line::{insert_stock_in_line, InsertStockInLineError};
 use crate::invoice_line::stock_out_line::{insert_stock_out_line, InsertStockOutLineError};
+use crate::period_lock::{check_invoice_period_lock, PeriodLockError};
 use crate::service_provider::ServiceContext;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -44,6 +45,8 @@ pub enum InsertInventoryAdjustmentError {
     AdjustmentReasonNotValid,
     AdjustmentReasonNotProvided,
     NewlyCreatedInvoiceDoesNotExist,
+    /// Holds the lock date of the closed period
+    PeriodLocked(NaiveDate),
     DatabaseError(RepositoryError),
     InternalError(String),
     StockInLineInsertError(InsertStockInLineError),
@@ -94,6 +97,7 @@ pub fn insert_inventory_adjustment(
                 verified_datetime: Some(Utc::now().naive_utc()),
                 ..invoice
             };
+            check_invoice_period_lock(ctx, &verified_invoice)?;
 
             invoice_row_repo.upsert_one(&verified_invoice)?;
 
@@ -120,6 +124,19 @@ impl From<RepositoryError> for InsertInventoryAdjustmentError {
     }
 }
 
+impl From<PeriodLockError> for InsertInventoryAdjustmentError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => {
+                InsertInventoryAdjustmentError::PeriodLocked(lock_date)
+            }
+            PeriodLockError::DatabaseError(error) => {
+                InsertInventoryAdjustmentError::DatabaseError(error)
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use repository::{
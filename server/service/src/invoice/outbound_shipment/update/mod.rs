@@ -1,3 +1,4 @@
+use chrono::NaiveDate;
 use repository::{
     Invoice, InvoiceLine, InvoiceLineRowRepository, InvoiceRowRepository, InvoiceStatus,
     LocationMovementRowRepository, RepositoryError, StockLineRowRepository, TransactionError,
@@ -13,6 +14,11 @@ use crate::activity_log::{activity_log_entry, log_type_from_invoice_status};
 use crate::invoice::outbound_shipment::update::generate::GenerateResult;
 use crate::invoice::query::get_invoice;
 use crate::invoice_line::ShipmentTaxUpdate;
+use crate::period_lock::{check_invoice_update_period_lock, PeriodLockError};
+use crate::plugin::server_plugin::hooks::{
+    call_plugin_hook, InvoiceStatusChangeHookInput, PluginHookError, PluginVeto,
+};
+use crate::plugin::server_plugin::PluginHook;
 use crate::service_provider::ServiceContext;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -52,6 +58,10 @@ pub enum UpdateOutboundShipmentError {
     DatabaseError(RepositoryError),
     /// Holds the id of the invalid invoice line
     InvoiceLineHasNoStockLine(String),
+    /// A server plugin rejected the status change
+    PluginVetoed(PluginVeto),
+    /// Holds the lock date of the closed period the shipment would move stock in
+    PeriodLocked(NaiveDate),
 }
 
 type OutError = UpdateOutboundShipmentError;
@@ -64,6 +74,8 @@ pub fn update_outbound_shipment(
         .connection
         .transaction_sync(|connection| {
             let (invoice, status_changed) = validate(connection, &ctx.store_id, &patch)?;
+            let current_status = invoice.status.clone();
+            let existing_invoice = invoice.clone();
             let GenerateResult {
                 batches_to_update,
                 update_invoice,
@@ -71,6 +83,23 @@ pub fn update_outbound_shipment(
                 location_movements,
                 update_lines,
             } = generate(&ctx.store_id, invoice, patch.clone(), connection)?;
+            check_invoice_update_period_lock(ctx, &existing_invoice, &update_invoice)?;
+
+            if status_changed {
+                call_plugin_hook(
+                    ctx,
+                    PluginHook::BeforeInvoiceStatusChange,
+                    &update_invoice.id,
+                    &InvoiceStatusChangeHookInput {
+                        store_id: ctx.store_id.clone(),
+                        invoice_id: update_invoice.id.clone(),
+                        invoice_type: update_invoice.r#type.clone(),
+                        name_link_id: update_invoice.name_link_id.clone(),
+                        current_status,
+                        new_status: update_invoice.status.clone(),
+                    },
+                )?;
+            }
 
             InvoiceRowRepository::new(connection).upsert_one(&update_invoice)?;
             let invoice_line_repo = InvoiceLineRowRepository::new(connection);
@@ -127,6 +156,30 @@ impl From<RepositoryError> for UpdateOutboundShipmentError {
     }
 }
 
+impl From<PluginHookError> for UpdateOutboundShipmentError {
+    fn from(error: PluginHookError) -> Self {
+        match error {
+            PluginHookError::Vetoed(veto) => UpdateOutboundShipmentError::PluginVetoed(veto),
+            PluginHookError::DatabaseError(error) => {
+                UpdateOutboundShipmentError::DatabaseError(error)
+            }
+        }
+    }
+}
+
+impl From<PeriodLockError> for UpdateOutboundShipmentError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => {
+                UpdateOutboundShipmentError::PeriodLocked(lock_date)
+            }
+            PeriodLockError::DatabaseError(error) => {
+                UpdateOutboundShipmentError::DatabaseError(error)
+            }
+        }
+    }
+}
+
 impl From<TransactionError<UpdateOutboundShipmentError>> for UpdateOutboundShipmentError {
     fn from(error: TransactionError<UpdateOutboundShipmentError>) -> Self {
         match error {
@@ -1,4 +1,4 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use repository::{
     Invoice, InvoiceLineRowRepository, InvoiceRowRepository, InvoiceStatus, RepositoryError,
     StockLineRowRepository,
@@ -7,6 +7,11 @@ use repository::{
 use crate::{
     activity_log::{activity_log_entry, log_type_from_invoice_status},
     invoice::query::get_invoice,
+    period_lock::{check_invoice_update_period_lock, PeriodLockError},
+    plugin::server_plugin::{
+        hooks::{call_plugin_hook, InvoiceStatusChangeHookInput, PluginHookError, PluginVeto},
+        PluginHook,
+    },
     service_provider::ServiceContext,
 };
 
@@ -49,6 +54,10 @@ pub enum UpdatePrescriptionError {
     InvoiceLineHasNoStockLine(String),
     /// Can't backdate an invoice with allocated lines
     CantBackDate(String),
+    /// A server plugin rejected the status change
+    PluginVetoed(PluginVeto),
+    /// Holds the lock date of the closed period the prescription would be dispensed in
+    PeriodLocked(NaiveDate),
 }
 
 type OutError = UpdatePrescriptionError;
@@ -61,11 +70,29 @@ pub fn update_prescription(
         .connection
         .transaction_sync(|connection| {
             let (invoice, status_changed) = validate(connection, &ctx.store_id, &patch)?;
+            let existing_invoice = invoice.clone();
             let GenerateResult {
                 batches_to_update,
                 update_invoice,
                 lines_to_trim,
             } = generate(invoice, patch.clone(), connection)?;
+            check_invoice_update_period_lock(ctx, &existing_invoice, &update_invoice)?;
+
+            if status_changed {
+                call_plugin_hook(
+                    ctx,
+                    PluginHook::BeforeInvoiceStatusChange,
+                    &update_invoice.id,
+                    &InvoiceStatusChangeHookInput {
+                        store_id: ctx.store_id.clone(),
+                        invoice_id: update_invoice.id.clone(),
+                        invoice_type: update_invoice.r#type.clone(),
+                        name_link_id: update_invoice.name_link_id.clone(),
+                        current_status: existing_invoice.status.clone(),
+                        new_status: update_invoice.status.clone(),
+                    },
+                )?;
+            }
 
             InvoiceRowRepository::new(connection).upsert_one(&update_invoice)?;
             let invoice_line_repo = InvoiceLineRowRepository::new(connection);
@@ -127,6 +154,26 @@ impl From<RepositoryError> for UpdatePrescriptionError {
     }
 }
 
+impl From<PluginHookError> for UpdatePrescriptionError {
+    fn from(error: PluginHookError) -> Self {
+        match error {
+            PluginHookError::Vetoed(veto) => UpdatePrescriptionError::PluginVetoed(veto),
+            PluginHookError::DatabaseError(error) => UpdatePrescriptionError::DatabaseError(error),
+        }
+    }
+}
+
+impl From<PeriodLockError> for UpdatePrescriptionError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => {
+                UpdatePrescriptionError::PeriodLocked(lock_date)
+            }
+            PeriodLockError::DatabaseError(error) => UpdatePrescriptionError::DatabaseError(error),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use chrono::NaiveDate;
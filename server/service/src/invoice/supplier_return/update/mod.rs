@@ -1,3 +1,4 @@
+use chrono::NaiveDate;
 use repository::{
     Invoice, InvoiceRowRepository, InvoiceStatus, RepositoryError, StockLineRowRepository,
 };
@@ -5,6 +6,11 @@ use repository::{
 use crate::{
     activity_log::{activity_log_entry, log_type_from_invoice_status},
     invoice::get_invoice,
+    period_lock::{check_invoice_update_period_lock, PeriodLockError},
+    plugin::server_plugin::{
+        hooks::{call_plugin_hook, InvoiceStatusChangeHookInput, PluginHookError, PluginVeto},
+        PluginHook,
+    },
     service_provider::ServiceContext,
 };
 
@@ -44,6 +50,10 @@ pub enum UpdateSupplierReturnError {
     InvoiceLineHasNoStockLine(String), // holds the id of the invalid invoice line
     UpdatedReturnDoesNotExist,
     DatabaseError(RepositoryError),
+    /// A server plugin rejected the status change
+    PluginVetoed(PluginVeto),
+    /// Holds the lock date of the closed period the return would move stock in
+    PeriodLocked(NaiveDate),
 }
 
 pub fn update_supplier_return(
@@ -54,10 +64,28 @@ pub fn update_supplier_return(
         .connection
         .transaction_sync(|connection| {
             let (return_row, status_changed) = validate(connection, &ctx.store_id, &input)?;
+            let existing_return = return_row.clone();
             let GenerateResult {
                 updated_return,
                 stock_lines_to_update,
             } = generate(connection, input.clone(), return_row)?;
+            check_invoice_update_period_lock(ctx, &existing_return, &updated_return)?;
+
+            if status_changed {
+                call_plugin_hook(
+                    ctx,
+                    PluginHook::BeforeInvoiceStatusChange,
+                    &updated_return.id,
+                    &InvoiceStatusChangeHookInput {
+                        store_id: ctx.store_id.clone(),
+                        invoice_id: updated_return.id.clone(),
+                        invoice_type: updated_return.r#type.clone(),
+                        name_link_id: updated_return.name_link_id.clone(),
+                        current_status: existing_return.status.clone(),
+                        new_status: updated_return.status.clone(),
+                    },
+                )?;
+            }
 
             InvoiceRowRepository::new(connection).upsert_one(&updated_return)?;
 
@@ -95,6 +123,30 @@ impl From<RepositoryError> for UpdateSupplierReturnError {
     }
 }
 
+impl From<PluginHookError> for UpdateSupplierReturnError {
+    fn from(error: PluginHookError) -> Self {
+        match error {
+            PluginHookError::Vetoed(veto) => UpdateSupplierReturnError::PluginVetoed(veto),
+            PluginHookError::DatabaseError(error) => {
+                UpdateSupplierReturnError::DatabaseError(error)
+            }
+        }
+    }
+}
+
+impl From<PeriodLockError> for UpdateSupplierReturnError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => {
+                UpdateSupplierReturnError::PeriodLocked(lock_date)
+            }
+            PeriodLockError::DatabaseError(error) => {
+                UpdateSupplierReturnError::DatabaseError(error)
+            }
+        }
+    }
+}
+
 impl UpdateSupplierReturnStatus {
     pub fn as_invoice_row_status(&self) -> InvoiceStatus {
         match self {
@@ -48,5 +48,6 @@ pub fn generate(
         inventory_adjustment_reason_id: None,
         return_reason_id: None,
         item_variant_id: None,
+        donor_link_id: None,
     })
 }
@@ -2,8 +2,8 @@ use std::cmp::Ordering;
 
 use repository::{
     EqualFilter, InvoiceLine, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineType,
-    Pagination, RepositoryError, StockLine, StockLineFilter, StockLineRepository, StockLineSort,
-    StockLineSortField, StorageConnection,
+    NameLinkRowRepository, Pagination, RepositoryError, StockLine, StockLineFilter,
+    StockLineRepository, StockLineSort, StockLineSortField, StorageConnection,
 };
 use util::{
     constants::stock_line_expiring_soon_offset, date_now, date_now_with_offset,
@@ -211,11 +211,21 @@ fn get_sorted_available_stock_lines(
     store_id: &str,
     unallocated_line: &InvoiceLine,
 ) -> Result<Vec<StockLine>, RepositoryError> {
-    let filter = StockLineFilter::new()
+    let mut filter = StockLineFilter::new()
         .item_id(EqualFilter::equal_to(&unallocated_line.item_row.id))
         .store_id(EqualFilter::equal_to(store_id))
         .is_available(true);
 
+    // Restrict allocation to stock owned by the requested donor, the filter matches stock of every
+    // name link of the donor (e.g. after names are merged)
+    if let Some(donor_link_id) = &unallocated_line.invoice_line_row.donor_link_id {
+        let donor_id = NameLinkRowRepository::new(connection)
+            .find_one_by_id(donor_link_id)?
+            .map(|name_link| name_link.name_id)
+            .unwrap_or_else(|| donor_link_id.clone());
+        filter = filter.donor_id(EqualFilter::equal_to(&donor_id));
+    }
+
     // Nulls should be last (as per test stock_line_repository_sort)
     let sort = StockLineSort {
         key: StockLineSortField::ExpiryDate,
@@ -4,12 +4,13 @@ mod test {
     use chrono::{Duration, NaiveDate};
     use repository::{
         mock::{
-            mock_item_a, mock_item_b, mock_name_a, mock_outbound_shipment_a_invoice_lines,
-            mock_store_a, mock_store_b, MockData, MockDataInserts,
+            mock_item_a, mock_item_b, mock_name_a, mock_name_b,
+            mock_outbound_shipment_a_invoice_lines, mock_store_a, mock_store_b, MockData,
+            MockDataInserts,
         },
         test_db::{setup_all, setup_all_with_data},
         InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineType, InvoiceRow, InvoiceType,
-        StockLine, StockLineRow,
+        NameLinkRow, StockLine, StockLineRow,
     };
     use util::{
         constants::stock_line_expiring_soon_offset, date_now, date_now_with_offset, inline_edit,
@@ -665,4 +666,113 @@ mod test {
             })
         );
     }
+
+    #[actix_rt::test]
+    async fn allocate_unallocated_line_restricted_by_donor() {
+        fn invoice() -> InvoiceRow {
+            inline_init(|r: &mut InvoiceRow| {
+                r.id = "invoice".to_string();
+                r.store_id = mock_store_a().id;
+                r.name_link_id = mock_name_a().id;
+                r.r#type = InvoiceType::OutboundShipment;
+            })
+        }
+
+        fn line() -> InvoiceLineRow {
+            inline_init(|r: &mut InvoiceLineRow| {
+                r.id = "line".to_string();
+                r.invoice_id = invoice().id;
+                r.item_link_id = mock_item_a().id;
+                r.r#type = InvoiceLineType::UnallocatedStock;
+                r.number_of_packs = 20.0;
+                r.pack_size = 1.0;
+                r.donor_link_id = Some(merged_donor_link().id);
+            })
+        }
+
+        // Link of a donor name that was merged into the stock's donor
+        fn merged_donor_link() -> NameLinkRow {
+            NameLinkRow {
+                id: "merged_donor".to_string(),
+                name_id: mock_name_b().id,
+            }
+        }
+
+        fn base_stock_line(id: &str) -> StockLineRow {
+            inline_init(|r: &mut StockLineRow| {
+                r.id = id.to_string();
+                r.store_id = mock_store_a().id;
+                r.item_link_id = mock_item_a().id;
+                r.pack_size = 1.0;
+                r.available_number_of_packs = 30.0;
+            })
+        }
+
+        // Would be allocated first (FEFO) if it wasn't owned by another donor
+        fn stock_line_other_donor() -> StockLineRow {
+            inline_edit(&base_stock_line("stock_line_other_donor"), |mut u| {
+                u.expiry_date = Some(date_now() + Duration::days(10));
+                u.donor_link_id = Some(mock_name_a().id);
+                u
+            })
+        }
+
+        fn stock_line_no_donor() -> StockLineRow {
+            inline_edit(&base_stock_line("stock_line_no_donor"), |mut u| {
+                u.expiry_date = Some(date_now() + Duration::days(20));
+                u
+            })
+        }
+
+        fn stock_line_donor() -> StockLineRow {
+            inline_edit(&base_stock_line("stock_line_donor"), |mut u| {
+                u.expiry_date = Some(date_now() + Duration::days(30));
+                u.donor_link_id = Some(mock_name_b().id);
+                u
+            })
+        }
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "allocate_unallocated_line_restricted_by_donor",
+            MockDataInserts::none()
+                .stores()
+                .items()
+                .names()
+                .units()
+                .currencies(),
+            inline_init(|r: &mut MockData| {
+                r.name_links = vec![merged_donor_link()];
+                r.invoices = vec![invoice()];
+                r.invoice_lines = vec![line()];
+                r.stock_lines = vec![
+                    stock_line_other_donor(),
+                    stock_line_no_donor(),
+                    stock_line_donor(),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager.clone());
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.invoice_line_service;
+
+        let result = service
+            .allocate_outbound_shipment_unallocated_line(&context, line().id.clone())
+            .unwrap();
+
+        assert_eq!(result.inserts.len(), 1);
+        assert_eq!(result.deletes.len(), 1);
+
+        let new_line = InvoiceLineRowRepository::new(&connection)
+            .find_one_by_id(&result.inserts[0].invoice_line_row.id)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(new_line.stock_line_id, Some(stock_line_donor().id));
+        assert_eq!(new_line.donor_link_id, Some(mock_name_b().id));
+        assert_eq!(new_line.number_of_packs, 20.0);
+    }
 }
@@ -18,6 +18,8 @@ pub struct InsertOutboundShipmentUnallocatedLine {
     pub invoice_id: String,
     pub item_id: String,
     pub quantity: u32,
+    /// Only allocate stock owned by this donor
+    pub donor_id: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -97,6 +99,7 @@ fn generate(
         invoice_id,
         item_id,
         quantity,
+        donor_id,
     }: InsertOutboundShipmentUnallocatedLine,
     item: ItemRow,
 ) -> Result<InvoiceLineRow, InsertOutboundShipmentUnallocatedLineError> {
@@ -125,6 +128,7 @@ fn generate(
         return_reason_id: None,
         foreign_currency_price_before_tax: None,
         item_variant_id: None,
+        donor_link_id: donor_id,
     };
 
     Ok(new_line)
@@ -195,7 +199,8 @@ mod test_insert {
                     id: existing_invoice_line.id.clone(),
                     invoice_id: "".to_owned(),
                     item_id: "".to_owned(),
-                    quantity: 0
+                    quantity: 0,
+                    donor_id: None,
                 },
             ),
             Err(ServiceError::LineAlreadyExists)
@@ -209,7 +214,8 @@ mod test_insert {
                     id: new_line_id.clone(),
                     invoice_id: "invalid".to_owned(),
                     item_id: "item_a".to_owned(),
-                    quantity: 0
+                    quantity: 0,
+                    donor_id: None,
                 },
             ),
             Err(ServiceError::InvoiceDoesNotExist)
@@ -223,7 +229,8 @@ mod test_insert {
                     id: new_line_id.clone(),
                     invoice_id: mock_inbound_shipment_a().id.clone(),
                     item_id: "item_a".to_owned(),
-                    quantity: 0
+                    quantity: 0,
+                    donor_id: None,
                 },
             ),
             Err(ServiceError::NotAnOutboundShipment)
@@ -237,7 +244,8 @@ mod test_insert {
                     id: new_line_id.clone(),
                     invoice_id: mock_allocated_invoice().id.clone(),
                     item_id: "item_a".to_owned(),
-                    quantity: 0
+                    quantity: 0,
+                    donor_id: None,
                 },
             ),
             Err(ServiceError::CanOnlyAddLinesToNewOutboundShipment)
@@ -251,7 +259,8 @@ mod test_insert {
                     id: new_line_id.clone(),
                     invoice_id: new_outbound_shipment.id.clone(),
                     item_id: "invalid".to_owned(),
-                    quantity: 0
+                    quantity: 0,
+                    donor_id: None,
                 },
             ),
             Err(ServiceError::ItemNotFound)
@@ -264,7 +273,8 @@ mod test_insert {
                     id: new_line_id.clone(),
                     invoice_id: new_outbound_shipment.id.clone(),
                     item_id: mock_item_service_item().id.clone(),
-                    quantity: 0
+                    quantity: 0,
+                    donor_id: None,
                 },
             ),
             Err(ServiceError::NotAStockItem)
@@ -277,7 +287,8 @@ mod test_insert {
                     id: "new unallocated line id".to_owned(),
                     invoice_id: mock_new_invoice_with_unallocated_line().id.clone(),
                     item_id: existing_invoice_line.item_link_id.clone(),
-                    quantity: 0
+                    quantity: 0,
+                    donor_id: None,
                 },
             ),
             Err(ServiceError::NotThisStoreInvoice)
@@ -291,7 +302,8 @@ mod test_insert {
                     id: new_line_id.clone(),
                     invoice_id: new_outbound_shipment.id.clone(),
                     item_id: existing_invoice_line.item_link_id.clone(),
-                    quantity: 0
+                    quantity: 0,
+                    donor_id: None,
                 },
             ),
             Err(ServiceError::UnallocatedLineForItemAlreadyExistsInInvoice)
@@ -325,6 +337,7 @@ mod test_insert {
                     invoice_id: invoice_id.clone(),
                     item_id: item.id.clone(),
                     quantity: 4,
+                    donor_id: None,
                 },
             )
             .unwrap();
@@ -1,6 +1,10 @@
 use crate::{
-    invoice::common::generate_invoice_user_id_update, service_provider::ServiceContext, WithDBError,
+    invoice::common::generate_invoice_user_id_update,
+    period_lock::{check_invoice_period_lock, PeriodLockError},
+    service_provider::ServiceContext,
+    WithDBError,
 };
+use chrono::NaiveDate;
 use repository::{
     InvoiceLineRowRepository, InvoiceRowRepository, RepositoryError, StockLineRowRepository,
 };
@@ -27,6 +31,7 @@ pub fn delete_stock_in_line(
         .connection
         .transaction_sync(|connection| {
             let (invoice_row, line) = validate(&input, &ctx.store_id, connection)?;
+            check_invoice_period_lock(ctx, &invoice_row)?;
 
             let delete_batch_id_option = line.stock_line_id.clone();
 
@@ -56,6 +61,8 @@ pub enum DeleteStockInLineError {
     BatchIsReserved,
     NotThisInvoiceLine(String),
     LineUsedInStocktake,
+    /// Holds the lock date of the closed period
+    PeriodLocked(NaiveDate),
 }
 
 impl From<RepositoryError> for DeleteStockInLineError {
@@ -64,6 +71,17 @@ impl From<RepositoryError> for DeleteStockInLineError {
     }
 }
 
+impl From<PeriodLockError> for DeleteStockInLineError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => {
+                DeleteStockInLineError::PeriodLocked(lock_date)
+            }
+            PeriodLockError::DatabaseError(error) => DeleteStockInLineError::DatabaseError(error),
+        }
+    }
+}
+
 impl<ERR> From<WithDBError<ERR>> for DeleteStockInLineError
 where
     ERR: Into<DeleteStockInLineError>,
@@ -86,6 +86,7 @@ fn generate_line(
         note,
         stock_line_id,
         item_variant_id,
+        donor_id,
         barcode: _,
         stock_on_hold: _,
         tax_percentage: _,
@@ -120,6 +121,7 @@ fn generate_line(
         tax_percentage,
         note,
         item_variant_id,
+        donor_link_id: donor_id,
         inventory_adjustment_reason_id: None,
         return_reason_id: None,
         foreign_currency_price_before_tax: None,
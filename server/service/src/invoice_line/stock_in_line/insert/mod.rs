@@ -1,6 +1,9 @@
 use crate::{
-    invoice_line::query::get_invoice_line, service_provider::ServiceContext, NullableUpdate,
-    WithDBError,
+    invoice_line::query::get_invoice_line,
+    period_lock::{check_invoice_period_lock, PeriodLockError},
+    recall::apply::hold_new_recalled_stock,
+    service_provider::ServiceContext,
+    NullableUpdate, WithDBError,
 };
 use chrono::NaiveDate;
 use repository::{
@@ -43,6 +46,8 @@ pub struct InsertStockInLine {
     pub barcode: Option<String>,
     pub stock_on_hold: bool,
     pub item_variant_id: Option<String>,
+    /// Donor or funding program that owns the received stock
+    pub donor_id: Option<String>,
 }
 
 type OutError = InsertStockInLineError;
@@ -55,6 +60,7 @@ pub fn insert_stock_in_line(
         .connection
         .transaction_sync(|connection| {
             let (item, invoice) = validate(&input, &ctx.store_id, connection)?;
+            check_invoice_period_lock(ctx, &invoice)?;
             let GenerateResult {
                 invoice: invoice_user_update,
                 invoice_line,
@@ -66,7 +72,8 @@ pub fn insert_stock_in_line(
                 BarcodeRowRepository::new(connection).upsert_one(&barcode_row)?;
             }
 
-            if let Some(stock_line_row) = stock_line {
+            if let Some(mut stock_line_row) = stock_line {
+                hold_new_recalled_stock(connection, &mut stock_line_row)?;
                 StockLineRowRepository::new(connection).upsert_one(&stock_line_row)?;
             }
             InvoiceLineRowRepository::new(connection).upsert_one(&invoice_line)?;
@@ -97,6 +104,8 @@ pub enum InsertStockInLineError {
     PackSizeBelowOne,
     NumberOfPacksBelowZero,
     NewlyCreatedLineDoesNotExist,
+    /// Holds the lock date of the closed period
+    PeriodLocked(NaiveDate),
 }
 
 impl From<RepositoryError> for InsertStockInLineError {
@@ -105,6 +114,17 @@ impl From<RepositoryError> for InsertStockInLineError {
     }
 }
 
+impl From<PeriodLockError> for InsertStockInLineError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => {
+                InsertStockInLineError::PeriodLocked(lock_date)
+            }
+            PeriodLockError::DatabaseError(error) => InsertStockInLineError::DatabaseError(error),
+        }
+    }
+}
+
 impl<ERR> From<WithDBError<ERR>> for InsertStockInLineError
 where
     ERR: Into<InsertStockInLineError>,
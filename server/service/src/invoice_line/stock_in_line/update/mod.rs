@@ -1,5 +1,6 @@
 use crate::{
     invoice_line::{query::get_invoice_line, ShipmentTaxUpdate},
+    period_lock::{check_invoice_period_lock, PeriodLockError},
     service_provider::ServiceContext,
     NullableUpdate, WithDBError,
 };
@@ -33,6 +34,7 @@ pub struct UpdateStockInLine {
     pub tax_percentage: Option<ShipmentTaxUpdate>,
     pub r#type: StockInType,
     pub item_variant_id: Option<NullableUpdate<String>>,
+    pub donor_id: Option<NullableUpdate<String>>,
 }
 
 type OutError = UpdateStockInLineError;
@@ -45,6 +47,7 @@ pub fn update_stock_in_line(
         .connection
         .transaction_sync(|connection| {
             let (line, item, invoice) = validate(&input, &ctx.store_id, connection)?;
+            check_invoice_period_lock(ctx, &invoice)?;
 
             let GenerateResult {
                 invoice_row_option,
@@ -94,6 +97,8 @@ pub enum UpdateStockInLineError {
     BatchIsReserved,
     UpdatedLineDoesNotExist,
     NotThisInvoiceLine(String),
+    /// Holds the lock date of the closed period
+    PeriodLocked(NaiveDate),
 }
 
 impl From<RepositoryError> for UpdateStockInLineError {
@@ -102,6 +107,17 @@ impl From<RepositoryError> for UpdateStockInLineError {
     }
 }
 
+impl From<PeriodLockError> for UpdateStockInLineError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => {
+                UpdateStockInLineError::PeriodLocked(lock_date)
+            }
+            PeriodLockError::DatabaseError(error) => UpdateStockInLineError::DatabaseError(error),
+        }
+    }
+}
+
 impl<ERR> From<WithDBError<ERR>> for UpdateStockInLineError
 where
     ERR: Into<UpdateStockInLineError>,
@@ -1,4 +1,6 @@
+use crate::period_lock::{check_invoice_period_lock, PeriodLockError};
 use crate::service_provider::ServiceContext;
+use chrono::NaiveDate;
 use repository::{
     InvoiceLineRowRepository, InvoiceRowRepository, InvoiceStatus, RepositoryError,
     StockLineRowRepository,
@@ -26,11 +28,14 @@ pub fn delete_stock_out_line(
         .transaction_sync(|connection| {
             let line = validate(&input, &ctx.store_id, connection)?;
             let stock_line_id_option = line.stock_line_id.clone();
+            let invoice = InvoiceRowRepository::new(connection)
+                .find_one_by_id(&line.invoice_id)?
+                .ok_or(DeleteStockOutLineError::InvoiceDoesNotExist)?;
+            check_invoice_period_lock(ctx, &invoice)?;
 
             InvoiceLineRowRepository::new(connection).delete(&line.id)?;
 
             if let Some(stock_line_id) = stock_line_id_option {
-                let invoice_repository = InvoiceRowRepository::new(connection);
                 let stock_line_repository = StockLineRowRepository::new(connection);
 
                 let mut stock_line = stock_line_repository
@@ -38,9 +43,6 @@ pub fn delete_stock_out_line(
                     .ok_or(DeleteStockOutLineError::StockLineDoesNotExist)?;
                 stock_line.available_number_of_packs += line.number_of_packs;
 
-                let invoice = invoice_repository
-                    .find_one_by_id(&line.invoice_id)?
-                    .ok_or(DeleteStockOutLineError::InvoiceDoesNotExist)?;
                 if invoice.status == InvoiceStatus::Picked {
                     stock_line.total_number_of_packs += line.number_of_packs;
                 }
@@ -65,6 +67,8 @@ pub enum DeleteStockOutLineError {
     NotThisStoreInvoice,
     CannotEditInvoice,
     NotThisInvoiceLine(String),
+    /// Holds the lock date of the closed period
+    PeriodLocked(NaiveDate),
 }
 
 impl From<RepositoryError> for DeleteStockOutLineError {
@@ -73,6 +77,17 @@ impl From<RepositoryError> for DeleteStockOutLineError {
     }
 }
 
+impl From<PeriodLockError> for DeleteStockOutLineError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => {
+                DeleteStockOutLineError::PeriodLocked(lock_date)
+            }
+            PeriodLockError::DatabaseError(error) => DeleteStockOutLineError::DatabaseError(error),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use chrono::NaiveDate;
@@ -123,6 +123,7 @@ fn generate_line(
         expiry_date,
         location_id,
         item_variant_id,
+        donor_link_id,
         note: _,
         ..
     }: StockLineRow,
@@ -171,6 +172,7 @@ fn generate_line(
         return_reason_id: None,
         foreign_currency_price_before_tax,
         item_variant_id,
+        donor_link_id,
     })
 }
 
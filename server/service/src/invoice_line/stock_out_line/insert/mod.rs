@@ -1,4 +1,9 @@
-use crate::{invoice_line::query::get_invoice_line, service_provider::ServiceContext, WithDBError};
+use crate::{
+    invoice_line::query::get_invoice_line,
+    period_lock::{check_invoice_period_lock, PeriodLockError},
+    service_provider::ServiceContext,
+    WithDBError,
+};
 use chrono::NaiveDate;
 use repository::{InvoiceLine, InvoiceLineRowRepository, RepositoryError, StockLineRowRepository};
 
@@ -42,7 +47,11 @@ pub enum InsertStockOutLineError {
     StockLineAlreadyExistsInInvoice(String),
     NewlyCreatedLineDoesNotExist,
     BatchIsOnHold,
-    ReductionBelowZero { stock_line_id: String },
+    ReductionBelowZero {
+        stock_line_id: String,
+    },
+    /// Holds the lock date of the closed period
+    PeriodLocked(NaiveDate),
 }
 
 impl From<RepositoryError> for InsertStockOutLineError {
@@ -51,6 +60,17 @@ impl From<RepositoryError> for InsertStockOutLineError {
     }
 }
 
+impl From<PeriodLockError> for InsertStockOutLineError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => {
+                InsertStockOutLineError::PeriodLocked(lock_date)
+            }
+            PeriodLockError::DatabaseError(error) => InsertStockOutLineError::DatabaseError(error),
+        }
+    }
+}
+
 impl<ERR> From<WithDBError<ERR>> for InsertStockOutLineError
 where
     ERR: Into<InsertStockOutLineError>,
@@ -73,6 +93,7 @@ pub fn insert_stock_out_line(
         .connection
         .transaction_sync(|connection| {
             let (item, invoice, batch) = validate(&connection, &input, &ctx.store_id)?;
+            check_invoice_period_lock(ctx, &invoice)?;
             let (new_line, update_batch) = generate(ctx, input, item, batch, invoice)?;
             InvoiceLineRowRepository::new(connection).upsert_one(&new_line)?;
             StockLineRowRepository::new(connection).upsert_one(&update_batch)?;
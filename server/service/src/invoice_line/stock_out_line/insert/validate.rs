@@ -11,7 +11,7 @@ use crate::{
     stock_line::historical_stock::get_historical_stock_line_available_quantity,
 };
 
-use super::{InsertStockOutLine, InsertStockOutLineError};
+use super::{InsertStockOutLine, InsertStockOutLineError, StockOutType};
 
 pub fn validate(
     connection: &StorageConnection,
@@ -57,7 +57,8 @@ pub fn validate(
     if !check_invoice_is_editable(&invoice) {
         return Err(CannotEditFinalised);
     }
-    if !check_batch_on_hold(&batch) {
+    // Stock on hold (e.g. recalled stock) can still be returned to the supplier
+    if input.r#type != StockOutType::SupplierReturn && !check_batch_on_hold(&batch) {
         return Err(BatchIsOnHold);
     }
     check_location_on_hold(&batch).map_err(|e| match e {
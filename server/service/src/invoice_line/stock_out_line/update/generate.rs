@@ -101,6 +101,7 @@ fn generate_line(
         expiry_date,
         location_id,
         item_variant_id,
+        donor_link_id,
         ..
     }: StockLineRow,
 ) -> InvoiceLineRow {
@@ -131,6 +132,7 @@ fn generate_line(
         return_reason_id: None,
         foreign_currency_price_before_tax,
         item_variant_id,
+        donor_link_id,
     };
 
     if let Some(number_of_packs) = input.number_of_packs {
@@ -1,3 +1,4 @@
+use chrono::NaiveDate;
 use repository::{
     InvoiceLine, InvoiceLineRow, InvoiceLineRowRepository, RepositoryError, StockLine,
     StockLineRowRepository,
@@ -5,6 +6,7 @@ use repository::{
 
 use crate::{
     invoice_line::{query::get_invoice_line, ShipmentTaxUpdate},
+    period_lock::{check_invoice_period_lock, PeriodLockError},
     service_provider::ServiceContext,
 };
 
@@ -50,6 +52,8 @@ pub enum UpdateStockOutLineError {
         stock_line_id: String,
         line_id: String,
     },
+    /// Holds the lock date of the closed period
+    PeriodLocked(NaiveDate),
 }
 
 type OutError = UpdateStockOutLineError;
@@ -62,6 +66,7 @@ pub fn update_stock_out_line(
         .connection
         .transaction_sync(|connection| {
             let (line, item, batch_pair, invoice) = validate(ctx, &input, &ctx.store_id)?;
+            check_invoice_period_lock(ctx, &invoice)?;
 
             let (update_line, batch_pair) = generate(input, line, item, batch_pair, invoice)?;
             InvoiceLineRowRepository::new(connection).upsert_one(&update_line)?;
@@ -119,6 +124,17 @@ impl From<RepositoryError> for UpdateStockOutLineError {
     }
 }
 
+impl From<PeriodLockError> for UpdateStockOutLineError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => {
+                UpdateStockOutLineError::PeriodLocked(lock_date)
+            }
+            PeriodLockError::DatabaseError(error) => UpdateStockOutLineError::DatabaseError(error),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use repository::{
@@ -1,4 +1,6 @@
-use repository::{InvoiceLineRow, InvoiceRow, InvoiceStatus, ItemRow, StorageConnection};
+use repository::{
+    InvoiceLineRow, InvoiceRow, InvoiceStatus, InvoiceType, ItemRow, StorageConnection,
+};
 
 use crate::{
     invoice::{check_invoice_exists, check_invoice_is_editable, check_invoice_type, check_store},
@@ -61,7 +63,9 @@ pub fn validate(
 
     let item = line.item_row.clone();
 
-    if !check_batch_on_hold(&batch_pair.main_batch) {
+    // Stock on hold (e.g. recalled stock) can still be returned to the supplier
+    if invoice.r#type != InvoiceType::SupplierReturn && !check_batch_on_hold(&batch_pair.main_batch)
+    {
         return Err(BatchIsOnHold);
     }
     check_location_on_hold(&batch_pair.main_batch).map_err(|e| match e {
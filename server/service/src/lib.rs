@@ -8,10 +8,12 @@ use service_provider::ServiceContext;
 use std::convert::TryInto;
 
 pub mod activity_log;
+pub mod api_token;
 pub mod apis;
 pub mod app_data;
 
 pub mod asset;
+pub mod audit_trail;
 pub mod auth;
 pub mod auth_data;
 pub mod barcode;
@@ -26,6 +28,10 @@ pub mod dashboard;
 pub mod demographic;
 pub mod display_settings_service;
 pub mod document;
+pub mod expiry_risk;
+pub mod goods_received;
+pub mod identity_provider;
+pub mod insurance;
 pub mod inventory_adjustment_reason;
 pub mod invoice;
 pub mod invoice_line;
@@ -33,6 +39,7 @@ pub mod item;
 pub mod item_stats;
 pub mod label_printer_settings_service;
 pub mod ledger;
+pub mod local_auth;
 pub mod localisations;
 pub mod location;
 pub mod log_service;
@@ -41,6 +48,7 @@ pub mod master_list;
 pub mod name;
 pub mod name_property;
 pub mod number;
+pub mod period_lock;
 pub mod permission;
 pub mod plugin;
 pub mod plugin_data;
@@ -50,19 +58,23 @@ pub mod processors;
 pub mod program;
 pub mod programs;
 pub mod reason_option;
+pub mod recall;
 pub mod repack;
 pub mod report;
 pub mod requisition;
 pub mod requisition_line;
 pub mod return_reason;
 pub mod rnr_form;
+pub mod role;
 pub mod sensor;
 pub mod service_provider;
 pub mod settings;
 pub mod settings_service;
+pub mod sms_gateway;
 pub mod standard_reports;
 pub mod static_files;
 pub mod stock_line;
+pub mod stock_valuation;
 pub mod stocktake;
 pub mod stocktake_line;
 pub mod store;
@@ -72,9 +84,11 @@ pub mod temperature_excursion;
 pub mod token;
 pub mod token_bucket;
 pub mod user_account;
+pub mod user_session;
 pub mod vaccination;
 pub mod vaccine_course;
 pub mod validate;
+pub mod warehouse;
 
 #[cfg(test)]
 mod login_mock_data;
@@ -0,0 +1,208 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use repository::{
+    system_log_row::{SystemLogRowRepository, SystemLogType},
+    ActivityLogType, RepositoryError, StorageConnection, UserAccountRowRepository,
+    UserAuthStateRow, UserAuthStateRowRepository,
+};
+
+use crate::{
+    activity_log::{activity_log_entry, system_log_entry, user_activity_log_entry},
+    service_provider::ServiceContext,
+};
+
+use super::get_auth_policy;
+
+/// At most one system log entry for failed logins with unknown usernames is written per interval,
+/// so guessing usernames doesn't fill up the system log
+const UNKNOWN_USERNAME_LOG_INTERVAL_MINUTES: i64 = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FailedLoginReason {
+    InvalidCredentials,
+    InvalidTotpCode,
+    AccountLocked,
+    PasswordExpired,
+}
+
+impl FailedLoginReason {
+    /// Only wrong credentials count towards the lockout
+    fn counts_towards_lockout(&self) -> bool {
+        match self {
+            FailedLoginReason::InvalidCredentials | FailedLoginReason::InvalidTotpCode => true,
+            FailedLoginReason::AccountLocked | FailedLoginReason::PasswordExpired => false,
+        }
+    }
+
+    fn to_log_value(&self) -> String {
+        match self {
+            FailedLoginReason::InvalidCredentials => "INVALID_CREDENTIALS",
+            FailedLoginReason::InvalidTotpCode => "INVALID_TOTP_CODE",
+            FailedLoginReason::AccountLocked => "ACCOUNT_LOCKED",
+            FailedLoginReason::PasswordExpired => "PASSWORD_EXPIRED",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UnlockUserAccountError {
+    UserDoesNotExist,
+    UserAccountNotLocked,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for UnlockUserAccountError {
+    fn from(error: RepositoryError) -> Self {
+        UnlockUserAccountError::DatabaseError(error)
+    }
+}
+
+/// Seconds until the lockout expires, None if the account isn't locked
+pub fn lockout_remaining(auth_state: &UserAuthStateRow, now: NaiveDateTime) -> Option<u64> {
+    match auth_state.locked_until_datetime {
+        Some(locked_until) if locked_until > now => {
+            // Round up so a lockout never reports 0 seconds remaining
+            let remaining = (locked_until - now).num_milliseconds();
+            Some(((remaining + 999) / 1000) as u64)
+        }
+        _ => None,
+    }
+}
+
+/// Auth state of the user with the given username, None if the username is unknown
+pub fn find_auth_state_by_username(
+    connection: &StorageConnection,
+    username: &str,
+) -> Result<Option<UserAuthStateRow>, RepositoryError> {
+    let Some(user) = UserAccountRowRepository::new(connection).find_one_by_user_name(username)?
+    else {
+        return Ok(None);
+    };
+    UserAuthStateRowRepository::new(connection)
+        .find_or_default(&user.id)
+        .map(Some)
+}
+
+/// Records a failed login attempt and locks the account once the policy limit is reached.
+/// Attempts for unknown usernames can't be linked to a user and go to the system log instead,
+/// see [UNKNOWN_USERNAME_LOG_INTERVAL_MINUTES].
+pub fn record_failed_login(
+    connection: &StorageConnection,
+    username: &str,
+    reason: FailedLoginReason,
+) -> Result<(), RepositoryError> {
+    let now = Utc::now().naive_utc();
+    let Some(mut auth_state) = find_auth_state_by_username(connection, username)? else {
+        let last_logged = SystemLogRowRepository::new(connection)
+            .find_latest_by_type(SystemLogType::LoginFailed)?
+            .map(|log| log.datetime);
+        let interval = Duration::minutes(UNKNOWN_USERNAME_LOG_INTERVAL_MINUTES);
+        if !last_logged.is_some_and(|last_logged| last_logged + interval > now) {
+            system_log_entry(
+                connection,
+                SystemLogType::LoginFailed,
+                &format!(
+                    "Failed login attempt for unknown username {}, further attempts in the next {} minutes are not logged",
+                    username, UNKNOWN_USERNAME_LOG_INTERVAL_MINUTES
+                ),
+            )?;
+        }
+        return Ok(());
+    };
+    let user_id = auth_state.user_id.clone();
+
+    user_activity_log_entry(
+        connection,
+        ActivityLogType::UserLoginFailed,
+        &user_id,
+        None,
+        Some(reason.to_log_value()),
+    )?;
+
+    if !reason.counts_towards_lockout() {
+        return Ok(());
+    }
+
+    let policy = get_auth_policy(connection)?;
+    // Failed logins older than the lockout duration no longer count towards the lockout
+    let reset_after = Duration::minutes(policy.lockout_duration_minutes as i64);
+    if auth_state
+        .last_failed_login_datetime
+        .is_some_and(|last_failed| last_failed + reset_after <= now)
+    {
+        auth_state.failed_login_count = 0;
+    }
+    auth_state.failed_login_count += 1;
+    auth_state.last_failed_login_datetime = Some(now);
+
+    if policy.max_failed_logins > 0
+        && auth_state.failed_login_count >= policy.max_failed_logins as i32
+    {
+        let locked_until = now + Duration::minutes(policy.lockout_duration_minutes as i64);
+        auth_state.locked_until_datetime = Some(locked_until);
+        auth_state.failed_login_count = 0;
+        user_activity_log_entry(
+            connection,
+            ActivityLogType::UserAccountLocked,
+            &user_id,
+            None,
+            Some(locked_until.to_string()),
+        )?;
+    }
+
+    UserAuthStateRowRepository::new(connection).upsert_one(&auth_state)
+}
+
+/// Resets the failed login count after a successful login
+pub fn record_successful_login(
+    connection: &StorageConnection,
+    user_id: &str,
+) -> Result<(), RepositoryError> {
+    let repo = UserAuthStateRowRepository::new(connection);
+    let Some(mut auth_state) = repo.find_one_by_user_id(user_id)? else {
+        return Ok(());
+    };
+    if auth_state.failed_login_count == 0 && auth_state.locked_until_datetime.is_none() {
+        return Ok(());
+    }
+
+    auth_state.failed_login_count = 0;
+    auth_state.locked_until_datetime = None;
+    repo.upsert_one(&auth_state)
+}
+
+/// Lifts the lockout of a user before it expires
+pub fn unlock_user_account(
+    ctx: &ServiceContext,
+    user_id: &str,
+) -> Result<(), UnlockUserAccountError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            if UserAccountRowRepository::new(connection)
+                .find_one_by_id(user_id)?
+                .is_none()
+            {
+                return Err(UnlockUserAccountError::UserDoesNotExist);
+            }
+
+            let repo = UserAuthStateRowRepository::new(connection);
+            let mut auth_state = repo.find_or_default(user_id)?;
+            if lockout_remaining(&auth_state, Utc::now().naive_utc()).is_none() {
+                return Err(UnlockUserAccountError::UserAccountNotLocked);
+            }
+
+            auth_state.failed_login_count = 0;
+            auth_state.locked_until_datetime = None;
+            repo.upsert_one(&auth_state)?;
+
+            activity_log_entry(
+                ctx,
+                ActivityLogType::UserAccountUnlocked,
+                Some(user_id.to_string()),
+                None,
+                None,
+            )?;
+            Ok(())
+        })
+        .map_err(|error| error.to_inner_error())
+}
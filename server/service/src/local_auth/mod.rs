@@ -0,0 +1,194 @@
+use chrono::{Duration, NaiveDateTime};
+use repository::{
+    KeyType, KeyValueStoreRepository, RepositoryError, StorageConnection, UserAuthStateRow,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::service_provider::ServiceContext;
+
+use self::{
+    lockout::{unlock_user_account, UnlockUserAccountError},
+    password::{change_password, ChangePassword, ChangePasswordError},
+    totp::{
+        confirm_totp_enrolment, disable_totp, start_totp_enrolment, ConfirmTotpEnrolmentError,
+        DisableTotpError, StartTotpEnrolmentError, TotpEnrolment,
+    },
+};
+
+pub mod lockout;
+pub mod password;
+pub mod totp;
+
+#[cfg(test)]
+mod tests;
+
+/// Site wide policy for logins checked on this site
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthPolicy {
+    /// Consecutive failed logins before the account is locked, 0 disables the lockout
+    pub max_failed_logins: u32,
+    /// Also the window for counting failed logins, the count restarts when the last failed login
+    /// is older than this
+    pub lockout_duration_minutes: u32,
+    /// Only enforced for locally managed users, central manages the passwords of all other users
+    pub min_password_length: u32,
+    /// Passwords need an upper case letter, a lower case letter and a digit
+    pub require_mixed_characters: bool,
+    /// Days until the password of a locally managed user expires, 0 for no expiry
+    pub max_password_age_days: u32,
+}
+
+impl Default for AuthPolicy {
+    fn default() -> Self {
+        AuthPolicy {
+            max_failed_logins: 5,
+            lockout_duration_minutes: 15,
+            min_password_length: 8,
+            require_mixed_characters: false,
+            max_password_age_days: 0,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PasswordPolicyViolation {
+    TooShort { min_length: u32 },
+    MissingCharacterTypes,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpdateAuthPolicyError {
+    LockoutDurationRequired,
+    MinPasswordLengthRequired,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for UpdateAuthPolicyError {
+    fn from(error: RepositoryError) -> Self {
+        UpdateAuthPolicyError::DatabaseError(error)
+    }
+}
+
+impl AuthPolicy {
+    pub fn validate_password_strength(
+        &self,
+        password: &str,
+    ) -> Result<(), PasswordPolicyViolation> {
+        if (password.chars().count() as u32) < self.min_password_length {
+            return Err(PasswordPolicyViolation::TooShort {
+                min_length: self.min_password_length,
+            });
+        }
+
+        if self.require_mixed_characters {
+            let has_upper = password.chars().any(char::is_uppercase);
+            let has_lower = password.chars().any(char::is_lowercase);
+            let has_digit = password.chars().any(|c| c.is_ascii_digit());
+            if !(has_upper && has_lower && has_digit) {
+                return Err(PasswordPolicyViolation::MissingCharacterTypes);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn is_password_expired(&self, auth_state: &UserAuthStateRow, now: NaiveDateTime) -> bool {
+        if !auth_state.is_locally_managed || self.max_password_age_days == 0 {
+            return false;
+        }
+        match auth_state.password_changed_datetime {
+            Some(changed) => changed + Duration::days(self.max_password_age_days as i64) < now,
+            // Password predates the policy, treat it as expired so it gets checked
+            None => true,
+        }
+    }
+}
+
+/// Loads the auth policy from the DB, falls back to the default policy if not set
+pub fn get_auth_policy(connection: &StorageConnection) -> Result<AuthPolicy, RepositoryError> {
+    let key_value_store = KeyValueStoreRepository::new(connection);
+
+    let auth_policy = match key_value_store.get_string(KeyType::SettingsAuthPolicy)? {
+        Some(value) => serde_json::from_str::<AuthPolicy>(&value).unwrap_or_default(),
+        None => AuthPolicy::default(),
+    };
+
+    Ok(auth_policy)
+}
+
+pub fn update_auth_policy(
+    ctx: &ServiceContext,
+    policy: AuthPolicy,
+) -> Result<AuthPolicy, UpdateAuthPolicyError> {
+    if policy.max_failed_logins > 0 && policy.lockout_duration_minutes == 0 {
+        return Err(UpdateAuthPolicyError::LockoutDurationRequired);
+    }
+    if policy.min_password_length == 0 {
+        return Err(UpdateAuthPolicyError::MinPasswordLengthRequired);
+    }
+
+    let serialised = serde_json::to_string(&policy)
+        .map_err(|error| RepositoryError::as_db_error(&error.to_string(), ""))?;
+    KeyValueStoreRepository::new(&ctx.connection)
+        .set_string(KeyType::SettingsAuthPolicy, Some(serialised))?;
+
+    Ok(policy)
+}
+
+pub trait LocalAuthServiceTrait: Sync + Send {
+    fn get_auth_policy(&self, ctx: &ServiceContext) -> Result<AuthPolicy, RepositoryError> {
+        get_auth_policy(&ctx.connection)
+    }
+
+    fn update_auth_policy(
+        &self,
+        ctx: &ServiceContext,
+        policy: AuthPolicy,
+    ) -> Result<AuthPolicy, UpdateAuthPolicyError> {
+        update_auth_policy(ctx, policy)
+    }
+
+    fn unlock_user_account(
+        &self,
+        ctx: &ServiceContext,
+        user_id: &str,
+    ) -> Result<(), UnlockUserAccountError> {
+        unlock_user_account(ctx, user_id)
+    }
+
+    fn change_password(
+        &self,
+        ctx: &ServiceContext,
+        input: ChangePassword,
+    ) -> Result<(), ChangePasswordError> {
+        change_password(ctx, input)
+    }
+
+    fn start_totp_enrolment(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<TotpEnrolment, StartTotpEnrolmentError> {
+        start_totp_enrolment(ctx)
+    }
+
+    fn confirm_totp_enrolment(
+        &self,
+        ctx: &ServiceContext,
+        code: &str,
+    ) -> Result<(), ConfirmTotpEnrolmentError> {
+        confirm_totp_enrolment(ctx, code)
+    }
+
+    fn disable_totp(
+        &self,
+        ctx: &ServiceContext,
+        user_id: &str,
+        code: Option<&str>,
+    ) -> Result<(), DisableTotpError> {
+        disable_totp(ctx, user_id, code)
+    }
+}
+
+pub struct LocalAuthService {}
+impl LocalAuthServiceTrait for LocalAuthService {}
@@ -0,0 +1,140 @@
+use bcrypt::BcryptError;
+use chrono::Utc;
+use repository::{
+    ActivityLogType, RepositoryError, UserAccountRow, UserAccountRowRepository,
+    UserAuthStateRowRepository,
+};
+
+use crate::{
+    activity_log::user_activity_log_entry,
+    service_provider::ServiceContext,
+    user_account::{UserAccountService, VerifyPasswordError},
+};
+
+use super::{
+    get_auth_policy,
+    lockout::{
+        find_auth_state_by_username, lockout_remaining, record_failed_login, FailedLoginReason,
+    },
+    totp::verify_totp_code_now,
+    PasswordPolicyViolation,
+};
+
+/// Doesn't require a logged in user so that users with an expired password can change it, the
+/// same credentials as for a login are required instead
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangePassword {
+    pub username: String,
+    pub current_password: String,
+    pub new_password: String,
+    /// Required if the user has a confirmed TOTP second factor
+    pub totp_code: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ChangePasswordError {
+    InvalidCredentials,
+    TotpRequired,
+    InvalidTotpCode,
+    AccountBlocked(u64),
+    /// Password is managed by the central server
+    NotLocallyManaged,
+    PasswordUnchanged,
+    PasswordPolicyViolation(PasswordPolicyViolation),
+    PasswordHashError(BcryptError),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for ChangePasswordError {
+    fn from(error: RepositoryError) -> Self {
+        ChangePasswordError::DatabaseError(error)
+    }
+}
+
+pub fn change_password(
+    ctx: &ServiceContext,
+    input: ChangePassword,
+) -> Result<(), ChangePasswordError> {
+    let connection = &ctx.connection;
+    let now = Utc::now().naive_utc();
+
+    let locked = find_auth_state_by_username(connection, &input.username)?
+        .and_then(|auth_state| lockout_remaining(&auth_state, now));
+    if let Some(timeout_remaining) = locked {
+        record_failed_login(
+            connection,
+            &input.username,
+            FailedLoginReason::AccountLocked,
+        )?;
+        return Err(ChangePasswordError::AccountBlocked(timeout_remaining));
+    }
+
+    let user_account = match UserAccountService::new(connection)
+        .verify_password(&input.username, &input.current_password)
+    {
+        Ok(user_account) => user_account,
+        Err(VerifyPasswordError::DatabaseError(error)) => return Err(error.into()),
+        Err(VerifyPasswordError::InvalidCredentialsBackend(error)) => {
+            return Err(ChangePasswordError::PasswordHashError(error))
+        }
+        Err(_) => {
+            record_failed_login(
+                connection,
+                &input.username,
+                FailedLoginReason::InvalidCredentials,
+            )?;
+            return Err(ChangePasswordError::InvalidCredentials);
+        }
+    };
+
+    let auth_state_repo = UserAuthStateRowRepository::new(connection);
+    let mut auth_state = auth_state_repo.find_or_default(&user_account.id)?;
+    if let Some(secret) = auth_state.enabled_totp_secret() {
+        let Some(totp_code) = &input.totp_code else {
+            return Err(ChangePasswordError::TotpRequired);
+        };
+        if !verify_totp_code_now(secret, totp_code) {
+            record_failed_login(
+                connection,
+                &input.username,
+                FailedLoginReason::InvalidTotpCode,
+            )?;
+            return Err(ChangePasswordError::InvalidTotpCode);
+        }
+    }
+    if !auth_state.is_locally_managed {
+        return Err(ChangePasswordError::NotLocallyManaged);
+    }
+    if input.new_password == input.current_password {
+        return Err(ChangePasswordError::PasswordUnchanged);
+    }
+    get_auth_policy(connection)?
+        .validate_password_strength(&input.new_password)
+        .map_err(ChangePasswordError::PasswordPolicyViolation)?;
+
+    let hashed_password = UserAccountService::hash_password(&input.new_password)
+        .map_err(ChangePasswordError::PasswordHashError)?;
+
+    connection
+        .transaction_sync(|connection| {
+            UserAccountRowRepository::new(connection).upsert_one(&UserAccountRow {
+                hashed_password,
+                ..user_account.clone()
+            })?;
+
+            auth_state.password_changed_datetime = Some(now);
+            auth_state.failed_login_count = 0;
+            UserAuthStateRowRepository::new(connection).upsert_one(&auth_state)?;
+
+            user_activity_log_entry(
+                connection,
+                ActivityLogType::UserPasswordChanged,
+                &user_account.id,
+                None,
+                None,
+            )
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(())
+}
@@ -0,0 +1,389 @@
+use std::sync::{Arc, RwLock};
+
+use chrono::{Duration, Utc};
+use repository::{
+    mock::{mock_store_a, mock_user_account_a, MockDataInserts},
+    system_log_row::{SystemLogRowRepository, SystemLogType},
+    test_db::setup_all,
+    ActivityLogRowRepository, ActivityLogType, KeyType, KeyValueStoreRepository, UserAccountRow,
+    UserAccountRowRepository, UserAuthStateRow, UserAuthStateRowRepository,
+};
+use util::assert_matches;
+
+use crate::{
+    auth_data::AuthData,
+    local_auth::{
+        lockout::{record_failed_login, FailedLoginReason, UnlockUserAccountError},
+        password::{ChangePassword, ChangePasswordError},
+        totp::{generate_totp_code, verify_totp_code, ConfirmTotpEnrolmentError},
+        AuthPolicy, PasswordPolicyViolation, UpdateAuthPolicyError,
+    },
+    login::{LoginError, LoginFailure, LoginInput, LoginService},
+    service_provider::ServiceProvider,
+    token_bucket::TokenBucket,
+    user_account::UserAccountService,
+};
+
+#[test]
+fn totp_codes() {
+    // RFC 6238 test vectors (SHA1), truncated to 6 digits
+    let secret = b"12345678901234567890";
+    assert_eq!(generate_totp_code(secret, 59), "287082");
+    assert_eq!(generate_totp_code(secret, 1111111109), "081804");
+    assert_eq!(generate_totp_code(secret, 1111111111), "050471");
+    assert_eq!(generate_totp_code(secret, 1234567890), "005924");
+    assert_eq!(generate_totp_code(secret, 2000000000), "279037");
+
+    // Base32 of the secret above
+    let encoded = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+    assert!(verify_totp_code(encoded, "081804", 1111111109));
+    // Lower case and spaces are accepted, previous and next step are accepted
+    assert!(verify_totp_code(
+        "gezd gnbv gy3t qojq gezd gnbv gy3t qojq",
+        "081804",
+        1111111109 + 30
+    ));
+    assert!(!verify_totp_code(encoded, "081804", 1111111109 + 90));
+    assert!(!verify_totp_code(encoded, "000000", 1111111109));
+    assert!(!verify_totp_code("not base32!", "081804", 1111111109));
+}
+
+#[test]
+fn password_policy() {
+    let policy = AuthPolicy {
+        min_password_length: 8,
+        require_mixed_characters: true,
+        ..AuthPolicy::default()
+    };
+
+    assert_eq!(
+        policy.validate_password_strength("Short1"),
+        Err(PasswordPolicyViolation::TooShort { min_length: 8 })
+    );
+    assert_eq!(
+        policy.validate_password_strength("alllowercase1"),
+        Err(PasswordPolicyViolation::MissingCharacterTypes)
+    );
+    assert_eq!(
+        policy.validate_password_strength("NoDigitsHere"),
+        Err(PasswordPolicyViolation::MissingCharacterTypes)
+    );
+    assert_eq!(policy.validate_password_strength("Mixed1Case"), Ok(()));
+
+    let now = Utc::now().naive_utc();
+    let policy = AuthPolicy {
+        max_password_age_days: 30,
+        ..AuthPolicy::default()
+    };
+    let auth_state = UserAuthStateRow {
+        is_locally_managed: true,
+        password_changed_datetime: Some(now - Duration::days(31)),
+        ..UserAuthStateRow::new("user")
+    };
+    assert!(policy.is_password_expired(&auth_state, now));
+    // Passwords of users managed by central don't expire on this site
+    assert!(!policy.is_password_expired(
+        &UserAuthStateRow {
+            is_locally_managed: false,
+            ..auth_state.clone()
+        },
+        now
+    ));
+    assert!(!AuthPolicy::default().is_password_expired(&auth_state, now));
+}
+
+#[actix_rt::test]
+async fn local_auth_login() {
+    let (_, connection, connection_manager, _) = setup_all(
+        "local_auth_login",
+        MockDataInserts::none()
+            .names()
+            .stores()
+            .user_accounts()
+            .user_store_joins(),
+    )
+    .await;
+
+    let user = mock_user_account_a();
+    let password = "Password1";
+    UserAccountRowRepository::new(&connection)
+        .upsert_one(&UserAccountRow {
+            hashed_password: UserAccountService::hash_password(password).unwrap(),
+            ..user.clone()
+        })
+        .unwrap();
+    UserAuthStateRowRepository::new(&connection)
+        .upsert_one(&UserAuthStateRow {
+            is_locally_managed: true,
+            password_changed_datetime: Some(Utc::now().naive_utc()),
+            ..UserAuthStateRow::new(&user.id)
+        })
+        .unwrap();
+    KeyValueStoreRepository::new(&connection)
+        .set_i32(KeyType::SettingsSyncSiteId, Some(mock_store_a().site_id))
+        .unwrap();
+
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider.basic_context().unwrap();
+    let service = &service_provider.local_auth_service;
+    let auth_data = AuthData {
+        auth_token_secret: "secret".to_string(),
+        token_bucket: Arc::new(RwLock::new(TokenBucket::new())),
+        no_ssl: true,
+        debug_no_access_control: false,
+    };
+    let login = |password: &str, totp_code: Option<String>| {
+        LoginService::login(
+            &service_provider,
+            &auth_data,
+            LoginInput {
+                username: user.username.clone(),
+                password: password.to_string(),
+                // Locally managed users never reach central
+                central_server_url: "http://localhost:0".to_string(),
+                totp_code,
+            },
+            0,
+        )
+    };
+
+    assert_eq!(
+        service.update_auth_policy(
+            &context,
+            AuthPolicy {
+                lockout_duration_minutes: 0,
+                ..AuthPolicy::default()
+            }
+        ),
+        Err(UpdateAuthPolicyError::LockoutDurationRequired)
+    );
+    service
+        .update_auth_policy(
+            &context,
+            AuthPolicy {
+                max_failed_logins: 2,
+                ..AuthPolicy::default()
+            },
+        )
+        .unwrap();
+
+    // Lockout after two failed logins, even the correct password is rejected
+    for _ in 0..2 {
+        assert_matches!(
+            login("wrong", None).await,
+            Err(LoginError::LoginFailure(LoginFailure::InvalidCredentials))
+        );
+    }
+    assert_matches!(
+        login(password, None).await,
+        Err(LoginError::LoginFailure(LoginFailure::AccountBlocked(_)))
+    );
+    let logs = ActivityLogRowRepository::new(&connection)
+        .find_many_by_record_id(&user.id)
+        .unwrap();
+    assert_eq!(
+        logs.iter()
+            .filter(|log| log.r#type == ActivityLogType::UserLoginFailed)
+            .count(),
+        3
+    );
+    assert!(logs
+        .iter()
+        .any(|log| log.r#type == ActivityLogType::UserAccountLocked));
+
+    service.unlock_user_account(&context, &user.id).unwrap();
+    assert_eq!(
+        service.unlock_user_account(&context, &user.id),
+        Err(UnlockUserAccountError::UserAccountNotLocked)
+    );
+    login(password, None).await.unwrap();
+
+    // TOTP second factor
+    let user_context = service_provider
+        .context("".to_string(), user.id.clone())
+        .unwrap();
+    let enrolment = service.start_totp_enrolment(&user_context).unwrap();
+    assert!(enrolment.uri.starts_with("otpauth://totp/"));
+    // Not enforced until confirmed
+    login(password, None).await.unwrap();
+    assert_eq!(
+        service.confirm_totp_enrolment(&user_context, "invalid"),
+        Err(ConfirmTotpEnrolmentError::InvalidTotpCode)
+    );
+    let secret = data_encoding::BASE32_NOPAD
+        .decode(enrolment.secret.as_bytes())
+        .unwrap();
+    let code = || generate_totp_code(&secret, Utc::now().timestamp() as u64);
+    service
+        .confirm_totp_enrolment(&user_context, &code())
+        .unwrap();
+
+    assert_matches!(
+        login(password, None).await,
+        Err(LoginError::LoginFailure(LoginFailure::TotpRequired))
+    );
+    assert_matches!(
+        login(password, Some("invalid".to_string())).await,
+        Err(LoginError::LoginFailure(LoginFailure::InvalidTotpCode))
+    );
+    login(password, Some(code())).await.unwrap();
+
+    // Changing the password needs the second factor as well
+    let totp_change_password = ChangePassword {
+        username: user.username.clone(),
+        current_password: password.to_string(),
+        new_password: "NewPassword1".to_string(),
+        totp_code: None,
+    };
+    assert_matches!(
+        service.change_password(&context, totp_change_password.clone()),
+        Err(ChangePasswordError::TotpRequired)
+    );
+    assert_matches!(
+        service.change_password(
+            &context,
+            ChangePassword {
+                totp_code: Some("invalid".to_string()),
+                ..totp_change_password
+            }
+        ),
+        Err(ChangePasswordError::InvalidTotpCode)
+    );
+
+    service.disable_totp(&context, &user.id, None).unwrap();
+    login(password, None).await.unwrap();
+
+    // Password expiry and change
+    service
+        .update_auth_policy(
+            &context,
+            AuthPolicy {
+                max_password_age_days: 30,
+                require_mixed_characters: true,
+                ..AuthPolicy::default()
+            },
+        )
+        .unwrap();
+    let auth_state = UserAuthStateRowRepository::new(&connection)
+        .find_one_by_user_id(&user.id)
+        .unwrap()
+        .unwrap();
+    UserAuthStateRowRepository::new(&connection)
+        .upsert_one(&UserAuthStateRow {
+            password_changed_datetime: Some(Utc::now().naive_utc() - Duration::days(31)),
+            ..auth_state
+        })
+        .unwrap();
+    assert_matches!(
+        login(password, None).await,
+        Err(LoginError::LoginFailure(LoginFailure::PasswordExpired))
+    );
+
+    let change_password = ChangePassword {
+        username: user.username.clone(),
+        current_password: password.to_string(),
+        new_password: "NewPassword2".to_string(),
+        totp_code: None,
+    };
+    assert_matches!(
+        service.change_password(
+            &context,
+            ChangePassword {
+                current_password: "wrong".to_string(),
+                ..change_password.clone()
+            }
+        ),
+        Err(ChangePasswordError::InvalidCredentials)
+    );
+    assert_matches!(
+        service.change_password(
+            &context,
+            ChangePassword {
+                new_password: "weakpassword".to_string(),
+                ..change_password.clone()
+            }
+        ),
+        Err(ChangePasswordError::PasswordPolicyViolation(
+            PasswordPolicyViolation::MissingCharacterTypes
+        ))
+    );
+    service.change_password(&context, change_password).unwrap();
+    assert_matches!(
+        login(password, None).await,
+        Err(LoginError::LoginFailure(LoginFailure::InvalidCredentials))
+    );
+    login("NewPassword2", None).await.unwrap();
+}
+
+#[actix_rt::test]
+async fn local_auth_failed_logins() {
+    let (_, connection, connection_manager, _) = setup_all(
+        "local_auth_failed_logins",
+        MockDataInserts::none()
+            .names()
+            .stores()
+            .user_accounts()
+            .user_store_joins(),
+    )
+    .await;
+
+    let user = mock_user_account_a();
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider.basic_context().unwrap();
+    service_provider
+        .local_auth_service
+        .update_auth_policy(
+            &context,
+            AuthPolicy {
+                max_failed_logins: 2,
+                lockout_duration_minutes: 15,
+                ..AuthPolicy::default()
+            },
+        )
+        .unwrap();
+    let auth_state_repo = UserAuthStateRowRepository::new(&connection);
+    let failed_login = |username: &str| {
+        record_failed_login(&connection, username, FailedLoginReason::InvalidCredentials).unwrap()
+    };
+
+    // Failed login older than the lockout duration doesn't count
+    failed_login(&user.username);
+    let auth_state = auth_state_repo
+        .find_one_by_user_id(&user.id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(auth_state.failed_login_count, 1);
+    auth_state_repo
+        .upsert_one(&UserAuthStateRow {
+            last_failed_login_datetime: Some(Utc::now().naive_utc() - Duration::minutes(16)),
+            ..auth_state
+        })
+        .unwrap();
+    failed_login(&user.username);
+    let auth_state = auth_state_repo
+        .find_one_by_user_id(&user.id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(auth_state.failed_login_count, 1);
+    assert_eq!(auth_state.locked_until_datetime, None);
+
+    failed_login(&user.username);
+    let auth_state = auth_state_repo
+        .find_one_by_user_id(&user.id)
+        .unwrap()
+        .unwrap();
+    assert!(auth_state.locked_until_datetime.is_some());
+
+    // Unknown usernames are only logged once per interval
+    failed_login("unknown_user_1");
+    failed_login("unknown_user_2");
+    let logs = SystemLogRowRepository::new(&connection)
+        .last_x_messages(100)
+        .unwrap();
+    assert_eq!(
+        logs.iter()
+            .filter(|log| log.r#type == SystemLogType::LoginFailed)
+            .count(),
+        1
+    );
+}
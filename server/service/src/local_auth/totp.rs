@@ -0,0 +1,218 @@
+use chrono::Utc;
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use repository::{
+    ActivityLogType, RepositoryError, UserAccountRowRepository, UserAuthStateRowRepository,
+};
+use sha1::Sha1;
+
+use crate::{activity_log::activity_log_entry, service_provider::ServiceContext};
+
+/// Time based one time passwords (RFC 6238) as used by common authenticator apps
+const TOTP_STEP_SECONDS: u64 = 30;
+const TOTP_DIGITS: u32 = 6;
+/// Number of steps before and after the current step that are still accepted, allows for clock
+/// drift between the server and the authenticator
+const TOTP_ALLOWED_STEP_DRIFT: u64 = 1;
+const TOTP_SECRET_BYTES: usize = 20;
+const TOTP_ISSUER: &str = "omSupply";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TotpEnrolment {
+    /// Base32 encoded secret, for manual entry in the authenticator app
+    pub secret: String,
+    /// otpauth:// uri, usually shown as a QR code
+    pub uri: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum StartTotpEnrolmentError {
+    UserDoesNotExist,
+    TotpAlreadyEnabled,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ConfirmTotpEnrolmentError {
+    NoTotpEnrolmentStarted,
+    TotpAlreadyEnabled,
+    InvalidTotpCode,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DisableTotpError {
+    TotpNotEnabled,
+    InvalidTotpCode,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for StartTotpEnrolmentError {
+    fn from(error: RepositoryError) -> Self {
+        StartTotpEnrolmentError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for ConfirmTotpEnrolmentError {
+    fn from(error: RepositoryError) -> Self {
+        ConfirmTotpEnrolmentError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for DisableTotpError {
+    fn from(error: RepositoryError) -> Self {
+        DisableTotpError::DatabaseError(error)
+    }
+}
+
+/// HOTP value (RFC 4226) for the given counter
+pub fn generate_hotp_code(secret: &[u8], counter: u64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC can take a key of any length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    // Dynamic truncation
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    )
+}
+
+pub fn generate_totp_code(secret: &[u8], unix_time: u64) -> String {
+    generate_hotp_code(secret, unix_time / TOTP_STEP_SECONDS)
+}
+
+fn decode_secret(secret: &str) -> Option<Vec<u8>> {
+    let normalised: String = secret
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '=')
+        .collect::<String>()
+        .to_uppercase();
+    BASE32_NOPAD.decode(normalised.as_bytes()).ok()
+}
+
+/// Checks a code against the base32 encoded secret
+pub fn verify_totp_code(secret: &str, code: &str, unix_time: u64) -> bool {
+    let Some(secret) = decode_secret(secret) else {
+        return false;
+    };
+    let code = code.trim();
+    let step = unix_time / TOTP_STEP_SECONDS;
+
+    (step.saturating_sub(TOTP_ALLOWED_STEP_DRIFT)..=step + TOTP_ALLOWED_STEP_DRIFT)
+        .any(|counter| generate_hotp_code(&secret, counter) == code)
+}
+
+pub(crate) fn verify_totp_code_now(secret: &str, code: &str) -> bool {
+    verify_totp_code(secret, code, Utc::now().timestamp() as u64)
+}
+
+fn totp_uri(username: &str, secret: &str) -> String {
+    let label: String =
+        url::form_urlencoded::byte_serialize(format!("{TOTP_ISSUER}:{username}").as_bytes())
+            .collect();
+    format!(
+        "otpauth://totp/{label}?secret={secret}&issuer={TOTP_ISSUER}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_STEP_SECONDS}"
+    )
+}
+
+/// Generates a new secret for the logged in user. TOTP is only enforced once the enrolment is
+/// confirmed with a code from the authenticator app.
+pub fn start_totp_enrolment(
+    ctx: &ServiceContext,
+) -> Result<TotpEnrolment, StartTotpEnrolmentError> {
+    let user = UserAccountRowRepository::new(&ctx.connection)
+        .find_one_by_id(&ctx.user_id)?
+        .ok_or(StartTotpEnrolmentError::UserDoesNotExist)?;
+
+    let repo = UserAuthStateRowRepository::new(&ctx.connection);
+    let mut auth_state = repo.find_or_default(&user.id)?;
+    if auth_state.is_totp_enabled() {
+        return Err(StartTotpEnrolmentError::TotpAlreadyEnabled);
+    }
+
+    let bytes: [u8; TOTP_SECRET_BYTES] = rand::thread_rng().gen();
+    let secret = BASE32_NOPAD.encode(&bytes);
+    auth_state.totp_secret = Some(secret.clone());
+    auth_state.totp_confirmed_datetime = None;
+    repo.upsert_one(&auth_state)?;
+
+    Ok(TotpEnrolment {
+        uri: totp_uri(&user.username, &secret),
+        secret,
+    })
+}
+
+pub fn confirm_totp_enrolment(
+    ctx: &ServiceContext,
+    code: &str,
+) -> Result<(), ConfirmTotpEnrolmentError> {
+    let repo = UserAuthStateRowRepository::new(&ctx.connection);
+    let mut auth_state = repo
+        .find_one_by_user_id(&ctx.user_id)?
+        .ok_or(ConfirmTotpEnrolmentError::NoTotpEnrolmentStarted)?;
+    if auth_state.is_totp_enabled() {
+        return Err(ConfirmTotpEnrolmentError::TotpAlreadyEnabled);
+    }
+    let Some(secret) = &auth_state.totp_secret else {
+        return Err(ConfirmTotpEnrolmentError::NoTotpEnrolmentStarted);
+    };
+    if !verify_totp_code_now(secret, code) {
+        return Err(ConfirmTotpEnrolmentError::InvalidTotpCode);
+    }
+
+    auth_state.totp_confirmed_datetime = Some(Utc::now().naive_utc());
+    repo.upsert_one(&auth_state)?;
+
+    activity_log_entry(
+        ctx,
+        ActivityLogType::UserTotpEnabled,
+        Some(ctx.user_id.clone()),
+        None,
+        None,
+    )?;
+    Ok(())
+}
+
+/// Removes the second factor of a user. Users disabling their own second factor need to provide
+/// a current code, `code` is None when a server admin resets it, e.g. for a lost device.
+pub fn disable_totp(
+    ctx: &ServiceContext,
+    user_id: &str,
+    code: Option<&str>,
+) -> Result<(), DisableTotpError> {
+    let repo = UserAuthStateRowRepository::new(&ctx.connection);
+    let mut auth_state = repo
+        .find_one_by_user_id(user_id)?
+        .filter(|auth_state| auth_state.is_totp_enabled())
+        .ok_or(DisableTotpError::TotpNotEnabled)?;
+
+    if let (Some(code), Some(secret)) = (code, &auth_state.totp_secret) {
+        if !verify_totp_code_now(secret, code) {
+            return Err(DisableTotpError::InvalidTotpCode);
+        }
+    }
+
+    auth_state.totp_secret = None;
+    auth_state.totp_confirmed_datetime = None;
+    repo.upsert_one(&auth_state)?;
+
+    activity_log_entry(
+        ctx,
+        ActivityLogType::UserTotpDisabled,
+        Some(user_id.to_string()),
+        None,
+        None,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use repository::{
+    item_variant::item_variant::{ItemVariantFilter, ItemVariantRepository},
+    ColdStorageTypeFilter, ColdStorageTypeRepository, ColdStorageTypeRow, EqualFilter,
+    LocationFilter, LocationRepository, Pagination, RepositoryError, StorageConnection,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ColdChainWarning {
+    /// The item variant needs cold storage but the location has no cold storage type
+    LocationIsNotColdStorage { required: ColdStorageTypeRow },
+    /// The temperature range of the location is not within the range required by the item variant
+    IncompatibleColdStorageType {
+        required: ColdStorageTypeRow,
+        location: ColdStorageTypeRow,
+    },
+}
+
+/// Item variant and location the stock is stored in
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ItemVariantAndLocation {
+    pub item_variant_id: String,
+    pub location_id: String,
+}
+
+/// Checks if stock of the item variant can be stored in the location, used to warn users when
+/// moving or receiving stock. Returns None if either the item variant or the location is not set
+/// or the item variant has no cold storage requirement.
+pub fn check_cold_chain_compatibility(
+    connection: &StorageConnection,
+    item_variant_id: Option<&str>,
+    location_id: Option<&str>,
+) -> Result<Option<ColdChainWarning>, RepositoryError> {
+    let (Some(item_variant_id), Some(location_id)) = (item_variant_id, location_id) else {
+        return Ok(None);
+    };
+
+    let warnings = check_cold_chain_compatibilities(
+        connection,
+        &[ItemVariantAndLocation {
+            item_variant_id: item_variant_id.to_string(),
+            location_id: location_id.to_string(),
+        }],
+    )?;
+    Ok(warnings.into_values().next())
+}
+
+/// Same as `check_cold_chain_compatibility` for many item variant and location pairs with a fixed
+/// number of queries, only pairs with a warning are returned
+pub fn check_cold_chain_compatibilities(
+    connection: &StorageConnection,
+    pairs: &[ItemVariantAndLocation],
+) -> Result<HashMap<ItemVariantAndLocation, ColdChainWarning>, RepositoryError> {
+    let item_variant_ids = pairs
+        .iter()
+        .map(|pair| pair.item_variant_id.clone())
+        .collect();
+    // Cold storage type id by item variant id
+    let required_types: HashMap<String, String> = ItemVariantRepository::new(connection)
+        .query_by_filter(ItemVariantFilter::new().id(EqualFilter::equal_any(item_variant_ids)))?
+        .into_iter()
+        .filter_map(|item_variant| {
+            let row = item_variant.item_variant_row;
+            Some((row.id, row.cold_storage_type_id?))
+        })
+        .collect();
+    if required_types.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let location_ids = pairs.iter().map(|pair| pair.location_id.clone()).collect();
+    // Cold storage type id by location id
+    let location_types: HashMap<String, String> = LocationRepository::new(connection)
+        .query(
+            Pagination::all(),
+            Some(LocationFilter::new().id(EqualFilter::equal_any(location_ids))),
+            None,
+        )?
+        .into_iter()
+        .filter_map(|location| {
+            let row = location.location_row;
+            Some((row.id, row.cold_storage_type_id?))
+        })
+        .collect();
+
+    let cold_storage_type_ids = required_types
+        .values()
+        .chain(location_types.values())
+        .cloned()
+        .collect();
+    let cold_storage_types: HashMap<String, ColdStorageTypeRow> =
+        ColdStorageTypeRepository::new(connection)
+            .query_by_filter(
+                ColdStorageTypeFilter::new().id(EqualFilter::equal_any(cold_storage_type_ids)),
+            )?
+            .into_iter()
+            .map(|cold_storage_type| {
+                let row = cold_storage_type.cold_storage_type_row;
+                (row.id.clone(), row)
+            })
+            .collect();
+    let cold_storage_type = |id: Option<&String>| id.and_then(|id| cold_storage_types.get(id));
+
+    let mut warnings = HashMap::new();
+    for pair in pairs {
+        let Some(required) = cold_storage_type(required_types.get(&pair.item_variant_id)) else {
+            continue;
+        };
+
+        let warning = match cold_storage_type(location_types.get(&pair.location_id)) {
+            None => ColdChainWarning::LocationIsNotColdStorage {
+                required: required.clone(),
+            },
+            Some(location) if !is_compatible(required, location) => {
+                ColdChainWarning::IncompatibleColdStorageType {
+                    required: required.clone(),
+                    location: location.clone(),
+                }
+            }
+            Some(_) => continue,
+        };
+        warnings.insert(pair.clone(), warning);
+    }
+
+    Ok(warnings)
+}
+
+pub(crate) fn is_compatible(required: &ColdStorageTypeRow, location: &ColdStorageTypeRow) -> bool {
+    required.id == location.id
+        || (location.min_temperature >= required.min_temperature
+            && location.max_temperature <= required.max_temperature)
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        item_variant::item_variant_row::{ItemVariantRow, ItemVariantRowRepository},
+        mock::{mock_item_a, mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        ColdStorageTypeRow, ColdStorageTypeRowRepository, LocationRow, LocationRowRepository,
+    };
+
+    use super::{
+        check_cold_chain_compatibilities, check_cold_chain_compatibility, ColdChainWarning,
+        ItemVariantAndLocation,
+    };
+
+    #[actix_rt::test]
+    async fn cold_chain_compatibility() {
+        let cold_storage_type =
+            |id: &str, min_temperature: f64, max_temperature: f64| ColdStorageTypeRow {
+                id: id.to_string(),
+                name: id.to_string(),
+                min_temperature,
+                max_temperature,
+            };
+        let fridge = cold_storage_type("fridge", 2.0, 8.0);
+        let cool_room = cold_storage_type("cool_room", 4.0, 6.0);
+        let freezer = cold_storage_type("freezer", -25.0, -15.0);
+
+        let location = |id: &str, cold_storage_type: Option<&ColdStorageTypeRow>| LocationRow {
+            id: id.to_string(),
+            code: id.to_string(),
+            store_id: mock_store_a().id,
+            cold_storage_type_id: cold_storage_type.map(|row| row.id.clone()),
+            ..Default::default()
+        };
+        let item_variant =
+            |id: &str, cold_storage_type: Option<&ColdStorageTypeRow>| ItemVariantRow {
+                id: id.to_string(),
+                item_link_id: mock_item_a().id,
+                cold_storage_type_id: cold_storage_type.map(|row| row.id.clone()),
+                ..Default::default()
+            };
+
+        let (_, connection, _, _) = setup_all(
+            "cold_chain_compatibility",
+            MockDataInserts::none().names().stores().units().items(),
+        )
+        .await;
+
+        let repo = ColdStorageTypeRowRepository::new(&connection);
+        for row in [&fridge, &cool_room, &freezer] {
+            repo.upsert_one(row).unwrap();
+        }
+        let repo = ItemVariantRowRepository::new(&connection);
+        repo.upsert_one(&item_variant("vaccine", Some(&fridge)))
+            .unwrap();
+        repo.upsert_one(&item_variant("tablets", None)).unwrap();
+        let repo = LocationRowRepository::new(&connection);
+        for row in [
+            location("shelf", None),
+            location("fridge", Some(&fridge)),
+            location("cool_room", Some(&cool_room)),
+            location("freezer", Some(&freezer)),
+        ] {
+            repo.upsert_one(&row).unwrap();
+        }
+
+        let check = |item_variant_id: &str, location_id: &str| {
+            check_cold_chain_compatibility(&connection, Some(item_variant_id), Some(location_id))
+                .unwrap()
+        };
+
+        assert_eq!(check("tablets", "freezer"), None);
+        assert_eq!(check("vaccine", "fridge"), None);
+        // Range within the required range
+        assert_eq!(check("vaccine", "cool_room"), None);
+        assert_eq!(
+            check("vaccine", "shelf"),
+            Some(ColdChainWarning::LocationIsNotColdStorage {
+                required: fridge.clone()
+            })
+        );
+        assert_eq!(
+            check("vaccine", "freezer"),
+            Some(ColdChainWarning::IncompatibleColdStorageType {
+                required: fridge.clone(),
+                location: freezer.clone()
+            })
+        );
+        assert_eq!(
+            check_cold_chain_compatibility(&connection, Some("vaccine"), None).unwrap(),
+            None
+        );
+
+        // Batched, only pairs with a warning are returned
+        let pair = |item_variant_id: &str, location_id: &str| ItemVariantAndLocation {
+            item_variant_id: item_variant_id.to_string(),
+            location_id: location_id.to_string(),
+        };
+        let warnings = check_cold_chain_compatibilities(
+            &connection,
+            &[
+                pair("vaccine", "fridge"),
+                pair("vaccine", "shelf"),
+                pair("tablets", "shelf"),
+                pair("vaccine", "freezer"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(
+            warnings.get(&pair("vaccine", "shelf")),
+            check("vaccine", "shelf").as_ref()
+        );
+        assert_eq!(
+            warnings.get(&pair("vaccine", "freezer")),
+            check("vaccine", "freezer").as_ref()
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use super::validate::check_location_exists;
+use super::validate::{check_location_exists, check_location_has_no_child_locations};
 use crate::service_provider::ServiceContext;
 use repository::EqualFilter;
 use repository::{
@@ -16,6 +16,7 @@ pub enum DeleteLocationError {
     LocationDoesNotExist,
     LocationDoesNotBelongToCurrentStore,
     LocationInUse(LocationInUse),
+    LocationHasChildLocations,
     DatabaseError(RepositoryError),
 }
 
@@ -55,6 +56,9 @@ pub fn validate(
     if let Some(location_in_use) = check_location_in_use(&input.id, connection)? {
         return Err(DeleteLocationError::LocationInUse(location_in_use));
     }
+    if !check_location_has_no_child_locations(&input.id, connection)? {
+        return Err(DeleteLocationError::LocationHasChildLocations);
+    }
 
     Ok(())
 }
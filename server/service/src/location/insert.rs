@@ -1,4 +1,9 @@
-use super::{query::get_location, validate::check_location_code_is_unique};
+use super::{
+    query::get_location,
+    validate::{
+        check_capacity_is_valid, check_location_code_is_unique, check_parent_location_exists,
+    },
+};
 use crate::{service_provider::ServiceContext, SingleRecordError};
 use repository::EqualFilter;
 use repository::{
@@ -10,6 +15,8 @@ use repository::{
 pub enum InsertLocationError {
     LocationAlreadyExists,
     LocationWithCodeAlreadyExists,
+    ParentLocationDoesNotExist,
+    CapacityCannotBeNegative,
     CreatedRecordNotFound,
     DatabaseError(RepositoryError),
 }
@@ -20,6 +27,10 @@ pub struct InsertLocation {
     pub name: Option<String>,
     pub on_hold: Option<bool>,
     pub cold_storage_type_id: Option<String>,
+    pub parent_location_id: Option<String>,
+    pub volume_capacity: Option<f64>,
+    pub pallet_capacity: Option<i32>,
+    pub walk_sequence: Option<i32>,
 }
 
 pub fn insert_location(
@@ -29,7 +40,7 @@ pub fn insert_location(
     let location = ctx
         .connection
         .transaction_sync(|connection| {
-            validate(&input, connection)?;
+            validate(&input, &ctx.store_id, connection)?;
             let new_location = generate(&ctx.store_id, input);
             LocationRowRepository::new(connection).upsert_one(&new_location)?;
 
@@ -41,6 +52,7 @@ pub fn insert_location(
 
 pub fn validate(
     input: &InsertLocation,
+    store_id: &str,
     connection: &StorageConnection,
 ) -> Result<(), InsertLocationError> {
     if !check_location_does_not_exist(&input.id, connection)? {
@@ -49,6 +61,14 @@ pub fn validate(
     if !check_location_code_is_unique(&input.id, Some(input.code.clone()), connection)? {
         return Err(InsertLocationError::LocationWithCodeAlreadyExists);
     }
+    if let Some(parent_location_id) = &input.parent_location_id {
+        if !check_parent_location_exists(parent_location_id, store_id, connection)? {
+            return Err(InsertLocationError::ParentLocationDoesNotExist);
+        }
+    }
+    if !check_capacity_is_valid(input.volume_capacity, input.pallet_capacity) {
+        return Err(InsertLocationError::CapacityCannotBeNegative);
+    }
 
     Ok(())
 }
@@ -61,6 +81,10 @@ pub fn generate(
         name,
         on_hold,
         cold_storage_type_id,
+        parent_location_id,
+        volume_capacity,
+        pallet_capacity,
+        walk_sequence,
     }: InsertLocation,
 ) -> LocationRow {
     LocationRow {
@@ -70,6 +94,10 @@ pub fn generate(
         on_hold: on_hold.unwrap_or(false),
         store_id: store_id.to_string(),
         cold_storage_type_id,
+        parent_location_id,
+        volume_capacity,
+        pallet_capacity,
+        walk_sequence,
     }
 }
 
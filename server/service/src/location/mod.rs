@@ -1,19 +1,28 @@
+use std::collections::HashMap;
+
 use self::{
+    cold_chain::{
+        check_cold_chain_compatibilities, check_cold_chain_compatibility, ColdChainWarning,
+        ItemVariantAndLocation,
+    },
     delete::{delete_location, DeleteLocation, DeleteLocationError},
     insert::{insert_location, InsertLocation, InsertLocationError},
     query::{get_location, get_locations},
     update::{update_location, UpdateLocation, UpdateLocationError},
+    utilisation::{get_location_utilisation, LocationUtilisation},
 };
 
 use super::{ListError, ListResult};
 use crate::{service_provider::ServiceContext, SingleRecordError};
 use repository::location::{Location, LocationFilter, LocationSort};
-use repository::PaginationOption;
+use repository::{LocationRow, PaginationOption, RepositoryError};
 
+pub mod cold_chain;
 pub mod delete;
 pub mod insert;
 pub mod query;
 pub mod update;
+pub mod utilisation;
 mod validate;
 
 pub trait LocationServiceTrait: Sync + Send {
@@ -58,6 +67,31 @@ pub trait LocationServiceTrait: Sync + Send {
     ) -> Result<Location, UpdateLocationError> {
         update_location(ctx, input)
     }
+
+    fn get_location_utilisation(
+        &self,
+        ctx: &ServiceContext,
+        location: &LocationRow,
+    ) -> Result<LocationUtilisation, RepositoryError> {
+        get_location_utilisation(ctx, location)
+    }
+
+    fn check_cold_chain_compatibility(
+        &self,
+        ctx: &ServiceContext,
+        item_variant_id: Option<&str>,
+        location_id: Option<&str>,
+    ) -> Result<Option<ColdChainWarning>, RepositoryError> {
+        check_cold_chain_compatibility(&ctx.connection, item_variant_id, location_id)
+    }
+
+    fn check_cold_chain_compatibilities(
+        &self,
+        ctx: &ServiceContext,
+        pairs: &[ItemVariantAndLocation],
+    ) -> Result<HashMap<ItemVariantAndLocation, ColdChainWarning>, RepositoryError> {
+        check_cold_chain_compatibilities(&ctx.connection, pairs)
+    }
 }
 
 pub struct LocationService {}
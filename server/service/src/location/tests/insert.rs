@@ -39,7 +39,11 @@ mod query {
                     code: "invalid".to_owned(),
                     name: None,
                     on_hold: None,
-                    cold_storage_type_id: None
+                    cold_storage_type_id: None,
+                    parent_location_id: None,
+                    volume_capacity: None,
+                    pallet_capacity: None,
+                    walk_sequence: None
                 },
             ),
             Err(InsertLocationError::LocationAlreadyExists)
@@ -53,7 +57,11 @@ mod query {
                     code: locations_in_store[0].location_row.code.clone(),
                     name: None,
                     on_hold: None,
-                    cold_storage_type_id: None
+                    cold_storage_type_id: None,
+                    parent_location_id: None,
+                    volume_capacity: None,
+                    pallet_capacity: None,
+                    walk_sequence: None
                 },
             ),
             Err(InsertLocationError::LocationWithCodeAlreadyExists)
@@ -81,6 +89,10 @@ mod query {
                 on_hold: false,
                 store_id: "store_a".to_owned(),
                 cold_storage_type_id: None,
+                parent_location_id: None,
+                volume_capacity: None,
+                pallet_capacity: None,
+                walk_sequence: None,
             },
         };
 
@@ -92,7 +104,11 @@ mod query {
                     code: "new_code".to_owned(),
                     name: None,
                     on_hold: None,
-                    cold_storage_type_id: None
+                    cold_storage_type_id: None,
+                    parent_location_id: None,
+                    volume_capacity: None,
+                    pallet_capacity: None,
+                    walk_sequence: None
                 },
             ),
             Ok(result_location.clone())
@@ -118,7 +134,11 @@ mod query {
                     code: "store_b_location_code".to_owned(),
                     name: Some("new_location_name".to_owned()),
                     on_hold: Some(true),
-                    cold_storage_type_id: None
+                    cold_storage_type_id: None,
+                    parent_location_id: None,
+                    volume_capacity: None,
+                    pallet_capacity: None,
+                    walk_sequence: None
                 },
             ),
             Ok(Location {
@@ -128,7 +148,11 @@ mod query {
                     code: "store_b_location_code".to_owned(),
                     on_hold: true,
                     store_id: "store_a".to_owned(),
-                    cold_storage_type_id: None
+                    cold_storage_type_id: None,
+                    parent_location_id: None,
+                    volume_capacity: None,
+                    pallet_capacity: None,
+                    walk_sequence: None
                 }
             })
         );
@@ -11,6 +11,7 @@ mod query {
     use crate::{
         location::update::{UpdateLocation, UpdateLocationError},
         service_provider::ServiceProvider,
+        NullableUpdate,
     };
 
     #[actix_rt::test]
@@ -70,6 +71,76 @@ mod query {
             ),
             Err(UpdateLocationError::CodeAlreadyExists)
         );
+
+        // Parent location in another store
+        assert_eq!(
+            service.update_location(
+                &context,
+                UpdateLocation {
+                    id: locations_in_store[0].location_row.id.clone(),
+                    parent_location_id: Some(NullableUpdate {
+                        value: Some(locations_not_in_store[0].location_row.id.clone()),
+                    }),
+                    ..Default::default()
+                },
+            ),
+            Err(UpdateLocationError::ParentLocationDoesNotExist)
+        );
+
+        // Location can't be its own parent
+        assert_eq!(
+            service.update_location(
+                &context,
+                UpdateLocation {
+                    id: locations_in_store[0].location_row.id.clone(),
+                    parent_location_id: Some(NullableUpdate {
+                        value: Some(locations_in_store[0].location_row.id.clone()),
+                    }),
+                    ..Default::default()
+                },
+            ),
+            Err(UpdateLocationError::ParentLocationIsDescendant)
+        );
+
+        // Parent location is a sub location
+        service
+            .update_location(
+                &context,
+                UpdateLocation {
+                    id: locations_in_store[1].location_row.id.clone(),
+                    parent_location_id: Some(NullableUpdate {
+                        value: Some(locations_in_store[0].location_row.id.clone()),
+                    }),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            service.update_location(
+                &context,
+                UpdateLocation {
+                    id: locations_in_store[0].location_row.id.clone(),
+                    parent_location_id: Some(NullableUpdate {
+                        value: Some(locations_in_store[1].location_row.id.clone()),
+                    }),
+                    ..Default::default()
+                },
+            ),
+            Err(UpdateLocationError::ParentLocationIsDescendant)
+        );
+
+        // Negative capacity
+        assert_eq!(
+            service.update_location(
+                &context,
+                UpdateLocation {
+                    id: locations_in_store[0].location_row.id.clone(),
+                    volume_capacity: Some(NullableUpdate { value: Some(-1.0) }),
+                    ..Default::default()
+                },
+            ),
+            Err(UpdateLocationError::CapacityCannotBeNegative)
+        );
     }
     #[actix_rt::test]
     async fn location_service_update_success() {
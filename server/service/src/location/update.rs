@@ -1,8 +1,11 @@
 use super::{
     query::get_location,
-    validate::{check_location_code_is_unique, check_location_exists},
+    validate::{
+        check_capacity_is_valid, check_location_code_is_unique, check_location_exists,
+        check_parent_location_exists, check_parent_location_is_not_descendant,
+    },
 };
-use crate::{service_provider::ServiceContext, SingleRecordError};
+use crate::{service_provider::ServiceContext, NullableUpdate, SingleRecordError};
 use repository::{
     location::Location, LocationRow, LocationRowRepository, RepositoryError, StorageConnection,
 };
@@ -12,6 +15,10 @@ pub enum UpdateLocationError {
     LocationDoesNotExist,
     CodeAlreadyExists,
     LocationDoesNotBelongToCurrentStore,
+    ParentLocationDoesNotExist,
+    /// The parent location is the location itself or one of its sub locations
+    ParentLocationIsDescendant,
+    CapacityCannotBeNegative,
     UpdatedRecordNotFound,
     DatabaseError(RepositoryError),
 }
@@ -23,6 +30,10 @@ pub struct UpdateLocation {
     pub name: Option<String>,
     pub on_hold: Option<bool>,
     pub cold_storage_type_id: Option<String>,
+    pub parent_location_id: Option<NullableUpdate<String>>,
+    pub volume_capacity: Option<NullableUpdate<f64>>,
+    pub pallet_capacity: Option<NullableUpdate<i32>>,
+    pub walk_sequence: Option<NullableUpdate<i32>>,
 }
 
 pub fn update_location(
@@ -60,6 +71,30 @@ pub fn validate(
         return Err(UpdateLocationError::LocationDoesNotBelongToCurrentStore);
     }
 
+    if let Some(NullableUpdate {
+        value: Some(parent_location_id),
+    }) = &input.parent_location_id
+    {
+        if !check_parent_location_exists(parent_location_id, store_id, connection)? {
+            return Err(UpdateLocationError::ParentLocationDoesNotExist);
+        }
+        if !check_parent_location_is_not_descendant(&input.id, parent_location_id, connection)? {
+            return Err(UpdateLocationError::ParentLocationIsDescendant);
+        }
+    }
+
+    let volume_capacity = input
+        .volume_capacity
+        .as_ref()
+        .and_then(|update| update.value);
+    let pallet_capacity = input
+        .pallet_capacity
+        .as_ref()
+        .and_then(|update| update.value);
+    if !check_capacity_is_valid(volume_capacity, pallet_capacity) {
+        return Err(UpdateLocationError::CapacityCannotBeNegative);
+    }
+
     Ok(location_row)
 }
 
@@ -70,6 +105,10 @@ pub fn generate(
         name,
         on_hold,
         cold_storage_type_id,
+        parent_location_id,
+        volume_capacity,
+        pallet_capacity,
+        walk_sequence,
     }: UpdateLocation,
     mut location_row: LocationRow,
 ) -> LocationRow {
@@ -77,6 +116,18 @@ pub fn generate(
     location_row.name = name.unwrap_or(location_row.name);
     location_row.on_hold = on_hold.unwrap_or(location_row.on_hold);
     location_row.cold_storage_type_id = cold_storage_type_id;
+    if let Some(parent_location_id) = parent_location_id {
+        location_row.parent_location_id = parent_location_id.value;
+    }
+    if let Some(volume_capacity) = volume_capacity {
+        location_row.volume_capacity = volume_capacity.value;
+    }
+    if let Some(pallet_capacity) = pallet_capacity {
+        location_row.pallet_capacity = pallet_capacity.value;
+    }
+    if let Some(walk_sequence) = walk_sequence {
+        location_row.walk_sequence = walk_sequence.value;
+    }
     location_row
 }
 
@@ -0,0 +1,289 @@
+use std::collections::{HashMap, HashSet};
+
+use repository::{
+    item_variant::{
+        packaging_variant::{PackagingVariantFilter, PackagingVariantRepository},
+        packaging_variant_row::PackagingVariantRow,
+    },
+    location::{LocationFilter, LocationRepository},
+    EqualFilter, LocationRow, RepositoryError, StockLineFilter, StockLineRepository, StockLineRow,
+    StorageConnection,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LocationUtilisation {
+    pub location_id: String,
+    pub volume_capacity: Option<f64>,
+    pub pallet_capacity: Option<i32>,
+    /// Volume of all stock in the location and its sub locations
+    pub volume_used: f64,
+    /// Stock lines for which the volume is unknown, i.e. stock lines without item variant or
+    /// without packaging variant volumes. These are not included in `volume_used`.
+    pub stock_lines_without_volume: u32,
+}
+
+impl LocationUtilisation {
+    /// Fraction of the volume capacity that is used, None if the location has no capacity
+    pub fn volume_utilisation(&self) -> Option<f64> {
+        match self.volume_capacity {
+            Some(capacity) if capacity > 0.0 => Some(self.volume_used / capacity),
+            _ => None,
+        }
+    }
+}
+
+pub fn get_location_utilisation(
+    ctx: &ServiceContext,
+    location: &LocationRow,
+) -> Result<LocationUtilisation, RepositoryError> {
+    let mut utilisation = get_store_location_utilisation(ctx, &location.store_id)?;
+
+    Ok(utilisation
+        .remove(&location.id)
+        .unwrap_or_else(|| LocationUtilisation {
+            location_id: location.id.clone(),
+            volume_capacity: location.volume_capacity,
+            pallet_capacity: location.pallet_capacity,
+            volume_used: 0.0,
+            stock_lines_without_volume: 0,
+        }))
+}
+
+/// Utilisation of all locations in the store, by location id
+pub fn get_store_location_utilisation(
+    ctx: &ServiceContext,
+    store_id: &str,
+) -> Result<HashMap<String, LocationUtilisation>, RepositoryError> {
+    let connection = &ctx.connection;
+
+    let locations: Vec<LocationRow> = LocationRepository::new(connection)
+        .query_by_filter(LocationFilter::new().store_id(EqualFilter::equal_to(store_id)))?
+        .into_iter()
+        .map(|location| location.location_row)
+        .collect();
+    let parents: HashMap<&str, &str> = locations
+        .iter()
+        .filter_map(|location| {
+            location
+                .parent_location_id
+                .as_deref()
+                .map(|parent_location_id| (location.id.as_str(), parent_location_id))
+        })
+        .collect();
+
+    let mut result: HashMap<String, LocationUtilisation> = locations
+        .iter()
+        .map(|location| {
+            (
+                location.id.clone(),
+                LocationUtilisation {
+                    location_id: location.id.clone(),
+                    volume_capacity: location.volume_capacity,
+                    pallet_capacity: location.pallet_capacity,
+                    volume_used: 0.0,
+                    stock_lines_without_volume: 0,
+                },
+            )
+        })
+        .collect();
+
+    let stock_lines: Vec<StockLineRow> = StockLineRepository::new(connection)
+        .query_by_filter(
+            StockLineFilter::new()
+                .store_id(EqualFilter::equal_to(store_id))
+                .has_packs_in_store(true),
+            Some(store_id.to_string()),
+        )?
+        .into_iter()
+        .map(|stock_line| stock_line.stock_line_row)
+        .filter(|stock_line| stock_line.location_id.is_some())
+        .collect();
+    let packaging_variants = get_packaging_variants_by_item_variant(
+        connection,
+        stock_lines
+            .iter()
+            .filter_map(|stock_line| stock_line.item_variant_id.clone())
+            .collect(),
+    )?;
+
+    for stock_line in &stock_lines {
+        let volume = stock_line
+            .item_variant_id
+            .as_ref()
+            .and_then(|id| packaging_variants.get(id))
+            .and_then(|variants| volume_per_pack(stock_line.pack_size, variants))
+            .map(|volume_per_pack| volume_per_pack * stock_line.total_number_of_packs);
+
+        // Stock counts towards its location and all locations above it
+        let mut visited = HashSet::new();
+        let mut current = stock_line.location_id.as_deref();
+        while let Some(location_id) = current {
+            if !visited.insert(location_id) {
+                break;
+            }
+            if let Some(utilisation) = result.get_mut(location_id) {
+                match volume {
+                    Some(volume) => utilisation.volume_used += volume,
+                    None => utilisation.stock_lines_without_volume += 1,
+                }
+            }
+            current = parents.get(location_id).copied();
+        }
+    }
+
+    Ok(result)
+}
+
+/// Packaging variants of the item variants, by item variant id
+pub(crate) fn get_packaging_variants_by_item_variant(
+    connection: &StorageConnection,
+    item_variant_ids: Vec<String>,
+) -> Result<HashMap<String, Vec<PackagingVariantRow>>, RepositoryError> {
+    let mut result: HashMap<String, Vec<PackagingVariantRow>> = HashMap::new();
+    for packaging_variant in PackagingVariantRepository::new(connection).query_by_filter(
+        PackagingVariantFilter::new().item_variant_id(EqualFilter::equal_any(item_variant_ids)),
+    )? {
+        result
+            .entry(packaging_variant.item_variant_id.clone())
+            .or_default()
+            .push(packaging_variant);
+    }
+    Ok(result)
+}
+
+/// Volume of one pack of stock. Uses the packaging variant with the same pack size if it has a
+/// volume, otherwise the volume is calculated from the lowest packaging level with a volume.
+pub(crate) fn volume_per_pack(
+    pack_size: f64,
+    packaging_variants: &[PackagingVariantRow],
+) -> Option<f64> {
+    let matching_pack_size = packaging_variants
+        .iter()
+        .find(|variant| variant.pack_size == Some(pack_size) && variant.volume_per_unit.is_some());
+    if let Some(variant) = matching_pack_size {
+        return variant.volume_per_unit;
+    }
+
+    let lowest_level = packaging_variants
+        .iter()
+        .filter(|variant| variant.volume_per_unit.is_some())
+        .min_by_key(|variant| variant.packaging_level)?;
+    let variant_pack_size = lowest_level
+        .pack_size
+        .filter(|size| *size > 0.0)
+        .unwrap_or(1.0);
+
+    lowest_level
+        .volume_per_unit
+        .map(|volume| volume / variant_pack_size * pack_size)
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        item_variant::{
+            item_variant_row::ItemVariantRow,
+            packaging_variant_row::{PackagingVariantRow, PackagingVariantRowRepository},
+        },
+        mock::{mock_item_a, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        LocationRow, StockLineRow,
+    };
+
+    use crate::{location::LocationServiceTrait, service_provider::ServiceProvider};
+
+    #[actix_rt::test]
+    async fn location_utilisation() {
+        let warehouse = LocationRow {
+            id: "warehouse".to_string(),
+            code: "warehouse".to_string(),
+            store_id: mock_store_a().id,
+            volume_capacity: Some(10.0),
+            ..Default::default()
+        };
+        let shelf = LocationRow {
+            id: "shelf".to_string(),
+            code: "shelf".to_string(),
+            store_id: mock_store_a().id,
+            parent_location_id: Some(warehouse.id.clone()),
+            volume_capacity: Some(2.0),
+            ..Default::default()
+        };
+        let item_variant = ItemVariantRow {
+            id: "item_variant".to_string(),
+            item_link_id: mock_item_a().id,
+            ..Default::default()
+        };
+        // 0.01 per unit
+        let primary_packaging = PackagingVariantRow {
+            id: "primary".to_string(),
+            item_variant_id: item_variant.id.clone(),
+            packaging_level: 1,
+            pack_size: Some(10.0),
+            volume_per_unit: Some(0.1),
+            ..Default::default()
+        };
+        let secondary_packaging = PackagingVariantRow {
+            id: "secondary".to_string(),
+            item_variant_id: item_variant.id.clone(),
+            packaging_level: 2,
+            pack_size: Some(100.0),
+            volume_per_unit: Some(0.5),
+            ..Default::default()
+        };
+        let stock_line = |id: &str, location: &LocationRow, pack_size: f64| StockLineRow {
+            id: id.to_string(),
+            item_link_id: mock_item_a().id,
+            store_id: mock_store_a().id,
+            location_id: Some(location.id.clone()),
+            item_variant_id: Some(item_variant.id.clone()),
+            pack_size,
+            total_number_of_packs: 2.0,
+            available_number_of_packs: 2.0,
+            ..Default::default()
+        };
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "location_utilisation",
+            MockDataInserts::none().names().stores().units().items(),
+            MockData {
+                locations: vec![warehouse.clone(), shelf.clone()],
+                item_variants: vec![item_variant.clone()],
+                stock_lines: vec![
+                    // Matching pack size: 2 * 0.5
+                    stock_line("stock_line_1", &shelf, 100.0),
+                    // Calculated from primary packaging: 2 * 5 * 0.01
+                    stock_line("stock_line_2", &warehouse, 5.0),
+                    StockLineRow {
+                        item_variant_id: None,
+                        ..stock_line("stock_line_3", &warehouse, 1.0)
+                    },
+                ],
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let repo = PackagingVariantRowRepository::new(&connection);
+        repo.upsert_one(&primary_packaging).unwrap();
+        repo.upsert_one(&secondary_packaging).unwrap();
+
+        let service_provider = ServiceProvider::new(connection_manager);
+        let context = service_provider.basic_context().unwrap();
+        let service = service_provider.location_service;
+
+        let result = service
+            .get_location_utilisation(&context, &warehouse)
+            .unwrap();
+        assert!((result.volume_used - 1.1).abs() < 1e-9);
+        assert_eq!(result.stock_lines_without_volume, 1);
+        assert!((result.volume_utilisation().unwrap() - 0.11).abs() < 1e-9);
+
+        let result = service.get_location_utilisation(&context, &shelf).unwrap();
+        assert!((result.volume_used - 1.0).abs() < 1e-9);
+        assert_eq!(result.stock_lines_without_volume, 0);
+        assert_eq!(result.volume_utilisation(), Some(0.5));
+    }
+}
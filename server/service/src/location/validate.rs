@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use repository::{
     location::{LocationFilter, LocationRepository},
     LocationRow, LocationRowRepository, RepositoryError, StorageConnection,
@@ -30,3 +32,60 @@ pub fn check_location_exists(
 ) -> Result<Option<LocationRow>, RepositoryError> {
     LocationRowRepository::new(connection).find_one_by_id(id)
 }
+
+pub fn check_parent_location_exists(
+    parent_location_id: &str,
+    store_id: &str,
+    connection: &StorageConnection,
+) -> Result<bool, RepositoryError> {
+    let count = LocationRepository::new(connection).count(Some(
+        LocationFilter::new()
+            .id(EqualFilter::equal_to(parent_location_id))
+            .store_id(EqualFilter::equal_to(store_id)),
+    ))?;
+
+    Ok(count > 0)
+}
+
+/// Checks that `location_id` is not `parent_location_id` or one of its ancestors, which would
+/// create a cycle in the location hierarchy
+pub fn check_parent_location_is_not_descendant(
+    location_id: &str,
+    parent_location_id: &str,
+    connection: &StorageConnection,
+) -> Result<bool, RepositoryError> {
+    let repo = LocationRowRepository::new(connection);
+    let mut visited = HashSet::new();
+    let mut current = Some(parent_location_id.to_string());
+
+    while let Some(id) = current {
+        if id == location_id {
+            return Ok(false);
+        }
+        // Guard against cycles that already exist, e.g. from sync
+        if !visited.insert(id.clone()) {
+            break;
+        }
+        current = repo
+            .find_one_by_id(&id)?
+            .and_then(|location| location.parent_location_id);
+    }
+
+    Ok(true)
+}
+
+pub fn check_capacity_is_valid(volume_capacity: Option<f64>, pallet_capacity: Option<i32>) -> bool {
+    volume_capacity.map_or(true, |volume| volume >= 0.0)
+        && pallet_capacity.map_or(true, |pallets| pallets >= 0)
+}
+
+pub fn check_location_has_no_child_locations(
+    id: &str,
+    connection: &StorageConnection,
+) -> Result<bool, RepositoryError> {
+    let count = LocationRepository::new(connection).count(Some(
+        LocationFilter::new().parent_location_id(EqualFilter::equal_to(id)),
+    ))?;
+
+    Ok(count == 0)
+}
@@ -8,7 +8,7 @@ use chrono::Utc;
 use log::info;
 use repository::{
     ActivityLogType, LanguageType, PermissionType, RepositoryError, UserAccountRow,
-    UserPermissionRow, UserStoreJoinRow,
+    UserAuthStateRowRepository, UserPermissionRow, UserStoreJoinRow,
 };
 use reqwest::{ClientBuilder, Url};
 use serde::{Deserialize, Serialize};
@@ -23,6 +23,15 @@ use crate::{
         permissions::{map_api_permissions, Permissions},
     },
     auth_data::AuthData,
+    identity_provider::ExternalAuthError,
+    local_auth::{
+        get_auth_policy,
+        lockout::{
+            find_auth_state_by_username, lockout_remaining, record_failed_login,
+            record_successful_login, FailedLoginReason,
+        },
+        totp::verify_totp_code_now,
+    },
     service_provider::{ServiceContext, ServiceProvider},
     settings::is_develop,
     token::{JWTIssuingError, TokenPair, TokenService},
@@ -55,6 +64,11 @@ pub enum LoginFailure {
     AccountBlocked(u64),
     /// User account does not have login rights to any stores on this site
     NoSiteAccess,
+    /// User has a TOTP second factor but no code was provided
+    TotpRequired,
+    InvalidTotpCode,
+    /// Password of a locally managed user is older than the password policy allows
+    PasswordExpired,
 }
 
 #[derive(Debug)]
@@ -66,6 +80,8 @@ pub enum LoginError {
     InternalError(String),
     DatabaseError(RepositoryError),
     MSupplyCentralNotReached,
+    /// Login with an identity provider failed, see identity_provider
+    ExternalAuthError(ExternalAuthError),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -74,6 +90,9 @@ pub struct LoginInput {
     pub password: String,
     /// Central server url needed to fetch user details during login
     pub central_server_url: String,
+    /// Code from the authenticator app, required for users with TOTP enabled
+    #[serde(default)]
+    pub totp_code: Option<String>,
 }
 
 impl LoginService {
@@ -96,18 +115,96 @@ impl LoginService {
         min_err_response_time_sec: u64,
     ) -> Result<TokenPair, LoginError> {
         let now = SystemTime::now();
-        match LoginService::do_login(service_provider, auth_data, input).await {
-            Ok(result) => Ok(result),
-            Err(err) => {
-                let elapsed = now.elapsed().unwrap_or(Duration::from_secs(0));
-                let minimum = Duration::from_secs(min_err_response_time_sec);
-                if elapsed < minimum {
-                    tokio::time::sleep(minimum - elapsed).await;
-                }
+        let result = LoginService::do_login(service_provider, auth_data, input).await;
+        LoginService::delay_error(now, min_err_response_time_sec, result).await
+    }
+
+    /// Delays an error result until at least `min_err_response_time_sec` have passed since
+    /// `started`
+    pub(crate) async fn delay_error<T>(
+        started: SystemTime,
+        min_err_response_time_sec: u64,
+        result: Result<T, LoginError>,
+    ) -> Result<T, LoginError> {
+        if result.is_err() {
+            let elapsed = started.elapsed().unwrap_or(Duration::from_secs(0));
+            let minimum = Duration::from_secs(min_err_response_time_sec);
+            if elapsed < minimum {
+                tokio::time::sleep(minimum - elapsed).await;
+            }
+        }
+        result
+    }
+
+    /// Issues the token pair for a successfully logged in user
+    pub(crate) fn issue_tokens(
+        auth_data: &AuthData,
+        user_id: &str,
+        password: &str,
+    ) -> Result<TokenPair, LoginError> {
+        let mut token_service = TokenService::new(
+            &auth_data.token_bucket,
+            auth_data.auth_token_secret.as_bytes(),
+            !is_develop(),
+        );
+        let max_age_token = chrono::Duration::minutes(60).num_seconds() as usize;
+        let max_age_refresh = chrono::Duration::hours(6).num_seconds() as usize;
+
+        token_service
+            .jwt_token(user_id, password, max_age_token, max_age_refresh)
+            .map_err(LoginError::FailedToGenerateToken)
+    }
+
+    /// Checks done once the credentials are verified, by this site, central or an identity
+    /// provider: site access, second factor and password expiry. Records the successful login
+    /// and sets the user of the context.
+    pub(crate) fn complete_authentication(
+        service_ctx: &mut ServiceContext,
+        username: &str,
+        user_id: &str,
+        totp_code: Option<&str>,
+    ) -> Result<(), LoginError> {
+        // Check that the logged in user has access to at least one store on the site
+        match UserAccountService::new(&service_ctx.connection)
+            .find_user_active_on_this_site(user_id)
+        {
+            Ok(Some(_)) => (),
+            Ok(None) => return Err(LoginError::LoginFailure(LoginFailure::NoSiteAccess)),
+            Err(err) => return Err(err.into()),
+        };
 
-                Err(err)
+        let auth_state =
+            UserAuthStateRowRepository::new(&service_ctx.connection).find_or_default(user_id)?;
+        if let Some(secret) = auth_state.enabled_totp_secret() {
+            let Some(totp_code) = totp_code else {
+                return Err(LoginError::LoginFailure(LoginFailure::TotpRequired));
+            };
+            if !verify_totp_code_now(secret, totp_code) {
+                record_failed_login(
+                    &service_ctx.connection,
+                    username,
+                    FailedLoginReason::InvalidTotpCode,
+                )?;
+                return Err(LoginError::LoginFailure(LoginFailure::InvalidTotpCode));
             }
         }
+
+        if get_auth_policy(&service_ctx.connection)?
+            .is_password_expired(&auth_state, Utc::now().naive_utc())
+        {
+            record_failed_login(
+                &service_ctx.connection,
+                username,
+                FailedLoginReason::PasswordExpired,
+            )?;
+            return Err(LoginError::LoginFailure(LoginFailure::PasswordExpired));
+        }
+
+        record_successful_login(&service_ctx.connection, user_id)?;
+        service_ctx.user_id = user_id.to_string();
+
+        activity_log_entry(service_ctx, ActivityLogType::UserLoggedIn, None, None, None)?;
+        Ok(())
     }
 
     async fn do_login(
@@ -117,40 +214,74 @@ impl LoginService {
     ) -> Result<TokenPair, LoginError> {
         let mut username = input.username.clone();
         let mut connection_failure = false;
-        match LoginService::fetch_user_from_central(&input).await {
-            Ok(user_info) => {
-                let service_ctx =
-                    service_provider.context("".to_string(), user_info.user.id.clone())?;
-                username.clone_from(&user_info.user.name);
-                LoginService::update_user(&service_ctx, &input.password, user_info)
-                    .map_err(LoginError::UpdateUserError)?;
-            }
-            Err(err) => match err {
-                FetchUserError::Unauthenticated => {
-                    return Err(LoginError::LoginFailure(LoginFailure::InvalidCredentials))
-                }
-                FetchUserError::AccountBlocked(timeout_remaining) => {
-                    return Err(LoginError::LoginFailure(LoginFailure::AccountBlocked(
-                        timeout_remaining,
-                    )))
-                }
-                FetchUserError::ConnectionError(_) => {
-                    info!("{:?}", err);
-                    connection_failure = true;
-                }
-                FetchUserError::InternalError(_) => info!("{:?}", err),
-            },
+
+        // Reject locked accounts straight away, this also stops the lockout from being bypassed
+        // by trying passwords against central
+        let is_locally_managed = {
+            let service_ctx = service_provider.basic_context()?;
+            let auth_state = find_auth_state_by_username(&service_ctx.connection, &username)?;
+            let timeout_remaining = auth_state
+                .as_ref()
+                .and_then(|auth_state| lockout_remaining(auth_state, Utc::now().naive_utc()));
+            if let Some(timeout_remaining) = timeout_remaining {
+                record_failed_login(
+                    &service_ctx.connection,
+                    &username,
+                    FailedLoginReason::AccountLocked,
+                )?;
+                return Err(LoginError::LoginFailure(LoginFailure::AccountBlocked(
+                    timeout_remaining,
+                )));
+            }
+            auth_state.is_some_and(|auth_state| auth_state.is_locally_managed)
         };
+
+        // Locally managed users are unknown to central and are only checked against this site
+        if !is_locally_managed {
+            match LoginService::fetch_user_from_central(&input).await {
+                Ok(user_info) => {
+                    let service_ctx =
+                        service_provider.context("".to_string(), user_info.user.id.clone())?;
+                    username.clone_from(&user_info.user.name);
+                    LoginService::update_user(&service_ctx, &input.password, user_info)
+                        .map_err(LoginError::UpdateUserError)?;
+                }
+                Err(err) => match err {
+                    FetchUserError::Unauthenticated => {
+                        let service_ctx = service_provider.basic_context()?;
+                        record_failed_login(
+                            &service_ctx.connection,
+                            &username,
+                            FailedLoginReason::InvalidCredentials,
+                        )?;
+                        return Err(LoginError::LoginFailure(LoginFailure::InvalidCredentials));
+                    }
+                    FetchUserError::AccountBlocked(timeout_remaining) => {
+                        return Err(LoginError::LoginFailure(LoginFailure::AccountBlocked(
+                            timeout_remaining,
+                        )))
+                    }
+                    FetchUserError::ConnectionError(_) => {
+                        info!("{:?}", err);
+                        connection_failure = true;
+                    }
+                    FetchUserError::InternalError(_) => info!("{:?}", err),
+                },
+            }
+        }
         let mut service_ctx = service_provider.basic_context()?;
         let user_service = UserAccountService::new(&service_ctx.connection);
         let user_account = match user_service.verify_password(&username, &input.password) {
             Ok(user) => user,
             Err(err) => {
                 return Err(match err {
-                    VerifyPasswordError::UsernameDoesNotExist => {
-                        LoginError::LoginFailure(LoginFailure::InvalidCredentials)
-                    }
-                    VerifyPasswordError::InvalidCredentials => {
+                    VerifyPasswordError::UsernameDoesNotExist
+                    | VerifyPasswordError::InvalidCredentials => {
+                        record_failed_login(
+                            &service_ctx.connection,
+                            &username,
+                            FailedLoginReason::InvalidCredentials,
+                        )?;
                         LoginError::LoginFailure(LoginFailure::InvalidCredentials)
                     }
                     VerifyPasswordError::InvalidCredentialsBackend(_) => {
@@ -168,41 +299,14 @@ impl LoginService {
             }
         };
 
-        // Check that the logged in user has access to at least one store on the site
-        match user_service.find_user_active_on_this_site(&user_account.id) {
-            Ok(Some(_)) => (),
-            Ok(None) => return Err(LoginError::LoginFailure(LoginFailure::NoSiteAccess)),
-            Err(err) => return Err(err.into()),
-        };
-
-        service_ctx.user_id.clone_from(&user_account.id);
-
-        activity_log_entry(
-            &service_ctx,
-            ActivityLogType::UserLoggedIn,
-            None,
-            None,
-            None,
+        LoginService::complete_authentication(
+            &mut service_ctx,
+            &username,
+            &user_account.id,
+            input.totp_code.as_deref(),
         )?;
 
-        let mut token_service = TokenService::new(
-            &auth_data.token_bucket,
-            auth_data.auth_token_secret.as_bytes(),
-            !is_develop(),
-        );
-        let max_age_token = chrono::Duration::minutes(60).num_seconds() as usize;
-        let max_age_refresh = chrono::Duration::hours(6).num_seconds() as usize;
-
-        let pair = match token_service.jwt_token(
-            &user_account.id,
-            &input.password,
-            max_age_token,
-            max_age_refresh,
-        ) {
-            Ok(pair) => pair,
-            Err(err) => return Err(LoginError::FailedToGenerateToken(err)),
-        };
-        Ok(pair)
+        LoginService::issue_tokens(auth_data, &user_account.id, &input.password)
     }
 
     pub async fn fetch_user_from_central(
@@ -567,6 +671,7 @@ mod test {
                     username: "Gryffindor".to_string(),
                     password: "password".to_string(),
                     central_server_url,
+                    totp_code: None,
                 },
                 0,
             )
@@ -608,6 +713,7 @@ mod test {
                     username: "Gryffindor".to_string(),
                     password: "password2".to_string(),
                     central_server_url,
+                    totp_code: None,
                 },
                 0,
             )
@@ -635,6 +741,7 @@ mod test {
                     username: "Gryffindor".to_string(),
                     password: "password".to_string(),
                     central_server_url,
+                    totp_code: None,
                 },
                 0,
             )
@@ -659,6 +766,7 @@ mod test {
                     username: mock_user_empty_hashed_password().username,
                     password: "password".to_string(),
                     central_server_url,
+                    totp_code: None,
                 },
                 0,
             )
@@ -689,6 +797,7 @@ mod test {
                     username: mock_user_empty_hashed_password().username,
                     password: "password".to_string(),
                     central_server_url,
+                    totp_code: None,
                 },
                 0,
             )
@@ -717,6 +826,7 @@ mod test {
                     username: "Gryffindor".to_string(),
                     password: "password2".to_string(),
                     central_server_url,
+                    totp_code: None,
                 },
                 0,
             )
@@ -749,6 +859,7 @@ mod test {
                     username: "Gryffindor".to_string(),
                     password: "password".to_string(),
                     central_server_url,
+                    totp_code: None,
                 },
                 0,
             )
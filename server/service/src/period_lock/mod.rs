@@ -0,0 +1,141 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use repository::{
+    ActivityLogType, InvoiceRow, InvoiceType, PeriodLockRow, PeriodLockRowRepository,
+    PermissionType, RepositoryError,
+};
+
+use self::set::{set_period_lock, SetPeriodLock, SetPeriodLockError};
+use crate::{
+    activity_log::activity_log_entry, role::resolve_user_permissions,
+    service_provider::ServiceContext,
+};
+
+pub mod set;
+
+#[derive(Debug, PartialEq)]
+pub enum PeriodLockError {
+    /// Holds the lock date of the store
+    PeriodLocked(NaiveDate),
+    DatabaseError(RepositoryError),
+}
+
+pub trait PeriodLockServiceTrait: Sync + Send {
+    fn get_period_lock(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+    ) -> Result<Option<PeriodLockRow>, RepositoryError> {
+        PeriodLockRowRepository::new(&ctx.connection).find_latest_by_store(store_id)
+    }
+
+    fn get_period_lock_history(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+    ) -> Result<Vec<PeriodLockRow>, RepositoryError> {
+        PeriodLockRowRepository::new(&ctx.connection).find_many_by_store(store_id)
+    }
+
+    fn set_period_lock(
+        &self,
+        ctx: &ServiceContext,
+        input: SetPeriodLock,
+    ) -> Result<PeriodLockRow, SetPeriodLockError> {
+        set_period_lock(ctx, input)
+    }
+}
+
+pub struct PeriodLockService {}
+impl PeriodLockServiceTrait for PeriodLockService {}
+
+/// Checks that a stock movement on `date` can be created or edited in the store. Users with the
+/// PeriodLockOverride permission can still change a closed period, the override is recorded in
+/// the activity log against `record_id`.
+pub fn check_period_lock(
+    ctx: &ServiceContext,
+    store_id: &str,
+    record_id: &str,
+    date: NaiveDate,
+) -> Result<(), PeriodLockError> {
+    let lock_date = PeriodLockRowRepository::new(&ctx.connection)
+        .find_latest_by_store(store_id)?
+        .and_then(|period_lock| period_lock.lock_date);
+    let Some(lock_date) = lock_date else {
+        return Ok(());
+    };
+    if date > lock_date {
+        return Ok(());
+    }
+
+    let can_override = !ctx.user_id.is_empty()
+        && resolve_user_permissions(&ctx.connection, &ctx.user_id, Some(store_id))?
+            .iter()
+            .any(|permission| permission.permission == PermissionType::PeriodLockOverride);
+    if !can_override {
+        return Err(PeriodLockError::PeriodLocked(lock_date));
+    }
+
+    activity_log_entry(
+        ctx,
+        ActivityLogType::PeriodLockOverridden,
+        Some(record_id.to_string()),
+        Some(lock_date.to_string()),
+        Some(date.to_string()),
+    )?;
+    Ok(())
+}
+
+/// Datetime at which the invoice moved stock, matches the datetime of the stock_movement view.
+/// None if the invoice hasn't moved stock yet.
+pub fn invoice_movement_datetime(invoice: &InvoiceRow) -> Option<NaiveDateTime> {
+    match invoice.r#type {
+        InvoiceType::OutboundShipment | InvoiceType::SupplierReturn | InvoiceType::Prescription => {
+            invoice.picked_datetime
+        }
+        InvoiceType::InboundShipment | InvoiceType::CustomerReturn => invoice.delivered_datetime,
+        InvoiceType::InventoryAddition | InvoiceType::InventoryReduction | InvoiceType::Repack => {
+            invoice.verified_datetime
+        }
+    }
+}
+
+/// Checks lines of the invoice can be changed, i.e. the invoice hasn't moved stock in a closed
+/// period
+pub fn check_invoice_period_lock(
+    ctx: &ServiceContext,
+    invoice: &InvoiceRow,
+) -> Result<(), PeriodLockError> {
+    match invoice_movement_datetime(invoice) {
+        Some(datetime) => check_period_lock(ctx, &invoice.store_id, &invoice.id, datetime.date()),
+        None => Ok(()),
+    }
+}
+
+/// Checks an invoice update that changes when the invoice moved stock, e.g. picking or backdating
+/// an invoice. Updates that don't affect the stock movement (e.g. comments or shipping a picked
+/// shipment) are allowed in a closed period.
+pub fn check_invoice_update_period_lock(
+    ctx: &ServiceContext,
+    existing: &InvoiceRow,
+    updated: &InvoiceRow,
+) -> Result<(), PeriodLockError> {
+    let before = invoice_movement_datetime(existing);
+    let after = invoice_movement_datetime(updated);
+    if before == after {
+        return Ok(());
+    }
+
+    match before.into_iter().chain(after).min() {
+        Some(datetime) => check_period_lock(ctx, &updated.store_id, &updated.id, datetime.date()),
+        None => Ok(()),
+    }
+}
+
+impl From<RepositoryError> for PeriodLockError {
+    fn from(error: RepositoryError) -> Self {
+        PeriodLockError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod tests;
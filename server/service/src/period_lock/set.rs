@@ -0,0 +1,74 @@
+use chrono::{NaiveDate, Utc};
+use repository::{ActivityLogType, PeriodLockRow, PeriodLockRowRepository, RepositoryError};
+
+use crate::{activity_log::activity_log_entry, service_provider::ServiceContext};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SetPeriodLock {
+    pub id: String,
+    /// None re-opens all closed periods of the store
+    pub lock_date: Option<NaiveDate>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SetPeriodLockError {
+    PeriodLockAlreadyExists,
+    LockDateInFuture,
+    DatabaseError(RepositoryError),
+}
+
+/// Closes (or re-opens) periods of the store in the service context. Each change is kept as a new
+/// row so the history of period closes is available.
+pub fn set_period_lock(
+    ctx: &ServiceContext,
+    input: SetPeriodLock,
+) -> Result<PeriodLockRow, SetPeriodLockError> {
+    let period_lock = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let repo = PeriodLockRowRepository::new(connection);
+            if repo.find_one_by_id(&input.id)?.is_some() {
+                return Err(SetPeriodLockError::PeriodLockAlreadyExists);
+            }
+            let now = Utc::now().naive_utc();
+            if input
+                .lock_date
+                .is_some_and(|lock_date| lock_date > now.date())
+            {
+                return Err(SetPeriodLockError::LockDateInFuture);
+            }
+
+            let previous_lock_date = repo
+                .find_latest_by_store(&ctx.store_id)?
+                .and_then(|period_lock| period_lock.lock_date);
+            let period_lock = PeriodLockRow {
+                id: input.id,
+                store_id: ctx.store_id.clone(),
+                lock_date: input.lock_date,
+                user_id: (!ctx.user_id.is_empty()).then(|| ctx.user_id.clone()),
+                created_datetime: now,
+                comment: input.comment,
+            };
+            repo.upsert_one(&period_lock)?;
+
+            activity_log_entry(
+                ctx,
+                ActivityLogType::PeriodLocked,
+                Some(period_lock.id.clone()),
+                previous_lock_date.map(|date| date.to_string()),
+                period_lock.lock_date.map(|date| date.to_string()),
+            )?;
+
+            Ok(period_lock)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(period_lock)
+}
+
+impl From<RepositoryError> for SetPeriodLockError {
+    fn from(error: RepositoryError) -> Self {
+        SetPeriodLockError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::{Duration, Utc};
+use repository::{
+    mock::{mock_stock_line_a, mock_store_a, mock_user_account_a, MockData, MockDataInserts},
+    test_db::setup_all_with_data,
+    ActivityLogFilter, ActivityLogRepository, ActivityLogType, PermissionType, UserPermissionRow,
+    UserPermissionRowRepository,
+};
+
+use crate::{
+    invoice::inventory_adjustment::{
+        adjust_existing_stock::{InsertInventoryAdjustment, InsertInventoryAdjustmentError},
+        AdjustmentType,
+    },
+    period_lock::set::{SetPeriodLock, SetPeriodLockError},
+    service_provider::ServiceProvider,
+};
+
+fn addition() -> InsertInventoryAdjustment {
+    InsertInventoryAdjustment {
+        stock_line_id: mock_stock_line_a().id,
+        adjustment: 2.0,
+        adjustment_type: AdjustmentType::Addition,
+        inventory_adjustment_reason_id: None,
+    }
+}
+
+#[actix_rt::test]
+async fn period_lock_blocks_stock_movements() {
+    let (_, connection, connection_manager, _) = setup_all_with_data(
+        "period_lock_blocks_stock_movements",
+        MockDataInserts::all(),
+        MockData::default(),
+    )
+    .await;
+
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider
+        .context(mock_store_a().id, mock_user_account_a().id)
+        .unwrap();
+    let service = &service_provider.period_lock_service;
+    let today = Utc::now().naive_utc().date();
+
+    // Lock date in the future
+    assert_eq!(
+        service.set_period_lock(
+            &context,
+            SetPeriodLock {
+                id: "future_lock".to_string(),
+                lock_date: Some(today + Duration::days(1)),
+                comment: None,
+            }
+        ),
+        Err(SetPeriodLockError::LockDateInFuture)
+    );
+
+    // Adjustments are allowed before the period is closed
+    service_provider
+        .invoice_service
+        .insert_inventory_adjustment(&context, addition())
+        .unwrap();
+
+    let period_lock = service
+        .set_period_lock(
+            &context,
+            SetPeriodLock {
+                id: "today_lock".to_string(),
+                lock_date: Some(today),
+                comment: Some("Month end".to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(period_lock.user_id, Some(mock_user_account_a().id));
+    assert_eq!(
+        service
+            .get_period_lock(&context, &mock_store_a().id)
+            .unwrap(),
+        Some(period_lock)
+    );
+
+    // Duplicate id
+    assert_eq!(
+        service.set_period_lock(
+            &context,
+            SetPeriodLock {
+                id: "today_lock".to_string(),
+                lock_date: Some(today),
+                comment: None,
+            }
+        ),
+        Err(SetPeriodLockError::PeriodLockAlreadyExists)
+    );
+
+    // Adjustment in the closed period
+    assert_eq!(
+        service_provider
+            .invoice_service
+            .insert_inventory_adjustment(&context, addition()),
+        Err(InsertInventoryAdjustmentError::PeriodLocked(today))
+    );
+
+    // Users with the override permission can still adjust stock, the override is logged
+    UserPermissionRowRepository::new(&connection)
+        .upsert_one(&UserPermissionRow {
+            id: "period_lock_override".to_string(),
+            user_id: mock_user_account_a().id,
+            store_id: Some(mock_store_a().id),
+            permission: PermissionType::PeriodLockOverride,
+            context_id: None,
+        })
+        .unwrap();
+    service_provider
+        .invoice_service
+        .insert_inventory_adjustment(&context, addition())
+        .unwrap();
+
+    let overrides = ActivityLogRepository::new(&connection)
+        .query_by_filter(
+            ActivityLogFilter::new().r#type(ActivityLogType::PeriodLockOverridden.equal_to()),
+        )
+        .unwrap();
+    assert_eq!(overrides.len(), 1);
+    assert_eq!(
+        overrides[0].activity_log_row.changed_from,
+        Some(today.to_string())
+    );
+
+    // Re-opening the period keeps the history
+    service
+        .set_period_lock(
+            &context,
+            SetPeriodLock {
+                id: "reopen".to_string(),
+                lock_date: None,
+                comment: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        service
+            .get_period_lock_history(&context, &mock_store_a().id)
+            .unwrap()
+            .len(),
+        2
+    );
+}
@@ -1,10 +1,11 @@
 use std::collections::HashMap;
 
 use repository::{
-    EqualFilter, RepositoryError, StorageConnectionManager, StoreRow, StoreRowRepository,
-    UserPermissionFilter, UserPermissionRepository, UserPermissionRow,
+    RepositoryError, StorageConnectionManager, StoreRow, StoreRowRepository, UserPermissionRow,
 };
 
+use crate::role::resolve_user_permissions;
+
 #[derive(PartialEq, Debug, Clone)]
 pub struct UserStorePermissions {
     pub store_row: StoreRow,
@@ -17,14 +18,9 @@ pub fn permissions(
     store: Option<String>,
 ) -> Result<Vec<UserStorePermissions>, RepositoryError> {
     let connection = connection_manager.connection()?;
-    let user_permission_repo = UserPermissionRepository::new(&connection);
     let store_repo = StoreRowRepository::new(&connection);
 
-    let mut filter = UserPermissionFilter::new().user_id(EqualFilter::equal_to(user_id));
-    if let Some(store) = store {
-        filter = filter.store_id(EqualFilter::equal_to(&store))
-    }
-    let permissions = user_permission_repo.query_by_filter(filter)?;
+    let permissions = resolve_user_permissions(&connection, user_id, store.as_deref())?;
 
     let mut permissions_by_store = HashMap::new();
     for permission in permissions {
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use base64::prelude::*;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use super::{MANIFEST_FILE, MANIFEST_SIGNATURE_FILE};
+
+/// A signed plugin as it is uploaded to the central server and distributed to remote sites.
+/// Contains all plugin files, including the plugin manifest and signature, base64 encoded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginBundle {
+    /// Plugin code, the bundle is installed into a plugin dir of this name
+    pub code: String,
+    pub version: String,
+    /// Path relative to the plugin dir -> base64 encoded file content
+    pub files: HashMap<String, String>,
+}
+
+impl PluginBundle {
+    /// Bundles a signed plugin dir, e.g. after running `sign-plugin`
+    pub fn from_dir(plugin_path: &Path, code: &str, version: &str) -> anyhow::Result<Self> {
+        if !plugin_path.join(MANIFEST_FILE).exists()
+            || !plugin_path.join(MANIFEST_SIGNATURE_FILE).exists()
+        {
+            return Err(anyhow::Error::msg("Plugin is not signed"));
+        }
+
+        let mut files = HashMap::new();
+        let mut walker = WalkDir::new(plugin_path).into_iter();
+        while let Some(entry) = walker.next() {
+            let entry = entry?;
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if file_name.starts_with('.') {
+                continue;
+            }
+            if entry.metadata()?.is_dir() {
+                if file_name == "node_modules" {
+                    walker.skip_current_dir();
+                }
+                continue;
+            }
+            let path = entry
+                .path()
+                .strip_prefix(plugin_path)?
+                .to_string_lossy()
+                .to_string();
+            files.insert(path, BASE64_STANDARD.encode(fs::read(entry.path())?));
+        }
+
+        let bundle = PluginBundle {
+            code: code.to_string(),
+            version: version.to_string(),
+            files,
+        };
+        bundle.check()?;
+        Ok(bundle)
+    }
+
+    pub fn from_slice(data: &[u8]) -> anyhow::Result<Self> {
+        let bundle: PluginBundle = serde_json::from_slice(data)?;
+        bundle.check()?;
+        Ok(bundle)
+    }
+
+    /// Writes the plugin files into `dir`
+    pub(crate) fn unpack(&self, dir: &Path) -> anyhow::Result<()> {
+        self.check()?;
+        for (path, content) in &self.files {
+            let file_path = dir.join(path);
+            if let Some(parent) = file_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(file_path, BASE64_STANDARD.decode(content)?)?;
+        }
+        Ok(())
+    }
+
+    /// Checks the plugin code can be used as a dir name and that no file is written outside the
+    /// plugin dir
+    fn check(&self) -> anyhow::Result<()> {
+        if self.code.is_empty()
+            || self.code.starts_with('.')
+            || !self
+                .code
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+        {
+            return Err(anyhow::Error::msg(format!(
+                "Invalid plugin code: {}",
+                self.code
+            )));
+        }
+        if self.version.trim().is_empty() {
+            return Err(anyhow::Error::msg("Plugin version is missing"));
+        }
+        for path in self.files.keys() {
+            let is_relative = PathBuf::from(path)
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+            if path.is_empty() || !is_relative {
+                return Err(anyhow::Error::msg(format!(
+                    "Invalid plugin file path: {}",
+                    path
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::PluginBundle;
+
+    #[test]
+    fn plugin_bundle_check() {
+        let bundle = |code: &str, path: &str| PluginBundle {
+            code: code.to_string(),
+            version: "1.0.0".to_string(),
+            files: HashMap::from([(path.to_string(), "".to_string())]),
+        };
+
+        assert!(bundle("StockDonor", "plugin.json").check().is_ok());
+        assert!(bundle("StockDonor", "dist/index.js").check().is_ok());
+        assert!(bundle("StockDonor", "../plugin.json").check().is_err());
+        assert!(bundle("StockDonor", "/etc/passwd").check().is_err());
+        assert!(bundle("StockDonor", "dist/../../plugin.json")
+            .check()
+            .is_err());
+        assert!(bundle("../StockDonor", "plugin.json").check().is_err());
+        assert!(bundle(".hidden", "plugin.json").check().is_err());
+        assert!(bundle("", "plugin.json").check().is_err());
+    }
+}
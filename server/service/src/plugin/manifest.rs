@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use walkdir::WalkDir;
 
-use super::{MANIFEST_FILE, MANIFEST_SIGNATURE_FILE};
+use super::{MANIFEST_FILE, MANIFEST_SIGNATURE_FILE, PLUGIN_FILE, SERVER_PLUGIN_FILE};
 
 /// Various details about how the manifest is signed
 #[derive(Clone, Serialize, Deserialize)]
@@ -18,6 +18,9 @@ pub struct ManifestSignatureInfo {
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Manifest {
+    /// Plugin code the plugin was signed for, required to distribute the plugin as a bundle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
     pub files: HashMap<String, String>,
     pub signature: ManifestSignatureInfo,
 }
@@ -32,13 +35,25 @@ impl Manifest {
         filename: &str,
         file_path: &PathBuf,
     ) -> anyhow::Result<Option<String>> {
+        let Some(content) = self.read_and_validate_bytes(filename, file_path)? else {
+            return Ok(None);
+        };
+        Ok(Some(String::from_utf8(content)?))
+    }
+
+    /// Same as `read_and_validate_file` but for binary files, e.g. WASM modules
+    pub(crate) fn read_and_validate_bytes(
+        &self,
+        filename: &str,
+        file_path: &PathBuf,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
         let Some(manifest_file_hash) = self.files.get(filename) else {
             return Ok(None);
         };
 
-        let content = fs::read_to_string(file_path)?;
+        let content = fs::read(file_path)?;
         let mut hasher = Sha256::new();
-        hasher.update(content.as_bytes());
+        hasher.update(&content);
         let file_hash = hex::encode(hasher.finalize());
 
         if manifest_file_hash != &file_hash {
@@ -50,15 +65,17 @@ impl Manifest {
 
 pub(crate) fn create_manifest(
     plugin_path: &Path,
+    code: Option<&str>,
     signature: ManifestSignatureInfo,
 ) -> anyhow::Result<String> {
     let plugin_path = PathBuf::from(plugin_path);
     if !plugin_path.exists() {
         return Err(anyhow::Error::msg("Plugin dir does not exist"));
     }
-    let plugin_json_path = plugin_path.join("plugin.json");
-    if !plugin_json_path.exists() {
-        return Err(anyhow::Error::msg("Invalid plugin dir (no plugin.json)"));
+    if !plugin_path.join(PLUGIN_FILE).exists() && !plugin_path.join(SERVER_PLUGIN_FILE).exists() {
+        return Err(anyhow::Error::msg(
+            "Invalid plugin dir (no plugin.json or server_plugin.json)",
+        ));
     }
 
     // collect all files + hashes of the plugin
@@ -89,8 +106,8 @@ pub(crate) fn create_manifest(
 
         // calculate file hash
         let mut hasher = Sha256::new();
-        let file_data = fs::read_to_string(entry.path())?;
-        hasher.update(file_data.as_bytes());
+        let file_data = fs::read(entry.path())?;
+        hasher.update(&file_data);
         let file_hash = hasher.finalize();
 
         files.insert(
@@ -102,6 +119,10 @@ pub(crate) fn create_manifest(
             hex::encode(file_hash),
         );
     }
-    let manifest = Manifest { files, signature };
+    let manifest = Manifest {
+        code: code.map(str::to_string),
+        files,
+        signature,
+    };
     Ok(serde_json::to_string_pretty(&manifest)?)
 }
@@ -7,10 +7,16 @@ pub(crate) const VERIFICATION_ALGO_PSS: &str = "pss";
 
 pub(crate) const PLUGIN_FILE_DIR: &str = "plugins";
 pub(crate) const PLUGIN_CERT_DIR: &str = "plugin_certs";
+/// Fingerprints of revoked plugin certificates, written from the plugin_certificate_revocation table
+pub(crate) const PLUGIN_REVOCATION_FILE: &str = "plugin_cert_revocations.json";
 pub(crate) const MANIFEST_FILE: &str = "manifest.json";
 pub(crate) const MANIFEST_SIGNATURE_FILE: &str = "manifest.signature";
 pub(crate) const PLUGIN_FILE: &str = "plugin.json";
+pub(crate) const SERVER_PLUGIN_FILE: &str = "server_plugin.json";
 
+pub mod bundle;
 pub mod manifest;
 pub mod plugin_files;
+pub mod plugin_version;
+pub mod server_plugin;
 pub mod validation;
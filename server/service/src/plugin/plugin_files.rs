@@ -88,7 +88,7 @@ impl PluginFileService {
     }
 }
 
-fn get_plugin_dir(base_dir: &Option<String>) -> Result<PathBuf, anyhow::Error> {
+pub(crate) fn get_plugin_dir(base_dir: &Option<String>) -> Result<PathBuf, anyhow::Error> {
     Ok(match base_dir {
         Some(file_dir) => PathBuf::from_str(file_dir)?.join(PLUGIN_FILE_DIR),
         None => PathBuf::from_str(PLUGIN_FILE_DIR)?,
@@ -101,6 +101,21 @@ fn read_plugin_file(
     filename: &str,
     file_path: &PathBuf,
 ) -> anyhow::Result<Option<String>> {
+    let Some(content) = read_plugin_file_bytes(plugin_bucket, plugin_dir, filename, file_path)?
+    else {
+        return Ok(None);
+    };
+    Ok(Some(String::from_utf8(content)?))
+}
+
+/// Reads a file of a plugin, the plugin must be signed by a trusted cert and the file must be
+/// listed in the plugin manifest (unless in dev mode)
+pub(crate) fn read_plugin_file_bytes(
+    plugin_bucket: &Mutex<ValidatedPluginBucket>,
+    plugin_dir: &PathBuf,
+    filename: &str,
+    file_path: &PathBuf,
+) -> anyhow::Result<Option<Vec<u8>>> {
     let mut validated_plugins = plugin_bucket.lock().unwrap();
     let validated_plugin = match validated_plugins.validate_plugin(plugin_dir) {
         Ok(validated_plugin) => validated_plugin,
@@ -110,11 +125,11 @@ fn read_plugin_file(
                 return Ok(None);
             }
             log::warn!("Continue serving plugin file in dev mode: {:?}", file_path);
-            return Ok(Some(fs::read_to_string(file_path)?));
+            return Ok(Some(fs::read(file_path)?));
         }
     };
     let plugin_manifest = validated_plugin.manifest;
-    let Some(content) = plugin_manifest.read_and_validate_file(filename, file_path)? else {
+    let Some(content) = plugin_manifest.read_and_validate_bytes(filename, file_path)? else {
         log::warn!("Plugin file not in manifest: {}", filename);
         return Ok(None);
     };
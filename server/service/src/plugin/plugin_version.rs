@@ -0,0 +1,598 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::Utc;
+use repository::{
+    sync_file_reference_row::{
+        SyncFileDirection, SyncFileReferenceRow, SyncFileReferenceRowRepository, SyncFileStatus,
+    },
+    PluginCertificateRevocationRow, PluginCertificateRevocationRowRepository, PluginVersionRow,
+    PluginVersionRowRepository, RepositoryError, StorageConnection,
+};
+use util::{sanitize_filename, uuid::uuid};
+
+use crate::{
+    service_provider::ServiceContext,
+    static_files::{StaticFileCategory, StaticFileService},
+    sync::CentralServerConfig,
+    usize_to_i32,
+};
+
+use super::{
+    bundle::PluginBundle,
+    manifest::Manifest,
+    plugin_files::get_plugin_dir,
+    validation::{manifest_certificate_fingerprint, verify_plugin_dir, write_revoked_fingerprints},
+};
+
+/// Table name of the sync file references for plugin bundles
+pub const PLUGIN_BUNDLE_TABLE_NAME: &str = "plugin_version";
+/// Dir for unpacking plugin bundles before they are validated and installed
+const PLUGIN_STAGING_DIR: &str = "plugin_staging";
+/// Marker file in plugin dirs installed from a plugin version, contains the plugin version id.
+/// Plugin dirs without this file were installed by hand and are left alone.
+const INSTALLED_VERSION_FILE: &str = ".plugin_version";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginVersion {
+    pub plugin_version_row: PluginVersionRow,
+    /// The certificate the plugin was signed with has been revoked
+    pub is_revoked: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UploadPluginBundleError {
+    NotACentralServer,
+    InvalidBundle(String),
+    /// Signature or certificate of the plugin can't be verified
+    PluginValidationFailed(String),
+    PluginVersionAlreadyExists,
+    DatabaseError(RepositoryError),
+    InternalError(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpdatePluginVersionError {
+    NotACentralServer,
+    PluginVersionDoesNotExist,
+    CertificateRevoked,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RevokePluginCertificateError {
+    NotACentralServer,
+    InvalidFingerprint,
+    CertificateAlreadyRevoked,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RevokePluginCertificate {
+    /// SHA-256 fingerprint of the certificate, e.g. as printed by
+    /// `openssl x509 -noout -fingerprint -sha256 -in cert.pem`
+    pub fingerprint: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct InstalledPlugins {
+    /// Codes of the plugins that have been installed or updated
+    pub installed: Vec<String>,
+    /// Codes of the plugins that have been removed
+    pub removed: Vec<String>,
+    /// Sync file reference ids of plugin bundles that are not available locally yet
+    pub missing_bundle_file_ids: Vec<String>,
+}
+
+pub fn get_plugin_versions(ctx: &ServiceContext) -> Result<Vec<PluginVersion>, RepositoryError> {
+    let revoked = revoked_fingerprints(&ctx.connection)?;
+    let versions = PluginVersionRowRepository::new(&ctx.connection)
+        .find_all()?
+        .into_iter()
+        .map(|plugin_version_row| PluginVersion {
+            is_revoked: revoked.contains(&plugin_version_row.certificate_fingerprint),
+            plugin_version_row,
+        })
+        .collect();
+    Ok(versions)
+}
+
+/// Validates and stores a new plugin version. The new version is not active until it is
+/// activated with `activate_plugin_version`.
+pub fn upload_plugin_bundle(
+    ctx: &ServiceContext,
+    base_dir: &Option<String>,
+    data: &[u8],
+) -> Result<PluginVersionRow, UploadPluginBundleError> {
+    use UploadPluginBundleError as Error;
+    if !CentralServerConfig::is_central_server() {
+        return Err(Error::NotACentralServer);
+    }
+
+    let bundle =
+        PluginBundle::from_slice(data).map_err(|err| Error::InvalidBundle(err.to_string()))?;
+    let certificate_fingerprint = {
+        let revoked = revoked_fingerprints(&ctx.connection)?;
+        let staging_dir = create_staging_dir(base_dir).map_err(internal_error)?;
+        bundle.unpack(staging_dir.path()).map_err(internal_error)?;
+        let manifest = verify_plugin_dir(staging_dir.path(), base_dir, &revoked)
+            .map_err(|err| Error::PluginValidationFailed(err.to_string()))?;
+        check_signed_code(&bundle, &manifest)
+            .map_err(|err| Error::PluginValidationFailed(err.to_string()))?;
+        manifest_certificate_fingerprint(&manifest)
+            .map_err(|err| Error::PluginValidationFailed(err.to_string()))?
+    };
+
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let repo = PluginVersionRowRepository::new(connection);
+            if repo
+                .find_many_by_code(&bundle.code)?
+                .iter()
+                .any(|existing| existing.version == bundle.version)
+            {
+                return Err(Error::PluginVersionAlreadyExists);
+            }
+
+            let id = uuid();
+            let file_service = StaticFileService::new(base_dir).map_err(internal_error)?;
+            let file = file_service
+                .reserve_file(
+                    &sanitize_filename(format!("{}_{}.json", bundle.code, bundle.version)),
+                    &StaticFileCategory::SyncFile(PLUGIN_BUNDLE_TABLE_NAME.to_string(), id.clone()),
+                    None,
+                )
+                .map_err(internal_error)?;
+            fs::write(&file.path, data).map_err(internal_error)?;
+
+            let now = Utc::now().naive_utc();
+            SyncFileReferenceRowRepository::new(connection).upsert_one(&SyncFileReferenceRow {
+                id: file.id.clone(),
+                table_name: PLUGIN_BUNDLE_TABLE_NAME.to_string(),
+                record_id: id.clone(),
+                file_name: file.name,
+                mime_type: Some("application/json".to_string()),
+                total_bytes: usize_to_i32(data.len()),
+                created_datetime: now,
+                // The bundle is already stored on central, remote sites download it on demand
+                status: SyncFileStatus::Done,
+                direction: SyncFileDirection::Download,
+                ..Default::default()
+            })?;
+
+            let row = PluginVersionRow {
+                id,
+                code: bundle.code.clone(),
+                version: bundle.version.clone(),
+                bundle_file_id: file.id,
+                certificate_fingerprint,
+                is_active: false,
+                created_datetime: now,
+            };
+            repo.upsert_one(&row)?;
+            Ok(row)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+/// Makes the plugin version the installed version of the plugin. Activating a previous version
+/// rolls the plugin back.
+pub fn activate_plugin_version(
+    ctx: &ServiceContext,
+    base_dir: &Option<String>,
+    plugin_version_id: &str,
+) -> Result<PluginVersionRow, UpdatePluginVersionError> {
+    set_plugin_version_active(ctx, base_dir, plugin_version_id, true)
+}
+
+/// Uninstalls the plugin from all sites
+pub fn deactivate_plugin_version(
+    ctx: &ServiceContext,
+    base_dir: &Option<String>,
+    plugin_version_id: &str,
+) -> Result<PluginVersionRow, UpdatePluginVersionError> {
+    set_plugin_version_active(ctx, base_dir, plugin_version_id, false)
+}
+
+fn set_plugin_version_active(
+    ctx: &ServiceContext,
+    base_dir: &Option<String>,
+    plugin_version_id: &str,
+    is_active: bool,
+) -> Result<PluginVersionRow, UpdatePluginVersionError> {
+    use UpdatePluginVersionError as Error;
+    if !CentralServerConfig::is_central_server() {
+        return Err(Error::NotACentralServer);
+    }
+
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let repo = PluginVersionRowRepository::new(connection);
+            let plugin_version = repo
+                .find_one_by_id(plugin_version_id)?
+                .ok_or(Error::PluginVersionDoesNotExist)?;
+            if is_active
+                && revoked_fingerprints(connection)?
+                    .contains(&plugin_version.certificate_fingerprint)
+            {
+                return Err(Error::CertificateRevoked);
+            }
+
+            // Only one version of a plugin can be active
+            for version in repo.find_many_by_code(&plugin_version.code)? {
+                let version_is_active = is_active && version.id == plugin_version.id;
+                if version.is_active != version_is_active {
+                    repo.upsert_one(&PluginVersionRow {
+                        is_active: version_is_active,
+                        ..version
+                    })?;
+                }
+            }
+            repo.find_one_by_id(plugin_version_id)?
+                .ok_or(Error::PluginVersionDoesNotExist)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    install_and_log(&ctx.connection, base_dir);
+    Ok(result)
+}
+
+/// Revokes a plugin certificate on all sites. Plugins signed with the certificate, or with a
+/// certificate issued by it, fail validation and versions signed with it are deactivated.
+pub fn revoke_plugin_certificate(
+    ctx: &ServiceContext,
+    base_dir: &Option<String>,
+    input: RevokePluginCertificate,
+) -> Result<PluginCertificateRevocationRow, RevokePluginCertificateError> {
+    use RevokePluginCertificateError as Error;
+    if !CentralServerConfig::is_central_server() {
+        return Err(Error::NotACentralServer);
+    }
+
+    let fingerprint = input.fingerprint.replace(':', "").trim().to_lowercase();
+    if fingerprint.len() != 64 || !fingerprint.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::InvalidFingerprint);
+    }
+
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let repo = PluginCertificateRevocationRowRepository::new(connection);
+            if repo.find_one_by_fingerprint(&fingerprint)?.is_some() {
+                return Err(Error::CertificateAlreadyRevoked);
+            }
+
+            let row = PluginCertificateRevocationRow {
+                id: uuid(),
+                fingerprint: fingerprint.clone(),
+                reason: input.reason,
+                revoked_datetime: Utc::now().naive_utc(),
+            };
+            repo.upsert_one(&row)?;
+
+            let version_repo = PluginVersionRowRepository::new(connection);
+            for version in version_repo.find_all_active()? {
+                if version.certificate_fingerprint == fingerprint {
+                    version_repo.upsert_one(&PluginVersionRow {
+                        is_active: false,
+                        ..version
+                    })?;
+                }
+            }
+            Ok(row)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    install_and_log(&ctx.connection, base_dir);
+    Ok(result)
+}
+
+/// Brings the plugin dir in line with the active plugin versions and writes the certificate
+/// revocation list. Plugin bundles that haven't been downloaded yet are returned in
+/// `missing_bundle_file_ids`, they are installed on the next call after they are downloaded.
+pub fn install_plugin_versions(
+    connection: &StorageConnection,
+    base_dir: &Option<String>,
+) -> anyhow::Result<InstalledPlugins> {
+    let revoked = revoked_fingerprints(connection)?;
+    write_revoked_fingerprints(base_dir, &revoked)?;
+
+    let active_versions: Vec<PluginVersionRow> = PluginVersionRowRepository::new(connection)
+        .find_all_active()?
+        .into_iter()
+        .filter(|version| !revoked.contains(&version.certificate_fingerprint))
+        .collect();
+
+    let plugin_dir = get_plugin_dir(base_dir)?;
+    fs::create_dir_all(&plugin_dir)?;
+    let file_service = StaticFileService::new(base_dir)?;
+
+    let mut result = InstalledPlugins::default();
+    for version in &active_versions {
+        let target_dir = plugin_dir.join(&version.code);
+        if installed_version(&target_dir).as_deref() == Some(version.id.as_str()) {
+            continue;
+        }
+
+        let Some(bundle_file) = file_service.find_file(
+            &version.bundle_file_id,
+            StaticFileCategory::SyncFile(PLUGIN_BUNDLE_TABLE_NAME.to_string(), version.id.clone()),
+        )?
+        else {
+            result
+                .missing_bundle_file_ids
+                .push(version.bundle_file_id.clone());
+            continue;
+        };
+
+        match install_bundle(base_dir, &target_dir, version, &bundle_file.path, &revoked) {
+            Ok(()) => result.installed.push(version.code.clone()),
+            Err(err) => log::error!(
+                "Failed to install plugin {} version {}: {}",
+                version.code,
+                version.version,
+                err
+            ),
+        }
+    }
+
+    // Remove plugins that were installed from a plugin version that is no longer active
+    for entry in fs::read_dir(&plugin_dir)? {
+        let dir = entry?.path();
+        if installed_version(&dir).is_none() {
+            continue;
+        }
+        let code = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if active_versions.iter().any(|version| version.code == code) {
+            continue;
+        }
+        fs::remove_dir_all(&dir)?;
+        result.removed.push(code);
+    }
+
+    Ok(result)
+}
+
+fn install_bundle(
+    base_dir: &Option<String>,
+    target_dir: &Path,
+    version: &PluginVersionRow,
+    bundle_path: &str,
+    revoked: &HashSet<String>,
+) -> anyhow::Result<()> {
+    let bundle = PluginBundle::from_slice(&fs::read(bundle_path)?)?;
+    if bundle.code != version.code {
+        return Err(anyhow::Error::msg(format!(
+            "Plugin bundle code {} doesn't match the plugin version",
+            bundle.code
+        )));
+    }
+
+    // Only plugin dirs installed from a plugin version are replaced
+    if target_dir.exists() && installed_version(target_dir).is_none() {
+        return Err(anyhow::Error::msg(format!(
+            "Plugin dir {} was installed by hand, remove it to install the plugin version",
+            target_dir.display()
+        )));
+    }
+
+    // Sites validate the bundle against their own trusted certificates before installing it
+    let staging_dir = create_staging_dir(base_dir)?;
+    bundle.unpack(staging_dir.path())?;
+    let manifest = verify_plugin_dir(staging_dir.path(), base_dir, revoked)?;
+    check_signed_code(&bundle, &manifest)?;
+    fs::write(staging_dir.path().join(INSTALLED_VERSION_FILE), &version.id)?;
+
+    if target_dir.exists() {
+        fs::remove_dir_all(target_dir)?;
+    }
+    fs::rename(staging_dir.into_path(), target_dir)?;
+    Ok(())
+}
+
+/// The plugin code is signed with the plugin files, so a signed plugin can't be installed in place
+/// of another plugin
+fn check_signed_code(bundle: &PluginBundle, manifest: &Manifest) -> anyhow::Result<()> {
+    match &manifest.code {
+        Some(code) if *code == bundle.code => Ok(()),
+        Some(code) => Err(anyhow::Error::msg(format!(
+            "Plugin bundle code {} doesn't match the signed plugin code {}",
+            bundle.code, code
+        ))),
+        None => Err(anyhow::Error::msg(
+            "Plugin was signed without a plugin code, sign it with --code to bundle it",
+        )),
+    }
+}
+
+fn install_and_log(connection: &StorageConnection, base_dir: &Option<String>) {
+    if let Err(err) = install_plugin_versions(connection, base_dir) {
+        log::error!("Failed to install plugin versions: {}", err);
+    }
+}
+
+fn installed_version(plugin_dir: &Path) -> Option<String> {
+    fs::read_to_string(plugin_dir.join(INSTALLED_VERSION_FILE)).ok()
+}
+
+fn revoked_fingerprints(
+    connection: &StorageConnection,
+) -> Result<HashSet<String>, RepositoryError> {
+    Ok(PluginCertificateRevocationRowRepository::new(connection)
+        .find_all()?
+        .into_iter()
+        .map(|row| row.fingerprint)
+        .collect())
+}
+
+fn create_staging_dir(base_dir: &Option<String>) -> anyhow::Result<tempfile::TempDir> {
+    let staging_dir = match base_dir {
+        Some(base_dir) => PathBuf::from_str(base_dir)?.join(PLUGIN_STAGING_DIR),
+        None => PathBuf::from_str(PLUGIN_STAGING_DIR)?,
+    };
+    fs::create_dir_all(&staging_dir)?;
+    Ok(tempfile::tempdir_in(staging_dir)?)
+}
+
+fn internal_error<E: std::fmt::Display>(error: E) -> UploadPluginBundleError {
+    UploadPluginBundleError::InternalError(error.to_string())
+}
+
+impl From<RepositoryError> for UploadPluginBundleError {
+    fn from(error: RepositoryError) -> Self {
+        UploadPluginBundleError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for UpdatePluginVersionError {
+    fn from(error: RepositoryError) -> Self {
+        UpdatePluginVersionError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for RevokePluginCertificateError {
+    fn from(error: RepositoryError) -> Self {
+        RevokePluginCertificateError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashSet, fs};
+
+    use repository::{
+        mock::MockDataInserts, test_db::setup_all, PluginCertificateRevocationRow,
+        PluginCertificateRevocationRowRepository, PluginVersionRow, PluginVersionRowRepository,
+    };
+
+    use crate::plugin::{
+        bundle::PluginBundle,
+        manifest::{Manifest, ManifestSignatureInfo},
+        plugin_files::get_plugin_dir,
+        validation::get_revocation_path,
+    };
+
+    use super::{
+        check_signed_code, install_bundle, install_plugin_versions, InstalledPlugins,
+        INSTALLED_VERSION_FILE,
+    };
+
+    #[actix_rt::test]
+    async fn plugin_version_install() {
+        let (_, connection, _, _) =
+            setup_all("plugin_version_install", MockDataInserts::none()).await;
+        let temp_dir = tempfile::tempdir().unwrap();
+        let base_dir = Some(temp_dir.path().to_string_lossy().to_string());
+        let plugin_dir = get_plugin_dir(&base_dir).unwrap();
+
+        let version_repo = PluginVersionRowRepository::new(&connection);
+        let version = |id: &str, code: &str, fingerprint: &str| PluginVersionRow {
+            id: id.to_string(),
+            code: code.to_string(),
+            version: "1.0.0".to_string(),
+            bundle_file_id: format!("{}_file", id),
+            certificate_fingerprint: fingerprint.to_string(),
+            is_active: true,
+            ..Default::default()
+        };
+        version_repo
+            .upsert_one(&version("dashboard_v1", "Dashboard", "a"))
+            .unwrap();
+        version_repo
+            .upsert_one(&version("donor_v1", "StockDonor", "b"))
+            .unwrap();
+        PluginCertificateRevocationRowRepository::new(&connection)
+            .upsert_one(&PluginCertificateRevocationRow {
+                id: "revocation".to_string(),
+                fingerprint: "b".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        // Installed from a plugin version that is revoked now
+        fs::create_dir_all(plugin_dir.join("StockDonor")).unwrap();
+        fs::write(
+            plugin_dir.join("StockDonor").join(INSTALLED_VERSION_FILE),
+            "donor_v1",
+        )
+        .unwrap();
+        // Installed by hand
+        fs::create_dir_all(plugin_dir.join("Manual")).unwrap();
+
+        let result = install_plugin_versions(&connection, &base_dir).unwrap();
+        assert_eq!(
+            result,
+            InstalledPlugins {
+                installed: vec![],
+                removed: vec!["StockDonor".to_string()],
+                missing_bundle_file_ids: vec!["dashboard_v1_file".to_string()],
+            }
+        );
+        assert!(!plugin_dir.join("StockDonor").exists());
+        assert!(plugin_dir.join("Manual").exists());
+
+        let revoked: HashSet<String> = serde_json::from_str(
+            &fs::read_to_string(get_revocation_path(&base_dir).unwrap()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(revoked, HashSet::from(["b".to_string()]));
+    }
+
+    #[test]
+    fn plugin_version_install_bundle_checks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let base_dir = Some(temp_dir.path().to_string_lossy().to_string());
+        let plugin_dir = get_plugin_dir(&base_dir).unwrap();
+
+        let bundle = PluginBundle {
+            code: "Dashboard".to_string(),
+            version: "1.0.0".to_string(),
+            files: Default::default(),
+        };
+        let bundle_path = temp_dir.path().join("bundle.json");
+        fs::write(&bundle_path, serde_json::to_string(&bundle).unwrap()).unwrap();
+        let version = PluginVersionRow {
+            id: "dashboard_v1".to_string(),
+            code: "Dashboard".to_string(),
+            ..Default::default()
+        };
+
+        // Plugin installed by hand under the same code is left alone
+        let target_dir = plugin_dir.join("Dashboard");
+        fs::create_dir_all(&target_dir).unwrap();
+        fs::write(target_dir.join("plugin.json"), "{}").unwrap();
+        assert!(install_bundle(
+            &base_dir,
+            &target_dir,
+            &version,
+            &bundle_path.to_string_lossy(),
+            &HashSet::new()
+        )
+        .is_err());
+        assert!(target_dir.join("plugin.json").exists());
+
+        // Bundle code must be the signed code
+        let manifest = |code: Option<&str>| Manifest {
+            code: code.map(str::to_string),
+            files: Default::default(),
+            signature: ManifestSignatureInfo {
+                cert: String::new(),
+                algo: String::new(),
+                hash: String::new(),
+            },
+        };
+        assert!(check_signed_code(&bundle, &manifest(Some("Dashboard"))).is_ok());
+        assert!(check_signed_code(&bundle, &manifest(Some("Other"))).is_err());
+        assert!(check_signed_code(&bundle, &manifest(None)).is_err());
+    }
+}
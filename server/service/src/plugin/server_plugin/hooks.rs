@@ -0,0 +1,227 @@
+use repository::{
+    ActivityLogType, InvoiceStatus, InvoiceType, RepositoryError, RequisitionLineRow,
+};
+use serde::Serialize;
+
+use crate::{activity_log::activity_log_entry, service_provider::ServiceContext};
+
+use super::{
+    host::{PluginHookResult, ServerPluginHost},
+    PluginHook,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginVeto {
+    pub plugin_code: String,
+    pub reason: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PluginHookError {
+    Vetoed(PluginVeto),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for PluginHookError {
+    fn from(error: RepositoryError) -> Self {
+        PluginHookError::DatabaseError(error)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InvoiceStatusChangeHookInput {
+    pub store_id: String,
+    pub invoice_id: String,
+    pub invoice_type: InvoiceType,
+    pub name_link_id: String,
+    pub current_status: InvoiceStatus,
+    pub new_status: InvoiceStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StocktakeFinaliseHookInput {
+    pub store_id: String,
+    pub stocktake_id: String,
+    pub stocktake_number: i64,
+    pub inventory_addition_id: Option<String>,
+    pub inventory_reduction_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RequisitionLineHookInput {
+    pub store_id: String,
+    pub requisition_id: String,
+    pub requisition_line_id: String,
+    pub item_link_id: String,
+    pub requested_quantity: f64,
+    pub suggested_quantity: f64,
+    pub available_stock_on_hand: f64,
+    pub comment: Option<String>,
+}
+
+impl RequisitionLineHookInput {
+    pub(crate) fn new(store_id: &str, line: &RequisitionLineRow) -> Self {
+        RequisitionLineHookInput {
+            store_id: store_id.to_string(),
+            requisition_id: line.requisition_id.clone(),
+            requisition_line_id: line.id.clone(),
+            item_link_id: line.item_link_id.clone(),
+            requested_quantity: line.requested_quantity,
+            suggested_quantity: line.suggested_quantity,
+            available_stock_on_hand: line.available_stock_on_hand,
+            comment: line.comment.clone(),
+        }
+    }
+}
+
+/// Runs the server plugins registered for the hook. Plugin annotations are recorded in the
+/// activity log against `record_id`, the first veto (including plugins failing to run) is returned
+/// as an error. Should be called inside the operation's transaction so that a veto rolls back the
+/// operation and plugins read the operation's uncommitted changes.
+pub(crate) fn call_plugin_hook<T: Serialize>(
+    ctx: &ServiceContext,
+    hook: PluginHook,
+    record_id: &str,
+    input: &T,
+) -> Result<(), PluginHookError> {
+    let Some(host) = ServerPluginHost::installed() else {
+        return Ok(());
+    };
+    if !host
+        .plugins()
+        .iter()
+        .any(|plugin| plugin.config.hooks.contains(&hook))
+    {
+        return Ok(());
+    }
+
+    let input = match serde_json::to_value(input) {
+        Ok(input) => input,
+        Err(err) => {
+            log::error!("Failed to serialise {:?} hook input: {}", hook, err);
+            return Err(PluginHookError::Vetoed(PluginVeto {
+                plugin_code: "".to_string(),
+                reason: format!("Failed to serialise hook input: {}", err),
+            }));
+        }
+    };
+
+    let results = host.call_hook(&ctx.connection, &ctx.store_id, hook, &input);
+    apply_hook_results(ctx, record_id, results)
+}
+
+fn apply_hook_results(
+    ctx: &ServiceContext,
+    record_id: &str,
+    results: Vec<PluginHookResult>,
+) -> Result<(), PluginHookError> {
+    let mut veto = None;
+    for PluginHookResult {
+        plugin_code,
+        output,
+    } in results
+    {
+        for annotation in output.annotations {
+            activity_log_entry(
+                ctx,
+                ActivityLogType::PluginAnnotation,
+                Some(record_id.to_string()),
+                None,
+                Some(format!("{}: {}", plugin_code, annotation)),
+            )?;
+        }
+
+        if let (None, Some(reason)) = (&veto, output.veto) {
+            veto = Some(PluginVeto {
+                plugin_code,
+                reason,
+            });
+        }
+    }
+
+    match veto {
+        Some(veto) => Err(PluginHookError::Vetoed(veto)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_outbound_shipment_a, mock_store_a, MockDataInserts},
+        ActivityLogRowRepository, ActivityLogType,
+    };
+
+    use crate::{
+        plugin::server_plugin::host::{PluginHookOutput, PluginHookResult},
+        test_helpers::{setup_all_and_service_provider, ServiceTestContext},
+    };
+
+    use super::{apply_hook_results, PluginHookError, PluginVeto};
+
+    #[actix_rt::test]
+    async fn server_plugin_hook_results() {
+        let ServiceTestContext {
+            connection,
+            service_provider,
+            ..
+        } = setup_all_and_service_provider("server_plugin_hook_results", MockDataInserts::all())
+            .await;
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let record_id = mock_outbound_shipment_a().id;
+
+        let result =
+            |plugin_code: &str, veto: Option<&str>, annotations: Vec<&str>| PluginHookResult {
+                plugin_code: plugin_code.to_string(),
+                output: PluginHookOutput {
+                    veto: veto.map(str::to_string),
+                    annotations: annotations.into_iter().map(str::to_string).collect(),
+                },
+            };
+
+        // No veto
+        assert_eq!(
+            apply_hook_results(
+                &context,
+                &record_id,
+                vec![result("plugin_a", None, vec!["checked"])]
+            ),
+            Ok(())
+        );
+
+        // First veto is returned, all annotations are recorded
+        assert_eq!(
+            apply_hook_results(
+                &context,
+                &record_id,
+                vec![
+                    result("plugin_a", None, vec![]),
+                    result("plugin_b", Some("Missing donor"), vec!["donor check"]),
+                    result("plugin_c", Some("Other reason"), vec![]),
+                ]
+            ),
+            Err(PluginHookError::Vetoed(PluginVeto {
+                plugin_code: "plugin_b".to_string(),
+                reason: "Missing donor".to_string()
+            }))
+        );
+
+        let mut annotations: Vec<String> = ActivityLogRowRepository::new(&connection)
+            .find_many_by_record_id(&record_id)
+            .unwrap()
+            .into_iter()
+            .filter(|log| log.r#type == ActivityLogType::PluginAnnotation)
+            .filter_map(|log| log.changed_to)
+            .collect();
+        annotations.sort();
+        assert_eq!(
+            annotations,
+            vec![
+                "plugin_a: checked".to_string(),
+                "plugin_b: donor check".to_string()
+            ]
+        );
+    }
+}
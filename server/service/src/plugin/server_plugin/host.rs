@@ -0,0 +1,355 @@
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+use extism::{convert::Json, Manifest, PluginBuilder, UserData, Wasm, WasmMetadata, PTR};
+use repository::StorageConnection;
+use serde::{Deserialize, Serialize};
+
+use crate::plugin::{
+    plugin_files::{get_plugin_dir, read_plugin_file_bytes},
+    validation::ValidatedPluginBucket,
+    SERVER_PLUGIN_FILE,
+};
+
+use super::{
+    host_functions::{get_items, get_stock_lines, log_message, with_hook_connection, HostContext},
+    PluginHook, ServerPluginConfig,
+};
+
+static SERVER_PLUGIN_HOST: RwLock<Option<Arc<ServerPluginHost>>> = RwLock::new(None);
+
+pub struct LoadedServerPlugin {
+    pub config: ServerPluginConfig,
+    wasm: Vec<u8>,
+}
+
+impl LoadedServerPlugin {
+    pub fn new(config: ServerPluginConfig, wasm: Vec<u8>) -> Self {
+        LoadedServerPlugin { config, wasm }
+    }
+}
+
+/// What a plugin returned from a hook call
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct PluginHookOutput {
+    /// Reason for rejecting the operation, the operation goes ahead when not set
+    #[serde(default)]
+    pub veto: Option<String>,
+    /// Notes to record against the operation
+    #[serde(default)]
+    pub annotations: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginHookResult {
+    pub plugin_code: String,
+    pub output: PluginHookOutput,
+}
+
+/// Runs signed server side WASM plugins for the hooks they registered
+pub struct ServerPluginHost {
+    plugins: Vec<LoadedServerPlugin>,
+}
+
+impl ServerPluginHost {
+    pub fn new(plugins: Vec<LoadedServerPlugin>) -> Self {
+        ServerPluginHost { plugins }
+    }
+
+    /// Loads the server plugins (plugin dirs with a `server_plugin.json` file) from the plugin
+    /// dir. Both the config and the WASM module are validated against the signed plugin manifest.
+    pub fn load(
+        plugin_bucket: &Mutex<ValidatedPluginBucket>,
+        base_dir: &Option<String>,
+    ) -> anyhow::Result<Self> {
+        let mut plugins = Vec::new();
+        let plugin_base_dir = get_plugin_dir(base_dir)?;
+        if let Ok(false) = plugin_base_dir.try_exists() {
+            return Ok(Self::new(plugins));
+        }
+
+        for plugin_dir in fs::read_dir(plugin_base_dir)? {
+            let plugin_dir = plugin_dir?.path();
+            let config_path = plugin_dir.join(SERVER_PLUGIN_FILE);
+            if !plugin_dir.is_dir() || !config_path.exists() {
+                continue;
+            }
+
+            match load_plugin(plugin_bucket, &plugin_dir, &config_path) {
+                Ok(Some(plugin)) => {
+                    log::info!("Loaded server plugin: {}", plugin.config.code);
+                    plugins.push(plugin)
+                }
+                Ok(None) => log::warn!("Server plugin failed validation: {:?}", plugin_dir),
+                Err(err) => log::error!("Failed to load server plugin {:?}: {}", plugin_dir, err),
+            }
+        }
+
+        Ok(Self::new(plugins))
+    }
+
+    /// Makes the host available to the services calling hooks
+    pub fn install(self) {
+        *SERVER_PLUGIN_HOST.write().unwrap() = Some(Arc::new(self));
+    }
+
+    pub(crate) fn installed() -> Option<Arc<ServerPluginHost>> {
+        SERVER_PLUGIN_HOST.read().unwrap().clone()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn uninstall() {
+        *SERVER_PLUGIN_HOST.write().unwrap() = None;
+    }
+
+    pub fn plugins(&self) -> &[LoadedServerPlugin] {
+        &self.plugins
+    }
+
+    /// Calls the hook function of all plugins that registered for the hook. Each call gets a fresh
+    /// plugin instance limited by the plugin's fuel and time limits. Host functions query through
+    /// `connection`, i.e. inside the transaction of the operation calling the hook.
+    ///
+    /// Hooks can veto the operation, so a plugin that fails (e.g. errors, traps or runs out of
+    /// fuel or time) vetoes it rather than letting the operation through unchecked.
+    pub fn call_hook(
+        &self,
+        connection: &StorageConnection,
+        store_id: &str,
+        hook: PluginHook,
+        input: &serde_json::Value,
+    ) -> Vec<PluginHookResult> {
+        self.plugins
+            .iter()
+            .filter(|plugin| plugin.config.hooks.contains(&hook))
+            .map(|plugin| {
+                let output = with_hook_connection(connection, || {
+                    self.call_plugin(plugin, store_id, hook, input)
+                })
+                .unwrap_or_else(|err| {
+                    log::error!(
+                        "Server plugin {} failed to run {:?}: {}",
+                        plugin.config.code,
+                        hook,
+                        err
+                    );
+                    PluginHookOutput {
+                        veto: Some(format!("Plugin failed to run: {}", err)),
+                        annotations: Vec::new(),
+                    }
+                });
+
+                PluginHookResult {
+                    plugin_code: plugin.config.code.clone(),
+                    output,
+                }
+            })
+            .collect()
+    }
+
+    fn call_plugin(
+        &self,
+        LoadedServerPlugin { config, wasm }: &LoadedServerPlugin,
+        store_id: &str,
+        hook: PluginHook,
+        input: &serde_json::Value,
+    ) -> anyhow::Result<PluginHookOutput> {
+        let manifest = Manifest::new([Wasm::Data {
+            data: wasm.clone(),
+            meta: WasmMetadata {
+                name: Some(config.code.clone()),
+                hash: None,
+            },
+        }])
+        .with_timeout(Duration::from_millis(config.timeout_ms()));
+
+        let user_data = UserData::new(HostContext {
+            plugin_code: config.code.clone(),
+            store_id: store_id.to_string(),
+            permissions: config.permissions.clone(),
+        });
+
+        let mut plugin = PluginBuilder::new(manifest)
+            // Needed for plugins built with the JS PDK, no dirs or network are exposed
+            .with_wasi(true)
+            // See report transform_data
+            .with_cache_disabled()
+            .with_fuel_limit(config.fuel_limit())
+            .with_function("log", [PTR], [], user_data.clone(), log_message)
+            .with_function("get_items", [PTR], [PTR], user_data.clone(), get_items)
+            .with_function("get_stock_lines", [PTR], [PTR], user_data, get_stock_lines)
+            .build()?;
+
+        let Json(output) = plugin.call::<Json<&serde_json::Value>, Json<PluginHookOutput>>(
+            hook.function_name(),
+            Json(input),
+        )?;
+        Ok(output)
+    }
+}
+
+fn load_plugin(
+    plugin_bucket: &Mutex<ValidatedPluginBucket>,
+    plugin_dir: &PathBuf,
+    config_path: &PathBuf,
+) -> anyhow::Result<Option<LoadedServerPlugin>> {
+    let Some(config) =
+        read_plugin_file_bytes(plugin_bucket, plugin_dir, SERVER_PLUGIN_FILE, config_path)?
+    else {
+        return Ok(None);
+    };
+    let config: ServerPluginConfig = serde_json::from_slice(&config)?;
+
+    let wasm_path = plugin_dir.join(&config.wasm);
+    let Some(wasm) = read_plugin_file_bytes(plugin_bucket, plugin_dir, &config.wasm, &wasm_path)?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(LoadedServerPlugin::new(config, wasm)))
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        ItemRow, ItemRowRepository, StorageConnection, TransactionError,
+    };
+
+    use crate::plugin::server_plugin::{HostPermission, PluginHook, ServerPluginConfig};
+
+    use super::{LoadedServerPlugin, PluginHookResult, ServerPluginHost};
+
+    /// Vetoes invoice status changes unless `plugin_item` exists (`get_items` returns more than
+    /// `[]`) and never finishes requisition line validation. Doesn't export a stocktake hook.
+    const PLUGIN_WAT: &str = r#"
+        (module
+            (import "extism:host/env" "alloc" (func $alloc (param i64) (result i64)))
+            (import "extism:host/env" "length" (func $length (param i64) (result i64)))
+            (import "extism:host/env" "store_u8" (func $store_u8 (param i64 i32)))
+            (import "extism:host/env" "output_set" (func $output_set (param i64 i64)))
+            (import "extism:host/user" "get_items" (func $get_items (param i64) (result i64)))
+            (memory 1)
+            (data (i32.const 0) "[\"plugin_item\"]")
+            (data (i32.const 32) "{\"veto\":\"Item not found\"}")
+            (data (i32.const 64) "{}")
+
+            (func $copy (param $from i32) (param $length i32) (result i64)
+                (local $offset i64)
+                (local $i i32)
+                (local.set $offset (call $alloc (i64.extend_i32_u (local.get $length))))
+                (block $done
+                    (loop $next
+                        (br_if $done (i32.ge_u (local.get $i) (local.get $length)))
+                        (call $store_u8
+                            (i64.add (local.get $offset) (i64.extend_i32_u (local.get $i)))
+                            (i32.load8_u (i32.add (local.get $from) (local.get $i))))
+                        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                        (br $next)))
+                (local.get $offset))
+
+            (func $output (param $from i32) (param $length i32)
+                (call $output_set
+                    (call $copy (local.get $from) (local.get $length))
+                    (i64.extend_i32_u (local.get $length))))
+
+            (func (export "before_invoice_status_change") (result i32)
+                (if (i64.eq
+                        (call $length (call $get_items (call $copy (i32.const 0) (i32.const 15))))
+                        (i64.const 2))
+                    (then (call $output (i32.const 32) (i32.const 25)))
+                    (else (call $output (i32.const 64) (i32.const 2))))
+                (i32.const 0))
+
+            (func (export "validate_requisition_line") (result i32)
+                (loop $forever (br $forever))
+                (i32.const 0)))
+    "#;
+
+    fn plugin(code: &str, permissions: Vec<HostPermission>) -> LoadedServerPlugin {
+        LoadedServerPlugin::new(
+            ServerPluginConfig {
+                code: code.to_string(),
+                wasm: "plugin.wasm".to_string(),
+                hooks: vec![
+                    PluginHook::BeforeInvoiceStatusChange,
+                    PluginHook::AfterStocktakeFinalise,
+                    PluginHook::ValidateRequisitionLine,
+                ],
+                permissions,
+                fuel_limit: Some(100_000),
+                timeout_ms: None,
+            },
+            PLUGIN_WAT.as_bytes().to_vec(),
+        )
+    }
+
+    fn vetoes(results: Vec<PluginHookResult>) -> Vec<(String, String)> {
+        results
+            .into_iter()
+            .filter_map(|result| Some((result.plugin_code, result.output.veto?)))
+            .collect()
+    }
+
+    #[actix_rt::test]
+    async fn server_plugin_host_call_hook() {
+        let (_, connection, _, _) =
+            setup_all("server_plugin_host_call_hook", MockDataInserts::none()).await;
+
+        let host = ServerPluginHost::new(vec![plugin("reader", vec![HostPermission::ReadItems])]);
+        let call_hook = |connection: &StorageConnection, hook: PluginHook| {
+            host.call_hook(connection, &mock_store_a().id, hook, &serde_json::json!({}))
+        };
+
+        // Item doesn't exist
+        assert_eq!(
+            vetoes(call_hook(
+                &connection,
+                PluginHook::BeforeInvoiceStatusChange
+            )),
+            vec![("reader".to_string(), "Item not found".to_string())]
+        );
+
+        // Host functions read through the caller's connection, i.e. see the uncommitted item
+        let result = connection.transaction_sync(|connection| {
+            ItemRowRepository::new(connection)
+                .upsert_one(&ItemRow {
+                    id: "plugin_item".to_string(),
+                    ..Default::default()
+                })
+                .unwrap();
+            // Roll back the item
+            Err::<(), _>(call_hook(connection, PluginHook::BeforeInvoiceStatusChange))
+        });
+        let Err(TransactionError::Inner(results)) = result else {
+            panic!("Expected hook results");
+        };
+        assert_eq!(vetoes(results), vec![]);
+
+        // Plugins that fail to run veto the operation: running out of fuel, missing the hook
+        // export or calling host functions without permission
+        for hook in [
+            PluginHook::ValidateRequisitionLine,
+            PluginHook::AfterStocktakeFinalise,
+        ] {
+            let failed = vetoes(call_hook(&connection, hook));
+            assert_eq!(failed.len(), 1, "{:?}", hook);
+            assert!(failed[0].1.starts_with("Plugin failed to run"));
+        }
+
+        let host = ServerPluginHost::new(vec![plugin("no_permission", vec![])]);
+        let failed = vetoes(host.call_hook(
+            &connection,
+            &mock_store_a().id,
+            PluginHook::BeforeInvoiceStatusChange,
+            &serde_json::json!({}),
+        ));
+        assert_eq!(failed.len(), 1);
+        assert!(failed[0].1.starts_with("Plugin failed to run"));
+    }
+}
@@ -0,0 +1,144 @@
+use std::cell::Cell;
+
+use chrono::NaiveDate;
+use extism::{convert::Json, host_fn, Error};
+use repository::{
+    EqualFilter, ItemRowRepository, StockLineFilter, StockLineRepository, StorageConnection,
+};
+use serde::{Deserialize, Serialize};
+
+use super::HostPermission;
+
+/// State available to host functions during a hook call. Queries are scoped to the store the
+/// hook was called for.
+pub(super) struct HostContext {
+    pub(super) plugin_code: String,
+    pub(super) store_id: String,
+    pub(super) permissions: Vec<HostPermission>,
+}
+
+thread_local! {
+    /// Connection of the operation calling the hook, set while a hook call is running. Host
+    /// functions can't borrow it through `UserData` since that requires 'static data.
+    static HOOK_CONNECTION: Cell<*const StorageConnection> = const { Cell::new(std::ptr::null()) };
+}
+
+/// Makes `connection` available to host functions while `f` runs the plugin
+pub(super) fn with_hook_connection<R>(connection: &StorageConnection, f: impl FnOnce() -> R) -> R {
+    struct Reset(*const StorageConnection);
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            HOOK_CONNECTION.with(|hook_connection| hook_connection.set(self.0));
+        }
+    }
+
+    let previous = HOOK_CONNECTION.with(|hook_connection| hook_connection.replace(connection));
+    let _reset = Reset(previous);
+    f()
+}
+
+fn with_connection<R>(f: impl FnOnce(&StorageConnection) -> Result<R, Error>) -> Result<R, Error> {
+    let connection = HOOK_CONNECTION.with(Cell::get);
+    if connection.is_null() {
+        return Err(Error::msg(
+            "Host functions can only be used during a hook call",
+        ));
+    }
+    // Safety: the pointer is only set by `with_hook_connection` for the duration of the borrow
+    // it was created from, and plugins call host functions on the thread running the hook
+    f(unsafe { &*connection })
+}
+
+impl HostContext {
+    fn check_permission(&self, permission: HostPermission) -> Result<(), Error> {
+        if !self.permissions.contains(&permission) {
+            return Err(Error::msg(format!(
+                "Plugin {} doesn't have the {:?} permission",
+                self.plugin_code, permission
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub(super) struct PluginItem {
+    id: String,
+    code: String,
+    name: String,
+    default_pack_size: f64,
+    is_vaccine: bool,
+    vaccine_doses: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(super) struct PluginStockLine {
+    id: String,
+    item_id: String,
+    batch: Option<String>,
+    expiry_date: Option<NaiveDate>,
+    pack_size: f64,
+    available_number_of_packs: f64,
+    total_number_of_packs: f64,
+    on_hold: bool,
+}
+
+host_fn!(pub(super) log_message(user_data: HostContext; message: String) {
+    let context = user_data.get()?;
+    let context = context.lock().unwrap();
+    context.check_permission(HostPermission::Log)?;
+
+    log::info!("Plugin {}: {}", context.plugin_code, message);
+    Ok(())
+});
+
+host_fn!(pub(super) get_items(user_data: HostContext; item_ids: Json<Vec<String>>) -> Json<Vec<PluginItem>> {
+    let context = user_data.get()?;
+    let context = context.lock().unwrap();
+    context.check_permission(HostPermission::ReadItems)?;
+
+    let Json(item_ids) = item_ids;
+    let items = with_connection(|connection| {
+        Ok(ItemRowRepository::new(connection).find_many_by_id(&item_ids)?)
+    })?
+        .into_iter()
+        .map(|item| PluginItem {
+            id: item.id,
+            code: item.code,
+            name: item.name,
+            default_pack_size: item.default_pack_size,
+            is_vaccine: item.is_vaccine,
+            vaccine_doses: item.vaccine_doses,
+        })
+        .collect();
+    Ok(Json(items))
+});
+
+host_fn!(pub(super) get_stock_lines(user_data: HostContext; item_ids: Json<Vec<String>>) -> Json<Vec<PluginStockLine>> {
+    let context = user_data.get()?;
+    let context = context.lock().unwrap();
+    context.check_permission(HostPermission::ReadStockLines)?;
+
+    let Json(item_ids) = item_ids;
+    let stock_lines = with_connection(|connection| {
+        Ok(StockLineRepository::new(connection).query_by_filter(
+            StockLineFilter::new()
+                .store_id(EqualFilter::equal_to(&context.store_id))
+                .item_id(EqualFilter::equal_any(item_ids)),
+            None,
+        )?)
+    })?
+        .into_iter()
+        .map(|stock_line| PluginStockLine {
+            id: stock_line.stock_line_row.id,
+            item_id: stock_line.item_row.id,
+            batch: stock_line.stock_line_row.batch,
+            expiry_date: stock_line.stock_line_row.expiry_date,
+            pack_size: stock_line.stock_line_row.pack_size,
+            available_number_of_packs: stock_line.stock_line_row.available_number_of_packs,
+            total_number_of_packs: stock_line.stock_line_row.total_number_of_packs,
+            on_hold: stock_line.stock_line_row.on_hold,
+        })
+        .collect();
+    Ok(Json(stock_lines))
+});
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+pub mod hooks;
+pub mod host;
+mod host_functions;
+
+/// Upper limits for a single hook call, plugins can only ask for lower limits
+pub const MAX_FUEL_LIMIT: u64 = 500_000_000;
+pub const MAX_TIMEOUT_MS: u64 = 2_000;
+
+/// Operations a server plugin can register for. The plugin WASM module needs to export a function
+/// with the hook name, e.g. `before_invoice_status_change`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginHook {
+    BeforeInvoiceStatusChange,
+    AfterStocktakeFinalise,
+    ValidateRequisitionLine,
+}
+
+impl PluginHook {
+    pub fn function_name(&self) -> &'static str {
+        match self {
+            PluginHook::BeforeInvoiceStatusChange => "before_invoice_status_change",
+            PluginHook::AfterStocktakeFinalise => "after_stocktake_finalise",
+            PluginHook::ValidateRequisitionLine => "validate_requisition_line",
+        }
+    }
+}
+
+/// Host functions a plugin needs to be granted in its (signed) config before it can call them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostPermission {
+    Log,
+    ReadItems,
+    ReadStockLines,
+}
+
+/// Content of the `server_plugin.json` file in a plugin dir
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerPluginConfig {
+    pub code: String,
+    /// WASM module, relative to the plugin dir and listed in the plugin manifest
+    pub wasm: String,
+    pub hooks: Vec<PluginHook>,
+    #[serde(default)]
+    pub permissions: Vec<HostPermission>,
+    pub fuel_limit: Option<u64>,
+    pub timeout_ms: Option<u64>,
+}
+
+impl ServerPluginConfig {
+    pub fn fuel_limit(&self) -> u64 {
+        self.fuel_limit
+            .unwrap_or(MAX_FUEL_LIMIT)
+            .min(MAX_FUEL_LIMIT)
+    }
+
+    pub fn timeout_ms(&self) -> u64 {
+        self.timeout_ms
+            .unwrap_or(MAX_TIMEOUT_MS)
+            .min(MAX_TIMEOUT_MS)
+    }
+
+    pub fn has_permission(&self, permission: HostPermission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{HostPermission, PluginHook, ServerPluginConfig, MAX_FUEL_LIMIT, MAX_TIMEOUT_MS};
+
+    #[test]
+    fn server_plugin_config() {
+        let config: ServerPluginConfig = serde_json::from_str(
+            r#"{
+                "code": "donor_rules",
+                "wasm": "plugin.wasm",
+                "hooks": ["before_invoice_status_change", "validate_requisition_line"],
+                "permissions": ["log"],
+                "timeout_ms": 100,
+                "fuel_limit": 1000000000000
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.hooks,
+            vec![
+                PluginHook::BeforeInvoiceStatusChange,
+                PluginHook::ValidateRequisitionLine
+            ]
+        );
+        assert!(config.has_permission(HostPermission::Log));
+        assert!(!config.has_permission(HostPermission::ReadStockLines));
+        assert_eq!(config.timeout_ms(), 100);
+        // Limits can't be raised above the host limits
+        assert_eq!(config.fuel_limit(), MAX_FUEL_LIMIT);
+        assert_eq!(
+            ServerPluginConfig {
+                timeout_ms: None,
+                ..config
+            }
+            .timeout_ms(),
+            MAX_TIMEOUT_MS
+        );
+    }
+}
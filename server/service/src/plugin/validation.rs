@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fs::{self, File};
 use std::io::Write;
@@ -9,7 +9,7 @@ use std::time::SystemTime;
 use pem::Pem;
 use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey};
 use rsa::pss::{Signature, SigningKey, VerifyingKey};
-use rsa::sha2::Sha256;
+use rsa::sha2::{Digest, Sha256};
 use rsa::signature::{RandomizedSigner, SignatureEncoding, Verifier};
 use rsa::{RsaPrivateKey, RsaPublicKey};
 
@@ -19,7 +19,7 @@ use x509_parser::prelude::{FromDer, X509Certificate};
 use super::manifest::{create_manifest, Manifest, ManifestSignatureInfo};
 use super::{
     CERTIFICATE_TAG, MANIFEST_FILE, MANIFEST_SIGNATURE_FILE, PLUGIN_CERT_DIR, PLUGIN_FILE_DIR,
-    PRIVATE_KEY_TAG, SHA256_NAME, SIGNATURE_TAG, VERIFICATION_ALGO_PSS,
+    PLUGIN_REVOCATION_FILE, PRIVATE_KEY_TAG, SHA256_NAME, SIGNATURE_TAG, VERIFICATION_ALGO_PSS,
 };
 
 #[derive(Clone)]
@@ -36,6 +36,10 @@ pub struct ValidatedPluginBucket {
     plugin_dir: PathBuf,
     /// Dir containing all trusted public plugin certs
     trusted_cert_path: PathBuf,
+    /// File containing the fingerprints of revoked certs
+    revocation_path: PathBuf,
+    /// Modification date of the revocation file when the plugins were last validated
+    revocation_datetime: Option<SystemTime>,
     /// Mapping the absolute plugin to a ValidatedPlugin
     manifests: HashMap<String, ValidatedPlugin>,
 }
@@ -54,6 +58,8 @@ impl ValidatedPluginBucket {
         Ok(ValidatedPluginBucket {
             plugin_dir,
             trusted_cert_path,
+            revocation_path: get_revocation_path(base_dir)?,
+            revocation_datetime: None,
             manifests: HashMap::new(),
         })
     }
@@ -63,7 +69,9 @@ impl ValidatedPluginBucket {
         let path_string = path.as_os_str().to_string_lossy().to_string();
         if let Some(plugin) = self.manifests.get(&path_string) {
             let metadata = File::open(path.join(MANIFEST_FILE))?.metadata()?;
-            if metadata.modified()? == plugin.manifest_datetime {
+            if metadata.modified()? == plugin.manifest_datetime
+                && modified_datetime(&self.revocation_path) == self.revocation_datetime
+            {
                 return Ok(plugin.clone());
             }
         };
@@ -78,6 +86,9 @@ impl ValidatedPluginBucket {
     }
 
     fn reload(&mut self) -> anyhow::Result<()> {
+        // Same as for the manifest, record the timestamp before reading the revocations
+        self.revocation_datetime = modified_datetime(&self.revocation_path);
+        let revoked = load_revoked_fingerprints(&self.revocation_path)?;
         let certs = load_trusted_certs_from_dir(&self.trusted_cert_path)?;
 
         self.manifests.clear();
@@ -95,7 +106,7 @@ impl ValidatedPluginBucket {
             let metadata = File::open(&manifest_path)?.metadata()?;
             let manifest_datetime = metadata.modified()?;
 
-            let manifest = match verify_plugin_manifest(entry.path(), &certs) {
+            let manifest = match verify_plugin_manifest(entry.path(), &certs, &revoked) {
                 Ok(manifest) => manifest,
                 Err(err) => {
                     log::error!("Can't verify plugin: {:?} ({})", entry.path(), err);
@@ -121,10 +132,12 @@ impl ValidatedPluginBucket {
 
 /// Works with RSA keys generated like:
 /// `openssl req -x509 -newkey rsa:2048 -keyout private.pem -out certificate.pem -nodes`
+/// `code` is signed with the plugin files, plugin bundles are only installed under this code
 pub fn sign_plugin(
     plugin_path: &str,
     key_path: &str,
     public_cert_path: &str,
+    code: Option<&str>,
 ) -> anyhow::Result<()> {
     let plugin_path = Path::new(plugin_path);
     // public cert
@@ -144,6 +157,7 @@ pub fn sign_plugin(
     // Create manifest
     let manifest = create_manifest(
         plugin_path,
+        code,
         ManifestSignatureInfo {
             cert: cert_data,
             algo: VERIFICATION_ALGO_PSS.to_string(),
@@ -169,10 +183,76 @@ pub fn sign_plugin(
     // Double check that we can verify the plugin (not strictly needed)
     let trusted_certs =
         load_trusted_certs_from_dir(PathBuf::from(public_cert_path).parent().unwrap())?;
-    verify_plugin_manifest(plugin_path, &trusted_certs)?;
+    verify_plugin_manifest(plugin_path, &trusted_certs, &HashSet::new())?;
     Ok(())
 }
 
+/// Verifies a plugin dir outside the plugin base dir, e.g. an uploaded plugin bundle, against the
+/// trusted certs in `base_dir` and returns the plugin manifest
+pub(crate) fn verify_plugin_dir(
+    plugin_path: &Path,
+    base_dir: &Option<String>,
+    revoked: &HashSet<String>,
+) -> anyhow::Result<Manifest> {
+    let trusted_cert_path = match base_dir {
+        Some(base_dir) => PathBuf::from_str(base_dir)?.join(PLUGIN_CERT_DIR),
+        None => PathBuf::from_str(PLUGIN_CERT_DIR)?,
+    };
+    let trusted_certs = load_trusted_certs_from_dir(&trusted_cert_path)?;
+    verify_plugin_manifest(plugin_path, &trusted_certs, revoked)
+}
+
+/// SHA-256 fingerprint (hex) of a DER encoded certificate
+pub fn certificate_fingerprint(der: &[u8]) -> String {
+    hex::encode(Sha256::digest(der))
+}
+
+/// Fingerprint of the certificate a plugin manifest was signed with
+pub(crate) fn manifest_certificate_fingerprint(manifest: &Manifest) -> anyhow::Result<String> {
+    let pem = pem::parse(&manifest.signature.cert)?;
+    Ok(certificate_fingerprint(pem.contents()))
+}
+
+pub(crate) fn get_revocation_path(base_dir: &Option<String>) -> anyhow::Result<PathBuf> {
+    Ok(match base_dir {
+        Some(base_dir) => PathBuf::from_str(base_dir)?.join(PLUGIN_REVOCATION_FILE),
+        None => PathBuf::from_str(PLUGIN_REVOCATION_FILE)?,
+    })
+}
+
+/// Writes the revocation file, the file is only touched when the fingerprints changed so that
+/// validated plugins are not reloaded unnecessarily
+pub(crate) fn write_revoked_fingerprints(
+    base_dir: &Option<String>,
+    fingerprints: &HashSet<String>,
+) -> anyhow::Result<()> {
+    let revocation_path = get_revocation_path(base_dir)?;
+    if &load_revoked_fingerprints(&revocation_path)? == fingerprints {
+        return Ok(());
+    }
+    let mut fingerprints: Vec<&String> = fingerprints.iter().collect();
+    fingerprints.sort();
+    fs::write(
+        revocation_path,
+        serde_json::to_string_pretty(&fingerprints)?,
+    )?;
+    Ok(())
+}
+
+fn load_revoked_fingerprints(revocation_path: &Path) -> anyhow::Result<HashSet<String>> {
+    if !revocation_path.exists() {
+        return Ok(HashSet::new());
+    }
+    let content = fs::read_to_string(revocation_path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn modified_datetime(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
 fn load_trusted_certs_from_dir(cert_path: &Path) -> anyhow::Result<Vec<Pem>> {
     let walker = WalkDir::new(cert_path);
     let mut out = Vec::<Pem>::new();
@@ -209,8 +289,12 @@ fn load_trusted_certs_from_dir(cert_path: &Path) -> anyhow::Result<Vec<Pem>> {
 fn verify_manifest_certificate(
     manifest_cert: &X509Certificate,
     trusted_certs: &Vec<Pem>,
+    revoked: &HashSet<String>,
 ) -> anyhow::Result<bool> {
     for trusted_cert in trusted_certs {
+        if revoked.contains(&certificate_fingerprint(trusted_cert.contents())) {
+            continue;
+        }
         let trusted_certificate = X509Certificate::from_der(trusted_cert.contents())?;
         let public_key = trusted_certificate.1.public_key();
         match manifest_cert.verify_signature(Some(public_key)) {
@@ -224,6 +308,7 @@ fn verify_manifest_certificate(
 fn verify_plugin_manifest(
     plugin_path: &Path,
     trusted_certs: &Vec<Pem>,
+    revoked: &HashSet<String>,
 ) -> anyhow::Result<Manifest> {
     let manifest_raw = fs::read_to_string(PathBuf::from(plugin_path).join(MANIFEST_FILE))?;
     let manifest: Manifest = serde_json::from_str(&manifest_raw)?;
@@ -232,8 +317,11 @@ fn verify_plugin_manifest(
     if pem.tag() != CERTIFICATE_TAG {
         return Err(anyhow::Error::msg("Not a certificate"));
     }
+    if revoked.contains(&certificate_fingerprint(pem.contents())) {
+        return Err(anyhow::Error::msg("Plugin certificate has been revoked"));
+    }
     let manifest_cert = X509Certificate::from_der(pem.contents())?.1;
-    if !verify_manifest_certificate(&manifest_cert, trusted_certs)? {
+    if !verify_manifest_certificate(&manifest_cert, trusted_certs, revoked)? {
         return Err(anyhow::Error::msg("Plugin certificate is not trusted"));
     }
 
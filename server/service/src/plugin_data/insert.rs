@@ -62,6 +62,7 @@ fn generate(
         related_record_type,
         store_id: store_id.to_string(),
         data,
+        version: 1,
     }
 }
 
@@ -143,6 +144,7 @@ mod test {
                 related_record_type: RelatedRecordType::StockLine,
                 data: "hogwarts".to_string(),
                 store_id: mock_store_a().id.to_string(),
+                version: 1,
             }
         );
     }
@@ -93,6 +93,7 @@ fn generate(
     Ok(PluginDataRow {
         id,
         data,
+        version: existing.version + 1,
         ..existing
     })
 }
@@ -136,6 +137,7 @@ mod test {
                 related_record_type: RelatedRecordType::StockLine,
                 store_id: mock_store_a().id.clone(),
                 data: "test".to_string(),
+                version: 1,
             }
         }
 
@@ -179,6 +181,7 @@ mod test {
             plugin_data,
             inline_edit(&donor, |mut u| {
                 u.data = "hogwarts".to_string();
+                u.version = 2;
                 u
             })
         );
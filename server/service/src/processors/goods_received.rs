@@ -0,0 +1,176 @@
+use repository::{
+    ActivityLogFilter, ActivityLogRepository, ActivityLogRow, ActivityLogRowRepository,
+    ActivityLogType, ChangelogFilter, ChangelogRepository, ChangelogRow, ChangelogTableName,
+    EqualFilter, GoodsReceivedDiscrepancyReason, GoodsReceivedLineRowRepository, InvoiceRow,
+    InvoiceRowRepository, KeyType, RepositoryError, RowActionType, StorageConnection,
+};
+use thiserror::Error;
+use util::{constants::SYSTEM_USER_ID, uuid::uuid};
+
+use crate::{
+    cursor_controller::CursorController,
+    processors::transfer::log_system_error,
+    service_provider::ServiceProvider,
+    sync::{ActiveStoresOnSite, GetActiveStoresOnSiteError},
+};
+
+const CHANGELOG_BATCH_SIZE: u32 = 20;
+
+#[derive(Error, Debug)]
+pub(crate) enum ProcessGoodsReceivedError {
+    #[error("{0}")]
+    GetActiveStoresOnSiteError(GetActiveStoresOnSiteError),
+    #[error("{0:?}")]
+    DatabaseError(RepositoryError),
+    #[error("Problem reporting goods received discrepancies {0:?} {1:?}")]
+    ReportDiscrepanciesError(ChangelogRow, RepositoryError),
+}
+
+/// Reports discrepancies of received goods back to the supplier store. Goods received lines are
+/// synced to the supplier store site, once every line of the inbound shipment has been received an
+/// activity log entry summarising the discrepancies is added to the source outbound shipment
+pub(crate) fn process_goods_received(
+    service_provider: &ServiceProvider,
+) -> Result<(), ProcessGoodsReceivedError> {
+    use ProcessGoodsReceivedError as Error;
+
+    let ctx = service_provider
+        .basic_context()
+        .map_err(Error::DatabaseError)?;
+
+    let active_stores =
+        ActiveStoresOnSite::get(&ctx.connection).map_err(Error::GetActiveStoresOnSiteError)?;
+
+    let changelog_repo = ChangelogRepository::new(&ctx.connection);
+    let cursor_controller = CursorController::new(KeyType::GoodsReceivedTransferProcessorCursor);
+    // Only goods received from stores on this site
+    let filter = ChangelogFilter::new()
+        .table_name(ChangelogTableName::GoodsReceivedLine.equal_to())
+        .name_id(EqualFilter::equal_any(active_stores.name_ids()))
+        .action(RowActionType::Upsert.equal_to());
+
+    loop {
+        let cursor = cursor_controller
+            .get(&ctx.connection)
+            .map_err(Error::DatabaseError)?;
+
+        let logs = changelog_repo
+            .changelogs(cursor, CHANGELOG_BATCH_SIZE, Some(filter.clone()))
+            .map_err(Error::DatabaseError)?;
+
+        if logs.is_empty() {
+            break;
+        }
+
+        for log in logs {
+            let result = ctx.connection.transaction_sync(|connection| {
+                report_discrepancies(connection, &log.record_id, &active_stores.store_ids())
+            });
+
+            match result {
+                Ok(Some(outbound_invoice_id)) => log::info!(
+                    "Goods received discrepancies reported for invoice {}",
+                    outbound_invoice_id
+                ),
+                Ok(None) => {}
+                Err(error) => {
+                    let error =
+                        Error::ReportDiscrepanciesError(log.clone(), error.to_inner_error());
+                    log_system_error(&ctx.connection, &error).map_err(Error::DatabaseError)?;
+                }
+            }
+
+            // Always update cursor and move on to the next log, even if there's an error
+            cursor_controller
+                .update(&ctx.connection, (log.cursor + 1) as u64)
+                .map_err(Error::DatabaseError)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Discrepancies are reported when all below conditions are met:
+///
+/// 1. Goods received line is for an outbound shipment of a store active on this site
+/// 2. All lines of the inbound shipment have been received
+/// 3. At least one line was received with a discrepancy
+/// 4. Discrepancies have not been reported already
+pub(crate) fn report_discrepancies(
+    connection: &StorageConnection,
+    goods_received_line_id: &str,
+    store_ids: &[String],
+) -> Result<Option<String>, RepositoryError> {
+    let repo = GoodsReceivedLineRowRepository::new(connection);
+    let Some(goods_received_line) = repo.find_one_by_id(goods_received_line_id)? else {
+        return Ok(None);
+    };
+    // 1.
+    let Some(outbound_invoice_id) = &goods_received_line.linked_invoice_id else {
+        return Ok(None);
+    };
+    let Some(outbound_invoice) =
+        InvoiceRowRepository::new(connection).find_one_by_id(outbound_invoice_id)?
+    else {
+        return Ok(None);
+    };
+    if !store_ids.contains(&outbound_invoice.store_id) {
+        return Ok(None);
+    }
+    // 2.
+    let lines = repo.find_many_by_linked_invoice_id(outbound_invoice_id)?;
+    if lines.iter().any(|l| l.received_number_of_packs.is_none()) {
+        return Ok(None);
+    }
+    // 3.
+    let discrepancies: Vec<_> = lines.iter().filter(|l| l.has_discrepancy()).collect();
+    if discrepancies.is_empty() {
+        return Ok(None);
+    }
+    // 4.
+    let already_reported = ActivityLogRepository::new(connection).count(Some(
+        ActivityLogFilter::new()
+            .r#type(ActivityLogType::InvoiceDiscrepanciesReported.equal_to())
+            .record_id(EqualFilter::equal_to(outbound_invoice_id)),
+    ))? > 0;
+    if already_reported {
+        return Ok(None);
+    }
+
+    let count = |reason: GoodsReceivedDiscrepancyReason| {
+        discrepancies
+            .iter()
+            .filter(|l| l.discrepancy_reason.as_ref() == Some(&reason))
+            .count()
+    };
+    let summary = format!(
+        "{} of {} lines received with discrepancies (short: {}, excess: {}, damaged: {})",
+        discrepancies.len(),
+        lines.len(),
+        count(GoodsReceivedDiscrepancyReason::Short),
+        count(GoodsReceivedDiscrepancyReason::Excess),
+        count(GoodsReceivedDiscrepancyReason::Damaged),
+    );
+
+    insert_discrepancy_log(connection, &outbound_invoice, summary)?;
+
+    Ok(Some(outbound_invoice.id))
+}
+
+fn insert_discrepancy_log(
+    connection: &StorageConnection,
+    outbound_invoice: &InvoiceRow,
+    summary: String,
+) -> Result<(), RepositoryError> {
+    ActivityLogRowRepository::new(connection).insert_one(&ActivityLogRow {
+        id: uuid(),
+        r#type: ActivityLogType::InvoiceDiscrepanciesReported,
+        user_id: Some(SYSTEM_USER_ID.to_string()),
+        store_id: Some(outbound_invoice.store_id.clone()),
+        record_id: Some(outbound_invoice.id.clone()),
+        datetime: chrono::Utc::now().naive_utc(),
+        changed_from: None,
+        changed_to: Some(summary),
+    })?;
+    Ok(())
+}
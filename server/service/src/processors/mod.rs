@@ -6,15 +6,25 @@ use tokio::task::JoinHandle;
 
 use crate::service_provider::ServiceProvider;
 
+use self::goods_received::{process_goods_received, ProcessGoodsReceivedError};
+use self::recall::{process_recalls, ProcessRecallsError};
+use self::redistribution_request::{
+    process_redistribution_requests, ProcessRedistributionRequestsError,
+};
 use self::transfer::invoice::ProcessInvoiceTransfersError;
 use self::transfer::requisition::ProcessRequisitionTransfersError;
 use self::transfer::{
     invoice::process_invoice_transfers, requisition::process_requisition_transfers,
 };
+use self::vaccine_vial::{process_expired_vaccine_vials, ProcessExpiredVaccineVialsError};
 
+pub(crate) mod goods_received;
+pub(crate) mod recall;
+pub(crate) mod redistribution_request;
 #[cfg(test)]
 mod test_helpers;
 pub(crate) mod transfer;
+pub(crate) mod vaccine_vial;
 
 const CHANNEL_BUFFER_SIZE: usize = 30;
 
@@ -22,12 +32,20 @@ const CHANNEL_BUFFER_SIZE: usize = 30;
 pub struct ProcessorsTrigger {
     requisition_transfer: Sender<()>,
     invoice_transfer: Sender<()>,
+    recall: Sender<()>,
+    goods_received: Sender<()>,
+    redistribution_request: Sender<()>,
+    expired_vaccine_vial: Sender<()>,
     await_process_queue: Sender<oneshot::Sender<()>>,
 }
 
 pub struct Processors {
     requisition_transfer: Receiver<()>,
     invoice_transfer: Receiver<()>,
+    recall: Receiver<()>,
+    goods_received: Receiver<()>,
+    redistribution_request: Receiver<()>,
+    expired_vaccine_vial: Receiver<()>,
     await_process_queue: Receiver<oneshot::Sender<()>>,
 }
 
@@ -37,6 +55,14 @@ enum ProcessorsError {
     InvoiceTransfer(ProcessInvoiceTransfersError),
     #[error("Error in requisition transfer processor ({0})")]
     RequisitionTransfer(ProcessRequisitionTransfersError),
+    #[error("Error in recall processor ({0})")]
+    Recall(ProcessRecallsError),
+    #[error("Error in goods received processor ({0})")]
+    GoodsReceived(ProcessGoodsReceivedError),
+    #[error("Error in redistribution request processor ({0})")]
+    RedistributionRequest(ProcessRedistributionRequestsError),
+    #[error("Error in expired vaccine vial processor ({0})")]
+    ExpiredVaccineVial(ProcessExpiredVaccineVialsError),
     #[error("Error when waiting for the process queue to be processed")]
     AwaitProcessQueue(()),
 }
@@ -49,17 +75,35 @@ impl Processors {
         let (invoice_transfer_sender, invoice_transfer_receiver) =
             mpsc::channel(CHANNEL_BUFFER_SIZE);
 
+        let (recall_sender, recall_receiver) = mpsc::channel(CHANNEL_BUFFER_SIZE);
+
+        let (goods_received_sender, goods_received_receiver) = mpsc::channel(CHANNEL_BUFFER_SIZE);
+
+        let (redistribution_request_sender, redistribution_request_receiver) =
+            mpsc::channel(CHANNEL_BUFFER_SIZE);
+
+        let (expired_vaccine_vial_sender, expired_vaccine_vial_receiver) =
+            mpsc::channel(CHANNEL_BUFFER_SIZE);
+
         let (request_check_sender, request_check_receiver) = mpsc::channel(CHANNEL_BUFFER_SIZE);
 
         (
             ProcessorsTrigger {
                 requisition_transfer: requisition_transfer_sender,
                 invoice_transfer: invoice_transfer_sender,
+                recall: recall_sender,
+                goods_received: goods_received_sender,
+                redistribution_request: redistribution_request_sender,
+                expired_vaccine_vial: expired_vaccine_vial_sender,
                 await_process_queue: request_check_sender,
             },
             Processors {
                 requisition_transfer: requisition_transfer_receiver,
                 invoice_transfer: invoice_transfer_receiver,
+                recall: recall_receiver,
+                goods_received: goods_received_receiver,
+                redistribution_request: redistribution_request_receiver,
+                expired_vaccine_vial: expired_vaccine_vial_receiver,
                 await_process_queue: request_check_receiver,
             },
         )
@@ -69,6 +113,10 @@ impl Processors {
         let Processors {
             mut requisition_transfer,
             mut invoice_transfer,
+            mut recall,
+            mut goods_received,
+            mut redistribution_request,
+            mut expired_vaccine_vial,
             mut await_process_queue,
         } = self;
 
@@ -86,6 +134,18 @@ impl Processors {
                     Some(_) = invoice_transfer.recv() => {
                         process_invoice_transfers(&service_provider).map_err(ProcessorsError::InvoiceTransfer)
                     },
+                    Some(_) = recall.recv() => {
+                        process_recalls(&service_provider).map_err(ProcessorsError::Recall)
+                    },
+                    Some(_) = goods_received.recv() => {
+                        process_goods_received(&service_provider).map_err(ProcessorsError::GoodsReceived)
+                    },
+                    Some(_) = redistribution_request.recv() => {
+                        process_redistribution_requests(&service_provider).map_err(ProcessorsError::RedistributionRequest)
+                    },
+                    Some(_) = expired_vaccine_vial.recv() => {
+                        process_expired_vaccine_vials(&service_provider).map_err(ProcessorsError::ExpiredVaccineVial)
+                    },
                     Some(sender) = await_process_queue.recv() => {
                         sender.send(()).map_err(ProcessorsError::AwaitProcessQueue)
                     },
@@ -117,6 +177,36 @@ impl ProcessorsTrigger {
         }
     }
 
+    pub(crate) fn trigger_recall_processors(&self) {
+        if let Err(error) = self.recall.try_send(()) {
+            log::error!("Problem triggering recall processor {:#?}", error)
+        }
+    }
+
+    pub(crate) fn trigger_goods_received_processors(&self) {
+        if let Err(error) = self.goods_received.try_send(()) {
+            log::error!("Problem triggering goods received processor {:#?}", error)
+        }
+    }
+
+    pub(crate) fn trigger_redistribution_request_processors(&self) {
+        if let Err(error) = self.redistribution_request.try_send(()) {
+            log::error!(
+                "Problem triggering redistribution request processor {:#?}",
+                error
+            )
+        }
+    }
+
+    pub(crate) fn trigger_expired_vaccine_vial_processors(&self) {
+        if let Err(error) = self.expired_vaccine_vial.try_send(()) {
+            log::error!(
+                "Problem triggering expired vaccine vial processor {:#?}",
+                error
+            )
+        }
+    }
+
     /// Waits till all current events in the processor queue are handled.
     /// Its guaranteed that all queued processor events that where in the queue before calling
     /// this method are handled when this method returns.
@@ -143,6 +233,10 @@ impl ProcessorsTrigger {
         ProcessorsTrigger {
             requisition_transfer: mpsc::channel(1).0,
             invoice_transfer: mpsc::channel(1).0,
+            recall: mpsc::channel(1).0,
+            goods_received: mpsc::channel(1).0,
+            redistribution_request: mpsc::channel(1).0,
+            expired_vaccine_vial: mpsc::channel(1).0,
             await_process_queue: mpsc::channel(1).0,
         }
     }
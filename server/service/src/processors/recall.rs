@@ -0,0 +1,89 @@
+use repository::{
+    ChangelogFilter, ChangelogRepository, ChangelogRow, ChangelogTableName, KeyType,
+    RepositoryError, RowActionType,
+};
+use thiserror::Error;
+
+use crate::{
+    cursor_controller::CursorController,
+    processors::transfer::log_system_error,
+    recall::{affected::get_recall, apply::apply_recall},
+    service_provider::ServiceProvider,
+    sync::{ActiveStoresOnSite, GetActiveStoresOnSiteError},
+};
+
+const CHANGELOG_BATCH_SIZE: u32 = 20;
+
+#[derive(Error, Debug)]
+pub(crate) enum ProcessRecallsError {
+    #[error("{0}")]
+    GetActiveStoresOnSiteError(GetActiveStoresOnSiteError),
+    #[error("{0:?}")]
+    DatabaseError(RepositoryError),
+    #[error("Problem applying recall {0:?} {1:?}")]
+    ApplyRecallError(ChangelogRow, RepositoryError),
+}
+
+/// Places stock of the stores active on this site on hold when a recall is created or synced
+/// Stock received after that is placed on hold as it is received, see `hold_new_recalled_stock`
+pub(crate) fn process_recalls(
+    service_provider: &ServiceProvider,
+) -> Result<(), ProcessRecallsError> {
+    use ProcessRecallsError as Error;
+
+    let ctx = service_provider
+        .basic_context()
+        .map_err(Error::DatabaseError)?;
+
+    let active_stores =
+        ActiveStoresOnSite::get(&ctx.connection).map_err(Error::GetActiveStoresOnSiteError)?;
+
+    let changelog_repo = ChangelogRepository::new(&ctx.connection);
+    let cursor_controller = CursorController::new(KeyType::RecallProcessorCursor);
+    let filter = ChangelogFilter::new()
+        .table_name(ChangelogTableName::Recall.equal_to())
+        .action(RowActionType::Upsert.equal_to());
+
+    loop {
+        let cursor = cursor_controller
+            .get(&ctx.connection)
+            .map_err(Error::DatabaseError)?;
+
+        let logs = changelog_repo
+            .changelogs(cursor, CHANGELOG_BATCH_SIZE, Some(filter.clone()))
+            .map_err(Error::DatabaseError)?;
+
+        if logs.is_empty() {
+            break;
+        }
+
+        for log in logs {
+            let result = ctx.connection.transaction_sync(|connection| {
+                let Some(recall) = get_recall(connection, &log.record_id)? else {
+                    return Ok(Vec::new());
+                };
+                apply_recall(connection, &recall, active_stores.store_ids())
+            });
+
+            match result {
+                Ok(held_stock_line_ids) if !held_stock_line_ids.is_empty() => log::info!(
+                    "Recall {} - placed stock lines on hold {:?}",
+                    log.record_id,
+                    held_stock_line_ids
+                ),
+                Ok(_) => {}
+                Err(error) => {
+                    let error = Error::ApplyRecallError(log.clone(), error.to_inner_error());
+                    log_system_error(&ctx.connection, &error).map_err(Error::DatabaseError)?;
+                }
+            }
+
+            // Always update cursor and move on to the next log, even if there's an error
+            cursor_controller
+                .update(&ctx.connection, (log.cursor + 1) as u64)
+                .map_err(Error::DatabaseError)?;
+        }
+    }
+
+    Ok(())
+}
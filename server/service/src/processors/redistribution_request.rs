@@ -0,0 +1,89 @@
+use repository::{
+    ChangelogFilter, ChangelogRepository, ChangelogRow, ChangelogTableName, EqualFilter, KeyType,
+    RepositoryError, RowActionType,
+};
+use thiserror::Error;
+use util::constants::SYSTEM_USER_ID;
+
+use crate::{
+    cursor_controller::CursorController,
+    expiry_risk::accept::{create_requested_shipment, AcceptRedistributionError},
+    processors::transfer::log_system_error,
+    service_provider::ServiceProvider,
+    sync::{ActiveStoresOnSite, GetActiveStoresOnSiteError},
+};
+
+const CHANGELOG_BATCH_SIZE: u32 = 20;
+
+#[derive(Error, Debug)]
+pub(crate) enum ProcessRedistributionRequestsError {
+    #[error("{0}")]
+    GetActiveStoresOnSiteError(GetActiveStoresOnSiteError),
+    #[error("{0:?}")]
+    DatabaseError(RepositoryError),
+    #[error("Problem creating redistribution shipment {0:?} {1:?}")]
+    CreateShipmentError(ChangelogRow, AcceptRedistributionError),
+}
+
+/// Creates the draft outbound shipments of redistribution requests accepted on another site
+/// (usually central) for the stores active on this site
+pub(crate) fn process_redistribution_requests(
+    service_provider: &ServiceProvider,
+) -> Result<(), ProcessRedistributionRequestsError> {
+    use ProcessRedistributionRequestsError as Error;
+
+    let ctx = service_provider
+        .basic_context()
+        .map_err(Error::DatabaseError)?;
+
+    let active_stores =
+        ActiveStoresOnSite::get(&ctx.connection).map_err(Error::GetActiveStoresOnSiteError)?;
+
+    let changelog_repo = ChangelogRepository::new(&ctx.connection);
+    let cursor_controller = CursorController::new(KeyType::RedistributionRequestProcessorCursor);
+    let filter = ChangelogFilter::new()
+        .table_name(ChangelogTableName::RedistributionRequest.equal_to())
+        .store_id(EqualFilter::equal_any(active_stores.store_ids()))
+        .action(RowActionType::Upsert.equal_to());
+
+    loop {
+        let cursor = cursor_controller
+            .get(&ctx.connection)
+            .map_err(Error::DatabaseError)?;
+
+        let logs = changelog_repo
+            .changelogs(cursor, CHANGELOG_BATCH_SIZE, Some(filter.clone()))
+            .map_err(Error::DatabaseError)?;
+
+        if logs.is_empty() {
+            break;
+        }
+
+        for log in logs {
+            let store_id = log.store_id.clone().unwrap_or_default();
+            let store_ctx = service_provider
+                .context(store_id, SYSTEM_USER_ID.to_string())
+                .map_err(Error::DatabaseError)?;
+
+            match create_requested_shipment(&store_ctx, &log.record_id) {
+                Ok(Some(invoice)) => log::info!(
+                    "Redistribution request {} - created outbound shipment {}",
+                    log.record_id,
+                    invoice.invoice_row.id
+                ),
+                Ok(None) => {}
+                Err(error) => {
+                    let error = Error::CreateShipmentError(log.clone(), error);
+                    log_system_error(&ctx.connection, &error).map_err(Error::DatabaseError)?;
+                }
+            }
+
+            // Always update cursor and move on to the next log, even if there's an error
+            cursor_controller
+                .update(&ctx.connection, (log.cursor + 1) as u64)
+                .map_err(Error::DatabaseError)?;
+        }
+    }
+
+    Ok(())
+}
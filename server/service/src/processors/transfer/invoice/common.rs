@@ -1,5 +1,7 @@
 use repository::{EqualFilter, Invoice, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineType};
-use repository::{InvoiceLineRow, RepositoryError, StorageConnection};
+use repository::{
+    GoodsReceivedLineRow, InvoiceLineRow, InvoiceRow, RepositoryError, StorageConnection,
+};
 use util::uuid::uuid;
 
 use crate::invoice::common::calculate_total_after_tax;
@@ -44,6 +46,7 @@ pub(crate) fn generate_inbound_lines(
                  return_reason_id,
                  foreign_currency_price_before_tax,
                  item_variant_id,
+                 donor_link_id,
              }| {
                 let cost_price_per_pack = sell_price_per_pack;
 
@@ -76,6 +79,7 @@ pub(crate) fn generate_inbound_lines(
                     foreign_currency_price_before_tax,
                     return_reason_id,
                     item_variant_id,
+                    donor_link_id,
                     // Default
                     stock_line_id: None,
                     location_id: None,
@@ -106,3 +110,29 @@ pub(crate) fn convert_invoice_line_to_single_pack(
         })
         .collect()
 }
+
+/// Expected receipt for each stock line of the inbound shipment, received quantities and
+/// discrepancies are recorded when the shipment is received
+pub(crate) fn generate_expected_goods_received_lines(
+    inbound_invoice: &InvoiceRow,
+    source_invoice: &Invoice,
+    inbound_lines: &[InvoiceLineRow],
+) -> Vec<GoodsReceivedLineRow> {
+    inbound_lines
+        .iter()
+        // Service lines don't work in packs
+        .filter(|line| line.r#type == InvoiceLineType::StockIn)
+        .map(|line| GoodsReceivedLineRow {
+            id: uuid(),
+            store_id: inbound_invoice.store_id.clone(),
+            invoice_id: inbound_invoice.id.clone(),
+            invoice_line_id: line.id.clone(),
+            linked_invoice_id: Some(source_invoice.invoice_row.id.clone()),
+            supplier_name_link_id: inbound_invoice.name_link_id.clone(),
+            item_link_id: line.item_link_id.clone(),
+            batch: line.batch.clone(),
+            expected_number_of_packs: line.number_of_packs,
+            ..Default::default()
+        })
+        .collect()
+}
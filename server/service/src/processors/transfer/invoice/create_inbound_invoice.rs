@@ -1,8 +1,9 @@
 use chrono::{Duration, NaiveDateTime, NaiveTime, Utc};
 use repository::{
-    ActivityLogType, EqualFilter, Invoice, InvoiceLineRowRepository, InvoiceRow,
-    InvoiceRowRepository, InvoiceStatus, InvoiceType, NumberRowType, RepositoryError, Requisition,
-    StorageConnection, StoreFilter, StoreRepository, StoreRowRepository,
+    ActivityLogType, EqualFilter, GoodsReceivedLineRowRepository, Invoice,
+    InvoiceLineRowRepository, InvoiceRow, InvoiceRowRepository, InvoiceStatus, InvoiceType,
+    NumberRowType, RepositoryError, Requisition, StorageConnection, StoreFilter, StoreRepository,
+    StoreRowRepository,
 };
 use util::uuid::uuid;
 
@@ -12,7 +13,10 @@ use crate::{
 };
 
 use super::{
-    common::{convert_invoice_line_to_single_pack, generate_inbound_lines},
+    common::{
+        convert_invoice_line_to_single_pack, generate_expected_goods_received_lines,
+        generate_inbound_lines,
+    },
     InvoiceTransferProcessor, InvoiceTransferProcessorRecord, Operation,
 };
 
@@ -130,6 +134,17 @@ impl InvoiceTransferProcessor for CreateInboundInvoiceProcessor {
             invoice_line_repository.upsert_one(line)?;
         }
 
+        if new_inbound_invoice.r#type == InvoiceType::InboundShipment {
+            let goods_received_line_repository = GoodsReceivedLineRowRepository::new(connection);
+            for row in generate_expected_goods_received_lines(
+                &new_inbound_invoice,
+                outbound_invoice,
+                &new_inbound_lines,
+            ) {
+                goods_received_line_repository.upsert_one(&row)?;
+            }
+        }
+
         let result = format!(
             "invoice ({}) lines ({:?}) source invoice ({})",
             new_inbound_invoice.id,
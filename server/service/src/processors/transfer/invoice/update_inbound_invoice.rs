@@ -1,6 +1,6 @@
 use repository::{
-    InvoiceLineRowRepository, InvoiceRow, InvoiceRowRepository, InvoiceStatus, InvoiceType,
-    RepositoryError, StorageConnection,
+    GoodsReceivedLineRowRepository, InvoiceLineRowRepository, InvoiceRow, InvoiceRowRepository,
+    InvoiceStatus, InvoiceType, RepositoryError, StorageConnection,
 };
 
 use crate::{
@@ -10,7 +10,10 @@ use crate::{
 };
 
 use super::{
-    common::{convert_invoice_line_to_single_pack, generate_inbound_lines},
+    common::{
+        convert_invoice_line_to_single_pack, generate_expected_goods_received_lines,
+        generate_inbound_lines,
+    },
     create_inbound_invoice::InboundInvoiceType,
     InvoiceTransferProcessor, InvoiceTransferProcessorRecord, Operation,
 };
@@ -93,6 +96,22 @@ impl InvoiceTransferProcessor for UpdateInboundInvoiceProcessor {
             invoice_line_repository.upsert_one(line)?;
         }
 
+        if matches!(inbound_invoice_type, InboundInvoiceType::InboundShipment) {
+            let goods_received_line_repository = GoodsReceivedLineRowRepository::new(connection);
+            for row in goods_received_line_repository
+                .find_many_by_invoice_id(&inbound_invoice.invoice_row.id)?
+            {
+                goods_received_line_repository.delete(&row.id)?;
+            }
+            for row in generate_expected_goods_received_lines(
+                &inbound_invoice.invoice_row,
+                outbound_invoice,
+                &new_inbound_lines,
+            ) {
+                goods_received_line_repository.upsert_one(&row)?;
+            }
+        }
+
         let outbound_invoice_row = &outbound_invoice.invoice_row;
 
         let formatted_ref = match &outbound_invoice_row.their_reference {
@@ -95,7 +95,7 @@ pub(crate) fn get_linked_original_shipment(
     Ok(linked_original_shipment)
 }
 
-fn log_system_error(
+pub(crate) fn log_system_error(
     connection: &StorageConnection,
     error: &impl std::error::Error,
 ) -> Result<(), RepositoryError> {
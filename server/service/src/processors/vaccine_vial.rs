@@ -0,0 +1,146 @@
+use chrono::Utc;
+use repository::RepositoryError;
+use thiserror::Error;
+use util::constants::SYSTEM_USER_ID;
+
+use crate::{
+    processors::transfer::log_system_error,
+    service_provider::ServiceProvider,
+    sync::{ActiveStoresOnSite, GetActiveStoresOnSiteError},
+    vaccination::vial::discard::{
+        discard_expired_vaccine_vials, DiscardExpiredVaccineVials, DiscardVaccineVialError,
+    },
+};
+
+#[derive(Error, Debug)]
+pub(crate) enum ProcessExpiredVaccineVialsError {
+    #[error("{0}")]
+    GetActiveStoresOnSiteError(GetActiveStoresOnSiteError),
+    #[error("{0:?}")]
+    DatabaseError(RepositoryError),
+    #[error("Problem discarding expired vaccine vials in store {0} {1:?}")]
+    DiscardError(String, DiscardVaccineVialError),
+}
+
+/// Discards open vaccine vials of the stores active on this site once their open vial period has
+/// passed. Vials expire with time rather than on a change, so this isn't driven by changelogs and
+/// runs every time the processor is triggered, see `SynchroniserDriver::sync`
+pub(crate) fn process_expired_vaccine_vials(
+    service_provider: &ServiceProvider,
+) -> Result<(), ProcessExpiredVaccineVialsError> {
+    use ProcessExpiredVaccineVialsError as Error;
+
+    let ctx = service_provider
+        .basic_context()
+        .map_err(Error::DatabaseError)?;
+
+    let active_stores =
+        ActiveStoresOnSite::get(&ctx.connection).map_err(Error::GetActiveStoresOnSiteError)?;
+    let as_of = Utc::now().naive_utc();
+
+    for store_id in active_stores.store_ids() {
+        let store_ctx = service_provider
+            .context(store_id.clone(), SYSTEM_USER_ID.to_string())
+            .map_err(Error::DatabaseError)?;
+
+        let result = discard_expired_vaccine_vials(
+            &store_ctx,
+            DiscardExpiredVaccineVials {
+                as_of,
+                inventory_adjustment_reason_id: None,
+            },
+        );
+
+        match result {
+            Ok(discarded) if !discarded.is_empty() => log::info!(
+                "Store {} - discarded expired vaccine vials {:?}",
+                store_id,
+                discarded.iter().map(|vial| &vial.id).collect::<Vec<_>>()
+            ),
+            Ok(_) => {}
+            Err(error) => {
+                let error = Error::DiscardError(store_id, error);
+                log_system_error(&ctx.connection, &error).map_err(Error::DatabaseError)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Utc};
+    use repository::{
+        mock::{
+            mock_stock_line_vaccine_item_a, mock_store_a, mock_user_account_a, MockDataInserts,
+        },
+        KeyType, KeyValueStoreRepository, VaccineVialOpeningRow, VaccineVialOpeningRowRepository,
+        VaccineVialStatus,
+    };
+
+    use crate::{
+        test_helpers::{setup_all_and_service_provider, ServiceTestContext},
+        vaccination::vial::open::OpenVaccineVial,
+    };
+
+    #[actix_rt::test]
+    async fn expired_vaccine_vial_processor() {
+        let ServiceTestContext {
+            connection,
+            service_provider,
+            ..
+        } = setup_all_and_service_provider(
+            "expired_vaccine_vial_processor",
+            MockDataInserts::all(),
+        )
+        .await;
+        KeyValueStoreRepository::new(&connection)
+            .set_i32(KeyType::SettingsSyncSiteId, Some(mock_store_a().site_id))
+            .unwrap();
+
+        let context = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let vial = service_provider
+            .vaccination_service
+            .open_vaccine_vial(
+                &context,
+                OpenVaccineVial {
+                    id: "expiring_vial".to_string(),
+                    stock_line_id: mock_stock_line_vaccine_item_a().id,
+                    open_vial_period_hours: 1,
+                },
+            )
+            .unwrap();
+        let find_vial = || {
+            VaccineVialOpeningRowRepository::new(&connection)
+                .find_one_by_id(&vial.id)
+                .unwrap()
+                .unwrap()
+        };
+
+        // Still within the open vial period
+        context
+            .processors_trigger
+            .trigger_expired_vaccine_vial_processors();
+        context.processors_trigger.await_events_processed().await;
+        assert_eq!(find_vial().status, VaccineVialStatus::Open);
+
+        VaccineVialOpeningRowRepository::new(&connection)
+            .upsert_one(&VaccineVialOpeningRow {
+                discard_after_datetime: Utc::now().naive_utc() - Duration::minutes(1),
+                ..find_vial()
+            })
+            .unwrap();
+        context
+            .processors_trigger
+            .trigger_expired_vaccine_vial_processors();
+        context.processors_trigger.await_events_processed().await;
+
+        let vial = find_vial();
+        assert_eq!(vial.status, VaccineVialStatus::Discarded);
+        assert_eq!(vial.wasted_doses, vial.doses_per_vial);
+        assert!(vial.inventory_adjustment_id.is_some());
+    }
+}
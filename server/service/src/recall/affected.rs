@@ -0,0 +1,104 @@
+use repository::{
+    EqualFilter, InvoiceLine, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineType,
+    InvoiceStatus, InvoiceType, Recall, RecallFilter, RecallRepository, RepositoryError, StockLine,
+    StockLineFilter, StockLineRepository, StorageConnection,
+};
+
+use crate::service_provider::ServiceContext;
+
+use super::matcher::RecallMatcher;
+
+#[derive(Debug, PartialEq)]
+pub enum GetRecallAffectedError {
+    RecallDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+pub(crate) fn get_recall(
+    connection: &StorageConnection,
+    recall_id: &str,
+) -> Result<Option<Recall>, RepositoryError> {
+    RecallRepository::new(connection)
+        .query_one(RecallFilter::new().id(EqualFilter::equal_to(recall_id)))
+}
+
+/// Stock lines with packs in store that match the recall, in the given stores or in all stores
+/// on this site when `store_ids` is None
+pub(crate) fn recalled_stock_lines(
+    connection: &StorageConnection,
+    recall: &Recall,
+    store_ids: Option<Vec<String>>,
+) -> Result<Vec<StockLine>, RepositoryError> {
+    let matcher = RecallMatcher::new(connection, recall)?;
+
+    let mut filter = StockLineFilter::new()
+        .item_id(EqualFilter::equal_to(&recall.item_row.id))
+        .has_packs_in_store(true);
+    if let Some(store_ids) = store_ids {
+        filter = filter.store_id(EqualFilter::equal_any(store_ids));
+    }
+
+    let stock_lines = StockLineRepository::new(connection)
+        .query_by_filter(filter, None)?
+        .into_iter()
+        .filter(|stock_line| {
+            let row = &stock_line.stock_line_row;
+            matcher.matches(&row.batch, &row.expiry_date, &row.item_variant_id)
+        })
+        .collect();
+
+    Ok(stock_lines)
+}
+
+/// Stock affected by the recall across all stores on this site
+pub fn get_recalled_stock(
+    ctx: &ServiceContext,
+    recall_id: &str,
+) -> Result<Vec<StockLine>, GetRecallAffectedError> {
+    let recall = get_recall(&ctx.connection, recall_id)?
+        .ok_or(GetRecallAffectedError::RecallDoesNotExist)?;
+
+    Ok(recalled_stock_lines(&ctx.connection, &recall, None)?)
+}
+
+/// Lines of outbound shipments and prescriptions that issued recalled stock to customers and
+/// patients. The invoice of each line identifies the recipient.
+pub fn get_recall_recipients(
+    ctx: &ServiceContext,
+    recall_id: &str,
+) -> Result<Vec<InvoiceLine>, GetRecallAffectedError> {
+    let recall = get_recall(&ctx.connection, recall_id)?
+        .ok_or(GetRecallAffectedError::RecallDoesNotExist)?;
+    let matcher = RecallMatcher::new(&ctx.connection, &recall)?;
+
+    let lines = InvoiceLineRepository::new(&ctx.connection)
+        .query_by_filter(
+            InvoiceLineFilter::new()
+                .item_id(EqualFilter::equal_to(&recall.item_row.id))
+                .r#type(InvoiceLineType::StockOut.equal_to())
+                .invoice_type(InvoiceType::equal_any(vec![
+                    InvoiceType::OutboundShipment,
+                    InvoiceType::Prescription,
+                ]))
+                .invoice_status(InvoiceStatus::equal_any(vec![
+                    InvoiceStatus::Picked,
+                    InvoiceStatus::Shipped,
+                    InvoiceStatus::Delivered,
+                    InvoiceStatus::Verified,
+                ])),
+        )?
+        .into_iter()
+        .filter(|line| {
+            let row = &line.invoice_line_row;
+            matcher.matches(&row.batch, &row.expiry_date, &row.item_variant_id)
+        })
+        .collect();
+
+    Ok(lines)
+}
+
+impl From<RepositoryError> for GetRecallAffectedError {
+    fn from(error: RepositoryError) -> Self {
+        GetRecallAffectedError::DatabaseError(error)
+    }
+}
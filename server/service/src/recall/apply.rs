@@ -0,0 +1,78 @@
+use repository::{
+    EqualFilter, ItemLinkRowRepository, Recall, RecallFilter, RecallRepository, RepositoryError,
+    StockLineRow, StockLineRowRepository, StorageConnection,
+};
+
+use super::{affected::recalled_stock_lines, matcher::RecallMatcher};
+
+/// Places stock of the given stores that matches the recall on hold, so it can't be issued.
+/// Returns the ids of the stock lines that were placed on hold.
+///
+/// Holds are not released when a recall is closed, stock is released manually once it has
+/// been checked.
+pub(crate) fn apply_recall(
+    connection: &StorageConnection,
+    recall: &Recall,
+    store_ids: Vec<String>,
+) -> Result<Vec<String>, RepositoryError> {
+    if recall.recall_row.closed_datetime.is_some() {
+        return Ok(Vec::new());
+    }
+
+    let repo = StockLineRowRepository::new(connection);
+    let mut held_stock_line_ids = Vec::new();
+    for stock_line in recalled_stock_lines(connection, recall, Some(store_ids))? {
+        let mut row = stock_line.stock_line_row;
+        if row.on_hold {
+            continue;
+        }
+        row.on_hold = true;
+        repo.upsert_one(&row)?;
+        held_stock_line_ids.push(row.id);
+    }
+
+    Ok(held_stock_line_ids)
+}
+
+/// Places a new stock line on hold when it matches an active recall, so stock received after the
+/// recall was created is held like the stock that was on hand. Call before upserting the stock
+/// line, existing stock lines are left as they are so holds released after checking aren't
+/// placed again. Returns true when the stock line was placed on hold.
+pub(crate) fn hold_new_recalled_stock(
+    connection: &StorageConnection,
+    stock_line: &mut StockLineRow,
+) -> Result<bool, RepositoryError> {
+    if stock_line.on_hold
+        || StockLineRowRepository::new(connection)
+            .find_one_by_id(&stock_line.id)?
+            .is_some()
+    {
+        return Ok(false);
+    }
+
+    // Stock lines reference the item through its link, which differs from the item id once
+    // items are merged
+    let Some(item_link) =
+        ItemLinkRowRepository::new(connection).find_one_by_id(&stock_line.item_link_id)?
+    else {
+        return Ok(false);
+    };
+    let recalls = RecallRepository::new(connection).query_by_filter(
+        RecallFilter::new()
+            .item_id(EqualFilter::equal_to(&item_link.item_id))
+            .is_active(true),
+    )?;
+    for recall in recalls {
+        let matcher = RecallMatcher::new(connection, &recall)?;
+        if matcher.matches(
+            &stock_line.batch,
+            &stock_line.expiry_date,
+            &stock_line.item_variant_id,
+        ) {
+            stock_line.on_hold = true;
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
@@ -0,0 +1,53 @@
+use chrono::Utc;
+use repository::{Recall, RecallRowRepository, RepositoryError};
+
+use crate::{service_provider::ServiceContext, sync::CentralServerConfig};
+
+use super::affected::get_recall;
+
+#[derive(Debug, PartialEq)]
+pub enum CloseRecallError {
+    NotACentralServer,
+    RecallDoesNotExist,
+    RecallAlreadyClosed,
+    UpdatedRecallDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+/// Closes the recall, stock is no longer matched against it. Stock placed on hold by the recall
+/// stays on hold.
+pub fn close_recall(ctx: &ServiceContext, id: &str) -> Result<Recall, CloseRecallError> {
+    if !CentralServerConfig::is_central_server() {
+        return Err(CloseRecallError::NotACentralServer);
+    }
+
+    close_recall_on_central(ctx, id)
+}
+
+pub(crate) fn close_recall_on_central(
+    ctx: &ServiceContext,
+    id: &str,
+) -> Result<Recall, CloseRecallError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let repo = RecallRowRepository::new(connection);
+            let mut recall = repo
+                .find_one_by_id(id)?
+                .ok_or(CloseRecallError::RecallDoesNotExist)?;
+            if recall.closed_datetime.is_some() {
+                return Err(CloseRecallError::RecallAlreadyClosed);
+            }
+
+            recall.closed_datetime = Some(Utc::now().naive_utc());
+            repo.upsert_one(&recall)?;
+
+            get_recall(connection, id)?.ok_or(CloseRecallError::UpdatedRecallDoesNotExist)
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+impl From<RepositoryError> for CloseRecallError {
+    fn from(error: RepositoryError) -> Self {
+        CloseRecallError::DatabaseError(error)
+    }
+}
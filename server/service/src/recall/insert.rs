@@ -0,0 +1,144 @@
+use chrono::{NaiveDate, Utc};
+use repository::{
+    item_variant::item_variant::{ItemVariantFilter, ItemVariantRepository},
+    EqualFilter, ItemRowRepository, Recall, RecallRow, RecallRowRepository, RepositoryError,
+    StorageConnection,
+};
+
+use crate::{
+    name::validate::check_name_exists, service_provider::ServiceContext, sync::CentralServerConfig,
+};
+
+use super::affected::get_recall;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InsertRecall {
+    pub id: String,
+    pub item_id: String,
+    pub item_variant_id: Option<String>,
+    pub manufacturer_id: Option<String>,
+    pub batch: Option<String>,
+    pub expiry_date_from: Option<NaiveDate>,
+    pub expiry_date_to: Option<NaiveDate>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InsertRecallError {
+    NotACentralServer,
+    RecallAlreadyExists,
+    ItemDoesNotExist,
+    ItemVariantDoesNotExist,
+    ManufacturerDoesNotExist,
+    InvalidExpiryDateRange,
+    NewlyCreatedRecallDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+/// Recalls are created on the central server and synced to all sites, where matching stock is
+/// placed on hold by the recall processor
+pub fn insert_recall(
+    ctx: &ServiceContext,
+    input: InsertRecall,
+) -> Result<Recall, InsertRecallError> {
+    if !CentralServerConfig::is_central_server() {
+        return Err(InsertRecallError::NotACentralServer);
+    }
+
+    insert_recall_on_central(ctx, input)
+}
+
+pub(crate) fn insert_recall_on_central(
+    ctx: &ServiceContext,
+    input: InsertRecall,
+) -> Result<Recall, InsertRecallError> {
+    let recall = ctx
+        .connection
+        .transaction_sync(|connection| {
+            validate(connection, &input)?;
+            RecallRowRepository::new(connection).upsert_one(&generate(input.clone()))?;
+
+            get_recall(connection, &input.id)?
+                .ok_or(InsertRecallError::NewlyCreatedRecallDoesNotExist)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    ctx.processors_trigger.trigger_recall_processors();
+
+    Ok(recall)
+}
+
+fn validate(connection: &StorageConnection, input: &InsertRecall) -> Result<(), InsertRecallError> {
+    use InsertRecallError::*;
+
+    if RecallRowRepository::new(connection)
+        .find_one_by_id(&input.id)?
+        .is_some()
+    {
+        return Err(RecallAlreadyExists);
+    }
+
+    if ItemRowRepository::new(connection)
+        .find_active_by_id(&input.item_id)?
+        .is_none()
+    {
+        return Err(ItemDoesNotExist);
+    }
+
+    if let Some(item_variant_id) = &input.item_variant_id {
+        let count = ItemVariantRepository::new(connection).count(Some(
+            ItemVariantFilter::new()
+                .id(EqualFilter::equal_to(item_variant_id))
+                .item_id(EqualFilter::equal_to(&input.item_id)),
+        ))?;
+        if count == 0 {
+            return Err(ItemVariantDoesNotExist);
+        }
+    }
+
+    if let Some(manufacturer_id) = &input.manufacturer_id {
+        if check_name_exists(connection, manufacturer_id)?.is_none() {
+            return Err(ManufacturerDoesNotExist);
+        }
+    }
+
+    if let (Some(from), Some(to)) = (input.expiry_date_from, input.expiry_date_to) {
+        if from > to {
+            return Err(InvalidExpiryDateRange);
+        }
+    }
+
+    Ok(())
+}
+
+fn generate(
+    InsertRecall {
+        id,
+        item_id,
+        item_variant_id,
+        manufacturer_id,
+        batch,
+        expiry_date_from,
+        expiry_date_to,
+        reason,
+    }: InsertRecall,
+) -> RecallRow {
+    RecallRow {
+        id,
+        item_link_id: item_id,
+        item_variant_id,
+        manufacturer_link_id: manufacturer_id,
+        batch,
+        expiry_date_from,
+        expiry_date_to,
+        reason,
+        created_datetime: Utc::now().naive_utc(),
+        closed_datetime: None,
+    }
+}
+
+impl From<RepositoryError> for InsertRecallError {
+    fn from(error: RepositoryError) -> Self {
+        InsertRecallError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::NaiveDate;
+use repository::{
+    item_variant::item_variant::{ItemVariantFilter, ItemVariantRepository},
+    EqualFilter, Recall, RecallRow, RepositoryError, StorageConnection,
+};
+
+/// Checks stock of the recalled item against the criteria of a recall
+pub(crate) struct RecallMatcher {
+    recall: RecallRow,
+    /// Item variants made by the recalled manufacturer, when the recall is by manufacturer
+    manufacturer_item_variant_ids: Option<Vec<String>>,
+}
+
+impl RecallMatcher {
+    pub(crate) fn new(
+        connection: &StorageConnection,
+        recall: &Recall,
+    ) -> Result<Self, RepositoryError> {
+        let manufacturer_item_variant_ids = match &recall.recall_row.manufacturer_link_id {
+            Some(manufacturer_id) => Some(
+                ItemVariantRepository::new(connection)
+                    .query_by_filter(
+                        ItemVariantFilter::new()
+                            .item_id(EqualFilter::equal_to(&recall.item_row.id)),
+                    )?
+                    .into_iter()
+                    .filter(|variant| {
+                        variant.item_variant_row.manufacturer_link_id.as_ref()
+                            == Some(manufacturer_id)
+                    })
+                    .map(|variant| variant.item_variant_row.id)
+                    .collect(),
+            ),
+            None => None,
+        };
+
+        Ok(RecallMatcher {
+            recall: recall.recall_row.clone(),
+            manufacturer_item_variant_ids,
+        })
+    }
+
+    /// Stock matches when it meets every criteria that is set on the recall.
+    /// Batches are compared ignoring case and surrounding whitespace, stock without a batch,
+    /// expiry date or item variant doesn't match a recall that specifies one.
+    pub(crate) fn matches(
+        &self,
+        batch: &Option<String>,
+        expiry_date: &Option<NaiveDate>,
+        item_variant_id: &Option<String>,
+    ) -> bool {
+        let recall = &self.recall;
+
+        if let Some(recalled_batch) = &recall.batch {
+            let matches_batch = batch.as_ref().is_some_and(|batch| {
+                batch.trim().to_lowercase() == recalled_batch.trim().to_lowercase()
+            });
+            if !matches_batch {
+                return false;
+            }
+        }
+
+        if recall.expiry_date_from.is_some() || recall.expiry_date_to.is_some() {
+            let Some(expiry_date) = expiry_date else {
+                return false;
+            };
+            if recall
+                .expiry_date_from
+                .is_some_and(|from| *expiry_date < from)
+                || recall.expiry_date_to.is_some_and(|to| *expiry_date > to)
+            {
+                return false;
+            }
+        }
+
+        if let Some(recalled_item_variant_id) = &recall.item_variant_id {
+            if item_variant_id.as_ref() != Some(recalled_item_variant_id) {
+                return false;
+            }
+        }
+
+        if let Some(manufacturer_item_variant_ids) = &self.manufacturer_item_variant_ids {
+            let is_made_by_manufacturer = item_variant_id
+                .as_ref()
+                .is_some_and(|id| manufacturer_item_variant_ids.contains(id));
+            if !is_made_by_manufacturer {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::RecallRow;
+
+    use super::RecallMatcher;
+
+    #[test]
+    fn recall_matcher() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day);
+        let matcher = RecallMatcher {
+            recall: RecallRow {
+                batch: Some("AB12".to_string()),
+                expiry_date_from: date(10),
+                expiry_date_to: date(20),
+                ..Default::default()
+            },
+            manufacturer_item_variant_ids: Some(vec!["made_by_manufacturer".to_string()]),
+        };
+        let variant = Some("made_by_manufacturer".to_string());
+
+        assert!(matcher.matches(&Some(" ab12 ".to_string()), &date(10), &variant));
+        assert!(matcher.matches(&Some("AB12".to_string()), &date(20), &variant));
+        assert!(!matcher.matches(&Some("AB13".to_string()), &date(15), &variant));
+        assert!(!matcher.matches(&None, &date(15), &variant));
+        assert!(!matcher.matches(&Some("AB12".to_string()), &date(21), &variant));
+        assert!(!matcher.matches(&Some("AB12".to_string()), &None, &variant));
+        assert!(!matcher.matches(
+            &Some("AB12".to_string()),
+            &date(15),
+            &Some("other".to_string())
+        ));
+        assert!(!matcher.matches(&Some("AB12".to_string()), &date(15), &None));
+
+        // Recall of all stock of the item
+        let matcher = RecallMatcher {
+            recall: RecallRow::default(),
+            manufacturer_item_variant_ids: None,
+        };
+        assert!(matcher.matches(&None, &None, &None));
+    }
+}
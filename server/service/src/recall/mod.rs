@@ -0,0 +1,70 @@
+use repository::{Invoice, InvoiceLine, Recall, RecallFilter, RepositoryError, StockLine};
+
+use self::{
+    affected::{get_recall_recipients, get_recalled_stock, GetRecallAffectedError},
+    close::{close_recall, CloseRecallError},
+    insert::{insert_recall, InsertRecall, InsertRecallError},
+    query::get_recalls,
+    supplier_return::{generate_recall_supplier_returns, GenerateRecallSupplierReturnsError},
+};
+use crate::service_provider::ServiceContext;
+
+pub mod affected;
+pub mod apply;
+pub mod close;
+pub mod insert;
+pub mod matcher;
+pub mod query;
+pub mod supplier_return;
+
+pub trait RecallServiceTrait: Sync + Send {
+    fn get_recalls(
+        &self,
+        ctx: &ServiceContext,
+        filter: Option<RecallFilter>,
+    ) -> Result<Vec<Recall>, RepositoryError> {
+        get_recalls(ctx, filter)
+    }
+
+    fn insert_recall(
+        &self,
+        ctx: &ServiceContext,
+        input: InsertRecall,
+    ) -> Result<Recall, InsertRecallError> {
+        insert_recall(ctx, input)
+    }
+
+    fn close_recall(&self, ctx: &ServiceContext, id: &str) -> Result<Recall, CloseRecallError> {
+        close_recall(ctx, id)
+    }
+
+    fn get_recalled_stock(
+        &self,
+        ctx: &ServiceContext,
+        recall_id: &str,
+    ) -> Result<Vec<StockLine>, GetRecallAffectedError> {
+        get_recalled_stock(ctx, recall_id)
+    }
+
+    fn get_recall_recipients(
+        &self,
+        ctx: &ServiceContext,
+        recall_id: &str,
+    ) -> Result<Vec<InvoiceLine>, GetRecallAffectedError> {
+        get_recall_recipients(ctx, recall_id)
+    }
+
+    fn generate_recall_supplier_returns(
+        &self,
+        ctx: &ServiceContext,
+        recall_id: &str,
+    ) -> Result<Vec<Invoice>, GenerateRecallSupplierReturnsError> {
+        generate_recall_supplier_returns(ctx, recall_id)
+    }
+}
+
+pub struct RecallService {}
+impl RecallServiceTrait for RecallService {}
+
+#[cfg(test)]
+mod tests;
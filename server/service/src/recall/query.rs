@@ -0,0 +1,10 @@
+use repository::{Recall, RecallFilter, RecallRepository, RepositoryError};
+
+use crate::service_provider::ServiceContext;
+
+pub fn get_recalls(
+    ctx: &ServiceContext,
+    filter: Option<RecallFilter>,
+) -> Result<Vec<Recall>, RepositoryError> {
+    RecallRepository::new(&ctx.connection).query_by_filter(filter.unwrap_or_default())
+}
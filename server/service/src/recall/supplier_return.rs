@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+
+use repository::{Invoice, RepositoryError, StockLine};
+use util::uuid::uuid;
+
+use crate::{
+    invoice::supplier_return::{
+        insert::{insert_supplier_return, InsertSupplierReturn, InsertSupplierReturnError},
+        SupplierReturnLineInput,
+    },
+    service_provider::ServiceContext,
+};
+
+use super::affected::{get_recall, recalled_stock_lines};
+
+#[derive(Debug, PartialEq)]
+pub enum GenerateRecallSupplierReturnsError {
+    RecallDoesNotExist,
+    NoRecalledStockToReturn,
+    SupplierReturnError {
+        supplier_id: String,
+        error: InsertSupplierReturnError,
+    },
+    DatabaseError(RepositoryError),
+}
+
+/// Creates a new supplier return per supplier for the available recalled stock of the store.
+/// Recalled stock without a supplier is left for the user to return manually.
+pub fn generate_recall_supplier_returns(
+    ctx: &ServiceContext,
+    recall_id: &str,
+) -> Result<Vec<Invoice>, GenerateRecallSupplierReturnsError> {
+    use GenerateRecallSupplierReturnsError::*;
+
+    ctx.connection
+        .transaction_sync(|connection| {
+            let recall = get_recall(connection, recall_id)?.ok_or(RecallDoesNotExist)?;
+
+            let mut stock_lines_by_supplier: BTreeMap<String, Vec<StockLine>> = BTreeMap::new();
+            for stock_line in
+                recalled_stock_lines(connection, &recall, Some(vec![ctx.store_id.clone()]))?
+            {
+                if stock_line.stock_line_row.available_number_of_packs <= 0.0 {
+                    continue;
+                }
+                let Some(supplier) = &stock_line.supplier_name_row else {
+                    continue;
+                };
+                stock_lines_by_supplier
+                    .entry(supplier.id.clone())
+                    .or_default()
+                    .push(stock_line);
+            }
+
+            if stock_lines_by_supplier.is_empty() {
+                return Err(NoRecalledStockToReturn);
+            }
+
+            let mut supplier_returns = Vec::new();
+            for (supplier_id, stock_lines) in stock_lines_by_supplier {
+                let supplier_return_lines = stock_lines
+                    .into_iter()
+                    .map(|stock_line| SupplierReturnLineInput {
+                        id: uuid(),
+                        stock_line_id: stock_line.stock_line_row.id,
+                        number_of_packs: stock_line.stock_line_row.available_number_of_packs,
+                        reason_id: None,
+                        note: recall.recall_row.reason.clone(),
+                    })
+                    .collect();
+
+                let supplier_return = insert_supplier_return(
+                    ctx,
+                    InsertSupplierReturn {
+                        id: uuid(),
+                        other_party_id: supplier_id.clone(),
+                        inbound_shipment_id: None,
+                        supplier_return_lines,
+                    },
+                )
+                .map_err(|error| SupplierReturnError { supplier_id, error })?;
+                supplier_returns.push(supplier_return);
+            }
+
+            Ok(supplier_returns)
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+impl From<RepositoryError> for GenerateRecallSupplierReturnsError {
+    fn from(error: RepositoryError) -> Self {
+        GenerateRecallSupplierReturnsError::DatabaseError(error)
+    }
+}
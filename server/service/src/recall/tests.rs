@@ -0,0 +1,448 @@
+use chrono::NaiveDate;
+use repository::{
+    mock::{
+        mock_item_a, mock_item_b, mock_name_a, mock_store_a, mock_store_b, MockData,
+        MockDataInserts,
+    },
+    test_db::setup_all_with_data,
+    EqualFilter, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineRow, InvoiceLineType,
+    InvoiceRow, InvoiceStatus, InvoiceType, ItemLinkRow, ItemLinkRowRepository, RecallRow,
+    RecallRowRepository, StockLineRow, StockLineRowRepository,
+};
+use util::inline_init;
+
+use crate::{
+    invoice::inbound_shipment::update::{UpdateInboundShipment, UpdateInboundShipmentStatus},
+    invoice_line::stock_in_line::{InsertStockInLine, StockInType},
+    recall::{
+        affected::{get_recall, GetRecallAffectedError},
+        apply::apply_recall,
+        close::{close_recall_on_central, CloseRecallError},
+        insert::{insert_recall_on_central, InsertRecall, InsertRecallError},
+        supplier_return::GenerateRecallSupplierReturnsError,
+    },
+    service_provider::ServiceProvider,
+};
+
+fn date(day: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(2024, 1, day)
+}
+
+fn stock_line(id: &str, store_id: &str, batch: &str, supplier_id: Option<&str>) -> StockLineRow {
+    StockLineRow {
+        id: id.to_string(),
+        item_link_id: mock_item_a().id,
+        store_id: store_id.to_string(),
+        batch: Some(batch.to_string()),
+        expiry_date: date(15),
+        supplier_link_id: supplier_id.map(str::to_string),
+        pack_size: 1.0,
+        total_number_of_packs: 10.0,
+        available_number_of_packs: 10.0,
+        ..Default::default()
+    }
+}
+
+fn invoice(id: &str, r#type: InvoiceType, status: InvoiceStatus) -> InvoiceRow {
+    inline_init(|r: &mut InvoiceRow| {
+        r.id = id.to_string();
+        r.name_link_id = mock_name_a().id;
+        r.store_id = mock_store_a().id;
+        r.r#type = r#type;
+        r.status = status;
+    })
+}
+
+fn stock_out_line(id: &str, invoice_id: &str, batch: &str) -> InvoiceLineRow {
+    InvoiceLineRow {
+        id: id.to_string(),
+        invoice_id: invoice_id.to_string(),
+        item_link_id: mock_item_a().id,
+        item_name: mock_item_a().name,
+        batch: Some(batch.to_string()),
+        expiry_date: date(15),
+        pack_size: 1.0,
+        number_of_packs: 2.0,
+        r#type: InvoiceLineType::StockOut,
+        ..Default::default()
+    }
+}
+
+#[actix_rt::test]
+async fn recall_stock_and_recipients() {
+    let (_, connection, connection_manager, _) = setup_all_with_data(
+        "recall_stock_and_recipients",
+        MockDataInserts::all(),
+        MockData {
+            stock_lines: vec![
+                stock_line("recalled", &mock_store_a().id, "RC1", Some("name_a")),
+                stock_line("recalled_no_supplier", &mock_store_a().id, "rc1", None),
+                stock_line("other_batch", &mock_store_a().id, "OTHER", Some("name_a")),
+                stock_line("recalled_store_b", &mock_store_b().id, "RC1", None),
+            ],
+            invoices: vec![
+                invoice(
+                    "shipped",
+                    InvoiceType::OutboundShipment,
+                    InvoiceStatus::Shipped,
+                ),
+                invoice(
+                    "verified_prescription",
+                    InvoiceType::Prescription,
+                    InvoiceStatus::Verified,
+                ),
+                invoice(
+                    "new_prescription",
+                    InvoiceType::Prescription,
+                    InvoiceStatus::New,
+                ),
+            ],
+            invoice_lines: vec![
+                stock_out_line("shipped_line", "shipped", "RC1"),
+                stock_out_line("shipped_other_batch", "shipped", "OTHER"),
+                stock_out_line("prescribed_line", "verified_prescription", "RC1"),
+                stock_out_line("not_prescribed_line", "new_prescription", "RC1"),
+            ],
+            ..Default::default()
+        },
+    )
+    .await;
+
+    RecallRowRepository::new(&connection)
+        .upsert_one(&RecallRow {
+            id: "recall".to_string(),
+            item_link_id: mock_item_a().id,
+            batch: Some("RC1".to_string()),
+            expiry_date_from: date(1),
+            expiry_date_to: date(31),
+            reason: Some("Contaminated".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    let recall = get_recall(&connection, "recall").unwrap().unwrap();
+
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider
+        .context(mock_store_a().id, "".to_string())
+        .unwrap();
+    let service = service_provider.recall_service;
+
+    // Affected stock
+    assert_eq!(
+        service.get_recalled_stock(&context, "invalid"),
+        Err(GetRecallAffectedError::RecallDoesNotExist)
+    );
+    let mut stock_line_ids: Vec<String> = service
+        .get_recalled_stock(&context, "recall")
+        .unwrap()
+        .into_iter()
+        .map(|stock_line| stock_line.stock_line_row.id)
+        .collect();
+    stock_line_ids.sort();
+    assert_eq!(
+        stock_line_ids,
+        vec!["recalled", "recalled_no_supplier", "recalled_store_b"]
+    );
+
+    // Recipients
+    let mut line_ids: Vec<String> = service
+        .get_recall_recipients(&context, "recall")
+        .unwrap()
+        .into_iter()
+        .map(|line| line.invoice_line_row.id)
+        .collect();
+    line_ids.sort();
+    assert_eq!(line_ids, vec!["prescribed_line", "shipped_line"]);
+
+    // Stock of active stores is placed on hold
+    let mut held = apply_recall(&connection, &recall, vec![mock_store_a().id]).unwrap();
+    held.sort();
+    assert_eq!(held, vec!["recalled", "recalled_no_supplier"]);
+    let stock_line_repo = StockLineRowRepository::new(&connection);
+    let on_hold = |id: &str| stock_line_repo.find_one_by_id(id).unwrap().unwrap().on_hold;
+    assert!(on_hold("recalled"));
+    assert!(!on_hold("other_batch"));
+    assert!(!on_hold("recalled_store_b"));
+    assert_eq!(
+        apply_recall(&connection, &recall, vec![mock_store_a().id]).unwrap(),
+        Vec::<String>::new()
+    );
+
+    // Supplier returns, on hold stock can be returned
+    assert_eq!(
+        service.generate_recall_supplier_returns(&context, "invalid"),
+        Err(GenerateRecallSupplierReturnsError::RecallDoesNotExist)
+    );
+    let supplier_returns = service
+        .generate_recall_supplier_returns(&context, "recall")
+        .unwrap();
+    assert_eq!(supplier_returns.len(), 1);
+    let supplier_return = &supplier_returns[0];
+    assert_eq!(
+        supplier_return.invoice_row.r#type,
+        InvoiceType::SupplierReturn
+    );
+    assert_eq!(supplier_return.invoice_row.name_link_id, mock_name_a().id);
+
+    let lines = InvoiceLineRepository::new(&connection)
+        .query_by_filter(
+            InvoiceLineFilter::new()
+                .invoice_id(EqualFilter::equal_to(&supplier_return.invoice_row.id)),
+        )
+        .unwrap();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(
+        lines[0].invoice_line_row.stock_line_id,
+        Some("recalled".to_string())
+    );
+    assert_eq!(lines[0].invoice_line_row.number_of_packs, 10.0);
+    assert_eq!(
+        lines[0].invoice_line_row.note,
+        Some("Contaminated".to_string())
+    );
+
+    // Nothing left to return
+    assert_eq!(
+        service.generate_recall_supplier_returns(&context, "recall"),
+        Err(GenerateRecallSupplierReturnsError::NoRecalledStockToReturn)
+    );
+}
+
+#[actix_rt::test]
+async fn recall_holds_stock_received_after_recall() {
+    let stock_in_line = |id: &str, batch: &str| InvoiceLineRow {
+        r#type: InvoiceLineType::StockIn,
+        ..stock_out_line(id, "inbound", batch)
+    };
+    let (_, connection, connection_manager, _) = setup_all_with_data(
+        "recall_holds_stock_received_after_recall",
+        MockDataInserts::all(),
+        MockData {
+            invoices: vec![invoice(
+                "inbound",
+                InvoiceType::InboundShipment,
+                InvoiceStatus::New,
+            )],
+            invoice_lines: vec![
+                stock_in_line("received_recalled", "RC1"),
+                stock_in_line("received_other_batch", "OTHER"),
+            ],
+            ..Default::default()
+        },
+    )
+    .await;
+
+    RecallRowRepository::new(&connection)
+        .upsert_one(&RecallRow {
+            id: "recall".to_string(),
+            item_link_id: mock_item_a().id,
+            batch: Some("RC1".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider
+        .context(mock_store_a().id, "".to_string())
+        .unwrap();
+    let stock_line_repo = StockLineRowRepository::new(&connection);
+    let line_on_hold = |invoice_line_id: &str| {
+        let line = InvoiceLineRepository::new(&connection)
+            .query_one(InvoiceLineFilter::new().id(EqualFilter::equal_to(invoice_line_id)))
+            .unwrap()
+            .unwrap();
+        let stock_line_id = line.invoice_line_row.stock_line_id.unwrap();
+        stock_line_repo
+            .find_one_by_id(&stock_line_id)
+            .unwrap()
+            .unwrap()
+            .on_hold
+    };
+
+    // Stock lines created when the inbound shipment is received
+    service_provider
+        .invoice_service
+        .update_inbound_shipment(
+            &context,
+            UpdateInboundShipment {
+                id: "inbound".to_string(),
+                status: Some(UpdateInboundShipmentStatus::Delivered),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert!(line_on_hold("received_recalled"));
+    assert!(!line_on_hold("received_other_batch"));
+
+    // Lines added to the received shipment
+    service_provider
+        .invoice_line_service
+        .insert_stock_in_line(
+            &context,
+            InsertStockInLine {
+                id: "added_recalled".to_string(),
+                invoice_id: "inbound".to_string(),
+                item_id: mock_item_a().id,
+                pack_size: 1.0,
+                batch: Some("rc1".to_string()),
+                number_of_packs: 2.0,
+                r#type: StockInType::InboundShipment,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert!(line_on_hold("added_recalled"));
+
+    // Stock of an item merged into the recalled item
+    ItemLinkRowRepository::new(&connection)
+        .upsert_one(&ItemLinkRow {
+            id: mock_item_b().id,
+            item_id: mock_item_a().id,
+        })
+        .unwrap();
+    service_provider
+        .invoice_line_service
+        .insert_stock_in_line(
+            &context,
+            InsertStockInLine {
+                id: "added_merged_item".to_string(),
+                invoice_id: "inbound".to_string(),
+                item_id: mock_item_b().id,
+                pack_size: 1.0,
+                batch: Some("RC1".to_string()),
+                number_of_packs: 2.0,
+                r#type: StockInType::InboundShipment,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert!(line_on_hold("added_merged_item"));
+
+    // Closed recalls don't hold new stock
+    let mut recall = RecallRowRepository::new(&connection)
+        .find_one_by_id("recall")
+        .unwrap()
+        .unwrap();
+    recall.closed_datetime = Some(chrono::Utc::now().naive_utc());
+    RecallRowRepository::new(&connection)
+        .upsert_one(&recall)
+        .unwrap();
+    service_provider
+        .invoice_line_service
+        .insert_stock_in_line(
+            &context,
+            InsertStockInLine {
+                id: "added_after_close".to_string(),
+                invoice_id: "inbound".to_string(),
+                item_id: mock_item_a().id,
+                pack_size: 1.0,
+                batch: Some("RC1".to_string()),
+                number_of_packs: 2.0,
+                r#type: StockInType::InboundShipment,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert!(!line_on_hold("added_after_close"));
+}
+
+#[actix_rt::test]
+async fn insert_and_close_recall() {
+    let (_, _, connection_manager, _) = setup_all_with_data(
+        "insert_and_close_recall",
+        MockDataInserts::all(),
+        MockData::default(),
+    )
+    .await;
+
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider
+        .context(mock_store_a().id, "".to_string())
+        .unwrap();
+
+    assert_eq!(
+        service_provider.recall_service.insert_recall(
+            &context,
+            InsertRecall {
+                id: "recall".to_string(),
+                item_id: mock_item_a().id,
+                ..Default::default()
+            },
+        ),
+        Err(InsertRecallError::NotACentralServer)
+    );
+    assert_eq!(
+        service_provider
+            .recall_service
+            .close_recall(&context, "recall"),
+        Err(CloseRecallError::NotACentralServer)
+    );
+
+    assert_eq!(
+        insert_recall_on_central(
+            &context,
+            InsertRecall {
+                id: "recall".to_string(),
+                item_id: "invalid".to_string(),
+                ..Default::default()
+            },
+        ),
+        Err(InsertRecallError::ItemDoesNotExist)
+    );
+    assert_eq!(
+        insert_recall_on_central(
+            &context,
+            InsertRecall {
+                id: "recall".to_string(),
+                item_id: mock_item_a().id,
+                item_variant_id: Some("invalid".to_string()),
+                ..Default::default()
+            },
+        ),
+        Err(InsertRecallError::ItemVariantDoesNotExist)
+    );
+    assert_eq!(
+        insert_recall_on_central(
+            &context,
+            InsertRecall {
+                id: "recall".to_string(),
+                item_id: mock_item_a().id,
+                expiry_date_from: date(20),
+                expiry_date_to: date(10),
+                ..Default::default()
+            },
+        ),
+        Err(InsertRecallError::InvalidExpiryDateRange)
+    );
+
+    let recall = insert_recall_on_central(
+        &context,
+        InsertRecall {
+            id: "recall".to_string(),
+            item_id: mock_item_a().id,
+            batch: Some("RC1".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(recall.item_row.id, mock_item_a().id);
+    assert_eq!(recall.recall_row.closed_datetime, None);
+    assert_eq!(
+        insert_recall_on_central(
+            &context,
+            InsertRecall {
+                id: "recall".to_string(),
+                item_id: mock_item_a().id,
+                ..Default::default()
+            },
+        ),
+        Err(InsertRecallError::RecallAlreadyExists)
+    );
+
+    let recall = close_recall_on_central(&context, "recall").unwrap();
+    assert!(recall.recall_row.closed_datetime.is_some());
+    assert_eq!(
+        close_recall_on_central(&context, "recall"),
+        Err(CloseRecallError::RecallAlreadyClosed)
+    );
+}
@@ -1,10 +1,14 @@
+use chrono::NaiveDate;
 use repository::{
     ActivityLogRowRepository, EqualFilter, Invoice, InvoiceFilter, InvoiceLineRowRepository,
     InvoiceRepository, InvoiceRowRepository, LocationMovementRowRepository, RepositoryError,
     StockLine, StockLineRowRepository,
 };
 
-use crate::service_provider::ServiceContext;
+use crate::{
+    period_lock::{check_invoice_period_lock, PeriodLockError},
+    service_provider::ServiceContext,
+};
 
 use super::{
     generate::{generate, GenerateRepack},
@@ -28,6 +32,8 @@ pub enum InsertRepackError {
     StockLineReducedBelowZero(StockLine),
     DatabaseError(RepositoryError),
     InternalError(String),
+    /// Holds the lock date of the closed period
+    PeriodLocked(NaiveDate),
 }
 
 pub fn insert_repack(
@@ -45,6 +51,7 @@ pub fn insert_repack(
                 location_movement,
                 activity_log,
             } = generate(ctx, stock_line, input)?;
+            check_invoice_period_lock(ctx, &repack_invoice)?;
 
             let stock_line_repo = StockLineRowRepository::new(connection);
 
@@ -86,6 +93,15 @@ impl From<RepositoryError> for InsertRepackError {
     }
 }
 
+impl From<PeriodLockError> for InsertRepackError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => InsertRepackError::PeriodLocked(lock_date),
+            PeriodLockError::DatabaseError(error) => InsertRepackError::DatabaseError(error),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::service_provider::ServiceProvider;
@@ -14,6 +14,14 @@ pub fn get_default_gql_query(query: DefaultQuery) -> GraphQlQuery {
             query: REQUISITION_QUERY.to_string(),
             variables: None,
         },
+        DefaultQuery::DonorStockStatement => GraphQlQuery {
+            query: DONOR_STOCK_STATEMENT_QUERY.to_string(),
+            variables: None,
+        },
+        DefaultQuery::StockValuation => GraphQlQuery {
+            query: STOCK_VALUATION_QUERY.to_string(),
+            variables: None,
+        },
     }
 }
 
@@ -354,3 +362,95 @@ const REQUISITION_QUERY: &str = r#"query RequisitionQuery($storeId: String, $dat
     }
   }
 }"#;
+
+const DONOR_STOCK_STATEMENT_QUERY: &str = r#"query DonorStockStatementQuery($storeId: String!, $dataId: String, $fromDatetime: DateTime!, $toDatetime: DateTime!) {
+  donorStockStatement(storeId: $storeId, donorId: $dataId, fromDatetime: $fromDatetime, toDatetime: $toDatetime) {
+    totalCount
+    nodes {
+      donorId
+      donor {
+        code
+        name
+      }
+      itemId
+      item {
+        code
+        name
+        unitName
+      }
+      openingQuantity
+      receivedQuantity
+      issuedQuantity
+      closingQuantity
+    }
+  }
+  store(id: $storeId) {
+    ... on StoreNode {
+      id
+      name(storeId: $storeId) {
+        address1
+        address2
+        code
+        country
+        email
+        name
+        phone
+      }
+      code
+      storeName
+      logo
+    }
+    ... on NodeError {
+      __typename
+      error {
+        description
+      }
+    }
+  }
+}"#;
+
+const STOCK_VALUATION_QUERY: &str = r#"query StockValuationQuery($storeId: String!, $method: StockValuationMethodInput!, $fromDatetime: DateTime, $toDatetime: DateTime!) {
+  stockValuation(storeId: $storeId, method: $method, fromDatetime: $fromDatetime, toDatetime: $toDatetime) {
+    totalCount
+    totalOpeningValue
+    totalCostOfGoodsIssued
+    totalClosingValue
+    nodes {
+      itemId
+      item {
+        code
+        name
+        unitName
+      }
+      openingQuantity
+      openingValue
+      issuedQuantity
+      costOfGoodsIssued
+      closingQuantity
+      closingValue
+    }
+  }
+  store(id: $storeId) {
+    ... on StoreNode {
+      id
+      name(storeId: $storeId) {
+        address1
+        address2
+        code
+        country
+        email
+        name
+        phone
+      }
+      code
+      storeName
+      logo
+    }
+    ... on NodeError {
+      __typename
+      error {
+        description
+      }
+    }
+  }
+}"#;
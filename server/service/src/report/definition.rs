@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use repository::ContextType;
+use repository::{database_settings::DatabaseBackend, ContextType};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -13,6 +13,15 @@ pub struct SQLQuery {
     pub query_postgres: String,
 }
 
+impl SQLQuery {
+    pub fn query_for(&self, backend: DatabaseBackend) -> &str {
+        match backend {
+            DatabaseBackend::Sqlite => &self.query_sqlite,
+            DatabaseBackend::Postgres => &self.query_postgres,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct GraphQlQuery {
     pub query: String,
@@ -34,6 +43,12 @@ pub enum DefaultQuery {
     Invoice,
     Stocktake,
     Requisition,
+    /// Opening, received, issued and closing stock per donor and item, the period is set by the
+    /// fromDatetime and toDatetime arguments and the dataId (if set) is the donor
+    DonorStockStatement,
+    /// Stock value and cost of goods issued per item using the valuation method set by the method
+    /// argument, the period is set by the fromDatetime and toDatetime arguments
+    StockValuation,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -1,6 +1,7 @@
 pub mod default_queries;
 pub mod definition;
-mod html_printing;
+pub(crate) mod html_printing;
 mod qr_code;
 pub mod report_service;
 mod string_or_vec;
+mod wasm_sql;
@@ -2,11 +2,11 @@ use base64::prelude::*;
 use chrono::{DateTime, Utc};
 use extism::{
     convert::{encoding, Json},
-    host_fn, FromBytes, Manifest, PluginBuilder, ToBytes, UserData, Wasm, WasmMetadata, PTR,
+    FromBytes, Manifest, PluginBuilder, ToBytes, UserData, Wasm, WasmMetadata, PTR,
 };
 use repository::{
-    raw_query, EqualFilter, JsonRawRow, PaginationOption, Report, ReportFilter, ReportRepository,
-    ReportRowRepository, ReportSort, ReportType, RepositoryError, StorageConnection,
+    EqualFilter, PaginationOption, Report, ReportFilter, ReportRepository, ReportRowRepository,
+    ReportSort, ReportType, RepositoryError, StorageConnection,
 };
 use scraper::{ElementRef, Html, Selector};
 use serde::{Deserialize, Serialize};
@@ -28,6 +28,7 @@ use super::{
     },
     html_printing::html_to_pdf,
     qr_code::qr_code_svg,
+    wasm_sql::sql,
 };
 
 pub enum PrintFormat {
@@ -418,44 +419,6 @@ fn resolve_report_definition(
     })
 }
 
-#[derive(Serialize, Debug, Deserialize, FromBytes)]
-#[encoding(Json)]
-struct WasmSqlQuery {
-    statement: String,
-    parameters: Vec<serde_json::Value>,
-}
-
-#[derive(Serialize, Debug, Deserialize, FromBytes)]
-#[encoding(Json)]
-struct WasmSqlResult {
-    rows: Vec<serde_json::Value>,
-}
-
-host_fn!(sql(user_data: StorageConnection; key: Json<WasmSqlQuery>) -> Json<WasmSqlResult> {
-    Ok(wasm_sql(user_data, key))
-});
-
-fn wasm_sql(
-    user_data: UserData<StorageConnection>,
-    Json(WasmSqlQuery {
-        statement,
-        parameters,
-    }): Json<WasmSqlQuery>,
-) -> Json<WasmSqlResult> {
-    let _ = parameters; // Explicitly ignore parameters
-    let con_mut = user_data.get().unwrap();
-    let con = con_mut.lock().unwrap();
-    let results = raw_query(&con, statement);
-    Json(WasmSqlResult {
-        rows: results
-            .into_iter()
-            .map(|JsonRawRow { json_row }| {
-                serde_json::from_str::<serde_json::Value>(&json_row).unwrap()
-            })
-            .collect(),
-    })
-}
-
 #[derive(Serialize, Deserialize, FromBytes, ToBytes)]
 #[encoding(Json)]
 struct ReportData {
@@ -996,8 +959,7 @@ mod report_generation_test {
         let (_, connection, connection_manager, _) =
             setup_all("test_report_translations", MockDataInserts::none()).await;
 
-        let translation_service =
-            ServiceProvider::new(connection_manager).translations_service;
+        let translation_service = ServiceProvider::new(connection_manager).translations_service;
 
         let mut templates = HashMap::new();
         templates.insert("test.html".to_string(), tera_template);
@@ -0,0 +1,411 @@
+use std::time::Duration;
+
+use extism::{
+    convert::{encoding, Json},
+    host_fn, FromBytes, ToBytes,
+};
+use repository::{
+    read_only_raw_query, JsonRawRow, RawQueryParameter, ReadOnlyQueryError, ReadOnlyQueryLimits,
+    StorageConnection,
+};
+use serde::{Deserialize, Serialize};
+
+const WASM_SQL_MAX_ROWS: usize = 10_000;
+const WASM_SQL_TIMEOUT_MS: u64 = 10_000;
+
+/// First keyword of the statements a report can run
+const ALLOWED_STATEMENTS: &[&str] = &["SELECT", "WITH"];
+/// Functions a report statement can't call since they read files, open connections outside of
+/// the read only transaction or run SQL passed as a string, which isn't checked. Writes
+/// (including `SET`, `PRAGMA` or data modifying CTEs) don't need to be listed, they fail in the
+/// read only transaction the statement runs in as a subquery.
+const DENIED_FUNCTIONS: &[&str] = &[
+    "LOAD_EXTENSION",
+    "PG_READ_FILE",
+    "PG_READ_BINARY_FILE",
+    "PG_LS_DIR",
+    "PG_STAT_FILE",
+    "LO_IMPORT",
+    "LO_EXPORT",
+    "DBLINK",
+    "DBLINK_EXEC",
+    "DBLINK_CONNECT",
+];
+/// Postgres `query_to_xml`, `cursor_to_xml`, `table_to_xml`, `..._to_xmlschema` and
+/// `..._to_xml_and_xmlschema` run the query or read the table given as a string
+const DENIED_FUNCTION_PART: &str = "_TO_XML";
+
+/// Query from a report's convert_data WASM function. The statement needs to return a `json_row`
+/// column and can use `$1`, `$2`, ... for the parameters.
+#[derive(Serialize, Debug, Deserialize, FromBytes)]
+#[encoding(Json)]
+pub(crate) struct WasmSqlQuery {
+    pub(crate) statement: String,
+    #[serde(default)]
+    pub(crate) parameters: Vec<serde_json::Value>,
+}
+
+#[derive(Serialize, Debug, Deserialize, ToBytes)]
+#[encoding(Json)]
+pub(crate) struct WasmSqlResult {
+    pub(crate) rows: Vec<serde_json::Value>,
+    /// Set when the query failed, `rows` is empty in that case
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<WasmSqlError>,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq)]
+#[serde(tag = "code", rename_all = "SCREAMING_SNAKE_CASE")]
+pub(crate) enum WasmSqlError {
+    StatementNotAllowed { message: String },
+    InvalidParameter { index: usize, message: String },
+    TooManyRows { max_rows: usize },
+    Timeout { timeout_ms: u64 },
+    InvalidRow { message: String },
+    DatabaseError { message: String },
+}
+
+host_fn!(pub(crate) sql(user_data: StorageConnection; query: Json<WasmSqlQuery>) -> Json<WasmSqlResult> {
+    let connection = user_data.get()?;
+    let connection = connection.lock().unwrap();
+    let limits = ReadOnlyQueryLimits {
+        max_rows: WASM_SQL_MAX_ROWS,
+        timeout: Duration::from_millis(WASM_SQL_TIMEOUT_MS),
+    };
+
+    let Json(query) = query;
+    let result = match wasm_sql(&connection, query, &limits) {
+        Ok(rows) => WasmSqlResult { rows, error: None },
+        Err(error) => WasmSqlResult { rows: Vec::new(), error: Some(error) },
+    };
+    Ok(Json(result))
+});
+
+/// Runs a report statement read only, errors are returned to the WASM module rather than failing
+/// the report generation
+pub(crate) fn wasm_sql(
+    connection: &StorageConnection,
+    WasmSqlQuery {
+        statement,
+        parameters,
+    }: WasmSqlQuery,
+    limits: &ReadOnlyQueryLimits,
+) -> Result<Vec<serde_json::Value>, WasmSqlError> {
+    check_statement(&statement)?;
+    let parameters = parameters
+        .into_iter()
+        .enumerate()
+        .map(|(index, parameter)| to_query_parameter(index, parameter))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let rows =
+        read_only_raw_query(connection, &statement, &parameters, limits).map_err(|error| {
+            match error {
+                ReadOnlyQueryError::TooManyRows(max_rows) => WasmSqlError::TooManyRows { max_rows },
+                ReadOnlyQueryError::Timeout(timeout) => WasmSqlError::Timeout {
+                    timeout_ms: timeout.as_millis() as u64,
+                },
+                ReadOnlyQueryError::DatabaseError(error) => WasmSqlError::DatabaseError {
+                    message: error.to_string(),
+                },
+            }
+        })?;
+
+    rows.into_iter()
+        .map(|JsonRawRow { json_row }| {
+            serde_json::from_str::<serde_json::Value>(&json_row).map_err(|error| {
+                WasmSqlError::InvalidRow {
+                    message: format!("json_row is not valid JSON: {}", error),
+                }
+            })
+        })
+        .collect()
+}
+
+fn check_statement(statement: &str) -> Result<(), WasmSqlError> {
+    let not_allowed = |message: String| Err(WasmSqlError::StatementNotAllowed { message });
+
+    let tokens = match tokenize(statement) {
+        Ok(tokens) => tokens,
+        Err(message) => return not_allowed(message),
+    };
+    let semicolon = tokens.iter().position(|token| *token == Token::Symbol(';'));
+    if semicolon.is_some_and(|position| position + 1 < tokens.len()) {
+        return not_allowed("Only a single statement can be run".to_string());
+    }
+
+    match tokens.first() {
+        Some(Token::Word(first)) if ALLOWED_STATEMENTS.contains(&first.as_str()) => {}
+        _ => {
+            return not_allowed(format!(
+                "Statement must start with one of {}",
+                ALLOWED_STATEMENTS.join(", ")
+            ))
+        }
+    }
+
+    // Only function calls are checked, the same names can be used as identifiers
+    for call in tokens.windows(2) {
+        if let [Token::Word(name), Token::Symbol('(')] = call {
+            if DENIED_FUNCTIONS.contains(&name.as_str()) || name.contains(DENIED_FUNCTION_PART) {
+                return not_allowed(format!("{} is not allowed", name));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    /// Upper case keyword or identifier, quoted identifiers included
+    Word(String),
+    Symbol(char),
+}
+
+/// Splits a statement into words and symbols, string literals and comments are skipped.
+/// Literals whose end can't be found without knowing the database's escaping rules (Postgres
+/// `E'..'` escape strings and `$tag$..$tag$` dollar quotes) are rejected, skipping them wrongly
+/// would hide the rest of the statement from the checks.
+fn tokenize(statement: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = statement.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                for next in chars.by_ref() {
+                    if next == '\'' {
+                        break;
+                    }
+                }
+            }
+            '$' if chars
+                .peek()
+                .is_some_and(|next| *next == '$' || next.is_alphabetic() || *next == '_') =>
+            {
+                return Err("Dollar quoted strings are not allowed".to_string());
+            }
+            '"' | '`' | '[' => {
+                let end = if c == '[' { ']' } else { c };
+                let identifier: String = chars.by_ref().take_while(|next| *next != end).collect();
+                tokens.push(Token::Word(identifier.to_uppercase()));
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(next) =
+                    chars.next_if(|next| next.is_ascii_alphanumeric() || *next == '_')
+                {
+                    word.push(next);
+                }
+                if word.eq_ignore_ascii_case("E") && chars.peek() == Some(&'\'') {
+                    return Err("Escape string literals are not allowed".to_string());
+                }
+                tokens.push(Token::Word(word.to_uppercase()));
+            }
+            c if c.is_whitespace() => {}
+            c => tokens.push(Token::Symbol(c)),
+        }
+    }
+    Ok(tokens)
+}
+
+fn to_query_parameter(
+    index: usize,
+    parameter: serde_json::Value,
+) -> Result<RawQueryParameter, WasmSqlError> {
+    Ok(match parameter {
+        serde_json::Value::Null => RawQueryParameter::Null,
+        serde_json::Value::Bool(value) => RawQueryParameter::Bool(value),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(value) => RawQueryParameter::Integer(value),
+            None => RawQueryParameter::Float(number.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(value) => RawQueryParameter::Text(value),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+            return Err(WasmSqlError::InvalidParameter {
+                index,
+                message: "Arrays and objects can't be bound, pass them as a JSON string"
+                    .to_string(),
+            })
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use repository::{
+        mock::{mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        ReadOnlyQueryLimits,
+    };
+    use serde_json::json;
+
+    use super::{wasm_sql, WasmSqlError, WasmSqlQuery};
+
+    fn query(statement: &str, parameters: Vec<serde_json::Value>) -> WasmSqlQuery {
+        WasmSqlQuery {
+            statement: statement.to_string(),
+            parameters,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn report_wasm_sql() {
+        let (_, connection, _, _) =
+            setup_all("report_wasm_sql", MockDataInserts::none().names().stores()).await;
+        let limits = ReadOnlyQueryLimits {
+            max_rows: 2,
+            timeout: Duration::from_secs(10),
+        };
+
+        // Bound parameters, the ';' in the literal is not a second statement
+        let result = wasm_sql(
+            &connection,
+            query(
+                "SELECT '{\"id\":\"' || id || '\",\"note\":\";\"}' AS json_row FROM store WHERE id = $1 AND $2",
+                vec![json!(mock_store_a().id), json!(true)],
+            ),
+            &limits,
+        );
+        assert_eq!(
+            result,
+            Ok(vec![json!({"id": mock_store_a().id, "note": ";"})])
+        );
+
+        // Statement allow list and denied functions
+        let not_allowed = |statement: &str| {
+            matches!(
+                wasm_sql(&connection, query(statement, vec![]), &limits),
+                Err(WasmSqlError::StatementNotAllowed { .. })
+            )
+        };
+        assert!(not_allowed("DELETE FROM store"));
+        assert!(not_allowed(
+            "SELECT id AS json_row FROM store; DELETE FROM store"
+        ));
+        assert!(not_allowed("/* SELECT */ PRAGMA query_only = OFF"));
+        assert!(not_allowed("SELECT load_extension('x') AS json_row"));
+        assert!(not_allowed(
+            "SELECT \"pg_read_file\"('/etc/passwd') AS json_row"
+        ));
+        assert!(!not_allowed(
+            "SELECT id AS json_row FROM store WHERE name = 'DELETE' -- DROP"
+        ));
+        assert!(not_allowed("SELECT `load_extension`('x') AS json_row"));
+        assert!(not_allowed("SELECT [load_extension]('x') AS json_row"));
+        // SQL passed as a string
+        assert!(not_allowed(
+            "SELECT query_to_xml('select pg_read_file(''/etc/passwd'')', true, false, '') AS json_row"
+        ));
+        assert!(not_allowed(
+            "SELECT cursor_to_xml('c', 1, true, false, '') AS json_row"
+        ));
+        assert!(not_allowed(
+            "SELECT table_to_xml('user_account', true, false, '') AS json_row"
+        ));
+        assert!(not_allowed(
+            "SELECT Query_To_Xml_And_XmlSchema('select 1', true, false, '') AS json_row"
+        ));
+        // Literals that would hide the rest of the statement from the checks
+        assert!(not_allowed(
+            "SELECT E'\\'' AS json_row, pg_read_file('/etc/passwd') --'"
+        ));
+        assert!(not_allowed("SELECT e'x' AS json_row"));
+        assert!(not_allowed(
+            "SELECT $$'$$ AS json_row, pg_read_file('/etc/passwd') --'"
+        ));
+        assert!(not_allowed("SELECT $tag$x$tag$ AS json_row FROM store"));
+        // Parameters and identifiers ending in e are fine
+        assert!(!not_allowed(
+            "SELECT id AS json_row FROM store WHERE name='x' AND code=$1",
+        ));
+
+        // Keywords can be used as identifiers
+        assert_eq!(
+            wasm_sql(
+                &connection,
+                query(
+                    "WITH load AS (SELECT id AS reset FROM store WHERE id = $1) SELECT '{\"id\":\"' || reset || '\"}' AS json_row FROM load",
+                    vec![json!(mock_store_a().id)]
+                ),
+                &limits
+            ),
+            Ok(vec![json!({"id": mock_store_a().id})])
+        );
+
+        // Writes fail in the read only transaction
+        assert!(matches!(
+            wasm_sql(
+                &connection,
+                query(
+                    "WITH deleted AS (DELETE FROM store RETURNING id) SELECT id AS json_row FROM deleted",
+                    vec![]
+                ),
+                &limits
+            ),
+            Err(WasmSqlError::DatabaseError { .. })
+        ));
+
+        assert_eq!(
+            wasm_sql(
+                &connection,
+                query(
+                    "SELECT id AS json_row FROM store WHERE id = $1",
+                    vec![json!(["a"])]
+                ),
+                &limits,
+            ),
+            Err(WasmSqlError::InvalidParameter {
+                index: 0,
+                message: "Arrays and objects can't be bound, pass them as a JSON string"
+                    .to_string()
+            })
+        );
+        assert_eq!(
+            wasm_sql(
+                &connection,
+                query("SELECT '{}' AS json_row FROM store", vec![]),
+                &limits
+            ),
+            Err(WasmSqlError::TooManyRows { max_rows: 2 })
+        );
+        assert!(matches!(
+            wasm_sql(
+                &connection,
+                query(
+                    "SELECT id AS json_row FROM store WHERE id = $1",
+                    vec![json!(mock_store_a().id)]
+                ),
+                &limits
+            ),
+            Err(WasmSqlError::InvalidRow { .. })
+        ));
+        assert!(matches!(
+            wasm_sql(
+                &connection,
+                query("SELECT id AS json_row FROM not_a_table", vec![]),
+                &limits
+            ),
+            Err(WasmSqlError::DatabaseError { .. })
+        ));
+    }
+}
@@ -1,5 +1,9 @@
 use crate::{
     item::item::check_item_exists,
+    plugin::server_plugin::{
+        hooks::{call_plugin_hook, PluginHookError, PluginVeto, RequisitionLineHookInput},
+        PluginHook,
+    },
     requisition::{
         common::check_requisition_row_exists, request_requisition::generate_requisition_lines,
     },
@@ -35,6 +39,8 @@ pub enum InsertRequestRequisitionLineError {
     NotThisStoreRequisition,
     CannotEditRequisition,
     NotARequestRequisition,
+    /// A server plugin rejected the line
+    PluginVetoed(PluginVeto),
     DatabaseError(RepositoryError),
     // Should never happen
     CannotFindItemStatusForRequisitionLine,
@@ -53,6 +59,13 @@ pub fn insert_request_requisition_line(
             let requisition_row = validate(connection, &ctx.store_id, &input)?;
             let new_requisition_line_row = generate(ctx, &ctx.store_id, requisition_row, input)?;
 
+            call_plugin_hook(
+                ctx,
+                PluginHook::ValidateRequisitionLine,
+                &new_requisition_line_row.id,
+                &RequisitionLineHookInput::new(&ctx.store_id, &new_requisition_line_row),
+            )?;
+
             RequisitionLineRowRepository::new(connection).upsert_one(&new_requisition_line_row)?;
 
             get_requisition_line(ctx, &new_requisition_line_row.id)
@@ -130,6 +143,17 @@ impl From<RepositoryError> for InsertRequestRequisitionLineError {
     }
 }
 
+impl From<PluginHookError> for InsertRequestRequisitionLineError {
+    fn from(error: PluginHookError) -> Self {
+        match error {
+            PluginHookError::Vetoed(veto) => InsertRequestRequisitionLineError::PluginVetoed(veto),
+            PluginHookError::DatabaseError(error) => {
+                InsertRequestRequisitionLineError::DatabaseError(error)
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use repository::{
@@ -141,11 +165,16 @@ mod test {
             mock_store_b, test_item_stats, MockDataInserts,
         },
         test_db::{setup_all, setup_all_with_data},
-        RequisitionLineRowRepository,
+        ItemRow, ItemRowRepository, RequisitionLineRowRepository,
     };
     use util::{inline_edit, inline_init};
 
     use crate::{
+        plugin::server_plugin::{
+            hooks::PluginVeto,
+            host::{LoadedServerPlugin, ServerPluginHost},
+            HostPermission, PluginHook, ServerPluginConfig,
+        },
         requisition_line::request_requisition_line::{
             InsertRequestRequisitionLine, InsertRequestRequisitionLineError as ServiceError,
         },
@@ -325,4 +354,116 @@ mod test {
 
         assert!(result.is_ok());
     }
+
+    /// Vetoes requisition lines while `plugin_veto_item` exists. Checking the database keeps the
+    /// installed plugin from vetoing lines of other tests.
+    const VETO_PLUGIN_WAT: &str = r#"
+        (module
+            (import "extism:host/env" "alloc" (func $alloc (param i64) (result i64)))
+            (import "extism:host/env" "length" (func $length (param i64) (result i64)))
+            (import "extism:host/env" "store_u8" (func $store_u8 (param i64 i32)))
+            (import "extism:host/env" "output_set" (func $output_set (param i64 i64)))
+            (import "extism:host/user" "get_items" (func $get_items (param i64) (result i64)))
+            (memory 1)
+            (data (i32.const 0) "[\"plugin_veto_item\"]")
+            (data (i32.const 32) "{\"veto\":\"Blocked item\"}")
+            (data (i32.const 64) "{}")
+
+            (func $copy (param $from i32) (param $length i32) (result i64)
+                (local $offset i64)
+                (local $i i32)
+                (local.set $offset (call $alloc (i64.extend_i32_u (local.get $length))))
+                (block $done
+                    (loop $next
+                        (br_if $done (i32.ge_u (local.get $i) (local.get $length)))
+                        (call $store_u8
+                            (i64.add (local.get $offset) (i64.extend_i32_u (local.get $i)))
+                            (i32.load8_u (i32.add (local.get $from) (local.get $i))))
+                        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                        (br $next)))
+                (local.get $offset))
+
+            (func $output (param $from i32) (param $length i32)
+                (call $output_set
+                    (call $copy (local.get $from) (local.get $length))
+                    (i64.extend_i32_u (local.get $length))))
+
+            (func (export "validate_requisition_line") (result i32)
+                (if (i64.eq
+                        (call $length (call $get_items (call $copy (i32.const 0) (i32.const 20))))
+                        (i64.const 2))
+                    (then (call $output (i32.const 64) (i32.const 2)))
+                    (else (call $output (i32.const 32) (i32.const 23))))
+                (i32.const 0)))
+    "#;
+
+    #[actix_rt::test]
+    async fn insert_request_requisition_line_plugin_veto() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "insert_request_requisition_line_plugin_veto",
+            MockDataInserts::all(),
+            test_item_stats::mock_item_stats(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager);
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.requisition_line_service;
+
+        ServerPluginHost::new(vec![LoadedServerPlugin::new(
+            ServerPluginConfig {
+                code: "veto_plugin".to_string(),
+                wasm: "plugin.wasm".to_string(),
+                hooks: vec![PluginHook::ValidateRequisitionLine],
+                permissions: vec![HostPermission::ReadItems],
+                fuel_limit: Some(100_000),
+                timeout_ms: None,
+            },
+            VETO_PLUGIN_WAT.as_bytes().to_vec(),
+        )])
+        .install();
+
+        // Plugin lets the line through
+        let result = service.insert_request_requisition_line(
+            &context,
+            InsertRequestRequisitionLine {
+                requisition_id: mock_request_draft_requisition().id,
+                id: "allowed line".to_string(),
+                item_id: mock_item_c().id,
+            },
+        );
+        assert!(result.is_ok());
+
+        ItemRowRepository::new(&connection)
+            .upsert_one(&ItemRow {
+                id: "plugin_veto_item".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        let result = service.insert_request_requisition_line(
+            &context,
+            InsertRequestRequisitionLine {
+                requisition_id: mock_request_draft_requisition_calculation_test()
+                    .requisition
+                    .id,
+                id: "vetoed line".to_string(),
+                item_id: test_item_stats::item2().id,
+            },
+        );
+        ServerPluginHost::uninstall();
+
+        assert_eq!(
+            result,
+            Err(ServiceError::PluginVetoed(PluginVeto {
+                plugin_code: "veto_plugin".to_string(),
+                reason: "Blocked item".to_string(),
+            }))
+        );
+        assert_eq!(
+            RequisitionLineRowRepository::new(&connection).find_one_by_id("vetoed line"),
+            Ok(None)
+        );
+    }
 }
@@ -1,4 +1,8 @@
 use crate::{
+    plugin::server_plugin::{
+        hooks::{call_plugin_hook, PluginHookError, PluginVeto, RequisitionLineHookInput},
+        PluginHook,
+    },
     requisition::common::check_requisition_row_exists,
     requisition_line::{common::check_requisition_line_exists, query::get_requisition_line},
     service_provider::ServiceContext,
@@ -28,6 +32,8 @@ pub enum UpdateRequestRequisitionLineError {
     NotARequestRequisition,
     UpdatedRequisitionLineDoesNotExist,
     RequisitionDoesNotExist,
+    /// A server plugin rejected the line
+    PluginVetoed(PluginVeto),
     DatabaseError(RepositoryError),
 }
 
@@ -43,6 +49,13 @@ pub fn update_request_requisition_line(
             let requisition_row = validate(connection, &ctx.store_id, &input)?;
             let updated_requisition_line_row = generate(requisition_row, input);
 
+            call_plugin_hook(
+                ctx,
+                PluginHook::ValidateRequisitionLine,
+                &updated_requisition_line_row.id,
+                &RequisitionLineHookInput::new(&ctx.store_id, &updated_requisition_line_row),
+            )?;
+
             RequisitionLineRowRepository::new(connection)
                 .upsert_one(&updated_requisition_line_row)?;
 
@@ -105,6 +118,17 @@ impl From<RepositoryError> for UpdateRequestRequisitionLineError {
     }
 }
 
+impl From<PluginHookError> for UpdateRequestRequisitionLineError {
+    fn from(error: PluginHookError) -> Self {
+        match error {
+            PluginHookError::Vetoed(veto) => UpdateRequestRequisitionLineError::PluginVetoed(veto),
+            PluginHookError::DatabaseError(error) => {
+                UpdateRequestRequisitionLineError::DatabaseError(error)
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use repository::{
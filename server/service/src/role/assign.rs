@@ -0,0 +1,138 @@
+use repository::{
+    RepositoryError, RoleRowRepository, StorageConnection, StoreRowRepository,
+    UserAccountRowRepository, UserRoleRow, UserRoleRowRepository,
+};
+
+use crate::{service_provider::ServiceContext, sync::CentralServerConfig};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AssignUserRole {
+    pub id: String,
+    pub user_id: String,
+    pub role_id: String,
+    /// Store the role applies in, a role without a store only applies outside of stores
+    pub store_id: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AssignUserRoleError {
+    NotACentralServer,
+    UserDoesNotExist,
+    RoleDoesNotExist,
+    StoreDoesNotExist,
+    RoleAlreadyAssigned,
+    UserRoleDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+/// Role assignments are made on the central server and synced to all sites
+pub fn assign_user_role(
+    ctx: &ServiceContext,
+    input: AssignUserRole,
+) -> Result<UserRoleRow, AssignUserRoleError> {
+    if !CentralServerConfig::is_central_server() {
+        return Err(AssignUserRoleError::NotACentralServer);
+    }
+
+    assign_user_role_on_central(ctx, input)
+}
+
+pub(crate) fn assign_user_role_on_central(
+    ctx: &ServiceContext,
+    input: AssignUserRole,
+) -> Result<UserRoleRow, AssignUserRoleError> {
+    let user_role = ctx
+        .connection
+        .transaction_sync(|connection| {
+            validate(connection, &input)?;
+            let AssignUserRole {
+                id,
+                user_id,
+                role_id,
+                store_id,
+            } = input;
+            let row = UserRoleRow {
+                id,
+                user_id,
+                role_id,
+                store_id,
+            };
+            UserRoleRowRepository::new(connection).upsert_one(&row)?;
+            Ok(row)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(user_role)
+}
+
+pub fn remove_user_role(
+    ctx: &ServiceContext,
+    user_role_id: &str,
+) -> Result<String, AssignUserRoleError> {
+    if !CentralServerConfig::is_central_server() {
+        return Err(AssignUserRoleError::NotACentralServer);
+    }
+
+    remove_user_role_on_central(ctx, user_role_id)
+}
+
+pub(crate) fn remove_user_role_on_central(
+    ctx: &ServiceContext,
+    user_role_id: &str,
+) -> Result<String, AssignUserRoleError> {
+    UserRoleRowRepository::new(&ctx.connection)
+        .delete(user_role_id)?
+        .ok_or(AssignUserRoleError::UserRoleDoesNotExist)?;
+
+    Ok(user_role_id.to_string())
+}
+
+fn validate(
+    connection: &StorageConnection,
+    input: &AssignUserRole,
+) -> Result<(), AssignUserRoleError> {
+    use AssignUserRoleError::*;
+
+    if UserAccountRowRepository::new(connection)
+        .find_one_by_id(&input.user_id)?
+        .is_none()
+    {
+        return Err(UserDoesNotExist);
+    }
+
+    if RoleRowRepository::new(connection)
+        .find_one_by_id(&input.role_id)?
+        .is_none()
+    {
+        return Err(RoleDoesNotExist);
+    }
+
+    if let Some(store_id) = &input.store_id {
+        if StoreRowRepository::new(connection)
+            .find_one_by_id(store_id)?
+            .is_none()
+        {
+            return Err(StoreDoesNotExist);
+        }
+    }
+
+    let already_assigned = UserRoleRowRepository::new(connection)
+        .find_many_by_user_id(&input.user_id)?
+        .into_iter()
+        .any(|existing| {
+            existing.id != input.id
+                && existing.role_id == input.role_id
+                && existing.store_id == input.store_id
+        });
+    if already_assigned {
+        return Err(RoleAlreadyAssigned);
+    }
+
+    Ok(())
+}
+
+impl From<RepositoryError> for AssignUserRoleError {
+    fn from(error: RepositoryError) -> Self {
+        AssignUserRoleError::DatabaseError(error)
+    }
+}
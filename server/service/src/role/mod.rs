@@ -0,0 +1,65 @@
+use repository::{RepositoryError, RolePermissionRow, RoleRow, UserRoleRow};
+
+use self::{
+    assign::{assign_user_role, remove_user_role, AssignUserRole, AssignUserRoleError},
+    query::{effective_permissions, get_roles, EffectivePermission},
+    upsert::{upsert_role, UpsertRole, UpsertRoleError},
+};
+use crate::service_provider::ServiceContext;
+
+pub mod assign;
+pub mod query;
+pub mod upsert;
+
+pub use self::query::resolve_user_permissions;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Role {
+    pub role_row: RoleRow,
+    pub permissions: Vec<RolePermissionRow>,
+}
+
+pub trait RoleServiceTrait: Sync + Send {
+    fn get_roles(&self, ctx: &ServiceContext) -> Result<Vec<Role>, RepositoryError> {
+        get_roles(ctx)
+    }
+
+    fn upsert_role(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertRole,
+    ) -> Result<Role, UpsertRoleError> {
+        upsert_role(ctx, input)
+    }
+
+    fn assign_user_role(
+        &self,
+        ctx: &ServiceContext,
+        input: AssignUserRole,
+    ) -> Result<UserRoleRow, AssignUserRoleError> {
+        assign_user_role(ctx, input)
+    }
+
+    fn remove_user_role(
+        &self,
+        ctx: &ServiceContext,
+        user_role_id: &str,
+    ) -> Result<String, AssignUserRoleError> {
+        remove_user_role(ctx, user_role_id)
+    }
+
+    fn effective_permissions(
+        &self,
+        ctx: &ServiceContext,
+        user_id: &str,
+        store_id: Option<&str>,
+    ) -> Result<Vec<EffectivePermission>, RepositoryError> {
+        effective_permissions(&ctx.connection, user_id, store_id)
+    }
+}
+
+pub struct RoleService {}
+impl RoleServiceTrait for RoleService {}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use repository::{
+    EqualFilter, PermissionType, RepositoryError, RolePermissionRowRepository, RoleRow,
+    RoleRowRepository, StorageConnection, UserPermissionFilter, UserPermissionRepository,
+    UserPermissionRow, UserRoleRowRepository,
+};
+
+use crate::service_provider::ServiceContext;
+
+use super::Role;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PermissionSource {
+    /// Permission row assigned to the user directly
+    Direct,
+    Role(RoleRow),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectivePermission {
+    pub permission: UserPermissionRow,
+    pub source: PermissionSource,
+}
+
+pub fn get_roles(ctx: &ServiceContext) -> Result<Vec<Role>, RepositoryError> {
+    let role_rows = RoleRowRepository::new(&ctx.connection).find_all()?;
+    let role_ids: Vec<String> = role_rows.iter().map(|role| role.id.clone()).collect();
+
+    let mut permissions_by_role = HashMap::new();
+    for permission in
+        RolePermissionRowRepository::new(&ctx.connection).find_many_by_role_ids(&role_ids)?
+    {
+        permissions_by_role
+            .entry(permission.role_id.clone())
+            .or_insert_with(Vec::new)
+            .push(permission);
+    }
+
+    Ok(role_rows
+        .into_iter()
+        .map(|role_row| Role {
+            permissions: permissions_by_role.remove(&role_row.id).unwrap_or_default(),
+            role_row,
+        })
+        .collect())
+}
+
+/// Permissions of the user, either assigned directly or through one of the user's active roles.
+/// When a store_id is given only permissions for that store are returned, otherwise permissions
+/// for all stores and those without a store.
+pub fn effective_permissions(
+    connection: &StorageConnection,
+    user_id: &str,
+    store_id: Option<&str>,
+) -> Result<Vec<EffectivePermission>, RepositoryError> {
+    let mut filter = UserPermissionFilter::new().user_id(EqualFilter::equal_to(user_id));
+    if let Some(store_id) = store_id {
+        filter = filter.store_id(EqualFilter::equal_to(store_id));
+    }
+    let mut result: Vec<EffectivePermission> = UserPermissionRepository::new(connection)
+        .query_by_filter(filter)?
+        .into_iter()
+        .map(|permission| EffectivePermission {
+            permission,
+            source: PermissionSource::Direct,
+        })
+        .collect();
+
+    let user_roles: Vec<_> = UserRoleRowRepository::new(connection)
+        .find_many_by_user_id(user_id)?
+        .into_iter()
+        .filter(|user_role| store_id.is_none() || user_role.store_id.as_deref() == store_id)
+        .collect();
+    if user_roles.is_empty() {
+        return Ok(result);
+    }
+
+    let role_ids: Vec<String> = user_roles.iter().map(|r| r.role_id.clone()).collect();
+    let roles: HashMap<String, RoleRow> = RoleRowRepository::new(connection)
+        .find_many_by_id(&role_ids)?
+        .into_iter()
+        .filter(|role| role.is_active)
+        .map(|role| (role.id.clone(), role))
+        .collect();
+    let role_permissions =
+        RolePermissionRowRepository::new(connection).find_many_by_role_ids(&role_ids)?;
+
+    for user_role in user_roles {
+        let Some(role) = roles.get(&user_role.role_id) else {
+            continue;
+        };
+        for role_permission in role_permissions
+            .iter()
+            .filter(|permission| permission.role_id == role.id)
+        {
+            result.push(EffectivePermission {
+                permission: UserPermissionRow {
+                    id: format!("{}_{}", user_role.id, role_permission.id),
+                    user_id: user_id.to_string(),
+                    store_id: user_role.store_id.clone(),
+                    permission: role_permission.permission.clone(),
+                    context_id: role_permission.context_id.clone(),
+                },
+                source: PermissionSource::Role(role.clone()),
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+/// Same as `effective_permissions` without the source, used for access control
+pub fn resolve_user_permissions(
+    connection: &StorageConnection,
+    user_id: &str,
+    store_id: Option<&str>,
+) -> Result<Vec<UserPermissionRow>, RepositoryError> {
+    Ok(effective_permissions(connection, user_id, store_id)?
+        .into_iter()
+        .map(|effective| effective.permission)
+        .collect())
+}
+
+/// Stores the user can log in to through an active role with the `StoreAccess` permission.
+/// Directly assigned stores come from `user_store_join` instead.
+pub fn role_store_ids(
+    connection: &StorageConnection,
+    user_id: &str,
+) -> Result<Vec<String>, RepositoryError> {
+    let mut store_ids: Vec<String> = effective_permissions(connection, user_id, None)?
+        .into_iter()
+        .filter(|effective| matches!(effective.source, PermissionSource::Role(_)))
+        .filter(|effective| effective.permission.permission == PermissionType::StoreAccess)
+        .filter_map(|effective| effective.permission.store_id)
+        .collect();
+    store_ids.sort();
+    store_ids.dedup();
+    Ok(store_ids)
+}
@@ -0,0 +1,385 @@
+use std::sync::{Arc, RwLock};
+
+use chrono::Utc;
+use repository::{
+    mock::{mock_store_a, mock_store_b, MockData, MockDataInserts},
+    test_db::setup_all_with_data,
+    KeyType, KeyValueStoreRepository, PermissionType, UserAccountRow, UserAuthStateRow,
+    UserAuthStateRowRepository, UserPermissionRow,
+};
+use util::assert_matches;
+
+use crate::{
+    auth_data::AuthData,
+    login::{LoginError, LoginFailure, LoginInput, LoginService},
+    role::{
+        assign::{
+            assign_user_role_on_central, remove_user_role_on_central, AssignUserRole,
+            AssignUserRoleError,
+        },
+        query::{effective_permissions, resolve_user_permissions, PermissionSource},
+        upsert::{upsert_role_on_central, RolePermissionInput, UpsertRole, UpsertRoleError},
+    },
+    service_provider::ServiceProvider,
+    token_bucket::TokenBucket,
+    user_account::UserAccountService,
+};
+
+fn role_user() -> UserAccountRow {
+    UserAccountRow {
+        id: "role_user".to_string(),
+        username: "role_user".to_string(),
+        ..Default::default()
+    }
+}
+
+fn permission(permission: PermissionType) -> RolePermissionInput {
+    RolePermissionInput {
+        permission,
+        context_id: None,
+    }
+}
+
+fn sorted_permissions(mut permissions: Vec<UserPermissionRow>) -> Vec<PermissionType> {
+    permissions.sort_by_key(|row| format!("{:?}", row.permission));
+    permissions.into_iter().map(|row| row.permission).collect()
+}
+
+#[actix_rt::test]
+async fn role_permissions_are_resolved_per_store() {
+    let (_, connection, connection_manager, _) = setup_all_with_data(
+        "role_permissions_are_resolved_per_store",
+        MockDataInserts::none().names().stores(),
+        MockData {
+            user_accounts: vec![role_user()],
+            user_permissions: vec![UserPermissionRow {
+                id: "direct_permission".to_string(),
+                user_id: role_user().id,
+                store_id: Some(mock_store_a().id),
+                permission: PermissionType::StoreAccess,
+                context_id: None,
+            }],
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider
+        .context(mock_store_a().id, "".to_string())
+        .unwrap();
+
+    upsert_role_on_central(
+        &context,
+        UpsertRole {
+            id: "stock_controller".to_string(),
+            name: "Stock controller".to_string(),
+            description: None,
+            is_active: true,
+            permissions: vec![
+                permission(PermissionType::StockLineQuery),
+                permission(PermissionType::StocktakeMutate),
+            ],
+        },
+    )
+    .unwrap();
+    assign_user_role_on_central(
+        &context,
+        AssignUserRole {
+            id: "role_user_store_b".to_string(),
+            user_id: role_user().id,
+            role_id: "stock_controller".to_string(),
+            store_id: Some(mock_store_b().id),
+        },
+    )
+    .unwrap();
+
+    // Role is only assigned in store b
+    assert_eq!(
+        sorted_permissions(
+            resolve_user_permissions(&connection, &role_user().id, Some(&mock_store_a().id))
+                .unwrap()
+        ),
+        vec![PermissionType::StoreAccess]
+    );
+    assert_eq!(
+        sorted_permissions(
+            resolve_user_permissions(&connection, &role_user().id, Some(&mock_store_b().id))
+                .unwrap()
+        ),
+        vec![
+            PermissionType::StockLineQuery,
+            PermissionType::StocktakeMutate
+        ]
+    );
+
+    let effective = effective_permissions(&connection, &role_user().id, None).unwrap();
+    assert_eq!(effective.len(), 3);
+    assert!(effective
+        .iter()
+        .any(|effective| effective.source == PermissionSource::Direct));
+    assert!(effective.iter().any(|effective| matches!(
+        &effective.source,
+        PermissionSource::Role(role) if role.id == "stock_controller"
+    )));
+
+    // Permissions are replaced when the role is updated
+    upsert_role_on_central(
+        &context,
+        UpsertRole {
+            id: "stock_controller".to_string(),
+            name: "Stock controller".to_string(),
+            description: None,
+            is_active: true,
+            permissions: vec![permission(PermissionType::StockLineQuery)],
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        sorted_permissions(
+            resolve_user_permissions(&connection, &role_user().id, Some(&mock_store_b().id))
+                .unwrap()
+        ),
+        vec![PermissionType::StockLineQuery]
+    );
+
+    // Inactive roles don't give permissions
+    upsert_role_on_central(
+        &context,
+        UpsertRole {
+            id: "stock_controller".to_string(),
+            name: "Stock controller".to_string(),
+            description: None,
+            is_active: false,
+            permissions: vec![permission(PermissionType::StockLineQuery)],
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        resolve_user_permissions(&connection, &role_user().id, Some(&mock_store_b().id)),
+        Ok(vec![])
+    );
+
+    // Removed roles don't give permissions
+    upsert_role_on_central(
+        &context,
+        UpsertRole {
+            id: "stock_controller".to_string(),
+            name: "Stock controller".to_string(),
+            description: None,
+            is_active: true,
+            permissions: vec![permission(PermissionType::StockLineQuery)],
+        },
+    )
+    .unwrap();
+    remove_user_role_on_central(&context, "role_user_store_b").unwrap();
+    assert_eq!(
+        resolve_user_permissions(&connection, &role_user().id, Some(&mock_store_b().id)),
+        Ok(vec![])
+    );
+}
+
+#[actix_rt::test]
+async fn role_validation() {
+    let (_, _, connection_manager, _) = setup_all_with_data(
+        "role_validation",
+        MockDataInserts::none().names().stores(),
+        MockData {
+            user_accounts: vec![role_user()],
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider
+        .context(mock_store_a().id, "".to_string())
+        .unwrap();
+
+    // Not central
+    assert_eq!(
+        service_provider.role_service.upsert_role(
+            &context,
+            UpsertRole {
+                id: "role".to_string(),
+                name: "Role".to_string(),
+                ..Default::default()
+            }
+        ),
+        Err(UpsertRoleError::NotACentralServer)
+    );
+
+    assert_eq!(
+        upsert_role_on_central(
+            &context,
+            UpsertRole {
+                id: "role".to_string(),
+                name: " ".to_string(),
+                ..Default::default()
+            }
+        ),
+        Err(UpsertRoleError::RoleNameCannotBeEmpty)
+    );
+    upsert_role_on_central(
+        &context,
+        UpsertRole {
+            id: "role".to_string(),
+            name: "Role".to_string(),
+            is_active: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        upsert_role_on_central(
+            &context,
+            UpsertRole {
+                id: "other_role".to_string(),
+                name: "Role".to_string(),
+                ..Default::default()
+            }
+        ),
+        Err(UpsertRoleError::RoleNameAlreadyExists)
+    );
+
+    let assignment = AssignUserRole {
+        id: "assignment".to_string(),
+        user_id: role_user().id,
+        role_id: "role".to_string(),
+        store_id: Some(mock_store_a().id),
+    };
+    assert_eq!(
+        assign_user_role_on_central(
+            &context,
+            AssignUserRole {
+                user_id: "invalid".to_string(),
+                ..assignment.clone()
+            }
+        ),
+        Err(AssignUserRoleError::UserDoesNotExist)
+    );
+    assert_eq!(
+        assign_user_role_on_central(
+            &context,
+            AssignUserRole {
+                role_id: "invalid".to_string(),
+                ..assignment.clone()
+            }
+        ),
+        Err(AssignUserRoleError::RoleDoesNotExist)
+    );
+    assert_eq!(
+        assign_user_role_on_central(
+            &context,
+            AssignUserRole {
+                store_id: Some("invalid".to_string()),
+                ..assignment.clone()
+            }
+        ),
+        Err(AssignUserRoleError::StoreDoesNotExist)
+    );
+    assign_user_role_on_central(&context, assignment.clone()).unwrap();
+    assert_eq!(
+        assign_user_role_on_central(
+            &context,
+            AssignUserRole {
+                id: "duplicate".to_string(),
+                ..assignment
+            }
+        ),
+        Err(AssignUserRoleError::RoleAlreadyAssigned)
+    );
+
+    assert_eq!(
+        remove_user_role_on_central(&context, "invalid"),
+        Err(AssignUserRoleError::UserRoleDoesNotExist)
+    );
+}
+
+#[actix_rt::test]
+async fn role_store_access_allows_login() {
+    let password = "Password1";
+    let (_, connection, connection_manager, _) = setup_all_with_data(
+        "role_store_access_allows_login",
+        MockDataInserts::none().names().stores(),
+        MockData {
+            // No user_store_join rows, store access only comes from the role
+            user_accounts: vec![UserAccountRow {
+                hashed_password: UserAccountService::hash_password(password).unwrap(),
+                ..role_user()
+            }],
+            ..Default::default()
+        },
+    )
+    .await;
+    UserAuthStateRowRepository::new(&connection)
+        .upsert_one(&UserAuthStateRow {
+            is_locally_managed: true,
+            password_changed_datetime: Some(Utc::now().naive_utc()),
+            ..UserAuthStateRow::new(&role_user().id)
+        })
+        .unwrap();
+    KeyValueStoreRepository::new(&connection)
+        .set_i32(KeyType::SettingsSyncSiteId, Some(mock_store_a().site_id))
+        .unwrap();
+
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider
+        .context(mock_store_a().id, "".to_string())
+        .unwrap();
+    let auth_data = AuthData {
+        auth_token_secret: "secret".to_string(),
+        token_bucket: Arc::new(RwLock::new(TokenBucket::new())),
+        no_ssl: true,
+        debug_no_access_control: false,
+    };
+    let login = || {
+        LoginService::login(
+            &service_provider,
+            &auth_data,
+            LoginInput {
+                username: role_user().username,
+                password: password.to_string(),
+                // Locally managed users never reach central
+                central_server_url: "http://localhost:0".to_string(),
+                totp_code: None,
+            },
+            0,
+        )
+    };
+
+    assert_matches!(
+        login().await,
+        Err(LoginError::LoginFailure(LoginFailure::NoSiteAccess))
+    );
+
+    upsert_role_on_central(
+        &context,
+        UpsertRole {
+            id: "store_user".to_string(),
+            name: "Store user".to_string(),
+            description: None,
+            is_active: true,
+            permissions: vec![permission(PermissionType::StoreAccess)],
+        },
+    )
+    .unwrap();
+    assign_user_role_on_central(
+        &context,
+        AssignUserRole {
+            id: "role_user_store_a".to_string(),
+            user_id: role_user().id,
+            role_id: "store_user".to_string(),
+            store_id: Some(mock_store_a().id),
+        },
+    )
+    .unwrap();
+
+    assert!(login().await.is_ok());
+    let user = UserAccountService::new(&connection)
+        .find_user_active_on_this_site(&role_user().id)
+        .unwrap()
+        .unwrap();
+    let store_ids: Vec<String> = user.stores.into_iter().map(|s| s.store_row.id).collect();
+    assert_eq!(store_ids, vec![mock_store_a().id]);
+}
@@ -0,0 +1,126 @@
+use repository::{
+    PermissionType, RepositoryError, RolePermissionRow, RolePermissionRowRepository, RoleRow,
+    RoleRowRepository, StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::{service_provider::ServiceContext, sync::CentralServerConfig};
+
+use super::Role;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RolePermissionInput {
+    pub permission: PermissionType,
+    pub context_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UpsertRole {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_active: bool,
+    /// Replaces all permissions of the role
+    pub permissions: Vec<RolePermissionInput>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertRoleError {
+    NotACentralServer,
+    RoleNameCannotBeEmpty,
+    RoleNameAlreadyExists,
+    DatabaseError(RepositoryError),
+}
+
+/// Roles are defined on the central server and synced to all sites
+pub fn upsert_role(ctx: &ServiceContext, input: UpsertRole) -> Result<Role, UpsertRoleError> {
+    if !CentralServerConfig::is_central_server() {
+        return Err(UpsertRoleError::NotACentralServer);
+    }
+
+    upsert_role_on_central(ctx, input)
+}
+
+pub(crate) fn upsert_role_on_central(
+    ctx: &ServiceContext,
+    input: UpsertRole,
+) -> Result<Role, UpsertRoleError> {
+    let role = ctx
+        .connection
+        .transaction_sync(|connection| {
+            validate(connection, &input)?;
+            let (role_row, permissions) = generate(input);
+
+            RoleRowRepository::new(connection).upsert_one(&role_row)?;
+            let permission_repo = RolePermissionRowRepository::new(connection);
+            for existing in permission_repo.find_many_by_role_ids(&[role_row.id.clone()])? {
+                permission_repo.delete(&existing.id)?;
+            }
+            for permission in permissions.iter() {
+                permission_repo.upsert_one(permission)?;
+            }
+
+            Ok(Role {
+                role_row,
+                permissions,
+            })
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(role)
+}
+
+fn validate(connection: &StorageConnection, input: &UpsertRole) -> Result<(), UpsertRoleError> {
+    if input.name.trim().is_empty() {
+        return Err(UpsertRoleError::RoleNameCannotBeEmpty);
+    }
+
+    if let Some(existing) = RoleRowRepository::new(connection).find_one_by_name(&input.name)? {
+        if existing.id != input.id {
+            return Err(UpsertRoleError::RoleNameAlreadyExists);
+        }
+    }
+
+    Ok(())
+}
+
+fn generate(
+    UpsertRole {
+        id,
+        name,
+        description,
+        is_active,
+        permissions,
+    }: UpsertRole,
+) -> (RoleRow, Vec<RolePermissionRow>) {
+    let permissions = permissions
+        .into_iter()
+        .map(
+            |RolePermissionInput {
+                 permission,
+                 context_id,
+             }| RolePermissionRow {
+                id: uuid(),
+                role_id: id.clone(),
+                permission,
+                context_id,
+            },
+        )
+        .collect();
+
+    (
+        RoleRow {
+            id,
+            name: name.trim().to_string(),
+            description,
+            is_active,
+        },
+        permissions,
+    )
+}
+
+impl From<RepositoryError> for UpsertRoleError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertRoleError::DatabaseError(error)
+    }
+}
@@ -1,6 +1,8 @@
 use crate::{
+    api_token::{ApiTokenService, ApiTokenServiceTrait},
     app_data::{AppDataService, AppDataServiceTrait},
     asset::AssetServiceTrait,
+    audit_trail::{AuditTrailService, AuditTrailServiceTrait},
     auth::{AuthService, AuthServiceTrait},
     barcode::{BarcodeService, BarcodeServiceTrait},
     catalogue::{AssetCatalogueServiceTrait, CatalogueService},
@@ -20,16 +22,21 @@ use crate::{
         document_service::{DocumentService, DocumentServiceTrait},
         form_schema_service::{FormSchemaService, FormSchemaServiceTrait},
     },
+    expiry_risk::{ExpiryRiskService, ExpiryRiskServiceTrait},
+    goods_received::{GoodsReceivedService, GoodsReceivedServiceTrait},
+    insurance::{InsuranceService, InsuranceServiceTrait},
     invoice::{InvoiceService, InvoiceServiceTrait},
     invoice_line::{InvoiceLineService, InvoiceLineServiceTrait},
     item::ItemServiceTrait,
     item_stats::{ItemStatsService, ItemStatsServiceTrait},
     label_printer_settings_service::LabelPrinterSettingsServiceTrait,
+    local_auth::{LocalAuthService, LocalAuthServiceTrait},
     localisations::Localisations,
     location::{LocationService, LocationServiceTrait},
     log_service::{LogService, LogServiceTrait},
     master_list::{MasterListService, MasterListServiceTrait},
     name::{NameService, NameServiceTrait},
+    period_lock::{PeriodLockService, PeriodLockServiceTrait},
     plugin_data::{PluginDataService, PluginDataServiceTrait},
     pricing::{PricingService, PricingServiceTrait},
     processors::ProcessorsTrigger,
@@ -41,6 +48,7 @@ use crate::{
         program_enrolment::{ProgramEnrolmentService, ProgramEnrolmentServiceTrait},
         program_event::{ProgramEventService, ProgramEventServiceTrait},
     },
+    recall::{RecallService, RecallServiceTrait},
     repack::{RepackService, RepackServiceTrait},
     report::report_service::{ReportService, ReportServiceTrait},
     requisition::{
@@ -50,10 +58,12 @@ use crate::{
     },
     requisition_line::{RequisitionLineService, RequisitionLineServiceTrait},
     rnr_form::{RnRFormService, RnRFormServiceTrait},
+    role::{RoleService, RoleServiceTrait},
     sensor::{SensorService, SensorServiceTrait},
     settings_service::{SettingsService, SettingsServiceTrait},
     standard_reports::StandardReports,
     stock_line::{StockLineService, StockLineServiceTrait},
+    stock_valuation::{StockValuationService, StockValuationServiceTrait},
     stocktake::{StocktakeService, StocktakeServiceTrait},
     stocktake_line::{StocktakeLineService, StocktakeLineServiceTrait},
     store::{get_store, get_stores},
@@ -63,20 +73,26 @@ use crate::{
         synchroniser_driver::{SiteIsInitialisedTrigger, SyncTrigger},
     },
     temperature_excursion::{TemperatureExcursionService, TemperatureExcursionServiceTrait},
+    user_session::{UserSessionService, UserSessionServiceTrait},
     vaccination::{VaccinationService, VaccinationServiceTrait},
     vaccine_course::VaccineCourseServiceTrait,
+    warehouse::{WarehouseService, WarehouseServiceTrait},
     ListError, ListResult,
 };
 use repository::{
-    PaginationOption, RepositoryError, StorageConnection, StorageConnectionManager, Store,
-    StoreFilter, StoreSort,
+    AuditUser, PaginationOption, RepositoryError, StorageConnection, StorageConnectionManager,
+    Store, StoreFilter, StoreSort,
 };
 
 pub struct ServiceProvider {
     pub connection_manager: StorageConnectionManager,
     pub validation_service: Box<dyn AuthServiceTrait>,
+    pub user_session_service: Box<dyn UserSessionServiceTrait>,
+    pub api_token_service: Box<dyn ApiTokenServiceTrait>,
+    pub local_auth_service: Box<dyn LocalAuthServiceTrait>,
 
     pub location_service: Box<dyn LocationServiceTrait>,
+    pub warehouse_service: Box<dyn WarehouseServiceTrait>,
 
     // Cold chain
     pub sensor_service: Box<dyn SensorServiceTrait>,
@@ -100,10 +116,17 @@ pub struct ServiceProvider {
     pub item_service: Box<dyn ItemServiceTrait>,
     pub item_count_service: Box<dyn ItemCountServiceTrait>,
     pub requisition_count_service: Box<dyn RequisitionCountServiceTrait>,
+    pub expiry_risk_service: Box<dyn ExpiryRiskServiceTrait>,
+    pub recall_service: Box<dyn RecallServiceTrait>,
+    pub role_service: Box<dyn RoleServiceTrait>,
+    pub audit_trail_service: Box<dyn AuditTrailServiceTrait>,
+    pub period_lock_service: Box<dyn PeriodLockServiceTrait>,
+    pub goods_received_service: Box<dyn GoodsReceivedServiceTrait>,
     // Stock stats
     pub item_stats_service: Box<dyn ItemStatsServiceTrait>,
     // Stock
     pub stock_line_service: Box<dyn StockLineServiceTrait>,
+    pub stock_valuation_service: Box<dyn StockValuationServiceTrait>,
     pub repack_service: Box<dyn RepackServiceTrait>,
     // Reports
     pub report_service: Box<dyn ReportServiceTrait>,
@@ -154,6 +177,8 @@ pub struct ServiceProvider {
     // Programs
     pub program_service: Box<dyn ProgramServiceTrait>,
     pub pricing_service: Box<dyn PricingServiceTrait>,
+    // Insurance
+    pub insurance_service: Box<dyn InsuranceServiceTrait>,
     // Translations
     pub translations_service: Box<Localisations>,
     // Standard Reports
@@ -189,13 +214,23 @@ impl ServiceProvider {
         ServiceProvider {
             connection_manager: connection_manager.clone(),
             validation_service: Box::new(AuthService::new()),
+            user_session_service: Box::new(UserSessionService {}),
+            api_token_service: Box::new(ApiTokenService {}),
+            local_auth_service: Box::new(LocalAuthService {}),
             location_service: Box::new(LocationService {}),
+            warehouse_service: Box::new(WarehouseService {}),
             sensor_service: Box::new(SensorService {}),
             cold_chain_service: Box::new(ColdChainService {}),
             master_list_service: Box::new(MasterListService {}),
             invoice_line_service: Box::new(InvoiceLineService {}),
             invoice_count_service: Box::new(InvoiceCountService {}),
             requisition_count_service: Box::new(RequisitionCountService {}),
+            expiry_risk_service: Box::new(ExpiryRiskService {}),
+            recall_service: Box::new(RecallService {}),
+            role_service: Box::new(RoleService {}),
+            audit_trail_service: Box::new(AuditTrailService {}),
+            period_lock_service: Box::new(PeriodLockService {}),
+            goods_received_service: Box::new(GoodsReceivedService {}),
             invoice_service: Box::new(InvoiceService {}),
             stock_expiry_count_service: Box::new(StockExpiryServiceCount {}),
             stocktake_service: Box::new(StocktakeService {}),
@@ -226,6 +261,7 @@ impl ServiceProvider {
             site_is_initialised_trigger,
             display_settings_service: Box::new(DisplaySettingsService {}),
             stock_line_service: Box::new(StockLineService {}),
+            stock_valuation_service: Box::new(StockValuationService {}),
             item_count_service: Box::new(ItemServiceCount {}),
             barcode_service: Box::new(BarcodeService {}),
             repack_service: Box::new(RepackService {}),
@@ -243,6 +279,7 @@ impl ServiceProvider {
             vaccine_course_service: Box::new(crate::vaccine_course::VaccineCourseService {}),
             program_service: Box::new(crate::program::ProgramService {}),
             pricing_service: Box::new(PricingService {}),
+            insurance_service: Box::new(InsuranceService {}),
             rnr_form_service: Box::new(RnRFormService {}),
             vaccination_service: Box::new(VaccinationService {}),
             translations_service: Box::new(Localisations::new()),
@@ -253,7 +290,7 @@ impl ServiceProvider {
     /// Creates a new service context with a new DB connection
     pub fn basic_context(&self) -> Result<ServiceContext, RepositoryError> {
         Ok(ServiceContext {
-            connection: self.connection()?,
+            connection: self.audited_connection("")?,
             processors_trigger: self.processors_trigger.clone(),
             user_id: "".to_string(),
             store_id: "".to_string(),
@@ -266,13 +303,23 @@ impl ServiceProvider {
         user_id: String,
     ) -> Result<ServiceContext, RepositoryError> {
         Ok(ServiceContext {
-            connection: self.connection()?,
+            connection: self.audited_connection(&user_id)?,
             processors_trigger: self.processors_trigger.clone(),
             user_id,
             store_id,
         })
     }
 
+    /// Connection on which changes to audited records are attributed to the user (empty for
+    /// changes made by the system) in the audit trail
+    fn audited_connection(&self, user_id: &str) -> Result<StorageConnection, RepositoryError> {
+        let connection = self.connection()?;
+        connection.set_audit_user(Some(AuditUser {
+            user_id: (!user_id.is_empty()).then(|| user_id.to_string()),
+        }));
+        Ok(connection)
+    }
+
     /// Establishes a new DB connection
     pub fn connection(&self) -> Result<StorageConnection, RepositoryError> {
         self.connection_manager.connection()
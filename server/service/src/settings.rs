@@ -2,7 +2,7 @@ use std::fmt::{Display, Formatter, Result};
 
 use repository::database_settings::DatabaseSettings;
 
-use crate::sync::settings::SyncSettings;
+use crate::{identity_provider::IdentityProviderSettings, sync::settings::SyncSettings};
 
 #[derive(serde::Deserialize, Clone)]
 pub struct Settings {
@@ -11,6 +11,8 @@ pub struct Settings {
     pub sync: Option<SyncSettings>,
     pub logging: Option<LoggingSettings>,
     pub backup: Option<BackupSettings>,
+    /// Authenticate users against OpenID Connect or LDAP instead of the central server
+    pub identity_provider: Option<IdentityProviderSettings>,
 }
 
 #[derive(serde::Deserialize, Clone)]
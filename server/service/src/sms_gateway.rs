@@ -0,0 +1,67 @@
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SmsGatewayError {
+    InvalidPhoneNumber(String),
+    SendFailed(String),
+}
+
+/// Sends SMS messages, implemented per SMS provider
+pub trait SmsGateway: Send + Sync {
+    fn send_sms(&self, phone_number: &str, message: &str) -> Result<(), SmsGatewayError>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentSms {
+    pub phone_number: String,
+    pub message: String,
+}
+
+/// Gateway that records messages instead of sending them, for tests and local development
+#[derive(Default)]
+pub struct StubSmsGateway {
+    sent: Mutex<Vec<SentSms>>,
+    failing_phone_numbers: Vec<String>,
+}
+
+impl StubSmsGateway {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sending to any of these phone numbers will fail
+    pub fn failing_for(phone_numbers: Vec<String>) -> Self {
+        StubSmsGateway {
+            sent: Mutex::new(Vec::new()),
+            failing_phone_numbers: phone_numbers,
+        }
+    }
+
+    pub fn sent_messages(&self) -> Vec<SentSms> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+impl SmsGateway for StubSmsGateway {
+    fn send_sms(&self, phone_number: &str, message: &str) -> Result<(), SmsGatewayError> {
+        if phone_number.trim().is_empty() {
+            return Err(SmsGatewayError::InvalidPhoneNumber(phone_number.to_string()));
+        }
+        if self
+            .failing_phone_numbers
+            .iter()
+            .any(|failing| failing == phone_number)
+        {
+            return Err(SmsGatewayError::SendFailed(format!(
+                "Stub gateway configured to fail for {}",
+                phone_number
+            )));
+        }
+
+        self.sent.lock().unwrap().push(SentSms {
+            phone_number: phone_number.to_string(),
+            message: message.to_string(),
+        });
+        Ok(())
+    }
+}
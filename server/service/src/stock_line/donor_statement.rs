@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDateTime;
+use repository::{
+    DatetimeFilter, EqualFilter, RepositoryError, StockMovementFilter, StockMovementRepository,
+};
+
+use crate::service_provider::ServiceContext;
+
+/// Stock movements of a donor and item for a period, all quantities are in units
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct DonorStockStatementLine {
+    pub donor_id: String,
+    pub item_id: String,
+    /// Stock on hand at the start of the period
+    pub opening_quantity: f64,
+    pub received_quantity: f64,
+    pub issued_quantity: f64,
+    /// Stock on hand at the end of the period
+    pub closing_quantity: f64,
+}
+
+/// Statement line for each donor and item with stock movements in the store up to the end of
+/// the period. Stock that is not owned by a donor is not included.
+pub fn get_donor_stock_statement(
+    ctx: &ServiceContext,
+    store_id: &str,
+    donor_id: Option<String>,
+    from_datetime: NaiveDateTime,
+    to_datetime: NaiveDateTime,
+) -> Result<Vec<DonorStockStatementLine>, RepositoryError> {
+    let filter = StockMovementFilter::new()
+        .store_id(EqualFilter::equal_to(store_id))
+        .datetime(DatetimeFilter::before_or_equal_to(to_datetime))
+        .donor_id(match &donor_id {
+            Some(donor_id) => EqualFilter::equal_to(donor_id),
+            None => EqualFilter::is_null(false),
+        });
+
+    let movements = StockMovementRepository::new(&ctx.connection).query(Some(filter))?;
+
+    // Keyed by donor and item to keep the statement sorted
+    let mut lines: BTreeMap<(String, String), DonorStockStatementLine> = BTreeMap::new();
+    for movement in movements {
+        let Some(donor_id) = movement.donor_id else {
+            continue;
+        };
+
+        let line = lines
+            .entry((donor_id.clone(), movement.item_id.clone()))
+            .or_insert_with(|| DonorStockStatementLine {
+                donor_id,
+                item_id: movement.item_id,
+                ..Default::default()
+            });
+
+        if movement.datetime < from_datetime {
+            line.opening_quantity += movement.quantity;
+        } else if movement.quantity > 0.0 {
+            line.received_quantity += movement.quantity;
+        } else {
+            line.issued_quantity -= movement.quantity;
+        }
+    }
+
+    Ok(lines
+        .into_values()
+        .map(|line| DonorStockStatementLine {
+            closing_quantity: line.opening_quantity + line.received_quantity - line.issued_quantity,
+            ..line
+        })
+        .collect())
+}
@@ -3,9 +3,11 @@ use self::query::{get_stock_line, get_stock_lines};
 use super::{ListError, ListResult};
 use crate::{service_provider::ServiceContext, SingleRecordError};
 use chrono::NaiveDateTime;
+use donor_statement::{get_donor_stock_statement, DonorStockStatementLine};
 use historical_stock::get_historical_stock_lines;
-use repository::{PaginationOption, StockLine, StockLineFilter, StockLineSort};
+use repository::{PaginationOption, RepositoryError, StockLine, StockLineFilter, StockLineSort};
 
+pub mod donor_statement;
 pub mod historical_stock;
 pub mod query;
 pub mod update;
@@ -50,6 +52,17 @@ pub trait StockLineServiceTrait: Sync + Send {
             ctx, &store_id, &item_id, &datetime,
         )?)
     }
+
+    fn get_donor_stock_statement(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        donor_id: Option<String>,
+        from_datetime: NaiveDateTime,
+        to_datetime: NaiveDateTime,
+    ) -> Result<Vec<DonorStockStatementLine>, RepositoryError> {
+        get_donor_stock_statement(ctx, store_id, donor_id, from_datetime, to_datetime)
+    }
 }
 
 pub struct StockLineService {}
@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod donor_statement {
+    use chrono::{NaiveDate, NaiveDateTime};
+    use repository::mock::{mock_item_a, mock_name_a, mock_name_b, mock_store_a};
+    use repository::mock::{MockData, MockDataInserts};
+    use repository::{InvoiceLineRow, InvoiceLineType, InvoiceRow, InvoiceStatus, InvoiceType};
+
+    use crate::stock_line::donor_statement::DonorStockStatementLine;
+    use crate::test_helpers::{setup_all_with_data_and_service_provider, ServiceTestContext};
+
+    fn datetime(month: u32, day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, month, day)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    fn movement(
+        id: &str,
+        datetime: NaiveDateTime,
+        number_of_packs: f64,
+        donor_link_id: Option<String>,
+    ) -> MockData {
+        let inbound = number_of_packs > 0.0;
+
+        MockData {
+            invoices: vec![InvoiceRow {
+                id: id.to_string(),
+                name_link_id: mock_name_a().id,
+                store_id: mock_store_a().id,
+                invoice_number: 1,
+                r#type: match inbound {
+                    true => InvoiceType::InboundShipment,
+                    false => InvoiceType::OutboundShipment,
+                },
+                status: match inbound {
+                    true => InvoiceStatus::Delivered,
+                    false => InvoiceStatus::Picked,
+                },
+                delivered_datetime: inbound.then_some(datetime),
+                picked_datetime: (!inbound).then_some(datetime),
+                ..Default::default()
+            }],
+            invoice_lines: vec![InvoiceLineRow {
+                id: format!("{id}_line"),
+                invoice_id: id.to_string(),
+                item_link_id: mock_item_a().id,
+                r#type: match inbound {
+                    true => InvoiceLineType::StockIn,
+                    false => InvoiceLineType::StockOut,
+                },
+                pack_size: 10.0,
+                number_of_packs: number_of_packs.abs(),
+                donor_link_id,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[actix_rt::test]
+    async fn donor_stock_statement() {
+        let donor_a = Some(mock_name_a().id);
+        let donor_b = Some(mock_name_b().id);
+
+        let ServiceTestContext {
+            service_provider,
+            service_context,
+            ..
+        } = setup_all_with_data_and_service_provider(
+            "donor_stock_statement",
+            MockDataInserts::all(),
+            movement("received_before", datetime(1, 10), 10.0, donor_a.clone())
+                .join(movement("issued", datetime(2, 5), -3.0, donor_a.clone()))
+                .join(movement("received", datetime(2, 10), 5.0, donor_b.clone()))
+                .join(movement("no_donor", datetime(2, 12), 1.0, None))
+                .join(movement(
+                    "issued_after",
+                    datetime(3, 5),
+                    -2.0,
+                    donor_a.clone(),
+                )),
+        )
+        .await;
+
+        let service = &service_provider.stock_line_service;
+        let from = datetime(2, 1);
+        let to = datetime(2, 28);
+
+        let statement = service
+            .get_donor_stock_statement(&service_context, &mock_store_a().id, None, from, to)
+            .unwrap();
+        assert_eq!(
+            statement,
+            vec![
+                DonorStockStatementLine {
+                    donor_id: mock_name_a().id,
+                    item_id: mock_item_a().id,
+                    opening_quantity: 100.0,
+                    received_quantity: 0.0,
+                    issued_quantity: 30.0,
+                    closing_quantity: 70.0,
+                },
+                DonorStockStatementLine {
+                    donor_id: mock_name_b().id,
+                    item_id: mock_item_a().id,
+                    opening_quantity: 0.0,
+                    received_quantity: 50.0,
+                    issued_quantity: 0.0,
+                    closing_quantity: 50.0,
+                },
+            ]
+        );
+
+        // Single donor
+        let statement = service
+            .get_donor_stock_statement(
+                &service_context,
+                &mock_store_a().id,
+                donor_b.clone(),
+                from,
+                to,
+            )
+            .unwrap();
+        assert_eq!(statement.len(), 1);
+        assert_eq!(statement[0].donor_id, mock_name_b().id);
+    }
+}
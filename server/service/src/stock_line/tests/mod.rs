@@ -1,3 +1,4 @@
+mod donor_statement;
 mod historical_stock;
 mod query;
 mod update;
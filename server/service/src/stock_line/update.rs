@@ -29,6 +29,8 @@ pub struct UpdateStockLine {
     pub batch: Option<String>,
     pub barcode: Option<String>,
     pub item_variant_id: Option<NullableUpdate<String>>,
+    /// Donor or funding program that owns the stock
+    pub donor_id: Option<NullableUpdate<String>>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -133,6 +135,7 @@ fn generate(
         on_hold,
         barcode,
         item_variant_id,
+        donor_id,
     }: UpdateStockLine,
 ) -> Result<GenerateResult, UpdateStockLineError> {
     let mut existing = existing_line.stock_line_row;
@@ -183,6 +186,7 @@ fn generate(
     existing.item_variant_id = item_variant_id
         .map(|v| v.value)
         .unwrap_or(existing.item_variant_id);
+    existing.donor_link_id = donor_id.map(|v| v.value).unwrap_or(existing.donor_link_id);
 
     Ok(GenerateResult {
         new_stock_line: existing,
@@ -0,0 +1,250 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use chrono::NaiveDateTime;
+use repository::{
+    ledger::{LedgerFilter, LedgerRepository, LedgerRow},
+    DatetimeFilter, EqualFilter, InvoiceType, Pagination, RepositoryError,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StockValuationMethod {
+    /// Issues and stock on hand are valued at the running average cost of receipts
+    WeightedAverageCost,
+    /// Issues consume the oldest receipts first, stock on hand is valued at the latest receipts
+    Fifo,
+    /// Issues and stock on hand are valued at the cost of the latest inbound shipment
+    LastPurchasePrice,
+}
+
+/// Value of an item in a store for a period, quantities are in units and values use the cost
+/// price of the stock movements
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct StockValuationLine {
+    pub item_id: String,
+    /// Stock on hand at the start of the period
+    pub opening_quantity: f64,
+    pub opening_value: f64,
+    /// Quantity issued by outbound shipments and prescriptions in the period
+    pub issued_quantity: f64,
+    pub cost_of_goods_issued: f64,
+    /// Stock on hand at the end of the period
+    pub closing_quantity: f64,
+    pub closing_value: f64,
+}
+
+pub trait StockValuationServiceTrait: Sync + Send {
+    /// Valuation line for each item with stock movements in the store up to the end of the
+    /// period. Without `from_datetime` the period starts at the first stock movement.
+    fn get_stock_valuation(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        method: StockValuationMethod,
+        item_id: Option<String>,
+        from_datetime: Option<NaiveDateTime>,
+        to_datetime: NaiveDateTime,
+    ) -> Result<Vec<StockValuationLine>, RepositoryError> {
+        get_stock_valuation(ctx, store_id, method, item_id, from_datetime, to_datetime)
+    }
+}
+
+pub struct StockValuationService {}
+impl StockValuationServiceTrait for StockValuationService {}
+
+pub fn get_stock_valuation(
+    ctx: &ServiceContext,
+    store_id: &str,
+    method: StockValuationMethod,
+    item_id: Option<String>,
+    from_datetime: Option<NaiveDateTime>,
+    to_datetime: NaiveDateTime,
+) -> Result<Vec<StockValuationLine>, RepositoryError> {
+    let mut filter = LedgerFilter::new()
+        .store_id(EqualFilter::equal_to(store_id))
+        .datetime(DatetimeFilter::before_or_equal_to(to_datetime));
+    if let Some(item_id) = &item_id {
+        filter = filter.item_id(EqualFilter::equal_to(item_id));
+    }
+
+    let mut movements =
+        LedgerRepository::new(&ctx.connection).query(Pagination::all(), Some(filter), None)?;
+    // Receipts are applied before issues at the same datetime so they can be consumed
+    movements.sort_by(|a, b| {
+        a.datetime
+            .cmp(&b.datetime)
+            .then(b.quantity.total_cmp(&a.quantity))
+    });
+
+    // Keyed by item to keep the valuation sorted
+    let mut items: BTreeMap<String, (ItemValuation, StockValuationLine)> = BTreeMap::new();
+
+    let (before, during): (Vec<LedgerRow>, Vec<LedgerRow>) = movements
+        .into_iter()
+        .partition(|movement| from_datetime.is_some_and(|from| movement.datetime < from));
+
+    for movement in &before {
+        let (valuation, _) = items
+            .entry(movement.item_id.clone())
+            .or_insert_with(|| new_item(method, &movement.item_id));
+        valuation.apply(movement);
+    }
+
+    for (valuation, line) in items.values_mut() {
+        line.opening_quantity = valuation.quantity;
+        line.opening_value = valuation.value();
+    }
+
+    for movement in &during {
+        let (valuation, line) = items
+            .entry(movement.item_id.clone())
+            .or_insert_with(|| new_item(method, &movement.item_id));
+        let cost = valuation.apply(movement);
+        if is_issue(movement) {
+            line.issued_quantity -= movement.quantity;
+            line.cost_of_goods_issued += cost;
+        }
+    }
+
+    Ok(items
+        .into_values()
+        .map(|(valuation, line)| StockValuationLine {
+            closing_quantity: valuation.quantity,
+            closing_value: valuation.value(),
+            ..line
+        })
+        .collect())
+}
+
+fn new_item(method: StockValuationMethod, item_id: &str) -> (ItemValuation, StockValuationLine) {
+    (
+        ItemValuation::new(method),
+        StockValuationLine {
+            item_id: item_id.to_string(),
+            ..Default::default()
+        },
+    )
+}
+
+/// Stock movements counted as cost of goods issued, other reductions (e.g. inventory adjustments
+/// or supplier returns) reduce the stock value without being issued
+fn is_issue(movement: &LedgerRow) -> bool {
+    movement.quantity < 0.0
+        && matches!(
+            movement.invoice_type,
+            InvoiceType::OutboundShipment | InvoiceType::Prescription
+        )
+}
+
+/// Running valuation of an item as stock movements are applied in order
+#[derive(Clone, Debug)]
+struct ItemValuation {
+    method: StockValuationMethod,
+    quantity: f64,
+    /// Value of stock on hand for the weighted average cost method
+    average_value: f64,
+    /// Remaining quantity and unit cost of receipts, oldest first, for the FIFO method
+    layers: VecDeque<(f64, f64)>,
+    last_purchase_cost: Option<f64>,
+    last_receipt_cost: Option<f64>,
+}
+
+impl ItemValuation {
+    fn new(method: StockValuationMethod) -> Self {
+        ItemValuation {
+            method,
+            quantity: 0.0,
+            average_value: 0.0,
+            layers: VecDeque::new(),
+            last_purchase_cost: None,
+            last_receipt_cost: None,
+        }
+    }
+
+    /// Applies the movement and returns the cost of the stock it removed (zero for receipts)
+    fn apply(&mut self, movement: &LedgerRow) -> f64 {
+        if movement.quantity >= 0.0 {
+            self.receive(movement);
+            return 0.0;
+        }
+        self.issue(-movement.quantity)
+    }
+
+    fn receive(&mut self, movement: &LedgerRow) {
+        let unit_cost = movement.cost_price_per_unit();
+
+        self.quantity += movement.quantity;
+        self.average_value += movement.quantity * unit_cost;
+        self.layers.push_back((movement.quantity, unit_cost));
+        self.last_receipt_cost = Some(unit_cost);
+        if movement.invoice_type == InvoiceType::InboundShipment {
+            self.last_purchase_cost = Some(unit_cost);
+        }
+    }
+
+    fn issue(&mut self, quantity: f64) -> f64 {
+        let cost = match self.method {
+            StockValuationMethod::WeightedAverageCost => match self.quantity > 0.0 {
+                true => quantity * self.average_value / self.quantity,
+                false => quantity * self.last_receipt_cost.unwrap_or_default(),
+            },
+            StockValuationMethod::Fifo => self.consume_layers(quantity),
+            StockValuationMethod::LastPurchasePrice => quantity * self.last_purchase_price(),
+        };
+
+        self.quantity -= quantity;
+        self.average_value = match self.quantity > 0.0 {
+            true => (self.average_value - cost).max(0.0),
+            false => 0.0,
+        };
+        cost
+    }
+
+    /// Issuing more than was received (negative stock) is costed at the latest receipt
+    fn consume_layers(&mut self, quantity: f64) -> f64 {
+        let mut remaining = quantity;
+        let mut cost = 0.0;
+        while remaining > 0.0 {
+            let Some((layer_quantity, unit_cost)) = self.layers.front_mut() else {
+                cost += remaining * self.last_receipt_cost.unwrap_or_default();
+                break;
+            };
+
+            let consumed = remaining.min(*layer_quantity);
+            cost += consumed * *unit_cost;
+            remaining -= consumed;
+            *layer_quantity -= consumed;
+            if *layer_quantity <= 0.0 {
+                self.layers.pop_front();
+            }
+        }
+        cost
+    }
+
+    /// Falls back to the latest receipt cost when the item hasn't been purchased, e.g. stock
+    /// added by inventory adjustments
+    fn last_purchase_price(&self) -> f64 {
+        self.last_purchase_cost
+            .or(self.last_receipt_cost)
+            .unwrap_or_default()
+    }
+
+    fn value(&self) -> f64 {
+        if self.quantity <= 0.0 {
+            return 0.0;
+        }
+        match self.method {
+            StockValuationMethod::WeightedAverageCost => self.average_value,
+            StockValuationMethod::Fifo => self
+                .layers
+                .iter()
+                .map(|(quantity, unit_cost)| quantity * unit_cost)
+                .sum(),
+            StockValuationMethod::LastPurchasePrice => self.quantity * self.last_purchase_price(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,180 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use repository::{
+    mock::{mock_name_a, mock_store_a, MockData, MockDataInserts},
+    InvoiceLineRow, InvoiceLineType, InvoiceRow, InvoiceStatus, InvoiceType, ItemRow,
+};
+
+use crate::{
+    stock_valuation::{StockValuationLine, StockValuationMethod},
+    test_helpers::{setup_all_with_data_and_service_provider, ServiceTestContext},
+};
+
+fn valuation_item() -> ItemRow {
+    ItemRow {
+        id: "valuation_item".to_string(),
+        name: "Valuation item".to_string(),
+        code: "valuation_item".to_string(),
+        ..Default::default()
+    }
+}
+
+fn datetime(month: u32, day: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, month, day)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap()
+}
+
+/// Movement of `number_of_packs` packs of 10 units, negative packs are issued by `r#type`
+fn movement(
+    id: &str,
+    r#type: InvoiceType,
+    datetime: NaiveDateTime,
+    number_of_packs: f64,
+    cost_price_per_pack: f64,
+) -> MockData {
+    let inbound = number_of_packs > 0.0;
+
+    MockData {
+        invoices: vec![InvoiceRow {
+            id: id.to_string(),
+            name_link_id: mock_name_a().id,
+            store_id: mock_store_a().id,
+            invoice_number: 1,
+            status: match r#type {
+                InvoiceType::InboundShipment => InvoiceStatus::Delivered,
+                InvoiceType::OutboundShipment => InvoiceStatus::Picked,
+                _ => InvoiceStatus::Verified,
+            },
+            delivered_datetime: (r#type == InvoiceType::InboundShipment).then_some(datetime),
+            picked_datetime: (r#type == InvoiceType::OutboundShipment).then_some(datetime),
+            verified_datetime: Some(datetime),
+            r#type,
+            ..Default::default()
+        }],
+        invoice_lines: vec![InvoiceLineRow {
+            id: format!("{id}_line"),
+            invoice_id: id.to_string(),
+            item_link_id: valuation_item().id,
+            r#type: match inbound {
+                true => InvoiceLineType::StockIn,
+                false => InvoiceLineType::StockOut,
+            },
+            pack_size: 10.0,
+            number_of_packs: number_of_packs.abs(),
+            cost_price_per_pack,
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+#[actix_rt::test]
+async fn stock_valuation_methods() {
+    let ServiceTestContext {
+        service_provider,
+        service_context,
+        ..
+    } = setup_all_with_data_and_service_provider(
+        "stock_valuation_methods",
+        MockDataInserts::all(),
+        MockData {
+            items: vec![valuation_item()],
+            ..Default::default()
+        }
+        .join(movement(
+            "received_jan",
+            InvoiceType::InboundShipment,
+            datetime(1, 10),
+            10.0,
+            20.0,
+        ))
+        .join(movement(
+            "received_feb",
+            InvoiceType::InboundShipment,
+            datetime(2, 1),
+            10.0,
+            40.0,
+        ))
+        .join(movement(
+            "issued",
+            InvoiceType::OutboundShipment,
+            datetime(2, 5),
+            -15.0,
+            0.0,
+        ))
+        .join(movement(
+            "reduced",
+            InvoiceType::InventoryReduction,
+            datetime(2, 12),
+            -1.0,
+            0.0,
+        )),
+    )
+    .await;
+
+    let service = &service_provider.stock_valuation_service;
+    let valuation = |method: StockValuationMethod, from: Option<NaiveDateTime>| {
+        service
+            .get_stock_valuation(
+                &service_context,
+                &mock_store_a().id,
+                method,
+                Some(valuation_item().id),
+                from,
+                datetime(2, 28),
+            )
+            .unwrap()
+    };
+    let line = |cost_of_goods_issued: f64, closing_value: f64| StockValuationLine {
+        item_id: valuation_item().id,
+        opening_quantity: 100.0,
+        opening_value: 200.0,
+        issued_quantity: 150.0,
+        cost_of_goods_issued,
+        closing_quantity: 40.0,
+        closing_value,
+    };
+
+    // Average cost of 3 per unit after the February receipt
+    assert_eq!(
+        valuation(
+            StockValuationMethod::WeightedAverageCost,
+            Some(datetime(1, 20))
+        ),
+        vec![line(450.0, 120.0)]
+    );
+    // Issue consumes all of January's receipt and half of February's
+    assert_eq!(
+        valuation(StockValuationMethod::Fifo, Some(datetime(1, 20))),
+        vec![line(400.0, 160.0)]
+    );
+    assert_eq!(
+        valuation(
+            StockValuationMethod::LastPurchasePrice,
+            Some(datetime(1, 20))
+        ),
+        vec![line(600.0, 160.0)]
+    );
+
+    // Valuation at a date, the inventory reduction isn't counted as issued
+    let at_date = service
+        .get_stock_valuation(
+            &service_context,
+            &mock_store_a().id,
+            StockValuationMethod::WeightedAverageCost,
+            Some(valuation_item().id),
+            None,
+            datetime(1, 31),
+        )
+        .unwrap();
+    assert_eq!(
+        at_date,
+        vec![StockValuationLine {
+            item_id: valuation_item().id,
+            closing_quantity: 100.0,
+            closing_value: 200.0,
+            ..Default::default()
+        }]
+    );
+}
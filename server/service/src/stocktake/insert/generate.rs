@@ -139,6 +139,7 @@ pub fn generate_lines_from_master_list(
                 counted_number_of_packs: None,
                 inventory_adjustment_reason_id: None,
                 item_variant_id: None,
+                donor_link_id: None,
             });
         } else {
             stock_lines.into_iter().for_each(|line| {
@@ -159,6 +160,7 @@ pub fn generate_lines_from_master_list(
                     available_number_of_packs: _,
                     barcode_id: _,
                     item_variant_id,
+                    donor_link_id,
                 } = line.stock_line_row;
 
                 result.push(StocktakeLineRow {
@@ -179,6 +181,7 @@ pub fn generate_lines_from_master_list(
                     counted_number_of_packs: None,
                     inventory_adjustment_reason_id: None,
                     item_variant_id,
+                    donor_link_id,
                 });
             });
         }
@@ -221,6 +224,7 @@ pub fn generate_lines_from_location(
                 available_number_of_packs: _,
                 barcode_id: _,
                 item_variant_id,
+                donor_link_id,
             } = line.stock_line_row;
 
             StocktakeLineRow {
@@ -241,6 +245,7 @@ pub fn generate_lines_from_location(
                 counted_number_of_packs: None,
                 inventory_adjustment_reason_id: None,
                 item_variant_id,
+                donor_link_id,
             }
         })
         .collect();
@@ -279,6 +284,7 @@ pub fn generate_lines_with_stock(
                 available_number_of_packs: _,
                 barcode_id: _,
                 item_variant_id,
+                donor_link_id,
             } = line.stock_line_row;
 
             StocktakeLineRow {
@@ -299,6 +305,7 @@ pub fn generate_lines_with_stock(
                 counted_number_of_packs: None,
                 inventory_adjustment_reason_id: None,
                 item_variant_id,
+                donor_link_id,
             }
         })
         .collect();
@@ -338,6 +345,7 @@ pub fn generate_lines_expiring_before(
                 available_number_of_packs: _,
                 barcode_id: _,
                 item_variant_id,
+                donor_link_id,
             } = line.stock_line_row;
 
             StocktakeLineRow {
@@ -358,6 +366,7 @@ pub fn generate_lines_expiring_before(
                 inventory_adjustment_reason_id: None,
                 item_name: line.item_row.name,
                 item_variant_id,
+                donor_link_id,
             }
         })
         .collect();
@@ -106,6 +106,8 @@ fn generate_stock_in_out_or_update(
     // If item_variant_id is null on the stocktake_line, we need to set the stock_line item_variant_id to null too.
     // Without this, we'd wouldn't be able to clear it...
     let item_variant_id = stocktake_line.line.item_variant_id.clone();
+    // Same for the donor, the stocktake line donor replaces the stock line donor
+    let donor_link_id = stocktake_line.line.donor_link_id.clone();
 
     log_stock_changes(ctx, stock_line_row.clone(), row.clone())?;
 
@@ -119,6 +121,7 @@ fn generate_stock_in_out_or_update(
             sell_price_per_pack,
             expiry_date,
             item_variant_id,
+            donor_link_id,
             ..stock_line_row
         }
         .to_owned();
@@ -157,6 +160,7 @@ fn generate_stock_in_out_or_update(
             stock_on_hold: stock_line_row.on_hold,
             note: stock_line_row.note,
             item_variant_id: stock_line_row.item_variant_id,
+            donor_id: donor_link_id,
             barcode: stock_line_row.barcode_id,
             // Default
             total_before_tax: None,
@@ -332,6 +336,7 @@ fn generate_new_stock_line(
         total_before_tax: None,
         tax_percentage: None,
         item_variant_id: stocktake_line.line.item_variant_id.clone(),
+        donor_id: stocktake_line.line.donor_link_id.clone(),
     });
 
     // If new stock line has a location, create location movement
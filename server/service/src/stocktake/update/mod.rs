@@ -17,6 +17,11 @@ use crate::{
         stock_in_line::{insert_stock_in_line, InsertStockInLineError},
         stock_out_line::{insert_stock_out_line, InsertStockOutLineError},
     },
+    period_lock::{check_period_lock, PeriodLockError},
+    plugin::server_plugin::{
+        hooks::{call_plugin_hook, PluginHookError, PluginVeto, StocktakeFinaliseHookInput},
+        PluginHook,
+    },
     service_provider::ServiceContext,
     stocktake::query::get_stocktake,
 };
@@ -63,6 +68,30 @@ pub enum UpdateStocktakeError {
     /// Holds list of affected stock lines
     SnapshotCountCurrentCountMismatch(Vec<StocktakeLine>),
     StockLinesReducedBelowZero(Vec<StockLine>),
+    /// A server plugin rejected the finalised stocktake
+    PluginVetoed(PluginVeto),
+    /// Holds the lock date of the closed period the stocktake would be finalised in
+    PeriodLocked(NaiveDate),
+}
+
+impl From<PeriodLockError> for UpdateStocktakeError {
+    fn from(error: PeriodLockError) -> Self {
+        match error {
+            PeriodLockError::PeriodLocked(lock_date) => {
+                UpdateStocktakeError::PeriodLocked(lock_date)
+            }
+            PeriodLockError::DatabaseError(error) => UpdateStocktakeError::DatabaseError(error),
+        }
+    }
+}
+
+impl From<PluginHookError> for UpdateStocktakeError {
+    fn from(error: PluginHookError) -> Self {
+        match error {
+            PluginHookError::Vetoed(veto) => UpdateStocktakeError::PluginVetoed(veto),
+            PluginHookError::DatabaseError(error) => UpdateStocktakeError::DatabaseError(error),
+        }
+    }
 }
 
 pub fn update_stocktake(
@@ -75,6 +104,17 @@ pub fn update_stocktake(
             let stocktake_id = input.id.clone();
             let (existing, stocktake_lines, status_changed) =
                 validate(connection, &ctx.store_id, &input)?;
+            if status_changed {
+                // Finalising adjusts stock now and reports the count on the stocktake date
+                let today = Utc::now().naive_utc().date();
+                let stocktake_date = input.stocktake_date.or(existing.stocktake_date);
+                check_period_lock(
+                    ctx,
+                    &ctx.store_id,
+                    &stocktake_id,
+                    stocktake_date.map_or(today, |date| date.min(today)),
+                )?;
+            }
             let result = generate(ctx, input, existing, stocktake_lines, status_changed)?;
 
             // write data to the DB
@@ -160,6 +200,20 @@ pub fn update_stocktake(
                     None,
                     None,
                 )?;
+
+                // Runs inside the transaction, a veto rolls back the finalisation
+                call_plugin_hook(
+                    ctx,
+                    PluginHook::AfterStocktakeFinalise,
+                    &stocktake_id,
+                    &StocktakeFinaliseHookInput {
+                        store_id: ctx.store_id.clone(),
+                        stocktake_id: stocktake_id.clone(),
+                        stocktake_number: result.stocktake.stocktake_number,
+                        inventory_addition_id: result.stocktake.inventory_addition_id.clone(),
+                        inventory_reduction_id: result.stocktake.inventory_reduction_id.clone(),
+                    },
+                )?;
             }
 
             // return the updated stocktake
@@ -22,6 +22,7 @@ pub fn generate(
         note,
         inventory_adjustment_reason_id,
         item_variant_id,
+        donor_id,
     }: InsertStocktakeLine,
 ) -> StocktakeLineRow {
     let snapshot_number_of_packs = if let Some(stock_line) = stock_line.clone() {
@@ -34,12 +35,18 @@ pub fn generate(
     // This should make it safe to assume that the item_variant_id is always present in the stocktake record if it was set on the previous stock_line
     let item_variant_id = match item_variant_id {
         Some(id) => Some(id),
-        None => match stock_line {
-            Some(stock_line) => stock_line.stock_line_row.item_variant_id,
+        None => match &stock_line {
+            Some(stock_line) => stock_line.stock_line_row.item_variant_id.clone(),
             None => None,
         },
     };
 
+    // Same for the donor of the stock line
+    let donor_link_id = match donor_id {
+        Some(id) => Some(id),
+        None => stock_line.and_then(|stock_line| stock_line.stock_line_row.donor_link_id),
+    };
+
     StocktakeLineRow {
         id,
         stocktake_id,
@@ -58,5 +65,6 @@ pub fn generate(
         note,
         inventory_adjustment_reason_id,
         item_variant_id,
+        donor_link_id,
     }
 }
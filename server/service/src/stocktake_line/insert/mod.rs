@@ -26,6 +26,7 @@ pub struct InsertStocktakeLine {
     pub note: Option<String>,
     pub inventory_adjustment_reason_id: Option<String>,
     pub item_variant_id: Option<String>,
+    pub donor_id: Option<String>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -18,6 +18,7 @@ pub fn generate(
         note,
         inventory_adjustment_reason_id,
         item_variant_id,
+        donor_id,
     }: UpdateStocktakeLine,
 ) -> Result<StocktakeLineRow, UpdateStocktakeLineError> {
     let existing_line = existing.line;
@@ -30,6 +31,10 @@ pub fn generate(
         None => existing_line.item_variant_id,
     };
 
+    let donor_link_id = donor_id
+        .map(|update| update.value)
+        .unwrap_or(existing_line.donor_link_id);
+
     Ok(StocktakeLineRow {
         id: existing_line.id,
         stocktake_id: existing_line.stocktake_id,
@@ -54,5 +59,6 @@ pub fn generate(
         inventory_adjustment_reason_id: inventory_adjustment_reason_id
             .or(existing_line.inventory_adjustment_reason_id),
         item_variant_id,
+        donor_link_id,
     })
 }
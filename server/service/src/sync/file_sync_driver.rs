@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use crate::plugin::plugin_version::{install_plugin_versions, InstalledPlugins};
 use crate::sync::is_initialised;
 use crate::{
     service_provider::ServiceProvider, settings::Settings, static_files::StaticFileService,
@@ -27,6 +28,7 @@ pub enum FileSyncMessage {
 pub struct FileSyncDriver {
     receiver: Receiver<FileSyncMessage>,
     static_file_service: Arc<StaticFileService>,
+    base_dir: Option<String>,
 }
 
 #[derive(Clone)]
@@ -52,6 +54,7 @@ impl FileSyncDriver {
             FileSyncDriver {
                 receiver,
                 static_file_service,
+                base_dir: settings.server.base_dir.clone(),
             },
         )
     }
@@ -132,7 +135,7 @@ impl FileSyncDriver {
         let synchroniser = FileSynchroniser::new(
             sync_v6_url,
             get_sync_settings(&service_provider),
-            service_provider,
+            service_provider.clone(),
             self.static_file_service.clone(),
         );
 
@@ -158,8 +161,49 @@ impl FileSyncDriver {
             log::info!("Found {} files to upload", files_to_upload);
         }
 
+        self.install_plugins(&synchroniser, &service_provider).await;
+
         files_to_upload
     }
+
+    /// Installs the active plugin versions synced from central, plugin bundles are downloaded
+    /// from central first if needed
+    async fn install_plugins(
+        &self,
+        synchroniser: &FileSynchroniser,
+        service_provider: &ServiceProvider,
+    ) {
+        let install = || -> anyhow::Result<InstalledPlugins> {
+            let ctx = service_provider.basic_context()?;
+            install_plugin_versions(&ctx.connection, &self.base_dir)
+        };
+
+        let missing_bundle_file_ids = match install() {
+            Ok(result) => {
+                log_installed_plugins(&result);
+                result.missing_bundle_file_ids
+            }
+            Err(error) => {
+                log::error!("Problem installing plugins {:#}", error);
+                return;
+            }
+        };
+        if missing_bundle_file_ids.is_empty() {
+            return;
+        }
+
+        for file_id in missing_bundle_file_ids {
+            log::info!("Downloading plugin bundle {}", file_id);
+            if let Err(error) = synchroniser.download_file_from_central(&file_id).await {
+                log::error!("Problem downloading plugin bundle {}", format_error(&error));
+            }
+        }
+
+        match install() {
+            Ok(result) => log_installed_plugins(&result),
+            Err(error) => log::error!("Problem installing plugins {:#}", error),
+        }
+    }
 }
 
 impl FileSyncTrigger {
@@ -188,6 +232,19 @@ impl FileSyncTrigger {
     }
 }
 
+fn log_installed_plugins(
+    InstalledPlugins {
+        installed, removed, ..
+    }: &InstalledPlugins,
+) {
+    if !installed.is_empty() {
+        log::info!("Installed plugins: {}", installed.join(", "));
+    }
+    if !removed.is_empty() {
+        log::info!("Removed plugins: {}", removed.join(", "));
+    }
+}
+
 // Should this really be inside FileSyncrhoniser::new ? (similar with other sync)
 pub fn get_sync_settings(service_provider: &ServiceProvider) -> SyncSettings {
     let ctx = service_provider.basic_context().unwrap();
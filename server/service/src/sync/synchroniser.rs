@@ -284,6 +284,10 @@ impl Synchroniser {
         ctx.processors_trigger
             .trigger_requisition_transfer_processors();
         ctx.processors_trigger.trigger_invoice_transfer_processors();
+        ctx.processors_trigger.trigger_recall_processors();
+        ctx.processors_trigger.trigger_goods_received_processors();
+        ctx.processors_trigger
+            .trigger_redistribution_request_processors();
 
         Ok(())
     }
@@ -375,11 +379,14 @@ pub fn integrate_and_translate_sync_buffer(
         ))
     };
 
+    // Synced records are audited on the site they were changed on
+    let audit_user = connection.set_audit_user(None);
     let result = connection
         .transaction_sync(integrate_and_translate)
-        .map_err::<RepositoryError, _>(|e| e.to_inner_error())?;
+        .map_err::<RepositoryError, _>(|e| e.to_inner_error());
+    connection.set_audit_user(audit_user);
 
-    Ok(result)
+    result
 }
 
 #[cfg(test)]
@@ -24,6 +24,7 @@ pub struct SyncTrigger {
 /// Used to 'drive' synchronisation, it's tasks:
 /// * Expose channel for manually triggering sync
 /// * Trigger sync every SyncSettings.interval_seconds (only when initialised)
+/// * Trigger the expired vaccine vial processor after every sync
 impl SynchroniserDriver {
     pub fn init(file_sync_trigger: FileSyncTrigger) -> (SyncTrigger, SynchroniserDriver) {
         // We use a single-element channel so that we can only have one sync pending at a time.
@@ -90,13 +91,21 @@ impl SynchroniserDriver {
         // Pause file sync
         self.file_sync_trigger.pause();
 
-        let _ = Synchroniser::new(get_sync_settings(&service_provider), service_provider)
-            .unwrap()
-            .sync()
-            .await;
+        let _ = Synchroniser::new(
+            get_sync_settings(&service_provider),
+            service_provider.clone(),
+        )
+        .unwrap()
+        .sync()
+        .await;
 
         // Unpause file sync
         self.file_sync_trigger.unpause();
+
+        // Open vials expire with time, check them every sync interval even if sync failed
+        service_provider
+            .processors_trigger
+            .trigger_expired_vaccine_vial_processors();
     }
 }
 
@@ -24,6 +24,10 @@ impl SyncRecordTester for InvoiceRecordTester {
             on_hold: false,
             store_id: store_id.to_string(),
             cold_storage_type_id: None,
+            parent_location_id: None,
+            volume_capacity: None,
+            pallet_capacity: None,
+            walk_sequence: None,
         };
         // create test home currency
         let currency_row = CurrencyRow {
@@ -92,6 +96,7 @@ impl SyncRecordTester for InvoiceRecordTester {
             foreign_currency_price_before_tax: Some(0.0),
             return_reason_id: None,
             item_variant_id: None,
+            donor_link_id: None,
         };
         let invoice_row_1 = base_invoice_row.clone();
         let invoice_line_row_1 = base_invoice_line_row.clone();
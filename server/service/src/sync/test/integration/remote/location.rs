@@ -20,6 +20,10 @@ impl SyncRecordTester for LocationRecordTester {
             on_hold: false,
             store_id: store_id.to_string(),
             cold_storage_type_id: None,
+            parent_location_id: None,
+            volume_capacity: None,
+            pallet_capacity: None,
+            walk_sequence: None,
         };
 
         result.push(TestStepData {
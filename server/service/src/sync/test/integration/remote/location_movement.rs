@@ -22,6 +22,10 @@ impl SyncRecordTester for LocationMovementRecordTester {
             on_hold: false,
             store_id: store_id.clone(),
             cold_storage_type_id: None,
+            parent_location_id: None,
+            volume_capacity: None,
+            pallet_capacity: None,
+            walk_sequence: None,
         };
         let stock_line_row = StockLineRow {
             id: uuid(),
@@ -40,6 +44,7 @@ impl SyncRecordTester for LocationMovementRecordTester {
             supplier_link_id: Some(new_site_properties.name_id.clone()),
             barcode_id: None,
             item_variant_id: None,
+            donor_link_id: None,
         };
 
         let location_movement_row = LocationMovementRow {
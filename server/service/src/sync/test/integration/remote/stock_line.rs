@@ -22,6 +22,10 @@ impl SyncRecordTester for StockLineRecordTester {
             on_hold: false,
             store_id: store_id.to_string(),
             cold_storage_type_id: None,
+            parent_location_id: None,
+            volume_capacity: None,
+            pallet_capacity: None,
+            walk_sequence: None,
         };
 
         let stock_line_row = StockLineRow {
@@ -41,6 +45,7 @@ impl SyncRecordTester for StockLineRecordTester {
             supplier_link_id: Some(new_site_properties.name_id.clone()),
             barcode_id: None,
             item_variant_id: None,
+            donor_link_id: None,
         };
 
         result.push(TestStepData {
@@ -27,6 +27,10 @@ impl SyncRecordTester for StocktakeRecordTester {
             on_hold: false,
             store_id: store_id.to_string(),
             cold_storage_type_id: None,
+            parent_location_id: None,
+            volume_capacity: None,
+            pallet_capacity: None,
+            walk_sequence: None,
         };
         let currency_row = CurrencyRow {
             id: uuid(),
@@ -72,6 +76,7 @@ impl SyncRecordTester for StocktakeRecordTester {
             note: None,
             inventory_adjustment_reason_id: None,
             item_variant_id: None,
+            donor_link_id: None,
         };
         result.push(TestStepData {
             central_upsert: json!({"item": [{
@@ -0,0 +1,47 @@
+use repository::insurance::insurance_coverage_rule_row::InsuranceCoverageRuleRow;
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "insurance_coverage_rule";
+
+const INSURANCE_COVERAGE_RULE1: (&str, &str) = (
+    "test_insurance_coverage_rule",
+    r#"{
+        "id": "test_insurance_coverage_rule",
+        "insurance_provider_id": "test_insurance_provider",
+        "item_link_id": null,
+        "coverage_percentage": 80.0,
+        "max_coverage_per_unit": 2.5,
+        "is_excluded": false,
+        "deleted_datetime": null
+    }"#,
+);
+
+fn insurance_coverage_rule1() -> InsuranceCoverageRuleRow {
+    InsuranceCoverageRuleRow {
+        id: INSURANCE_COVERAGE_RULE1.0.to_string(),
+        insurance_provider_id: "test_insurance_provider".to_string(),
+        item_link_id: None,
+        coverage_percentage: Some(80.0),
+        max_coverage_per_unit: Some(2.5),
+        is_excluded: false,
+        deleted_datetime: None,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        INSURANCE_COVERAGE_RULE1,
+        insurance_coverage_rule1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: INSURANCE_COVERAGE_RULE1.0.to_string(),
+        push_data: json!(insurance_coverage_rule1()),
+    }]
+}
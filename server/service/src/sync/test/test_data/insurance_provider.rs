@@ -0,0 +1,43 @@
+use repository::insurance::insurance_provider_row::InsuranceProviderRow;
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "insurance_provider";
+
+const INSURANCE_PROVIDER1: (&str, &str) = (
+    "test_insurance_provider",
+    r#"{
+        "id": "test_insurance_provider",
+        "provider_name": "National Health Insurance",
+        "is_active": true,
+        "comment": "Government scheme",
+        "prescription_validity_days": 30
+    }"#,
+);
+
+fn insurance_provider1() -> InsuranceProviderRow {
+    InsuranceProviderRow {
+        id: INSURANCE_PROVIDER1.0.to_string(),
+        provider_name: "National Health Insurance".to_string(),
+        is_active: true,
+        comment: Some("Government scheme".to_string()),
+        prescription_validity_days: Some(30),
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        INSURANCE_PROVIDER1,
+        insurance_provider1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: INSURANCE_PROVIDER1.0.to_string(),
+        push_data: json!(insurance_provider1()),
+    }]
+}
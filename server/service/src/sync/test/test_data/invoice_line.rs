@@ -95,6 +95,7 @@ fn trans_line_1_pull_record() -> TestSyncIncomingRecord {
             return_reason_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: None,
+            donor_link_id: None,
         },
     )
 }
@@ -124,6 +125,7 @@ fn trans_line_1_push_record() -> TestSyncOutgoingRecord {
             option_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: None,
+            donor_id: None,
         }),
     }
 }
@@ -212,6 +214,7 @@ fn trans_line_2_pull_record() -> TestSyncIncomingRecord {
             return_reason_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: None,
+            donor_link_id: None,
         },
     )
 }
@@ -241,6 +244,7 @@ fn trans_line_2_push_record() -> TestSyncOutgoingRecord {
             option_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: None,
+            donor_id: None,
         }),
     }
 }
@@ -333,6 +337,7 @@ fn trans_line_om_fields_pull_record() -> TestSyncIncomingRecord {
             return_reason_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: Some("5fb99f9c-03f4-47f2-965b-c9ecd083c675".to_string()),
+            donor_link_id: None,
         },
     )
 }
@@ -362,6 +367,7 @@ fn trans_line_om_fields_push_record() -> TestSyncOutgoingRecord {
             option_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: Some("5fb99f9c-03f4-47f2-965b-c9ecd083c675".to_string()),
+            donor_id: None,
         }),
     }
 }
@@ -453,6 +459,7 @@ fn trans_line_om_fields_unset_tax_pull_record() -> TestSyncIncomingRecord {
             return_reason_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: None,
+            donor_link_id: None,
         },
     )
 }
@@ -482,6 +489,7 @@ fn trans_line_om_fields_unset_tax_push_record() -> TestSyncOutgoingRecord {
             option_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: None,
+            donor_id: None,
         }),
     }
 }
@@ -42,6 +42,10 @@ pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
             on_hold: false,
             store_id: "store_a".to_string(),
             cold_storage_type_id: None,
+            parent_location_id: None,
+            volume_capacity: None,
+            pallet_capacity: None,
+            walk_sequence: None,
         },
     )]
 }
@@ -56,6 +60,9 @@ pub(crate) fn test_push_records() -> Vec<TestSyncOutgoingRecord> {
             code: "Red.02".to_string(),
             on_hold: false,
             store_id: "store_a".to_string(),
+            parent_id: None,
+            volume: 0.0,
+            priority: 0,
         }),
     }]
 }
@@ -18,6 +18,8 @@ pub(crate) mod currency;
 pub(crate) mod demographic;
 pub(crate) mod indicator_attribute;
 pub(crate) mod indicator_value;
+pub(crate) mod insurance_coverage_rule;
+pub(crate) mod insurance_provider;
 pub(crate) mod invoice;
 pub(crate) mod invoice_line;
 pub(crate) mod item;
@@ -110,6 +112,8 @@ pub(crate) fn get_all_pull_upsert_central_test_records() -> Vec<TestSyncIncoming
     test_records.append(&mut item_variant::test_pull_upsert_records());
     test_records.append(&mut packaging_variant::test_pull_upsert_records());
     test_records.append(&mut system_log::test_pull_upsert_records());
+    test_records.append(&mut insurance_provider::test_pull_upsert_records());
+    test_records.append(&mut insurance_coverage_rule::test_pull_upsert_records());
 
     test_records
 }
@@ -211,6 +215,8 @@ pub(crate) fn get_all_sync_v6_records() -> Vec<TestSyncOutgoingRecord> {
     test_records.append(&mut item_variant::test_v6_central_push_records());
     test_records.append(&mut packaging_variant::test_v6_central_push_records());
     test_records.append(&mut property::test_v6_central_push_records());
+    test_records.append(&mut insurance_provider::test_v6_records());
+    test_records.append(&mut insurance_coverage_rule::test_v6_records());
 
     // Remote
     test_records.append(&mut asset::test_v6_records());
@@ -72,6 +72,7 @@ fn item_line_1_pull_record() -> TestSyncIncomingRecord {
             supplier_link_id: Some("name_store_b".to_string()),
             barcode_id: None,
             item_variant_id: None,
+            donor_link_id: None,
         },
     )
 }
@@ -96,6 +97,7 @@ fn item_line_1_push_record() -> TestSyncOutgoingRecord {
             supplier_id: Some("name_store_b".to_string()),
             barcode_id: None,
             item_variant_id: None,
+            donor_id: None,
         }),
     }
 }
@@ -164,6 +166,7 @@ fn item_line_2_pull_record() -> TestSyncIncomingRecord {
             supplier_link_id: None,
             barcode_id: None,
             item_variant_id: None,
+            donor_link_id: None,
         },
     )
 }
@@ -188,6 +191,7 @@ fn item_line_2_push_record() -> TestSyncOutgoingRecord {
             supplier_id: None,
             barcode_id: None,
             item_variant_id: None,
+            donor_id: None,
         }),
     }
 }
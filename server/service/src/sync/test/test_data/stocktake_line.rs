@@ -15,7 +15,7 @@ const STOCKTAKE_LINE_1: (&str, &str) = (
       "ID": "0a3de900f0d211eb8dddb54df6d741bc",
       "comment": "",
       "cost_price": 12,
-      "donor_ID": "",
+      "donor_id": "",
       "expiry": "0000-00-00",
       "is_edited": true,
       "item_ID": "item_a",
@@ -56,6 +56,7 @@ fn stocktake_line_pull_record() -> TestSyncIncomingRecord {
             note: None,
             inventory_adjustment_reason_id: None,
             item_variant_id: None,
+            donor_link_id: None,
         },
     )
 }
@@ -82,6 +83,7 @@ fn stocktake_line_push_record() -> TestSyncOutgoingRecord {
             note: None,
             inventory_adjustment_reason_id: None,
             item_variant_id: None,
+            donor_id: None,
         }),
     }
 }
@@ -135,6 +137,7 @@ fn stocktake_line_om_field_pull_record() -> TestSyncIncomingRecord {
             note: Some("om note".to_string()),
             inventory_adjustment_reason_id: None,
             item_variant_id: None,
+            donor_link_id: None,
         },
     )
 }
@@ -161,6 +164,7 @@ fn stocktake_line_om_field_push_record() -> TestSyncOutgoingRecord {
             note: Some("om note".to_string()),
             inventory_adjustment_reason_id: None,
             item_variant_id: None,
+            donor_id: None,
         }),
     }
 }
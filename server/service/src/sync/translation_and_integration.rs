@@ -223,7 +223,7 @@ pub(crate) fn integrate(
     source_site_id: Option<i32>,
 ) -> Result<(), RepositoryError> {
     for integration_record in integration_records.iter() {
-        if cfg!(feature = "postgres") {
+        if connection.backend().is_postgres() {
             // In Postgres the parent transaction fails when there is a DB error in any of the
             // statements executed in the transaction. Thus, integrate every record in a nested
             // transaction to catch potential errors (e.g. foreign key violations).
@@ -0,0 +1,87 @@
+use repository::{
+    asset_work_order_spare_part_row::{
+        AssetWorkOrderSparePartRow, AssetWorkOrderSparePartRowRepository,
+    },
+    ChangelogRow, ChangelogTableName, StorageConnection, SyncBufferRow,
+};
+
+use crate::sync::translations::{
+    asset_work_order::AssetWorkOrderTranslation, invoice::InvoiceTranslation,
+    item::ItemTranslation, stock_line::StockLineTranslation,
+};
+
+use super::{
+    PullTranslateResult, PushTranslateResult, SyncTranslation, ToSyncRecordTranslationType,
+};
+
+// Needs to be added to all_translators()
+#[deny(dead_code)]
+pub(crate) fn boxed() -> Box<dyn SyncTranslation> {
+    Box::new(AssetWorkOrderSparePartTranslation)
+}
+
+pub(crate) struct AssetWorkOrderSparePartTranslation;
+
+impl SyncTranslation for AssetWorkOrderSparePartTranslation {
+    fn table_name(&self) -> &'static str {
+        "asset_work_order_spare_part"
+    }
+
+    fn pull_dependencies(&self) -> Vec<&'static str> {
+        vec![
+            AssetWorkOrderTranslation.table_name(),
+            ItemTranslation.table_name(),
+            StockLineTranslation.table_name(),
+            InvoiceTranslation.table_name(),
+        ]
+    }
+
+    fn try_translate_from_upsert_sync_record(
+        &self,
+        _: &StorageConnection,
+        sync_record: &SyncBufferRow,
+    ) -> Result<PullTranslateResult, anyhow::Error> {
+        Ok(PullTranslateResult::upsert(serde_json::from_str::<
+            AssetWorkOrderSparePartRow,
+        >(&sync_record.data)?))
+    }
+
+    fn change_log_type(&self) -> Option<ChangelogTableName> {
+        Some(ChangelogTableName::AssetWorkOrderSparePart)
+    }
+
+    fn should_translate_to_sync_record(
+        &self,
+        row: &ChangelogRow,
+        r#type: &ToSyncRecordTranslationType,
+    ) -> bool {
+        match r#type {
+            ToSyncRecordTranslationType::PullFromOmSupplyCentral => {
+                self.change_log_type().as_ref() == Some(&row.table_name)
+            }
+            ToSyncRecordTranslationType::PushToOmSupplyCentral => {
+                self.change_log_type().as_ref() == Some(&row.table_name)
+            }
+            _ => false,
+        }
+    }
+
+    fn try_translate_to_upsert_sync_record(
+        &self,
+        connection: &StorageConnection,
+        changelog: &ChangelogRow,
+    ) -> Result<PushTranslateResult, anyhow::Error> {
+        let row = AssetWorkOrderSparePartRowRepository::new(connection)
+            .find_one_by_id(&changelog.record_id)?
+            .ok_or(anyhow::Error::msg(format!(
+                "Asset work order spare part row ({}) not found",
+                changelog.record_id
+            )))?;
+
+        Ok(PushTranslateResult::upsert(
+            changelog,
+            self.table_name(),
+            serde_json::to_value(row)?,
+        ))
+    }
+}
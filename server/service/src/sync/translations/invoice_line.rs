@@ -16,8 +16,9 @@ use repository::{
 use serde::{Deserialize, Serialize};
 
 use super::{
-    is_active_record_on_site, utils::clear_invalid_location_id, ActiveRecordCheck,
-    PullTranslateResult, PushTranslateResult, SyncTranslation,
+    is_active_record_on_site,
+    utils::{clear_invalid_location_id, name_id_from_link_id},
+    ActiveRecordCheck, PullTranslateResult, PushTranslateResult, SyncTranslation,
 };
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -84,6 +85,9 @@ pub struct LegacyTransLineRow {
     pub foreign_currency_price_before_tax: Option<f64>,
     #[serde(rename = "om_item_variant_id")]
     pub item_variant_id: Option<String>,
+    #[serde(default)]
+    #[serde(deserialize_with = "empty_str_as_option_string")]
+    pub donor_id: Option<String>,
 }
 // Needs to be added to all_translators()
 #[deny(dead_code)]
@@ -140,6 +144,7 @@ impl SyncTranslation for InvoiceLineTranslation {
             option_id,
             foreign_currency_price_before_tax,
             item_variant_id,
+            donor_id,
         } = serde_json::from_str::<LegacyTransLineRow>(&sync_record.data)?;
         let line_type = match to_invoice_line_type(&r#type) {
             Some(line_type) => line_type,
@@ -280,6 +285,7 @@ impl SyncTranslation for InvoiceLineTranslation {
             },
             foreign_currency_price_before_tax,
             item_variant_id,
+            donor_link_id: donor_id,
         };
 
         Ok(PullTranslateResult::upsert(result))
@@ -332,6 +338,7 @@ impl SyncTranslation for InvoiceLineTranslation {
                     return_reason_id,
                     foreign_currency_price_before_tax,
                     item_variant_id,
+                    donor_link_id,
                 },
             item_row,
             invoice_row,
@@ -368,6 +375,7 @@ impl SyncTranslation for InvoiceLineTranslation {
             foreign_currency_price_before_tax,
             item_variant_id,
             option_id,
+            donor_id: name_id_from_link_id(connection, donor_link_id)?,
         };
         Ok(PushTranslateResult::upsert(
             changelog,
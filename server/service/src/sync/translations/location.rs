@@ -4,7 +4,7 @@ use repository::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::sync::translations::store::StoreTranslation;
+use crate::sync::{sync_serde::empty_str_as_option_string, translations::store::StoreTranslation};
 
 use super::{PullTranslateResult, PushTranslateResult, SyncTranslation};
 
@@ -19,6 +19,14 @@ pub struct LegacyLocationRow {
     pub on_hold: bool,
     #[serde(rename = "store_ID")]
     pub store_id: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "empty_str_as_option_string")]
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    #[serde(rename = "Volume")]
+    pub volume: f64,
+    #[serde(default)]
+    pub priority: i32,
 }
 
 // Needs to be added to all_translators()
@@ -52,6 +60,9 @@ impl SyncTranslation for LocationTranslation {
             code,
             on_hold,
             store_id,
+            parent_id,
+            volume,
+            priority,
         } = serde_json::from_str::<LegacyLocationRow>(&sync_record.data)?;
 
         let result = LocationRow {
@@ -61,6 +72,11 @@ impl SyncTranslation for LocationTranslation {
             on_hold,
             store_id,
             cold_storage_type_id: None,
+            parent_location_id: parent_id,
+            volume_capacity: (volume > 0.0).then_some(volume),
+            // Not available in legacy mSupply
+            pallet_capacity: None,
+            walk_sequence: (priority > 0).then_some(priority),
         };
 
         Ok(PullTranslateResult::upsert(result))
@@ -78,6 +94,10 @@ impl SyncTranslation for LocationTranslation {
             on_hold,
             store_id,
             cold_storage_type_id: _, // TODO: Translate cold_storage_type_id id from `location_type_id`
+            parent_location_id,
+            volume_capacity,
+            pallet_capacity: _,
+            walk_sequence,
         } = LocationRowRepository::new(connection)
             .find_one_by_id(&changelog.record_id)?
             .ok_or(anyhow::Error::msg(format!(
@@ -91,6 +111,9 @@ impl SyncTranslation for LocationTranslation {
             code,
             on_hold,
             store_id,
+            parent_id: parent_location_id,
+            volume: volume_capacity.unwrap_or_default(),
+            priority: walk_sequence.unwrap_or_default(),
         };
 
         Ok(PushTranslateResult::upsert(
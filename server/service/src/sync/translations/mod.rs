@@ -6,8 +6,12 @@ pub(crate) mod asset_category;
 pub(crate) mod asset_class;
 pub(crate) mod asset_log;
 pub(crate) mod asset_log_reason;
+pub(crate) mod asset_maintenance_plan;
 pub(crate) mod asset_property;
 pub(crate) mod asset_type;
+pub(crate) mod asset_work_order;
+pub(crate) mod asset_work_order_spare_part;
+pub(crate) mod audit_trail;
 pub(crate) mod barcode;
 pub(crate) mod category;
 pub(crate) mod clinician;
@@ -18,8 +22,12 @@ pub(crate) mod demographic;
 pub(crate) mod document;
 pub(crate) mod document_registry;
 pub(crate) mod form_schema;
+pub(crate) mod goods_received_line;
 pub(crate) mod indicator_attribute;
 pub(crate) mod indicator_value;
+pub(crate) mod insurance_claim_line;
+pub(crate) mod insurance_coverage_rule;
+pub(crate) mod insurance_provider;
 pub(crate) mod invoice;
 pub(crate) mod invoice_line;
 pub(crate) mod item;
@@ -30,6 +38,7 @@ pub(crate) mod master_list;
 pub(crate) mod master_list_line;
 pub(crate) mod master_list_name_join;
 pub(crate) mod name;
+pub(crate) mod name_insurance_join;
 pub(crate) mod name_oms_fields;
 pub(crate) mod name_property;
 pub(crate) mod name_store_join;
@@ -37,16 +46,24 @@ pub(crate) mod name_tag;
 pub(crate) mod name_tag_join;
 pub(crate) mod packaging_variant;
 pub(crate) mod period;
+pub(crate) mod period_lock;
 pub(crate) mod period_schedule;
+pub(crate) mod plugin_certificate_revocation;
+pub(crate) mod plugin_data;
+pub(crate) mod plugin_version;
 pub(crate) mod program_indicator;
 pub(crate) mod program_requisition_settings;
 pub(crate) mod property;
 pub(crate) mod reason;
+pub(crate) mod recall;
+pub(crate) mod redistribution_request;
 pub(crate) mod report;
 pub(crate) mod requisition;
 pub(crate) mod requisition_line;
 pub(crate) mod rnr_form;
 pub(crate) mod rnr_form_line;
+pub(crate) mod role;
+pub(crate) mod role_permission;
 pub(crate) mod sensor;
 pub(crate) mod special;
 pub(crate) mod stock_line;
@@ -61,11 +78,13 @@ pub(crate) mod temperature_log;
 pub(crate) mod unit;
 pub(crate) mod user;
 pub(crate) mod user_permission;
+pub(crate) mod user_role;
 pub(crate) mod utils;
 pub(crate) mod vaccination;
 pub(crate) mod vaccine_course;
 pub(crate) mod vaccine_course_dose;
 pub(crate) mod vaccine_course_item;
+pub(crate) mod vaccine_vial_opening;
 
 use repository::*;
 use thiserror::Error;
@@ -142,6 +161,9 @@ pub(crate) fn all_translators() -> SyncTranslators {
         asset_log::boxed(),
         asset_log_reason::boxed(),
         asset_property::boxed(),
+        asset_maintenance_plan::boxed(),
+        asset_work_order::boxed(),
+        asset_work_order_spare_part::boxed(),
         //Sync file reference
         sync_file_reference::boxed(),
         // RnR Form
@@ -154,11 +176,35 @@ pub(crate) fn all_translators() -> SyncTranslators {
         demographic::boxed(),
         // Vaccination
         vaccination::boxed(),
+        vaccine_vial_opening::boxed(),
         // Item Variant
         item_variant::boxed(),
         packaging_variant::boxed(),
+        // Recall
+        recall::boxed(),
+        // Goods received
+        goods_received_line::boxed(),
         // System log
         system_log::boxed(),
+        // Insurance
+        insurance_provider::boxed(),
+        insurance_coverage_rule::boxed(),
+        name_insurance_join::boxed(),
+        insurance_claim_line::boxed(),
+        // Plugins
+        plugin_version::boxed(),
+        plugin_certificate_revocation::boxed(),
+        plugin_data::boxed(),
+        // Roles
+        role::boxed(),
+        role_permission::boxed(),
+        user_role::boxed(),
+        // Audit trail
+        audit_trail::boxed(),
+        // Period close
+        period_lock::boxed(),
+        // Expiry risk redistribution
+        redistribution_request::boxed(),
     ]
 }
 
@@ -0,0 +1,171 @@
+use repository::{
+    ChangelogRow, ChangelogTableName, PluginDataRow, PluginDataRowRepository, StorageConnection,
+    SyncBufferRow,
+};
+
+use crate::sync::{translations::store::StoreTranslation, CentralServerConfig};
+
+use super::{
+    PullTranslateResult, PushTranslateResult, SyncTranslation, ToSyncRecordTranslationType,
+};
+
+// Needs to be added to all_translators()
+#[deny(dead_code)]
+pub(crate) fn boxed() -> Box<dyn SyncTranslation> {
+    Box::new(PluginDataTranslation)
+}
+
+pub(crate) struct PluginDataTranslation;
+
+impl SyncTranslation for PluginDataTranslation {
+    fn table_name(&self) -> &'static str {
+        "plugin_data"
+    }
+
+    fn pull_dependencies(&self) -> Vec<&'static str> {
+        vec![StoreTranslation.table_name()]
+    }
+
+    fn try_translate_from_upsert_sync_record(
+        &self,
+        connection: &StorageConnection,
+        sync_record: &SyncBufferRow,
+    ) -> Result<PullTranslateResult, anyhow::Error> {
+        let row = serde_json::from_str::<PluginDataRow>(&sync_record.data)?;
+
+        // Plugin data can be changed on central and on the site of the store at the same time.
+        // The change with the higher version wins, central's change wins when both sites made the
+        // same number of changes so that both end up with the same data.
+        let existing = PluginDataRowRepository::new(connection).find_one_by_id(&row.id)?;
+        if let Some(existing) = existing {
+            let keep_existing = match CentralServerConfig::is_central_server() {
+                true => existing.version >= row.version,
+                false => existing.version > row.version,
+            };
+            if keep_existing {
+                return Ok(PullTranslateResult::Ignored(format!(
+                    "Plugin data ({}) has more recent changes on this site",
+                    row.id
+                )));
+            }
+        }
+
+        Ok(PullTranslateResult::upsert(row))
+    }
+
+    fn change_log_type(&self) -> Option<ChangelogTableName> {
+        Some(ChangelogTableName::PluginData)
+    }
+
+    fn should_translate_to_sync_record(
+        &self,
+        row: &ChangelogRow,
+        r#type: &ToSyncRecordTranslationType,
+    ) -> bool {
+        match r#type {
+            ToSyncRecordTranslationType::PullFromOmSupplyCentral => {
+                self.change_log_type().as_ref() == Some(&row.table_name)
+            }
+            ToSyncRecordTranslationType::PushToOmSupplyCentral => {
+                self.change_log_type().as_ref() == Some(&row.table_name)
+            }
+            _ => false,
+        }
+    }
+
+    fn try_translate_to_upsert_sync_record(
+        &self,
+        connection: &StorageConnection,
+        changelog: &ChangelogRow,
+    ) -> Result<PushTranslateResult, anyhow::Error> {
+        let row = PluginDataRowRepository::new(connection)
+            .find_one_by_id(&changelog.record_id)?
+            .ok_or(anyhow::Error::msg(format!(
+                "Plugin data row ({}) not found",
+                changelog.record_id
+            )))?;
+
+        Ok(PushTranslateResult::upsert(
+            changelog,
+            self.table_name(),
+            serde_json::to_value(row)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use repository::{
+        mock::{mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        RelatedRecordType,
+    };
+
+    #[actix_rt::test]
+    async fn test_plugin_data_translation() {
+        let translator = PluginDataTranslation;
+        let (_, connection, _, _) = setup_all(
+            "test_plugin_data_translation",
+            MockDataInserts::none().names().stores(),
+        )
+        .await;
+
+        let row = |data: &str, version: i64| PluginDataRow {
+            id: "plugin_data".to_string(),
+            plugin_name: "donor_tracking".to_string(),
+            related_record_id: "stock_line".to_string(),
+            related_record_type: RelatedRecordType::StockLine,
+            store_id: mock_store_a().id,
+            data: data.to_string(),
+            version,
+        };
+        let sync_record = |row: &PluginDataRow| SyncBufferRow {
+            table_name: translator.table_name().to_string(),
+            record_id: row.id.clone(),
+            data: serde_json::to_string(row).unwrap(),
+            ..Default::default()
+        };
+
+        // New record
+        let incoming = row("central", 1);
+        assert_eq!(
+            translator
+                .try_translate_from_upsert_sync_record(&connection, &sync_record(&incoming))
+                .unwrap(),
+            PullTranslateResult::upsert(incoming)
+        );
+
+        // More local changes than incoming changes, regardless of the time they were made at
+        PluginDataRowRepository::new(&connection)
+            .upsert_one(&row("local", 3))
+            .unwrap();
+        assert!(matches!(
+            translator
+                .try_translate_from_upsert_sync_record(
+                    &connection,
+                    &sync_record(&row("central", 2))
+                )
+                .unwrap(),
+            PullTranslateResult::Ignored(_)
+        ));
+
+        // Same number of changes, central wins on the remote site
+        let incoming = row("central", 3);
+        assert_eq!(
+            translator
+                .try_translate_from_upsert_sync_record(&connection, &sync_record(&incoming))
+                .unwrap(),
+            PullTranslateResult::upsert(incoming)
+        );
+
+        // Incoming change is newer
+        let incoming = row("central", 4);
+        assert_eq!(
+            translator
+                .try_translate_from_upsert_sync_record(&connection, &sync_record(&incoming))
+                .unwrap(),
+            PullTranslateResult::upsert(incoming)
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use repository::{
+    ChangelogRow, ChangelogTableName, RecallRow, RecallRowRepository, StorageConnection,
+    SyncBufferRow,
+};
+
+use crate::sync::translations::{
+    item::ItemTranslation, item_variant::ItemVariantTranslation, name::NameTranslation,
+};
+
+use super::{
+    PullTranslateResult, PushTranslateResult, SyncTranslation, ToSyncRecordTranslationType,
+};
+
+// Needs to be added to all_translators()
+#[deny(dead_code)]
+pub(crate) fn boxed() -> Box<dyn SyncTranslation> {
+    Box::new(RecallTranslation)
+}
+
+pub(crate) struct RecallTranslation;
+
+impl SyncTranslation for RecallTranslation {
+    fn table_name(&self) -> &'static str {
+        "recall"
+    }
+
+    fn pull_dependencies(&self) -> Vec<&str> {
+        vec![
+            ItemTranslation.table_name(),
+            NameTranslation.table_name(),
+            ItemVariantTranslation.table_name(),
+        ]
+    }
+
+    fn try_translate_from_upsert_sync_record(
+        &self,
+        _: &StorageConnection,
+        sync_record: &SyncBufferRow,
+    ) -> Result<PullTranslateResult, anyhow::Error> {
+        Ok(PullTranslateResult::upsert(serde_json::from_str::<
+            RecallRow,
+        >(&sync_record.data)?))
+    }
+
+    fn change_log_type(&self) -> Option<ChangelogTableName> {
+        Some(ChangelogTableName::Recall)
+    }
+
+    // Recalls are created on the central server and only pulled by remote sites
+    fn should_translate_to_sync_record(
+        &self,
+        row: &ChangelogRow,
+        r#type: &ToSyncRecordTranslationType,
+    ) -> bool {
+        match r#type {
+            ToSyncRecordTranslationType::PullFromOmSupplyCentral => {
+                self.change_log_type().as_ref() == Some(&row.table_name)
+            }
+            _ => false,
+        }
+    }
+
+    fn try_translate_to_upsert_sync_record(
+        &self,
+        connection: &StorageConnection,
+        changelog: &ChangelogRow,
+    ) -> Result<PushTranslateResult, anyhow::Error> {
+        let row = RecallRowRepository::new(connection)
+            .find_one_by_id(&changelog.record_id)?
+            .ok_or(anyhow::Error::msg(format!(
+                "Recall row ({}) not found",
+                changelog.record_id
+            )))?;
+
+        Ok(PushTranslateResult::upsert(
+            changelog,
+            self.table_name(),
+            serde_json::to_value(row)?,
+        ))
+    }
+}
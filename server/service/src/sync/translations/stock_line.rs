@@ -13,7 +13,7 @@ use repository::{
 use serde::{Deserialize, Serialize};
 
 use super::{
-    utils::{clear_invalid_barcode_id, clear_invalid_location_id},
+    utils::{clear_invalid_barcode_id, clear_invalid_location_id, name_id_from_link_id},
     PullTranslateResult, PushTranslateResult, SyncTranslation,
 };
 
@@ -46,6 +46,9 @@ pub struct LegacyStockLineRow {
     #[serde(rename = "om_item_variant_id")]
     #[serde(default)]
     pub item_variant_id: Option<String>,
+    #[serde(deserialize_with = "empty_str_as_option_string")]
+    #[serde(default)]
+    pub donor_id: Option<String>,
 }
 // Needs to be added to all_translators()
 #[deny(dead_code)]
@@ -96,6 +99,7 @@ impl SyncTranslation for StockLineTranslation {
             supplier_id,
             barcode_id,
             item_variant_id,
+            donor_id,
         } = serde_json::from_str::<LegacyStockLineRow>(&sync_record.data)?;
 
         let barcode_id = clear_invalid_barcode_id(connection, barcode_id)?;
@@ -117,6 +121,7 @@ impl SyncTranslation for StockLineTranslation {
             supplier_link_id: supplier_id,
             barcode_id,
             item_variant_id,
+            donor_link_id: donor_id,
         };
 
         Ok(PullTranslateResult::upsert(result))
@@ -156,6 +161,7 @@ impl SyncTranslation for StockLineTranslation {
                     supplier_link_id: _,
                     barcode_id,
                     item_variant_id,
+                    donor_link_id,
                 },
             item_row,
             supplier_name_row,
@@ -179,6 +185,7 @@ impl SyncTranslation for StockLineTranslation {
             supplier_id: supplier_name_row.map(|supplier| supplier.id),
             barcode_id,
             item_variant_id,
+            donor_id: name_id_from_link_id(connection, donor_link_id)?,
         };
 
         Ok(PushTranslateResult::upsert(
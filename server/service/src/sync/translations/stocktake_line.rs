@@ -14,7 +14,8 @@ use repository::{
 use serde::{Deserialize, Serialize};
 
 use super::{
-    utils::clear_invalid_location_id, PullTranslateResult, PushTranslateResult, SyncTranslation,
+    utils::{clear_invalid_location_id, name_id_from_link_id},
+    PullTranslateResult, PushTranslateResult, SyncTranslation,
 };
 
 #[allow(non_snake_case)]
@@ -53,6 +54,11 @@ pub struct LegacyStocktakeLineRow {
     #[serde(deserialize_with = "empty_str_as_option_string")]
     #[serde(default)]
     pub item_variant_id: Option<String>,
+    /// Same wire name as the stock and invoice lines, `donor_ID` is still read from older records
+    #[serde(alias = "donor_ID")]
+    #[serde(deserialize_with = "empty_str_as_option_string")]
+    #[serde(default)]
+    pub donor_id: Option<String>,
 }
 // Needs to be added to all_translators()
 #[deny(dead_code)]
@@ -104,6 +110,7 @@ impl SyncTranslation for StocktakeLineTranslation {
             note,
             inventory_adjustment_reason_id,
             item_variant_id,
+            donor_id,
         } = serde_json::from_str::<LegacyStocktakeLineRow>(&sync_record.data)?;
 
         // TODO is this correct?
@@ -152,6 +159,7 @@ impl SyncTranslation for StocktakeLineTranslation {
             note,
             inventory_adjustment_reason_id,
             item_variant_id,
+            donor_link_id: donor_id,
         };
 
         Ok(PullTranslateResult::upsert(result))
@@ -192,6 +200,7 @@ impl SyncTranslation for StocktakeLineTranslation {
                     note,
                     inventory_adjustment_reason_id,
                     item_variant_id,
+                    donor_link_id,
                 },
             item,
             stock_line,
@@ -218,6 +227,7 @@ impl SyncTranslation for StocktakeLineTranslation {
             note,
             inventory_adjustment_reason_id,
             item_variant_id,
+            donor_id: name_id_from_link_id(connection, donor_link_id)?,
         };
 
         Ok(PushTranslateResult::upsert(
@@ -1,4 +1,7 @@
-use repository::{BarcodeRowRepository, LocationRowRepository, RepositoryError, StorageConnection};
+use repository::{
+    BarcodeRowRepository, LocationRowRepository, NameLinkRowRepository, RepositoryError,
+    StorageConnection,
+};
 
 /// Some datafiles contain links to non-existing barcode references.
 /// Check if the entry exists and if not return None.
@@ -31,3 +34,19 @@ pub(crate) fn clear_invalid_location_id(
     };
     Ok(location_id)
 }
+
+/// Legacy records reference names by name id, resolves a name link (e.g. of a merged name) to the
+/// name it currently links to
+pub(crate) fn name_id_from_link_id(
+    connection: &StorageConnection,
+    name_link_id: Option<String>,
+) -> Result<Option<String>, RepositoryError> {
+    let name_id = if let Some(id) = name_link_id {
+        NameLinkRowRepository::new(connection)
+            .find_one_by_id(&id)?
+            .map(|it| it.name_id)
+    } else {
+        None
+    };
+    Ok(name_id)
+}
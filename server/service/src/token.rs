@@ -4,7 +4,9 @@ use anyhow::anyhow;
 use chrono::Utc;
 use jsonwebtoken::errors::{Error as JWTError, ErrorKind as JWTErrorKind};
 use log::error;
+use repository::RepositoryError;
 use serde::{Deserialize, Serialize};
+use util::uuid::uuid;
 
 use super::token_bucket::TokenBucket;
 
@@ -32,6 +34,9 @@ pub struct OmSupplyClaim {
     pub iss: String,
     /// Subject (user id the token refers to)
     pub sub: String,
+    /// Unique token id, tokens issued for the same user in the same second are otherwise identical
+    #[serde(default)]
+    pub jti: Option<String>,
 }
 
 /// Error for getting a JWT token
@@ -39,6 +44,7 @@ pub struct OmSupplyClaim {
 pub enum JWTIssuingError {
     CanNotCreateToken(JWTError),
     ConcurrencyLockError(anyhow::Error),
+    DatabaseError(RepositoryError),
 }
 
 #[derive(Debug)]
@@ -60,11 +66,13 @@ pub enum JWTRefreshError {
     /// Token has been invalidated on the backend
     TokenInvalided,
     ConcurrencyLockError(anyhow::Error),
+    DatabaseError(RepositoryError),
 }
 
 #[derive(Debug)]
 pub enum JWTLogoutError {
     ConcurrencyLockError(anyhow::Error),
+    DatabaseError(RepositoryError),
 }
 
 #[derive(Debug)]
@@ -153,8 +161,25 @@ impl<'a> TokenService<'a> {
             error!("{}", e);
             JWTIssuingError::ConcurrencyLockError(anyhow!("jwt_token: {}", e))
         })?;
-        token_bucket.put(user_id, password, &pair.token, pair.expiry_date);
-        token_bucket.put(user_id, password, &pair.refresh, pair.refresh_expiry_date);
+        let session_id = uuid();
+        token_bucket
+            .put(
+                user_id,
+                password,
+                &session_id,
+                &pair.token,
+                pair.expiry_date,
+            )
+            .map_err(JWTIssuingError::DatabaseError)?;
+        token_bucket
+            .put(
+                user_id,
+                password,
+                &session_id,
+                &pair.refresh,
+                pair.refresh_expiry_date,
+            )
+            .map_err(JWTIssuingError::DatabaseError)?;
 
         Ok(pair)
     }
@@ -207,10 +232,30 @@ impl<'a> TokenService<'a> {
             return Err(JWTRefreshError::TokenInvalided);
         }
         let password: String = token_bucket.get_password(&user_id);
+        // The new tokens continue the session of the refresh token
+        let session_id = token_bucket
+            .session_id(&user_id, refresh_token)
+            .unwrap_or_else(uuid);
 
         // add new tokens to bucket
-        token_bucket.put(&user_id, &password, &pair.token, pair.expiry_date);
-        token_bucket.put(&user_id, &password, &pair.refresh, pair.refresh_expiry_date);
+        token_bucket
+            .put(
+                &user_id,
+                &password,
+                &session_id,
+                &pair.token,
+                pair.expiry_date,
+            )
+            .map_err(JWTRefreshError::DatabaseError)?;
+        token_bucket
+            .put(
+                &user_id,
+                &password,
+                &session_id,
+                &pair.refresh,
+                pair.refresh_expiry_date,
+            )
+            .map_err(JWTRefreshError::DatabaseError)?;
         // Shorten the expiry time of the old refresh token.
         //
         // Note, if the client goes offline before receiving the new refresh token the user might
@@ -218,7 +263,15 @@ impl<'a> TokenService<'a> {
         // issue.
         let reduced_expiry =
             std::cmp::min(Utc::now().timestamp() as usize + 5 * 60, decoded.claims.exp);
-        token_bucket.put(&user_id, &password, refresh_token, reduced_expiry);
+        token_bucket
+            .put(
+                &user_id,
+                &password,
+                &session_id,
+                refresh_token,
+                reduced_expiry,
+            )
+            .map_err(JWTRefreshError::DatabaseError)?;
 
         Ok(pair)
     }
@@ -267,8 +320,20 @@ impl<'a> TokenService<'a> {
             error!("logout: {}", e);
             JWTLogoutError::ConcurrencyLockError(anyhow!("logout: {}", e))
         })?;
-        token_bucket.clear(user_id);
-        Ok(())
+        token_bucket
+            .clear(user_id)
+            .map_err(JWTLogoutError::DatabaseError)
+    }
+
+    /// Log a single session out, returns false if the session isn't known
+    pub fn revoke_session(&mut self, session_id: &str) -> Result<bool, JWTLogoutError> {
+        let mut token_bucket = self.token_bucket.write().map_err(|e| {
+            error!("revoke_session: {}", e);
+            JWTLogoutError::ConcurrencyLockError(anyhow!("revoke_session: {}", e))
+        })?;
+        token_bucket
+            .remove_session(session_id)
+            .map_err(JWTLogoutError::DatabaseError)
     }
 }
 
@@ -290,6 +355,7 @@ fn create_jwt_pair(
         iat: now,
         iss: ISSUER.to_string(),
         sub: user_id.to_owned(),
+        jti: Some(uuid()),
     };
     let api_token = jsonwebtoken::encode(
         &jsonwebtoken::Header::default(),
@@ -304,6 +370,7 @@ fn create_jwt_pair(
         iat: now,
         iss: ISSUER.to_string(),
         sub: user_id.to_owned(),
+        jti: Some(uuid()),
     };
     let refresh_token = jsonwebtoken::encode(
         &jsonwebtoken::Header::default(),
@@ -321,6 +388,7 @@ fn create_jwt_pair(
 
 #[cfg(test)]
 mod user_account_test {
+    use repository::{mock::MockDataInserts, test_db::setup_all};
     use util::assert_matches;
 
     use super::*;
@@ -414,4 +482,59 @@ mod user_account_test {
             .unwrap_err();
         assert_matches!(err, JWTRefreshError::ExpiredSignature);
     }
+
+    #[actix_rt::test]
+    async fn test_persisted_sessions() {
+        let (_, _, connection_manager, _) =
+            setup_all("persisted_sessions", MockDataInserts::none()).await;
+        const JWT_TOKEN_SECRET: &[u8] = "some secret".as_bytes();
+        let user_id = "test_user_id";
+        let password = "pass";
+
+        let bucket = RwLock::new(TokenBucket::persisted(connection_manager.clone()).unwrap());
+        let mut service = TokenService::new(&bucket, JWT_TOKEN_SECRET, true);
+        let session_a = service.jwt_token(user_id, password, 60, 120).unwrap();
+        let session_b = service.jwt_token(user_id, password, 60, 120).unwrap();
+        let session_b = service
+            .refresh_token(&session_b.refresh, 60, 120, Some(0))
+            .unwrap();
+
+        // sessions should survive a restart
+        let bucket = RwLock::new(TokenBucket::persisted(connection_manager.clone()).unwrap());
+        let mut service = TokenService::new(&bucket, JWT_TOKEN_SECRET, true);
+        service.verify_token(&session_a.token, Some(0)).unwrap();
+        service.verify_token(&session_b.token, Some(0)).unwrap();
+        // but not the password
+        assert_eq!(bucket.read().unwrap().get_password(user_id), "");
+
+        // should only invalidate the revoked session
+        let session_b_id = bucket
+            .read()
+            .unwrap()
+            .session_id(user_id, &session_b.refresh)
+            .unwrap();
+        assert!(service.revoke_session(&session_b_id).unwrap());
+        assert!(!service.revoke_session(&session_b_id).unwrap());
+        let err = service.verify_token(&session_b.token, Some(0)).unwrap_err();
+        assert_matches!(err, JWTValidationError::TokenInvalidated);
+        let err = service
+            .refresh_token(&session_b.refresh, 60, 120, Some(0))
+            .unwrap_err();
+        assert_matches!(err, JWTRefreshError::TokenInvalided);
+        service.verify_token(&session_a.token, Some(0)).unwrap();
+
+        // revoked session should stay invalid after a restart
+        let bucket = RwLock::new(TokenBucket::persisted(connection_manager.clone()).unwrap());
+        let mut service = TokenService::new(&bucket, JWT_TOKEN_SECRET, true);
+        let err = service.verify_token(&session_b.token, Some(0)).unwrap_err();
+        assert_matches!(err, JWTValidationError::TokenInvalidated);
+        service.verify_token(&session_a.token, Some(0)).unwrap();
+
+        // logout should invalidate all sessions after a restart
+        service.logout(user_id).unwrap();
+        let bucket = RwLock::new(TokenBucket::persisted(connection_manager).unwrap());
+        let service = TokenService::new(&bucket, JWT_TOKEN_SECRET, true);
+        let err = service.verify_token(&session_a.token, Some(0)).unwrap_err();
+        assert_matches!(err, JWTValidationError::TokenInvalidated);
+    }
 }
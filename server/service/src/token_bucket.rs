@@ -1,14 +1,20 @@
 use std::collections::{hash_map::Entry, HashMap};
 
-use chrono::Utc;
+use chrono::{DateTime, NaiveDateTime, Utc};
 
+use repository::{
+    RepositoryError, StorageConnectionManager, UserSessionTokenRow, UserSessionTokenRowRepository,
+};
 use util::hash::sha256;
 
 struct TokenInfo {
     token_hash: String,
+    /// Shared by all tokens issued from the same login
+    session_id: String,
     expiry_date: usize,
     // Temporarily store password in token info.
     // Will need to delete once server has implemented its own central server.
+    // The password is never persisted, i.e. it is empty for sessions loaded from the database.
     password: String,
 }
 
@@ -16,22 +22,59 @@ fn token_hash(token: &str) -> String {
     sha256(token)
 }
 
+fn to_datetime(timestamp: usize) -> NaiveDateTime {
+    DateTime::from_timestamp(timestamp as i64, 0)
+        .unwrap_or_default()
+        .naive_utc()
+}
+
 /// Tracks if a token is still valid
 ///
 /// There are two ways a token can expire prematurely:
-/// 1) User logs out and token is removed from the bucket
+/// 1) User logs out or the session is revoked and the token is removed from the bucket
 /// 2) Token expiry time is reduce (server side), e.g. when an token has been renewed and the old
 ///     token should expiry sooner.
+///
+/// A persisted bucket also stores the (hashed) tokens in the database so that sessions survive a
+/// server restart. The in memory map is the source for token validation, the database is updated
+/// before the map.
 #[derive(Default)]
 pub struct TokenBucket {
     users: HashMap<String, Vec<TokenInfo>>,
+    connection_manager: Option<StorageConnectionManager>,
 }
 
 impl TokenBucket {
+    /// In memory bucket, all sessions are lost when the server stops
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Bucket persisted in the database, loaded with the tokens that haven't expired yet
+    pub fn persisted(
+        connection_manager: StorageConnectionManager,
+    ) -> Result<Self, RepositoryError> {
+        let connection = connection_manager.connection()?;
+        let repo = UserSessionTokenRowRepository::new(&connection);
+        let now = Utc::now().naive_utc();
+        repo.delete_expired(now)?;
+
+        let mut users: HashMap<String, Vec<TokenInfo>> = HashMap::new();
+        for row in repo.find_unexpired(now)? {
+            users.entry(row.user_id).or_default().push(TokenInfo {
+                token_hash: row.id,
+                session_id: row.session_id,
+                expiry_date: row.expiry_datetime.and_utc().timestamp() as usize,
+                password: String::new(),
+            });
+        }
+
+        Ok(TokenBucket {
+            users,
+            connection_manager: Some(connection_manager),
+        })
+    }
+
     /// Checks if the token is known for the given user
     pub fn contains(&self, user_id: &str, token: &str) -> bool {
         let user_tokens = match self.users.get(user_id) {
@@ -53,15 +96,35 @@ impl TokenBucket {
         existing_token.expiry_date >= now
     }
 
+    /// Session of a known token
+    pub fn session_id(&self, user_id: &str, token: &str) -> Option<String> {
+        let token_hash = token_hash(token);
+        self.users
+            .get(user_id)?
+            .iter()
+            .find(|item| item.token_hash == token_hash)
+            .map(|item| item.session_id.clone())
+    }
+
     /// Adds a token for a given user.
     /// If token is already known the expiry_date is updated.
     /// This can be used to reduce the expiry date of a token on the server, e.g. to reduce the
     /// token expiry time of a token that just has been refreshed.
-    pub fn put(&mut self, user_id: &str, password: &str, token: &str, expiry_date: usize) {
+    pub fn put(
+        &mut self,
+        user_id: &str,
+        password: &str,
+        session_id: &str,
+        token: &str,
+        expiry_date: usize,
+    ) -> Result<(), RepositoryError> {
         let now = Utc::now().timestamp() as usize;
         if expiry_date < now {
-            return;
+            return Ok(());
         }
+        let token_hash = token_hash(token);
+        self.persist(user_id, session_id, &token_hash, expiry_date)?;
+
         let user_tokens = match self.users.entry(user_id.to_string()) {
             Entry::Occupied(o) => o.into_mut(),
             Entry::Vacant(v) => v.insert(Vec::new()),
@@ -71,7 +134,6 @@ impl TokenBucket {
         user_tokens.retain(|item| item.expiry_date > now);
 
         // update existing or add new token
-        let token_hash = token_hash(token);
         let existing_token = user_tokens
             .iter_mut()
             .find(|item| item.token_hash == token_hash);
@@ -80,11 +142,41 @@ impl TokenBucket {
             None => {
                 user_tokens.push(TokenInfo {
                     token_hash,
+                    session_id: session_id.to_string(),
                     expiry_date,
                     password: password.to_string(),
                 });
             }
         }
+        Ok(())
+    }
+
+    fn persist(
+        &self,
+        user_id: &str,
+        session_id: &str,
+        token_hash: &str,
+        expiry_date: usize,
+    ) -> Result<(), RepositoryError> {
+        let Some(connection_manager) = &self.connection_manager else {
+            return Ok(());
+        };
+        let connection = connection_manager.connection()?;
+        let repo = UserSessionTokenRowRepository::new(&connection);
+        let now = Utc::now().naive_utc();
+        repo.delete_expired(now)?;
+
+        let created_datetime = repo
+            .find_one_by_id(token_hash)?
+            .map(|row| row.created_datetime)
+            .unwrap_or(now);
+        repo.upsert_one(&UserSessionTokenRow {
+            id: token_hash.to_string(),
+            session_id: session_id.to_string(),
+            user_id: user_id.to_string(),
+            created_datetime,
+            expiry_datetime: to_datetime(expiry_date),
+        })
     }
 
     pub fn get_password(&self, user_id: &str) -> String {
@@ -101,7 +193,27 @@ impl TokenBucket {
     }
 
     /// Removes all known tokens for a given user
-    pub fn clear(&mut self, user_id: &str) {
+    pub fn clear(&mut self, user_id: &str) -> Result<(), RepositoryError> {
+        if let Some(connection_manager) = &self.connection_manager {
+            UserSessionTokenRowRepository::new(&connection_manager.connection()?)
+                .delete_by_user_id(user_id)?;
+        }
         self.users.remove(user_id);
+        Ok(())
+    }
+
+    /// Removes all tokens of a session, returns false if the session isn't known
+    pub fn remove_session(&mut self, session_id: &str) -> Result<bool, RepositoryError> {
+        if let Some(connection_manager) = &self.connection_manager {
+            UserSessionTokenRowRepository::new(&connection_manager.connection()?)
+                .delete_by_session_id(session_id)?;
+        }
+        let mut removed = false;
+        for user_tokens in self.users.values_mut() {
+            let count = user_tokens.len();
+            user_tokens.retain(|item| item.session_id != session_id);
+            removed = removed || user_tokens.len() != count;
+        }
+        Ok(removed)
     }
 }
@@ -1,12 +1,17 @@
 use repository::{
     EqualFilter, KeyType, KeyValueStoreRepository, RepositoryError, StorageConnection,
-    TransactionError, User, UserAccountRow, UserAccountRowRepository, UserFilter,
-    UserPermissionFilter, UserPermissionRepository, UserPermissionRow, UserPermissionRowRepository,
-    UserRepository, UserStoreJoinRow, UserStoreJoinRowRepository,
+    StorePreferenceRow, StorePreferenceRowRepository, StoreRow, StoreRowRepository,
+    TransactionError, User, UserAccountRow, UserAccountRowRepository, UserAuthStateRow,
+    UserAuthStateRowRepository, UserFilter, UserPermissionFilter, UserPermissionRepository,
+    UserPermissionRow, UserPermissionRowRepository, UserRepository, UserStore, UserStoreJoinRow,
+    UserStoreJoinRowRepository,
 };
 use util::uuid::uuid;
 
+use crate::role::query::role_store_ids;
+
 use bcrypt::{hash, verify, BcryptError, DEFAULT_COST};
+use chrono::Utc;
 use log::{error, warn};
 
 pub struct CreateUserAccount {
@@ -145,6 +150,13 @@ impl<'a> UserAccountService<'a> {
                     ..UserAccountRow::default()
                 };
                 repo.insert_one(&row)?;
+                // Users created on this site aren't known to central, so the local password
+                // policies apply to them
+                UserAuthStateRowRepository::new(con).upsert_one(&UserAuthStateRow {
+                    is_locally_managed: true,
+                    password_changed_datetime: Some(Utc::now().naive_utc()),
+                    ..UserAuthStateRow::new(&row.id)
+                })?;
                 Ok(row)
             })
             .map_err(
@@ -157,6 +169,10 @@ impl<'a> UserAccountService<'a> {
             )
     }
 
+    /// Finds the user with the stores it can access on this site, either through
+    /// `user_store_join` or through a role with the `StoreAccess` permission.
+    /// Stores only reachable through a role have no `user_store_join` row, a placeholder join is
+    /// returned for them.
     pub fn find_user_active_on_this_site(
         &self,
         user_id: &str,
@@ -167,12 +183,53 @@ impl<'a> UserAccountService<'a> {
             .unwrap(); //TODO relocate to service
 
         let repo = UserRepository::new(self.connection);
-        repo.query_one(
+        let Some(mut user) = repo.query_one(
             UserFilter::new()
                 .id(EqualFilter::equal_to(user_id))
-                .hashed_password(EqualFilter::not_equal_to(""))
-                .site_id(EqualFilter::equal_to_i32(site_id)),
-        )
+                .hashed_password(EqualFilter::not_equal_to("")),
+        )?
+        else {
+            return Ok(None);
+        };
+        user.stores.retain(|store| store.store_row.site_id == site_id);
+
+        let role_store_ids: Vec<String> = role_store_ids(self.connection, user_id)?
+            .into_iter()
+            .filter(|store_id| !user.stores.iter().any(|s| &s.store_row.id == store_id))
+            .collect();
+        if !role_store_ids.is_empty() {
+            let store_rows: Vec<StoreRow> = StoreRowRepository::new(self.connection)
+                .find_many_by_id(&role_store_ids)?
+                .into_iter()
+                .filter(|store| store.site_id == site_id)
+                .collect();
+            let store_ids: Vec<String> = store_rows.iter().map(|s| s.id.clone()).collect();
+            let mut preferences =
+                StorePreferenceRowRepository::new(self.connection).find_many_by_id(&store_ids)?;
+
+            for store_row in store_rows {
+                let store_preferences = match preferences.iter().position(|p| p.id == store_row.id)
+                {
+                    Some(index) => preferences.swap_remove(index),
+                    None => StorePreferenceRow::default(),
+                };
+                user.stores.push(UserStore {
+                    user_store_join: UserStoreJoinRow {
+                        id: format!("{}_{}", user_id, store_row.id),
+                        user_id: user_id.to_string(),
+                        store_id: store_row.id.clone(),
+                        is_default: false,
+                    },
+                    store_row,
+                    store_preferences,
+                });
+            }
+        }
+
+        if user.stores.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(user))
     }
 
     /// Finds a user account and verifies that the password is ok
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDateTime, Utc};
+use repository::{RepositoryError, UserAccountRowRepository, UserSessionTokenRowRepository};
+
+use crate::{
+    auth_data::AuthData,
+    service_provider::ServiceContext,
+    settings::is_develop,
+    token::{JWTLogoutError, TokenService},
+};
+
+/// Login of a user, including all the token refreshes since the login
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserSession {
+    pub id: String,
+    pub user_id: String,
+    pub username: String,
+    pub created_datetime: NaiveDateTime,
+    /// Expiry of the latest refresh token of the session
+    pub expiry_datetime: NaiveDateTime,
+}
+
+#[derive(Debug)]
+pub enum RevokeUserSessionError {
+    UserSessionDoesNotExist,
+    InternalError(String),
+    DatabaseError(RepositoryError),
+}
+
+/// Sessions that haven't expired, most recent first
+pub fn get_user_sessions(ctx: &ServiceContext) -> Result<Vec<UserSession>, RepositoryError> {
+    let tokens = UserSessionTokenRowRepository::new(&ctx.connection)
+        .find_unexpired(Utc::now().naive_utc())?;
+
+    let mut sessions: HashMap<String, UserSession> = HashMap::new();
+    for token in tokens {
+        let session = sessions
+            .entry(token.session_id.clone())
+            .or_insert_with(|| UserSession {
+                id: token.session_id,
+                user_id: token.user_id,
+                username: String::new(),
+                created_datetime: token.created_datetime,
+                expiry_datetime: token.expiry_datetime,
+            });
+        session.created_datetime = session.created_datetime.min(token.created_datetime);
+        session.expiry_datetime = session.expiry_datetime.max(token.expiry_datetime);
+    }
+
+    let user_ids: Vec<String> = sessions
+        .values()
+        .map(|session| session.user_id.clone())
+        .collect();
+    let usernames: HashMap<String, String> = UserAccountRowRepository::new(&ctx.connection)
+        .find_many_by_id(&user_ids)?
+        .into_iter()
+        .map(|user| (user.id, user.username))
+        .collect();
+
+    let mut result: Vec<UserSession> = sessions
+        .into_values()
+        .map(|mut session| {
+            session.username = usernames.get(&session.user_id).cloned().unwrap_or_default();
+            session
+        })
+        .collect();
+    result.sort_by(|a, b| b.created_datetime.cmp(&a.created_datetime));
+    Ok(result)
+}
+
+/// Logs out a single session, its auth and refresh tokens are rejected straight away
+pub fn revoke_user_session(
+    auth_data: &AuthData,
+    session_id: &str,
+) -> Result<String, RevokeUserSessionError> {
+    let mut service = TokenService::new(
+        &auth_data.token_bucket,
+        auth_data.auth_token_secret.as_bytes(),
+        !is_develop(),
+    );
+    let revoked = service
+        .revoke_session(session_id)
+        .map_err(|error| match error {
+            JWTLogoutError::ConcurrencyLockError(error) => {
+                RevokeUserSessionError::InternalError(error.to_string())
+            }
+            JWTLogoutError::DatabaseError(error) => RevokeUserSessionError::DatabaseError(error),
+        })?;
+    if !revoked {
+        return Err(RevokeUserSessionError::UserSessionDoesNotExist);
+    }
+    Ok(session_id.to_string())
+}
+
+pub trait UserSessionServiceTrait: Sync + Send {
+    fn get_user_sessions(&self, ctx: &ServiceContext) -> Result<Vec<UserSession>, RepositoryError> {
+        get_user_sessions(ctx)
+    }
+
+    fn revoke_user_session(
+        &self,
+        auth_data: &AuthData,
+        session_id: &str,
+    ) -> Result<String, RevokeUserSessionError> {
+        revoke_user_session(auth_data, session_id)
+    }
+}
+
+pub struct UserSessionService {}
+impl UserSessionServiceTrait for UserSessionService {}
@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use repository::{EqualFilter, RepositoryError, StoreFilter, StoreRepository};
+use serde::Serialize;
+use util::{to_csv, uuid::uuid};
+
+use crate::{
+    report::html_printing::html_to_pdf,
+    service_provider::ServiceContext,
+    static_files::{StaticFileCategory, StaticFileService},
+};
+
+use super::query::{get_defaulters, DefaulterArea, DefaulterListInput, DefaulterStatus};
+
+const DEFAULTER_LIST_TEMPLATE: &str = include_str!("templates/defaulter_list.html");
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DefaulterExportFormat {
+    Csv,
+    /// Printable list for outreach teams
+    Html,
+    Pdf,
+}
+
+#[derive(Debug)]
+pub enum ExportDefaultersError {
+    DatabaseError(RepositoryError),
+    FileGenerationError(String),
+}
+
+#[derive(Serialize)]
+struct DefaulterListTemplateData<'a> {
+    store_name: String,
+    as_of: String,
+    due_within_days: i64,
+    areas: &'a [DefaulterArea],
+}
+
+/// Exports the store's defaulter list and returns the file id
+pub fn export_defaulters(
+    ctx: &ServiceContext,
+    base_dir: &Option<String>,
+    input: DefaulterListInput,
+    format: DefaulterExportFormat,
+) -> Result<String, ExportDefaultersError> {
+    let as_of = input.as_of;
+    let due_within_days = input.due_within_days;
+    let areas = get_defaulters(ctx, input)?;
+
+    let (extension, content) = match format {
+        DefaulterExportFormat::Csv => ("csv", defaulters_to_csv(&areas).into_bytes()),
+        DefaulterExportFormat::Html | DefaulterExportFormat::Pdf => {
+            let store_name = StoreRepository::new(&ctx.connection)
+                .query_one(StoreFilter::new().id(EqualFilter::equal_to(&ctx.store_id)))?
+                .map(|store| store.name_row.name)
+                .unwrap_or_default();
+            let data = DefaulterListTemplateData {
+                store_name,
+                as_of: as_of.to_string(),
+                due_within_days,
+                areas: &areas,
+            };
+            let html = render_defaulter_list(&data)?;
+
+            if format == DefaulterExportFormat::Html {
+                ("html", html.into_bytes())
+            } else {
+                let pdf = html_to_pdf(base_dir, &html, &uuid())
+                    .map_err(|err| ExportDefaultersError::FileGenerationError(err.to_string()))?;
+                ("pdf", pdf)
+            }
+        }
+    };
+
+    let file_service = StaticFileService::new(base_dir)
+        .map_err(|err| ExportDefaultersError::FileGenerationError(format!("{}", err)))?;
+    let now: DateTime<Utc> = Utc::now();
+    let file = file_service
+        .store_file(
+            &format!(
+                "{}_immunisation_defaulters.{}",
+                now.format("%Y%m%d_%H%M%S"),
+                extension
+            ),
+            StaticFileCategory::Temporary,
+            &content,
+        )
+        .map_err(|err| ExportDefaultersError::FileGenerationError(format!("{}", err)))?;
+    Ok(file.id)
+}
+
+fn render_defaulter_list(
+    data: &DefaulterListTemplateData,
+) -> Result<String, ExportDefaultersError> {
+    let context = tera::Context::from_serialize(data)
+        .map_err(|err| ExportDefaultersError::FileGenerationError(err.to_string()))?;
+    tera::Tera::one_off(DEFAULTER_LIST_TEMPLATE, &context, true)
+        .map_err(|err| ExportDefaultersError::FileGenerationError(err.to_string()))
+}
+
+fn defaulters_to_csv(areas: &[DefaulterArea]) -> String {
+    let headers = [
+        "area",
+        "patient_code",
+        "patient_name",
+        "date_of_birth",
+        "phone",
+        "program",
+        "dose",
+        "due_date",
+        "days_overdue",
+        "status",
+    ];
+
+    let rows: Vec<Vec<String>> = areas
+        .iter()
+        .flat_map(|area| area.defaulters.iter())
+        .map(|defaulter| {
+            vec![
+                defaulter.area.clone().unwrap_or_default(),
+                defaulter.patient_code.clone(),
+                defaulter.patient_name.clone(),
+                defaulter
+                    .date_of_birth
+                    .map(|date| date.to_string())
+                    .unwrap_or_default(),
+                defaulter.phone.clone().unwrap_or_default(),
+                defaulter.program_name.clone(),
+                defaulter.dose_label.clone(),
+                defaulter.due_date.to_string(),
+                defaulter.days_overdue.to_string(),
+                match defaulter.status {
+                    DefaulterStatus::Overdue => "OVERDUE",
+                    DefaulterStatus::DueSoon => "DUE_SOON",
+                }
+                .to_string(),
+            ]
+        })
+        .collect();
+
+    to_csv(&headers, &rows)
+}
+
+impl From<RepositoryError> for ExportDefaultersError {
+    fn from(error: RepositoryError) -> Self {
+        ExportDefaultersError::DatabaseError(error)
+    }
+}
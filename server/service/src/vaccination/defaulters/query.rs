@@ -0,0 +1,187 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Duration, NaiveDate};
+use repository::{
+    EqualFilter, NameRow, ProgramEnrolmentFilter, ProgramEnrolmentRepository, RepositoryError,
+    StringFilter, VaccinationCardRepository, VaccinationCardRow,
+};
+use serde::Serialize;
+use util::constants::DAYS_PER_MONTH;
+
+use crate::{
+    service_provider::ServiceContext, vaccination::get_vaccination_card::get_suggested_date,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefaulterListInput {
+    /// Date doses are checked against, usually today
+    pub as_of: NaiveDate,
+    /// Also include doses becoming due within this many days of `as_of`
+    pub due_within_days: i64,
+    pub program_id: Option<String>,
+    pub enrolment_status: Option<StringFilter>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DefaulterStatus {
+    Overdue,
+    DueSoon,
+}
+
+/// A patient's next dose in a vaccine course that is overdue or due soon
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Defaulter {
+    pub patient_id: String,
+    pub patient_code: String,
+    pub patient_name: String,
+    pub date_of_birth: Option<NaiveDate>,
+    pub phone: Option<String>,
+    pub area: Option<String>,
+    pub program_enrolment_id: String,
+    pub program_name: String,
+    pub vaccine_course_id: String,
+    pub vaccine_course_dose_id: String,
+    pub dose_label: String,
+    pub due_date: NaiveDate,
+    /// Negative when the dose isn't due yet
+    pub days_overdue: i64,
+    pub status: DefaulterStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DefaulterArea {
+    /// Village or area of the patients, `None` for patients without an address
+    pub area: Option<String>,
+    pub defaulters: Vec<Defaulter>,
+}
+
+/// Patients of the store, enrolled in an immunisation program, that are overdue or due soon for
+/// a dose, grouped by the area they live in.
+/// Doses the patient has grown out of (older than the dose's max age) are not included.
+pub fn get_defaulters(
+    ctx: &ServiceContext,
+    input: DefaulterListInput,
+) -> Result<Vec<DefaulterArea>, RepositoryError> {
+    let connection = &ctx.connection;
+    let DefaulterListInput {
+        as_of,
+        due_within_days,
+        program_id,
+        enrolment_status,
+    } = input;
+
+    let mut enrolment_filter = ProgramEnrolmentFilter::new()
+        .is_immunisation_program(true)
+        .patient_store_id(&ctx.store_id);
+    if let Some(program_id) = program_id {
+        enrolment_filter = enrolment_filter.program_id(EqualFilter::equal_to(&program_id));
+    }
+    if let Some(status) = enrolment_status {
+        enrolment_filter = enrolment_filter.status(status);
+    }
+    let enrolments: Vec<_> = ProgramEnrolmentRepository::new(connection)
+        .query_by_filter(enrolment_filter.clone())?
+        .into_iter()
+        .filter(|enrolment| {
+            !enrolment.patient_row.is_deceased && enrolment.patient_row.deleted_datetime.is_none()
+        })
+        .collect();
+
+    let mut cards: HashMap<String, Vec<VaccinationCardRow>> = HashMap::new();
+    for row in
+        VaccinationCardRepository::new(connection).query_by_enrolment_filter(enrolment_filter)?
+    {
+        cards
+            .entry(row.program_enrolment_id.clone())
+            .or_default()
+            .push(row);
+    }
+
+    let due_by = as_of + Duration::days(due_within_days);
+    let mut areas: BTreeMap<Option<String>, Vec<Defaulter>> = BTreeMap::new();
+
+    for enrolment in enrolments {
+        let Some(card) = cards.remove(&enrolment.row.id) else {
+            continue;
+        };
+        let patient = &enrolment.patient_row;
+
+        for row in card.iter() {
+            if is_past_max_age(row, patient.date_of_birth, as_of) {
+                continue;
+            }
+
+            let course_rows = card
+                .iter()
+                .filter(|course_row| course_row.vaccine_course_id == row.vaccine_course_id)
+                .cloned()
+                .collect();
+            let Some(due_date) = get_suggested_date(row, patient.date_of_birth, course_rows) else {
+                continue;
+            };
+            if due_date > due_by {
+                continue;
+            }
+
+            let area = patient_area(patient);
+            areas.entry(area.clone()).or_default().push(Defaulter {
+                patient_id: patient.id.clone(),
+                patient_code: patient.code.clone(),
+                patient_name: patient.name.clone(),
+                date_of_birth: patient.date_of_birth,
+                phone: patient.phone.clone(),
+                area,
+                program_enrolment_id: enrolment.row.id.clone(),
+                program_name: enrolment.program_row.name.clone(),
+                vaccine_course_id: row.vaccine_course_id.clone(),
+                vaccine_course_dose_id: row.vaccine_course_dose_id.clone(),
+                dose_label: row.label.clone(),
+                due_date,
+                days_overdue: (as_of - due_date).num_days(),
+                status: if due_date < as_of {
+                    DefaulterStatus::Overdue
+                } else {
+                    DefaulterStatus::DueSoon
+                },
+            });
+        }
+    }
+
+    Ok(areas
+        .into_iter()
+        .map(|(area, mut defaulters)| {
+            defaulters.sort_by(|a, b| {
+                a.due_date
+                    .cmp(&b.due_date)
+                    .then_with(|| a.patient_name.cmp(&b.patient_name))
+            });
+            DefaulterArea { area, defaulters }
+        })
+        .collect())
+}
+
+fn is_past_max_age(
+    row: &VaccinationCardRow,
+    date_of_birth: Option<NaiveDate>,
+    as_of: NaiveDate,
+) -> bool {
+    match date_of_birth {
+        Some(date_of_birth) if row.max_age > 0.0 => {
+            ((as_of - date_of_birth).num_days() as f64) / DAYS_PER_MONTH > row.max_age
+        }
+        _ => false,
+    }
+}
+
+/// Village or area from the patient's address, the second address line is preferred as the
+/// first usually holds the street address
+fn patient_area(patient: &NameRow) -> Option<String> {
+    patient
+        .address2
+        .iter()
+        .chain(patient.address1.iter())
+        .map(|address| address.trim())
+        .find(|address| !address.is_empty())
+        .map(str::to_string)
+}
@@ -0,0 +1,254 @@
+use chrono::Utc;
+use repository::{
+    EqualFilter, RepositoryError, StoreFilter, StoreRepository, TransactionError,
+    VaccinationReminderRow, VaccinationReminderRowRepository, VaccinationReminderStatus,
+};
+use util::uuid::uuid;
+
+use crate::{service_provider::ServiceContext, sms_gateway::SmsGateway};
+
+use super::query::{get_defaulters, Defaulter, DefaulterListInput};
+
+pub const DEFAULT_REMINDER_MESSAGE: &str =
+    "Reminder: {patient_name} is due for {dose_label} on {due_date}. Please visit {store_name}.";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnqueueVaccinationReminders {
+    pub defaulters: DefaulterListInput,
+    /// Message with `{patient_name}`, `{dose_label}`, `{due_date}` and `{store_name}`
+    /// placeholders, `DEFAULT_REMINDER_MESSAGE` is used when not set
+    pub message_template: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SendVaccinationRemindersResult {
+    pub sent: u32,
+    pub failed: u32,
+}
+
+/// Queues an SMS reminder for each defaulter with a phone number. A dose isn't queued again for
+/// the same due date unless the previous reminder failed.
+pub fn enqueue_vaccination_reminders(
+    ctx: &ServiceContext,
+    input: EnqueueVaccinationReminders,
+) -> Result<Vec<VaccinationReminderRow>, RepositoryError> {
+    let EnqueueVaccinationReminders {
+        defaulters,
+        message_template,
+    } = input;
+    let message_template = message_template.unwrap_or_else(|| DEFAULT_REMINDER_MESSAGE.to_string());
+
+    let result: Result<_, TransactionError<RepositoryError>> =
+        ctx.connection.transaction_sync(|connection| {
+            let store_name = StoreRepository::new(connection)
+                .query_one(StoreFilter::new().id(EqualFilter::equal_to(&ctx.store_id)))?
+                .map(|store| store.name_row.name)
+                .unwrap_or_default();
+            let repository = VaccinationReminderRowRepository::new(connection);
+            let now = Utc::now().naive_utc();
+
+            let mut result = Vec::new();
+            for area in get_defaulters(ctx, defaulters)? {
+                for defaulter in area.defaulters {
+                    let Some(phone_number) = defaulter
+                        .phone
+                        .as_ref()
+                        .map(|phone| phone.trim())
+                        .filter(|phone| !phone.is_empty())
+                    else {
+                        continue;
+                    };
+
+                    let already_reminded = repository
+                        .find_many_by_dose(
+                            &defaulter.program_enrolment_id,
+                            &defaulter.vaccine_course_dose_id,
+                        )?
+                        .into_iter()
+                        .any(|reminder| {
+                            reminder.due_date == defaulter.due_date
+                                && reminder.status != VaccinationReminderStatus::Failed
+                        });
+                    if already_reminded {
+                        continue;
+                    }
+
+                    let reminder = VaccinationReminderRow {
+                        id: uuid(),
+                        store_id: ctx.store_id.clone(),
+                        program_enrolment_id: defaulter.program_enrolment_id.clone(),
+                        vaccine_course_dose_id: defaulter.vaccine_course_dose_id.clone(),
+                        patient_link_id: defaulter.patient_id.clone(),
+                        phone_number: phone_number.to_string(),
+                        message: reminder_message(&message_template, &defaulter, &store_name),
+                        due_date: defaulter.due_date,
+                        status: VaccinationReminderStatus::Pending,
+                        created_datetime: now,
+                        sent_datetime: None,
+                        error_message: None,
+                    };
+                    repository.upsert_one(&reminder)?;
+                    result.push(reminder);
+                }
+            }
+
+            Ok(result)
+        });
+    result.map_err(|error| error.to_inner_error())
+}
+
+/// Sends the store's pending reminders through the gateway, reminders that fail to send are
+/// marked as failed with the gateway error
+pub fn send_vaccination_reminders(
+    ctx: &ServiceContext,
+    gateway: &dyn SmsGateway,
+) -> Result<SendVaccinationRemindersResult, RepositoryError> {
+    let repository = VaccinationReminderRowRepository::new(&ctx.connection);
+    let pending = repository
+        .find_many_by_store_id_and_status(&ctx.store_id, VaccinationReminderStatus::Pending)?;
+
+    let mut result = SendVaccinationRemindersResult::default();
+    for mut reminder in pending {
+        match gateway.send_sms(&reminder.phone_number, &reminder.message) {
+            Ok(()) => {
+                reminder.status = VaccinationReminderStatus::Sent;
+                reminder.sent_datetime = Some(Utc::now().naive_utc());
+                reminder.error_message = None;
+                result.sent += 1;
+            }
+            Err(error) => {
+                reminder.status = VaccinationReminderStatus::Failed;
+                reminder.error_message = Some(format!("{:?}", error));
+                result.failed += 1;
+            }
+        }
+        repository.upsert_one(&reminder)?;
+    }
+
+    Ok(result)
+}
+
+fn reminder_message(template: &str, defaulter: &Defaulter, store_name: &str) -> String {
+    template
+        .replace("{patient_name}", &defaulter.patient_name)
+        .replace("{dose_label}", &defaulter.dose_label)
+        .replace("{due_date}", &defaulter.due_date.to_string())
+        .replace("{store_name}", store_name)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{mock_patient, mock_store_a, mock_vaccine_course_a_dose_b, MockDataInserts},
+        NameRow, NameRowRepository, VaccinationReminderRowRepository, VaccinationReminderStatus,
+    };
+
+    use crate::{
+        sms_gateway::StubSmsGateway,
+        test_helpers::{setup_all_and_service_provider, ServiceTestContext},
+        vaccination::defaulters::query::{get_defaulters, DefaulterListInput, DefaulterStatus},
+    };
+
+    use super::{
+        enqueue_vaccination_reminders, send_vaccination_reminders, EnqueueVaccinationReminders,
+    };
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn defaulter_input(as_of: NaiveDate, due_within_days: i64) -> DefaulterListInput {
+        DefaulterListInput {
+            as_of,
+            due_within_days,
+            program_id: None,
+            enrolment_status: None,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn vaccination_defaulters_and_reminders() {
+        let ServiceTestContext {
+            connection,
+            service_provider,
+            ..
+        } = setup_all_and_service_provider(
+            "vaccination_defaulters_and_reminders",
+            MockDataInserts::all(),
+        )
+        .await;
+        let service_context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+
+        NameRowRepository::new(&connection)
+            .upsert_one(&NameRow {
+                date_of_birth: Some(date(1, 1)),
+                phone: Some("021 555 0100".to_string()),
+                address2: Some("Village A".to_string()),
+                ..mock_patient()
+            })
+            .unwrap();
+
+        // Dose A was given, dose B is due on 2024-01-31
+        let areas = get_defaulters(&service_context, defaulter_input(date(2, 15), 0)).unwrap();
+        assert_eq!(areas.len(), 1);
+        assert_eq!(areas[0].area, Some("Village A".to_string()));
+        let defaulter = &areas[0].defaulters[0];
+        assert_eq!(defaulter.patient_id, mock_patient().id);
+        assert_eq!(
+            defaulter.vaccine_course_dose_id,
+            mock_vaccine_course_a_dose_b().id
+        );
+        assert_eq!(defaulter.due_date, date(1, 31));
+        assert_eq!(defaulter.days_overdue, 15);
+        assert_eq!(defaulter.status, DefaulterStatus::Overdue);
+
+        // Not due yet
+        let areas = get_defaulters(&service_context, defaulter_input(date(1, 20), 7)).unwrap();
+        assert_eq!(areas, vec![]);
+        // Due within the next two weeks
+        let areas = get_defaulters(&service_context, defaulter_input(date(1, 20), 14)).unwrap();
+        assert_eq!(areas[0].defaulters[0].status, DefaulterStatus::DueSoon);
+        // Patient is past the dose's max age
+        let areas = get_defaulters(&service_context, defaulter_input(date(6, 1), 0)).unwrap();
+        assert_eq!(areas, vec![]);
+
+        // Reminders
+        let input = EnqueueVaccinationReminders {
+            defaulters: defaulter_input(date(2, 15), 0),
+            message_template: Some("{patient_name}: {dose_label} due {due_date}".to_string()),
+        };
+        let reminders = enqueue_vaccination_reminders(&service_context, input.clone()).unwrap();
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].store_id, mock_store_a().id);
+        assert_eq!(reminders[0].phone_number, "021 555 0100");
+        assert_eq!(reminders[0].status, VaccinationReminderStatus::Pending);
+        // Not queued twice
+        assert_eq!(
+            enqueue_vaccination_reminders(&service_context, input).unwrap(),
+            vec![]
+        );
+
+        let gateway = StubSmsGateway::new();
+        let result = send_vaccination_reminders(&service_context, &gateway).unwrap();
+        assert_eq!(result.sent, 1);
+        assert_eq!(result.failed, 0);
+        let sent = gateway.sent_messages();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].phone_number, "021 555 0100");
+        assert_eq!(sent[0].message, reminders[0].message);
+
+        let reminder = VaccinationReminderRowRepository::new(&connection)
+            .find_one_by_id(&reminders[0].id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(reminder.status, VaccinationReminderStatus::Sent);
+        assert!(reminder.sent_datetime.is_some());
+
+        // Nothing left to send
+        let result = send_vaccination_reminders(&service_context, &gateway).unwrap();
+        assert_eq!(result.sent, 0);
+    }
+}
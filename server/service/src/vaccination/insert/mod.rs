@@ -7,7 +7,7 @@ use crate::{
     service_provider::ServiceContext,
 };
 
-use chrono::NaiveDate;
+use chrono::{NaiveDate, Utc};
 use repository::{ActivityLogType, RepositoryError, Vaccination, VaccinationRowRepository};
 
 mod generate;
@@ -16,7 +16,7 @@ mod validate;
 use generate::{generate, GenerateInput, GenerateResult};
 use validate::validate;
 
-use super::{generate::CreatePrescription, query::get_vaccination};
+use super::{generate::CreatePrescription, query::get_vaccination, vial::draw::draw_vaccine_dose};
 
 #[derive(PartialEq, Debug)]
 pub enum InsertVaccinationError {
@@ -89,6 +89,10 @@ pub fn insert_vaccination(
                 insert_stock_out_line(ctx, insert_stock_out_line_input)?;
                 // Finalise the prescription - also link clinician
                 update_prescription(ctx, finalise_prescription)?;
+                // Record the dose against an open vial of the stock line
+                if let Some(stock_line_id) = &vaccination.stock_line_id {
+                    draw_vaccine_dose(connection, store_id, stock_line_id, Utc::now().naive_utc())?;
+                }
             }
 
             activity_log_entry(
@@ -135,6 +139,7 @@ impl From<UpdatePrescriptionError> for InsertVaccinationError {
 
 #[cfg(test)]
 mod insert {
+    use chrono::{Duration, Utc};
     use repository::mock::{
         mock_encounter_a, mock_immunisation_encounter_a, mock_name_1, mock_patient_b,
         mock_program_a, mock_stock_line_a, mock_stock_line_vaccine_item_a, mock_store_a,
@@ -143,8 +148,8 @@ mod insert {
     };
     use repository::test_db::{setup_all, setup_all_with_data};
     use repository::{
-        EncounterRow, InvoiceFilter, InvoiceRepository, InvoiceStatus, InvoiceType,
-        StockLineRowRepository,
+        EncounterRow, EqualFilter, InvoiceFilter, InvoiceRepository, InvoiceStatus, InvoiceType,
+        StockLineRowRepository, VaccineVialOpeningFilter, VaccineVialOpeningRepository,
     };
 
     use crate::service_provider::ServiceProvider;
@@ -451,6 +456,17 @@ mod insert {
         // 5 doses per unit, 2 units per pack. 1 dose given, was 5.0, so 4.9 left
         assert_eq!(stock_line.available_number_of_packs, 4.9);
 
+        // Dose was drawn from a newly opened vial
+        let vials = VaccineVialOpeningRepository::new(&context.connection)
+            .query_by_filter(
+                VaccineVialOpeningFilter::new()
+                    .stock_line_id(EqualFilter::equal_to(&mock_stock_line_vaccine_item_a().id)),
+            )
+            .unwrap();
+        assert_eq!(vials.len(), 1);
+        assert_eq!(vials[0].doses_drawn, 1);
+        assert!(Utc::now().naive_utc() - vials[0].opened_datetime < Duration::minutes(1));
+
         // Can create - dose not given
         let result = service_provider
             .vaccination_service
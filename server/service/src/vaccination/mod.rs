@@ -1,14 +1,26 @@
+use defaulters::{
+    export::{DefaulterExportFormat, ExportDefaultersError},
+    query::{DefaulterArea, DefaulterListInput},
+    reminders::{EnqueueVaccinationReminders, SendVaccinationRemindersResult},
+};
 use get_vaccination_card::VaccinationCard;
-use repository::{RepositoryError, Vaccination};
+use repository::{RepositoryError, Vaccination, VaccinationReminderRow, VaccineVialOpeningRow};
+use vial::{
+    discard::{DiscardExpiredVaccineVials, DiscardVaccineVial, DiscardVaccineVialError},
+    open::{OpenVaccineVial, OpenVaccineVialError},
+    wastage::{VaccineWastageInput, VaccineWastageRate},
+};
 
-use crate::service_provider::ServiceContext;
+use crate::{service_provider::ServiceContext, sms_gateway::SmsGateway};
 
+pub mod defaulters;
 mod generate;
 pub mod get_vaccination_card;
 pub mod insert;
 pub mod query;
 pub mod update;
 mod validate;
+pub mod vial;
 
 pub trait VaccinationServiceTrait: Sync + Send {
     fn get_vaccination(
@@ -44,6 +56,72 @@ pub trait VaccinationServiceTrait: Sync + Send {
     ) -> Result<Vaccination, update::UpdateVaccinationError> {
         update::update_vaccination(ctx, store_id, input)
     }
+
+    fn get_defaulters(
+        &self,
+        ctx: &ServiceContext,
+        input: DefaulterListInput,
+    ) -> Result<Vec<DefaulterArea>, RepositoryError> {
+        defaulters::query::get_defaulters(ctx, input)
+    }
+
+    fn export_defaulters(
+        &self,
+        ctx: &ServiceContext,
+        base_dir: &Option<String>,
+        input: DefaulterListInput,
+        format: DefaulterExportFormat,
+    ) -> Result<String, ExportDefaultersError> {
+        defaulters::export::export_defaulters(ctx, base_dir, input, format)
+    }
+
+    fn enqueue_vaccination_reminders(
+        &self,
+        ctx: &ServiceContext,
+        input: EnqueueVaccinationReminders,
+    ) -> Result<Vec<VaccinationReminderRow>, RepositoryError> {
+        defaulters::reminders::enqueue_vaccination_reminders(ctx, input)
+    }
+
+    fn send_vaccination_reminders(
+        &self,
+        ctx: &ServiceContext,
+        gateway: &dyn SmsGateway,
+    ) -> Result<SendVaccinationRemindersResult, RepositoryError> {
+        defaulters::reminders::send_vaccination_reminders(ctx, gateway)
+    }
+
+    fn open_vaccine_vial(
+        &self,
+        ctx: &ServiceContext,
+        input: OpenVaccineVial,
+    ) -> Result<VaccineVialOpeningRow, OpenVaccineVialError> {
+        vial::open::open_vaccine_vial(ctx, input)
+    }
+
+    fn discard_vaccine_vial(
+        &self,
+        ctx: &ServiceContext,
+        input: DiscardVaccineVial,
+    ) -> Result<VaccineVialOpeningRow, DiscardVaccineVialError> {
+        vial::discard::discard_vaccine_vial(ctx, input)
+    }
+
+    fn discard_expired_vaccine_vials(
+        &self,
+        ctx: &ServiceContext,
+        input: DiscardExpiredVaccineVials,
+    ) -> Result<Vec<VaccineVialOpeningRow>, DiscardVaccineVialError> {
+        vial::discard::discard_expired_vaccine_vials(ctx, input)
+    }
+
+    fn get_vaccine_wastage(
+        &self,
+        ctx: &ServiceContext,
+        input: VaccineWastageInput,
+    ) -> Result<Vec<VaccineWastageRate>, RepositoryError> {
+        vial::wastage::get_vaccine_wastage(ctx, input)
+    }
 }
 
 pub struct VaccinationService {}
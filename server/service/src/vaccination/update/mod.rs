@@ -12,7 +12,7 @@ use crate::{
     NullableUpdate,
 };
 
-use chrono::NaiveDate;
+use chrono::{NaiveDate, Utc};
 use repository::{ActivityLogType, RepositoryError, Vaccination, VaccinationRowRepository};
 
 mod generate;
@@ -21,7 +21,7 @@ mod validate;
 use generate::{generate, CreateCustomerReturn, GenerateInput, GenerateResult};
 use validate::{validate, ValidateResult};
 
-use super::{generate::CreatePrescription, query::get_vaccination};
+use super::{generate::CreatePrescription, query::get_vaccination, vial::draw::draw_vaccine_dose};
 
 #[derive(PartialEq, Debug)]
 pub enum UpdateVaccinationError {
@@ -109,6 +109,10 @@ pub fn update_vaccination(
                 insert_stock_out_line(ctx, insert_stock_out_line_input)?;
                 // Finalise the prescription - also link clinician
                 update_prescription(ctx, finalise_prescription)?;
+                // Record the dose against an open vial of the stock line
+                if let Some(stock_line_id) = &vaccination.stock_line_id {
+                    draw_vaccine_dose(connection, store_id, stock_line_id, Utc::now().naive_utc())?;
+                }
             }
 
             activity_log_entry(
@@ -171,7 +175,7 @@ impl From<UpdatePrescriptionError> for UpdateVaccinationError {
 
 #[cfg(test)]
 mod update {
-    use chrono::NaiveDate;
+    use chrono::{Duration, NaiveDate, Utc};
     use repository::mock::{
         mock_immunisation_encounter_a, mock_immunisation_program_enrolment_a, mock_stock_line_a,
         mock_stock_line_b_vaccine_item_a, mock_stock_line_vaccine_item_a, mock_store_a,
@@ -180,8 +184,9 @@ mod update {
     };
     use repository::test_db::{setup_all, setup_all_with_data};
     use repository::{
-        InvoiceFilter, InvoiceRepository, InvoiceType, StockLineRowRepository, VaccinationRow,
-        VaccinationRowRepository,
+        EqualFilter, InvoiceFilter, InvoiceRepository, InvoiceType, StockLineRowRepository,
+        VaccinationRow, VaccinationRowRepository, VaccineVialOpeningFilter,
+        VaccineVialOpeningRepository,
     };
 
     use crate::service_provider::ServiceProvider;
@@ -497,6 +502,18 @@ mod update {
         // 5 doses per unit, 2 units per pack. 1 dose given, was 5.0, so 4.9 left
         assert_eq!(stock_line.available_number_of_packs, 4.9);
 
+        // Dose was drawn from a newly opened vial, opened when the vaccination was given rather
+        // than when it was created
+        let vials = VaccineVialOpeningRepository::new(&context.connection)
+            .query_by_filter(
+                VaccineVialOpeningFilter::new()
+                    .stock_line_id(EqualFilter::equal_to(&mock_stock_line_vaccine_item_a().id)),
+            )
+            .unwrap();
+        assert_eq!(vials.len(), 1);
+        assert_eq!(vials[0].doses_drawn, 1);
+        assert!(Utc::now().naive_utc() - vials[0].opened_datetime < Duration::minutes(1));
+
         // ----------------------------
         // Update: Change stock_line
         // ----------------------------
@@ -0,0 +1,145 @@
+use chrono::{NaiveDateTime, Utc};
+use repository::{
+    DatetimeFilter, EqualFilter, RepositoryError, StockLineRowRepository, VaccineVialOpeningFilter,
+    VaccineVialOpeningRepository, VaccineVialOpeningRow, VaccineVialOpeningRowRepository,
+    VaccineVialStatus,
+};
+
+use crate::{
+    invoice::inventory_adjustment::{
+        insert_inventory_adjustment, AdjustmentType, InsertInventoryAdjustment,
+        InsertInventoryAdjustmentError,
+    },
+    service_provider::ServiceContext,
+};
+
+#[derive(PartialEq, Debug)]
+pub enum DiscardVaccineVialError {
+    VialOpeningDoesNotExist,
+    VialOpeningDoesNotBelongToCurrentStore,
+    VialIsNotOpen,
+    InventoryAdjustmentError(InsertInventoryAdjustmentError),
+    DatabaseError(RepositoryError),
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct DiscardVaccineVial {
+    pub id: String,
+    /// Wastage reason for the inventory adjustment of the doses left in the vial
+    pub inventory_adjustment_reason_id: Option<String>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct DiscardExpiredVaccineVials {
+    /// Vials whose open vial period ended at or before this time are discarded
+    pub as_of: NaiveDateTime,
+    pub inventory_adjustment_reason_id: Option<String>,
+}
+
+pub fn discard_vaccine_vial(
+    ctx: &ServiceContext,
+    input: DiscardVaccineVial,
+) -> Result<VaccineVialOpeningRow, DiscardVaccineVialError> {
+    let vial_opening = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let vial = VaccineVialOpeningRowRepository::new(connection)
+                .find_one_by_id(&input.id)?
+                .ok_or(DiscardVaccineVialError::VialOpeningDoesNotExist)?;
+            if vial.store_id != ctx.store_id {
+                return Err(DiscardVaccineVialError::VialOpeningDoesNotBelongToCurrentStore);
+            }
+            if vial.status != VaccineVialStatus::Open {
+                return Err(DiscardVaccineVialError::VialIsNotOpen);
+            }
+
+            discard(
+                ctx,
+                vial,
+                Utc::now().naive_utc(),
+                input.inventory_adjustment_reason_id,
+            )
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(vial_opening)
+}
+
+/// Discards the store's open vials that are past their open vial policy period
+pub fn discard_expired_vaccine_vials(
+    ctx: &ServiceContext,
+    input: DiscardExpiredVaccineVials,
+) -> Result<Vec<VaccineVialOpeningRow>, DiscardVaccineVialError> {
+    let vial_openings = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let expired = VaccineVialOpeningRepository::new(connection).query_by_filter(
+                VaccineVialOpeningFilter::new()
+                    .store_id(EqualFilter::equal_to(&ctx.store_id))
+                    .status(VaccineVialStatus::Open.equal_to())
+                    .discard_after_datetime(DatetimeFilter::before_or_equal_to(input.as_of)),
+            )?;
+
+            expired
+                .into_iter()
+                .map(|vial| {
+                    discard(
+                        ctx,
+                        vial,
+                        input.as_of,
+                        input.inventory_adjustment_reason_id.clone(),
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(vial_openings)
+}
+
+/// Closes the vial and removes the doses left in it from stock
+fn discard(
+    ctx: &ServiceContext,
+    mut vial: VaccineVialOpeningRow,
+    datetime: NaiveDateTime,
+    inventory_adjustment_reason_id: Option<String>,
+) -> Result<VaccineVialOpeningRow, DiscardVaccineVialError> {
+    let wasted_doses = (vial.doses_per_vial - vial.doses_drawn).max(0);
+
+    if wasted_doses > 0 {
+        let stock_line = StockLineRowRepository::new(&ctx.connection)
+            .find_one_by_id(&vial.stock_line_id)?
+            .ok_or(RepositoryError::NotFound)?;
+        // Doses are drawn as a fraction of a pack, see get_dose_as_number_of_packs
+        let number_of_packs =
+            (wasted_doses as f64 / vial.doses_per_vial as f64 / stock_line.pack_size)
+                .min(stock_line.available_number_of_packs);
+
+        if number_of_packs > 0.0 {
+            let adjustment = insert_inventory_adjustment(
+                ctx,
+                InsertInventoryAdjustment {
+                    stock_line_id: stock_line.id,
+                    adjustment: number_of_packs,
+                    adjustment_type: AdjustmentType::Reduction,
+                    inventory_adjustment_reason_id,
+                },
+            )
+            .map_err(DiscardVaccineVialError::InventoryAdjustmentError)?;
+            vial.inventory_adjustment_id = Some(adjustment.invoice_row.id);
+        }
+    }
+
+    vial.status = VaccineVialStatus::Discarded;
+    vial.wasted_doses = wasted_doses;
+    vial.closed_datetime = Some(datetime);
+    VaccineVialOpeningRowRepository::new(&ctx.connection).upsert_one(&vial)?;
+
+    Ok(vial)
+}
+
+impl From<RepositoryError> for DiscardVaccineVialError {
+    fn from(error: RepositoryError) -> Self {
+        DiscardVaccineVialError::DatabaseError(error)
+    }
+}
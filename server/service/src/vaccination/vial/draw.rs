@@ -0,0 +1,62 @@
+use chrono::NaiveDateTime;
+use repository::{
+    DatetimeFilter, EqualFilter, RepositoryError, StockLineFilter, StockLineRepository,
+    StorageConnection, VaccineVialOpeningFilter, VaccineVialOpeningRepository,
+    VaccineVialOpeningRow, VaccineVialOpeningRowRepository, VaccineVialStatus,
+};
+use util::uuid::uuid;
+
+use super::{generate_vial_opening, is_multi_dose_vial, DEFAULT_OPEN_VIAL_PERIOD_HOURS};
+
+/// Records a dose drawn from the stock line for a vaccination. The dose is taken from the
+/// earliest open vial that is still within its open vial period, or a new vial is opened.
+/// Returns None when the stock line isn't a multi-dose vaccine.
+pub(crate) fn draw_vaccine_dose(
+    connection: &StorageConnection,
+    store_id: &str,
+    stock_line_id: &str,
+    datetime: NaiveDateTime,
+) -> Result<Option<VaccineVialOpeningRow>, RepositoryError> {
+    let Some(stock_line) = StockLineRepository::new(connection)
+        .query_by_filter(
+            StockLineFilter::new().id(EqualFilter::equal_to(stock_line_id)),
+            None,
+        )?
+        .pop()
+    else {
+        return Ok(None);
+    };
+    if !is_multi_dose_vial(&stock_line) {
+        return Ok(None);
+    }
+
+    let open_vial = VaccineVialOpeningRepository::new(connection)
+        .query_by_filter(
+            VaccineVialOpeningFilter::new()
+                .store_id(EqualFilter::equal_to(store_id))
+                .stock_line_id(EqualFilter::equal_to(stock_line_id))
+                .status(VaccineVialStatus::Open.equal_to())
+                .discard_after_datetime(DatetimeFilter::after_or_equal_to(datetime)),
+        )?
+        .into_iter()
+        .find(|vial| vial.doses_drawn < vial.doses_per_vial);
+
+    let mut vial = open_vial.unwrap_or_else(|| {
+        generate_vial_opening(
+            uuid(),
+            store_id,
+            &stock_line,
+            datetime,
+            DEFAULT_OPEN_VIAL_PERIOD_HOURS,
+        )
+    });
+
+    vial.doses_drawn += 1;
+    if vial.doses_drawn >= vial.doses_per_vial {
+        vial.status = VaccineVialStatus::Empty;
+        vial.closed_datetime = Some(datetime);
+    }
+    VaccineVialOpeningRowRepository::new(connection).upsert_one(&vial)?;
+
+    Ok(Some(vial))
+}
@@ -0,0 +1,39 @@
+use chrono::{Duration, NaiveDateTime};
+use repository::{StockLine, VaccineVialOpeningRow, VaccineVialStatus};
+
+pub mod discard;
+pub mod draw;
+pub mod open;
+pub mod wastage;
+
+/// Open vial policy period used when a vial is opened by drawing a dose, rather than opened
+/// explicitly with a period for the vaccine
+pub const DEFAULT_OPEN_VIAL_PERIOD_HOURS: u32 = 6;
+
+fn generate_vial_opening(
+    id: String,
+    store_id: &str,
+    stock_line: &StockLine,
+    opened_datetime: NaiveDateTime,
+    open_vial_period_hours: u32,
+) -> VaccineVialOpeningRow {
+    VaccineVialOpeningRow {
+        id,
+        store_id: store_id.to_string(),
+        item_link_id: stock_line.stock_line_row.item_link_id.clone(),
+        stock_line_id: stock_line.stock_line_row.id.clone(),
+        status: VaccineVialStatus::Open,
+        doses_per_vial: stock_line.item_row.vaccine_doses,
+        doses_drawn: 0,
+        wasted_doses: 0,
+        opened_datetime,
+        discard_after_datetime: opened_datetime + Duration::hours(open_vial_period_hours as i64),
+        closed_datetime: None,
+        inventory_adjustment_id: None,
+    }
+}
+
+/// Only multi-dose vials are tracked, wastage can't happen with single dose vials
+fn is_multi_dose_vial(stock_line: &StockLine) -> bool {
+    stock_line.item_row.is_vaccine && stock_line.item_row.vaccine_doses > 1
+}
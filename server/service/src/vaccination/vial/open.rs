@@ -0,0 +1,96 @@
+use chrono::Utc;
+use repository::{
+    RepositoryError, StockLine, StorageConnection, VaccineVialOpeningRow,
+    VaccineVialOpeningRowRepository,
+};
+
+use crate::{
+    common_stock::{check_stock_line_exists, CommonStockLineError},
+    service_provider::ServiceContext,
+};
+
+use super::{generate_vial_opening, is_multi_dose_vial};
+
+#[derive(PartialEq, Debug)]
+pub enum OpenVaccineVialError {
+    VialOpeningAlreadyExists,
+    StockLineDoesNotExist,
+    NotAMultiDoseVaccine,
+    NoStockAvailable,
+    InvalidOpenVialPeriod,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct OpenVaccineVial {
+    pub id: String,
+    pub stock_line_id: String,
+    /// Hours the vial can be used for after opening, as per the vaccine's open vial policy
+    pub open_vial_period_hours: u32,
+}
+
+pub fn open_vaccine_vial(
+    ctx: &ServiceContext,
+    input: OpenVaccineVial,
+) -> Result<VaccineVialOpeningRow, OpenVaccineVialError> {
+    let vial_opening = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let stock_line = validate(connection, &ctx.store_id, &input)?;
+
+            let vial_opening = generate_vial_opening(
+                input.id,
+                &ctx.store_id,
+                &stock_line,
+                Utc::now().naive_utc(),
+                input.open_vial_period_hours,
+            );
+            VaccineVialOpeningRowRepository::new(connection).upsert_one(&vial_opening)?;
+
+            Ok(vial_opening)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(vial_opening)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &OpenVaccineVial,
+) -> Result<StockLine, OpenVaccineVialError> {
+    use OpenVaccineVialError::*;
+
+    if VaccineVialOpeningRowRepository::new(connection)
+        .find_one_by_id(&input.id)?
+        .is_some()
+    {
+        return Err(VialOpeningAlreadyExists);
+    }
+    if input.open_vial_period_hours == 0 {
+        return Err(InvalidOpenVialPeriod);
+    }
+
+    let stock_line =
+        check_stock_line_exists(connection, store_id, &input.stock_line_id).map_err(|error| {
+            match error {
+                CommonStockLineError::DatabaseError(RepositoryError::NotFound)
+                | CommonStockLineError::StockLineDoesNotBelongToStore => StockLineDoesNotExist,
+                CommonStockLineError::DatabaseError(error) => DatabaseError(error),
+            }
+        })?;
+    if !is_multi_dose_vial(&stock_line) {
+        return Err(NotAMultiDoseVaccine);
+    }
+    if stock_line.stock_line_row.available_number_of_packs <= 0.0 {
+        return Err(NoStockAvailable);
+    }
+
+    Ok(stock_line)
+}
+
+impl From<RepositoryError> for OpenVaccineVialError {
+    fn from(error: RepositoryError) -> Self {
+        OpenVaccineVialError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDateTime;
+use repository::{
+    DatetimeFilter, EqualFilter, ItemLinkRowRepository, ItemRowRepository, RepositoryError,
+    VaccineVialOpeningFilter, VaccineVialOpeningRepository, VaccineVialStatus,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct VaccineWastageInput {
+    /// All stores with vial openings on this site when not set
+    pub store_id: Option<String>,
+    pub item_id: Option<String>,
+    pub from_datetime: NaiveDateTime,
+    pub to_datetime: NaiveDateTime,
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct VaccineWastageRate {
+    pub store_id: String,
+    pub item_id: String,
+    pub item_name: String,
+    pub vials_opened: u32,
+    pub doses_administered: i32,
+    pub doses_wasted: i32,
+}
+
+impl VaccineWastageRate {
+    /// Share of the doses in the opened vials that were wasted, comparable to the planning
+    /// wastage rate on the vaccine course
+    pub fn wastage_rate(&self) -> f64 {
+        let total_doses = self.doses_administered + self.doses_wasted;
+        if total_doses == 0 {
+            return 0.0;
+        }
+        self.doses_wasted as f64 / total_doses as f64
+    }
+}
+
+/// Actual wastage per store and vaccine from the vials opened within the period. Vials that are
+/// still open aren't counted as their wastage isn't known yet.
+pub fn get_vaccine_wastage(
+    ctx: &ServiceContext,
+    input: VaccineWastageInput,
+) -> Result<Vec<VaccineWastageRate>, RepositoryError> {
+    let connection = &ctx.connection;
+    let VaccineWastageInput {
+        store_id,
+        item_id,
+        from_datetime,
+        to_datetime,
+    } = input;
+
+    let mut filter = VaccineVialOpeningFilter::new()
+        .status(VaccineVialStatus::equal_any(vec![
+            VaccineVialStatus::Empty,
+            VaccineVialStatus::Discarded,
+        ]))
+        .opened_datetime(DatetimeFilter::date_range(from_datetime, to_datetime));
+    if let Some(store_id) = store_id {
+        filter = filter.store_id(EqualFilter::equal_to(&store_id));
+    }
+    if let Some(item_id) = item_id {
+        let item_link_ids = ItemLinkRowRepository::new(connection)
+            .find_many_by_item_id(&item_id)?
+            .into_iter()
+            .map(|item_link| item_link.id)
+            .collect();
+        filter = filter.item_link_id(EqualFilter::equal_any(item_link_ids));
+    }
+    let vial_openings = VaccineVialOpeningRepository::new(connection).query_by_filter(filter)?;
+
+    let mut item_link_ids: Vec<String> = vial_openings
+        .iter()
+        .map(|vial| vial.item_link_id.clone())
+        .collect();
+    item_link_ids.sort();
+    item_link_ids.dedup();
+    let item_ids: BTreeMap<String, String> = ItemLinkRowRepository::new(connection)
+        .find_many_by_id(&item_link_ids)?
+        .into_iter()
+        .map(|item_link| (item_link.id, item_link.item_id))
+        .collect();
+
+    let mut rates: BTreeMap<(String, String), VaccineWastageRate> = BTreeMap::new();
+    for vial in vial_openings {
+        let item_id = item_ids
+            .get(&vial.item_link_id)
+            .cloned()
+            .unwrap_or(vial.item_link_id);
+        let rate = rates
+            .entry((vial.store_id.clone(), item_id.clone()))
+            .or_insert_with(|| VaccineWastageRate {
+                store_id: vial.store_id.clone(),
+                item_id,
+                ..Default::default()
+            });
+        rate.vials_opened += 1;
+        rate.doses_administered += vial.doses_drawn;
+        rate.doses_wasted += vial.wasted_doses;
+    }
+
+    let item_names: BTreeMap<String, String> = ItemRowRepository::new(connection)
+        .find_many_by_id(&item_ids.values().cloned().collect())?
+        .into_iter()
+        .map(|item| (item.id, item.name))
+        .collect();
+
+    Ok(rates
+        .into_values()
+        .map(|rate| VaccineWastageRate {
+            item_name: item_names.get(&rate.item_id).cloned().unwrap_or_default(),
+            ..rate
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use assert_approx_eq::assert_approx_eq;
+    use chrono::{Duration, Utc};
+    use repository::{
+        mock::{
+            mock_immunisation_encounter_a, mock_name_1, mock_stock_line_vaccine_item_a,
+            mock_store_a, mock_user_account_a, mock_vaccine_course_a_dose_b, mock_vaccine_item_a,
+            MockDataInserts,
+        },
+        test_db::setup_all,
+        StockLineRowRepository, VaccineVialStatus,
+    };
+
+    use crate::{
+        service_provider::ServiceProvider,
+        vaccination::{
+            insert::InsertVaccination,
+            vial::{
+                discard::{DiscardExpiredVaccineVials, DiscardVaccineVial},
+                open::{OpenVaccineVial, OpenVaccineVialError},
+                wastage::VaccineWastageInput,
+            },
+        },
+    };
+
+    #[actix_rt::test]
+    async fn vaccine_vial_wastage() {
+        let (_, _, connection_manager, _) =
+            setup_all("vaccine_vial_wastage", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager);
+        let context = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = service_provider.vaccination_service;
+        let stock_line_packs = || {
+            StockLineRowRepository::new(&context.connection)
+                .find_one_by_id(&mock_stock_line_vaccine_item_a().id)
+                .unwrap()
+                .unwrap()
+                .available_number_of_packs
+        };
+
+        assert_eq!(
+            service.open_vaccine_vial(
+                &context,
+                OpenVaccineVial {
+                    id: "vial_a".to_string(),
+                    stock_line_id: mock_stock_line_vaccine_item_a().id,
+                    open_vial_period_hours: 0,
+                }
+            ),
+            Err(OpenVaccineVialError::InvalidOpenVialPeriod)
+        );
+        let vial = service
+            .open_vaccine_vial(
+                &context,
+                OpenVaccineVial {
+                    id: "vial_a".to_string(),
+                    stock_line_id: mock_stock_line_vaccine_item_a().id,
+                    open_vial_period_hours: 24,
+                },
+            )
+            .unwrap();
+        assert_eq!(vial.doses_per_vial, mock_vaccine_item_a().vaccine_doses);
+        assert_eq!(vial.status, VaccineVialStatus::Open);
+
+        // Giving a vaccination draws a dose from the open vial
+        service
+            .insert_vaccination(
+                &context,
+                &mock_store_a().id,
+                InsertVaccination {
+                    id: "vial_vaccination".to_string(),
+                    encounter_id: mock_immunisation_encounter_a().id,
+                    vaccine_course_dose_id: mock_vaccine_course_a_dose_b().id,
+                    facility_name_id: Some(mock_name_1().id),
+                    given: true,
+                    stock_line_id: Some(mock_stock_line_vaccine_item_a().id),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        // 2 doses per unit, 5 units per pack, 1 dose given
+        assert_eq!(stock_line_packs(), 4.9);
+
+        // Remaining dose is wasted when the vial is discarded
+        let vial = service
+            .discard_vaccine_vial(
+                &context,
+                DiscardVaccineVial {
+                    id: "vial_a".to_string(),
+                    inventory_adjustment_reason_id: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(vial.status, VaccineVialStatus::Discarded);
+        assert_eq!(vial.doses_drawn, 1);
+        assert_eq!(vial.wasted_doses, 1);
+        assert!(vial.inventory_adjustment_id.is_some());
+        assert_approx_eq!(stock_line_packs(), 4.8);
+
+        // Vials past their open vial period are discarded
+        service
+            .open_vaccine_vial(
+                &context,
+                OpenVaccineVial {
+                    id: "vial_b".to_string(),
+                    stock_line_id: mock_stock_line_vaccine_item_a().id,
+                    open_vial_period_hours: 1,
+                },
+            )
+            .unwrap();
+        let now = Utc::now().naive_utc();
+        let discarded = service
+            .discard_expired_vaccine_vials(
+                &context,
+                DiscardExpiredVaccineVials {
+                    as_of: now,
+                    inventory_adjustment_reason_id: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(discarded, vec![]);
+        let discarded = service
+            .discard_expired_vaccine_vials(
+                &context,
+                DiscardExpiredVaccineVials {
+                    as_of: now + Duration::hours(2),
+                    inventory_adjustment_reason_id: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(discarded.len(), 1);
+        assert_eq!(discarded[0].wasted_doses, 2);
+        assert_approx_eq!(stock_line_packs(), 4.6);
+
+        // 1 dose administered, 3 wasted
+        let rates = service
+            .get_vaccine_wastage(
+                &context,
+                VaccineWastageInput {
+                    store_id: Some(mock_store_a().id),
+                    item_id: Some(mock_vaccine_item_a().id),
+                    from_datetime: now - Duration::days(1),
+                    to_datetime: now + Duration::days(1),
+                },
+            )
+            .unwrap();
+        assert_eq!(rates.len(), 1);
+        assert_eq!(rates[0].item_name, mock_vaccine_item_a().name);
+        assert_eq!(rates[0].vials_opened, 2);
+        assert_eq!(rates[0].doses_administered, 1);
+        assert_eq!(rates[0].doses_wasted, 3);
+        assert_eq!(rates[0].wastage_rate(), 0.75);
+    }
+}
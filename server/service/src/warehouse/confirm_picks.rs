@@ -0,0 +1,135 @@
+use repository::{
+    EqualFilter, Invoice, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineType,
+    InvoiceRowRepository, InvoiceStatus, InvoiceType, RepositoryError,
+};
+
+use crate::{
+    invoice::outbound_shipment::update::{
+        update_outbound_shipment, UpdateOutboundShipment, UpdateOutboundShipmentError,
+        UpdateOutboundShipmentStatus,
+    },
+    invoice_line::stock_out_line::{
+        update_stock_out_line, StockOutType, UpdateStockOutLine, UpdateStockOutLineError,
+    },
+    service_provider::ServiceContext,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfirmPickLine {
+    pub invoice_line_id: String,
+    pub picked_number_of_packs: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ConfirmPicks {
+    pub invoice_id: String,
+    /// Lines where a different quantity than allocated was picked, all other lines are picked as
+    /// allocated
+    pub lines: Vec<ConfirmPickLine>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ConfirmPicksError {
+    InvoiceDoesNotExist,
+    NotThisStoreInvoice,
+    NotAnOutboundShipment,
+    InvoiceIsNotAllocated,
+    /// Holds the id of the line that doesn't belong to the shipment
+    LineDoesNotBelongToShipment(String),
+    LineError {
+        line_id: String,
+        error: UpdateStockOutLineError,
+    },
+    UpdateShipmentError(UpdateOutboundShipmentError),
+    DatabaseError(RepositoryError),
+}
+
+/// Confirms picking of an allocated outbound shipment. Allocations of lines where a different
+/// quantity was picked are adjusted before the shipment is changed to picked.
+pub fn confirm_picks(
+    ctx: &ServiceContext,
+    input: ConfirmPicks,
+) -> Result<Invoice, ConfirmPicksError> {
+    let invoice = ctx
+        .connection
+        .transaction_sync(|connection| {
+            validate(ctx, &input)?;
+
+            let line_repo = InvoiceLineRepository::new(connection);
+            for line in input.lines {
+                let existing = line_repo
+                    .query_one(
+                        InvoiceLineFilter::new().id(EqualFilter::equal_to(&line.invoice_line_id)),
+                    )?
+                    .map(|line| line.invoice_line_row);
+                let existing = match existing {
+                    Some(existing)
+                        if existing.invoice_id == input.invoice_id
+                            && existing.r#type == InvoiceLineType::StockOut =>
+                    {
+                        existing
+                    }
+                    _ => {
+                        return Err(ConfirmPicksError::LineDoesNotBelongToShipment(
+                            line.invoice_line_id,
+                        ))
+                    }
+                };
+                if existing.number_of_packs == line.picked_number_of_packs {
+                    continue;
+                }
+
+                update_stock_out_line(
+                    ctx,
+                    UpdateStockOutLine {
+                        id: line.invoice_line_id.clone(),
+                        r#type: Some(StockOutType::OutboundShipment),
+                        number_of_packs: Some(line.picked_number_of_packs),
+                        ..Default::default()
+                    },
+                )
+                .map_err(|error| ConfirmPicksError::LineError {
+                    line_id: line.invoice_line_id,
+                    error,
+                })?;
+            }
+
+            update_outbound_shipment(
+                ctx,
+                UpdateOutboundShipment {
+                    id: input.invoice_id,
+                    status: Some(UpdateOutboundShipmentStatus::Picked),
+                    ..Default::default()
+                },
+            )
+            .map_err(ConfirmPicksError::UpdateShipmentError)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(invoice)
+}
+
+fn validate(ctx: &ServiceContext, input: &ConfirmPicks) -> Result<(), ConfirmPicksError> {
+    use ConfirmPicksError::*;
+
+    let invoice = InvoiceRowRepository::new(&ctx.connection)
+        .find_one_by_id(&input.invoice_id)?
+        .ok_or(InvoiceDoesNotExist)?;
+    if invoice.store_id != ctx.store_id {
+        return Err(NotThisStoreInvoice);
+    }
+    if invoice.r#type != InvoiceType::OutboundShipment {
+        return Err(NotAnOutboundShipment);
+    }
+    if invoice.status != InvoiceStatus::Allocated {
+        return Err(InvoiceIsNotAllocated);
+    }
+
+    Ok(())
+}
+
+impl From<RepositoryError> for ConfirmPicksError {
+    fn from(error: RepositoryError) -> Self {
+        ConfirmPicksError::DatabaseError(error)
+    }
+}
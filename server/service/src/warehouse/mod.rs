@@ -0,0 +1,44 @@
+use repository::{Invoice, InvoiceLine};
+
+use self::{
+    confirm_picks::{confirm_picks, ConfirmPicks, ConfirmPicksError},
+    pick_list::{generate_pick_list, GeneratePickListError},
+    put_away::{get_put_away_suggestions, GetPutAwaySuggestionsError, PutAwaySuggestion},
+};
+use crate::service_provider::ServiceContext;
+
+pub mod confirm_picks;
+pub mod pick_list;
+pub mod put_away;
+
+pub trait WarehouseServiceTrait: Sync + Send {
+    fn get_put_away_suggestions(
+        &self,
+        ctx: &ServiceContext,
+        invoice_line_id: &str,
+    ) -> Result<Vec<PutAwaySuggestion>, GetPutAwaySuggestionsError> {
+        get_put_away_suggestions(ctx, invoice_line_id)
+    }
+
+    fn generate_pick_list(
+        &self,
+        ctx: &ServiceContext,
+        invoice_ids: Vec<String>,
+    ) -> Result<Vec<InvoiceLine>, GeneratePickListError> {
+        generate_pick_list(ctx, invoice_ids)
+    }
+
+    fn confirm_picks(
+        &self,
+        ctx: &ServiceContext,
+        input: ConfirmPicks,
+    ) -> Result<Invoice, ConfirmPicksError> {
+        confirm_picks(ctx, input)
+    }
+}
+
+pub struct WarehouseService {}
+impl WarehouseServiceTrait for WarehouseService {}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,97 @@
+use std::cmp::Ordering;
+
+use repository::{
+    EqualFilter, InvoiceLine, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineType,
+    InvoiceRowRepository, InvoiceStatus, InvoiceType, RepositoryError,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, PartialEq)]
+pub enum GeneratePickListError {
+    NoShipmentsSelected,
+    InvoiceDoesNotExist(String),
+    NotThisStoreInvoice(String),
+    NotAnOutboundShipment(String),
+    /// Only allocated shipments can be picked
+    InvoiceIsNotAllocated(String),
+    DatabaseError(RepositoryError),
+}
+
+/// Lines to pick for one or many allocated outbound shipments, ordered by the walk sequence of
+/// their location so stock can be picked in a single walk through the store. Lines without
+/// location are picked last.
+pub fn generate_pick_list(
+    ctx: &ServiceContext,
+    invoice_ids: Vec<String>,
+) -> Result<Vec<InvoiceLine>, GeneratePickListError> {
+    use GeneratePickListError::*;
+    let connection = &ctx.connection;
+
+    if invoice_ids.is_empty() {
+        return Err(NoShipmentsSelected);
+    }
+
+    let invoice_repo = InvoiceRowRepository::new(connection);
+    for invoice_id in &invoice_ids {
+        let invoice = invoice_repo
+            .find_one_by_id(invoice_id)?
+            .ok_or_else(|| InvoiceDoesNotExist(invoice_id.clone()))?;
+        if invoice.store_id != ctx.store_id {
+            return Err(NotThisStoreInvoice(invoice_id.clone()));
+        }
+        if invoice.r#type != InvoiceType::OutboundShipment {
+            return Err(NotAnOutboundShipment(invoice_id.clone()));
+        }
+        if invoice.status != InvoiceStatus::Allocated {
+            return Err(InvoiceIsNotAllocated(invoice_id.clone()));
+        }
+    }
+
+    let mut lines: Vec<InvoiceLine> = InvoiceLineRepository::new(connection)
+        .query_by_filter(
+            InvoiceLineFilter::new()
+                .invoice_id(EqualFilter::equal_any(invoice_ids))
+                .r#type(InvoiceLineType::StockOut.equal_to()),
+        )?
+        .into_iter()
+        .filter(|line| line.invoice_line_row.number_of_packs > 0.0)
+        .collect();
+
+    lines.sort_by(compare_pick_order);
+
+    Ok(lines)
+}
+
+fn compare_pick_order(a: &InvoiceLine, b: &InvoiceLine) -> Ordering {
+    let location_key = |line: &InvoiceLine| {
+        let location = line.location_row_option.as_ref();
+        (
+            location.is_none(),
+            location
+                .and_then(|location| location.walk_sequence)
+                .is_none(),
+            location.and_then(|location| location.walk_sequence),
+            location.map(|location| location.code.clone()),
+        )
+    };
+    let line_key = |line: &InvoiceLine| {
+        let row = &line.invoice_line_row;
+        (
+            row.item_name.clone(),
+            row.expiry_date.is_none(),
+            row.expiry_date,
+            row.batch.clone(),
+        )
+    };
+
+    location_key(a)
+        .cmp(&location_key(b))
+        .then_with(|| line_key(a).cmp(&line_key(b)))
+}
+
+impl From<RepositoryError> for GeneratePickListError {
+    fn from(error: RepositoryError) -> Self {
+        GeneratePickListError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,168 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use repository::{
+    item_variant::item_variant_row::ItemVariantRowRepository,
+    location::{LocationFilter, LocationRepository},
+    ColdStorageTypeRowRepository, EqualFilter, InvoiceLineFilter, InvoiceLineRepository,
+    InvoiceLineType, InvoiceType, LocationRow, RepositoryError, StockLineFilter,
+    StockLineRepository,
+};
+
+use crate::{
+    location::{
+        cold_chain::is_compatible,
+        utilisation::{
+            get_packaging_variants_by_item_variant, get_store_location_utilisation, volume_per_pack,
+        },
+    },
+    service_provider::ServiceContext,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PutAwaySuggestion {
+    pub location: LocationRow,
+    /// Location already holds stock of the same item
+    pub has_same_item: bool,
+    /// Volume left in the location after putting the stock away, None if the location has no
+    /// volume capacity or the volume of the stock is unknown
+    pub remaining_volume: Option<f64>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GetPutAwaySuggestionsError {
+    InvoiceLineDoesNotExist,
+    NotThisStoreInvoice,
+    NotAnInboundShipmentLine,
+    DatabaseError(RepositoryError),
+}
+
+/// Suggests locations for stock received on an inbound shipment line, best suggestion first.
+///
+/// Locations on hold, locations that don't meet the cold storage requirement of the item variant
+/// and locations without enough volume left are excluded. Locations that already hold the same
+/// item come first, followed by the locations with the least volume left (best fit). Stock
+/// without cold storage requirement is suggested to go into ambient locations before cold ones.
+pub fn get_put_away_suggestions(
+    ctx: &ServiceContext,
+    invoice_line_id: &str,
+) -> Result<Vec<PutAwaySuggestion>, GetPutAwaySuggestionsError> {
+    use GetPutAwaySuggestionsError::*;
+    let connection = &ctx.connection;
+
+    let line = InvoiceLineRepository::new(connection)
+        .query_one(InvoiceLineFilter::new().id(EqualFilter::equal_to(invoice_line_id)))?
+        .ok_or(InvoiceLineDoesNotExist)?;
+    if line.invoice_row.store_id != ctx.store_id {
+        return Err(NotThisStoreInvoice);
+    }
+    if line.invoice_row.r#type != InvoiceType::InboundShipment
+        || line.invoice_line_row.r#type != InvoiceLineType::StockIn
+    {
+        return Err(NotAnInboundShipmentLine);
+    }
+    let line_row = line.invoice_line_row;
+
+    let cold_storage_type_repo = ColdStorageTypeRowRepository::new(connection);
+    let required_cold_storage_type = match &line_row.item_variant_id {
+        Some(item_variant_id) => ItemVariantRowRepository::new(connection)
+            .find_one_by_id(item_variant_id)?
+            .and_then(|item_variant| item_variant.cold_storage_type_id)
+            .map(|id| cold_storage_type_repo.find_one_by_id(&id))
+            .transpose()?
+            .flatten(),
+        None => None,
+    };
+
+    let line_volume = match &line_row.item_variant_id {
+        Some(item_variant_id) => {
+            get_packaging_variants_by_item_variant(connection, vec![item_variant_id.clone()])?
+                .get(item_variant_id)
+                .and_then(|variants| volume_per_pack(line_row.pack_size, variants))
+                .map(|volume_per_pack| volume_per_pack * line_row.number_of_packs)
+        }
+        None => None,
+    };
+
+    let locations_with_item: HashSet<String> = StockLineRepository::new(connection)
+        .query_by_filter(
+            StockLineFilter::new()
+                .item_id(EqualFilter::equal_to(&line.item_row.id))
+                .store_id(EqualFilter::equal_to(&ctx.store_id))
+                .has_packs_in_store(true),
+            Some(ctx.store_id.clone()),
+        )?
+        .into_iter()
+        .filter_map(|stock_line| stock_line.stock_line_row.location_id)
+        .collect();
+
+    let utilisation = get_store_location_utilisation(ctx, &ctx.store_id)?;
+    let locations = LocationRepository::new(connection).query_by_filter(
+        LocationFilter::new()
+            .store_id(EqualFilter::equal_to(&ctx.store_id))
+            .on_hold(false),
+    )?;
+
+    // Suggestions with their sort key
+    let mut suggestions = Vec::new();
+    for location in locations {
+        let location = location.location_row;
+        let location_cold_storage_type = location
+            .cold_storage_type_id
+            .as_ref()
+            .map(|id| cold_storage_type_repo.find_one_by_id(id))
+            .transpose()?
+            .flatten();
+
+        let is_cold_storage = location_cold_storage_type.is_some();
+        match (&required_cold_storage_type, &location_cold_storage_type) {
+            (Some(_), None) => continue,
+            (Some(required), Some(location)) if !is_compatible(required, location) => continue,
+            _ => {}
+        }
+
+        let remaining_volume = match (
+            location.volume_capacity,
+            utilisation.get(&location.id),
+            line_volume,
+        ) {
+            (Some(capacity), Some(utilisation), Some(line_volume)) => {
+                Some(capacity - utilisation.volume_used - line_volume)
+            }
+            _ => None,
+        };
+        if remaining_volume.map_or(false, |remaining_volume| remaining_volume < 0.0) {
+            continue;
+        }
+
+        let has_same_item = locations_with_item.contains(&location.id);
+        let sort_key = (
+            !has_same_item,
+            // Keep cold storage free for stock that needs it
+            required_cold_storage_type.is_none() && is_cold_storage,
+            remaining_volume.is_none(),
+            remaining_volume.unwrap_or_default(),
+            location.code.clone(),
+        );
+        suggestions.push((
+            sort_key,
+            PutAwaySuggestion {
+                location,
+                has_same_item,
+                remaining_volume,
+            },
+        ));
+    }
+
+    suggestions.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    Ok(suggestions
+        .into_iter()
+        .map(|(_, suggestion)| suggestion)
+        .collect())
+}
+
+impl From<RepositoryError> for GetPutAwaySuggestionsError {
+    fn from(error: RepositoryError) -> Self {
+        GetPutAwaySuggestionsError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,327 @@
+use repository::{
+    item_variant::{
+        item_variant_row::{ItemVariantRow, ItemVariantRowRepository},
+        packaging_variant_row::{PackagingVariantRow, PackagingVariantRowRepository},
+    },
+    mock::{mock_item_a, mock_item_b, mock_name_store_b, mock_store_a, MockData, MockDataInserts},
+    test_db::setup_all_with_data,
+    ColdStorageTypeRow, ColdStorageTypeRowRepository, InvoiceLineRow, InvoiceLineType, InvoiceRow,
+    InvoiceStatus, InvoiceType, LocationRow, LocationRowRepository, StockLineRow,
+    StockLineRowRepository,
+};
+use util::inline_init;
+
+use crate::{
+    service_provider::ServiceProvider,
+    warehouse::{
+        confirm_picks::{ConfirmPickLine, ConfirmPicks, ConfirmPicksError},
+        pick_list::GeneratePickListError,
+        put_away::GetPutAwaySuggestionsError,
+    },
+};
+
+fn location(id: &str, walk_sequence: Option<i32>) -> LocationRow {
+    LocationRow {
+        id: id.to_string(),
+        code: id.to_string(),
+        name: id.to_string(),
+        store_id: mock_store_a().id,
+        walk_sequence,
+        ..Default::default()
+    }
+}
+
+fn outbound_shipment(id: &str, status: InvoiceStatus) -> InvoiceRow {
+    inline_init(|r: &mut InvoiceRow| {
+        r.id = id.to_string();
+        r.name_link_id = mock_name_store_b().id;
+        r.store_id = mock_store_a().id;
+        r.r#type = InvoiceType::OutboundShipment;
+        r.status = status;
+    })
+}
+
+fn stock_line(id: &str, item_id: &str, location_id: Option<&str>) -> StockLineRow {
+    StockLineRow {
+        id: id.to_string(),
+        item_link_id: item_id.to_string(),
+        store_id: mock_store_a().id,
+        location_id: location_id.map(str::to_string),
+        pack_size: 1.0,
+        total_number_of_packs: 10.0,
+        available_number_of_packs: 5.0,
+        ..Default::default()
+    }
+}
+
+fn stock_out_line(id: &str, invoice_id: &str, stock_line: &StockLineRow) -> InvoiceLineRow {
+    InvoiceLineRow {
+        id: id.to_string(),
+        invoice_id: invoice_id.to_string(),
+        item_link_id: stock_line.item_link_id.clone(),
+        item_name: stock_line.item_link_id.clone(),
+        stock_line_id: Some(stock_line.id.clone()),
+        location_id: stock_line.location_id.clone(),
+        pack_size: stock_line.pack_size,
+        number_of_packs: 5.0,
+        r#type: InvoiceLineType::StockOut,
+        ..Default::default()
+    }
+}
+
+#[actix_rt::test]
+async fn pick_list_and_confirm_picks() {
+    let stock_lines = vec![
+        stock_line("stock_no_location", &mock_item_a().id, None),
+        stock_line("stock_no_sequence", &mock_item_a().id, Some("no_sequence")),
+        stock_line("stock_second", &mock_item_a().id, Some("second")),
+        stock_line("stock_first", &mock_item_b().id, Some("first")),
+    ];
+
+    let (_, connection, connection_manager, _) = setup_all_with_data(
+        "pick_list_and_confirm_picks",
+        MockDataInserts::none().names().stores().units().items(),
+        MockData {
+            locations: vec![
+                location("second", Some(2)),
+                location("no_sequence", None),
+                location("first", Some(1)),
+            ],
+            invoices: vec![
+                outbound_shipment("allocated_a", InvoiceStatus::Allocated),
+                outbound_shipment("allocated_b", InvoiceStatus::Allocated),
+                outbound_shipment("new", InvoiceStatus::New),
+            ],
+            stock_lines: stock_lines.clone(),
+            invoice_lines: vec![
+                stock_out_line("line_no_location", "allocated_a", &stock_lines[0]),
+                stock_out_line("line_no_sequence", "allocated_b", &stock_lines[1]),
+                stock_out_line("line_second", "allocated_a", &stock_lines[2]),
+                stock_out_line("line_first", "allocated_b", &stock_lines[3]),
+            ],
+            ..Default::default()
+        },
+    )
+    .await;
+
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider
+        .context(mock_store_a().id, "".to_string())
+        .unwrap();
+    let service = service_provider.warehouse_service;
+
+    // Pick list
+    assert_eq!(
+        service.generate_pick_list(&context, vec![]),
+        Err(GeneratePickListError::NoShipmentsSelected)
+    );
+    assert_eq!(
+        service.generate_pick_list(&context, vec!["allocated_a".to_string(), "new".to_string()]),
+        Err(GeneratePickListError::InvoiceIsNotAllocated(
+            "new".to_string()
+        ))
+    );
+
+    let pick_list = service
+        .generate_pick_list(
+            &context,
+            vec!["allocated_a".to_string(), "allocated_b".to_string()],
+        )
+        .unwrap();
+    let line_ids: Vec<&str> = pick_list
+        .iter()
+        .map(|line| line.invoice_line_row.id.as_str())
+        .collect();
+    assert_eq!(
+        line_ids,
+        vec![
+            "line_first",
+            "line_second",
+            "line_no_sequence",
+            "line_no_location"
+        ]
+    );
+
+    // Confirm picks
+    assert_eq!(
+        service.confirm_picks(
+            &context,
+            ConfirmPicks {
+                invoice_id: "allocated_a".to_string(),
+                lines: vec![ConfirmPickLine {
+                    invoice_line_id: "line_first".to_string(),
+                    picked_number_of_packs: 5.0,
+                }],
+            },
+        ),
+        Err(ConfirmPicksError::LineDoesNotBelongToShipment(
+            "line_first".to_string()
+        ))
+    );
+
+    let invoice = service
+        .confirm_picks(
+            &context,
+            ConfirmPicks {
+                invoice_id: "allocated_a".to_string(),
+                lines: vec![ConfirmPickLine {
+                    invoice_line_id: "line_second".to_string(),
+                    picked_number_of_packs: 3.0,
+                }],
+            },
+        )
+        .unwrap();
+    assert_eq!(invoice.invoice_row.status, InvoiceStatus::Picked);
+
+    // Packs that were not picked are available again
+    let stock_line = StockLineRowRepository::new(&connection)
+        .find_one_by_id("stock_second")
+        .unwrap()
+        .unwrap();
+    assert_eq!(stock_line.available_number_of_packs, 7.0);
+
+    assert_eq!(
+        service.confirm_picks(
+            &context,
+            ConfirmPicks {
+                invoice_id: "allocated_a".to_string(),
+                ..Default::default()
+            },
+        ),
+        Err(ConfirmPicksError::InvoiceIsNotAllocated)
+    );
+}
+
+#[actix_rt::test]
+async fn put_away_suggestions() {
+    let fridge_type = ColdStorageTypeRow {
+        id: "fridge".to_string(),
+        name: "fridge".to_string(),
+        min_temperature: 2.0,
+        max_temperature: 8.0,
+    };
+    let item_variant = ItemVariantRow {
+        id: "item_variant".to_string(),
+        item_link_id: mock_item_a().id,
+        ..Default::default()
+    };
+    let inbound_shipment = inline_init(|r: &mut InvoiceRow| {
+        r.id = "inbound_shipment".to_string();
+        r.name_link_id = mock_name_store_b().id;
+        r.store_id = mock_store_a().id;
+        r.r#type = InvoiceType::InboundShipment;
+        r.status = InvoiceStatus::Delivered;
+    });
+    // 10 packs of volume 1
+    let inbound_line = InvoiceLineRow {
+        id: "inbound_line".to_string(),
+        invoice_id: inbound_shipment.id.clone(),
+        item_link_id: mock_item_a().id,
+        item_variant_id: Some(item_variant.id.clone()),
+        pack_size: 1.0,
+        number_of_packs: 10.0,
+        r#type: InvoiceLineType::StockIn,
+        ..Default::default()
+    };
+    let with_capacity = |id: &str, volume_capacity: f64| LocationRow {
+        volume_capacity: Some(volume_capacity),
+        ..location(id, None)
+    };
+
+    let (_, connection, connection_manager, _) = setup_all_with_data(
+        "put_away_suggestions",
+        MockDataInserts::none().names().stores().units().items(),
+        MockData {
+            item_variants: vec![item_variant.clone()],
+            invoices: vec![inbound_shipment],
+            invoice_lines: vec![inbound_line],
+            ..Default::default()
+        },
+    )
+    .await;
+
+    ColdStorageTypeRowRepository::new(&connection)
+        .upsert_one(&fridge_type)
+        .unwrap();
+    PackagingVariantRowRepository::new(&connection)
+        .upsert_one(&PackagingVariantRow {
+            id: "packaging_variant".to_string(),
+            item_variant_id: item_variant.id.clone(),
+            packaging_level: 1,
+            pack_size: Some(1.0),
+            volume_per_unit: Some(1.0),
+            ..Default::default()
+        })
+        .unwrap();
+    let location_repo = LocationRowRepository::new(&connection);
+    for row in [
+        with_capacity("too_small", 5.0),
+        with_capacity("large", 100.0),
+        with_capacity("best_fit", 20.0),
+        LocationRow {
+            cold_storage_type_id: Some(fridge_type.id.clone()),
+            ..with_capacity("fridge", 20.0)
+        },
+        location("unknown_capacity", None),
+        location("same_item", None),
+        LocationRow {
+            on_hold: true,
+            ..location("on_hold", None)
+        },
+    ] {
+        location_repo.upsert_one(&row).unwrap();
+    }
+    StockLineRowRepository::new(&connection)
+        .upsert_one(&stock_line(
+            "existing_stock",
+            &mock_item_a().id,
+            Some("same_item"),
+        ))
+        .unwrap();
+
+    let service_provider = ServiceProvider::new(connection_manager);
+    let context = service_provider
+        .context(mock_store_a().id, "".to_string())
+        .unwrap();
+    let service = service_provider.warehouse_service;
+
+    let suggestions = service
+        .get_put_away_suggestions(&context, "inbound_line")
+        .unwrap();
+    let location_ids: Vec<&str> = suggestions
+        .iter()
+        .map(|suggestion| suggestion.location.id.as_str())
+        .collect();
+    assert_eq!(
+        location_ids,
+        vec![
+            "same_item",
+            "best_fit",
+            "large",
+            "unknown_capacity",
+            "fridge"
+        ]
+    );
+    assert_eq!(suggestions[1].remaining_volume, Some(10.0));
+
+    // Cold storage is required
+    ItemVariantRowRepository::new(&connection)
+        .upsert_one(&ItemVariantRow {
+            cold_storage_type_id: Some(fridge_type.id.clone()),
+            ..item_variant
+        })
+        .unwrap();
+    let suggestions = service
+        .get_put_away_suggestions(&context, "inbound_line")
+        .unwrap();
+    let location_ids: Vec<&str> = suggestions
+        .iter()
+        .map(|suggestion| suggestion.location.id.as_str())
+        .collect();
+    assert_eq!(location_ids, vec!["fridge"]);
+
+    assert_eq!(
+        service.get_put_away_suggestions(&context, "does_not_exist"),
+        Err(GetPutAwaySuggestionsError::InvoiceLineDoesNotExist)
+    );
+}
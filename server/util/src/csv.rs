@@ -0,0 +1,51 @@
+/// Quotes a CSV field if it contains a separator, quote or line break (RFC 4180)
+pub fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Builds a CSV document from a header row and data rows
+pub fn to_csv(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut result = headers
+        .iter()
+        .map(|header| csv_escape(header))
+        .collect::<Vec<String>>()
+        .join(",");
+    result.push_str("\r\n");
+
+    for row in rows {
+        result.push_str(
+            &row.iter()
+                .map(|field| csv_escape(field))
+                .collect::<Vec<String>>()
+                .join(","),
+        );
+        result.push_str("\r\n");
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_to_csv() {
+        let csv = to_csv(
+            &["name", "comment"],
+            &[
+                vec!["Patient A".to_string(), "plain".to_string()],
+                vec!["Patient, B".to_string(), "said \"hi\"".to_string()],
+            ],
+        );
+
+        assert_eq!(
+            csv,
+            "name,comment\r\nPatient A,plain\r\n\"Patient, B\",\"said \"\"hi\"\"\"\r\n"
+        );
+    }
+}
@@ -30,3 +30,6 @@ pub use file::*;
 
 mod gs1;
 pub use gs1::*;
+
+mod csv;
+pub use csv::*;